@@ -0,0 +1,502 @@
+//! Async HTTP client for the custodian signing API.
+//!
+//! The custodian is addressed through a small JSON-RPC 2.0 interface so that
+//! vendor-specific services (Fireblocks, Turnkey, in-house HSM gateways) can be
+//! fronted by a thin adapter:
+//!
+//! - `signTransaction` - `{ "pubkey", "message" }` (base64 message bytes)
+//! - `getSignatureRequest` - `{ "requestId" }`
+//!
+//! Both methods return a [`SignatureRequest`] describing whether the request is
+//! still awaiting approval, has been signed, or was rejected.
+//!
+//! Co-signers holding one share of a split key (see [`crate::split`]) also
+//! implement:
+//!
+//! - `enrollShare` - `{ "share", "proof" }`, returning the co-signer's
+//!   [`ShareEnrollment`]
+//! - `coSign` - `{ "pubkey", "message", "nonce" }`, returning a
+//!   [`PartialSignature`]
+
+use std::time::{Duration, Instant};
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{Value, json};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+use crate::error::{CustodianError, Result};
+use crate::split::{PartialSignature, ShareEnrollment};
+
+/// Default interval between approval status polls.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Default time to wait for a custodian approval when blocking.
+pub const DEFAULT_APPROVAL_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Default timeout for a single HTTP request.
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Configuration for a remote custodial signer.
+#[derive(Debug, Clone)]
+pub struct CustodianConfig {
+    /// JSON-RPC endpoint of the custodian (or its adapter).
+    pub endpoint: String,
+    /// API key sent as a bearer token.
+    pub api_key: String,
+    /// Public key of the custodied account.
+    pub pubkey: Pubkey,
+    /// Interval between status polls while awaiting approval.
+    pub poll_interval: Duration,
+    /// Maximum time a blocking signer waits for approval.
+    pub approval_timeout: Duration,
+    /// Timeout for a single HTTP request.
+    pub request_timeout: Duration,
+}
+
+impl CustodianConfig {
+    /// Create a new config with default timeouts.
+    pub fn new(endpoint: impl Into<String>, api_key: impl Into<String>, pubkey: Pubkey) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            api_key: api_key.into(),
+            pubkey,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            approval_timeout: DEFAULT_APPROVAL_TIMEOUT,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+        }
+    }
+
+    /// Set the interval between status polls.
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Set the maximum time to wait for approval.
+    pub fn approval_timeout(mut self, timeout: Duration) -> Self {
+        self.approval_timeout = timeout;
+        self
+    }
+
+    /// Set the timeout for a single HTTP request.
+    pub fn request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = timeout;
+        self
+    }
+}
+
+/// Status of a custodian signing request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignatureStatus {
+    /// Awaiting approval by the custodian's approvers or policy engine.
+    Pending,
+    /// Approved and signed.
+    Signed,
+    /// Rejected by an approver or policy.
+    Rejected,
+}
+
+/// A signing request tracked by the custodian.
+#[derive(Debug, Clone)]
+pub struct SignatureRequest {
+    /// The custodian's identifier for this request.
+    pub request_id: String,
+    /// Current status.
+    pub status: SignatureStatus,
+    /// The signature, once signed.
+    pub signature: Option<Signature>,
+    /// Approvals collected so far, if reported by the custodian.
+    pub approvals: u32,
+    /// Approvals required, if reported by the custodian.
+    pub threshold: u32,
+    /// Rejection reason, if rejected.
+    pub reason: Option<String>,
+}
+
+/// Wire format of a signing request.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawSignatureRequest {
+    request_id: String,
+    status: SignatureStatus,
+    #[serde(default)]
+    signature: Option<String>,
+    #[serde(default)]
+    approvals: Option<u32>,
+    #[serde(default)]
+    threshold: Option<u32>,
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+impl TryFrom<RawSignatureRequest> for SignatureRequest {
+    type Error = CustodianError;
+
+    fn try_from(raw: RawSignatureRequest) -> Result<Self> {
+        let signature = match raw.signature {
+            Some(s) => {
+                let bytes = bs58::decode(&s)
+                    .into_vec()
+                    .map_err(|e| CustodianError::InvalidResponse(e.to_string()))?;
+                let sig = Signature::try_from(bytes.as_slice()).map_err(|_| {
+                    CustodianError::InvalidResponse("Signature must be 64 bytes".into())
+                })?;
+                Some(sig)
+            }
+            None => None,
+        };
+
+        if raw.status == SignatureStatus::Signed && signature.is_none() {
+            return Err(CustodianError::InvalidResponse(
+                "Signed request is missing a signature".into(),
+            ));
+        }
+
+        Ok(Self {
+            request_id: raw.request_id,
+            status: raw.status,
+            signature,
+            approvals: raw.approvals.unwrap_or(0),
+            threshold: raw.threshold.unwrap_or(1),
+            reason: raw.reason,
+        })
+    }
+}
+
+/// Async client for the custodian API.
+#[derive(Debug, Clone)]
+pub struct CustodianClient {
+    config: CustodianConfig,
+    http: reqwest::Client,
+}
+
+impl CustodianClient {
+    /// Create a new client from a config.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be built.
+    pub fn new(config: CustodianConfig) -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(config.request_timeout)
+            .build()?;
+        Ok(Self { config, http })
+    }
+
+    /// Get the client configuration.
+    pub fn config(&self) -> &CustodianConfig {
+        &self.config
+    }
+
+    /// Submit a transaction message for signing.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or the response is malformed.
+    pub async fn request_signature(&self, message: &[u8]) -> Result<SignatureRequest> {
+        let raw: RawSignatureRequest = self
+            .call(
+                "signTransaction",
+                json!({
+                    "pubkey": self.config.pubkey.to_string(),
+                    "message": BASE64.encode(message),
+                }),
+            )
+            .await?;
+        raw.try_into()
+    }
+
+    /// Fetch the current state of a signing request.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or the response is malformed.
+    pub async fn get_signature_request(&self, request_id: &str) -> Result<SignatureRequest> {
+        let raw: RawSignatureRequest = self
+            .call("getSignatureRequest", json!({ "requestId": request_id }))
+            .await?;
+        raw.try_into()
+    }
+
+    /// Poll a signing request until it is signed, rejected, or `timeout` elapses.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CustodianError::Rejected`] if the request is rejected, or
+    /// [`CustodianError::ApprovalTimeout`] if approval is not granted in time.
+    pub async fn wait_for_signature(
+        &self,
+        request: SignatureRequest,
+        timeout: Duration,
+    ) -> Result<Signature> {
+        let deadline = Instant::now() + timeout;
+        let mut current = request;
+
+        loop {
+            match current.status {
+                SignatureStatus::Signed => {
+                    return current.signature.ok_or_else(|| {
+                        CustodianError::InvalidResponse("Missing signature".into())
+                    });
+                }
+                SignatureStatus::Rejected => {
+                    return Err(CustodianError::Rejected {
+                        request_id: current.request_id,
+                        reason: current.reason.unwrap_or_else(|| "no reason given".into()),
+                    });
+                }
+                SignatureStatus::Pending => {}
+            }
+
+            if Instant::now() >= deadline {
+                return Err(CustodianError::ApprovalTimeout(current.request_id));
+            }
+            tokio::time::sleep(self.config.poll_interval).await;
+            current = self.get_signature_request(&current.request_id).await?;
+        }
+    }
+
+    /// Exchange public shares with a split-key co-signer.
+    ///
+    /// `share` is the local share's public key and `proof` its
+    /// [`KeyShare::prove`](crate::KeyShare::prove) signature over the
+    /// co-signer's domain.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or the response is malformed.
+    pub async fn enroll_share(&self, share: &Pubkey, proof: &Signature) -> Result<ShareEnrollment> {
+        let raw: RawShareEnrollment = self
+            .call(
+                "enrollShare",
+                json!({
+                    "share": share.to_string(),
+                    "proof": proof.to_string(),
+                }),
+            )
+            .await?;
+        raw.try_into()
+    }
+
+    /// Ask a split-key co-signer for its partial signature of a message.
+    ///
+    /// `nonce` is the local nonce commitment from
+    /// [`KeyShare::commit`](crate::KeyShare::commit).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails, the co-signer refuses, or the
+    /// response is malformed.
+    pub async fn co_sign(&self, message: &[u8], nonce: &Pubkey) -> Result<PartialSignature> {
+        let raw: RawPartialSignature = self
+            .call(
+                "coSign",
+                json!({
+                    "pubkey": self.config.pubkey.to_string(),
+                    "message": BASE64.encode(message),
+                    "nonce": nonce.to_string(),
+                }),
+            )
+            .await?;
+        raw.try_into()
+    }
+
+    async fn call<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+
+        let response = self
+            .http
+            .post(&self.config.endpoint)
+            .bearer_auth(&self.config.api_key)
+            .json(&body)
+            .send()
+            .await?;
+
+        if matches!(
+            response.status(),
+            reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN
+        ) {
+            return Err(CustodianError::Unauthorized);
+        }
+
+        let value: Value = response.error_for_status()?.json().await?;
+        parse_result(value)
+    }
+}
+
+/// Wire format of a co-signer's enrollment.
+#[derive(Deserialize)]
+struct RawShareEnrollment {
+    share: String,
+    proof: String,
+}
+
+impl TryFrom<RawShareEnrollment> for ShareEnrollment {
+    type Error = CustodianError;
+
+    fn try_from(raw: RawShareEnrollment) -> Result<Self> {
+        Ok(Self {
+            share: raw
+                .share
+                .parse()
+                .map_err(|_| CustodianError::InvalidResponse("Invalid share".into()))?,
+            proof: raw
+                .proof
+                .parse()
+                .map_err(|_| CustodianError::InvalidResponse("Invalid share proof".into()))?,
+        })
+    }
+}
+
+/// Wire format of a co-signer's partial signature.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawPartialSignature {
+    nonce: String,
+    partial_signature: String,
+}
+
+impl TryFrom<RawPartialSignature> for PartialSignature {
+    type Error = CustodianError;
+
+    fn try_from(raw: RawPartialSignature) -> Result<Self> {
+        let scalar = bs58::decode(&raw.partial_signature)
+            .into_vec()
+            .map_err(|e| CustodianError::InvalidResponse(e.to_string()))?;
+        Ok(Self {
+            nonce: raw
+                .nonce
+                .parse()
+                .map_err(|_| CustodianError::InvalidResponse("Invalid nonce".into()))?,
+            scalar: scalar.try_into().map_err(|_| {
+                CustodianError::InvalidResponse("Partial signature must be 32 bytes".into())
+            })?,
+        })
+    }
+}
+
+/// Parse the result of a JSON-RPC response envelope.
+fn parse_result<T: DeserializeOwned>(value: Value) -> Result<T> {
+    if let Some(error) = value.get("error") {
+        return Err(CustodianError::Rpc {
+            code: error.get("code").and_then(Value::as_i64).unwrap_or(0),
+            message: error
+                .get("message")
+                .and_then(Value::as_str)
+                .unwrap_or("unknown error")
+                .to_string(),
+        });
+    }
+
+    let result = value
+        .get("result")
+        .cloned()
+        .ok_or_else(|| CustodianError::InvalidResponse("Missing result".into()))?;
+    serde_json::from_value(result).map_err(|e| CustodianError::InvalidResponse(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_response(value: Value) -> Result<SignatureRequest> {
+        parse_result::<RawSignatureRequest>(value)?.try_into()
+    }
+
+    #[test]
+    fn test_parse_pending() {
+        let req = parse_response(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {
+                "requestId": "abc",
+                "status": "pending",
+                "approvals": 1,
+                "threshold": 2
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(req.request_id, "abc");
+        assert_eq!(req.status, SignatureStatus::Pending);
+        assert!(req.signature.is_none());
+        assert_eq!(req.approvals, 1);
+        assert_eq!(req.threshold, 2);
+    }
+
+    #[test]
+    fn test_parse_signed() {
+        let sig = Signature::from([7u8; 64]);
+        let req = parse_response(json!({
+            "result": {
+                "requestId": "abc",
+                "status": "signed",
+                "signature": sig.to_string()
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(req.status, SignatureStatus::Signed);
+        assert_eq!(req.signature, Some(sig));
+        assert_eq!(req.threshold, 1);
+    }
+
+    #[test]
+    fn test_parse_signed_without_signature() {
+        let err = parse_response(json!({
+            "result": { "requestId": "abc", "status": "signed" }
+        }))
+        .unwrap_err();
+        assert!(matches!(err, CustodianError::InvalidResponse(_)));
+    }
+
+    #[test]
+    fn test_parse_rpc_error() {
+        let err = parse_response(json!({
+            "error": { "code": -32000, "message": "policy violation" }
+        }))
+        .unwrap_err();
+        assert!(matches!(err, CustodianError::Rpc { code: -32000, .. }));
+    }
+
+    #[test]
+    fn test_parse_partial_signature() {
+        let nonce = Pubkey::new_unique();
+        let partial: PartialSignature = parse_result::<RawPartialSignature>(json!({
+            "result": {
+                "nonce": nonce.to_string(),
+                "partialSignature": bs58::encode([3u8; 32]).into_string()
+            }
+        }))
+        .unwrap()
+        .try_into()
+        .unwrap();
+        assert_eq!(partial.nonce, nonce);
+        assert_eq!(partial.scalar, [3u8; 32]);
+
+        let short: Result<PartialSignature> = parse_result::<RawPartialSignature>(json!({
+            "result": { "nonce": nonce.to_string(), "partialSignature": "abc" }
+        }))
+        .unwrap()
+        .try_into();
+        assert!(matches!(short, Err(CustodianError::InvalidResponse(_))));
+    }
+
+    #[test]
+    fn test_config_builder() {
+        let config = CustodianConfig::new("https://custody.example", "key", Pubkey::new_unique())
+            .poll_interval(Duration::from_millis(500))
+            .approval_timeout(Duration::from_secs(60));
+
+        assert_eq!(config.poll_interval, Duration::from_millis(500));
+        assert_eq!(config.approval_timeout, Duration::from_secs(60));
+        assert_eq!(config.request_timeout, DEFAULT_REQUEST_TIMEOUT);
+    }
+}
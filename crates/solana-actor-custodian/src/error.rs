@@ -0,0 +1,76 @@
+//! Error types for custodial signer operations.
+
+use thiserror::Error;
+
+/// Errors that can occur when talking to a remote custodian.
+#[derive(Error, Debug)]
+pub enum CustodianError {
+    /// HTTP transport error.
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// The custodian returned a JSON-RPC error.
+    #[error("Custodian error {code}: {message}")]
+    Rpc {
+        /// JSON-RPC error code.
+        code: i64,
+        /// Error message from the custodian.
+        message: String,
+    },
+
+    /// The API key was rejected.
+    #[error("Unauthorized: check the custodian API key")]
+    Unauthorized,
+
+    /// The custodian response could not be parsed.
+    #[error("Invalid response: {0}")]
+    InvalidResponse(String),
+
+    /// The signing request was rejected by the custodian's approvers or policy.
+    #[error("Signing request {request_id} rejected: {reason}")]
+    Rejected {
+        /// The custodian's request identifier.
+        request_id: String,
+        /// Reason given by the custodian.
+        reason: String,
+    },
+
+    /// Approval was not granted before the configured timeout.
+    #[error("Timed out waiting for custodian approval of request {0}")]
+    ApprovalTimeout(String),
+
+    /// A split-key share, nonce or partial signature was unusable.
+    #[error("Invalid key share: {0}")]
+    InvalidShare(String),
+
+    /// Unknown pending request handle.
+    #[error("Unknown signing request: {0}")]
+    UnknownRequest(u64),
+}
+
+/// Result type for custodial signer operations.
+pub type Result<T> = std::result::Result<T, CustodianError>;
+
+impl From<CustodianError> for solana_actor::SignerError {
+    fn from(err: CustodianError) -> Self {
+        match err {
+            CustodianError::Http(e) => Self::DeviceError(e.to_string()),
+            CustodianError::Unauthorized => Self::InvalidKey(err.to_string()),
+            CustodianError::Rejected { .. } => Self::UserCancelled,
+            other => Self::SigningFailed(other.to_string()),
+        }
+    }
+}
+
+impl From<CustodianError> for solana_actor::TransportError {
+    fn from(err: CustodianError) -> Self {
+        match err {
+            CustodianError::Http(e) => {
+                Self::Connection(solana_actor::ConnectionError::Rpc(e.to_string()))
+            }
+            CustodianError::ApprovalTimeout(_) => Self::Timeout,
+            CustodianError::Rejected { .. } => Self::ApprovalFailed(err.to_string()),
+            other => Self::Signer(other.into()),
+        }
+    }
+}
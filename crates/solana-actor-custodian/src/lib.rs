@@ -0,0 +1,63 @@
+//! Remote custodial signer for Solana.
+//!
+//! This crate lets teams put keys held by a custodian (Fireblocks, Turnkey,
+//! an in-house HSM gateway, etc.) behind the same interfaces as local keys:
+//!
+//! - [`CustodianSigner`] implements [`TransactionSigner`], blocking until the
+//!   custodian approves and signs
+//! - [`CustodianTransport`] implements [`WalletTransport`], surfacing the
+//!   custodian's approval latency as [`SubmitResult::Pending`]
+//! - [`SplitKeySigner`] implements [`TransactionSigner`] for a 2-of-2 split
+//!   key, combining a local share with a co-signer's so that neither can sign
+//!   alone (see [`split`])
+//!
+//! # Protocol
+//!
+//! The custodian is reached over HTTP with a bearer API key, using a small
+//! JSON-RPC 2.0 interface (`signTransaction`, `getSignatureRequest`). Vendor
+//! APIs are expected to be fronted by a thin adapter speaking this protocol.
+//! See the [`client`] module for details.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use solana_actor_custodian::{CustodianConfig, CustodianTransport};
+//! use solana_actor::{SubmitResult, WalletTransport};
+//! use std::time::Duration;
+//!
+//! let config = CustodianConfig::new(
+//!     "https://custody.example.com/rpc",
+//!     std::env::var("CUSTODIAN_API_KEY")?,
+//!     "CUSTODIED_PUBKEY".parse()?,
+//! )
+//! .poll_interval(Duration::from_secs(5));
+//!
+//! let transport = CustodianTransport::new(config)?;
+//! let result = transport.submit(&tx_message).await?;
+//!
+//! match result {
+//!     SubmitResult::Signed(sig) => println!("Signed: {}", sig),
+//!     SubmitResult::Pending { .. } => {
+//!         let done = transport
+//!             .wait_for_completion(result, Duration::from_secs(600))
+//!             .await?;
+//!         println!("Signed: {}", done.signature().unwrap());
+//!     }
+//!     _ => {}
+//! }
+//! ```
+
+pub mod client;
+mod error;
+mod signer;
+pub mod split;
+mod transport;
+
+pub use client::{CustodianClient, CustodianConfig, SignatureRequest, SignatureStatus};
+pub use error::{CustodianError, Result};
+pub use signer::CustodianSigner;
+pub use split::{KeyShare, PartialSignature, ShareEnrollment, SplitKeySigner};
+pub use transport::CustodianTransport;
+
+// Re-export traits for convenience
+pub use solana_actor::{SignerError, SubmitResult, TransactionSigner, WalletTransport};
@@ -0,0 +1,110 @@
+//! Blocking custodial signer implementation.
+
+use solana_actor::{SignerError, TransactionSigner};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+use crate::client::{CustodianClient, CustodianConfig};
+use crate::error::{CustodianError, Result};
+
+/// A [`TransactionSigner`] backed by a remote custodian.
+///
+/// Signing submits the message to the custodian and blocks until the request
+/// is approved and signed, rejected, or the configured approval timeout
+/// elapses. Use [`CustodianTransport`](crate::CustodianTransport) instead when
+/// the caller should not block on approval.
+///
+/// Because it blocks on a private runtime, `sign_transaction` must not be
+/// called directly from an async context; wrap it in [`DirectTransport`]
+/// (which uses `spawn_blocking`) or `tokio::task::spawn_blocking`.
+///
+/// [`DirectTransport`]: solana_actor::DirectTransport
+///
+/// # Example
+///
+/// ```ignore
+/// use solana_actor_custodian::{CustodianConfig, CustodianSigner};
+/// use solana_actor::TransactionSigner;
+///
+/// let config = CustodianConfig::new(
+///     "https://custody.example.com/rpc",
+///     std::env::var("CUSTODIAN_API_KEY")?,
+///     "CUSTODIED_PUBKEY".parse()?,
+/// );
+/// let signer = CustodianSigner::new(config)?;
+///
+/// // Blocks until the custodian's approvers sign off
+/// let signature = signer.sign_transaction(&tx_message)?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct CustodianSigner {
+    client: CustodianClient,
+}
+
+impl CustodianSigner {
+    /// Create a new custodial signer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be built.
+    pub fn new(config: CustodianConfig) -> Result<Self> {
+        Ok(Self {
+            client: CustodianClient::new(config)?,
+        })
+    }
+
+    /// Get a reference to the underlying client.
+    pub fn client(&self) -> &CustodianClient {
+        &self.client
+    }
+
+    /// Get the public key as a base58 string.
+    pub fn pubkey_base58(&self) -> String {
+        self.client.config().pubkey.to_string()
+    }
+
+    fn sign_blocking(&self, message: &[u8]) -> Result<Signature> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| CustodianError::InvalidResponse(e.to_string()))?;
+
+        runtime.block_on(async {
+            let request = self.client.request_signature(message).await?;
+            self.client
+                .wait_for_signature(request, self.client.config().approval_timeout)
+                .await
+        })
+    }
+}
+
+impl TransactionSigner for CustodianSigner {
+    fn pubkey(&self) -> Pubkey {
+        self.client.config().pubkey
+    }
+
+    fn sign_transaction(&self, message: &[u8]) -> std::result::Result<Signature, SignerError> {
+        Ok(self.sign_blocking(message)?)
+    }
+
+    fn is_interactive(&self) -> bool {
+        // Custodian approvals may require a human in the loop.
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signer_pubkey() {
+        let pubkey = Pubkey::new_unique();
+        let signer =
+            CustodianSigner::new(CustodianConfig::new("http://localhost:0", "key", pubkey))
+                .unwrap();
+
+        assert_eq!(TransactionSigner::pubkey(&signer), pubkey);
+        assert_eq!(signer.pubkey_base58(), pubkey.to_string());
+        assert!(signer.is_interactive());
+    }
+}
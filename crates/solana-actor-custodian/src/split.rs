@@ -0,0 +1,495 @@
+//! Split-key signing with a remote co-signer.
+//!
+//! A split key is an ordinary ed25519 public key whose secret is the sum of
+//! two shares: one held locally (typically a keypair in the keyring) and one
+//! held by a co-signer service. Neither share can sign alone, so a stolen
+//! laptop can't move funds without the co-signer, and the co-signer can't
+//! sign without the laptop. Signatures are plain ed25519 and verify against
+//! the split key, so on-chain it looks like any other account.
+//!
+//! # Protocol
+//!
+//! Enrollment exchanges public shares, each with a proof of possession
+//! ([`KeyShare::prove`]), so neither side can pick its share to cancel out
+//! the other's. The split key is the sum of the two public shares
+//! ([`KeyShare::combine`]).
+//!
+//! Signing takes one round trip:
+//!
+//! 1. The local side picks a fresh nonce and sends its commitment `R1` with
+//!    the message ([`KeyShare::commit`]).
+//! 2. The co-signer picks its own nonce `R2` and returns it with its partial
+//!    signature `s2 = r2 + c·a2`, where `c = H(R1 + R2 ‖ A ‖ M)`
+//!    ([`KeyShare::respond`]).
+//! 3. The local side adds its own partial signature and checks that the
+//!    result verifies before using it ([`KeyShare::finish`]).
+//!
+//! The local side should run one signing session at a time; concurrent
+//! sessions let a malicious co-signer choose its nonces across them.
+
+use std::fmt;
+
+use curve25519_dalek::{
+    edwards::{CompressedEdwardsY, EdwardsPoint},
+    scalar::{Scalar, clamp_integer},
+};
+use rand::RngCore;
+use sha2::{Digest, Sha512};
+use solana_actor::{SignerError, TransactionSigner};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::client::{CustodianClient, CustodianConfig};
+use crate::error::{CustodianError, Result};
+
+/// Prefix of the message a share signs to prove possession.
+pub const SHARE_PROOF_DOMAIN: &[u8] = b"solana-split-key-share:";
+
+/// A co-signer's public share and its proof of possession.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShareEnrollment {
+    /// The co-signer's public share.
+    pub share: Pubkey,
+    /// Signature by `share` over [`SHARE_PROOF_DOMAIN`] and `share`.
+    pub proof: Signature,
+}
+
+/// A co-signer's nonce and partial signature for one message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartialSignature {
+    /// The co-signer's nonce commitment.
+    pub nonce: Pubkey,
+    /// The co-signer's partial signature scalar.
+    pub scalar: [u8; 32],
+}
+
+/// The local half of a signing session, from [`KeyShare::commit`].
+///
+/// Consumed by [`KeyShare::finish`] so a nonce is never used twice.
+pub struct SigningNonce {
+    secret: Scalar,
+    commitment: Pubkey,
+}
+
+impl SigningNonce {
+    /// The commitment to send to the co-signer.
+    pub fn commitment(&self) -> Pubkey {
+        self.commitment
+    }
+}
+
+impl Drop for SigningNonce {
+    fn drop(&mut self) {
+        self.secret.zeroize();
+    }
+}
+
+impl fmt::Debug for SigningNonce {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SigningNonce")
+            .field("commitment", &self.commitment)
+            .finish_non_exhaustive()
+    }
+}
+
+/// One share of a split key.
+///
+/// The secret is zeroized when dropped.
+pub struct KeyShare {
+    scalar: Scalar,
+    prefix: [u8; 32],
+    public: Pubkey,
+}
+
+impl KeyShare {
+    /// Derive a share from a 32-byte ed25519 secret key, such as a keyring
+    /// keypair's.
+    ///
+    /// The share's public key is that keypair's public key.
+    pub fn from_seed(seed: &[u8; 32]) -> Self {
+        let mut hash = Zeroizing::new([0u8; 64]);
+        hash.copy_from_slice(&Sha512::digest(seed));
+        let mut lower = Zeroizing::new([0u8; 32]);
+        lower.copy_from_slice(&hash[..32]);
+        let scalar = Scalar::from_bytes_mod_order(clamp_integer(*lower));
+
+        let mut prefix = [0u8; 32];
+        prefix.copy_from_slice(&hash[32..]);
+
+        Self {
+            scalar,
+            prefix,
+            public: to_pubkey(&EdwardsPoint::mul_base(&scalar)),
+        }
+    }
+
+    /// Generate a random share.
+    pub fn generate() -> Self {
+        let mut seed = Zeroizing::new([0u8; 32]);
+        rand::rngs::OsRng.fill_bytes(seed.as_mut());
+        Self::from_seed(&seed)
+    }
+
+    /// The share's public key.
+    pub fn public(&self) -> Pubkey {
+        self.public
+    }
+
+    /// Prove possession of the share, for enrollment.
+    pub fn prove(&self) -> Signature {
+        let message = proof_message(&self.public);
+        let mut r = self.derive_nonce(&[], &message);
+        let nonce = EdwardsPoint::mul_base(&r);
+        let c = challenge(&nonce, &self.public, &message);
+        let s = r + c * self.scalar;
+        r.zeroize();
+        to_signature(&nonce, &s)
+    }
+
+    /// Check the other side's proof of possession and combine the public
+    /// shares into the split key.
+    ///
+    /// Both sides get the same key.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CustodianError::InvalidShare`] if the other share is not a
+    /// usable point or its proof doesn't verify.
+    pub fn combine(&self, other: &ShareEnrollment) -> Result<Pubkey> {
+        let point = decompress(&other.share)?;
+        if !other
+            .proof
+            .verify(other.share.as_ref(), &proof_message(&other.share))
+        {
+            return Err(CustodianError::InvalidShare(format!(
+                "proof of possession for {} does not verify",
+                other.share
+            )));
+        }
+        Ok(to_pubkey(&(EdwardsPoint::mul_base(&self.scalar) + point)))
+    }
+
+    /// Start signing `message`: pick a nonce whose commitment goes to the
+    /// co-signer.
+    pub fn commit(&self, message: &[u8]) -> SigningNonce {
+        let secret = self.fresh_nonce(message);
+        SigningNonce {
+            commitment: to_pubkey(&EdwardsPoint::mul_base(&secret)),
+            secret,
+        }
+    }
+
+    /// Co-signer side: answer the local side's nonce commitment with a nonce
+    /// and partial signature.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CustodianError::InvalidShare`] if `commitment` is not a
+    /// usable point.
+    pub fn respond(
+        &self,
+        split_key: &Pubkey,
+        message: &[u8],
+        commitment: &Pubkey,
+    ) -> Result<PartialSignature> {
+        let theirs = decompress(commitment)?;
+        let mut r = self.fresh_nonce(message);
+        let ours = EdwardsPoint::mul_base(&r);
+        let c = challenge(&(theirs + ours), split_key, message);
+        let s = r + c * self.scalar;
+        r.zeroize();
+
+        Ok(PartialSignature {
+            nonce: to_pubkey(&ours),
+            scalar: s.to_bytes(),
+        })
+    }
+
+    /// Local side: add this share's partial signature to the co-signer's.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CustodianError::InvalidShare`] if the co-signer's response is
+    /// malformed or the combined signature doesn't verify against
+    /// `split_key`.
+    pub fn finish(
+        &self,
+        nonce: SigningNonce,
+        split_key: &Pubkey,
+        message: &[u8],
+        partial: &PartialSignature,
+    ) -> Result<Signature> {
+        let theirs = decompress(&partial.nonce)?;
+        let their_s = Option::<Scalar>::from(Scalar::from_canonical_bytes(partial.scalar))
+            .ok_or_else(|| {
+                CustodianError::InvalidShare("partial signature is not a canonical scalar".into())
+            })?;
+
+        let combined = EdwardsPoint::mul_base(&nonce.secret) + theirs;
+        let c = challenge(&combined, split_key, message);
+        let s = nonce.secret + c * self.scalar + their_s;
+
+        let signature = to_signature(&combined, &s);
+        if !signature.verify(split_key.as_ref(), message) {
+            return Err(CustodianError::InvalidShare(
+                "combined signature does not verify against the split key".into(),
+            ));
+        }
+        Ok(signature)
+    }
+
+    /// Deterministic nonce for `message`, hedged with `randomness`.
+    fn derive_nonce(&self, randomness: &[u8], message: &[u8]) -> Scalar {
+        let mut hash = Sha512::new();
+        hash.update(self.prefix);
+        hash.update(randomness);
+        hash.update(message);
+        wide_scalar(hash)
+    }
+
+    /// Nonce that is never reused, even for the same message.
+    fn fresh_nonce(&self, message: &[u8]) -> Scalar {
+        let mut randomness = Zeroizing::new([0u8; 32]);
+        rand::rngs::OsRng.fill_bytes(randomness.as_mut());
+        self.derive_nonce(randomness.as_ref(), message)
+    }
+}
+
+impl Drop for KeyShare {
+    fn drop(&mut self) {
+        self.scalar.zeroize();
+        self.prefix.zeroize();
+    }
+}
+
+impl fmt::Debug for KeyShare {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyShare")
+            .field("public", &self.public)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A [`TransactionSigner`] for a split key, holding the local share and
+/// reaching the co-signer over the custodian protocol.
+///
+/// Signing blocks on a private runtime like
+/// [`CustodianSigner`](crate::CustodianSigner), so from async code wrap it in
+/// [`DirectTransport`](solana_actor::DirectTransport).
+///
+/// # Example
+///
+/// ```ignore
+/// use solana_actor_custodian::{CustodianConfig, KeyShare, SplitKeySigner};
+///
+/// // The local share is a keypair in the keyring
+/// let share = || KeyShare::from_seed(&keypair.secret_bytes());
+///
+/// // Once, to create the split key
+/// let config = CustodianConfig::new(endpoint, api_key, Pubkey::default());
+/// let signer = SplitKeySigner::enroll(config, share()).await?;
+/// println!("Split key: {}", signer.pubkey());
+///
+/// // Afterwards
+/// let config = CustodianConfig::new(endpoint, api_key, split_key);
+/// let signer = SplitKeySigner::new(config, share())?;
+/// let signature = signer.sign_transaction(&tx_message)?;
+/// ```
+#[derive(Debug)]
+pub struct SplitKeySigner {
+    client: CustodianClient,
+    share: KeyShare,
+}
+
+impl SplitKeySigner {
+    /// Create a signer for an enrolled split key.
+    ///
+    /// `config.pubkey` is the split key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be built.
+    pub fn new(config: CustodianConfig, share: KeyShare) -> Result<Self> {
+        Ok(Self {
+            client: CustodianClient::new(config)?,
+            share,
+        })
+    }
+
+    /// Enroll `share` with the co-signer and create a signer for the
+    /// resulting split key.
+    ///
+    /// `config.pubkey` is replaced by the split key. Store it (and keep the
+    /// share) to recreate the signer with [`new`](Self::new).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or the co-signer's share is
+    /// invalid.
+    pub async fn enroll(mut config: CustodianConfig, share: KeyShare) -> Result<Self> {
+        let client = CustodianClient::new(config.clone())?;
+        let enrollment = client.enroll_share(&share.public(), &share.prove()).await?;
+        config.pubkey = share.combine(&enrollment)?;
+        Self::new(config, share)
+    }
+
+    /// Get a reference to the underlying client.
+    pub fn client(&self) -> &CustodianClient {
+        &self.client
+    }
+
+    /// The local share.
+    pub fn share(&self) -> &KeyShare {
+        &self.share
+    }
+
+    fn sign_blocking(&self, message: &[u8]) -> Result<Signature> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| CustodianError::InvalidResponse(e.to_string()))?;
+
+        let nonce = self.share.commit(message);
+        let partial = runtime.block_on(self.client.co_sign(message, &nonce.commitment()))?;
+        self.share
+            .finish(nonce, &self.client.config().pubkey, message, &partial)
+    }
+}
+
+impl TransactionSigner for SplitKeySigner {
+    fn pubkey(&self) -> Pubkey {
+        self.client.config().pubkey
+    }
+
+    fn sign_transaction(&self, message: &[u8]) -> std::result::Result<Signature, SignerError> {
+        Ok(self.sign_blocking(message)?)
+    }
+}
+
+fn proof_message(share: &Pubkey) -> Vec<u8> {
+    [SHARE_PROOF_DOMAIN, share.as_ref()].concat()
+}
+
+/// Ed25519 challenge `H(R ‖ A ‖ M)`.
+fn challenge(nonce: &EdwardsPoint, pubkey: &Pubkey, message: &[u8]) -> Scalar {
+    let mut hash = Sha512::new();
+    hash.update(nonce.compress().as_bytes());
+    hash.update(pubkey.as_ref());
+    hash.update(message);
+    wide_scalar(hash)
+}
+
+fn wide_scalar(hash: Sha512) -> Scalar {
+    let mut bytes = Zeroizing::new([0u8; 64]);
+    bytes.copy_from_slice(&hash.finalize());
+    Scalar::from_bytes_mod_order_wide(&bytes)
+}
+
+fn decompress(pubkey: &Pubkey) -> Result<EdwardsPoint> {
+    CompressedEdwardsY(pubkey.to_bytes())
+        .decompress()
+        .filter(|point| !point.is_small_order())
+        .ok_or_else(|| CustodianError::InvalidShare(format!("{} is not a usable point", pubkey)))
+}
+
+fn to_pubkey(point: &EdwardsPoint) -> Pubkey {
+    Pubkey::new_from_array(point.compress().to_bytes())
+}
+
+fn to_signature(nonce: &EdwardsPoint, s: &Scalar) -> Signature {
+    let mut bytes = [0u8; 64];
+    bytes[..32].copy_from_slice(nonce.compress().as_bytes());
+    bytes[32..].copy_from_slice(s.as_bytes());
+    Signature::from(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::signature::Keypair;
+    use solana_sdk::signer::Signer;
+
+    fn enroll(local: &KeyShare, remote: &KeyShare) -> Pubkey {
+        let split_key = local
+            .combine(&ShareEnrollment {
+                share: remote.public(),
+                proof: remote.prove(),
+            })
+            .unwrap();
+        let remote_view = remote
+            .combine(&ShareEnrollment {
+                share: local.public(),
+                proof: local.prove(),
+            })
+            .unwrap();
+        assert_eq!(split_key, remote_view);
+        split_key
+    }
+
+    #[test]
+    fn test_share_matches_keypair() {
+        let keypair = Keypair::new();
+        let seed: [u8; 32] = keypair.to_bytes()[..32].try_into().unwrap();
+        let share = KeyShare::from_seed(&seed);
+        assert_eq!(share.public(), keypair.pubkey());
+    }
+
+    #[test]
+    fn test_split_signature_verifies() {
+        let local = KeyShare::generate();
+        let remote = KeyShare::generate();
+        let split_key = enroll(&local, &remote);
+        assert_ne!(split_key, local.public());
+
+        let message = b"transfer 1 SOL";
+        let nonce = local.commit(message);
+        let partial = remote
+            .respond(&split_key, message, &nonce.commitment())
+            .unwrap();
+        let signature = local.finish(nonce, &split_key, message, &partial).unwrap();
+
+        assert!(signature.verify(split_key.as_ref(), message));
+        assert!(!signature.verify(local.public().as_ref(), message));
+    }
+
+    #[test]
+    fn test_partial_for_other_message_is_rejected() {
+        let local = KeyShare::generate();
+        let remote = KeyShare::generate();
+        let split_key = enroll(&local, &remote);
+
+        let nonce = local.commit(b"transfer 1 SOL");
+        let partial = remote
+            .respond(&split_key, b"transfer 100 SOL", &nonce.commitment())
+            .unwrap();
+        assert!(matches!(
+            local.finish(nonce, &split_key, b"transfer 1 SOL", &partial),
+            Err(CustodianError::InvalidShare(_))
+        ));
+    }
+
+    #[test]
+    fn test_rogue_share_is_rejected() {
+        let local = KeyShare::generate();
+        let attacker = KeyShare::generate();
+
+        // A share chosen to cancel the local one, so the split key would be
+        // the attacker's own key; the attacker can't prove possession of it
+        let local_point = decompress(&local.public()).unwrap();
+        let attacker_point = decompress(&attacker.public()).unwrap();
+        let rogue = to_pubkey(&(attacker_point - local_point));
+
+        let err = local
+            .combine(&ShareEnrollment {
+                share: rogue,
+                proof: attacker.prove(),
+            })
+            .unwrap_err();
+        assert!(matches!(err, CustodianError::InvalidShare(_)));
+
+        // Small-order points are refused outright
+        let identity = to_pubkey(&EdwardsPoint::default());
+        assert_ne!(local.commit(b"m").commitment(), identity);
+        assert!(attacker.respond(&rogue, b"m", &identity).is_err());
+    }
+}
@@ -0,0 +1,289 @@
+//! Non-blocking custodial transport implementation.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use solana_actor::{SubmitResult, TransportError, WalletTransport};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::client::{CustodianClient, CustodianConfig, SignatureRequest, SignatureStatus};
+use crate::error::{CustodianError, Result};
+
+/// A [`WalletTransport`] backed by a remote custodian.
+///
+/// Unlike [`CustodianSigner`](crate::CustodianSigner), submitting does not
+/// block on approval. If the custodian has not signed immediately, the result
+/// is [`SubmitResult::Pending`] where:
+///
+/// - `proposal` is the custodied account's public key
+/// - `transaction_index` is a local handle for the custodian request
+///   (see [`CustodianTransport::request_id`])
+/// - `approvals` / `threshold` are the custodian's reported approval counts
+///
+/// Once approved, [`check_status`](WalletTransport::check_status) returns
+/// [`SubmitResult::Signed`] with the custodian's signature.
+///
+/// # Example
+///
+/// ```ignore
+/// use solana_actor_custodian::{CustodianConfig, CustodianTransport};
+/// use solana_actor::{SubmitResult, WalletTransport};
+/// use std::time::Duration;
+///
+/// let transport = CustodianTransport::new(config)?;
+/// let result = transport.submit(&tx_message).await?;
+///
+/// if let SubmitResult::Pending { approvals, threshold, .. } = &result {
+///     println!("Awaiting custodian approval ({}/{})", approvals, threshold);
+/// }
+///
+/// let signed = transport
+///     .wait_for_completion(result, Duration::from_secs(600))
+///     .await?;
+/// ```
+#[derive(Debug)]
+pub struct CustodianTransport {
+    client: CustodianClient,
+    next_index: AtomicU64,
+    pending: Mutex<HashMap<u64, String>>,
+}
+
+impl CustodianTransport {
+    /// Create a new custodial transport.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be built.
+    pub fn new(config: CustodianConfig) -> Result<Self> {
+        Ok(Self {
+            client: CustodianClient::new(config)?,
+            next_index: AtomicU64::new(0),
+            pending: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Get a reference to the underlying client.
+    pub fn client(&self) -> &CustodianClient {
+        &self.client
+    }
+
+    /// Get the custodian request ID for a pending `transaction_index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pending request map lock is poisoned.
+    pub fn request_id(&self, transaction_index: u64) -> Option<String> {
+        self.pending
+            .lock()
+            .unwrap()
+            .get(&transaction_index)
+            .cloned()
+    }
+
+    fn track(&self, request_id: String) -> u64 {
+        let index = self.next_index.fetch_add(1, Ordering::Relaxed);
+        self.pending.lock().unwrap().insert(index, request_id);
+        index
+    }
+
+    fn untrack(&self, transaction_index: u64) {
+        self.pending.lock().unwrap().remove(&transaction_index);
+    }
+
+    /// Convert a custodian request into a [`SubmitResult`].
+    fn to_result(&self, request: SignatureRequest, index: Option<u64>) -> Result<SubmitResult> {
+        match request.status {
+            SignatureStatus::Signed => {
+                if let Some(index) = index {
+                    self.untrack(index);
+                }
+                let signature = request
+                    .signature
+                    .ok_or_else(|| CustodianError::InvalidResponse("Missing signature".into()))?;
+                Ok(SubmitResult::Signed(signature))
+            }
+            SignatureStatus::Rejected => {
+                if let Some(index) = index {
+                    self.untrack(index);
+                }
+                Err(CustodianError::Rejected {
+                    request_id: request.request_id,
+                    reason: request.reason.unwrap_or_else(|| "no reason given".into()),
+                })
+            }
+            SignatureStatus::Pending => {
+                let transaction_index = match index {
+                    Some(index) => index,
+                    None => self.track(request.request_id),
+                };
+                Ok(SubmitResult::Pending {
+                    proposal: self.client.config().pubkey,
+                    transaction_index,
+                    approvals: request.approvals,
+                    threshold: request.threshold,
+                })
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl WalletTransport for CustodianTransport {
+    fn authority(&self) -> Pubkey {
+        self.client.config().pubkey
+    }
+
+    async fn submit(&self, message: &[u8]) -> std::result::Result<SubmitResult, TransportError> {
+        let request = self.client.request_signature(message).await?;
+        Ok(self.to_result(request, None)?)
+    }
+
+    async fn check_status(
+        &self,
+        result: &SubmitResult,
+    ) -> std::result::Result<SubmitResult, TransportError> {
+        let SubmitResult::Pending {
+            transaction_index, ..
+        } = result
+        else {
+            return Ok(result.clone());
+        };
+
+        let request_id = self
+            .request_id(*transaction_index)
+            .ok_or(CustodianError::UnknownRequest(*transaction_index))?;
+        let request = self.client.get_signature_request(&request_id).await?;
+        Ok(self.to_result(request, Some(*transaction_index))?)
+    }
+
+    async fn wait_for_completion(
+        &self,
+        result: SubmitResult,
+        timeout: Duration,
+    ) -> std::result::Result<SubmitResult, TransportError> {
+        if result.is_complete() {
+            return Ok(result);
+        }
+
+        let deadline = Instant::now() + timeout;
+        let mut current = result;
+
+        while Instant::now() < deadline {
+            current = self.check_status(&current).await?;
+            if current.is_complete() {
+                return Ok(current);
+            }
+            tokio::time::sleep(self.client.config().poll_interval).await;
+        }
+
+        Err(TransportError::Timeout)
+    }
+
+    fn requires_network(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::signature::Signature;
+
+    fn transport() -> CustodianTransport {
+        CustodianTransport::new(CustodianConfig::new(
+            "http://localhost:0",
+            "key",
+            Pubkey::new_unique(),
+        ))
+        .unwrap()
+    }
+
+    fn request(status: SignatureStatus, signature: Option<Signature>) -> SignatureRequest {
+        SignatureRequest {
+            request_id: "req-1".into(),
+            status,
+            signature,
+            approvals: 1,
+            threshold: 2,
+            reason: None,
+        }
+    }
+
+    #[test]
+    fn test_pending_is_tracked() {
+        let transport = transport();
+        let result = transport
+            .to_result(request(SignatureStatus::Pending, None), None)
+            .unwrap();
+
+        let SubmitResult::Pending {
+            proposal,
+            transaction_index,
+            approvals,
+            threshold,
+        } = result
+        else {
+            panic!("expected pending");
+        };
+        assert_eq!(proposal, transport.authority());
+        assert_eq!(approvals, 1);
+        assert_eq!(threshold, 2);
+        assert_eq!(
+            transport.request_id(transaction_index).as_deref(),
+            Some("req-1")
+        );
+    }
+
+    #[test]
+    fn test_signed_untracks() {
+        let transport = transport();
+        let SubmitResult::Pending {
+            transaction_index, ..
+        } = transport
+            .to_result(request(SignatureStatus::Pending, None), None)
+            .unwrap()
+        else {
+            panic!("expected pending");
+        };
+
+        let sig = Signature::from([1u8; 64]);
+        let result = transport
+            .to_result(
+                request(SignatureStatus::Signed, Some(sig)),
+                Some(transaction_index),
+            )
+            .unwrap();
+
+        assert_eq!(result.signature(), Some(&sig));
+        assert!(transport.request_id(transaction_index).is_none());
+    }
+
+    #[test]
+    fn test_rejected_is_error() {
+        let transport = transport();
+        let err = transport
+            .to_result(request(SignatureStatus::Rejected, None), None)
+            .unwrap_err();
+        assert!(matches!(err, CustodianError::Rejected { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_check_status_unknown_index() {
+        let transport = transport();
+        let pending = SubmitResult::Pending {
+            proposal: transport.authority(),
+            transaction_index: 42,
+            approvals: 0,
+            threshold: 1,
+        };
+        assert!(transport.check_status(&pending).await.is_err());
+    }
+
+    #[test]
+    fn test_requires_network() {
+        assert!(transport().requires_network());
+    }
+}
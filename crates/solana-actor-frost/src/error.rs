@@ -0,0 +1,80 @@
+//! Error types for FROST signing.
+
+use thiserror::Error;
+
+/// Errors that can occur during a FROST signing ceremony.
+#[derive(Error, Debug)]
+pub enum FrostError {
+    /// The FROST library rejected a key, commitment or share.
+    #[error("FROST error: {0}")]
+    Frost(#[from] frost_ed25519::Error),
+
+    /// A contribution came from an identifier that holds no share of the key.
+    #[error("Unknown participant: {0}")]
+    UnknownParticipant(String),
+
+    /// A participant contributed to a round it wasn't asked for.
+    #[error("Unexpected contribution for round {0}")]
+    UnexpectedRound(String),
+
+    /// The participant's commitment wasn't chosen for this signing package.
+    #[error("Participant was not selected to sign")]
+    NotSelected,
+
+    /// The signing package carries a different message than was committed to.
+    #[error("Signing package message differs from the committed message")]
+    MessageMismatch,
+
+    /// The participant has no open commitment for the session.
+    #[error("No commitment for session {0}")]
+    UnknownSession(String),
+
+    /// The aggregated signature doesn't verify for the group key.
+    #[error("Aggregated signature does not verify")]
+    InvalidSignature,
+
+    /// The group key isn't a valid Solana public key.
+    #[error("Invalid group key: {0}")]
+    InvalidKey(String),
+
+    /// A request or contribution couldn't be parsed.
+    #[error("Invalid message: {0}")]
+    InvalidFormat(String),
+
+    /// Reading or writing a request or contribution failed.
+    #[error("Exchange error: {0}")]
+    Exchange(#[from] std::io::Error),
+
+    /// Not enough participants contributed before the deadline.
+    #[error("Signing ceremony timed out")]
+    Timeout,
+}
+
+/// Result type for FROST operations.
+pub type Result<T> = std::result::Result<T, FrostError>;
+
+impl From<serde_json::Error> for FrostError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::InvalidFormat(err.to_string())
+    }
+}
+
+impl From<FrostError> for solana_actor::SignerError {
+    fn from(err: FrostError) -> Self {
+        match err {
+            FrostError::InvalidKey(msg) => Self::InvalidKey(msg),
+            FrostError::InvalidFormat(msg) => Self::InvalidFormat(msg),
+            FrostError::Exchange(err) => Self::Io(err),
+            other => Self::SigningFailed(other.to_string()),
+        }
+    }
+}
+
+impl From<FrostError> for solana_actor::TransportError {
+    fn from(err: FrostError) -> Self {
+        match err {
+            FrostError::Timeout => Self::Timeout,
+            other => Self::Signer(other.into()),
+        }
+    }
+}
@@ -0,0 +1,261 @@
+//! Moving requests and contributions between coordinator and participants.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+
+use crate::error::{FrostError, Result};
+use crate::session::{Contribution, SigningRequest};
+
+/// Suffix of request files in a [`DirectoryExchange`].
+const REQUEST_SUFFIX: &str = ".request.json";
+
+/// Suffix of contribution files in a [`DirectoryExchange`].
+const CONTRIBUTION_SUFFIX: &str = ".contribution.json";
+
+/// Carries requests to participants and their contributions back.
+///
+/// Implement this for however participants are reached: a shared or
+/// sneakernet directory, a socket, or a chat bot.
+#[async_trait]
+pub trait ShareExchange: Send + Sync {
+    /// Make `request` available to participants.
+    ///
+    /// A round two request replaces the round one request of its session.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FrostError::Exchange`] if the request can't be published.
+    async fn publish(&self, request: &SigningRequest) -> Result<()>;
+
+    /// Wait for the next contribution from any participant.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FrostError::Exchange`] if reading fails, or
+    /// [`FrostError::InvalidFormat`] for a malformed contribution.
+    async fn receive(&self) -> Result<Contribution>;
+}
+
+/// Exchanges JSON files through a directory.
+///
+/// The coordinator writes `<session>.request.json`; participants answer by
+/// dropping a `*.contribution.json` file next to it, which the coordinator
+/// reads and removes. The directory can be shared, synced, or carried on a
+/// USB stick to air-gapped participants.
+pub struct DirectoryExchange {
+    dir: PathBuf,
+    poll_interval: Duration,
+}
+
+impl DirectoryExchange {
+    /// Exchange files through `dir`, which must exist.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            poll_interval: Duration::from_millis(500),
+        }
+    }
+
+    /// Set how often the directory is checked for contributions.
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Read the open request files in a directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory or a request can't be read.
+    pub fn read_requests(dir: &Path) -> Result<Vec<SigningRequest>> {
+        let mut requests = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if has_suffix(&path, REQUEST_SUFFIX) {
+                requests.push(serde_json::from_slice(&std::fs::read(&path)?)?);
+            }
+        }
+        Ok(requests)
+    }
+
+    /// Write a participant's contribution into a directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FrostError::Exchange`] if the file can't be written.
+    pub fn write_contribution(dir: &Path, contribution: &Contribution) -> Result<PathBuf> {
+        let round = match contribution {
+            Contribution::Commitment { .. } => "commit",
+            Contribution::Share { .. } => "sign",
+        };
+        let identifier = serde_json::to_string(&contribution.identifier())?;
+        let name = format!(
+            "{}.{}.{}{}",
+            contribution.session(),
+            round,
+            identifier.trim_matches('"'),
+            CONTRIBUTION_SUFFIX
+        );
+        let path = dir.join(name);
+        write_atomic(&path, &serde_json::to_vec_pretty(contribution)?)?;
+        Ok(path)
+    }
+}
+
+#[async_trait]
+impl ShareExchange for DirectoryExchange {
+    async fn publish(&self, request: &SigningRequest) -> Result<()> {
+        let path = self
+            .dir
+            .join(format!("{}{}", request.session(), REQUEST_SUFFIX));
+        write_atomic(&path, &serde_json::to_vec_pretty(request)?)
+    }
+
+    async fn receive(&self) -> Result<Contribution> {
+        loop {
+            for entry in std::fs::read_dir(&self.dir)? {
+                let path = entry?.path();
+                if !has_suffix(&path, CONTRIBUTION_SUFFIX) {
+                    continue;
+                }
+                let contents = std::fs::read(&path)?;
+                std::fs::remove_file(&path)?;
+                return Ok(serde_json::from_slice(&contents)?);
+            }
+            tokio::time::sleep(self.poll_interval).await;
+        }
+    }
+}
+
+/// Exchanges JSON lines over a Unix socket.
+///
+/// Each participant connects, reads the current request as one line, and
+/// writes its contribution back as one line.
+pub struct SocketExchange {
+    listener: UnixListener,
+    current: Mutex<Option<SigningRequest>>,
+}
+
+impl SocketExchange {
+    /// Listen on a Unix socket at `path`.
+    ///
+    /// Must be called from within a Tokio runtime.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FrostError::Exchange`] if the socket can't be bound.
+    pub fn bind(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self {
+            listener: UnixListener::bind(path)?,
+            current: Mutex::new(None),
+        })
+    }
+
+    /// Connect to a coordinator's socket and answer its current request.
+    ///
+    /// `respond` sees the request and returns the contribution, or `None`
+    /// to disconnect without contributing.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection fails or `respond` fails.
+    pub async fn contribute<F>(path: impl AsRef<Path>, respond: F) -> Result<bool>
+    where
+        F: FnOnce(&SigningRequest) -> Result<Option<Contribution>>,
+    {
+        let stream = UnixStream::connect(path).await?;
+        let (reader, mut writer) = stream.into_split();
+
+        let mut line = String::new();
+        BufReader::new(reader).read_line(&mut line).await?;
+        let request: SigningRequest = serde_json::from_str(&line)?;
+
+        let Some(contribution) = respond(&request)? else {
+            return Ok(false);
+        };
+        let mut line = serde_json::to_string(&contribution)?;
+        line.push('\n');
+        writer.write_all(line.as_bytes()).await?;
+        writer.shutdown().await?;
+        Ok(true)
+    }
+}
+
+#[async_trait]
+impl ShareExchange for SocketExchange {
+    async fn publish(&self, request: &SigningRequest) -> Result<()> {
+        *self.current.lock().unwrap() = Some(request.clone());
+        Ok(())
+    }
+
+    async fn receive(&self) -> Result<Contribution> {
+        loop {
+            let (stream, _) = self.listener.accept().await?;
+            let Some(request) = self.current.lock().unwrap().clone() else {
+                continue;
+            };
+            let (reader, mut writer) = stream.into_split();
+
+            let mut line = serde_json::to_string(&request)?;
+            line.push('\n');
+            writer.write_all(line.as_bytes()).await?;
+
+            let mut line = String::new();
+            BufReader::new(reader).read_line(&mut line).await?;
+            // A participant that declines hangs up without a contribution
+            if line.trim().is_empty() {
+                continue;
+            }
+            return Ok(serde_json::from_str(&line)?);
+        }
+    }
+}
+
+fn has_suffix(path: &Path, suffix: &str) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.ends_with(suffix))
+}
+
+/// Write through a temporary file so readers never see a partial file.
+fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, contents)?;
+    std::fs::rename(&tmp, path).map_err(FrostError::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::trusted_dealer;
+    use crate::participant::FrostParticipant;
+    use crate::session::SigningSession;
+
+    #[tokio::test]
+    async fn test_directory_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let exchange =
+            DirectoryExchange::new(dir.path()).with_poll_interval(Duration::from_millis(10));
+        let (shares, _) = trusted_dealer(2, 3).unwrap();
+        let participant = FrostParticipant::new(shares.into_iter().next().unwrap());
+
+        let session = SigningSession::new(b"message", 2);
+        exchange.publish(&session.commit_request()).await.unwrap();
+
+        let requests = DirectoryExchange::read_requests(dir.path()).unwrap();
+        assert_eq!(requests.len(), 1);
+        let contribution = participant.respond(&requests[0]).unwrap().unwrap();
+        DirectoryExchange::write_contribution(dir.path(), &contribution).unwrap();
+
+        let received = exchange.receive().await.unwrap();
+        assert_eq!(received.session(), session.id());
+        assert_eq!(received.identifier(), participant.identifier());
+        // Consumed contributions are removed
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
@@ -0,0 +1,35 @@
+//! Key shares for a FROST group.
+
+use frost_ed25519::keys::{IdentifierList, KeyPackage, PublicKeyPackage};
+
+use crate::error::Result;
+
+/// Split a fresh Ed25519 key into `max_signers` shares, any `min_signers`
+/// of which can sign.
+///
+/// The dealer sees the whole key while splitting it, so run this on a
+/// trusted, offline machine and hand each [`KeyPackage`] to its participant.
+/// Distributed key generation, where no one ever holds the whole key, is
+/// not provided.
+///
+/// # Errors
+///
+/// Returns [`FrostError::Frost`](crate::FrostError::Frost) if the threshold
+/// is below two or above `max_signers`.
+pub fn trusted_dealer(
+    min_signers: u16,
+    max_signers: u16,
+) -> Result<(Vec<KeyPackage>, PublicKeyPackage)> {
+    let (shares, keys) = frost_ed25519::keys::generate_with_dealer(
+        max_signers,
+        min_signers,
+        IdentifierList::Default,
+        rand::thread_rng(),
+    )?;
+
+    let packages = shares
+        .into_values()
+        .map(KeyPackage::try_from)
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok((packages, keys))
+}
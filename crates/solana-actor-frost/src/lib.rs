@@ -0,0 +1,65 @@
+//! FROST threshold Ed25519 signing transport for Solana.
+//!
+//! FROST splits one Ed25519 key into shares so that any `t` of `n`
+//! participants can sign together, without the key ever being rebuilt. The
+//! result is a plain Ed25519 signature: on chain, a FROST group is an
+//! ordinary wallet, with no multisig program or extra fees.
+//!
+//! - [`FrostTransport`] implements [`WalletTransport`], running the two-round
+//!   ceremony for each submission and returning the aggregated signature
+//! - [`FrostParticipant`] holds one key share and answers requests
+//! - [`ShareExchange`] is the pluggable channel between them, with
+//!   [`DirectoryExchange`] (JSON files) and [`SocketExchange`] (a Unix
+//!   socket) provided
+//! - [`trusted_dealer`] splits a fresh key into shares
+//!
+//! Participants review the message in round one; round two only signs a
+//! package for that same message. Every share is checked as it is
+//! aggregated, and the signature is verified for the group key before it
+//! is returned.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use solana_actor_frost::{DirectoryExchange, FrostTransport};
+//! use solana_actor::WalletTransport;
+//!
+//! // Coordinator: 2 of 3 participants must sign
+//! let transport = FrostTransport::new(public_key_package, 2, DirectoryExchange::new("/mnt/frost"))?;
+//! let result = transport.submit(&tx_message).await?;
+//! ```
+//!
+//! # Participating
+//!
+//! ```ignore
+//! use solana_actor_frost::{DirectoryExchange, FrostParticipant};
+//!
+//! let participant = FrostParticipant::new(key_package);
+//! for request in DirectoryExchange::read_requests(dir)? {
+//!     // Show request.message()? to the participant before answering
+//!     if let Some(contribution) = participant.respond(&request)? {
+//!         DirectoryExchange::write_contribution(dir, &contribution)?;
+//!     }
+//! }
+//! ```
+
+mod error;
+mod exchange;
+mod keys;
+mod participant;
+mod session;
+mod transport;
+
+pub use error::{FrostError, Result};
+pub use exchange::{DirectoryExchange, ShareExchange, SocketExchange};
+pub use keys::trusted_dealer;
+pub use participant::FrostParticipant;
+pub use session::{Contribution, SigningRequest, SigningSession, group_pubkey};
+pub use transport::FrostTransport;
+
+// Re-export FROST key types for convenience
+pub use frost_ed25519::Identifier;
+pub use frost_ed25519::keys::{KeyPackage, PublicKeyPackage};
+
+// Re-export traits for convenience
+pub use solana_actor::{SubmitResult, WalletTransport};
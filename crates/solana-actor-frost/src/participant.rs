@@ -0,0 +1,159 @@
+//! A participant holding one share of the group key.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use frost_ed25519::Identifier;
+use frost_ed25519::keys::KeyPackage;
+use frost_ed25519::round1::{self, SigningNonces};
+use frost_ed25519::round2;
+
+use crate::error::{FrostError, Result};
+use crate::session::{Contribution, SigningRequest};
+
+/// Nonces and the message they were committed for.
+struct Pending {
+    message: Vec<u8>,
+    nonces: SigningNonces,
+}
+
+/// Answers signing requests with one share of the group key.
+///
+/// Nonces from round one are held in memory until round two and used once:
+/// a second signing request for the same session is refused, and so is a
+/// package whose message differs from the one committed to.
+pub struct FrostParticipant {
+    key_package: KeyPackage,
+    pending: Mutex<HashMap<String, Pending>>,
+}
+
+impl FrostParticipant {
+    /// Create a participant from its key share.
+    pub fn new(key_package: KeyPackage) -> Self {
+        Self {
+            key_package,
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The participant's identifier within the group.
+    pub fn identifier(&self) -> Identifier {
+        *self.key_package.identifier()
+    }
+
+    /// Answer a request from the coordinator.
+    ///
+    /// Review [`SigningRequest::message`] before calling this: round one is
+    /// where the participant agrees to sign. Returns `None` for a round two
+    /// package that doesn't include this participant.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FrostError::UnknownSession`] for a round two request without
+    /// a matching commitment, and [`FrostError::MessageMismatch`] if the
+    /// package's message isn't the one committed to.
+    pub fn respond(&self, request: &SigningRequest) -> Result<Option<Contribution>> {
+        match request {
+            SigningRequest::Commit { session, .. } => {
+                let message = request.message()?;
+                let (nonces, commitments) =
+                    round1::commit(self.key_package.signing_share(), &mut rand::thread_rng());
+                self.pending
+                    .lock()
+                    .unwrap()
+                    .insert(session.clone(), Pending { message, nonces });
+
+                Ok(Some(Contribution::Commitment {
+                    session: session.clone(),
+                    identifier: self.identifier(),
+                    commitments,
+                }))
+            }
+            SigningRequest::Sign { session, package } => {
+                if !package
+                    .signing_commitments()
+                    .contains_key(&self.identifier())
+                {
+                    self.pending.lock().unwrap().remove(session);
+                    return Ok(None);
+                }
+                let pending = self
+                    .pending
+                    .lock()
+                    .unwrap()
+                    .remove(session)
+                    .ok_or_else(|| FrostError::UnknownSession(session.clone()))?;
+                if pending.message != package.message() {
+                    return Err(FrostError::MessageMismatch);
+                }
+
+                let share = round2::sign(package, &pending.nonces, &self.key_package)?;
+                Ok(Some(Contribution::Share {
+                    session: session.clone(),
+                    identifier: self.identifier(),
+                    share,
+                }))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::trusted_dealer;
+    use crate::session::SigningSession;
+
+    #[test]
+    fn test_refuses_swapped_message_and_reused_nonces() {
+        let (shares, keys) = trusted_dealer(2, 2).unwrap();
+        let participants: Vec<_> = shares.into_iter().map(FrostParticipant::new).collect();
+
+        let mut approved = SigningSession::new(b"approved", 2);
+        let mut swapped = SigningSession::new(b"swapped", 2);
+        let request = approved.commit_request();
+        for participant in &participants {
+            let contribution = participant.respond(&request).unwrap().unwrap();
+            let Contribution::Commitment {
+                identifier,
+                commitments,
+                ..
+            } = contribution.clone()
+            else {
+                panic!("Expected a commitment");
+            };
+            approved.add(&keys, contribution).unwrap();
+            // Replay the same commitments with another message
+            swapped
+                .add(
+                    &keys,
+                    Contribution::Commitment {
+                        session: swapped.id().to_string(),
+                        identifier,
+                        commitments,
+                    },
+                )
+                .unwrap();
+        }
+
+        let SigningRequest::Sign { package, .. } = swapped.sign_request().unwrap() else {
+            panic!("Expected a sign request");
+        };
+        let forged = SigningRequest::Sign {
+            session: approved.id().to_string(),
+            package,
+        };
+        assert!(matches!(
+            participants[0].respond(&forged),
+            Err(FrostError::MessageMismatch)
+        ));
+
+        // The nonces are gone after one attempt
+        let request = approved.sign_request().unwrap();
+        assert!(matches!(
+            participants[0].respond(&request),
+            Err(FrostError::UnknownSession(_))
+        ));
+        assert!(participants[1].respond(&request).unwrap().is_some());
+    }
+}
@@ -0,0 +1,326 @@
+//! Signing sessions and the messages exchanged with participants.
+
+use std::collections::BTreeMap;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use frost_ed25519 as frost;
+use frost_ed25519::keys::PublicKeyPackage;
+use frost_ed25519::round1::SigningCommitments;
+use frost_ed25519::round2::SignatureShare;
+use frost_ed25519::{Identifier, SigningPackage};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+use crate::error::{FrostError, Result};
+
+/// A request the coordinator publishes to participants.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "round", rename_all = "snake_case")]
+pub enum SigningRequest {
+    /// Round one: review the message and commit to fresh nonces.
+    Commit {
+        /// Session the request belongs to.
+        session: String,
+        /// The message to be signed, base64 encoded.
+        message: String,
+    },
+    /// Round two: sign the package built from the chosen commitments.
+    Sign {
+        /// Session the request belongs to.
+        session: String,
+        /// Commitments of the chosen signers and the message.
+        package: SigningPackage,
+    },
+}
+
+impl SigningRequest {
+    /// The session this request belongs to.
+    pub fn session(&self) -> &str {
+        match self {
+            Self::Commit { session, .. } | Self::Sign { session, .. } => session,
+        }
+    }
+
+    /// The message to be signed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FrostError::InvalidFormat`] if a round one message isn't
+    /// valid base64.
+    pub fn message(&self) -> Result<Vec<u8>> {
+        match self {
+            Self::Commit { message, .. } => BASE64
+                .decode(message)
+                .map_err(|e| FrostError::InvalidFormat(e.to_string())),
+            Self::Sign { package, .. } => Ok(package.message().to_vec()),
+        }
+    }
+}
+
+/// A participant's answer to a [`SigningRequest`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "round", rename_all = "snake_case")]
+pub enum Contribution {
+    /// Round one: the participant's nonce commitments.
+    Commitment {
+        /// Session the contribution belongs to.
+        session: String,
+        /// The participant's identifier.
+        identifier: Identifier,
+        /// Commitments to the participant's nonces.
+        commitments: SigningCommitments,
+    },
+    /// Round two: the participant's signature share.
+    Share {
+        /// Session the contribution belongs to.
+        session: String,
+        /// The participant's identifier.
+        identifier: Identifier,
+        /// The participant's share of the signature.
+        share: SignatureShare,
+    },
+}
+
+impl Contribution {
+    /// The session this contribution belongs to.
+    pub fn session(&self) -> &str {
+        match self {
+            Self::Commitment { session, .. } | Self::Share { session, .. } => session,
+        }
+    }
+
+    /// The contributing participant.
+    pub fn identifier(&self) -> Identifier {
+        match self {
+            Self::Commitment { identifier, .. } | Self::Share { identifier, .. } => *identifier,
+        }
+    }
+}
+
+/// The coordinator's side of one signing ceremony.
+///
+/// Collects commitments until `threshold` participants have committed,
+/// builds the signing package from them, then collects their signature
+/// shares and aggregates them into an Ed25519 signature.
+pub struct SigningSession {
+    id: String,
+    message: Vec<u8>,
+    threshold: usize,
+    commitments: BTreeMap<Identifier, SigningCommitments>,
+    package: Option<SigningPackage>,
+    shares: BTreeMap<Identifier, SignatureShare>,
+}
+
+impl SigningSession {
+    /// Start a session for `message` with a random session ID.
+    pub fn new(message: &[u8], threshold: usize) -> Self {
+        let mut id = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut id);
+
+        Self {
+            id: hex::encode(id),
+            message: message.to_vec(),
+            threshold,
+            commitments: BTreeMap::new(),
+            package: None,
+            shares: BTreeMap::new(),
+        }
+    }
+
+    /// The session ID.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// The round one request.
+    pub fn commit_request(&self) -> SigningRequest {
+        SigningRequest::Commit {
+            session: self.id.clone(),
+            message: BASE64.encode(&self.message),
+        }
+    }
+
+    /// Whether enough participants have committed to start round two.
+    pub fn has_commitments(&self) -> bool {
+        self.commitments.len() >= self.threshold
+    }
+
+    /// Whether every chosen signer has contributed a share.
+    pub fn has_shares(&self) -> bool {
+        self.package
+            .as_ref()
+            .is_some_and(|package| self.shares.len() >= package.signing_commitments().len())
+    }
+
+    /// Add a participant's contribution.
+    ///
+    /// Returns `false` if the contribution is for another session, or is a
+    /// commitment that arrived after the package was built.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FrostError::UnknownParticipant`] if the contributor holds no
+    /// share of `keys`, or isn't a chosen signer in round two, and
+    /// [`FrostError::UnexpectedRound`] for a share before round two.
+    pub fn add(&mut self, keys: &PublicKeyPackage, contribution: Contribution) -> Result<bool> {
+        if contribution.session() != self.id {
+            return Ok(false);
+        }
+        let identifier = contribution.identifier();
+        if !keys.verifying_shares().contains_key(&identifier) {
+            return Err(FrostError::UnknownParticipant(format!("{:?}", identifier)));
+        }
+
+        match contribution {
+            Contribution::Commitment { commitments, .. } => {
+                if self.package.is_some() {
+                    return Ok(false);
+                }
+                self.commitments.insert(identifier, commitments);
+            }
+            Contribution::Share { share, .. } => {
+                let Some(package) = &self.package else {
+                    return Err(FrostError::UnexpectedRound("sign".to_string()));
+                };
+                if !package.signing_commitments().contains_key(&identifier) {
+                    return Err(FrostError::UnknownParticipant(format!("{:?}", identifier)));
+                }
+                self.shares.insert(identifier, share);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Build the round two request from the first `threshold` commitments.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FrostError::UnexpectedRound`] if too few participants have
+    /// committed.
+    pub fn sign_request(&mut self) -> Result<SigningRequest> {
+        if !self.has_commitments() {
+            return Err(FrostError::UnexpectedRound("commit".to_string()));
+        }
+        let package = match &self.package {
+            Some(package) => package.clone(),
+            None => {
+                let chosen: BTreeMap<_, _> = self
+                    .commitments
+                    .iter()
+                    .take(self.threshold)
+                    .map(|(id, commitments)| (*id, *commitments))
+                    .collect();
+                let package = SigningPackage::new(chosen, &self.message);
+                self.package = Some(package.clone());
+                package
+            }
+        };
+
+        Ok(SigningRequest::Sign {
+            session: self.id.clone(),
+            package,
+        })
+    }
+
+    /// Aggregate the signature shares and check the result for `authority`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FrostError::Frost`] if a share is invalid, naming the
+    /// cheating participant, and [`FrostError::InvalidSignature`] if the
+    /// signature doesn't verify for `authority`.
+    pub fn aggregate(&self, keys: &PublicKeyPackage, authority: &Pubkey) -> Result<Signature> {
+        let package = self
+            .package
+            .as_ref()
+            .ok_or_else(|| FrostError::UnexpectedRound("sign".to_string()))?;
+        let signature = frost::aggregate(package, &self.shares, keys)?;
+
+        let bytes: [u8; 64] = signature
+            .serialize()?
+            .try_into()
+            .map_err(|_| FrostError::InvalidSignature)?;
+        let signature = Signature::from(bytes);
+        if !signature.verify(authority.as_ref(), &self.message) {
+            return Err(FrostError::InvalidSignature);
+        }
+        Ok(signature)
+    }
+}
+
+/// The Solana address of a FROST group key.
+///
+/// # Errors
+///
+/// Returns [`FrostError::InvalidKey`] if the group key can't be serialized
+/// as a 32-byte Ed25519 point.
+pub fn group_pubkey(keys: &PublicKeyPackage) -> Result<Pubkey> {
+    let bytes = keys.verifying_key().serialize()?;
+    Pubkey::try_from(bytes.as_slice()).map_err(|e| FrostError::InvalidKey(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::trusted_dealer;
+    use crate::participant::FrostParticipant;
+
+    #[test]
+    fn test_two_of_three_ceremony() {
+        let (shares, keys) = trusted_dealer(2, 3).unwrap();
+        let participants: Vec<_> = shares.into_iter().map(FrostParticipant::new).collect();
+        let authority = group_pubkey(&keys).unwrap();
+
+        let message = b"transfer 1 SOL";
+        let mut session = SigningSession::new(message, 2);
+
+        let request = session.commit_request();
+        for participant in &participants {
+            let contribution = participant.respond(&request).unwrap().unwrap();
+            assert!(session.add(&keys, contribution).unwrap());
+        }
+        assert!(session.has_commitments());
+
+        let request = session.sign_request().unwrap();
+        for participant in &participants {
+            if let Some(contribution) = participant.respond(&request).unwrap() {
+                session.add(&keys, contribution).unwrap();
+            }
+        }
+        assert!(session.has_shares());
+
+        let signature = session.aggregate(&keys, &authority).unwrap();
+        assert!(signature.verify(authority.as_ref(), message));
+    }
+
+    #[test]
+    fn test_ignores_other_sessions_and_rejects_strangers() {
+        let (shares, keys) = trusted_dealer(2, 3).unwrap();
+        let participant = FrostParticipant::new(shares.into_iter().next().unwrap());
+        let (other_shares, _) = trusted_dealer(2, 3).unwrap();
+        let mut session = SigningSession::new(b"message", 2);
+
+        let other = SigningSession::new(b"message", 2);
+        let stale = participant
+            .respond(&other.commit_request())
+            .unwrap()
+            .unwrap();
+        assert!(!session.add(&keys, stale).unwrap());
+
+        // Same identifier as a real participant, but a share of another key
+        let stranger = FrostParticipant::new(other_shares.into_iter().last().unwrap());
+        let mut contribution = stranger
+            .respond(&session.commit_request())
+            .unwrap()
+            .unwrap();
+        if let Contribution::Commitment { identifier, .. } = &mut contribution {
+            *identifier = Identifier::try_from(9).unwrap();
+        }
+        assert!(matches!(
+            session.add(&keys, contribution),
+            Err(FrostError::UnknownParticipant(_))
+        ));
+    }
+}
@@ -0,0 +1,206 @@
+//! Threshold signing transport.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use frost_ed25519::keys::PublicKeyPackage;
+use solana_actor::{SubmitResult, TransportError, WalletTransport};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+use crate::error::{FrostError, Result};
+use crate::exchange::ShareExchange;
+use crate::session::{SigningSession, group_pubkey};
+
+/// Signs with a FROST group key, `threshold` participants at a time.
+///
+/// Each submission opens a [`SigningSession`] and runs both rounds through
+/// the [`ShareExchange`]: participants commit to nonces, the first
+/// `threshold` commitments form the signing package, and the chosen
+/// participants' shares are aggregated into one Ed25519 signature for the
+/// group key. To the chain it is an ordinary single-key signature.
+///
+/// # Example
+///
+/// ```ignore
+/// use solana_actor_frost::{DirectoryExchange, FrostTransport};
+/// use solana_actor::WalletTransport;
+///
+/// let transport = FrostTransport::new(public_key_package, 2, DirectoryExchange::new("/mnt/frost"))?
+///     .with_timeout(Duration::from_secs(3600));
+///
+/// let result = transport.submit(&tx_message).await?;
+/// ```
+pub struct FrostTransport<E> {
+    keys: PublicKeyPackage,
+    authority: Pubkey,
+    threshold: usize,
+    timeout: Duration,
+    exchange: E,
+}
+
+impl<E: ShareExchange> FrostTransport<E> {
+    /// Create a transport for a group key.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - The group's public key package.
+    /// * `threshold` - How many participants must sign.
+    /// * `exchange` - How requests and contributions travel.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FrostError::InvalidKey`] if the group key isn't a valid
+    /// Solana public key.
+    pub fn new(keys: PublicKeyPackage, threshold: u16, exchange: E) -> Result<Self> {
+        Ok(Self {
+            authority: group_pubkey(&keys)?,
+            keys,
+            threshold: threshold.into(),
+            timeout: Duration::from_secs(600),
+            exchange,
+        })
+    }
+
+    /// Set how long a ceremony may take before it is abandoned.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// The share exchange.
+    pub fn exchange(&self) -> &E {
+        &self.exchange
+    }
+
+    /// Run a signing ceremony for a serialized transaction message.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FrostError::Timeout`] if too few participants contribute in
+    /// time, or an error if the exchange fails, a contribution is invalid,
+    /// or the aggregated signature doesn't verify.
+    pub async fn sign(&self, message: &[u8]) -> Result<Signature> {
+        tokio::time::timeout(self.timeout, self.run(message))
+            .await
+            .map_err(|_| FrostError::Timeout)?
+    }
+
+    async fn run(&self, message: &[u8]) -> Result<Signature> {
+        let mut session = SigningSession::new(message, self.threshold);
+
+        // Round one: commitments
+        self.exchange.publish(&session.commit_request()).await?;
+        while !session.has_commitments() {
+            let contribution = self.exchange.receive().await?;
+            session.add(&self.keys, contribution)?;
+        }
+
+        // Round two: signature shares from the chosen participants
+        self.exchange.publish(&session.sign_request()?).await?;
+        while !session.has_shares() {
+            let contribution = self.exchange.receive().await?;
+            session.add(&self.keys, contribution)?;
+        }
+
+        session.aggregate(&self.keys, &self.authority)
+    }
+}
+
+#[async_trait]
+impl<E: ShareExchange> WalletTransport for FrostTransport<E> {
+    fn authority(&self) -> Pubkey {
+        self.authority
+    }
+
+    async fn submit(&self, message: &[u8]) -> std::result::Result<SubmitResult, TransportError> {
+        Ok(SubmitResult::Signed(self.sign(message).await?))
+    }
+
+    async fn check_status(
+        &self,
+        result: &SubmitResult,
+    ) -> std::result::Result<SubmitResult, TransportError> {
+        // The ceremony completes within submit
+        Ok(result.clone())
+    }
+
+    async fn wait_for_completion(
+        &self,
+        result: SubmitResult,
+        _timeout: Duration,
+    ) -> std::result::Result<SubmitResult, TransportError> {
+        Ok(result)
+    }
+
+    fn requires_network(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::trusted_dealer;
+    use crate::participant::FrostParticipant;
+    use crate::session::{Contribution, SigningRequest};
+    use std::sync::Mutex;
+
+    /// Delivers each published request to in-process participants
+    struct LocalExchange {
+        participants: Vec<FrostParticipant>,
+        inbox: Mutex<Vec<Contribution>>,
+    }
+
+    #[async_trait]
+    impl ShareExchange for LocalExchange {
+        async fn publish(&self, request: &SigningRequest) -> Result<()> {
+            let mut inbox = self.inbox.lock().unwrap();
+            for participant in &self.participants {
+                inbox.extend(participant.respond(request)?);
+            }
+            Ok(())
+        }
+
+        async fn receive(&self) -> Result<Contribution> {
+            match self.inbox.lock().unwrap().pop() {
+                Some(contribution) => Ok(contribution),
+                None => Err(FrostError::Timeout),
+            }
+        }
+    }
+
+    fn transport(online: usize) -> FrostTransport<LocalExchange> {
+        let (shares, keys) = trusted_dealer(2, 3).unwrap();
+        let exchange = LocalExchange {
+            participants: shares
+                .into_iter()
+                .take(online)
+                .map(FrostParticipant::new)
+                .collect(),
+            inbox: Mutex::new(Vec::new()),
+        };
+        FrostTransport::new(keys, 2, exchange).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_signs_with_threshold() {
+        let transport = transport(3);
+        let message = vec![7u8; 300];
+
+        let SubmitResult::Signed(signature) = transport.submit(&message).await.unwrap() else {
+            panic!("Expected a signature");
+        };
+        assert!(signature.verify(transport.authority().as_ref(), &message));
+        assert!(!transport.requires_network());
+    }
+
+    #[tokio::test]
+    async fn test_fails_below_threshold() {
+        let transport = transport(1);
+
+        assert!(matches!(
+            transport.submit(b"message").await,
+            Err(TransportError::Timeout)
+        ));
+    }
+}
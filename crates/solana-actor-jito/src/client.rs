@@ -0,0 +1,299 @@
+//! Async HTTP client for the Jito block engine bundle API.
+//!
+//! The block engine exposes JSON-RPC 2.0 methods under `/api/v1/bundles`:
+//!
+//! - `sendBundle` - `[[<base64 transactions>], { "encoding": "base64" }]`,
+//!   returning the bundle ID
+//! - `getInflightBundleStatuses` - `[[<bundle IDs>]]`, returning whether each
+//!   bundle is pending, landed, failed or unknown
+
+use std::time::Duration;
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use solana_sdk::{pubkey::Pubkey, transaction::VersionedTransaction};
+
+use crate::error::{JitoError, Result};
+
+/// Mainnet block engine bundle endpoint.
+pub const DEFAULT_BLOCK_ENGINE_URL: &str = "https://mainnet.block-engine.jito.wtf/api/v1/bundles";
+
+/// Default tip paid with each bundle, in lamports.
+pub const DEFAULT_TIP_LAMPORTS: u64 = 10_000;
+
+/// Default interval between bundle status polls.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Default timeout for a single HTTP request.
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Configuration for a Jito block engine connection.
+#[derive(Debug, Clone)]
+pub struct JitoConfig {
+    /// Bundle JSON-RPC endpoint of the block engine.
+    pub endpoint: String,
+    /// Tip paid with each bundle, in lamports.
+    pub tip_lamports: u64,
+    /// Tip account to pay; a random mainnet tip account if unset.
+    pub tip_account: Option<Pubkey>,
+    /// UUID sent as `x-jito-auth`, for authenticated rate limits.
+    pub auth_uuid: Option<String>,
+    /// Interval between status polls while a bundle is in flight.
+    pub poll_interval: Duration,
+    /// Timeout for a single HTTP request.
+    pub request_timeout: Duration,
+}
+
+impl Default for JitoConfig {
+    fn default() -> Self {
+        Self::new(DEFAULT_BLOCK_ENGINE_URL)
+    }
+}
+
+impl JitoConfig {
+    /// Create a new config with the default tip and timeouts.
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            tip_lamports: DEFAULT_TIP_LAMPORTS,
+            tip_account: None,
+            auth_uuid: None,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+        }
+    }
+
+    /// Set the tip paid with each bundle.
+    pub fn tip_lamports(mut self, lamports: u64) -> Self {
+        self.tip_lamports = lamports;
+        self
+    }
+
+    /// Pay tips to a specific tip account.
+    pub fn tip_account(mut self, account: Pubkey) -> Self {
+        self.tip_account = Some(account);
+        self
+    }
+
+    /// Authenticate requests with a Jito UUID.
+    pub fn auth_uuid(mut self, uuid: impl Into<String>) -> Self {
+        self.auth_uuid = Some(uuid.into());
+        self
+    }
+
+    /// Set the interval between status polls.
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Set the timeout for a single HTTP request.
+    pub fn request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = timeout;
+        self
+    }
+}
+
+/// Status of an in-flight bundle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BundleStatus {
+    /// Accepted and not yet landed or failed.
+    Pending,
+    /// Landed on-chain.
+    Landed {
+        /// Slot the bundle landed in.
+        slot: u64,
+    },
+    /// Failed in every region it was sent to.
+    Failed,
+    /// Unknown to the block engine (never accepted, or older than five minutes).
+    Invalid,
+}
+
+/// Wire format of an in-flight bundle status.
+#[derive(Deserialize)]
+struct RawInflightStatus {
+    bundle_id: String,
+    status: String,
+    #[serde(default)]
+    landed_slot: Option<u64>,
+}
+
+/// Async client for the block engine bundle API.
+#[derive(Debug, Clone)]
+pub struct JitoClient {
+    config: JitoConfig,
+    http: reqwest::Client,
+}
+
+impl JitoClient {
+    /// Create a new client from a config.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be built.
+    pub fn new(config: JitoConfig) -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(config.request_timeout)
+            .build()?;
+        Ok(Self { config, http })
+    }
+
+    /// Get the client configuration.
+    pub fn config(&self) -> &JitoConfig {
+        &self.config
+    }
+
+    /// Send signed transactions as a bundle, returning its bundle ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a transaction cannot be serialized, the request
+    /// fails, or the block engine rejects the bundle.
+    pub async fn send_bundle(&self, transactions: &[VersionedTransaction]) -> Result<String> {
+        let encoded = transactions
+            .iter()
+            .map(|tx| {
+                bincode::serialize(tx)
+                    .map(|bytes| BASE64.encode(bytes))
+                    .map_err(|e| JitoError::InvalidMessage(e.to_string()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let result = self
+            .call("sendBundle", json!([encoded, { "encoding": "base64" }]))
+            .await?;
+        result
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| JitoError::InvalidResponse("Bundle ID must be a string".into()))
+    }
+
+    /// Get the status of an in-flight bundle.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or the response is malformed.
+    pub async fn get_inflight_bundle_status(&self, bundle_id: &str) -> Result<BundleStatus> {
+        let result = self
+            .call("getInflightBundleStatuses", json!([[bundle_id]]))
+            .await?;
+        parse_inflight_status(result, bundle_id)
+    }
+
+    async fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+
+        let mut request = self.http.post(&self.config.endpoint).json(&body);
+        if let Some(uuid) = &self.config.auth_uuid {
+            request = request.header("x-jito-auth", uuid);
+        }
+
+        let value: Value = request.send().await?.error_for_status()?.json().await?;
+        parse_response(value)
+    }
+}
+
+/// Unwrap a JSON-RPC response envelope.
+fn parse_response(value: Value) -> Result<Value> {
+    if let Some(error) = value.get("error") {
+        return Err(JitoError::Rpc {
+            code: error.get("code").and_then(Value::as_i64).unwrap_or(0),
+            message: error
+                .get("message")
+                .and_then(Value::as_str)
+                .unwrap_or("unknown error")
+                .to_string(),
+        });
+    }
+
+    value
+        .get("result")
+        .cloned()
+        .ok_or_else(|| JitoError::InvalidResponse("Missing result".into()))
+}
+
+/// Find a bundle's status in a `getInflightBundleStatuses` result.
+fn parse_inflight_status(result: Value, bundle_id: &str) -> Result<BundleStatus> {
+    let statuses: Vec<RawInflightStatus> = serde_json::from_value(
+        result
+            .get("value")
+            .cloned()
+            .ok_or_else(|| JitoError::InvalidResponse("Missing value".into()))?,
+    )
+    .map_err(|e| JitoError::InvalidResponse(e.to_string()))?;
+
+    let Some(status) = statuses.into_iter().find(|s| s.bundle_id == bundle_id) else {
+        return Ok(BundleStatus::Invalid);
+    };
+
+    match status.status.as_str() {
+        "Pending" => Ok(BundleStatus::Pending),
+        "Landed" => Ok(BundleStatus::Landed {
+            slot: status.landed_slot.unwrap_or(0),
+        }),
+        "Failed" => Ok(BundleStatus::Failed),
+        "Invalid" => Ok(BundleStatus::Invalid),
+        other => Err(JitoError::InvalidResponse(format!(
+            "Unknown bundle status: {}",
+            other
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_landed() {
+        let result = parse_response(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {
+                "context": { "slot": 280999028 },
+                "value": [
+                    { "bundle_id": "other", "status": "Pending", "landed_slot": null },
+                    { "bundle_id": "abc", "status": "Landed", "landed_slot": 280999001 }
+                ]
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            parse_inflight_status(result.clone(), "abc").unwrap(),
+            BundleStatus::Landed { slot: 280999001 }
+        );
+        assert_eq!(
+            parse_inflight_status(result, "other").unwrap(),
+            BundleStatus::Pending
+        );
+    }
+
+    #[test]
+    fn test_parse_missing_bundle_is_invalid() {
+        let result = json!({ "context": { "slot": 1 }, "value": [] });
+        assert_eq!(
+            parse_inflight_status(result, "abc").unwrap(),
+            BundleStatus::Invalid
+        );
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = parse_response(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "error": { "code": -32602, "message": "bundle must contain at most 5 transactions" }
+        }))
+        .unwrap_err();
+
+        assert!(matches!(err, JitoError::Rpc { code: -32602, .. }));
+    }
+}
@@ -0,0 +1,64 @@
+//! Error types for Jito bundle operations.
+
+use thiserror::Error;
+
+/// Errors that can occur when submitting bundles to a Jito block engine.
+#[derive(Error, Debug)]
+pub enum JitoError {
+    /// HTTP transport error.
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// The block engine returned a JSON-RPC error.
+    #[error("Block engine error {code}: {message}")]
+    Rpc {
+        /// JSON-RPC error code.
+        code: i64,
+        /// Error message from the block engine.
+        message: String,
+    },
+
+    /// The block engine response could not be parsed.
+    #[error("Invalid response: {0}")]
+    InvalidResponse(String),
+
+    /// The transaction message cannot be sent in a bundle by this transport.
+    #[error("Invalid message: {0}")]
+    InvalidMessage(String),
+
+    /// Signing failed.
+    #[error("Signer error: {0}")]
+    Signer(#[from] solana_actor::SignerError),
+
+    /// The bundle failed to land or is unknown to the block engine.
+    #[error("Bundle {bundle_id} {status}")]
+    BundleFailed {
+        /// The block engine's bundle identifier.
+        bundle_id: String,
+        /// What happened to the bundle.
+        status: String,
+    },
+
+    /// Unknown pending bundle handle.
+    #[error("Unknown bundle: {0}")]
+    UnknownBundle(u64),
+}
+
+/// Result type for Jito bundle operations.
+pub type Result<T> = std::result::Result<T, JitoError>;
+
+impl From<JitoError> for solana_actor::TransportError {
+    fn from(err: JitoError) -> Self {
+        match err {
+            JitoError::Http(e) => {
+                Self::Connection(solana_actor::ConnectionError::Rpc(e.to_string()))
+            }
+            JitoError::Signer(e) => Self::Signer(e),
+            JitoError::InvalidMessage(msg) => {
+                Self::Signer(solana_actor::SignerError::InvalidFormat(msg))
+            }
+            JitoError::BundleFailed { .. } => Self::ExecutionFailed(err.to_string()),
+            other => Self::Connection(solana_actor::ConnectionError::Rpc(other.to_string())),
+        }
+    }
+}
@@ -0,0 +1,50 @@
+//! Jito bundle transport for Solana.
+//!
+//! This crate submits transactions through a Jito block engine instead of a
+//! regular RPC node, so they land atomically with a validator tip:
+//!
+//! - [`JitoTransport`] implements [`WalletTransport`], signing each message
+//!   with a [`TransactionSigner`] (or any [`AsyncTransactionSigner`]) and
+//!   sending it in a bundle with a tip transaction
+//! - [`JitoClient`] speaks the block engine's bundle JSON-RPC API
+//!   (`sendBundle`, `getInflightBundleStatuses`)
+//!
+//! Submissions return [`SubmitResult::Pending`] until the bundle lands, then
+//! [`SubmitResult::Executed`] with the transaction signature.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use solana_actor_jito::{JitoConfig, JitoTransport};
+//! use solana_actor::{SubmitResult, WalletTransport};
+//! use std::time::Duration;
+//!
+//! let config = JitoConfig::default()
+//!     .tip_lamports(25_000)
+//!     .auth_uuid(std::env::var("JITO_UUID")?);
+//!
+//! let transport = JitoTransport::new(signer, config)?;
+//! let result = transport.submit_versioned(&message).await?;
+//!
+//! let landed = transport
+//!     .wait_for_completion(result, Duration::from_secs(60))
+//!     .await?;
+//! if let SubmitResult::Executed { signature, .. } = landed {
+//!     println!("Landed: {}", signature);
+//! }
+//! ```
+
+pub mod client;
+mod error;
+pub mod tip;
+mod transport;
+
+pub use client::{BundleStatus, JitoClient, JitoConfig};
+pub use error::{JitoError, Result};
+pub use tip::{TIP_ACCOUNTS, tip_instruction};
+pub use transport::JitoTransport;
+
+// Re-export traits for convenience
+pub use solana_actor::{
+    AsyncTransactionSigner, SignerError, SubmitResult, TransactionSigner, WalletTransport,
+};
@@ -0,0 +1,54 @@
+//! Jito tip accounts and tip instructions.
+//!
+//! Block engines only consider bundles that pay a tip to one of the Jito tip
+//! accounts. Spreading tips across the accounts avoids write-lock contention.
+
+use rand::Rng;
+use solana_sdk::{instruction::Instruction, pubkey, pubkey::Pubkey};
+
+/// Mainnet Jito tip accounts.
+pub const TIP_ACCOUNTS: [Pubkey; 8] = [
+    pubkey!("96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5"),
+    pubkey!("HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe"),
+    pubkey!("Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY"),
+    pubkey!("ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49"),
+    pubkey!("DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh"),
+    pubkey!("ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt"),
+    pubkey!("DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL"),
+    pubkey!("3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT"),
+];
+
+/// Pick a random mainnet tip account.
+pub fn random_tip_account() -> Pubkey {
+    TIP_ACCOUNTS[rand::thread_rng().gen_range(0..TIP_ACCOUNTS.len())]
+}
+
+/// Whether `account` is a mainnet tip account.
+pub fn is_tip_account(account: &Pubkey) -> bool {
+    TIP_ACCOUNTS.contains(account)
+}
+
+/// Build an instruction paying a tip from `payer`.
+pub fn tip_instruction(payer: &Pubkey, tip_account: &Pubkey, lamports: u64) -> Instruction {
+    solana_system_interface::instruction::transfer(payer, tip_account, lamports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_random_tip_account() {
+        assert!(is_tip_account(&random_tip_account()));
+        assert!(!is_tip_account(&Pubkey::new_unique()));
+    }
+
+    #[test]
+    fn test_tip_instruction() {
+        let payer = Pubkey::new_unique();
+        let ix = tip_instruction(&payer, &TIP_ACCOUNTS[0], 10_000);
+        assert_eq!(ix.accounts[0].pubkey, payer);
+        assert!(ix.accounts[0].is_signer);
+        assert_eq!(ix.accounts[1].pubkey, TIP_ACCOUNTS[0]);
+    }
+}
@@ -0,0 +1,415 @@
+//! Bundle-submitting transport implementation.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use solana_actor::{AsyncTransactionSigner, SubmitResult, TransportError, WalletTransport};
+use solana_sdk::{
+    message::{Message, VersionedMessage},
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::VersionedTransaction,
+};
+
+use crate::client::{BundleStatus, JitoClient, JitoConfig};
+use crate::error::{JitoError, Result};
+use crate::tip::{is_tip_account, random_tip_account, tip_instruction};
+
+/// A bundle sent to the block engine.
+#[derive(Debug, Clone)]
+struct PendingBundle {
+    bundle_id: String,
+    signature: Signature,
+}
+
+/// A [`WalletTransport`] that signs transactions and sends them as Jito bundles.
+///
+/// Each submitted message is signed by the wrapped signer and sent to the
+/// block engine together with a second transaction paying the configured tip.
+/// Messages that already transfer to a tip account are sent alone.
+///
+/// The signer must be the message's only required signer. Submitting returns
+/// [`SubmitResult::Pending`] where:
+///
+/// - `proposal` is the signer's public key
+/// - `transaction_index` is a local handle for the bundle
+///   (see [`JitoTransport::bundle_id`])
+/// - `approvals` / `threshold` are `0` / `1` until the bundle lands
+///
+/// Once the bundle lands, [`check_status`](WalletTransport::check_status)
+/// returns [`SubmitResult::Executed`] with the submitted transaction's
+/// signature. Failed or expired bundles are reported as
+/// [`TransportError::ExecutionFailed`].
+///
+/// # Example
+///
+/// ```ignore
+/// use solana_actor_jito::{JitoConfig, JitoTransport};
+/// use solana_actor::WalletTransport;
+/// use std::time::Duration;
+///
+/// let config = JitoConfig::default().tip_lamports(50_000);
+/// let transport = JitoTransport::new(signer, config)?;
+///
+/// let result = transport.submit_versioned(&message).await?;
+/// let landed = transport
+///     .wait_for_completion(result, Duration::from_secs(60))
+///     .await?;
+/// println!("Landed: {}", landed.signature().unwrap());
+/// ```
+#[derive(Debug)]
+pub struct JitoTransport<S> {
+    signer: S,
+    client: JitoClient,
+    next_index: AtomicU64,
+    pending: Mutex<HashMap<u64, PendingBundle>>,
+}
+
+impl<S: AsyncTransactionSigner> JitoTransport<S> {
+    /// Create a new Jito transport wrapping the given signer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be built.
+    pub fn new(signer: S, config: JitoConfig) -> Result<Self> {
+        Ok(Self {
+            signer,
+            client: JitoClient::new(config)?,
+            next_index: AtomicU64::new(0),
+            pending: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Get a reference to the underlying signer.
+    pub fn signer(&self) -> &S {
+        &self.signer
+    }
+
+    /// Get a reference to the underlying client.
+    pub fn client(&self) -> &JitoClient {
+        &self.client
+    }
+
+    /// Get the block engine bundle ID for a pending `transaction_index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pending bundle map lock is poisoned.
+    pub fn bundle_id(&self, transaction_index: u64) -> Option<String> {
+        self.pending
+            .lock()
+            .unwrap()
+            .get(&transaction_index)
+            .map(|bundle| bundle.bundle_id.clone())
+    }
+
+    fn track(&self, bundle: PendingBundle) -> u64 {
+        let index = self.next_index.fetch_add(1, Ordering::Relaxed);
+        self.pending.lock().unwrap().insert(index, bundle);
+        index
+    }
+
+    fn untrack(&self, transaction_index: u64) {
+        self.pending.lock().unwrap().remove(&transaction_index);
+    }
+
+    fn pending_result(&self, transaction_index: u64) -> SubmitResult {
+        SubmitResult::Pending {
+            proposal: self.signer.pubkey(),
+            transaction_index,
+            approvals: 0,
+            threshold: 1,
+        }
+    }
+
+    /// Build the unsigned tip transaction message for a bundle, if one is needed.
+    fn tip_message(&self, message: &VersionedMessage) -> Option<VersionedMessage> {
+        if message.static_account_keys().iter().any(is_tip_account) {
+            return None;
+        }
+
+        let config = self.client.config();
+        let payer = self.signer.pubkey();
+        let tip_account = config.tip_account.unwrap_or_else(random_tip_account);
+        Some(VersionedMessage::Legacy(Message::new_with_blockhash(
+            &[tip_instruction(&payer, &tip_account, config.tip_lamports)],
+            Some(&payer),
+            message.recent_blockhash(),
+        )))
+    }
+
+    /// Convert a bundle status into a [`SubmitResult`].
+    fn to_result(&self, status: BundleStatus, transaction_index: u64) -> Result<SubmitResult> {
+        let bundle = self
+            .pending
+            .lock()
+            .unwrap()
+            .get(&transaction_index)
+            .cloned()
+            .ok_or(JitoError::UnknownBundle(transaction_index))?;
+
+        match status {
+            BundleStatus::Pending => Ok(self.pending_result(transaction_index)),
+            BundleStatus::Landed { .. } => {
+                self.untrack(transaction_index);
+                Ok(SubmitResult::Executed {
+                    signature: bundle.signature,
+                    proposal: self.signer.pubkey(),
+                })
+            }
+            BundleStatus::Failed | BundleStatus::Invalid => {
+                self.untrack(transaction_index);
+                Err(JitoError::BundleFailed {
+                    bundle_id: bundle.bundle_id,
+                    status: if status == BundleStatus::Failed {
+                        "failed to land".into()
+                    } else {
+                        "is unknown to the block engine".into()
+                    },
+                })
+            }
+        }
+    }
+
+    /// Sign a message with the wrapped signer as its only required signer.
+    async fn sign(&self, message: VersionedMessage) -> Result<VersionedTransaction> {
+        let header = message.header();
+        let keys = message.static_account_keys();
+        if header.num_required_signatures != 1 || keys.first() != Some(&self.signer.pubkey()) {
+            return Err(JitoError::InvalidMessage(format!(
+                "{} must be the only required signer",
+                self.signer.pubkey()
+            )));
+        }
+
+        let signature = self.signer.sign_transaction(&message.serialize()).await?;
+        Ok(VersionedTransaction {
+            signatures: vec![signature],
+            message,
+        })
+    }
+
+    /// Sign a message and its tip transaction and send them as a bundle.
+    async fn send(&self, message: VersionedMessage) -> Result<SubmitResult> {
+        let tip = self.tip_message(&message);
+
+        let mut transactions = vec![self.sign(message).await?];
+        if let Some(tip) = tip {
+            transactions.push(self.sign(tip).await?);
+        }
+
+        let bundle_id = self.client.send_bundle(&transactions).await?;
+        let index = self.track(PendingBundle {
+            bundle_id,
+            signature: transactions[0].signatures[0],
+        });
+        Ok(self.pending_result(index))
+    }
+}
+
+#[async_trait]
+impl<S: AsyncTransactionSigner> WalletTransport for JitoTransport<S> {
+    fn authority(&self) -> Pubkey {
+        self.signer.pubkey()
+    }
+
+    async fn submit(&self, message: &[u8]) -> std::result::Result<SubmitResult, TransportError> {
+        let message: VersionedMessage =
+            bincode::deserialize(message).map_err(|e| JitoError::InvalidMessage(e.to_string()))?;
+        Ok(self.send(message).await?)
+    }
+
+    async fn submit_versioned(
+        &self,
+        message: &VersionedMessage,
+    ) -> std::result::Result<SubmitResult, TransportError> {
+        Ok(self.send(message.clone()).await?)
+    }
+
+    async fn check_status(
+        &self,
+        result: &SubmitResult,
+    ) -> std::result::Result<SubmitResult, TransportError> {
+        let SubmitResult::Pending {
+            transaction_index, ..
+        } = result
+        else {
+            return Ok(result.clone());
+        };
+
+        let bundle_id = self
+            .bundle_id(*transaction_index)
+            .ok_or(JitoError::UnknownBundle(*transaction_index))?;
+        let status = self.client.get_inflight_bundle_status(&bundle_id).await?;
+        Ok(self.to_result(status, *transaction_index)?)
+    }
+
+    async fn wait_for_completion(
+        &self,
+        result: SubmitResult,
+        timeout: Duration,
+    ) -> std::result::Result<SubmitResult, TransportError> {
+        if result.is_complete() {
+            return Ok(result);
+        }
+
+        let deadline = Instant::now() + timeout;
+        let mut current = result;
+
+        while Instant::now() < deadline {
+            current = self.check_status(&current).await?;
+            if current.is_complete() {
+                return Ok(current);
+            }
+            tokio::time::sleep(self.client.config().poll_interval).await;
+        }
+
+        Err(TransportError::Timeout)
+    }
+
+    fn requires_network(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tip::TIP_ACCOUNTS;
+    use solana_actor::{SignerError, TransactionSigner};
+    use solana_sdk::{hash::Hash, instruction::Instruction};
+
+    #[derive(Clone)]
+    struct MockSigner {
+        pubkey: Pubkey,
+    }
+
+    impl TransactionSigner for MockSigner {
+        fn pubkey(&self) -> Pubkey {
+            self.pubkey
+        }
+
+        fn sign_transaction(&self, _message: &[u8]) -> std::result::Result<Signature, SignerError> {
+            Ok(Signature::from([7u8; 64]))
+        }
+    }
+
+    fn transport() -> JitoTransport<MockSigner> {
+        JitoTransport::new(
+            MockSigner {
+                pubkey: Pubkey::new_unique(),
+            },
+            JitoConfig::new("http://localhost:0"),
+        )
+        .unwrap()
+    }
+
+    fn message(payer: &Pubkey, to: &Pubkey) -> VersionedMessage {
+        VersionedMessage::Legacy(Message::new_with_blockhash(
+            &[tip_instruction(payer, to, 1)],
+            Some(payer),
+            &Hash::new_unique(),
+        ))
+    }
+
+    #[test]
+    fn test_tip_message() {
+        let transport = transport();
+        let payer = transport.authority();
+        let message = message(&payer, &Pubkey::new_unique());
+
+        let tip = transport.tip_message(&message).unwrap();
+        assert_eq!(tip.recent_blockhash(), message.recent_blockhash());
+        assert_eq!(tip.static_account_keys()[0], payer);
+        assert!(tip.static_account_keys().iter().any(is_tip_account));
+    }
+
+    #[test]
+    fn test_message_with_tip_needs_no_tip_message() {
+        let transport = transport();
+        let payer = transport.authority();
+        assert!(
+            transport
+                .tip_message(&message(&payer, &TIP_ACCOUNTS[3]))
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sign_requires_sole_signer() {
+        let transport = transport();
+        let payer = transport.authority();
+        assert!(
+            transport
+                .sign(message(&payer, &Pubkey::new_unique()))
+                .await
+                .is_ok()
+        );
+
+        let other = Pubkey::new_unique();
+        let err = transport
+            .sign(message(&other, &Pubkey::new_unique()))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, JitoError::InvalidMessage(_)));
+
+        let mut instruction: Instruction = tip_instruction(&payer, &Pubkey::new_unique(), 1);
+        instruction.accounts[1].is_signer = true;
+        let two_signers = VersionedMessage::Legacy(Message::new(&[instruction], Some(&payer)));
+        assert!(transport.sign(two_signers).await.is_err());
+    }
+
+    #[test]
+    fn test_landed_untracks() {
+        let transport = transport();
+        let signature = Signature::from([1u8; 64]);
+        let index = transport.track(PendingBundle {
+            bundle_id: "bundle-1".into(),
+            signature,
+        });
+
+        assert!(
+            transport
+                .to_result(BundleStatus::Pending, index)
+                .unwrap()
+                .is_pending()
+        );
+        assert_eq!(transport.bundle_id(index).as_deref(), Some("bundle-1"));
+
+        let result = transport
+            .to_result(BundleStatus::Landed { slot: 10 }, index)
+            .unwrap();
+        assert_eq!(result.signature(), Some(&signature));
+        assert!(transport.bundle_id(index).is_none());
+    }
+
+    #[test]
+    fn test_failed_is_error() {
+        let transport = transport();
+        let index = transport.track(PendingBundle {
+            bundle_id: "bundle-1".into(),
+            signature: Signature::default(),
+        });
+
+        let err = transport
+            .to_result(BundleStatus::Failed, index)
+            .unwrap_err();
+        assert!(matches!(err, JitoError::BundleFailed { .. }));
+        assert!(transport.bundle_id(index).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_check_status_unknown_index() {
+        let transport = transport();
+        let pending = transport.pending_result(42);
+        assert!(transport.check_status(&pending).await.is_err());
+    }
+
+    #[test]
+    fn test_requires_network() {
+        assert!(transport().requires_network());
+    }
+}
@@ -24,6 +24,10 @@ pub enum KeypairError {
     /// Base58 decoding error.
     #[error("Base58 decode error: {0}")]
     Base58(#[from] bs58::decode::Error),
+
+    /// Key derivation from a passphrase failed.
+    #[error("Key derivation error: {0}")]
+    KeyDerivation(String),
 }
 
 /// Result type for keypair operations.
@@ -37,6 +41,7 @@ impl From<KeypairError> for solana_actor::SignerError {
             KeypairError::Io(e) => Self::Io(e),
             KeypairError::Json(e) => Self::InvalidFormat(e.to_string()),
             KeypairError::Base58(e) => Self::InvalidKey(e.to_string()),
+            KeypairError::KeyDerivation(msg) => Self::InvalidKey(msg),
         }
     }
 }
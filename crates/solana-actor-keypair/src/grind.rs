@@ -0,0 +1,373 @@
+//! Vanity keypair grinding.
+//!
+//! [`Grinder`] generates random keypairs on every core until one's base58
+//! public key matches a [`GrindPattern`], such as a prefix of `"sol"`. Each
+//! extra character makes a match about 58 times rarer, so patterns beyond five
+//! or six characters can take hours; progress callbacks report the attempt
+//! rate and a cancellation flag stops the search.
+//!
+//! # Example
+//!
+//! ```
+//! use solana_actor_keypair::grind::{GrindPattern, Grinder};
+//!
+//! let pattern = GrindPattern::starts_with("A").unwrap().case_insensitive();
+//! let signer = Grinder::new(pattern).run(|_| {}).unwrap();
+//! assert!(signer.pubkey_base58().to_lowercase().starts_with('a'));
+//! ```
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, mpsc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::error::{KeypairError, Result};
+use crate::signer::KeypairSigner;
+
+/// Characters of the Bitcoin base58 alphabet Solana uses.
+const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Keypairs each thread generates between checks for a match elsewhere.
+const BATCH_SIZE: u64 = 256;
+
+/// Default time between progress callbacks.
+const DEFAULT_PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Whether a pattern distinguishes upper and lower case.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CaseSensitivity {
+    /// Characters must match exactly.
+    #[default]
+    Sensitive,
+    /// `a` matches `a` or `A`.
+    Insensitive,
+}
+
+/// A pattern for the base58 public key of a vanity keypair.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrindPattern {
+    prefix: String,
+    suffix: String,
+    case: CaseSensitivity,
+}
+
+impl GrindPattern {
+    /// Match public keys starting with `prefix`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`KeypairError::InvalidFormat`] if `prefix` has a character no
+    /// base58 key contains, such as `0` or `l`.
+    pub fn starts_with(prefix: &str) -> Result<Self> {
+        Self::new(prefix, "")
+    }
+
+    /// Match public keys ending with `suffix`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`KeypairError::InvalidFormat`] if `suffix` has a character no
+    /// base58 key contains.
+    pub fn ends_with(suffix: &str) -> Result<Self> {
+        Self::new("", suffix)
+    }
+
+    /// Match public keys starting with `prefix` and ending with `suffix`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`KeypairError::InvalidFormat`] if both are empty or either has
+    /// a character no base58 key contains.
+    pub fn new(prefix: &str, suffix: &str) -> Result<Self> {
+        if prefix.is_empty() && suffix.is_empty() {
+            return Err(KeypairError::InvalidFormat(
+                "Pattern needs a prefix or a suffix".into(),
+            ));
+        }
+        let pattern = Self {
+            prefix: prefix.to_string(),
+            suffix: suffix.to_string(),
+            case: CaseSensitivity::Sensitive,
+        };
+        pattern.validate()?;
+        Ok(pattern)
+    }
+
+    /// Ignore case when matching.
+    ///
+    /// Characters only valid in one case, such as `o` (base58 has no `O`),
+    /// still match that case.
+    pub fn case_insensitive(mut self) -> Self {
+        self.case = CaseSensitivity::Insensitive;
+        self
+    }
+
+    /// Set how case is matched.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`KeypairError::InvalidFormat`] if the pattern can't match in
+    /// that case, such as `L` when matching case-sensitively.
+    pub fn with_case(mut self, case: CaseSensitivity) -> Result<Self> {
+        self.case = case;
+        self.validate()?;
+        Ok(self)
+    }
+
+    /// The required prefix, possibly empty.
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// The required suffix, possibly empty.
+    pub fn suffix(&self) -> &str {
+        &self.suffix
+    }
+
+    /// How case is matched.
+    pub fn case(&self) -> CaseSensitivity {
+        self.case
+    }
+
+    /// Whether a base58 public key matches the pattern.
+    pub fn matches(&self, pubkey: &str) -> bool {
+        match self.case {
+            CaseSensitivity::Sensitive => {
+                pubkey.starts_with(&self.prefix) && pubkey.ends_with(&self.suffix)
+            }
+            CaseSensitivity::Insensitive => {
+                let pubkey = pubkey.as_bytes();
+                pubkey.len() >= self.prefix.len() + self.suffix.len()
+                    && pubkey[..self.prefix.len()].eq_ignore_ascii_case(self.prefix.as_bytes())
+                    && pubkey[pubkey.len() - self.suffix.len()..]
+                        .eq_ignore_ascii_case(self.suffix.as_bytes())
+            }
+        }
+    }
+
+    /// Expected number of keypairs to generate before a match.
+    ///
+    /// Ignores the slight bias of the leading base58 character, so treat it
+    /// as an estimate.
+    pub fn expected_attempts(&self) -> f64 {
+        self.prefix
+            .chars()
+            .chain(self.suffix.chars())
+            .map(|c| 58.0 / self.variants(c) as f64)
+            .product()
+    }
+
+    /// Number of base58 characters `c` matches.
+    fn variants(&self, c: char) -> usize {
+        match self.case {
+            CaseSensitivity::Sensitive => usize::from(BASE58_ALPHABET.contains(c)),
+            CaseSensitivity::Insensitive => BASE58_ALPHABET
+                .chars()
+                .filter(|a| a.eq_ignore_ascii_case(&c))
+                .count(),
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        match self
+            .prefix
+            .chars()
+            .chain(self.suffix.chars())
+            .find(|&c| self.variants(c) == 0)
+        {
+            Some(c) => Err(KeypairError::InvalidFormat(format!(
+                "'{}' never appears in a base58 public key",
+                c
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Progress of a running search.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GrindProgress {
+    /// Keypairs generated so far.
+    pub attempts: u64,
+    /// Time since the search started.
+    pub elapsed: Duration,
+    /// Expected attempts before a match, from
+    /// [`GrindPattern::expected_attempts`].
+    pub expected_attempts: f64,
+}
+
+impl GrindProgress {
+    /// Keypairs generated per second.
+    pub fn rate(&self) -> f64 {
+        self.attempts as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+/// Searches for a keypair matching a [`GrindPattern`] on several threads.
+#[derive(Debug, Clone)]
+pub struct Grinder {
+    pattern: GrindPattern,
+    threads: usize,
+    progress_interval: Duration,
+    cancel: Arc<AtomicBool>,
+}
+
+impl Grinder {
+    /// Create a grinder using every available core.
+    pub fn new(pattern: GrindPattern) -> Self {
+        Self {
+            pattern,
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+            progress_interval: DEFAULT_PROGRESS_INTERVAL,
+            cancel: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Use `threads` threads (at least one).
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Call the progress callback every `interval`.
+    pub fn with_progress_interval(mut self, interval: Duration) -> Self {
+        self.progress_interval = interval;
+        self
+    }
+
+    /// Stop the search once `cancel` is set, e.g. from a Ctrl-C handler.
+    pub fn with_cancel(mut self, cancel: Arc<AtomicBool>) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    /// Search until a keypair matches or the search is cancelled.
+    ///
+    /// Blocks the calling thread, which calls `on_progress` every progress
+    /// interval while worker threads generate keypairs. Returns `None` if
+    /// cancelled. Keypairs that don't match are zeroized as they are dropped.
+    pub fn run(&self, mut on_progress: impl FnMut(GrindProgress)) -> Option<KeypairSigner> {
+        let started = Instant::now();
+        let attempts = AtomicU64::new(0);
+        let found = AtomicBool::new(false);
+        let (tx, rx) = mpsc::channel();
+
+        thread::scope(|scope| {
+            for _ in 0..self.threads {
+                let tx = tx.clone();
+                let (attempts, found) = (&attempts, &found);
+                scope.spawn(move || {
+                    while !found.load(Ordering::Relaxed) && !self.cancel.load(Ordering::Relaxed) {
+                        for i in 0..BATCH_SIZE {
+                            let signer = KeypairSigner::generate();
+                            if self.pattern.matches(&signer.pubkey_base58()) {
+                                attempts.fetch_add(i + 1, Ordering::Relaxed);
+                                found.store(true, Ordering::Relaxed);
+                                let _ = tx.send(signer);
+                                return;
+                            }
+                        }
+                        attempts.fetch_add(BATCH_SIZE, Ordering::Relaxed);
+                    }
+                });
+            }
+            // Workers hold the only senders, so the channel closes once all
+            // have stopped
+            drop(tx);
+
+            loop {
+                match rx.recv_timeout(self.progress_interval) {
+                    Ok(signer) => {
+                        found.store(true, Ordering::Relaxed);
+                        return Some(signer);
+                    }
+                    Err(mpsc::RecvTimeoutError::Timeout) => on_progress(GrindProgress {
+                        attempts: attempts.load(Ordering::Relaxed),
+                        elapsed: started.elapsed(),
+                        expected_attempts: self.pattern.expected_attempts(),
+                    }),
+                    Err(mpsc::RecvTimeoutError::Disconnected) => return None,
+                }
+            }
+        })
+    }
+}
+
+/// Search for a keypair matching `prefix` and `suffix` on every core.
+///
+/// Either may be empty. For progress reporting or cancellation, use a
+/// [`Grinder`].
+///
+/// # Errors
+///
+/// Returns [`KeypairError::InvalidFormat`] if the pattern can never match.
+pub fn grind(prefix: &str, suffix: &str, case: CaseSensitivity) -> Result<KeypairSigner> {
+    let pattern = GrindPattern::new(prefix, suffix)?.with_case(case)?;
+    // Without a cancellation flag the search only ends with a match
+    Grinder::new(pattern)
+        .run(|_| {})
+        .ok_or_else(|| KeypairError::InvalidFormat("Search was cancelled".into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pattern_matches() {
+        let pattern = GrindPattern::new("ab", "Z").unwrap();
+        assert!(pattern.matches("abcdZ"));
+        assert!(!pattern.matches("Abcdz"));
+        assert!(pattern.clone().case_insensitive().matches("Abcdz"));
+        assert!(!pattern.case_insensitive().matches("aZ"));
+
+        assert!(GrindPattern::starts_with("0").is_err());
+        assert!(GrindPattern::ends_with("l").is_err());
+        assert!(GrindPattern::new("", "").is_err());
+
+        // Only lowercase o and uppercase L exist, but either case matches them
+        let pattern = GrindPattern::starts_with("O").unwrap_err();
+        assert!(matches!(pattern, KeypairError::InvalidFormat(_)));
+        let pattern = GrindPattern::starts_with("o").unwrap();
+        assert!(pattern.with_case(CaseSensitivity::Insensitive).is_ok());
+    }
+
+    #[test]
+    fn test_expected_attempts() {
+        let pattern = GrindPattern::starts_with("ab").unwrap();
+        assert_eq!(pattern.expected_attempts(), 58.0 * 58.0);
+        assert_eq!(pattern.case_insensitive().expected_attempts(), 29.0 * 29.0);
+        // 'o' has no uppercase counterpart in base58
+        let pattern = GrindPattern::starts_with("o").unwrap().case_insensitive();
+        assert_eq!(pattern.expected_attempts(), 58.0);
+    }
+
+    #[test]
+    fn test_grind() {
+        let signer = grind("", "A", CaseSensitivity::Insensitive).unwrap();
+        assert!(signer.pubkey_base58().to_lowercase().ends_with('a'));
+
+        let pattern = GrindPattern::starts_with("2").unwrap();
+        let signer = Grinder::new(pattern).with_threads(2).run(|_| {}).unwrap();
+        assert!(signer.pubkey_base58().starts_with('2'));
+    }
+
+    #[test]
+    fn test_cancel() {
+        let cancel = Arc::new(AtomicBool::new(false));
+        let pattern = GrindPattern::starts_with("zzzzzzzzzz").unwrap();
+        let grinder = Grinder::new(pattern)
+            .with_threads(2)
+            .with_progress_interval(Duration::from_millis(10))
+            .with_cancel(cancel.clone());
+
+        let mut calls = 0;
+        let result = grinder.run(|progress| {
+            calls += 1;
+            assert!(progress.expected_attempts > 1e17);
+            cancel.store(true, Ordering::Relaxed);
+        });
+        assert!(result.is_none());
+        assert!(calls >= 1);
+    }
+}
@@ -0,0 +1,300 @@
+//! Hierarchical deterministic (HD) account derivation.
+//!
+//! Wallets such as Phantom, Solflare and Ledger derive many accounts from one
+//! BIP39 seed with SLIP-0010 ed25519 derivation along `m/44'/501'/{i}'/0'`.
+//! [`HdSeed`] derives those accounts and enumerates their public keys without
+//! handing out any secret, so a caller can show the accounts and let the user
+//! pick which one to import.
+
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+
+use hmac::{Hmac, Mac};
+use sha2::Sha512;
+use solana_sdk::pubkey::Pubkey;
+use zeroize::Zeroizing;
+
+use crate::error::{KeypairError, Result};
+use crate::signer::KeypairSigner;
+
+/// HMAC key for the SLIP-0010 ed25519 master key.
+const ED25519_SEED_KEY: &[u8] = b"ed25519 seed";
+
+/// Bit marking a hardened child index.
+const HARDENED: u32 = 0x8000_0000;
+
+/// BIP44 purpose.
+const PURPOSE: u32 = 44;
+
+/// SLIP-0044 coin type of Solana.
+const SOLANA_COIN_TYPE: u32 = 501;
+
+/// A derivation path of hardened indices, such as `m/44'/501'/0'/0'`.
+///
+/// ed25519 only supports hardened derivation, so every index is hardened
+/// whether or not it is written with `'`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DerivationPath(Vec<u32>);
+
+impl DerivationPath {
+    /// The path of Solana account `account`: `m/44'/501'/{account}'/0'`.
+    ///
+    /// This is the path Phantom, Solflare and `solana-keygen` with
+    /// `--derivation-path` use for the account at that index. `account` must
+    /// be below `2^31`.
+    pub fn solana(account: u32) -> Self {
+        Self(vec![PURPOSE, SOLANA_COIN_TYPE, account, 0])
+    }
+
+    /// Create a path from its indices, without the hardened bit.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`KeypairError::KeyDerivation`] if an index is `2^31` or more.
+    pub fn from_indices(indices: Vec<u32>) -> Result<Self> {
+        if let Some(index) = indices.iter().find(|&&index| index >= HARDENED) {
+            return Err(KeypairError::KeyDerivation(format!(
+                "Index {} is out of range",
+                index
+            )));
+        }
+        Ok(Self(indices))
+    }
+
+    /// The indices of the path, without the hardened bit.
+    pub fn indices(&self) -> &[u32] {
+        &self.0
+    }
+}
+
+impl fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "m")?;
+        for index in &self.0 {
+            write!(f, "/{}'", index)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for DerivationPath {
+    type Err = KeypairError;
+
+    /// Parse `m/44'/501'/0'/0'`. The leading `m/` is optional, and `'`, `h`
+    /// and `H` all mark an index as hardened.
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || KeypairError::KeyDerivation(format!("Invalid derivation path: {}", s));
+
+        let s = s.trim();
+        let rest = s
+            .strip_prefix("m/")
+            .unwrap_or(if s == "m" { "" } else { s });
+        let indices = rest
+            .split('/')
+            .filter(|part| !part.is_empty())
+            .map(|part| {
+                part.trim_end_matches(['\'', 'h', 'H'])
+                    .parse::<u32>()
+                    .map_err(|_| invalid())
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Self::from_indices(indices)
+    }
+}
+
+/// An account derived from an [`HdSeed`], identified by its public key only.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DerivedAccount {
+    /// The account index in `m/44'/501'/{index}'/0'`.
+    pub index: u32,
+    /// The full derivation path.
+    pub path: DerivationPath,
+    /// The account's public key.
+    pub pubkey: Pubkey,
+}
+
+/// A BIP39 seed to derive accounts from.
+///
+/// The seed is zeroized when dropped. Deriving a [`KeypairSigner`] is the only
+/// way to obtain a secret; [`accounts`](Self::accounts) and
+/// [`pubkey`](Self::pubkey) return public keys alone.
+pub struct HdSeed {
+    seed: Zeroizing<Vec<u8>>,
+}
+
+impl fmt::Debug for HdSeed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HdSeed").finish_non_exhaustive()
+    }
+}
+
+impl HdSeed {
+    /// Wrap a seed, usually the 64-byte output of BIP39 `to_seed`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`KeypairError::InvalidFormat`] if the seed is not between 16
+    /// and 64 bytes, the lengths SLIP-0010 allows.
+    pub fn from_bytes(seed: &[u8]) -> Result<Self> {
+        if !(16..=64).contains(&seed.len()) {
+            return Err(KeypairError::InvalidFormat(format!(
+                "Seed must be 16 to 64 bytes, got {}",
+                seed.len()
+            )));
+        }
+        Ok(Self {
+            seed: Zeroizing::new(seed.to_vec()),
+        })
+    }
+
+    /// Derive the signer at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`KeypairError::KeyDerivation`] if derivation fails.
+    pub fn derive(&self, path: &DerivationPath) -> Result<KeypairSigner> {
+        KeypairSigner::from_bytes(&self.secret(path)?)
+    }
+
+    /// Derive the signer of Solana account `account`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`KeypairError::KeyDerivation`] if derivation fails.
+    pub fn derive_account(&self, account: u32) -> Result<KeypairSigner> {
+        self.derive(&DerivationPath::solana(account))
+    }
+
+    /// The public key at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`KeypairError::KeyDerivation`] if derivation fails.
+    pub fn pubkey(&self, path: &DerivationPath) -> Result<Pubkey> {
+        Ok(Pubkey::new_from_array(self.derive(path)?.pubkey_bytes()))
+    }
+
+    /// The Solana accounts at `indices`, by public key.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`KeypairError::KeyDerivation`] if derivation fails or an index
+    /// is `2^31` or more.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use solana_actor_keypair::hd::HdSeed;
+    ///
+    /// let seed = HdSeed::from_bytes(&[7u8; 64]).unwrap();
+    /// for account in seed.accounts(0..5).unwrap() {
+    ///     println!("{}  {}  {}", account.index, account.path, account.pubkey);
+    /// }
+    /// ```
+    pub fn accounts(&self, indices: Range<u32>) -> Result<Vec<DerivedAccount>> {
+        indices
+            .map(|index| {
+                let path = DerivationPath::from_indices(DerivationPath::solana(index).0)?;
+                Ok(DerivedAccount {
+                    index,
+                    pubkey: self.pubkey(&path)?,
+                    path,
+                })
+            })
+            .collect()
+    }
+
+    /// SLIP-0010 ed25519 derivation of the secret key at `path`.
+    fn secret(&self, path: &DerivationPath) -> Result<Zeroizing<[u8; 32]>> {
+        let (mut key, mut chain_code) = split(hmac_sha512(ED25519_SEED_KEY, &[&self.seed[..]])?);
+        for &index in path.indices() {
+            let hardened = (index | HARDENED).to_be_bytes();
+            (key, chain_code) = split(hmac_sha512(
+                &chain_code[..],
+                &[&[0u8], &key[..], &hardened],
+            )?);
+        }
+        Ok(key)
+    }
+}
+
+fn hmac_sha512(key: &[u8], parts: &[&[u8]]) -> Result<Zeroizing<[u8; 64]>> {
+    let mut mac = Hmac::<Sha512>::new_from_slice(key)
+        .map_err(|e| KeypairError::KeyDerivation(e.to_string()))?;
+    for part in parts {
+        mac.update(part);
+    }
+    let mut out = Zeroizing::new([0u8; 64]);
+    out.copy_from_slice(&mac.finalize().into_bytes());
+    Ok(out)
+}
+
+/// Split HMAC output into the key (left half) and chain code (right half).
+fn split(output: Zeroizing<[u8; 64]>) -> (Zeroizing<[u8; 32]>, Zeroizing<[u8; 32]>) {
+    let mut key = Zeroizing::new([0u8; 32]);
+    let mut chain_code = Zeroizing::new([0u8; 32]);
+    key.copy_from_slice(&output[..32]);
+    chain_code.copy_from_slice(&output[32..]);
+    (key, chain_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn test_slip10_vector() {
+        // SLIP-0010 ed25519 test vector 1
+        let seed: Vec<u8> = (0u8..16).collect();
+        let seed = HdSeed::from_bytes(&seed).unwrap();
+
+        let master = seed.secret(&"m".parse().unwrap()).unwrap();
+        assert_eq!(
+            hex(&master[..]),
+            "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7"
+        );
+
+        let path: DerivationPath = "m/0H".parse().unwrap();
+        let child = seed.derive(&path).unwrap();
+        assert_eq!(
+            hex(&child.secret_bytes()[..]),
+            "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3"
+        );
+        assert_eq!(
+            hex(&child.pubkey_bytes()),
+            "8c8a13df77a28f3445213a0f432fde644acaa215fc72dcdf300d5efaa85d350c"
+        );
+    }
+
+    #[test]
+    fn test_accounts_match_derive() {
+        let seed = HdSeed::from_bytes(&[7u8; 64]).unwrap();
+        let accounts = seed.accounts(0..3).unwrap();
+
+        assert_eq!(accounts.len(), 3);
+        assert_eq!(accounts[2].index, 2);
+        assert_eq!(accounts[2].path.to_string(), "m/44'/501'/2'/0'");
+        for account in &accounts {
+            let signer = seed.derive_account(account.index).unwrap();
+            assert_eq!(account.pubkey.to_bytes(), signer.pubkey_bytes());
+        }
+        assert_ne!(accounts[0].pubkey, accounts[1].pubkey);
+    }
+
+    #[test]
+    fn test_parse_derivation_path() {
+        let path: DerivationPath = "m/44'/501'/3'/0'".parse().unwrap();
+        assert_eq!(path, DerivationPath::solana(3));
+        assert_eq!("44h/501H/3/0".parse::<DerivationPath>().unwrap(), path);
+        assert!("m".parse::<DerivationPath>().unwrap().indices().is_empty());
+
+        assert!("m/44'/x'".parse::<DerivationPath>().is_err());
+        assert!("m/2147483648'".parse::<DerivationPath>().is_err());
+        assert!(HdSeed::from_bytes(&[0u8; 8]).is_err());
+    }
+}
@@ -8,6 +8,12 @@
 //! - **Secure memory handling** - Secret keys are automatically zeroized when dropped
 //! - **Multiple input formats** - Load from files, bytes, or base58 encoding
 //! - **Solana CLI compatible** - Works with standard Solana keypair JSON files
+//! - **Passphrase derivation** - Reproducible dev/test keys from a passphrase
+//!   via Argon2id (see [`passphrase`]; never for real funds)
+//! - **HD accounts** - Derive and list `m/44'/501'/{i}'/0'` accounts from a
+//!   BIP39 seed by public key (see [`hd`])
+//! - **Vanity keys** - Grind for a public key with a chosen prefix or suffix
+//!   on every core, with progress and cancellation (see [`grind`])
 //!
 //! # Example
 //!
@@ -52,10 +58,16 @@
 
 mod error;
 mod file;
+pub mod grind;
+pub mod hd;
+pub mod passphrase;
 mod signer;
 
 pub use error::{KeypairError, Result};
 pub use file::{from_file, from_json_string, to_base58, to_file, to_json};
+pub use grind::{CaseSensitivity, GrindPattern, Grinder};
+pub use hd::{DerivationPath, DerivedAccount, HdSeed};
+pub use passphrase::{PassphraseParams, passphrase_warnings};
 pub use signer::KeypairSigner;
 
 // Re-export traits for convenience
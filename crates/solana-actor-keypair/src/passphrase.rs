@@ -0,0 +1,205 @@
+//! Deterministic keypairs derived from a passphrase ("brain wallets").
+//!
+//! **Brain wallets are weak.** Anyone who guesses the passphrase and salt gets
+//! the key, and attackers run large dictionaries against known salts. Use
+//! passphrase-derived keys only for ephemeral development and test identities
+//! that must be reproducible across machines without storing a file, never
+//! for funds that matter.
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use zeroize::Zeroizing;
+
+use crate::error::{KeypairError, Result};
+use crate::signer::KeypairSigner;
+
+/// Minimum salt length accepted by Argon2.
+pub const MIN_SALT_LEN: usize = 8;
+
+/// Passphrases shorter than this trigger a strength warning.
+const MIN_RECOMMENDED_LEN: usize = 16;
+
+/// Argon2id cost parameters for passphrase derivation.
+///
+/// The same passphrase, salt and parameters always produce the same key, so
+/// parameters must be recorded alongside the salt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PassphraseParams {
+    /// Memory cost in KiB.
+    pub memory_kib: u32,
+    /// Number of iterations.
+    pub iterations: u32,
+    /// Degree of parallelism.
+    pub parallelism: u32,
+}
+
+impl Default for PassphraseParams {
+    /// 64 MiB, 3 iterations, 4 lanes (the keyring's master key parameters).
+    fn default() -> Self {
+        Self {
+            memory_kib: 65536,
+            iterations: 3,
+            parallelism: 4,
+        }
+    }
+}
+
+impl PassphraseParams {
+    /// Create parameters with explicit costs.
+    pub fn new(memory_kib: u32, iterations: u32, parallelism: u32) -> Self {
+        Self {
+            memory_kib,
+            iterations,
+            parallelism,
+        }
+    }
+}
+
+impl KeypairSigner {
+    /// Derive a keypair deterministically from a passphrase and salt.
+    ///
+    /// The 32-byte secret key is Argon2id(passphrase, salt, params). Strength
+    /// warnings from [`passphrase_warnings`] are printed to stderr, along with
+    /// a reminder that brain wallets are only suitable for dev/test keys.
+    ///
+    /// # Security
+    ///
+    /// **Do not hold real funds with a passphrase-derived key.** Its security
+    /// is exactly the passphrase's; see the [module docs](crate::passphrase).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`KeypairError::KeyDerivation`] if the salt is shorter than
+    /// [`MIN_SALT_LEN`] bytes or the parameters are invalid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use solana_actor_keypair::{KeypairSigner, PassphraseParams};
+    ///
+    /// let params = PassphraseParams::new(1024, 1, 1);
+    /// let a = KeypairSigner::from_passphrase("correct horse battery staple", b"devnet-alice", &params).unwrap();
+    /// let b = KeypairSigner::from_passphrase("correct horse battery staple", b"devnet-alice", &params).unwrap();
+    /// assert_eq!(a.pubkey_bytes(), b.pubkey_bytes());
+    /// ```
+    pub fn from_passphrase(
+        passphrase: &str,
+        salt: &[u8],
+        params: &PassphraseParams,
+    ) -> Result<Self> {
+        if salt.len() < MIN_SALT_LEN {
+            return Err(KeypairError::KeyDerivation(format!(
+                "Salt must be at least {} bytes, got {}",
+                MIN_SALT_LEN,
+                salt.len()
+            )));
+        }
+
+        eprintln!(
+            "WARNING: deriving a keypair from a passphrase (brain wallet). \
+             Use it only for dev/test identities, never for real funds."
+        );
+        for warning in passphrase_warnings(passphrase) {
+            eprintln!("WARNING: {}", warning);
+        }
+
+        let params = Params::new(
+            params.memory_kib,
+            params.iterations,
+            params.parallelism,
+            Some(32),
+        )
+        .map_err(|e| KeypairError::KeyDerivation(e.to_string()))?;
+        let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, params);
+
+        let mut secret = Zeroizing::new([0u8; 32]);
+        argon2
+            .hash_password_into(passphrase.as_bytes(), salt, secret.as_mut())
+            .map_err(|e| KeypairError::KeyDerivation(e.to_string()))?;
+
+        Self::from_bytes(&secret)
+    }
+}
+
+/// Describe the weaknesses of a passphrase for key derivation.
+///
+/// Returns an empty list if no weakness was found. An empty list does not
+/// make a passphrase safe for real funds.
+pub fn passphrase_warnings(passphrase: &str) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    let len = passphrase.chars().count();
+    if len < MIN_RECOMMENDED_LEN {
+        warnings.push(format!(
+            "Passphrase is only {} characters; use at least {}",
+            len, MIN_RECOMMENDED_LEN
+        ));
+    }
+
+    let classes = [
+        passphrase.chars().any(|c| c.is_ascii_lowercase()),
+        passphrase.chars().any(|c| c.is_ascii_uppercase()),
+        passphrase.chars().any(|c| c.is_ascii_digit()),
+        passphrase.chars().any(|c| !c.is_ascii_alphanumeric()),
+    ]
+    .into_iter()
+    .filter(|&present| present)
+    .count();
+    let words = passphrase.split_whitespace().count();
+    if classes < 2 && words < 4 {
+        warnings.push(
+            "Passphrase uses a single kind of character; mix cases, digits and symbols or use four or more words"
+                .to_string(),
+        );
+    }
+
+    let mut unique: Vec<char> = passphrase.chars().collect();
+    unique.sort_unstable();
+    unique.dedup();
+    if len > 0 && unique.len() * 2 < len.min(MIN_RECOMMENDED_LEN) {
+        warnings.push("Passphrase repeats the same few characters".to_string());
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params() -> PassphraseParams {
+        PassphraseParams::new(1024, 1, 1)
+    }
+
+    #[test]
+    fn test_from_passphrase_is_deterministic() {
+        let a = KeypairSigner::from_passphrase("passphrase", b"salt-salt", &params()).unwrap();
+        let b = KeypairSigner::from_passphrase("passphrase", b"salt-salt", &params()).unwrap();
+        assert_eq!(a.pubkey_bytes(), b.pubkey_bytes());
+
+        let other_salt =
+            KeypairSigner::from_passphrase("passphrase", b"salt-other", &params()).unwrap();
+        assert_ne!(a.pubkey_bytes(), other_salt.pubkey_bytes());
+
+        let other_params = KeypairSigner::from_passphrase(
+            "passphrase",
+            b"salt-salt",
+            &PassphraseParams::new(2048, 1, 1),
+        )
+        .unwrap();
+        assert_ne!(a.pubkey_bytes(), other_params.pubkey_bytes());
+    }
+
+    #[test]
+    fn test_short_salt_rejected() {
+        let result = KeypairSigner::from_passphrase("passphrase", b"short", &params());
+        assert!(matches!(result, Err(KeypairError::KeyDerivation(_))));
+    }
+
+    #[test]
+    fn test_passphrase_warnings() {
+        assert_eq!(passphrase_warnings("password").len(), 2);
+        assert!(!passphrase_warnings("aaaaaaaaaaaaaaaaaaaa").is_empty());
+        assert!(passphrase_warnings("correct horse battery staple").is_empty());
+        assert!(passphrase_warnings("Tr0ub4dor&3-xkcd-936!").is_empty());
+    }
+}
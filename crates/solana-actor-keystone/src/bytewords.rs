@@ -0,0 +1,135 @@
+//! Bytewords encoding (BCR-2020-012).
+//!
+//! URs encode their CBOR payload in the minimal bytewords style: each byte
+//! becomes the first and last letters of a four-letter word, and a CRC-32 of
+//! the payload is appended so damaged scans are caught.
+
+use crate::error::{KeystoneError, Result};
+
+/// The 256 bytewords, in byte order.
+const WORDS: [&str; 256] = [
+    "able", "acid", "also", "apex", "aqua", "arch", "atom", "aunt", "away", "axis", "back", "bald",
+    "barn", "belt", "beta", "bias", "blue", "body", "brag", "brew", "bulb", "buzz", "calm", "cash",
+    "cats", "chef", "city", "claw", "code", "cola", "cook", "cost", "crux", "curl", "cusp", "cyan",
+    "dark", "data", "days", "deli", "dice", "diet", "door", "down", "draw", "drop", "drum", "dull",
+    "duty", "each", "easy", "echo", "edge", "epic", "even", "exam", "exit", "eyes", "fact", "fair",
+    "fern", "figs", "film", "fish", "fizz", "flap", "flew", "flux", "foxy", "free", "frog", "fuel",
+    "fund", "gala", "game", "gear", "gems", "gift", "girl", "glow", "good", "gray", "grim", "guru",
+    "gush", "gyro", "half", "hang", "hard", "hawk", "heat", "help", "high", "hill", "holy", "hope",
+    "horn", "huts", "iced", "idea", "idle", "inch", "inky", "into", "iris", "iron", "item", "jade",
+    "jazz", "join", "jolt", "jowl", "judo", "jugs", "jump", "junk", "jury", "keep", "keno", "kept",
+    "keys", "kick", "kiln", "king", "kite", "kiwi", "knob", "lamb", "lava", "lazy", "leaf", "legs",
+    "liar", "limp", "lion", "list", "logo", "loud", "love", "luau", "luck", "lung", "main", "many",
+    "math", "maze", "memo", "menu", "meow", "mild", "mint", "miss", "monk", "nail", "navy", "need",
+    "news", "next", "noon", "note", "numb", "obey", "oboe", "omit", "onyx", "open", "oval", "owls",
+    "paid", "part", "peck", "play", "plus", "poem", "pool", "pose", "puff", "puma", "purr", "quad",
+    "quiz", "race", "ramp", "real", "redo", "rich", "road", "rock", "roof", "ruby", "ruin", "runs",
+    "rust", "safe", "saga", "scar", "sets", "silk", "skew", "slot", "soap", "solo", "song", "stub",
+    "surf", "swan", "taco", "task", "taxi", "tent", "tied", "time", "tiny", "toil", "tomb", "toys",
+    "trip", "tuna", "twin", "ugly", "undo", "unit", "urge", "user", "vast", "very", "veto", "vial",
+    "vibe", "view", "visa", "void", "vows", "wall", "wand", "warm", "wasp", "wave", "waxy", "webs",
+    "what", "when", "whiz", "wolf", "work", "yank", "yawn", "yell", "yoga", "yurt", "zaps", "zero",
+    "zest", "zinc", "zone", "zoom",
+];
+
+/// Encode bytes as minimal bytewords with a trailing checksum.
+pub fn encode(data: &[u8]) -> String {
+    let mut encoded = String::with_capacity((data.len() + 4) * 2);
+    for &byte in data.iter().chain(&crc32(data).to_be_bytes()) {
+        let word = WORDS[byte as usize].as_bytes();
+        encoded.push(word[0] as char);
+        encoded.push(word[3] as char);
+    }
+    encoded
+}
+
+/// Decode minimal bytewords, checking and removing the checksum.
+///
+/// Decoding is case-insensitive, since QR codes carry URs in upper case.
+///
+/// # Errors
+///
+/// Returns [`KeystoneError::InvalidUr`] for unknown words and
+/// [`KeystoneError::ChecksumMismatch`] if the checksum doesn't match.
+pub fn decode(encoded: &str) -> Result<Vec<u8>> {
+    let encoded = encoded.to_ascii_lowercase();
+    if !encoded.is_ascii() || encoded.len() % 2 != 0 || encoded.len() < 10 {
+        return Err(KeystoneError::InvalidUr("Bad bytewords length".into()));
+    }
+
+    let mut data = encoded
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            WORDS
+                .iter()
+                .position(|word| {
+                    let word = word.as_bytes();
+                    word[0] == pair[0] && word[3] == pair[1]
+                })
+                .map(|byte| byte as u8)
+                .ok_or_else(|| {
+                    KeystoneError::InvalidUr(format!(
+                        "Unknown byteword: {}",
+                        String::from_utf8_lossy(pair)
+                    ))
+                })
+        })
+        .collect::<Result<Vec<u8>>>()?;
+
+    let checksum = data.split_off(data.len() - 4);
+    if crc32(&data).to_be_bytes() != checksum[..] {
+        return Err(KeystoneError::ChecksumMismatch);
+    }
+    Ok(data)
+}
+
+/// CRC-32 (IEEE), as used by bytewords and multi-part URs.
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_reference_vector() {
+        // Single-part `ur:bytes` example from BCR-2020-005
+        let data = decode(
+            "hdeymejtswhhylkepmykhhtsytsnoyoyaxaedsuttydmmhhpktpmsrjtgwdpfnsboxgwlbaawzuefywkdplrsrjynbvygabwjldapfcsdwkbrkch",
+        )
+        .unwrap();
+        assert_eq!(data.len(), 52);
+        assert_eq!(&data[..2], &[0x58, 0x32]);
+        assert_eq!(encode(&data).len(), 112);
+    }
+
+    #[test]
+    fn test_round_trip_and_checksum() {
+        let data = b"solana".to_vec();
+        let encoded = encode(&data);
+        assert_eq!(decode(&encoded.to_ascii_uppercase()).unwrap(), data);
+
+        // Flip one word
+        let mut damaged = encoded.into_bytes();
+        damaged[0] = if damaged[0] == b'a' { b'b' } else { b'a' };
+        assert!(decode(std::str::from_utf8(&damaged).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    }
+}
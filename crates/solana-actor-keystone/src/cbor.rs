@@ -0,0 +1,273 @@
+//! Minimal CBOR encoding for UR payloads.
+//!
+//! Covers the subset the Solana UR types use: unsigned integers, byte and
+//! text strings, arrays, maps, tags and booleans, always in the shortest
+//! (canonical) form.
+
+use crate::error::{KeystoneError, Result};
+
+const MAJOR_UNSIGNED: u8 = 0;
+const MAJOR_BYTES: u8 = 2;
+const MAJOR_TEXT: u8 = 3;
+const MAJOR_ARRAY: u8 = 4;
+const MAJOR_MAP: u8 = 5;
+const MAJOR_TAG: u8 = 6;
+const MAJOR_SIMPLE: u8 = 7;
+
+const SIMPLE_FALSE: u8 = 20;
+const SIMPLE_TRUE: u8 = 21;
+
+/// Deepest nesting accepted when decoding
+const MAX_DEPTH: usize = 16;
+
+/// A CBOR data item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Cbor {
+    /// Unsigned integer.
+    Unsigned(u64),
+    /// Byte string.
+    Bytes(Vec<u8>),
+    /// Text string.
+    Text(String),
+    /// Array.
+    Array(Vec<Cbor>),
+    /// Map, in encoding order.
+    Map(Vec<(Cbor, Cbor)>),
+    /// Tagged item.
+    Tag(u64, Box<Cbor>),
+    /// Boolean.
+    Bool(bool),
+}
+
+impl Cbor {
+    /// Encode the item.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.encode_into(&mut out);
+        out
+    }
+
+    fn encode_into(&self, out: &mut Vec<u8>) {
+        match self {
+            Cbor::Unsigned(value) => write_head(out, MAJOR_UNSIGNED, *value),
+            Cbor::Bytes(bytes) => {
+                write_head(out, MAJOR_BYTES, bytes.len() as u64);
+                out.extend_from_slice(bytes);
+            }
+            Cbor::Text(text) => {
+                write_head(out, MAJOR_TEXT, text.len() as u64);
+                out.extend_from_slice(text.as_bytes());
+            }
+            Cbor::Array(items) => {
+                write_head(out, MAJOR_ARRAY, items.len() as u64);
+                for item in items {
+                    item.encode_into(out);
+                }
+            }
+            Cbor::Map(entries) => {
+                write_head(out, MAJOR_MAP, entries.len() as u64);
+                for (key, value) in entries {
+                    key.encode_into(out);
+                    value.encode_into(out);
+                }
+            }
+            Cbor::Tag(tag, item) => {
+                write_head(out, MAJOR_TAG, *tag);
+                item.encode_into(out);
+            }
+            Cbor::Bool(value) => {
+                out.push((MAJOR_SIMPLE << 5) | if *value { SIMPLE_TRUE } else { SIMPLE_FALSE })
+            }
+        }
+    }
+
+    /// Decode a single item that spans all of `data`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`KeystoneError::InvalidUr`] if the data is not a CBOR item of
+    /// the supported kinds, or has bytes left over.
+    pub fn decode(mut data: &[u8]) -> Result<Self> {
+        let item = Self::decode_item(&mut data, 0)?;
+        if !data.is_empty() {
+            return Err(invalid("Trailing bytes after CBOR item"));
+        }
+        Ok(item)
+    }
+
+    fn decode_item(data: &mut &[u8], depth: usize) -> Result<Self> {
+        if depth > MAX_DEPTH {
+            return Err(invalid("CBOR nested too deeply"));
+        }
+        let (major, value) = read_head(data)?;
+        match major {
+            MAJOR_UNSIGNED => Ok(Cbor::Unsigned(value)),
+            MAJOR_BYTES => Ok(Cbor::Bytes(take(data, value)?.to_vec())),
+            MAJOR_TEXT => String::from_utf8(take(data, value)?.to_vec())
+                .map(Cbor::Text)
+                .map_err(|_| invalid("CBOR text is not UTF-8")),
+            MAJOR_ARRAY => (0..length(data, value)?)
+                .map(|_| Self::decode_item(data, depth + 1))
+                .collect::<Result<_>>()
+                .map(Cbor::Array),
+            MAJOR_MAP => (0..length(data, value)?)
+                .map(|_| {
+                    Ok((
+                        Self::decode_item(data, depth + 1)?,
+                        Self::decode_item(data, depth + 1)?,
+                    ))
+                })
+                .collect::<Result<_>>()
+                .map(Cbor::Map),
+            MAJOR_TAG => Ok(Cbor::Tag(
+                value,
+                Box::new(Self::decode_item(data, depth + 1)?),
+            )),
+            MAJOR_SIMPLE if value == u64::from(SIMPLE_FALSE) => Ok(Cbor::Bool(false)),
+            MAJOR_SIMPLE if value == u64::from(SIMPLE_TRUE) => Ok(Cbor::Bool(true)),
+            _ => Err(invalid("Unsupported CBOR item")),
+        }
+    }
+
+    /// The value of an integer key in a map.
+    pub fn get(&self, key: u64) -> Option<&Cbor> {
+        let Cbor::Map(entries) = self else {
+            return None;
+        };
+        entries
+            .iter()
+            .find(|(k, _)| *k == Cbor::Unsigned(key))
+            .map(|(_, value)| value)
+    }
+
+    /// The item inside `tag`, or the item itself if it isn't tagged.
+    ///
+    /// UR payloads may omit tags on fields whose type is implied.
+    pub fn untag(&self, tag: u64) -> &Cbor {
+        match self {
+            Cbor::Tag(t, item) if *t == tag => item,
+            other => other,
+        }
+    }
+
+    /// The byte string, if this is one.
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Cbor::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    /// The unsigned integer, if this is one.
+    pub fn as_unsigned(&self) -> Option<u64> {
+        match self {
+            Cbor::Unsigned(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+fn write_head(out: &mut Vec<u8>, major: u8, value: u64) {
+    let major = major << 5;
+    if value < 24 {
+        out.push(major | value as u8);
+    } else if value <= u64::from(u8::MAX) {
+        out.push(major | 24);
+        out.push(value as u8);
+    } else if value <= u64::from(u16::MAX) {
+        out.push(major | 25);
+        out.extend_from_slice(&(value as u16).to_be_bytes());
+    } else if value <= u64::from(u32::MAX) {
+        out.push(major | 26);
+        out.extend_from_slice(&(value as u32).to_be_bytes());
+    } else {
+        out.push(major | 27);
+        out.extend_from_slice(&value.to_be_bytes());
+    }
+}
+
+fn read_head(data: &mut &[u8]) -> Result<(u8, u64)> {
+    let initial = take(data, 1)?[0];
+    let major = initial >> 5;
+    let info = initial & 0x1f;
+    let value = match info {
+        0..=23 => u64::from(info),
+        24 => u64::from(take(data, 1)?[0]),
+        25 => u64::from(u16::from_be_bytes(
+            take(data, 2)?.try_into().unwrap_or_default(),
+        )),
+        26 => u64::from(u32::from_be_bytes(
+            take(data, 4)?.try_into().unwrap_or_default(),
+        )),
+        27 => u64::from_be_bytes(take(data, 8)?.try_into().unwrap_or_default()),
+        _ => return Err(invalid("Indefinite-length CBOR is not supported")),
+    };
+    Ok((major, value))
+}
+
+fn take<'a>(data: &mut &'a [u8], len: u64) -> Result<&'a [u8]> {
+    let len = usize::try_from(len).map_err(|_| invalid("CBOR length too large"))?;
+    if data.len() < len {
+        return Err(invalid("Truncated CBOR"));
+    }
+    let (taken, rest) = data.split_at(len);
+    *data = rest;
+    Ok(taken)
+}
+
+/// A container length, bounded by the bytes left so a bogus length can't
+/// allocate or loop for long.
+fn length(data: &[u8], value: u64) -> Result<u64> {
+    if value > data.len() as u64 {
+        return Err(invalid("Truncated CBOR"));
+    }
+    Ok(value)
+}
+
+fn invalid(message: &str) -> KeystoneError {
+    KeystoneError::InvalidUr(message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_canonical() {
+        assert_eq!(Cbor::Unsigned(23).encode(), [0x17]);
+        assert_eq!(Cbor::Unsigned(500).encode(), [0x19, 0x01, 0xf4]);
+        assert_eq!(
+            Cbor::Tag(304, Box::new(Cbor::Bool(true))).encode(),
+            [0xd9, 0x01, 0x30, 0xf5]
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let item = Cbor::Map(vec![
+            (Cbor::Unsigned(1), Cbor::Bytes(vec![1, 2, 3])),
+            (
+                Cbor::Unsigned(2),
+                Cbor::Array(vec![Cbor::Text("solana".into()), Cbor::Bool(false)]),
+            ),
+            (Cbor::Unsigned(3), Cbor::Unsigned(u64::MAX)),
+        ]);
+        let decoded = Cbor::decode(&item.encode()).unwrap();
+        assert_eq!(decoded, item);
+        assert_eq!(
+            decoded.get(1).and_then(Cbor::as_bytes),
+            Some(&[1, 2, 3][..])
+        );
+        assert!(decoded.get(4).is_none());
+    }
+
+    #[test]
+    fn test_rejects_malformed() {
+        // Byte string claiming more bytes than present
+        assert!(Cbor::decode(&[0x45, 0x01]).is_err());
+        // Trailing bytes
+        assert!(Cbor::decode(&[0x01, 0x02]).is_err());
+        // Array claiming a huge length
+        assert!(Cbor::decode(&[0x9b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]).is_err());
+    }
+}
@@ -0,0 +1,67 @@
+//! Error types for Keystone operations.
+
+use thiserror::Error;
+
+/// Errors that can occur when signing with an air-gapped QR wallet.
+#[derive(Error, Debug)]
+pub enum KeystoneError {
+    /// A scanned QR code is not a valid UR, or its payload is malformed.
+    #[error("Invalid UR: {0}")]
+    InvalidUr(String),
+
+    /// A UR's checksum doesn't match its payload.
+    #[error("UR checksum mismatch")]
+    ChecksumMismatch,
+
+    /// A UR of a different type than expected was scanned.
+    #[error("Expected a {expected} UR, scanned {found}")]
+    UnexpectedType {
+        /// Expected UR type.
+        expected: String,
+        /// Scanned UR type.
+        found: String,
+    },
+
+    /// The wallet answered a different request.
+    #[error("Signature is for a different request")]
+    RequestMismatch,
+
+    /// The wallet's signature doesn't verify for the signer and message.
+    #[error("Signature from wallet does not verify")]
+    InvalidSignature,
+
+    /// Invalid derivation path.
+    #[error("Invalid derivation path: {0}")]
+    InvalidPath(String),
+
+    /// The display or scan callback failed.
+    #[error("QR interface error: {0}")]
+    Interface(String),
+
+    /// The user cancelled while the request was displayed or being scanned.
+    #[error("User cancelled QR signing")]
+    Cancelled,
+}
+
+/// Result type for Keystone operations.
+pub type Result<T> = std::result::Result<T, KeystoneError>;
+
+impl From<KeystoneError> for solana_actor::SignerError {
+    fn from(err: KeystoneError) -> Self {
+        match err {
+            KeystoneError::Cancelled => Self::UserCancelled,
+            KeystoneError::Interface(msg) => Self::DeviceError(msg),
+            KeystoneError::InvalidPath(msg) => Self::InvalidKey(msg),
+            KeystoneError::InvalidSignature | KeystoneError::RequestMismatch => {
+                Self::SigningFailed(err.to_string())
+            }
+            other => Self::InvalidFormat(other.to_string()),
+        }
+    }
+}
+
+impl From<KeystoneError> for solana_actor::TransportError {
+    fn from(err: KeystoneError) -> Self {
+        Self::Signer(err.into())
+    }
+}
@@ -0,0 +1,57 @@
+//! Keystone and air-gapped QR wallet transport for Solana.
+//!
+//! Air-gapped wallets such as Keystone never connect to the host. Requests
+//! and signatures travel as QR codes in the Uniform Resources (UR) format:
+//!
+//! - [`KeystoneTransport`] implements [`WalletTransport`], showing each
+//!   message as a `sol-sign-request` and scanning back the wallet's
+//!   `sol-signature`
+//! - [`QrInterface`] is the pluggable display and scan callback, so the same
+//!   transport works in a terminal, a desktop app or a phone
+//! - [`UrEncoder`] and [`UrDecoder`] split and reassemble animated
+//!   (multi-part) QR codes, for other UR types or custom interfaces
+//!
+//! Every signature is checked against the request ID and the signer's key
+//! before it is returned.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use async_trait::async_trait;
+//! use solana_actor_keystone::{KeystoneError, KeystoneTransport, QrInterface};
+//! use solana_actor::WalletTransport;
+//!
+//! struct TerminalQr;
+//!
+//! #[async_trait]
+//! impl QrInterface for TerminalQr {
+//!     async fn display(&self, parts: &[String]) -> Result<(), KeystoneError> {
+//!         // Render `parts` as QR codes, cycling through them
+//!         start_animation(parts);
+//!         Ok(())
+//!     }
+//!
+//!     async fn scan(&self) -> Result<String, KeystoneError> {
+//!         // Read the next code from a webcam or pasted text
+//!         read_ur_line().await.ok_or(KeystoneError::Cancelled)
+//!     }
+//! }
+//!
+//! let transport = KeystoneTransport::new(pubkey, master_fingerprint, TerminalQr);
+//! let result = transport.submit(&tx_message).await?;
+//! ```
+
+mod bytewords;
+pub mod cbor;
+mod error;
+pub mod registry;
+mod transport;
+pub mod ur;
+
+pub use error::{KeystoneError, Result};
+pub use registry::{SignType, SolSignRequest, SolSignature};
+pub use transport::{KeystoneTransport, QrInterface};
+pub use ur::{Ur, UrDecoder, UrEncoder};
+
+// Re-export traits for convenience
+pub use solana_actor::{SubmitResult, WalletTransport};
@@ -0,0 +1,245 @@
+//! Solana UR types.
+//!
+//! Keystone and compatible air-gapped wallets exchange two UR types for
+//! Solana: the host shows a `sol-sign-request` and scans back a
+//! `sol-signature`.
+
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+use crate::cbor::Cbor;
+use crate::error::{KeystoneError, Result};
+use crate::ur::Ur;
+
+/// UR type of a signing request.
+pub const SOL_SIGN_REQUEST: &str = "sol-sign-request";
+/// UR type of a signing response.
+pub const SOL_SIGNATURE: &str = "sol-signature";
+
+/// Default Solana derivation path (BIP-44).
+pub const DEFAULT_PATH: &str = "44'/501'/0'/0'";
+
+// CBOR tags
+const TAG_UUID: u64 = 37;
+const TAG_KEYPATH: u64 = 304;
+
+const HARDENED: u32 = 0x8000_0000;
+
+/// What a signing request asks the wallet to sign.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignType {
+    /// A serialized transaction message.
+    Transaction = 1,
+    /// An off-chain message.
+    Message = 2,
+}
+
+/// A `sol-sign-request` for the wallet to scan.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolSignRequest {
+    /// Random ID the wallet echoes in its signature.
+    pub request_id: [u8; 16],
+    /// Bytes to sign.
+    pub sign_data: Vec<u8>,
+    /// Derivation path of the signing key, with hardened bits set.
+    pub derivation_path: Vec<u32>,
+    /// Fingerprint of the wallet's master key, which the wallet checks
+    /// before signing.
+    pub master_fingerprint: u32,
+    /// Expected signer address, shown by the wallet.
+    pub address: Option<Pubkey>,
+    /// Name of the requesting application, shown by the wallet.
+    pub origin: Option<String>,
+    /// What `sign_data` is.
+    pub sign_type: SignType,
+}
+
+impl SolSignRequest {
+    /// Encode the request as a UR.
+    pub fn to_ur(&self) -> Ur {
+        let components = self
+            .derivation_path
+            .iter()
+            .flat_map(|&component| {
+                [
+                    Cbor::Unsigned(u64::from(component & !HARDENED)),
+                    Cbor::Bool(component & HARDENED != 0),
+                ]
+            })
+            .collect();
+        let keypath = Cbor::Map(vec![
+            (Cbor::Unsigned(1), Cbor::Array(components)),
+            (
+                Cbor::Unsigned(2),
+                Cbor::Unsigned(u64::from(self.master_fingerprint)),
+            ),
+        ]);
+
+        let mut entries = vec![
+            (
+                Cbor::Unsigned(1),
+                Cbor::Tag(TAG_UUID, Box::new(Cbor::Bytes(self.request_id.to_vec()))),
+            ),
+            (Cbor::Unsigned(2), Cbor::Bytes(self.sign_data.clone())),
+            (Cbor::Unsigned(3), Cbor::Tag(TAG_KEYPATH, Box::new(keypath))),
+        ];
+        if let Some(address) = &self.address {
+            entries.push((Cbor::Unsigned(4), Cbor::Bytes(address.to_bytes().to_vec())));
+        }
+        if let Some(origin) = &self.origin {
+            entries.push((Cbor::Unsigned(5), Cbor::Text(origin.clone())));
+        }
+        entries.push((Cbor::Unsigned(6), Cbor::Unsigned(self.sign_type as u64)));
+
+        Ur::new(SOL_SIGN_REQUEST, Cbor::Map(entries).encode())
+    }
+}
+
+/// A `sol-signature` scanned from the wallet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolSignature {
+    /// ID of the request this answers.
+    pub request_id: [u8; 16],
+    /// The signature.
+    pub signature: Signature,
+}
+
+impl SolSignature {
+    /// Decode a `sol-signature` UR.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the UR is of another type or malformed.
+    pub fn from_ur(ur: &Ur) -> Result<Self> {
+        let cbor = ur.decode_as(SOL_SIGNATURE)?;
+
+        let request_id = cbor
+            .get(1)
+            .map(|id| id.untag(TAG_UUID))
+            .and_then(Cbor::as_bytes)
+            .and_then(|bytes| <[u8; 16]>::try_from(bytes).ok())
+            .ok_or_else(|| KeystoneError::InvalidUr("Missing request ID".into()))?;
+        let signature = cbor
+            .get(2)
+            .and_then(Cbor::as_bytes)
+            .and_then(|bytes| <[u8; 64]>::try_from(bytes).ok())
+            .ok_or_else(|| KeystoneError::InvalidUr("Missing signature".into()))?;
+
+        Ok(Self {
+            request_id,
+            signature: Signature::from(signature),
+        })
+    }
+
+    /// Encode the signature as a UR, as the wallet does.
+    pub fn to_ur(&self) -> Ur {
+        let cbor = Cbor::Map(vec![
+            (
+                Cbor::Unsigned(1),
+                Cbor::Tag(TAG_UUID, Box::new(Cbor::Bytes(self.request_id.to_vec()))),
+            ),
+            (
+                Cbor::Unsigned(2),
+                Cbor::Bytes(self.signature.as_ref().to_vec()),
+            ),
+        ]);
+        Ur::new(SOL_SIGNATURE, cbor.encode())
+    }
+}
+
+/// Parse a derivation path string like "44'/501'/0'/0'" or "m/44'/501'/0'/0'".
+///
+/// Supports both `'` and `h` as hardened markers. Solana keys are derived
+/// with SLIP-10 ed25519, so every component must be hardened.
+///
+/// # Errors
+///
+/// Returns [`KeystoneError::InvalidPath`] if the path cannot be parsed or has
+/// a non-hardened component.
+pub fn parse_path(path: &str) -> Result<Vec<u32>> {
+    path.trim_start_matches("m/")
+        .split('/')
+        .map(|part| {
+            let index = part
+                .strip_suffix('\'')
+                .or_else(|| part.strip_suffix('h'))
+                .ok_or_else(|| {
+                    KeystoneError::InvalidPath(format!("Component must be hardened: {}", part))
+                })?;
+            match index.parse::<u32>() {
+                Ok(index) if index < HARDENED => Ok(index | HARDENED),
+                _ => Err(KeystoneError::InvalidPath(format!(
+                    "Invalid component: {}",
+                    part
+                ))),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_request_layout() {
+        let request = SolSignRequest {
+            request_id: [9; 16],
+            sign_data: vec![1, 2, 3],
+            derivation_path: parse_path(DEFAULT_PATH).unwrap(),
+            master_fingerprint: 0x1234_5678,
+            address: None,
+            origin: Some("sign-agent".into()),
+            sign_type: SignType::Transaction,
+        };
+        let cbor = request.to_ur().decode_as(SOL_SIGN_REQUEST).unwrap();
+
+        assert_eq!(
+            cbor.get(1),
+            Some(&Cbor::Tag(TAG_UUID, Box::new(Cbor::Bytes(vec![9; 16]))))
+        );
+        let Some(Cbor::Tag(TAG_KEYPATH, keypath)) = cbor.get(3) else {
+            panic!("Missing keypath");
+        };
+        assert_eq!(
+            keypath.get(1),
+            Some(&Cbor::Array(vec![
+                Cbor::Unsigned(44),
+                Cbor::Bool(true),
+                Cbor::Unsigned(501),
+                Cbor::Bool(true),
+                Cbor::Unsigned(0),
+                Cbor::Bool(true),
+                Cbor::Unsigned(0),
+                Cbor::Bool(true),
+            ]))
+        );
+        assert_eq!(keypath.get(2), Some(&Cbor::Unsigned(0x1234_5678)));
+        assert!(cbor.get(4).is_none());
+        assert_eq!(cbor.get(6), Some(&Cbor::Unsigned(1)));
+    }
+
+    #[test]
+    fn test_signature_round_trip() {
+        let signature = SolSignature {
+            request_id: [3; 16],
+            signature: Signature::from([5; 64]),
+        };
+        assert_eq!(
+            SolSignature::from_ur(&signature.to_ur()).unwrap(),
+            signature
+        );
+
+        let wrong_type = Ur::new(SOL_SIGN_REQUEST, signature.to_ur().cbor);
+        assert!(SolSignature::from_ur(&wrong_type).is_err());
+    }
+
+    #[test]
+    fn test_parse_path() {
+        assert_eq!(
+            parse_path("m/44h/501h/1'").unwrap(),
+            [44 | HARDENED, 501 | HARDENED, 1 | HARDENED]
+        );
+        assert!(parse_path("44'/501'/0").is_err());
+        assert!(parse_path("").is_err());
+    }
+}
@@ -0,0 +1,320 @@
+//! Air-gapped QR wallet transport.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use rand::RngCore;
+use solana_actor::{SubmitResult, TransportError, WalletTransport};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+use crate::error::{KeystoneError, Result};
+use crate::registry::{DEFAULT_PATH, SignType, SolSignRequest, SolSignature, parse_path};
+use crate::ur::{DEFAULT_MAX_FRAGMENT_LEN, Ur, UrDecoder, UrEncoder};
+
+/// Shows QR codes to the wallet and scans its answer.
+///
+/// Implement this for the UI at hand: a terminal that prints QR codes and
+/// reads pasted UR text, a desktop app with a webcam, or a phone.
+#[async_trait]
+pub trait QrInterface: Send + Sync {
+    /// Start showing `parts` for the wallet to scan.
+    ///
+    /// With more than one part, cycle through them as an animated QR code,
+    /// a few frames per second, until [`done`](Self::done) is called.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`KeystoneError::Cancelled`] if the user gives up, or
+    /// [`KeystoneError::Interface`] if the codes can't be shown.
+    async fn display(&self, parts: &[String]) -> Result<()>;
+
+    /// Scan the next QR code from the wallet and return its text.
+    ///
+    /// Called repeatedly until the wallet's animated answer is complete.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`KeystoneError::Cancelled`] if the user gives up, or
+    /// [`KeystoneError::Interface`] if scanning fails.
+    async fn scan(&self) -> Result<String>;
+
+    /// Stop showing the request; the wallet's answer is in.
+    async fn done(&self) {}
+}
+
+/// Signs through an air-gapped wallet, such as a Keystone, by QR code.
+///
+/// Each submission shows a `sol-sign-request` through the [`QrInterface`],
+/// scans the wallet's `sol-signature` back, and checks that it answers the
+/// request and verifies for the signer.
+///
+/// # Example
+///
+/// ```ignore
+/// use solana_actor_keystone::KeystoneTransport;
+/// use solana_actor::WalletTransport;
+///
+/// // Pubkey and master fingerprint come from the wallet's "connect" QR code
+/// let transport = KeystoneTransport::new(pubkey, 0x1234_5678, TerminalQr)
+///     .with_derivation_path("44'/501'/1'/0'")?
+///     .with_origin("sign-agent");
+///
+/// let result = transport.submit(&tx_message).await?;
+/// ```
+pub struct KeystoneTransport<Q> {
+    pubkey: Pubkey,
+    master_fingerprint: u32,
+    derivation_path: Vec<u32>,
+    origin: Option<String>,
+    max_fragment_len: usize,
+    qr: Q,
+}
+
+impl<Q: QrInterface> KeystoneTransport<Q> {
+    /// Create a transport for the key at the default derivation path.
+    ///
+    /// # Arguments
+    ///
+    /// * `pubkey` - The wallet key that signs.
+    /// * `master_fingerprint` - Fingerprint of the wallet's master key.
+    /// * `qr` - How QR codes are shown and scanned.
+    pub fn new(pubkey: Pubkey, master_fingerprint: u32, qr: Q) -> Self {
+        Self {
+            pubkey,
+            master_fingerprint,
+            derivation_path: parse_path(DEFAULT_PATH).unwrap_or_default(),
+            origin: None,
+            max_fragment_len: DEFAULT_MAX_FRAGMENT_LEN,
+            qr,
+        }
+    }
+
+    /// Set the derivation path of the signing key.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`KeystoneError::InvalidPath`] if the path is invalid.
+    pub fn with_derivation_path(mut self, path: &str) -> Result<Self> {
+        self.derivation_path = parse_path(path)?;
+        Ok(self)
+    }
+
+    /// Set the application name the wallet shows with each request.
+    pub fn with_origin(mut self, origin: impl Into<String>) -> Self {
+        self.origin = Some(origin.into());
+        self
+    }
+
+    /// Set the largest fragment per QR code, in bytes.
+    pub fn with_max_fragment_len(mut self, max_fragment_len: usize) -> Self {
+        self.max_fragment_len = max_fragment_len;
+        self
+    }
+
+    /// The QR interface.
+    pub fn qr(&self) -> &Q {
+        &self.qr
+    }
+
+    /// Build a signing request for a serialized transaction message.
+    pub fn sign_request(&self, message: &[u8]) -> SolSignRequest {
+        let mut request_id = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut request_id);
+        // Random (version 4) UUID
+        request_id[6] = (request_id[6] & 0x0f) | 0x40;
+        request_id[8] = (request_id[8] & 0x3f) | 0x80;
+
+        SolSignRequest {
+            request_id,
+            sign_data: message.to_vec(),
+            derivation_path: self.derivation_path.clone(),
+            master_fingerprint: self.master_fingerprint,
+            address: Some(self.pubkey),
+            origin: self.origin.clone(),
+            sign_type: SignType::Transaction,
+        }
+    }
+
+    /// Have the wallet sign a serialized transaction message.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the QR interface fails or is cancelled, or the
+    /// wallet's answer is malformed, for another request, or doesn't verify.
+    pub async fn sign(&self, message: &[u8]) -> Result<Signature> {
+        let request = self.sign_request(message);
+        let parts = UrEncoder::new(request.to_ur())
+            .with_max_fragment_len(self.max_fragment_len)
+            .parts();
+
+        self.qr.display(&parts).await?;
+        let answer = self.scan_answer().await;
+        self.qr.done().await;
+
+        let answer = SolSignature::from_ur(&answer?)?;
+        if answer.request_id != request.request_id {
+            return Err(KeystoneError::RequestMismatch);
+        }
+        if !answer.signature.verify(self.pubkey.as_ref(), message) {
+            return Err(KeystoneError::InvalidSignature);
+        }
+        Ok(answer.signature)
+    }
+
+    async fn scan_answer(&self) -> Result<Ur> {
+        let mut decoder = UrDecoder::new();
+        loop {
+            let part = self.qr.scan().await?;
+            if let Some(ur) = decoder.receive(&part)? {
+                return Ok(ur);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<Q: QrInterface> WalletTransport for KeystoneTransport<Q> {
+    fn authority(&self) -> Pubkey {
+        self.pubkey
+    }
+
+    async fn submit(&self, message: &[u8]) -> std::result::Result<SubmitResult, TransportError> {
+        Ok(SubmitResult::Signed(self.sign(message).await?))
+    }
+
+    async fn check_status(
+        &self,
+        result: &SubmitResult,
+    ) -> std::result::Result<SubmitResult, TransportError> {
+        // Signing completes within submit
+        Ok(result.clone())
+    }
+
+    async fn wait_for_completion(
+        &self,
+        result: SubmitResult,
+        _timeout: Duration,
+    ) -> std::result::Result<SubmitResult, TransportError> {
+        Ok(result)
+    }
+
+    fn requires_network(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cbor::Cbor;
+    use crate::registry::SOL_SIGN_REQUEST;
+    use solana_actor::SignerError;
+    use solana_sdk::signature::{Keypair, Signer};
+    use std::sync::Mutex;
+
+    /// Plays the wallet: reads the displayed request and answers it
+    struct FakeWallet {
+        keypair: Keypair,
+        /// Answer with this request ID instead of the real one
+        wrong_request: bool,
+        answer: Mutex<Vec<String>>,
+        frames_shown: Mutex<usize>,
+    }
+
+    impl FakeWallet {
+        fn new(wrong_request: bool) -> Self {
+            Self {
+                keypair: Keypair::new(),
+                wrong_request,
+                answer: Mutex::new(Vec::new()),
+                frames_shown: Mutex::new(0),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl QrInterface for FakeWallet {
+        async fn display(&self, parts: &[String]) -> Result<()> {
+            *self.frames_shown.lock().unwrap() = parts.len();
+
+            let mut decoder = UrDecoder::new();
+            let ur = parts
+                .iter()
+                .find_map(|part| decoder.receive(part).unwrap())
+                .unwrap();
+            let request = ur.decode_as(SOL_SIGN_REQUEST).unwrap();
+            let mut request_id: [u8; 16] = request
+                .get(1)
+                .unwrap()
+                .untag(37)
+                .as_bytes()
+                .unwrap()
+                .try_into()
+                .unwrap();
+            if self.wrong_request {
+                request_id[0] ^= 1;
+            }
+            let sign_data = request.get(2).and_then(Cbor::as_bytes).unwrap();
+
+            let answer = SolSignature {
+                request_id,
+                signature: self.keypair.sign_message(sign_data),
+            };
+            // Animate the answer in two frames, last one first
+            let mut parts = UrEncoder::new(answer.to_ur())
+                .with_max_fragment_len(60)
+                .parts();
+            parts.reverse();
+            *self.answer.lock().unwrap() = parts;
+            Ok(())
+        }
+
+        async fn scan(&self) -> Result<String> {
+            self.answer
+                .lock()
+                .unwrap()
+                .pop()
+                .ok_or(KeystoneError::Cancelled)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_signs_through_qr() {
+        let wallet = FakeWallet::new(false);
+        let pubkey = wallet.keypair.pubkey();
+        let transport = KeystoneTransport::new(pubkey, 0xdead_beef, wallet)
+            .with_origin("sign-agent")
+            .with_max_fragment_len(100);
+
+        let message = vec![42u8; 500];
+        let SubmitResult::Signed(signature) = transport.submit(&message).await.unwrap() else {
+            panic!("Expected a signature");
+        };
+        assert!(signature.verify(pubkey.as_ref(), &message));
+        assert!(*transport.qr().frames_shown.lock().unwrap() > 1);
+        assert!(!transport.requires_network());
+    }
+
+    #[tokio::test]
+    async fn test_rejects_answer_to_other_request() {
+        let wallet = FakeWallet::new(true);
+        let transport = KeystoneTransport::new(wallet.keypair.pubkey(), 0, wallet);
+
+        assert!(matches!(
+            transport.sign(b"message").await,
+            Err(KeystoneError::RequestMismatch)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_wrong_signer() {
+        let wallet = FakeWallet::new(false);
+        let transport = KeystoneTransport::new(Pubkey::new_unique(), 0, wallet);
+
+        let err = transport.submit(b"message").await.unwrap_err();
+        assert!(matches!(
+            err,
+            TransportError::Signer(SignerError::SigningFailed(_))
+        ));
+    }
+}
@@ -0,0 +1,335 @@
+//! Uniform Resources (BCR-2020-005).
+//!
+//! A UR carries a typed CBOR payload as text suited to QR codes:
+//! `ur:<type>/<bytewords>`. Payloads too large for one QR code are split
+//! into parts, `ur:<type>/<seq>-<count>/<bytewords>`, shown as an animated
+//! QR code.
+//!
+//! [`UrEncoder`] emits the pure fragments in order, to be shown in a loop.
+//! [`UrDecoder`] reassembles pure fragments in any order. Fountain-coded
+//! parts (sequence numbers above the count), which some wallets show after
+//! the first cycle, are skipped; the wallet's loop shows every pure fragment
+//! again.
+
+use std::collections::BTreeMap;
+
+use crate::bytewords;
+use crate::cbor::Cbor;
+use crate::error::{KeystoneError, Result};
+
+/// Default largest fragment per QR code, in bytes.
+pub const DEFAULT_MAX_FRAGMENT_LEN: usize = 200;
+
+/// Smallest fragment the encoder produces, in bytes.
+const MIN_FRAGMENT_LEN: usize = 10;
+
+/// A typed CBOR payload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ur {
+    /// UR type, e.g. `sol-sign-request`.
+    pub ur_type: String,
+    /// CBOR-encoded payload.
+    pub cbor: Vec<u8>,
+}
+
+impl Ur {
+    /// Create a UR.
+    pub fn new(ur_type: impl Into<String>, cbor: Vec<u8>) -> Self {
+        Self {
+            ur_type: ur_type.into(),
+            cbor,
+        }
+    }
+
+    /// Check the type and decode the payload.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`KeystoneError::UnexpectedType`] for another type, or
+    /// [`KeystoneError::InvalidUr`] if the payload isn't valid CBOR.
+    pub fn decode_as(&self, ur_type: &str) -> Result<Cbor> {
+        if self.ur_type != ur_type {
+            return Err(KeystoneError::UnexpectedType {
+                expected: ur_type.to_string(),
+                found: self.ur_type.clone(),
+            });
+        }
+        Cbor::decode(&self.cbor)
+    }
+}
+
+/// Splits a UR into QR code parts.
+#[derive(Debug, Clone)]
+pub struct UrEncoder {
+    ur: Ur,
+    max_fragment_len: usize,
+}
+
+impl UrEncoder {
+    /// Create an encoder with the default fragment size.
+    pub fn new(ur: Ur) -> Self {
+        Self {
+            ur,
+            max_fragment_len: DEFAULT_MAX_FRAGMENT_LEN,
+        }
+    }
+
+    /// Set the largest fragment per QR code, in bytes.
+    ///
+    /// Smaller fragments make less dense codes that scan more easily, at the
+    /// cost of more frames.
+    pub fn with_max_fragment_len(mut self, max_fragment_len: usize) -> Self {
+        self.max_fragment_len = max_fragment_len.max(MIN_FRAGMENT_LEN);
+        self
+    }
+
+    /// The QR code parts.
+    ///
+    /// A payload that fits one fragment is a single-part UR. Otherwise each
+    /// part is a pure fragment, and the parts should be shown in a loop.
+    pub fn parts(&self) -> Vec<String> {
+        let message = &self.ur.cbor;
+        if message.len() <= self.max_fragment_len {
+            return vec![format!(
+                "ur:{}/{}",
+                self.ur.ur_type,
+                bytewords::encode(message)
+            )];
+        }
+
+        let count = message.len().div_ceil(self.max_fragment_len);
+        let fragment_len = message.len().div_ceil(count);
+        let checksum = bytewords::crc32(message);
+
+        (0..count)
+            .map(|index| {
+                let start = (index * fragment_len).min(message.len());
+                let end = (start + fragment_len).min(message.len());
+                let mut fragment = message[start..end].to_vec();
+                fragment.resize(fragment_len, 0);
+
+                let part = Cbor::Array(vec![
+                    Cbor::Unsigned(index as u64 + 1),
+                    Cbor::Unsigned(count as u64),
+                    Cbor::Unsigned(message.len() as u64),
+                    Cbor::Unsigned(u64::from(checksum)),
+                    Cbor::Bytes(fragment),
+                ]);
+                format!(
+                    "ur:{}/{}-{}/{}",
+                    self.ur.ur_type,
+                    index + 1,
+                    count,
+                    bytewords::encode(&part.encode())
+                )
+            })
+            .collect()
+    }
+}
+
+/// Reassembles a UR from scanned QR code parts.
+#[derive(Debug, Default)]
+pub struct UrDecoder {
+    ur_type: Option<String>,
+    /// Count, message length and checksum shared by all parts
+    header: Option<(u64, u64, u32)>,
+    fragments: BTreeMap<u64, Vec<u8>>,
+}
+
+impl UrDecoder {
+    /// Create an empty decoder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a scanned part, returning the UR once every fragment is in.
+    ///
+    /// Duplicate and fountain-coded parts are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the part is malformed or belongs to a different
+    /// UR than the parts before it.
+    pub fn receive(&mut self, part: &str) -> Result<Option<Ur>> {
+        let part = part.trim().to_ascii_lowercase();
+        let body = part
+            .strip_prefix("ur:")
+            .ok_or_else(|| invalid("Missing ur: scheme"))?;
+        let components: Vec<&str> = body.split('/').collect();
+
+        match components[..] {
+            [ur_type, payload] => {
+                check_type(ur_type)?;
+                Ok(Some(Ur::new(ur_type, bytewords::decode(payload)?)))
+            }
+            [ur_type, sequence, payload] => {
+                check_type(ur_type)?;
+                self.receive_fragment(ur_type, sequence, payload)
+            }
+            _ => Err(invalid("Wrong number of UR path components")),
+        }
+    }
+
+    /// Fraction of fragments received, for showing scan progress.
+    pub fn progress(&self) -> f64 {
+        match self.header {
+            Some((count, _, _)) => self.fragments.len() as f64 / count as f64,
+            None => 0.0,
+        }
+    }
+
+    fn receive_fragment(
+        &mut self,
+        ur_type: &str,
+        sequence: &str,
+        payload: &str,
+    ) -> Result<Option<Ur>> {
+        let part = Cbor::decode(&bytewords::decode(payload)?)?;
+        let Cbor::Array(fields) = part else {
+            return Err(invalid("Multi-part payload is not an array"));
+        };
+        let [
+            seq_num,
+            seq_len,
+            message_len,
+            checksum,
+            Cbor::Bytes(fragment),
+        ] = &fields[..]
+        else {
+            return Err(invalid("Malformed multi-part payload"));
+        };
+        let (Some(seq_num), Some(count), Some(message_len), Some(checksum)) = (
+            seq_num.as_unsigned(),
+            seq_len.as_unsigned(),
+            message_len.as_unsigned(),
+            checksum.as_unsigned(),
+        ) else {
+            return Err(invalid("Malformed multi-part payload"));
+        };
+        let checksum = u32::try_from(checksum).map_err(|_| invalid("Bad UR checksum"))?;
+
+        if sequence != format!("{}-{}", seq_num, count) {
+            return Err(invalid("Sequence in path and payload differ"));
+        }
+        if seq_num == 0 || count == 0 || fragment.is_empty() {
+            return Err(invalid("Empty multi-part UR"));
+        }
+        if count.saturating_mul(fragment.len() as u64) < message_len {
+            return Err(invalid("Fragments too short for message"));
+        }
+
+        let header = (count, message_len, checksum);
+        match (&self.ur_type, self.header) {
+            (None, None) => {
+                self.ur_type = Some(ur_type.to_string());
+                self.header = Some(header);
+            }
+            (Some(t), Some(h)) if t == ur_type && h == header => {}
+            _ => return Err(invalid("Part belongs to a different UR")),
+        }
+
+        // Fountain-coded part
+        if seq_num > count {
+            return Ok(None);
+        }
+        if let Some(first) = self.fragments.values().next()
+            && first.len() != fragment.len()
+        {
+            return Err(invalid("Fragment lengths differ"));
+        }
+        self.fragments.insert(seq_num, fragment.clone());
+
+        if self.fragments.len() as u64 != count {
+            return Ok(None);
+        }
+
+        let mut message: Vec<u8> = self.fragments.values().flatten().copied().collect();
+        message.truncate(message_len as usize);
+        if bytewords::crc32(&message) != checksum {
+            return Err(KeystoneError::ChecksumMismatch);
+        }
+        Ok(Some(Ur::new(ur_type, message)))
+    }
+}
+
+fn check_type(ur_type: &str) -> Result<()> {
+    if ur_type.is_empty()
+        || !ur_type
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+    {
+        return Err(invalid("Invalid UR type"));
+    }
+    Ok(())
+}
+
+fn invalid(message: &str) -> KeystoneError {
+    KeystoneError::InvalidUr(message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_part() {
+        let ur = Ur::new("bytes", Cbor::Bytes(vec![7; 20]).encode());
+        let parts = UrEncoder::new(ur.clone()).parts();
+        assert_eq!(parts.len(), 1);
+        assert!(parts[0].starts_with("ur:bytes/"));
+        assert!(!parts[0].contains('-'));
+
+        let mut decoder = UrDecoder::new();
+        assert_eq!(
+            decoder.receive(&parts[0].to_ascii_uppercase()).unwrap(),
+            Some(ur)
+        );
+    }
+
+    #[test]
+    fn test_multi_part_any_order() {
+        let payload: Vec<u8> = (0..=255).cycle().take(1000).collect();
+        let ur = Ur::new("bytes", Cbor::Bytes(payload).encode());
+        let parts = UrEncoder::new(ur.clone())
+            .with_max_fragment_len(100)
+            .parts();
+        assert_eq!(parts.len(), 11);
+        assert!(parts[0].starts_with("ur:bytes/1-11/"));
+
+        // Scanning starts mid-animation and sees some frames twice
+        let mut decoder = UrDecoder::new();
+        let order = (5..11).chain(5..8).chain(0..5);
+        let mut result = None;
+        for index in order {
+            assert!(result.is_none());
+            result = decoder.receive(&parts[index]).unwrap();
+        }
+        assert_eq!(result, Some(ur));
+        assert_eq!(decoder.progress(), 1.0);
+    }
+
+    #[test]
+    fn test_rejects_mixed_urs() {
+        let first = UrEncoder::new(Ur::new("bytes", vec![1; 300]))
+            .with_max_fragment_len(100)
+            .parts();
+        let second = UrEncoder::new(Ur::new("bytes", vec![2; 300]))
+            .with_max_fragment_len(100)
+            .parts();
+
+        let mut decoder = UrDecoder::new();
+        decoder.receive(&first[0]).unwrap();
+        assert!(decoder.receive(&second[1]).is_err());
+        assert!(decoder.receive("https://example.com").is_err());
+    }
+
+    #[test]
+    fn test_decode_as_checks_type() {
+        let ur = Ur::new("sol-signature", Cbor::Unsigned(1).encode());
+        assert!(ur.decode_as("sol-signature").is_ok());
+        assert!(matches!(
+            ur.decode_as("sol-sign-request"),
+            Err(KeystoneError::UnexpectedType { .. })
+        ));
+    }
+}
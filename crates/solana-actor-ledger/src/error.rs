@@ -9,6 +9,14 @@ pub enum LedgerError {
     #[error("Ledger device not connected")]
     NotConnected,
 
+    /// No attached Ledger matches the requested device.
+    #[error("Ledger device not found: {0}")]
+    DeviceNotFound(String),
+
+    /// No attached Ledger holds the expected public key.
+    #[error("No connected Ledger holds {0}")]
+    PubkeyNotFound(String),
+
     /// Communication error with the device.
     #[error("Ledger communication error: {0}")]
     Communication(String),
@@ -25,6 +33,29 @@ pub enum LedgerError {
     #[error("Solana app not opened on Ledger")]
     AppNotOpened,
 
+    /// The device is locked and needs its PIN.
+    #[error("Ledger is locked")]
+    DeviceLocked,
+
+    /// The app rejected the request's parameters or data.
+    #[error("Solana app rejected the request parameters (0x{0:04X})")]
+    InvalidRequest(u16),
+
+    /// Blind signing is disabled and the app can't display the message.
+    #[error(
+        "Blind signing is disabled on the Ledger. Enable it in the Solana app under \
+         Settings > Blind signing, then try again"
+    )]
+    BlindSigningDisabled,
+
+    /// The app refused the request with a status word that doesn't say why.
+    #[error("Solana app does not support this request (0x{0:04X})")]
+    Unsupported(u16),
+
+    /// Message that the device cannot sign.
+    #[error("Invalid message: {0}")]
+    InvalidMessage(String),
+
     /// Invalid derivation path.
     #[error("Invalid derivation path: {0}")]
     InvalidPath(String),
@@ -34,6 +65,24 @@ pub enum LedgerError {
     Hid(String),
 }
 
+impl LedgerError {
+    /// What the user can do on the device to fix the error, if anything.
+    pub fn remediation(&self) -> Option<&'static str> {
+        match self {
+            Self::NotConnected | Self::DeviceNotFound(_) => {
+                Some("Connect and unlock the Ledger, then open the Solana app")
+            }
+            Self::AppNotOpened => Some("Open the Solana app on the Ledger"),
+            Self::DeviceLocked => Some("Unlock the Ledger with its PIN"),
+            Self::BlindSigningDisabled => Some(
+                "In the Solana app, open Settings and enable Blind signing; \
+                 disable it again once done",
+            ),
+            _ => None,
+        }
+    }
+}
+
 /// Result type for Ledger operations.
 pub type Result<T> = std::result::Result<T, LedgerError>;
 
@@ -41,10 +90,17 @@ impl From<LedgerError> for solana_actor::SignerError {
     fn from(err: LedgerError) -> Self {
         match err {
             LedgerError::NotConnected => Self::DeviceNotFound,
+            LedgerError::DeviceNotFound(_) => Self::DeviceNotFound,
+            LedgerError::PubkeyNotFound(_) => Self::DeviceNotFound,
             LedgerError::UserRejected => Self::UserCancelled,
             LedgerError::Communication(msg) => Self::DeviceError(msg),
             LedgerError::InvalidResponse(msg) => Self::DeviceError(msg),
             LedgerError::AppNotOpened => Self::DeviceError("Solana app not opened".into()),
+            LedgerError::DeviceLocked => Self::DeviceError(err.to_string()),
+            LedgerError::InvalidRequest(_) => Self::DeviceError(err.to_string()),
+            LedgerError::BlindSigningDisabled => Self::DeviceError(err.to_string()),
+            LedgerError::Unsupported(_) => Self::DeviceError(err.to_string()),
+            LedgerError::InvalidMessage(msg) => Self::SigningFailed(msg),
             LedgerError::InvalidPath(msg) => Self::InvalidKey(msg),
             LedgerError::Hid(msg) => Self::DeviceError(msg),
         }
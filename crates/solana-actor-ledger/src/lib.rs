@@ -10,6 +10,11 @@
 //! - **User confirmation** - All signing requires physical button press
 //! - **BIP-44 paths** - Standard derivation path support
 //! - **Trait implementations** - Implements `MessageSigner` and `TransactionSigner`
+//! - **Off-chain messages** - `MessageSigner::sign_message` signs in the standard
+//!   off-chain message format, as the Solana app requires
+//! - **Blind signing detection** - Refusals caused by blind signing being off
+//!   surface as [`LedgerError::BlindSigningDisabled`], with
+//!   [`LedgerError::remediation`] telling the user what to change
 //!
 //! # Requirements
 //!
@@ -40,6 +45,19 @@
 //! let signer = LedgerSigner::connect_with_path("44'/501'/1'/0'")?;
 //! ```
 //!
+//! # Multiple Devices
+//!
+//! ```ignore
+//! use solana_actor_ledger::{LedgerSigner, list_devices};
+//!
+//! for device in list_devices()? {
+//!     println!("{} at {}", device.model(), device.path);
+//! }
+//!
+//! // Sign with the device and path holding this key, whichever unit it is
+//! let signer = LedgerSigner::find(&expected_pubkey, &["44'/501'/0'/0'", "44'/501'/1'/0'"])?;
+//! ```
+//!
 //! # Using with Transports
 //!
 //! ```ignore
@@ -61,6 +79,7 @@ mod transport;
 pub use derivation::{DEFAULT_PATH, format_path, parse_path};
 pub use error::{LedgerError, Result};
 pub use signer::LedgerSigner;
+pub use transport::{AppConfig, LedgerDevice, list_devices};
 
 // Re-export traits for convenience
 pub use solana_actor::{MessageSigner, SignerError, TransactionSigner};
@@ -1,11 +1,11 @@
 //! Ledger hardware wallet signer implementation.
 
-use solana_actor::{MessageSigner, SignerError, TransactionSigner};
+use solana_actor::{MessageSigner, OffchainMessage, SignerError, TransactionSigner};
 use solana_sdk::{pubkey::Pubkey, signature::Signature};
 
 use crate::derivation::{DEFAULT_PATH, format_path, parse_path};
-use crate::error::Result;
-use crate::transport;
+use crate::error::{LedgerError, Result};
+use crate::transport::{self, AppConfig, LedgerDevice};
 
 /// Ledger hardware wallet signer.
 ///
@@ -32,7 +32,16 @@ use crate::transport;
 /// // Sign a transaction (user must confirm on device)
 /// let signature = signer.sign_transaction(&tx_message)?;
 /// ```
+///
+/// # Multiple Devices
+///
+/// A signer is bound to the device it connected to, so with several Ledgers
+/// attached it keeps signing with the same unit. Use
+/// [`connect_to`](Self::connect_to) to pick a device from
+/// [`list_devices`](crate::list_devices), or [`find`](Self::find) to pick the
+/// one holding an expected public key.
 pub struct LedgerSigner {
+    device: LedgerDevice,
     derivation_path: Vec<u32>,
     pubkey: Pubkey,
 }
@@ -68,19 +77,153 @@ impl LedgerSigner {
 
     /// Connect with an already-parsed derivation path.
     ///
+    /// Uses the first attached Ledger that answers. With several devices
+    /// attached, prefer [`connect_to`](Self::connect_to) or
+    /// [`find`](Self::find).
+    ///
     /// # Errors
     ///
     /// Returns an error if device communication fails or the device is not available.
     pub fn connect_with_parsed_path(derivation_path: Vec<u32>) -> Result<Self> {
-        let pubkey_bytes = transport::get_pubkey(&derivation_path)?;
+        let mut last_error = LedgerError::NotConnected;
+        for device in transport::list_devices()? {
+            match Self::connect_device(device, derivation_path.clone()) {
+                Ok(signer) => return Ok(signer),
+                Err(e) => last_error = e,
+            }
+        }
+        Err(last_error)
+    }
+
+    /// Connect to a specific Ledger with the default derivation path.
+    ///
+    /// `device_id` is the HID path of a device from
+    /// [`list_devices`](crate::list_devices), or its serial number.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LedgerError::DeviceNotFound`] if no attached Ledger matches
+    /// `device_id`, or an error if device communication fails.
+    pub fn connect_to(device_id: &str) -> Result<Self> {
+        Self::connect_to_with_path(device_id, DEFAULT_PATH)
+    }
+
+    /// Connect to a specific Ledger with a custom derivation path.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LedgerError::DeviceNotFound`] if no attached Ledger matches
+    /// `device_id`, or an error if the path is invalid or device
+    /// communication fails.
+    pub fn connect_to_with_path(device_id: &str, path: &str) -> Result<Self> {
+        let derivation_path = parse_path(path)?;
+        let device = transport::list_devices()?
+            .into_iter()
+            .find(|device| device.matches(device_id))
+            .ok_or_else(|| LedgerError::DeviceNotFound(device_id.to_string()))?;
+        Self::connect_device(device, derivation_path)
+    }
+
+    /// Find the attached Ledger and derivation path holding `pubkey`.
+    ///
+    /// Tries every attached device with each of `paths` in order and returns
+    /// the first signer whose public key matches, so a multi-device setup
+    /// never signs with the wrong unit. Devices that don't answer, such as
+    /// ones with the Solana app closed, are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LedgerError::PubkeyNotFound`] if no device holds `pubkey` at
+    /// any of `paths`, the error of the last device if none answered, or an
+    /// error if a path is invalid.
+    pub fn find(pubkey: &Pubkey, paths: &[&str]) -> Result<Self> {
+        let paths = paths
+            .iter()
+            .map(|path| parse_path(path))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut answered = false;
+        let mut last_error = LedgerError::NotConnected;
+        for device in transport::list_devices()? {
+            for derivation_path in &paths {
+                match transport::get_pubkey(&device.path, derivation_path) {
+                    Ok(bytes) if Pubkey::new_from_array(bytes) == *pubkey => {
+                        return Ok(Self {
+                            device,
+                            derivation_path: derivation_path.clone(),
+                            pubkey: *pubkey,
+                        });
+                    }
+                    Ok(_) => answered = true,
+                    Err(e) => {
+                        last_error = e;
+                        // Later paths won't fare better on a device that failed
+                        break;
+                    }
+                }
+            }
+        }
+
+        if answered {
+            Err(LedgerError::PubkeyNotFound(pubkey.to_string()))
+        } else {
+            Err(last_error)
+        }
+    }
+
+    /// Read the public key at `derivation_path` from `device`.
+    fn connect_device(device: LedgerDevice, derivation_path: Vec<u32>) -> Result<Self> {
+        let pubkey_bytes = transport::get_pubkey(&device.path, &derivation_path)?;
         let pubkey = Pubkey::new_from_array(pubkey_bytes);
 
         Ok(Self {
+            device,
             derivation_path,
             pubkey,
         })
     }
 
+    /// Show this signer's address on the Ledger screen for the user to verify.
+    ///
+    /// Call this before storing or sharing the address, so a compromised host
+    /// can't substitute its own. The device shows the address derived at this
+    /// signer's path and waits for the user to approve or reject it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LedgerError::UserRejected`] if the user rejects the address,
+    /// [`LedgerError::InvalidResponse`] if the device now derives a different
+    /// key, or an error if device communication fails.
+    pub fn confirm_pubkey_on_device(&self) -> Result<()> {
+        let confirmed = transport::confirm_pubkey(&self.device.path, &self.derivation_path)?;
+        if confirmed != self.pubkey.to_bytes() {
+            return Err(LedgerError::InvalidResponse(format!(
+                "Device confirmed {}, expected {}",
+                Pubkey::new_from_array(confirmed),
+                self.pubkey
+            )));
+        }
+        Ok(())
+    }
+
+    /// Get the device this signer is bound to.
+    pub fn device(&self) -> &LedgerDevice {
+        &self.device
+    }
+
+    /// Read the Solana app's settings and version from the device.
+    ///
+    /// Useful to warn before signing when
+    /// [`blind_signing_enabled`](AppConfig::blind_signing_enabled) is off and
+    /// the transaction has instructions the app can't display.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if device communication fails.
+    pub fn app_config(&self) -> Result<AppConfig> {
+        transport::get_app_config(&self.device.path)
+    }
+
     /// Get the derivation path used by this signer.
     pub fn derivation_path(&self) -> String {
         format_path(&self.derivation_path)
@@ -108,9 +251,34 @@ impl LedgerSigner {
     ///
     /// # Errors
     ///
-    /// Returns an error if device communication fails or the user rejects the signing.
+    /// Returns [`LedgerError::BlindSigningDisabled`] if the app can't display
+    /// the transaction and blind signing is off, or an error if device
+    /// communication fails or the user rejects the signing.
     pub fn sign(&self, message: &[u8]) -> Result<[u8; 64]> {
-        transport::sign_message(&self.derivation_path, message)
+        transport::sign_message(&self.device.path, &self.derivation_path, message)
+    }
+
+    /// Sign a serialized off-chain message and return raw signature bytes.
+    ///
+    /// The Solana app shows the message text on the device before signing.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message is not a well-formed off-chain message,
+    /// is too long for the device, or the user rejects the signing. Messages
+    /// the app can't display, such as non-ASCII text, need blind signing and
+    /// fail with [`LedgerError::BlindSigningDisabled`] while it is off.
+    pub fn sign_offchain(&self, message: &[u8]) -> Result<[u8; 64]> {
+        let parsed = OffchainMessage::deserialize(message)
+            .map_err(|e| LedgerError::InvalidMessage(e.to_string()))?;
+        if !parsed.fits_ledger() {
+            return Err(LedgerError::InvalidMessage(format!(
+                "Off-chain message is {} bytes, Ledger signs at most {}",
+                parsed.message().len(),
+                OffchainMessage::MAX_LEN_LEDGER
+            )));
+        }
+        transport::sign_offchain_message(&self.device.path, &self.derivation_path, message)
     }
 }
 
@@ -119,8 +287,21 @@ impl MessageSigner for LedgerSigner {
         self.pubkey
     }
 
+    /// Sign `message` as an off-chain message, the only format the Solana app
+    /// signs outside transactions.
+    ///
+    /// Messages already serialized as an [`OffchainMessage`] are signed as-is;
+    /// anything else is wrapped first, so the signature is over
+    /// [`OffchainMessage::serialize`] rather than the raw bytes.
     fn sign_message(&self, message: &[u8]) -> std::result::Result<Signature, SignerError> {
-        let sig_bytes = self.sign(message).map_err(SignerError::from)?;
+        let sig_bytes = if OffchainMessage::is_offchain_message(message) {
+            self.sign_offchain(message)
+        } else {
+            OffchainMessage::new(message)
+                .map_err(|e| LedgerError::InvalidMessage(e.to_string()))
+                .and_then(|wrapped| self.sign_offchain(&wrapped.serialize()))
+        }
+        .map_err(SignerError::from)?;
         Ok(Signature::from(sig_bytes))
     }
 }
@@ -154,6 +335,31 @@ mod tests {
         println!("Derivation path: {}", signer.derivation_path());
     }
 
+    #[test]
+    #[ignore]
+    fn test_find_by_pubkey() {
+        for device in crate::list_devices().expect("Failed to list devices") {
+            println!("{} {} ({})", device.model(), device.path, device.product_id);
+            let signer = LedgerSigner::connect_to(&device.path).expect("Failed to connect");
+            let found = LedgerSigner::find(&signer.pubkey, &[DEFAULT_PATH, "44'/501'/1'/0'"])
+                .expect("Failed to find pubkey");
+            assert_eq!(found.device().path, device.path);
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn test_confirm_pubkey_on_device() {
+        let signer = LedgerSigner::connect().expect("Failed to connect to Ledger");
+        println!(
+            "Please approve {} on your Ledger...",
+            signer.pubkey_base58()
+        );
+        signer
+            .confirm_pubkey_on_device()
+            .expect("Failed to confirm address");
+    }
+
     #[test]
     #[ignore]
     fn test_sign_message() {
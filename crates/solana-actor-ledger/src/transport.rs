@@ -2,27 +2,156 @@
 //!
 //! This module handles the low-level APDU communication with Ledger devices.
 
+use std::ffi::CString;
+
 use crate::error::{LedgerError, Result};
 
 // Solana app APDU constants
 const SOLANA_CLA: u8 = 0xE0;
+const INS_GET_APP_CONFIG: u8 = 0x04;
 const INS_GET_PUBKEY: u8 = 0x05;
 const INS_SIGN_MESSAGE: u8 = 0x06;
+const INS_SIGN_OFFCHAIN_MESSAGE: u8 = 0x07;
+const P1_NON_CONFIRM: u8 = 0x00;
+const P1_CONFIRM: u8 = 0x01;
 
 // Ledger vendor ID
 const LEDGER_VID: u16 = 0x2c97;
 
+// HID usage page of the Ledger APDU interface
+const LEDGER_USAGE_PAGE: u16 = 0xffa0;
+
+// HID framing
+const PACKET_SIZE: usize = 64;
+const CHANNEL: [u8; 2] = [0x01, 0x01];
+const TAG_APDU: u8 = 0x05;
+const READ_TIMEOUT_MS: i32 = 30000;
+
 // Status words
 const SW_OK: u16 = 0x9000;
 const SW_USER_REJECTED: u16 = 0x6985;
 const SW_APP_NOT_OPEN: u16 = 0x6700;
+const SW_NOT_SUPPORTED: u16 = 0x6808;
+const SW_INVALID_MESSAGE: u16 = 0x6a80;
+const SW_WRONG_DATA: u16 = 0x6a87;
+const SW_INCORRECT_P1_P2: u16 = 0x6a86;
+const SW_WRONG_PARAMS: u16 = 0x6b00;
+const SW_INS_NOT_SUPPORTED: u16 = 0x6d00;
+const SW_CLA_NOT_SUPPORTED: u16 = 0x6e00;
+const SW_WRONG_APP: u16 = 0x6e01;
+const SW_NO_APP: u16 = 0x6511;
+const SW_SECURITY_STATUS: u16 = 0x6982;
+const SW_LOCKED: u16 = 0x5515;
+const SW_LOCKED_LEGACY: u16 = 0x6b0c;
+
+/// Settings and version of the Solana app.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AppConfig {
+    /// Whether the app signs transactions it can't display in full.
+    pub blind_signing_enabled: bool,
+    /// Whether the app shows public keys in long form.
+    pub pubkey_display_long: bool,
+    /// App version as (major, minor, patch).
+    pub version: (u8, u8, u8),
+}
+
+impl AppConfig {
+    /// Parse the response to the app configuration APDU.
+    fn parse(response: &[u8]) -> Result<Self> {
+        match *response {
+            [blind_signing, pubkey_display, major, minor, patch, ..] => Ok(Self {
+                blind_signing_enabled: blind_signing != 0,
+                pubkey_display_long: pubkey_display == 0,
+                version: (major, minor, patch),
+            }),
+            _ => Err(LedgerError::InvalidResponse(
+                "App configuration too short".into(),
+            )),
+        }
+    }
+}
+
+/// A Ledger device attached over USB.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LedgerDevice {
+    /// Platform HID path, which identifies the device while it stays plugged in.
+    pub path: String,
+    /// USB serial number, if reported. Ledger firmware reports the same
+    /// serial on every unit, so it can't tell devices apart.
+    pub serial_number: Option<String>,
+    /// USB product string, such as `Nano S Plus`.
+    pub product: Option<String>,
+    /// USB product ID.
+    pub product_id: u16,
+}
+
+impl LedgerDevice {
+    /// The device model, derived from the USB product ID.
+    pub fn model(&self) -> &'static str {
+        // Current firmware puts the model in the high byte; older firmware
+        // used small product IDs
+        match (self.product_id >> 8, self.product_id) {
+            (_, 0x0001) | (0x10, _) => "Nano S",
+            (_, 0x0004) | (0x40, _) => "Nano X",
+            (_, 0x0005) | (0x50, _) => "Nano S Plus",
+            (_, 0x0006) | (0x60, _) => "Stax",
+            (_, 0x0007) | (0x70, _) => "Flex",
+            _ => "Unknown",
+        }
+    }
+
+    /// Whether `device_id` names this device, by HID path or serial number.
+    pub fn matches(&self, device_id: &str) -> bool {
+        self.path == device_id || self.serial_number.as_deref() == Some(device_id)
+    }
+}
+
+/// List attached Ledger devices.
+///
+/// Each device exposes several HID interfaces; only the one carrying APDUs is
+/// listed, so every entry is a distinct unit.
+pub fn list_devices() -> Result<Vec<LedgerDevice>> {
+    let api = hidapi::HidApi::new().map_err(|e| LedgerError::Hid(e.to_string()))?;
+
+    Ok(api
+        .device_list()
+        .filter(|device| {
+            device.vendor_id() == LEDGER_VID
+                && (device.usage_page() == LEDGER_USAGE_PAGE || device.interface_number() == 0)
+        })
+        .map(|device| LedgerDevice {
+            path: device.path().to_string_lossy().into_owned(),
+            serial_number: device.serial_number().map(str::to_string),
+            product: device.product_string().map(str::to_string),
+            product_id: device.product_id(),
+        })
+        .collect())
+}
+
+/// Get the Solana app configuration from the Ledger device at `device_path`.
+pub fn get_app_config(device_path: &str) -> Result<AppConfig> {
+    let transport = open_device(device_path)?;
+    let response = exchange_apdu(&transport, SOLANA_CLA, INS_GET_APP_CONFIG, 0x00, 0x00, &[])?;
+    AppConfig::parse(&response)
+}
 
-/// Get the public key from the Ledger device.
-pub fn get_pubkey(derivation_path: &[u32]) -> Result<[u8; 32]> {
-    let transport = open_device()?;
+/// Get the public key from the Ledger device at `device_path`.
+pub fn get_pubkey(device_path: &str, derivation_path: &[u32]) -> Result<[u8; 32]> {
+    read_pubkey(device_path, derivation_path, P1_NON_CONFIRM)
+}
+
+/// Show the public key on the Ledger screen and return it once the user
+/// approves.
+pub fn confirm_pubkey(device_path: &str, derivation_path: &[u32]) -> Result<[u8; 32]> {
+    read_pubkey(device_path, derivation_path, P1_CONFIRM)
+}
+
+/// Read the public key, displaying it for confirmation if `p1` asks to.
+fn read_pubkey(device_path: &str, derivation_path: &[u32], p1: u8) -> Result<[u8; 32]> {
+    let transport = open_device(device_path)?;
     let data = serialize_derivation_path(derivation_path);
 
-    let response = exchange_apdu(&transport, SOLANA_CLA, INS_GET_PUBKEY, 0x00, 0x00, &data)?;
+    let response = exchange_apdu(&transport, SOLANA_CLA, INS_GET_PUBKEY, p1, 0x00, &data)?;
 
     if response.len() < 32 {
         return Err(LedgerError::InvalidResponse("Public key too short".into()));
@@ -33,10 +162,59 @@ pub fn get_pubkey(derivation_path: &[u32]) -> Result<[u8; 32]> {
     Ok(pubkey)
 }
 
-/// Sign a message using the Ledger device.
-pub fn sign_message(derivation_path: &[u32], message: &[u8]) -> Result<[u8; 64]> {
-    let transport = open_device()?;
+/// Sign a transaction message using the Ledger device at `device_path`.
+pub fn sign_message(
+    device_path: &str,
+    derivation_path: &[u32],
+    message: &[u8],
+) -> Result<[u8; 64]> {
+    sign(device_path, INS_SIGN_MESSAGE, derivation_path, message)
+}
+
+/// Sign a serialized off-chain message using the Ledger device at `device_path`.
+pub fn sign_offchain_message(
+    device_path: &str,
+    derivation_path: &[u32],
+    message: &[u8],
+) -> Result<[u8; 64]> {
+    sign(
+        device_path,
+        INS_SIGN_OFFCHAIN_MESSAGE,
+        derivation_path,
+        message,
+    )
+}
+
+/// Send `message` in chunks with the given signing instruction.
+///
+/// The app refuses messages it can't display when blind signing is off,
+/// with a status word that doesn't say why; the app configuration tells.
+fn sign(device_path: &str, ins: u8, derivation_path: &[u32], message: &[u8]) -> Result<[u8; 64]> {
+    // Close the device before asking for the configuration
+    let result = {
+        let transport = open_device(device_path)?;
+        send_chunks(&transport, ins, derivation_path, message)
+    };
+
+    match result {
+        Err(LedgerError::Unsupported(sw)) => match get_app_config(device_path) {
+            Ok(config) if !config.blind_signing_enabled => Err(LedgerError::BlindSigningDisabled),
+            _ => Err(LedgerError::InvalidMessage(format!(
+                "Solana app cannot display the message (0x{:04X})",
+                sw
+            ))),
+        },
+        result => result,
+    }
+}
 
+/// Send the signing chunks over an open device and read the signature.
+fn send_chunks(
+    transport: &hidapi::HidDevice,
+    ins: u8,
+    derivation_path: &[u32],
+    message: &[u8],
+) -> Result<[u8; 64]> {
     let mut data = serialize_derivation_path(derivation_path);
     data.extend_from_slice(message);
 
@@ -48,7 +226,7 @@ pub fn sign_message(derivation_path: &[u32], message: &[u8]) -> Result<[u8; 64]>
         let p1 = if i == 0 { 0x00 } else { 0x80 };
         let p2 = if i == chunks.len() - 1 { 0x00 } else { 0x80 };
 
-        let response = exchange_apdu(&transport, SOLANA_CLA, INS_SIGN_MESSAGE, p1, p2, chunk)?;
+        let response = exchange_apdu(transport, SOLANA_CLA, ins, p1, p2, chunk)?;
 
         if i == chunks.len() - 1 {
             signature = Some(response);
@@ -66,19 +244,15 @@ pub fn sign_message(derivation_path: &[u32], message: &[u8]) -> Result<[u8; 64]>
     Ok(sig)
 }
 
-/// Open the Ledger device.
-fn open_device() -> Result<hidapi::HidDevice> {
+/// Open the Ledger device at `device_path`.
+fn open_device(device_path: &str) -> Result<hidapi::HidDevice> {
     let api = hidapi::HidApi::new().map_err(|e| LedgerError::Hid(e.to_string()))?;
+    let path = CString::new(device_path)
+        .map_err(|_| LedgerError::DeviceNotFound(device_path.to_string()))?;
 
-    for device in api.device_list() {
-        if device.vendor_id() == LEDGER_VID
-            && let Ok(dev) = api.open_path(device.path())
-        {
-            return Ok(dev);
-        }
-    }
-
-    Err(LedgerError::NotConnected)
+    // A path that no longer opens means the device was unplugged
+    api.open_path(&path)
+        .map_err(|_| LedgerError::DeviceNotFound(device_path.to_string()))
 }
 
 /// Serialize derivation path for APDU.
@@ -103,51 +277,235 @@ fn exchange_apdu(
     let mut apdu = vec![cla, ins, p1, p2, data.len() as u8];
     apdu.extend_from_slice(data);
 
-    // Wrap in HID frame
-    let mut frame = vec![0x00]; // Report ID
-    frame.push(0x01); // Channel high
-    frame.push(0x01); // Channel low
-    frame.push(0x05); // Tag
-    frame.push(0x00); // Sequence high
-    frame.push(0x00); // Sequence low
-    frame.push((apdu.len() >> 8) as u8);
-    frame.push((apdu.len() & 0xff) as u8);
-    frame.extend_from_slice(&apdu);
-
-    // Pad to 65 bytes
-    frame.resize(65, 0);
-
-    device
-        .write(&frame)
-        .map_err(|e| LedgerError::Communication(e.to_string()))?;
-
-    // Read response
-    let mut response = vec![0u8; 65];
-    device
-        .read_timeout(&mut response, 30000)
-        .map_err(|e| LedgerError::Communication(e.to_string()))?;
-
-    // Parse response (skip HID framing)
-    if response.len() < 9 {
-        return Err(LedgerError::InvalidResponse("Response too short".into()));
-    }
-
-    let data_len = ((response[5] as usize) << 8) | (response[6] as usize);
-    if data_len < 2 {
+    for packet in frame_apdu(&apdu) {
+        device
+            .write(&packet)
+            .map_err(|e| LedgerError::Communication(e.to_string()))?;
+    }
+
+    let response = read_response(|packet| {
+        device
+            .read_timeout(packet, READ_TIMEOUT_MS)
+            .map_err(|e| LedgerError::Communication(e.to_string()))
+    })?;
+    check_status(response)
+}
+
+/// Split an APDU into HID packets, each prefixed with report ID 0.
+///
+/// Every packet starts with the channel, tag and sequence number; the first
+/// also carries the APDU length.
+fn frame_apdu(apdu: &[u8]) -> Vec<[u8; PACKET_SIZE + 1]> {
+    let mut packets = Vec::new();
+    let mut rest = apdu;
+    let mut sequence: u16 = 0;
+
+    while sequence == 0 || !rest.is_empty() {
+        let mut packet = [0u8; PACKET_SIZE + 1];
+        let mut header = vec![CHANNEL[0], CHANNEL[1], TAG_APDU];
+        header.extend_from_slice(&sequence.to_be_bytes());
+        if sequence == 0 {
+            header.extend_from_slice(&(apdu.len() as u16).to_be_bytes());
+        }
+
+        let payload = (PACKET_SIZE - header.len()).min(rest.len());
+        packet[1..=header.len()].copy_from_slice(&header);
+        packet[1 + header.len()..1 + header.len() + payload].copy_from_slice(&rest[..payload]);
+        rest = &rest[payload..];
+
+        packets.push(packet);
+        sequence += 1;
+    }
+
+    packets
+}
+
+/// Reassemble a response from HID packets read with `read`.
+///
+/// The first packet gives the response length; packets continue until it is
+/// reached, and each must carry the next sequence number.
+fn read_response(mut read: impl FnMut(&mut [u8]) -> Result<usize>) -> Result<Vec<u8>> {
+    let mut response = Vec::new();
+    let mut expected_len = None;
+    let mut sequence: u16 = 0;
+
+    while expected_len.is_none_or(|len| response.len() < len) {
+        let mut packet = [0u8; PACKET_SIZE];
+        let read_len = read(&mut packet)?;
+        if read_len == 0 {
+            return Err(LedgerError::Communication(
+                "Timed out waiting for the Ledger".into(),
+            ));
+        }
+        if read_len < 5 || packet[..2] != CHANNEL || packet[2] != TAG_APDU {
+            return Err(LedgerError::InvalidResponse("Malformed HID packet".into()));
+        }
+
+        let packet_sequence = u16::from_be_bytes([packet[3], packet[4]]);
+        if packet_sequence != sequence {
+            return Err(LedgerError::InvalidResponse(format!(
+                "Expected packet {}, got {}",
+                sequence, packet_sequence
+            )));
+        }
+
+        let payload = if sequence == 0 {
+            if read_len < 7 {
+                return Err(LedgerError::InvalidResponse("Response too short".into()));
+            }
+            expected_len = Some(u16::from_be_bytes([packet[5], packet[6]]) as usize);
+            &packet[7..read_len]
+        } else {
+            &packet[5..read_len]
+        };
+
+        let wanted = expected_len.unwrap_or(0) - response.len();
+        response.extend_from_slice(&payload[..payload.len().min(wanted)]);
+        sequence = sequence.wrapping_add(1);
+    }
+
+    Ok(response)
+}
+
+/// Split the status word off a response and map it to an error.
+fn check_status(mut response: Vec<u8>) -> Result<Vec<u8>> {
+    if response.len() < 2 {
         return Err(LedgerError::InvalidResponse("Invalid data length".into()));
     }
 
-    // Check status word
-    let data_end = 7 + data_len - 2;
-    let sw = ((response[data_end] as u16) << 8) | (response[data_end + 1] as u16);
+    let sw_bytes = response.split_off(response.len() - 2);
+    let sw = u16::from_be_bytes([sw_bytes[0], sw_bytes[1]]);
 
     match sw {
-        SW_OK => Ok(response[7..data_end].to_vec()),
+        SW_OK => Ok(response),
         SW_USER_REJECTED => Err(LedgerError::UserRejected),
-        SW_APP_NOT_OPEN => Err(LedgerError::AppNotOpened),
+        SW_APP_NOT_OPEN | SW_CLA_NOT_SUPPORTED | SW_INS_NOT_SUPPORTED | SW_NO_APP
+        | SW_WRONG_APP => Err(LedgerError::AppNotOpened),
+        SW_LOCKED | SW_LOCKED_LEGACY | SW_SECURITY_STATUS => Err(LedgerError::DeviceLocked),
+        SW_WRONG_PARAMS | SW_INCORRECT_P1_P2 | SW_WRONG_DATA => {
+            Err(LedgerError::InvalidRequest(sw))
+        }
+        SW_NOT_SUPPORTED | SW_INVALID_MESSAGE => Err(LedgerError::Unsupported(sw)),
         _ => Err(LedgerError::Communication(format!(
             "Ledger error: 0x{:04X}",
             sw
         ))),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device(product_id: u16) -> LedgerDevice {
+        LedgerDevice {
+            path: "/dev/hidraw3".to_string(),
+            serial_number: Some("0001".to_string()),
+            product: None,
+            product_id,
+        }
+    }
+
+    #[test]
+    fn test_device_model() {
+        assert_eq!(device(0x1011).model(), "Nano S");
+        assert_eq!(device(0x4011).model(), "Nano X");
+        assert_eq!(device(0x5011).model(), "Nano S Plus");
+        assert_eq!(device(0x0005).model(), "Nano S Plus");
+        assert_eq!(device(0x7011).model(), "Flex");
+        assert_eq!(device(0x9999).model(), "Unknown");
+    }
+
+    #[test]
+    fn test_parse_app_config() {
+        let config = AppConfig::parse(&[0x01, 0x00, 1, 3, 1]).unwrap();
+        assert!(config.blind_signing_enabled);
+        assert!(config.pubkey_display_long);
+        assert_eq!(config.version, (1, 3, 1));
+
+        assert!(
+            !AppConfig::parse(&[0x00, 0x01, 1, 4, 0])
+                .unwrap()
+                .blind_signing_enabled
+        );
+        assert!(AppConfig::parse(&[0x01, 0x00]).is_err());
+    }
+
+    /// Strip the report ID from written packets, as the device sees them.
+    fn as_read(packets: &[[u8; PACKET_SIZE + 1]]) -> Vec<[u8; PACKET_SIZE]> {
+        packets
+            .iter()
+            .map(|packet| packet[1..].try_into().unwrap())
+            .collect()
+    }
+
+    fn reader(packets: Vec<[u8; PACKET_SIZE]>) -> impl FnMut(&mut [u8]) -> Result<usize> {
+        let mut packets = packets.into_iter();
+        move |buf| match packets.next() {
+            Some(packet) => {
+                buf.copy_from_slice(&packet);
+                Ok(PACKET_SIZE)
+            }
+            None => Ok(0),
+        }
+    }
+
+    #[test]
+    fn test_frame_roundtrip() {
+        for len in [0, 1, 57, 58, 116, 117, 300] {
+            let apdu: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            let packets = frame_apdu(&apdu);
+            assert_eq!(packets.len(), 1 + len.saturating_sub(57).div_ceil(59));
+            assert!(packets.iter().all(|packet| packet[0] == 0));
+
+            let read = read_response(reader(as_read(&packets))).unwrap();
+            assert_eq!(read, apdu, "length {}", len);
+        }
+    }
+
+    #[test]
+    fn test_read_response_errors() {
+        let mut packets = as_read(&frame_apdu(&[0xaa; 100]));
+
+        // Missing continuation packet
+        assert!(read_response(reader(packets[..1].to_vec())).is_err());
+
+        // Out-of-order sequence number
+        packets[1][4] = 5;
+        assert!(read_response(reader(packets)).is_err());
+    }
+
+    #[test]
+    fn test_check_status() {
+        assert_eq!(check_status(vec![1, 2, 0x90, 0x00]).unwrap(), vec![1, 2]);
+        assert!(matches!(
+            check_status(vec![0x69, 0x85]),
+            Err(LedgerError::UserRejected)
+        ));
+        assert!(matches!(
+            check_status(vec![0x6e, 0x00]),
+            Err(LedgerError::AppNotOpened)
+        ));
+        assert!(matches!(
+            check_status(vec![0x55, 0x15]),
+            Err(LedgerError::DeviceLocked)
+        ));
+        assert!(matches!(
+            check_status(vec![0x6b, 0x00]),
+            Err(LedgerError::InvalidRequest(0x6b00))
+        ));
+        assert!(matches!(
+            check_status(vec![0x6a, 0x80]),
+            Err(LedgerError::Unsupported(0x6a80))
+        ));
+        assert!(check_status(vec![0x90]).is_err());
+    }
+
+    #[test]
+    fn test_device_matches() {
+        let device = device(0x1011);
+        assert!(device.matches("/dev/hidraw3"));
+        assert!(device.matches("0001"));
+        assert!(!device.matches("/dev/hidraw4"));
+    }
+}
@@ -0,0 +1,54 @@
+//! Error types for remote signing.
+
+use thiserror::Error;
+
+/// Errors that can occur when signing through a remote agent.
+#[derive(Error, Debug)]
+pub enum RemoteError {
+    /// A certificate or key couldn't be loaded.
+    #[error("TLS configuration error: {0}")]
+    Tls(String),
+
+    /// The request couldn't be sent or the reply couldn't be read.
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// The agent refused or failed the request.
+    #[error("Agent error ({code}): {message}")]
+    Agent {
+        /// Error code, as sent by the agent.
+        code: String,
+        /// Error message from the agent.
+        message: String,
+    },
+
+    /// The agent's reply wasn't a valid response.
+    #[error("Invalid response: {0}")]
+    InvalidResponse(String),
+
+    /// The returned signature doesn't verify for the signer's public key.
+    #[error("Signature does not verify for the signer")]
+    InvalidSignature,
+
+    /// Reading a certificate or key file failed.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Result type for remote signing operations.
+pub type Result<T> = std::result::Result<T, RemoteError>;
+
+impl From<serde_json::Error> for RemoteError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::InvalidResponse(err.to_string())
+    }
+}
+
+impl From<RemoteError> for solana_actor::SignerError {
+    fn from(err: RemoteError) -> Self {
+        match err {
+            RemoteError::Io(err) => Self::Io(err),
+            other => Self::SigningFailed(other.to_string()),
+        }
+    }
+}
@@ -0,0 +1,32 @@
+//! Remote Solana signer over HTTPS with mutual TLS.
+//!
+//! CI runners and servers often can't hold keys themselves. [`RemoteSigner`]
+//! implements [`AsyncTransactionSigner`] by sending each signing request to a
+//! keyring agent on a central host, started with `--remote-listen`.
+//!
+//! Both sides authenticate: the client presents a certificate the agent's
+//! client CA issued, and only trusts agents whose certificate chains to the
+//! CA given in [`MutualTls`]. The keyring host applies its usual policies and
+//! confirmation before signing, and every returned signature is verified for
+//! the signer's public key.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use solana_actor_remote::{MutualTls, RemoteSigner};
+//! use solana_actor::AsyncTransactionSigner;
+//!
+//! let tls = MutualTls::from_files("client.pem".as_ref(), "client-key.pem".as_ref(), "ca.pem".as_ref())?;
+//! let signer = RemoteSigner::connect("https://keyring.internal:8443", pubkey, tls)?
+//!     .with_token(std::env::var("KEYRING_TOKEN")?);
+//! let signature = signer.sign_transaction(&tx_message).await?;
+//! ```
+
+mod error;
+mod signer;
+
+pub use error::{RemoteError, Result};
+pub use signer::{MutualTls, RemoteSigner};
+
+// Re-export traits for convenience
+pub use solana_actor::AsyncTransactionSigner;
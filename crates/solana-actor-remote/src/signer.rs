@@ -0,0 +1,219 @@
+//! Remote signer client.
+
+use std::path::Path;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD;
+use reqwest::{Certificate, Client, Identity};
+use serde::Deserialize;
+use solana_actor::{AsyncTransactionSigner, SignerError};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+
+use crate::error::{RemoteError, Result};
+
+/// Path the agent serves remote requests on.
+const AGENT_PATH: &str = "/v1/agent";
+
+/// How long to wait for a signature.
+///
+/// Signing requests wait for confirmation on the keyring host, which can
+/// take a while when an approver is prompted.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Client certificate and trusted CA for a mutual TLS connection.
+pub struct MutualTls {
+    identity: Identity,
+    ca: Certificate,
+}
+
+impl MutualTls {
+    /// Create from PEM data.
+    ///
+    /// # Arguments
+    ///
+    /// * `cert_and_key` - PEM client certificate chain followed by its private key.
+    /// * `ca` - PEM CA certificate the server's certificate must chain to.
+    pub fn from_pem(cert_and_key: &[u8], ca: &[u8]) -> Result<Self> {
+        Ok(Self {
+            identity: Identity::from_pem(cert_and_key)
+                .map_err(|e| RemoteError::Tls(format!("Invalid client certificate: {}", e)))?,
+            ca: Certificate::from_pem(ca)
+                .map_err(|e| RemoteError::Tls(format!("Invalid CA certificate: {}", e)))?,
+        })
+    }
+
+    /// Create from PEM files.
+    ///
+    /// # Arguments
+    ///
+    /// * `cert` - Client certificate chain.
+    /// * `key` - Client private key.
+    /// * `ca` - CA certificate the server's certificate must chain to.
+    pub fn from_files(cert: &Path, key: &Path, ca: &Path) -> Result<Self> {
+        let mut cert_and_key = std::fs::read(cert)?;
+        cert_and_key.push(b'\n');
+        cert_and_key.extend(std::fs::read(key)?);
+        Self::from_pem(&cert_and_key, &std::fs::read(ca)?)
+    }
+}
+
+/// Signs through a keyring agent on another host.
+///
+/// Requests go to the agent's remote signing endpoint over HTTPS. The client
+/// authenticates with its certificate, only trusts servers issued by the
+/// configured CA, and checks every returned signature against the signer's
+/// public key.
+pub struct RemoteSigner {
+    client: Client,
+    endpoint: String,
+    pubkey: Pubkey,
+    signer: String,
+    token: Option<String>,
+}
+
+impl std::fmt::Debug for RemoteSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RemoteSigner")
+            .field("endpoint", &self.endpoint)
+            .field("pubkey", &self.pubkey)
+            .field("signer", &self.signer)
+            .field("token", &self.token.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+impl RemoteSigner {
+    /// Connect to an agent.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - Base URL of the agent, e.g. `https://keyring.internal:8443`.
+    /// * `pubkey` - Public key of the signer to use.
+    /// * `tls` - Client certificate and CA.
+    pub fn connect(url: &str, pubkey: Pubkey, tls: MutualTls) -> Result<Self> {
+        let client = Client::builder()
+            .use_rustls_tls()
+            .tls_built_in_root_certs(false)
+            .add_root_certificate(tls.ca)
+            .identity(tls.identity)
+            .https_only(true)
+            .timeout(DEFAULT_TIMEOUT)
+            .build()?;
+
+        Ok(Self {
+            client,
+            endpoint: format!("{}{}", url.trim_end_matches('/'), AGENT_PATH),
+            pubkey,
+            signer: pubkey.to_string(),
+            token: None,
+        })
+    }
+
+    /// Limit requests to a session token's scope.
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Name the signer by label instead of public key.
+    pub fn with_signer(mut self, identifier: impl Into<String>) -> Self {
+        self.signer = identifier.into();
+        self
+    }
+
+    /// Send a request and return its `result`.
+    async fn call(&self, request: serde_json::Value) -> Result<serde_json::Value> {
+        let mut builder = self.client.post(&self.endpoint).json(&request);
+        if let Some(token) = &self.token {
+            builder = builder.bearer_auth(token);
+        }
+        let body = builder.send().await?.bytes().await?;
+        parse_response(&body)
+    }
+}
+
+/// An agent response, as sent on the wire.
+#[derive(Deserialize)]
+#[serde(tag = "status")]
+enum Response {
+    #[serde(rename = "ok")]
+    Ok { result: serde_json::Value },
+    #[serde(rename = "error")]
+    Error { code: String, message: String },
+}
+
+fn parse_response(body: &[u8]) -> Result<serde_json::Value> {
+    match serde_json::from_slice::<Response>(body)? {
+        Response::Ok { result } => Ok(result),
+        Response::Error { code, message } => Err(RemoteError::Agent { code, message }),
+    }
+}
+
+fn decode_signature(result: &serde_json::Value) -> Result<Signature> {
+    let encoded = result
+        .as_str()
+        .ok_or_else(|| RemoteError::InvalidResponse("Expected a signature".to_string()))?;
+    let bytes = STANDARD
+        .decode(encoded)
+        .map_err(|e| RemoteError::InvalidResponse(e.to_string()))?;
+    Signature::try_from(bytes.as_slice())
+        .map_err(|_| RemoteError::InvalidResponse("Invalid signature length".to_string()))
+}
+
+#[async_trait]
+impl AsyncTransactionSigner for RemoteSigner {
+    fn pubkey(&self) -> Pubkey {
+        self.pubkey
+    }
+
+    async fn sign_transaction(
+        &self,
+        message: &[u8],
+    ) -> std::result::Result<Signature, SignerError> {
+        let result = self
+            .call(serde_json::json!({
+                "method": "SignTransaction",
+                "params": {
+                    "transaction": STANDARD.encode(message),
+                    "signer": self.signer,
+                }
+            }))
+            .await?;
+
+        let signature = decode_signature(&result)?;
+        if !signature.verify(self.pubkey.as_ref(), message) {
+            return Err(RemoteError::InvalidSignature.into());
+        }
+        Ok(signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_response() {
+        let signature = Signature::from([7u8; 64]);
+        let ok = format!(
+            r#"{{"status":"ok","result":"{}"}}"#,
+            STANDARD.encode(signature.as_ref())
+        );
+        let result = parse_response(ok.as_bytes()).unwrap();
+        assert_eq!(decode_signature(&result).unwrap(), signature);
+
+        let err = parse_response(
+            br#"{"status":"error","code":"Unauthorized","message":"Token expired"}"#,
+        );
+        assert!(matches!(err, Err(RemoteError::Agent { code, .. }) if code == "Unauthorized"));
+
+        let short = serde_json::json!(STANDARD.encode([1u8; 32]));
+        assert!(matches!(
+            decode_signature(&short),
+            Err(RemoteError::InvalidResponse(_))
+        ));
+    }
+}
@@ -0,0 +1,79 @@
+//! Compute budget settings for the transactions the transport sends.
+//!
+//! During congestion, proposal transactions that pay no priority fee are
+//! often dropped before they land. A [`ComputeBudget`] prepends compute budget
+//! instructions to every create, vote and execute transaction.
+
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+
+/// Compute-unit price paid by the transactions the transport sends.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ComputeUnitPrice {
+    /// No price instruction; the transaction pays only the base fee.
+    #[default]
+    None,
+    /// A fixed price in micro-lamports per compute unit.
+    Fixed(u64),
+    /// The connection's recommended price for the accounts the transaction
+    /// writes, looked up before each send and capped at `max` micro-lamports
+    /// per compute unit.
+    Recommended {
+        /// The highest price to pay.
+        max: u64,
+    },
+}
+
+/// Compute budget instructions added to every transaction the transport
+/// sends.
+///
+/// The default adds none.
+///
+/// # Example
+///
+/// ```ignore
+/// use solana_actor_squads::{ComputeBudget, ComputeUnitPrice};
+///
+/// let budget = ComputeBudget::default()
+///     .with_unit_limit(400_000)
+///     .with_unit_price(ComputeUnitPrice::Recommended { max: 1_000_000 });
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ComputeBudget {
+    /// Compute-unit limit, or `None` for the runtime's default.
+    pub unit_limit: Option<u32>,
+    /// Compute-unit price.
+    pub unit_price: ComputeUnitPrice,
+}
+
+impl ComputeBudget {
+    /// Set the compute-unit limit.
+    ///
+    /// Vault transaction execution runs the inner instructions, so the limit
+    /// must cover them as well as the Squads program.
+    pub fn with_unit_limit(mut self, units: u32) -> Self {
+        self.unit_limit = Some(units);
+        self
+    }
+
+    /// Set the compute-unit price.
+    pub fn with_unit_price(mut self, price: ComputeUnitPrice) -> Self {
+        self.unit_price = price;
+        self
+    }
+
+    /// Whether the budget adds no instructions.
+    pub fn is_empty(&self) -> bool {
+        self.unit_limit.is_none() && self.unit_price == ComputeUnitPrice::None
+    }
+}
+
+/// Accounts written by `instructions`, for fee lookups.
+pub(crate) fn writable_accounts(instructions: &[Instruction]) -> Vec<Pubkey> {
+    let mut accounts: Vec<Pubkey> = Vec::new();
+    for meta in instructions.iter().flat_map(|ix| &ix.accounts) {
+        if meta.is_writable && !accounts.contains(&meta.pubkey) {
+            accounts.push(meta.pubkey);
+        }
+    }
+    accounts
+}
@@ -0,0 +1,353 @@
+//! Multisig creation.
+
+use std::collections::HashSet;
+
+use solana_actor::{AsyncTransactionSigner, Connection, RpcConnection};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
+};
+
+use crate::SQUADS_PROGRAM_ID;
+use crate::error::{Result, SquadsError};
+use crate::instructions::{MultisigCreateArgsV2, multisig_create_v2};
+use crate::pda::{get_multisig_pda, get_program_config_pda, get_vault_pda};
+use crate::rpc::SquadsRpc;
+use crate::state::{Member, Permission, ProgramConfig};
+
+/// Settings for a new multisig.
+///
+/// # Example
+///
+/// ```ignore
+/// use solana_actor_squads::{Member, MultisigConfig, Permission};
+///
+/// let config = MultisigConfig::new(
+///     vec![
+///         Member::new(alice, &Permission::ALL),
+///         Member::new(bob, &[Permission::Vote]),
+///     ],
+///     2,
+/// )
+/// .with_time_lock(3600);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultisigConfig {
+    /// Members and their permissions.
+    pub members: Vec<Member>,
+    /// Approvals needed to execute a proposal.
+    pub threshold: u16,
+    /// Seconds between a proposal's approval and when it can be executed.
+    pub time_lock: u32,
+    /// Authority that can change the config without a vote. `None` makes
+    /// every config change go through a proposal.
+    pub config_authority: Option<Pubkey>,
+    /// Account that receives rent from closed accounts, if any.
+    pub rent_collector: Option<Pubkey>,
+    /// Memo logged with the creation.
+    pub memo: Option<String>,
+}
+
+impl MultisigConfig {
+    /// Settings for a multisig of `members` needing `threshold` approvals,
+    /// with no time lock and no config authority.
+    pub fn new(members: Vec<Member>, threshold: u16) -> Self {
+        Self {
+            members,
+            threshold,
+            time_lock: 0,
+            config_authority: None,
+            rent_collector: None,
+            memo: None,
+        }
+    }
+
+    /// Set the seconds between a proposal's approval and when it can be
+    /// executed.
+    pub fn with_time_lock(mut self, seconds: u32) -> Self {
+        self.time_lock = seconds;
+        self
+    }
+
+    /// Set an authority that can change the config without a vote.
+    pub fn with_config_authority(mut self, authority: Pubkey) -> Self {
+        self.config_authority = Some(authority);
+        self
+    }
+
+    /// Set the account that receives rent from closed accounts.
+    pub fn with_rent_collector(mut self, rent_collector: Pubkey) -> Self {
+        self.rent_collector = Some(rent_collector);
+        self
+    }
+
+    /// Set a memo logged with the creation.
+    pub fn with_memo(mut self, memo: impl Into<String>) -> Self {
+        self.memo = Some(memo.into());
+        self
+    }
+
+    /// Check the settings against the program's rules.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SquadsError::InvalidConfig`] if there are no members, a
+    /// member is listed twice or has unknown permission bits, no member can
+    /// initiate or execute proposals, or the threshold is zero or above the
+    /// number of members that can vote.
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |msg: String| Err(SquadsError::InvalidConfig(msg));
+
+        if self.members.is_empty() {
+            return invalid("no members".into());
+        }
+
+        let mut keys = HashSet::new();
+        let all = Permission::mask(&Permission::ALL);
+        for member in &self.members {
+            if !keys.insert(member.key) {
+                return invalid(format!("duplicate member {}", member.key));
+            }
+            if member.permissions & !all != 0 {
+                return invalid(format!(
+                    "invalid permissions {} for {}",
+                    member.permissions, member.key
+                ));
+            }
+        }
+
+        let count = |permission| {
+            self.members
+                .iter()
+                .filter(|member| member.has(permission))
+                .count()
+        };
+        if count(Permission::Initiate) == 0 {
+            return invalid("no member can initiate proposals".into());
+        }
+        if count(Permission::Execute) == 0 {
+            return invalid("no member can execute proposals".into());
+        }
+
+        let voters = count(Permission::Vote);
+        if self.threshold == 0 || usize::from(self.threshold) > voters {
+            return invalid(format!(
+                "threshold {} must be between 1 and the {} voting members",
+                self.threshold, voters
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// A multisig created by [`create_multisig`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CreatedMultisig {
+    /// The multisig account.
+    pub multisig: Pubkey,
+    /// The key that seeded the multisig PDA.
+    pub create_key: Pubkey,
+    /// The default vault (index 0).
+    pub vault: Pubkey,
+    /// Signature of the creation transaction.
+    pub signature: Signature,
+}
+
+/// Create a multisig, paid for by `creator`.
+///
+/// The creator pays the rent and the program's creation fee; it need not be
+/// a member. A fresh key seeds the multisig address. Any
+/// [`TransactionSigner`](solana_actor::TransactionSigner) that is
+/// `Clone + 'static` can be the creator, signing on a blocking thread.
+///
+/// # Errors
+///
+/// Returns [`SquadsError::InvalidConfig`] if `config` fails
+/// [`MultisigConfig::validate`], or an error if the program config can't be
+/// read, the creator fails to sign or the creation transaction fails.
+pub async fn create_multisig<S: AsyncTransactionSigner + ?Sized>(
+    rpc_url: &str,
+    creator: &S,
+    config: &MultisigConfig,
+) -> Result<CreatedMultisig> {
+    create_multisig_with_connection(RpcConnection::new(rpc_url), creator, config).await
+}
+
+/// Create a multisig through `connection`, paid for by `creator`.
+///
+/// See [`create_multisig`].
+///
+/// # Errors
+///
+/// Returns [`SquadsError::InvalidConfig`] if `config` fails
+/// [`MultisigConfig::validate`], or an error if the program config can't be
+/// read or the creation transaction fails.
+pub async fn create_multisig_with_connection<S: AsyncTransactionSigner + ?Sized, C: Connection>(
+    connection: C,
+    creator: &S,
+    config: &MultisigConfig,
+) -> Result<CreatedMultisig> {
+    create(&SquadsRpc::new(connection), creator, config).await
+}
+
+/// Create a multisig through `rpc`.
+async fn create<S: AsyncTransactionSigner + ?Sized, C: Connection>(
+    rpc: &SquadsRpc<C>,
+    creator: &S,
+    config: &MultisigConfig,
+) -> Result<CreatedMultisig> {
+    config.validate()?;
+
+    let program_id: Pubkey = SQUADS_PROGRAM_ID
+        .parse()
+        .map_err(|_| SquadsError::InvalidAddress("Invalid program ID".into()))?;
+
+    let program_config_pda = get_program_config_pda(&program_id);
+    let program_config = ProgramConfig::from_account_data(
+        &rpc.account_data("Failed to fetch program config", &program_config_pda)
+            .await?,
+    )?;
+
+    let create_key = Keypair::new();
+    let multisig = get_multisig_pda(&create_key.pubkey(), &program_id);
+
+    let args = MultisigCreateArgsV2 {
+        config_authority: config.config_authority,
+        threshold: config.threshold,
+        members: config.members.clone(),
+        time_lock: config.time_lock,
+        rent_collector: config.rent_collector,
+        memo: config.memo.clone(),
+    };
+    let instruction = multisig_create_v2(
+        program_config_pda,
+        program_config.treasury,
+        multisig,
+        create_key.pubkey(),
+        creator.pubkey(),
+        args,
+        program_id,
+    );
+
+    let signature = rpc
+        .send_with_signers(
+            &[instruction],
+            creator,
+            &[&create_key],
+            SquadsError::MultisigCreation,
+        )
+        .await?;
+
+    Ok(CreatedMultisig {
+        multisig,
+        create_key: create_key.pubkey(),
+        vault: get_vault_pda(&multisig, 0, &program_id),
+        signature,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{MockSquadsExt, program_config_data};
+    use solana_actor::{MockConnection, MockSigner, SignerError, TransactionSigner};
+
+    fn voter(permissions: &[Permission]) -> Member {
+        Member::new(Pubkey::new_unique(), permissions)
+    }
+
+    #[test]
+    fn test_validate() {
+        let members = vec![
+            voter(&Permission::ALL),
+            voter(&[Permission::Vote]),
+            voter(&[Permission::Execute]),
+        ];
+        assert!(MultisigConfig::new(members.clone(), 2).validate().is_ok());
+
+        // Only two members can vote
+        assert!(MultisigConfig::new(members.clone(), 3).validate().is_err());
+        assert!(MultisigConfig::new(members.clone(), 0).validate().is_err());
+        assert!(MultisigConfig::new(vec![], 1).validate().is_err());
+
+        let duplicate = vec![members[0], members[0]];
+        assert!(MultisigConfig::new(duplicate, 1).validate().is_err());
+
+        let no_executor = vec![voter(&[Permission::Initiate, Permission::Vote])];
+        assert!(MultisigConfig::new(no_executor, 1).validate().is_err());
+
+        let mut unknown = voter(&Permission::ALL);
+        unknown.permissions = 8 | 7;
+        assert!(MultisigConfig::new(vec![unknown], 1).validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_multisig() {
+        let program_id: Pubkey = SQUADS_PROGRAM_ID.parse().unwrap();
+        let treasury = Pubkey::new_unique();
+        let rpc = SquadsRpc::new(MockConnection::new().with_program_account(
+            get_program_config_pda(&program_id),
+            program_config_data(0, &treasury),
+        ));
+
+        let creator = MockSigner::new();
+        let config = MultisigConfig::new(vec![voter(&Permission::ALL)], 1).with_time_lock(60);
+        let created = create(&rpc, &creator, &config).await.unwrap();
+        assert_eq!(
+            created.multisig,
+            get_multisig_pda(&created.create_key, &program_id)
+        );
+        assert_eq!(
+            created.vault,
+            get_vault_pda(&created.multisig, 0, &program_id)
+        );
+
+        // Signed by the creator and the create key, paying the treasury
+        let sent = rpc.connection().sent_transactions();
+        assert_eq!(sent.len(), 1);
+        let keys = sent[0].message.static_account_keys();
+        assert_eq!(sent[0].signatures.len(), 2);
+        assert_eq!(keys[0], TransactionSigner::pubkey(&creator));
+        assert!(keys.contains(&created.create_key));
+        assert!(keys.contains(&treasury));
+
+        // An invalid config sends nothing
+        let config = MultisigConfig::new(vec![voter(&Permission::ALL)], 2);
+        assert!(matches!(
+            create(&rpc, &creator, &config).await,
+            Err(SquadsError::InvalidConfig(_))
+        ));
+        assert_eq!(rpc.connection().sent_transactions().len(), 1);
+    }
+
+    /// A creator that refuses to sign, like a device the user declined on.
+    #[derive(Clone)]
+    struct Declining(Pubkey);
+
+    impl TransactionSigner for Declining {
+        fn pubkey(&self) -> Pubkey {
+            self.0
+        }
+
+        fn sign_transaction(&self, _message: &[u8]) -> std::result::Result<Signature, SignerError> {
+            Err(SignerError::SigningFailed("declined on device".into()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_multisig_creator_declines() {
+        let program_id: Pubkey = SQUADS_PROGRAM_ID.parse().unwrap();
+        let rpc = SquadsRpc::new(MockConnection::new().with_program_account(
+            get_program_config_pda(&program_id),
+            program_config_data(0, &Pubkey::new_unique()),
+        ));
+
+        let creator = Declining(Pubkey::new_unique());
+        let config = MultisigConfig::new(vec![voter(&Permission::ALL)], 1);
+        let err = create(&rpc, &creator, &config).await.unwrap_err();
+        assert!(matches!(err, SquadsError::MultisigCreation(ref msg) if msg.contains("declined")));
+        assert!(rpc.connection().sent_transactions().is_empty());
+    }
+}
@@ -1,8 +1,11 @@
 //! Error types for Squads operations.
 
+use solana_actor::SubmitResult;
 use solana_sdk::pubkey::Pubkey;
 use thiserror::Error;
 
+use crate::state::Permission;
+
 /// Errors that can occur during Squads operations.
 #[derive(Error, Debug)]
 pub enum SquadsError {
@@ -26,18 +29,59 @@ pub enum SquadsError {
     #[error("Failed to create proposal: {0}")]
     ProposalCreation(String),
 
+    /// Activating a draft proposal failed.
+    #[error("Failed to activate proposal: {0}")]
+    Activation(String),
+
     /// Approval failed.
     #[error("Failed to approve proposal: {0}")]
     Approval(String),
 
+    /// Rejection failed.
+    #[error("Failed to reject proposal: {0}")]
+    Rejection(String),
+
+    /// Cancellation failed.
+    #[error("Failed to cancel proposal: {0}")]
+    Cancellation(String),
+
     /// Execution failed.
     #[error("Failed to execute proposal: {0}")]
     Execution(String),
 
+    /// Invalid multisig configuration, such as a threshold above the number
+    /// of voting members.
+    #[error("Invalid multisig config: {0}")]
+    InvalidConfig(String),
+
+    /// Multisig creation failed.
+    #[error("Failed to create multisig: {0}")]
+    MultisigCreation(String),
+
+    /// The member key lacks permissions the operation needs, found before
+    /// sending a transaction the program would reject.
+    #[error(
+        "{member} lacks the {} on multisig {multisig}",
+        permission_list(.missing)
+    )]
+    InsufficientPermissions {
+        /// The member key.
+        member: Pubkey,
+        /// The multisig.
+        multisig: Pubkey,
+        /// The permissions it lacks.
+        missing: Vec<Permission>,
+    },
+
     /// Proposal not found.
     #[error("Proposal not found: {0}")]
     ProposalNotFound(Pubkey),
 
+    /// A proposal executed, but the node's history no longer reaches the
+    /// transaction that executed it.
+    #[error("Proposal {0} executed, but its execution is not in the node's history")]
+    ExecutionNotFound(Pubkey),
+
     /// Insufficient approvals.
     #[error("Insufficient approvals: {current}/{required}")]
     InsufficientApprovals {
@@ -47,9 +91,63 @@ pub enum SquadsError {
         required: u32,
     },
 
+    /// A proposal was created but approving or executing it failed.
+    ///
+    /// Pass `result` to `retry_approval` to resume without creating a
+    /// duplicate proposal.
+    #[error("Proposal created but not completed: {reason}")]
+    PartialSubmit {
+        /// The created proposal, as a pending result.
+        result: Box<SubmitResult>,
+        /// Why the submission did not complete.
+        reason: String,
+    },
+
     /// Signer error.
     #[error("Signer error: {0}")]
     Signer(#[from] solana_actor::SignerError),
+
+    /// A request that still failed after retrying transient errors.
+    #[error("{source} (after {attempts} attempts)")]
+    Retried {
+        /// Attempts made, including the first.
+        attempts: u32,
+        /// The error from the last attempt.
+        source: Box<SquadsError>,
+    },
+}
+
+impl SquadsError {
+    /// Number of attempts made before failing: more than one if transient
+    /// RPC errors were retried.
+    pub fn attempts(&self) -> u32 {
+        match self {
+            Self::Retried { attempts, .. } => *attempts,
+            _ => 1,
+        }
+    }
+
+    /// Record that the request was made `attempts` times.
+    pub(crate) fn after(self, attempts: u32) -> Self {
+        if attempts > 1 {
+            Self::Retried {
+                attempts,
+                source: Box::new(self),
+            }
+        } else {
+            self
+        }
+    }
+}
+
+/// Name permissions for an error message, e.g. `initiate and vote
+/// permissions`.
+fn permission_list(permissions: &[Permission]) -> String {
+    let names: Vec<String> = permissions.iter().map(ToString::to_string).collect();
+    match names.len() {
+        1 => format!("{} permission", names[0]),
+        _ => format!("{} permissions", names.join(" and ")),
+    }
 }
 
 /// Result type for Squads operations.
@@ -58,18 +156,44 @@ pub type Result<T> = std::result::Result<T, SquadsError>;
 impl From<SquadsError> for solana_actor::TransportError {
     fn from(err: SquadsError) -> Self {
         match err {
+            // Keep the attempt count in the message of the underlying error
+            SquadsError::Retried { attempts, source } => {
+                let retried = |msg: String| format!("{} (after {} attempts)", msg, attempts);
+                match Self::from(*source) {
+                    Self::ProposalFailed(msg) => Self::ProposalFailed(retried(msg)),
+                    Self::ApprovalFailed(msg) => Self::ApprovalFailed(retried(msg)),
+                    Self::ExecutionFailed(msg) => Self::ExecutionFailed(retried(msg)),
+                    Self::Connection(solana_actor::ConnectionError::Rpc(msg)) => {
+                        Self::Connection(solana_actor::ConnectionError::Rpc(retried(msg)))
+                    }
+                    other => other,
+                }
+            }
             SquadsError::MultisigNotFound(pk) => Self::MultisigNotFound(pk),
             SquadsError::InsufficientApprovals { current, required } => {
                 Self::InsufficientApprovals { current, required }
             }
-            SquadsError::ProposalCreation(msg) => Self::ProposalFailed(msg),
+            SquadsError::ProposalCreation(msg) | SquadsError::Activation(msg) => {
+                Self::ProposalFailed(msg)
+            }
             SquadsError::Approval(msg) => Self::ApprovalFailed(msg),
+            SquadsError::Rejection(msg) | SquadsError::Cancellation(msg) => {
+                Self::ApprovalFailed(msg)
+            }
             SquadsError::Execution(msg) => Self::ExecutionFailed(msg),
             SquadsError::Rpc(msg) => Self::Connection(solana_actor::ConnectionError::Rpc(msg)),
+            SquadsError::PartialSubmit { result, reason } => Self::PartialSubmit { result, reason },
             SquadsError::Signer(e) => Self::Signer(e),
             SquadsError::InvalidAddress(msg) => Self::ProposalFailed(msg),
             SquadsError::InvalidAccountData(msg) => Self::ProposalFailed(msg),
+            SquadsError::InvalidConfig(msg) | SquadsError::MultisigCreation(msg) => {
+                Self::ProposalFailed(msg)
+            }
+            SquadsError::InsufficientPermissions { .. } => Self::ProposalFailed(err.to_string()),
             SquadsError::ProposalNotFound(pk) => Self::ProposalFailed(format!("Not found: {}", pk)),
+            SquadsError::ExecutionNotFound(_) => {
+                Self::Connection(solana_actor::ConnectionError::Rpc(err.to_string()))
+            }
         }
     }
 }
@@ -8,6 +8,8 @@ use solana_sdk::{
     pubkey::Pubkey,
 };
 
+use crate::state::{Member, write_optional_pubkey};
+
 /// System program ID.
 const SYSTEM_PROGRAM_ID: Pubkey = solana_sdk::pubkey!("11111111111111111111111111111111");
 
@@ -19,8 +21,41 @@ mod discriminator {
     pub const PROPOSAL_CREATE: [u8; 8] = [132, 116, 68, 174, 216, 160, 198, 22];
     /// proposal_approve
     pub const PROPOSAL_APPROVE: [u8; 8] = [227, 43, 144, 43, 163, 82, 190, 5];
+    /// proposal_reject
+    pub const PROPOSAL_REJECT: [u8; 8] = [243, 62, 134, 156, 230, 106, 246, 135];
+    /// proposal_cancel
+    pub const PROPOSAL_CANCEL: [u8; 8] = [27, 42, 127, 237, 38, 163, 84, 203];
     /// vault_transaction_execute
     pub const VAULT_TRANSACTION_EXECUTE: [u8; 8] = [142, 231, 170, 21, 232, 184, 207, 168];
+    /// proposal_activate
+    pub const PROPOSAL_ACTIVATE: [u8; 8] = [11, 34, 92, 248, 154, 27, 51, 106];
+    /// batch_create
+    pub const BATCH_CREATE: [u8; 8] = [194, 142, 141, 17, 55, 185, 20, 248];
+    /// batch_add_transaction
+    pub const BATCH_ADD_TRANSACTION: [u8; 8] = [89, 100, 224, 18, 69, 70, 54, 76];
+    /// batch_execute_transaction
+    pub const BATCH_EXECUTE_TRANSACTION: [u8; 8] = [172, 44, 179, 152, 21, 127, 234, 180];
+    /// multisig_create_v2
+    pub const MULTISIG_CREATE_V2: [u8; 8] = [50, 221, 199, 93, 40, 245, 139, 233];
+}
+
+/// Arguments for multisig_create_v2 instruction.
+#[derive(BorshSerialize)]
+pub struct MultisigCreateArgsV2 {
+    /// Authority that can change the config without a vote.
+    #[borsh(serialize_with = "write_optional_pubkey")]
+    pub config_authority: Option<Pubkey>,
+    /// Approvals needed to execute a proposal.
+    pub threshold: u16,
+    /// Members and their permissions.
+    pub members: Vec<Member>,
+    /// Seconds between a proposal's approval and when it can be executed.
+    pub time_lock: u32,
+    /// Account that receives rent from closed accounts.
+    #[borsh(serialize_with = "write_optional_pubkey")]
+    pub rent_collector: Option<Pubkey>,
+    /// Optional memo.
+    pub memo: Option<String>,
 }
 
 /// Arguments for vault_transaction_create instruction.
@@ -45,7 +80,26 @@ pub struct ProposalCreateArgs {
     pub draft: bool,
 }
 
-/// Arguments for proposal_approve instruction.
+/// Arguments for batch_create instruction.
+#[derive(BorshSerialize)]
+pub struct BatchCreateArgs {
+    /// Vault index the batch's transactions run as.
+    pub vault_index: u8,
+    /// Optional memo.
+    pub memo: Option<String>,
+}
+
+/// Arguments for batch_add_transaction instruction.
+#[derive(BorshSerialize)]
+pub struct BatchAddTransactionArgs {
+    /// Number of ephemeral signers required.
+    pub ephemeral_signers: u8,
+    /// Serialized transaction message.
+    pub transaction_message: Vec<u8>,
+}
+
+/// Arguments for proposal_approve, proposal_reject and proposal_cancel
+/// instructions.
 #[derive(BorshSerialize)]
 pub struct ProposalVoteArgs {
     /// Optional memo.
@@ -110,7 +164,64 @@ pub fn proposal_approve(
     args: ProposalVoteArgs,
     program_id: Pubkey,
 ) -> Instruction {
-    let mut data = discriminator::PROPOSAL_APPROVE.to_vec();
+    proposal_vote(
+        discriminator::PROPOSAL_APPROVE,
+        multisig,
+        proposal,
+        member,
+        args,
+        program_id,
+    )
+}
+
+/// Build a proposal_reject instruction.
+pub fn proposal_reject(
+    multisig: Pubkey,
+    proposal: Pubkey,
+    member: Pubkey,
+    args: ProposalVoteArgs,
+    program_id: Pubkey,
+) -> Instruction {
+    proposal_vote(
+        discriminator::PROPOSAL_REJECT,
+        multisig,
+        proposal,
+        member,
+        args,
+        program_id,
+    )
+}
+
+/// Build a proposal_cancel instruction.
+///
+/// Cancels an approved proposal that has not been executed yet.
+pub fn proposal_cancel(
+    multisig: Pubkey,
+    proposal: Pubkey,
+    member: Pubkey,
+    args: ProposalVoteArgs,
+    program_id: Pubkey,
+) -> Instruction {
+    proposal_vote(
+        discriminator::PROPOSAL_CANCEL,
+        multisig,
+        proposal,
+        member,
+        args,
+        program_id,
+    )
+}
+
+/// Build an instruction that takes the accounts and arguments of a vote.
+fn proposal_vote(
+    discriminator: [u8; 8],
+    multisig: Pubkey,
+    proposal: Pubkey,
+    member: Pubkey,
+    args: ProposalVoteArgs,
+    program_id: Pubkey,
+) -> Instruction {
+    let mut data = discriminator.to_vec();
     data.extend(borsh::to_vec(&args).unwrap());
 
     Instruction {
@@ -149,3 +260,142 @@ pub fn vault_transaction_execute(
         data,
     }
 }
+
+/// Build a proposal_activate instruction.
+///
+/// Moves a draft proposal, such as a batch's, to active so it can be voted on.
+pub fn proposal_activate(
+    multisig: Pubkey,
+    proposal: Pubkey,
+    member: Pubkey,
+    program_id: Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(multisig, false),
+            AccountMeta::new(member, true),
+            AccountMeta::new(proposal, false),
+        ],
+        data: discriminator::PROPOSAL_ACTIVATE.to_vec(),
+    }
+}
+
+/// Build a batch_create instruction.
+pub fn batch_create(
+    multisig: Pubkey,
+    batch: Pubkey,
+    creator: Pubkey,
+    rent_payer: Pubkey,
+    args: BatchCreateArgs,
+    program_id: Pubkey,
+) -> Instruction {
+    let mut data = discriminator::BATCH_CREATE.to_vec();
+    data.extend(borsh::to_vec(&args).unwrap());
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(multisig, false),
+            AccountMeta::new(batch, false),
+            AccountMeta::new_readonly(creator, true),
+            AccountMeta::new(rent_payer, true),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data,
+    }
+}
+
+/// Build a batch_add_transaction instruction.
+///
+/// The batch's proposal must still be a draft.
+#[allow(clippy::too_many_arguments)]
+pub fn batch_add_transaction(
+    multisig: Pubkey,
+    proposal: Pubkey,
+    batch: Pubkey,
+    transaction: Pubkey,
+    member: Pubkey,
+    rent_payer: Pubkey,
+    args: BatchAddTransactionArgs,
+    program_id: Pubkey,
+) -> Instruction {
+    let mut data = discriminator::BATCH_ADD_TRANSACTION.to_vec();
+    data.extend(borsh::to_vec(&args).unwrap());
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(multisig, false),
+            AccountMeta::new_readonly(proposal, false),
+            AccountMeta::new(batch, false),
+            AccountMeta::new(transaction, false),
+            AccountMeta::new_readonly(member, true),
+            AccountMeta::new(rent_payer, true),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data,
+    }
+}
+
+/// Build a batch_execute_transaction instruction.
+///
+/// Executes the batch's next unexecuted transaction, which must be
+/// `transaction`. `remaining_accounts` are ordered as for
+/// [`vault_transaction_execute`].
+pub fn batch_execute_transaction(
+    multisig: Pubkey,
+    proposal: Pubkey,
+    batch: Pubkey,
+    transaction: Pubkey,
+    member: Pubkey,
+    remaining_accounts: Vec<AccountMeta>,
+    program_id: Pubkey,
+) -> Instruction {
+    let data = discriminator::BATCH_EXECUTE_TRANSACTION.to_vec();
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(multisig, false),
+        AccountMeta::new_readonly(member, true),
+        AccountMeta::new(proposal, false),
+        AccountMeta::new(batch, false),
+        AccountMeta::new_readonly(transaction, false),
+    ];
+    accounts.extend(remaining_accounts);
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Build a multisig_create_v2 instruction.
+///
+/// `create_key` seeds the multisig PDA and must sign; `creator` pays the
+/// rent and the creation fee to `treasury`.
+pub fn multisig_create_v2(
+    program_config: Pubkey,
+    treasury: Pubkey,
+    multisig: Pubkey,
+    create_key: Pubkey,
+    creator: Pubkey,
+    args: MultisigCreateArgsV2,
+    program_id: Pubkey,
+) -> Instruction {
+    let mut data = discriminator::MULTISIG_CREATE_V2.to_vec();
+    data.extend(borsh::to_vec(&args).unwrap());
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(program_config, false),
+            AccountMeta::new(treasury, false),
+            AccountMeta::new(multisig, false),
+            AccountMeta::new_readonly(create_key, true),
+            AccountMeta::new(creator, true),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data,
+    }
+}
@@ -52,9 +52,116 @@
 //! }
 //! ```
 //!
+//! # Versioned Transactions
+//!
+//! [`WalletTransport::submit_versioned`] converts a legacy or v0 message into a
+//! [`VaultTransactionMessage`], keeping its address table lookups. When the
+//! proposal is executed, the lookup tables are fetched and the loaded accounts
+//! are passed to `vault_transaction_execute`.
+//!
+//! # Ephemeral Signers
+//!
+//! Inner transactions that need fresh signers (e.g. creating an account with
+//! a new keypair) can use any placeholder pubkey for them. Every signer other
+//! than the vault is replaced with an ephemeral signer PDA derived from the
+//! vault transaction (see [`get_ephemeral_signer_pda`]), which the program
+//! signs for at execution.
+//!
+//! # Nested Multisigs
+//!
+//! When a multisig member is another multisig's vault, or the member's key is
+//! behind any other [`WalletTransport`] such as a remote signing service,
+//! pass the member's transport (for a vault, the child multisig's
+//! [`SquadsTransport`]) to [`SquadsTransport::with_member_transport`]: each
+//! action the member takes is one transaction submitted through that
+//! transport, and a member that defers it is tracked in
+//! [`SubmitResult::Nested`].
+//!
+//! # Retries
+//!
+//! Every RPC read and send is retried on transient failures (rate limits,
+//! lagging nodes, network errors and expired blockhashes) with jittered
+//! exponential backoff, per [`SquadsTransport::with_retry_policy`].
+//! [`RpcErrorKind`] is the classification used; errors that persist report
+//! their attempt count through [`SquadsError::attempts`].
+//!
+//! # Memos
+//!
+//! [`SquadsTransport::submit_versioned_with`],
+//! [`SquadsTransport::submit_batch_with`] and the `_with` variants of
+//! rejecting and cancelling take [`SubmitOptions`], whose memo is recorded
+//! with the proposal and the member's vote for other members to see in the
+//! Squads UI.
+//!
+//! # Drafts
+//!
+//! With [`SubmitOptions::draft`], the proposal is created as a draft that
+//! members can review but not vote on, and the member doesn't approve it.
+//! [`SquadsTransport::activate_proposal`] opens it for votes.
+//!
+//! # Compute Budget
+//!
+//! Transactions the transport sends carry no compute budget instructions by
+//! default. [`SquadsTransport::with_compute_budget`] adds a compute-unit
+//! limit and a fixed or recommended [`ComputeUnitPrice`] to every create,
+//! vote and execute transaction, so they land during congestion.
+//!
+//! # Permissions
+//!
+//! Before creating a proposal, the transport reads the multisig's members and
+//! fails with [`SquadsError::InsufficientPermissions`] if the member key can't
+//! initiate and vote, rather than paying for a transaction the program
+//! rejects.
+//!
+//! # Partial Submissions
+//!
+//! If the proposal is created but approving or executing it fails, `submit`
+//! returns [`TransportError::PartialSubmit`] carrying the pending result
+//! instead of losing the transaction index. Pass it to
+//! [`SquadsTransport::retry_approval`] to resume without creating a duplicate
+//! proposal.
+//!
+//! # Execution
+//!
+//! `submit` executes a proposal as soon as it reaches threshold. Use
+//! [`SquadsTransport::with_auto_execute`] to leave it pending for review, and
+//! [`SquadsTransport::wait_for_completion_with`] to execute it once ready.
+//!
+//! # Batches
+//!
+//! [`SquadsTransport::submit_batch`] proposes many messages under one
+//! proposal, so a payout run of dozens of transfers takes a single vote.
+//! Executing the proposal runs the batch's transactions in order.
+//!
+//! # Rejecting and Cancelling
+//!
+//! [`SquadsTransport::reject_proposal`] votes against an active proposal and
+//! [`SquadsTransport::cancel_proposal`] withdraws an approved one before it is
+//! executed.
+//!
+//! # Creating a Multisig
+//!
+//! [`create_multisig`] creates a multisig from a [`MultisigConfig`] of
+//! members, permissions, threshold and time lock, with any
+//! [`TransactionSigner`] paying the rent and creation fee.
+//!
+//! # Account State
+//!
+//! The [`state`] module deserializes multisig, proposal, vault transaction
+//! and batch accounts; [`SquadsTransport::multisig_account`] and
+//! [`SquadsTransport::proposal`] fetch them.
+//!
+//! # Connections
+//!
+//! Reads and sends go through an async [`Connection`], an [`RpcConnection`]
+//! by default. [`SquadsTransport::with_connection`] takes any other, such as
+//! a rate-limited connection shared with other transports or a
+//! `MockConnection` for testing proposal logic.
+//!
 //! # Architecture
 //!
-//! The transport wraps any [`TransactionSigner`] as the member signer:
+//! The member acts through a [`WalletTransport`]. Any [`TransactionSigner`]
+//! is wrapped in a `DirectTransport`:
 //!
 //! ```ignore
 //! // With a keypair
@@ -64,17 +171,37 @@
 //! let transport = SquadsTransport::new(multisig, 0, url, LedgerSigner::connect()?)?;
 //! ```
 
+mod compute_budget;
+mod create;
 mod error;
 mod instructions;
+mod message;
 mod pda;
+mod rpc;
+pub mod state;
+#[cfg(test)]
+mod test_utils;
 mod transport;
 
+pub use compute_budget::{ComputeBudget, ComputeUnitPrice};
+pub use create::{
+    CreatedMultisig, MultisigConfig, create_multisig, create_multisig_with_connection,
+};
 pub use error::{Result, SquadsError};
-pub use pda::{get_proposal_pda, get_transaction_pda, get_vault_pda};
-pub use transport::SquadsTransport;
+pub use message::{VaultInstruction, VaultTransactionMessage};
+pub use pda::{
+    get_batch_transaction_pda, get_ephemeral_signer_pda, get_multisig_pda, get_program_config_pda,
+    get_proposal_pda, get_transaction_pda, get_vault_pda,
+};
+pub use rpc::RpcErrorKind;
+pub use state::{Member, Permission};
+pub use transport::{SquadsTransport, SubmitOptions};
 
 // Re-export traits for convenience
-pub use solana_actor::{SubmitResult, TransactionSigner, TransportError, WalletTransport};
+pub use solana_actor::{
+    Connection, RetryPolicy, RpcConnection, SubmitResult, TransactionSigner, TransportError,
+    WalletTransport,
+};
 
 /// Squads V4 program ID (mainnet).
 pub const SQUADS_PROGRAM_ID: &str = "SQDS4nPHovALA9Sm5LCgJqkKhkYshJwKhN9kD3h8Zzg";
@@ -0,0 +1,503 @@
+//! Squads v4 vault transaction messages.
+//!
+//! Squads stores the inner transaction of a vault transaction in its own
+//! `TransactionMessage` format rather than as a serialized Solana message.
+//! The instruction argument uses compact length prefixes (`u8`, or `u16` for
+//! instruction data), while the stored account uses Borsh `Vec`s with `u32`
+//! prefixes. This module converts [`VersionedMessage`]s into that format;
+//! [`crate::state`] parses it back out of vault transaction accounts.
+
+use borsh::BorshDeserialize;
+use solana_actor::LoadedAddresses;
+use solana_sdk::{
+    instruction::AccountMeta,
+    message::{VersionedMessage, v0::MessageAddressTableLookup},
+    pubkey::Pubkey,
+};
+
+use crate::error::{Result, SquadsError};
+use crate::pda::get_ephemeral_signer_pda;
+use crate::state::{read_lookups, read_pubkeys};
+
+/// A compiled instruction inside a vault transaction message.
+#[derive(Debug, Clone, PartialEq, Eq, BorshDeserialize)]
+pub struct VaultInstruction {
+    /// Index of the program ID in the message account keys.
+    pub program_id_index: u8,
+    /// Indexes of the instruction accounts in the message account keys.
+    pub account_indexes: Vec<u8>,
+    /// Instruction data.
+    pub data: Vec<u8>,
+}
+
+/// The inner transaction message of a Squads vault transaction.
+///
+/// Its Borsh encoding is the stored account format; see
+/// [`VaultTransaction`](crate::state::VaultTransaction).
+#[derive(Debug, Clone, PartialEq, Eq, BorshDeserialize)]
+pub struct VaultTransactionMessage {
+    /// Number of signer accounts.
+    pub num_signers: u8,
+    /// Number of writable signer accounts.
+    pub num_writable_signers: u8,
+    /// Number of writable non-signer accounts.
+    pub num_writable_non_signers: u8,
+    /// Static account keys.
+    #[borsh(deserialize_with = "read_pubkeys")]
+    pub account_keys: Vec<Pubkey>,
+    /// Compiled instructions.
+    pub instructions: Vec<VaultInstruction>,
+    /// Address lookup tables the message loads accounts from.
+    #[borsh(deserialize_with = "read_lookups")]
+    pub address_table_lookups: Vec<MessageAddressTableLookup>,
+}
+
+impl VaultTransactionMessage {
+    /// Convert a legacy or v0 message into the Squads format.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SquadsError::InvalidAccountData`] if the message has a header
+    /// inconsistent with its account keys.
+    pub fn from_versioned(message: &VersionedMessage) -> Result<Self> {
+        let header = message.header();
+        let account_keys = message.static_account_keys().to_vec();
+
+        let num_signers = header.num_required_signatures;
+        let num_writable_signers = num_signers
+            .checked_sub(header.num_readonly_signed_accounts)
+            .ok_or_else(|| SquadsError::InvalidAccountData("Invalid message header".into()))?;
+        let num_writable_non_signers = account_keys
+            .len()
+            .checked_sub(num_signers as usize + header.num_readonly_unsigned_accounts as usize)
+            .and_then(|n| u8::try_from(n).ok())
+            .ok_or_else(|| SquadsError::InvalidAccountData("Invalid message header".into()))?;
+
+        let instructions = message
+            .instructions()
+            .iter()
+            .map(|ix| VaultInstruction {
+                program_id_index: ix.program_id_index,
+                account_indexes: ix.accounts.clone(),
+                data: ix.data.clone(),
+            })
+            .collect();
+
+        Ok(Self {
+            num_signers,
+            num_writable_signers,
+            num_writable_non_signers,
+            account_keys,
+            instructions,
+            address_table_lookups: message
+                .address_table_lookups()
+                .map(<[_]>::to_vec)
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Serialize as the `transaction_message` argument of `vault_transaction_create`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SquadsError::InvalidAccountData`] if a list is too long for
+    /// its compact length prefix.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        fn len_u8(len: usize) -> Result<u8> {
+            u8::try_from(len)
+                .map_err(|_| SquadsError::InvalidAccountData("Message list too long".into()))
+        }
+
+        let mut out = vec![
+            self.num_signers,
+            self.num_writable_signers,
+            self.num_writable_non_signers,
+        ];
+
+        out.push(len_u8(self.account_keys.len())?);
+        for key in &self.account_keys {
+            out.extend_from_slice(key.as_ref());
+        }
+
+        out.push(len_u8(self.instructions.len())?);
+        for ix in &self.instructions {
+            out.push(ix.program_id_index);
+            out.push(len_u8(ix.account_indexes.len())?);
+            out.extend_from_slice(&ix.account_indexes);
+            let data_len = u16::try_from(ix.data.len())
+                .map_err(|_| SquadsError::InvalidAccountData("Instruction data too long".into()))?;
+            out.extend_from_slice(&data_len.to_le_bytes());
+            out.extend_from_slice(&ix.data);
+        }
+
+        out.push(len_u8(self.address_table_lookups.len())?);
+        for lookup in &self.address_table_lookups {
+            out.extend_from_slice(lookup.account_key.as_ref());
+            out.push(len_u8(lookup.writable_indexes.len())?);
+            out.extend_from_slice(&lookup.writable_indexes);
+            out.push(len_u8(lookup.readonly_indexes.len())?);
+            out.extend_from_slice(&lookup.readonly_indexes);
+        }
+
+        Ok(out)
+    }
+
+    /// Parse the `transaction_message` argument of `vault_transaction_create`.
+    ///
+    /// This is the inverse of [`to_bytes`](Self::to_bytes).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SquadsError::InvalidAccountData`] if the bytes are truncated
+    /// or have trailing data.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut reader = Reader {
+            data: bytes,
+            offset: 0,
+        };
+
+        let num_signers = reader.u8()?;
+        let num_writable_signers = reader.u8()?;
+        let num_writable_non_signers = reader.u8()?;
+
+        let num_keys = reader.u8()?;
+        let mut account_keys = Vec::with_capacity(num_keys as usize);
+        for _ in 0..num_keys {
+            account_keys.push(reader.pubkey()?);
+        }
+
+        let num_instructions = reader.u8()?;
+        let mut instructions = Vec::with_capacity(num_instructions as usize);
+        for _ in 0..num_instructions {
+            let program_id_index = reader.u8()?;
+            let num_accounts = reader.u8()? as usize;
+            let account_indexes = reader.take(num_accounts)?.to_vec();
+            let data_len = reader.u16()? as usize;
+            let data = reader.take(data_len)?.to_vec();
+            instructions.push(VaultInstruction {
+                program_id_index,
+                account_indexes,
+                data,
+            });
+        }
+
+        let num_lookups = reader.u8()?;
+        let mut address_table_lookups = Vec::with_capacity(num_lookups as usize);
+        for _ in 0..num_lookups {
+            let account_key = reader.pubkey()?;
+            let num_writable = reader.u8()? as usize;
+            let writable_indexes = reader.take(num_writable)?.to_vec();
+            let num_readonly = reader.u8()? as usize;
+            let readonly_indexes = reader.take(num_readonly)?.to_vec();
+            address_table_lookups.push(MessageAddressTableLookup {
+                account_key,
+                writable_indexes,
+                readonly_indexes,
+            });
+        }
+
+        if reader.offset != bytes.len() {
+            return Err(SquadsError::InvalidAccountData(
+                "Trailing bytes after transaction message".into(),
+            ));
+        }
+
+        Ok(Self {
+            num_signers,
+            num_writable_signers,
+            num_writable_non_signers,
+            account_keys,
+            instructions,
+            address_table_lookups,
+        })
+    }
+
+    /// Replace every signer other than the vault with an ephemeral signer PDA.
+    ///
+    /// Signers are assigned ephemeral signer indexes in the order they appear
+    /// in the account keys. Returns the number of ephemeral signers, which is
+    /// the `ephemeral_signers` argument of `vault_transaction_create`.
+    pub fn assign_ephemeral_signers(
+        &mut self,
+        vault: &Pubkey,
+        transaction_pda: &Pubkey,
+        program_id: &Pubkey,
+    ) -> u8 {
+        let mut count = 0;
+        for key in self.account_keys.iter_mut().take(self.num_signers as usize) {
+            if key != vault {
+                *key = get_ephemeral_signer_pda(transaction_pda, count, program_id);
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Whether the static account at `index` is writable.
+    pub fn is_static_writable_index(&self, index: usize) -> bool {
+        let num_signers = self.num_signers as usize;
+        if index < num_signers {
+            index < self.num_writable_signers as usize
+        } else {
+            index < num_signers + self.num_writable_non_signers as usize
+        }
+    }
+
+    /// The remaining accounts `vault_transaction_execute` expects.
+    ///
+    /// Squads expects the lookup table accounts first, then the static account
+    /// keys, then the writable and readonly accounts loaded from the tables.
+    /// None of them sign the outer transaction; the vault signs via CPI.
+    pub fn execute_accounts(&self, loaded: &LoadedAddresses) -> Vec<AccountMeta> {
+        let lookup_tables = self
+            .address_table_lookups
+            .iter()
+            .map(|lookup| AccountMeta::new_readonly(lookup.account_key, false));
+
+        let static_keys = self.account_keys.iter().enumerate().map(|(i, key)| {
+            if self.is_static_writable_index(i) {
+                AccountMeta::new(*key, false)
+            } else {
+                AccountMeta::new_readonly(*key, false)
+            }
+        });
+
+        lookup_tables
+            .chain(static_keys)
+            .chain(
+                loaded
+                    .writable
+                    .iter()
+                    .map(|key| AccountMeta::new(*key, false)),
+            )
+            .chain(
+                loaded
+                    .readonly
+                    .iter()
+                    .map(|key| AccountMeta::new_readonly(*key, false)),
+            )
+            .collect()
+    }
+}
+
+/// Cursor over a compactly encoded transaction message.
+struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self
+            .offset
+            .checked_add(len)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| {
+                SquadsError::InvalidAccountData("Transaction message too small".into())
+            })?;
+        let slice = &self.data[self.offset..end];
+        self.offset = end;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_le_bytes(bytes.try_into().expect("2 bytes")))
+    }
+
+    fn pubkey(&mut self) -> Result<Pubkey> {
+        let bytes = self.take(32)?;
+        Ok(Pubkey::new_from_array(bytes.try_into().expect("32 bytes")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{VaultBatchTransaction, VaultTransaction};
+    use solana_sdk::{
+        hash::Hash,
+        instruction::Instruction,
+        message::{AddressLookupTableAccount, v0},
+    };
+
+    /// Encode a message the way Squads stores it in the vault transaction account.
+    fn account_data(message: &VaultTransactionMessage) -> Vec<u8> {
+        fn vec_u8(out: &mut Vec<u8>, bytes: &[u8]) {
+            out.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+            out.extend_from_slice(bytes);
+        }
+
+        let mut out = vec![0u8; 8 + 32 + 32 + 8 + 1 + 1 + 1];
+        vec_u8(&mut out, &[]);
+        out.extend_from_slice(&[
+            message.num_signers,
+            message.num_writable_signers,
+            message.num_writable_non_signers,
+        ]);
+        out.extend_from_slice(&(message.account_keys.len() as u32).to_le_bytes());
+        for key in &message.account_keys {
+            out.extend_from_slice(key.as_ref());
+        }
+        out.extend_from_slice(&(message.instructions.len() as u32).to_le_bytes());
+        for ix in &message.instructions {
+            out.push(ix.program_id_index);
+            vec_u8(&mut out, &ix.account_indexes);
+            vec_u8(&mut out, &ix.data);
+        }
+        out.extend_from_slice(&(message.address_table_lookups.len() as u32).to_le_bytes());
+        for lookup in &message.address_table_lookups {
+            out.extend_from_slice(lookup.account_key.as_ref());
+            vec_u8(&mut out, &lookup.writable_indexes);
+            vec_u8(&mut out, &lookup.readonly_indexes);
+        }
+        out
+    }
+
+    fn v0_message() -> (Pubkey, AddressLookupTableAccount, VersionedMessage) {
+        let vault = Pubkey::new_unique();
+        let table = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+        };
+        let ix = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[1, 2, 3],
+            vec![
+                AccountMeta::new(vault, true),
+                AccountMeta::new(table.addresses[1], false),
+                AccountMeta::new_readonly(table.addresses[0], false),
+            ],
+        );
+        let message = VersionedMessage::V0(
+            v0::Message::try_compile(&vault, &[ix], std::slice::from_ref(&table), Hash::default())
+                .unwrap(),
+        );
+        (vault, table, message)
+    }
+
+    #[test]
+    fn test_from_versioned_v0() {
+        let (vault, table, message) = v0_message();
+        let vault_message = VaultTransactionMessage::from_versioned(&message).unwrap();
+
+        assert_eq!(vault_message.num_signers, 1);
+        assert_eq!(vault_message.num_writable_signers, 1);
+        assert_eq!(vault_message.num_writable_non_signers, 0);
+        assert_eq!(vault_message.account_keys[0], vault);
+        assert_eq!(vault_message.instructions[0].data, vec![1, 2, 3]);
+        assert_eq!(vault_message.address_table_lookups.len(), 1);
+        assert_eq!(
+            vault_message.address_table_lookups[0].account_key,
+            table.key
+        );
+
+        let bytes = vault_message.to_bytes().unwrap();
+        assert_eq!(&bytes[..4], &[1, 1, 0, 2]);
+    }
+
+    #[test]
+    fn test_bytes_roundtrip() {
+        let (_, _, message) = v0_message();
+        let vault_message = VaultTransactionMessage::from_versioned(&message).unwrap();
+
+        let bytes = vault_message.to_bytes().unwrap();
+        assert_eq!(
+            VaultTransactionMessage::from_bytes(&bytes).unwrap(),
+            vault_message
+        );
+        assert!(VaultTransactionMessage::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(VaultTransactionMessage::from_bytes(&[bytes.as_slice(), &[0]].concat()).is_err());
+    }
+
+    #[test]
+    fn test_assign_ephemeral_signers() {
+        let program_id: Pubkey = crate::SQUADS_PROGRAM_ID.parse().unwrap();
+        let vault = Pubkey::new_unique();
+        let new_account = Pubkey::new_unique();
+        let ix = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[0],
+            vec![
+                AccountMeta::new(vault, true),
+                AccountMeta::new(new_account, true),
+            ],
+        );
+        let message =
+            VersionedMessage::Legacy(solana_sdk::message::Message::new(&[ix], Some(&vault)));
+        let mut vault_message = VaultTransactionMessage::from_versioned(&message).unwrap();
+
+        let transaction_pda = Pubkey::new_unique();
+        let count = vault_message.assign_ephemeral_signers(&vault, &transaction_pda, &program_id);
+
+        assert_eq!(count, 1);
+        assert_eq!(vault_message.account_keys[0], vault);
+        assert_eq!(
+            vault_message.account_keys[1],
+            get_ephemeral_signer_pda(&transaction_pda, 0, &program_id)
+        );
+        assert!(!vault_message.account_keys.contains(&new_account));
+
+        const FIXED_SIZE: usize = 8 + 32 + 32 + 8 + 1 + 1 + 1;
+        let mut data = account_data(&vault_message);
+        let account = VaultTransaction::from_account_data(&data).unwrap();
+        assert!(account.ephemeral_signer_bumps.is_empty());
+        data[FIXED_SIZE] = 1;
+        data.insert(FIXED_SIZE + 4, 255);
+        let account = VaultTransaction::from_account_data(&data).unwrap();
+        assert_eq!(account.ephemeral_signer_bumps, [255]);
+        assert_eq!(account.message, vault_message);
+    }
+
+    #[test]
+    fn test_parse_account_roundtrip() {
+        let (_, _, message) = v0_message();
+        let vault_message = VaultTransactionMessage::from_versioned(&message).unwrap();
+
+        let parsed = VaultTransaction::from_account_data(&account_data(&vault_message)).unwrap();
+        assert_eq!(parsed.message, vault_message);
+
+        let data = account_data(&vault_message);
+        assert!(VaultTransaction::from_account_data(&data[..data.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_parse_batch_account() {
+        let (_, _, message) = v0_message();
+        let vault_message = VaultTransactionMessage::from_versioned(&message).unwrap();
+
+        // A batch transaction holds only the bump before the ephemeral signers
+        let mut data = account_data(&vault_message);
+        data.drain(8..8 + 32 + 32 + 8 + 1 + 1);
+        let parsed = VaultBatchTransaction::from_account_data(&data).unwrap();
+        assert_eq!(parsed.message, vault_message);
+        assert!(parsed.ephemeral_signer_bumps.is_empty());
+    }
+
+    #[test]
+    fn test_execute_accounts_order() {
+        let (vault, table, message) = v0_message();
+        let vault_message = VaultTransactionMessage::from_versioned(&message).unwrap();
+        let loaded = solana_actor::resolve_loaded_addresses(
+            &vault_message.address_table_lookups,
+            std::slice::from_ref(&table),
+        )
+        .unwrap();
+
+        let accounts = vault_message.execute_accounts(&loaded);
+        let keys: Vec<Pubkey> = accounts.iter().map(|a| a.pubkey).collect();
+        assert_eq!(keys[0], table.key);
+        assert_eq!(keys[1], vault);
+        assert_eq!(
+            &keys[keys.len() - 2..],
+            &[table.addresses[1], table.addresses[0]]
+        );
+        assert!(accounts.iter().all(|a| !a.is_signer));
+        assert!(!accounts[0].is_writable);
+        assert!(accounts[1].is_writable);
+    }
+}
@@ -10,6 +10,33 @@ pub const SEED_AUTHORITY: &[u8] = b"authority";
 pub const SEED_TRANSACTION: &[u8] = b"transaction";
 /// Seed prefix for proposal PDAs.
 pub const SEED_PROPOSAL: &[u8] = b"proposal";
+/// Seed prefix for ephemeral signer PDAs.
+pub const SEED_PREFIX: &[u8] = b"multisig";
+/// Seed for ephemeral signer PDAs.
+pub const SEED_EPHEMERAL_SIGNER: &[u8] = b"ephemeral_signer";
+/// Seed for batch transaction PDAs.
+pub const SEED_BATCH_TRANSACTION: &[u8] = b"batch_transaction";
+/// Seed for multisig PDAs.
+pub const SEED_MULTISIG: &[u8] = b"multisig";
+/// Seed for the program config PDA.
+pub const SEED_PROGRAM_CONFIG: &[u8] = b"program_config";
+
+/// Get the PDA of the multisig seeded by `create_key`.
+pub fn get_multisig_pda(create_key: &Pubkey, program_id: &Pubkey) -> Pubkey {
+    let (pda, _bump) = Pubkey::find_program_address(
+        &[SEED_PREFIX, SEED_MULTISIG, create_key.as_ref()],
+        program_id,
+    );
+    pda
+}
+
+/// Get the PDA of the program config, which holds the multisig creation fee
+/// and the treasury it is paid to.
+pub fn get_program_config_pda(program_id: &Pubkey) -> Pubkey {
+    let (pda, _bump) =
+        Pubkey::find_program_address(&[SEED_PREFIX, SEED_PROGRAM_CONFIG], program_id);
+    pda
+}
 
 /// Get the vault PDA for a multisig.
 pub fn get_vault_pda(multisig: &Pubkey, vault_index: u8, program_id: &Pubkey) -> Pubkey {
@@ -55,6 +82,51 @@ pub fn get_proposal_pda(multisig: &Pubkey, transaction_index: u64, program_id: &
     pda
 }
 
+/// Get the PDA of an ephemeral signer of a vault transaction.
+///
+/// Ephemeral signers stand in for fresh keypairs (e.g. new accounts) in the
+/// inner transaction; the program signs for them at execution.
+pub fn get_ephemeral_signer_pda(
+    transaction_pda: &Pubkey,
+    ephemeral_signer_index: u8,
+    program_id: &Pubkey,
+) -> Pubkey {
+    let (pda, _bump) = Pubkey::find_program_address(
+        &[
+            SEED_PREFIX,
+            transaction_pda.as_ref(),
+            SEED_EPHEMERAL_SIGNER,
+            &[ephemeral_signer_index],
+        ],
+        program_id,
+    );
+    pda
+}
+
+/// Get the PDA of a transaction in a batch.
+///
+/// A batch lives at the transaction PDA of its index; its transactions are
+/// numbered from 1 in the order they were added.
+pub fn get_batch_transaction_pda(
+    multisig: &Pubkey,
+    batch_index: u64,
+    transaction_index: u32,
+    program_id: &Pubkey,
+) -> Pubkey {
+    let (pda, _bump) = Pubkey::find_program_address(
+        &[
+            SEED_PREFIX,
+            multisig.as_ref(),
+            SEED_TRANSACTION,
+            &batch_index.to_le_bytes(),
+            SEED_BATCH_TRANSACTION,
+            &transaction_index.to_le_bytes(),
+        ],
+        program_id,
+    );
+    pda
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -92,4 +164,34 @@ mod tests {
         let pda2 = get_transaction_pda(&multisig, 2, &program_id());
         assert_ne!(pda1, pda2);
     }
+
+    #[test]
+    fn test_ephemeral_signer_pda_derivation() {
+        let transaction = get_transaction_pda(&Pubkey::new_unique(), 1, &program_id());
+        let first = get_ephemeral_signer_pda(&transaction, 0, &program_id());
+        let second = get_ephemeral_signer_pda(&transaction, 1, &program_id());
+        assert_ne!(first, second);
+        assert_ne!(first, transaction);
+    }
+
+    #[test]
+    fn test_batch_transaction_pda_derivation() {
+        let multisig = Pubkey::new_unique();
+        let first = get_batch_transaction_pda(&multisig, 1, 1, &program_id());
+        let second = get_batch_transaction_pda(&multisig, 1, 2, &program_id());
+        assert_ne!(first, second);
+        assert_ne!(first, get_transaction_pda(&multisig, 1, &program_id()));
+    }
+
+    #[test]
+    fn test_multisig_pda_derivation() {
+        let create_key = Pubkey::new_unique();
+        let multisig = get_multisig_pda(&create_key, &program_id());
+        assert_ne!(multisig, create_key);
+        assert_ne!(
+            multisig,
+            get_multisig_pda(&Pubkey::new_unique(), &program_id())
+        );
+        assert_ne!(multisig, get_program_config_pda(&program_id()));
+    }
 }
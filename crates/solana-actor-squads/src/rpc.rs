@@ -0,0 +1,633 @@
+//! RPC access with retries for transient failures.
+//!
+//! Public RPC endpoints rate limit, fall behind the cluster and let
+//! blockhashes expire under load. [`RpcErrorKind`] sorts failures into those
+//! worth retrying and fatal ones, and the transport's connection wrapper
+//! retries the former with jittered exponential backoff on every read and
+//! send.
+
+use std::future::Future;
+
+use solana_actor::{
+    AsyncTransactionSigner, Connection, ConnectionError, RetryPolicy, SendConfig, SubmitResult,
+    TransportError, WalletTransport, set_compute_unit_limit_instruction,
+    set_compute_unit_price_instruction,
+};
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_client::rpc_custom_error::{
+    JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED, JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY,
+};
+use solana_client::rpc_request::RpcError;
+use solana_sdk::{
+    instruction::Instruction,
+    message::{Message, VersionedMessage},
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    transaction::{Transaction, TransactionError},
+};
+
+use crate::compute_budget::{ComputeBudget, ComputeUnitPrice, writable_accounts};
+use crate::error::{Result, SquadsError};
+
+/// How an RPC failure is handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RpcErrorKind {
+    /// The endpoint rejected the request with HTTP 429.
+    RateLimited,
+    /// The node is unhealthy or behind the requested slot.
+    NodeBehind,
+    /// The request never reached the node or timed out.
+    Network,
+    /// The blockhash expired before the transaction landed; it must be
+    /// re-signed with a new one.
+    BlockhashNotFound,
+    /// The same transaction was already processed, so it has landed.
+    AlreadyProcessed,
+    /// Anything else, such as a program error. Retrying won't help.
+    Fatal,
+}
+
+impl RpcErrorKind {
+    /// Classify an RPC client error.
+    pub fn classify(err: &ClientError) -> Self {
+        match err.get_transaction_error() {
+            Some(TransactionError::BlockhashNotFound) => return Self::BlockhashNotFound,
+            Some(TransactionError::AlreadyProcessed) => return Self::AlreadyProcessed,
+            Some(_) => return Self::Fatal,
+            None => {}
+        }
+
+        match err.kind() {
+            ClientErrorKind::Reqwest(e) if e.status().is_some_and(|s| s.as_u16() == 429) => {
+                Self::RateLimited
+            }
+            ClientErrorKind::Reqwest(e) if e.is_timeout() || e.is_connect() => Self::Network,
+            ClientErrorKind::Io(_) => Self::Network,
+            ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. })
+                if matches!(
+                    *code,
+                    JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY
+                        | JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED
+                ) =>
+            {
+                Self::NodeBehind
+            }
+            _ => Self::classify_message(&err.to_string()),
+        }
+    }
+
+    /// Classify a connection error.
+    ///
+    /// Connections report most RPC failures as [`ConnectionError::Rpc`], so
+    /// those are classified from their message.
+    pub fn classify_connection(err: &ConnectionError) -> Self {
+        match err {
+            ConnectionError::RateLimited => Self::RateLimited,
+            ConnectionError::NetworkUnreachable | ConnectionError::Timeout => Self::Network,
+            ConnectionError::BlockhashExpired => Self::BlockhashNotFound,
+            ConnectionError::AlreadyProcessed(_) => Self::AlreadyProcessed,
+            ConnectionError::Rpc(message) => Self::classify_message(message),
+            _ => Self::Fatal,
+        }
+    }
+
+    /// Classify an error from its message, for errors without structured
+    /// detail (e.g. a failed confirmation or a proxy's error page).
+    fn classify_message(message: &str) -> Self {
+        let message = message.to_lowercase();
+        let has = |needle: &str| message.contains(needle);
+
+        if has("429") || has("too many requests") {
+            Self::RateLimited
+        } else if has("node is behind") || has("node is unhealthy") || has("minimum context slot") {
+            Self::NodeBehind
+        } else if has("error sending request") || has("connection refused") || has("timed out") {
+            Self::Network
+        } else if has("blockhash not found") || has("unable to confirm transaction") {
+            Self::BlockhashNotFound
+        } else if has("already been processed") {
+            Self::AlreadyProcessed
+        } else {
+            Self::Fatal
+        }
+    }
+
+    /// Whether the request may succeed if made again.
+    pub fn is_retryable(self) -> bool {
+        matches!(
+            self,
+            Self::RateLimited | Self::NodeBehind | Self::Network | Self::BlockhashNotFound
+        )
+    }
+}
+
+/// What a member transport did with a transaction.
+#[derive(Debug, Clone)]
+pub(crate) enum MemberAction {
+    /// The transaction landed with this signature.
+    Sent(Signature),
+    /// The member deferred the transaction, e.g. to its own multisig's
+    /// approvals; it lands once this result completes.
+    Deferred(SubmitResult),
+}
+
+/// A [`Connection`] that retries transient failures per a [`RetryPolicy`].
+pub(crate) struct SquadsRpc<C> {
+    connection: C,
+    retry: RetryPolicy,
+    compute_budget: ComputeBudget,
+}
+
+impl<C: Connection> SquadsRpc<C> {
+    /// Wrap `connection` with the default retry policy.
+    pub(crate) fn new(connection: C) -> Self {
+        Self {
+            connection,
+            retry: RetryPolicy::default(),
+            compute_budget: ComputeBudget::default(),
+        }
+    }
+
+    /// The underlying connection.
+    pub(crate) fn connection(&self) -> &C {
+        &self.connection
+    }
+
+    /// The retry policy.
+    pub(crate) fn retry_policy(&self) -> RetryPolicy {
+        self.retry
+    }
+
+    /// Set the retry policy.
+    pub(crate) fn set_retry_policy(&mut self, retry: RetryPolicy) {
+        self.retry = retry;
+    }
+
+    /// The compute budget added to sent transactions.
+    pub(crate) fn compute_budget(&self) -> ComputeBudget {
+        self.compute_budget
+    }
+
+    /// Set the compute budget added to sent transactions.
+    pub(crate) fn set_compute_budget(&mut self, compute_budget: ComputeBudget) {
+        self.compute_budget = compute_budget;
+    }
+
+    /// Compute budget instructions to prepend to `instructions`.
+    ///
+    /// A recommended price is looked up for the accounts they write.
+    async fn compute_budget_instructions(
+        &self,
+        instructions: &[Instruction],
+    ) -> Result<Vec<Instruction>> {
+        let mut budget = Vec::new();
+        if let Some(units) = self.compute_budget.unit_limit {
+            budget.push(set_compute_unit_limit_instruction(units));
+        }
+        let price = match self.compute_budget.unit_price {
+            ComputeUnitPrice::None => None,
+            ComputeUnitPrice::Fixed(price) => Some(price),
+            ComputeUnitPrice::Recommended { max } => {
+                let writable = writable_accounts(instructions);
+                let price = self
+                    .read("Failed to get prioritization fees", |connection| {
+                        connection.get_recommended_compute_unit_price(&writable)
+                    })
+                    .await?;
+                Some(price.min(max))
+            }
+        };
+        if let Some(price) = price.filter(|price| *price > 0) {
+            budget.push(set_compute_unit_price_instruction(price));
+        }
+        Ok(budget)
+    }
+
+    /// Make a read request, retrying transient failures.
+    ///
+    /// Failures are reported as [`SquadsError::Rpc`] prefixed with `context`.
+    pub(crate) async fn read<'a, T, F, Fut>(&'a self, context: &str, request: F) -> Result<T>
+    where
+        F: Fn(&'a C) -> Fut,
+        Fut: Future<Output = std::result::Result<T, ConnectionError>>,
+    {
+        let max_attempts = self.retry.max_attempts.max(1);
+        let mut attempt = 1;
+        loop {
+            match request(&self.connection).await {
+                Ok(value) => return Ok(value),
+                Err(e)
+                    if attempt < max_attempts
+                        && RpcErrorKind::classify_connection(&e).is_retryable() =>
+                {
+                    tokio::time::sleep(self.retry.jittered_backoff(attempt)).await;
+                    attempt += 1;
+                }
+                Err(e) => {
+                    return Err(SquadsError::Rpc(format!("{}: {}", context, e)).after(attempt));
+                }
+            }
+        }
+    }
+
+    /// Read an account's data, retrying transient failures.
+    ///
+    /// A missing account is reported as [`SquadsError::Rpc`] prefixed with
+    /// `context`.
+    pub(crate) async fn account_data(&self, context: &str, pubkey: &Pubkey) -> Result<Vec<u8>> {
+        self.read(context, |connection| connection.get_account(pubkey))
+            .await?
+            .map(|account| account.data)
+            .ok_or_else(|| SquadsError::Rpc(format!("{}: account {} not found", context, pubkey)))
+    }
+
+    /// Have `member` take `instructions`, paying for them.
+    ///
+    /// The member is asked to submit a message against a fresh blockhash. A
+    /// bare signature is attached and the transaction sent and confirmed, so
+    /// an expired blockhash is retried like any other transient failure by
+    /// asking the member again. A transaction the node reports as already
+    /// processed has landed and counts as sent. A member that sends the
+    /// transaction itself has it count as sent, and one that defers it, such
+    /// as a multisig, has its result returned as
+    /// [`MemberAction::Deferred`]. Failures and rejections are reported with
+    /// `error`. Compute budget instructions are prepended per the configured
+    /// [`ComputeBudget`].
+    pub(crate) async fn submit_through<M: WalletTransport>(
+        &self,
+        instructions: &[Instruction],
+        member: &M,
+        error: fn(String) -> SquadsError,
+    ) -> Result<MemberAction> {
+        let max_attempts = self.retry.max_attempts.max(1);
+        let mut attempt = 1;
+        loop {
+            let blockhash = self
+                .read("Failed to get blockhash", |connection| {
+                    connection.get_latest_blockhash()
+                })
+                .await?;
+            let mut budgeted = self.compute_budget_instructions(instructions).await?;
+            budgeted.extend_from_slice(instructions);
+            let message =
+                Message::new_with_blockhash(&budgeted, Some(&member.authority()), &blockhash);
+
+            let result = match member
+                .submit_versioned(&VersionedMessage::Legacy(message.clone()))
+                .await
+            {
+                Ok(result) => result,
+                Err(TransportError::PartialSubmit { result, reason }) => {
+                    return Err(SquadsError::PartialSubmit { result, reason });
+                }
+                Err(e) => return Err(error(e.to_string()).after(attempt)),
+            };
+            let signature = match result {
+                SubmitResult::Signed(signature) => signature,
+                SubmitResult::Executed { signature, .. } => {
+                    return Ok(MemberAction::Sent(signature));
+                }
+                SubmitResult::Rejected { reason, .. } => return Err(error(reason)),
+                result => return Ok(MemberAction::Deferred(result)),
+            };
+
+            let tx = Transaction {
+                signatures: vec![signature],
+                message,
+            };
+            let err = match self
+                .connection
+                .send_and_confirm(&tx, SendConfig::default())
+                .await
+            {
+                Ok(signature) => return Ok(MemberAction::Sent(signature)),
+                Err(e) => e,
+            };
+            match RpcErrorKind::classify_connection(&err) {
+                RpcErrorKind::AlreadyProcessed => return Ok(MemberAction::Sent(signature)),
+                kind if kind.is_retryable() && attempt < max_attempts => {
+                    tokio::time::sleep(self.retry.jittered_backoff(attempt)).await;
+                    attempt += 1;
+                }
+                _ => return Err(error(err.to_string()).after(attempt)),
+            }
+        }
+    }
+
+    /// Sign `instructions` with `payer` and `signers`, send and confirm them.
+    ///
+    /// Each attempt signs against a fresh blockhash, so an expired blockhash
+    /// is retried like any other transient failure. A transaction the node
+    /// reports as already processed has landed and counts as sent. Failures,
+    /// including a payer that can't sign, are reported with `error`. Compute
+    /// budget instructions are prepended per the configured
+    /// [`ComputeBudget`].
+    pub(crate) async fn send_with_signers<P: AsyncTransactionSigner + ?Sized>(
+        &self,
+        instructions: &[Instruction],
+        payer: &P,
+        signers: &[&Keypair],
+        error: fn(String) -> SquadsError,
+    ) -> Result<Signature> {
+        let max_attempts = self.retry.max_attempts.max(1);
+        let mut attempt = 1;
+        loop {
+            let blockhash = self
+                .read("Failed to get blockhash", |connection| {
+                    connection.get_latest_blockhash()
+                })
+                .await?;
+            let mut budgeted = self.compute_budget_instructions(instructions).await?;
+            budgeted.extend_from_slice(instructions);
+            let mut tx = Transaction::new_with_payer(&budgeted, Some(&payer.pubkey()));
+            tx.try_partial_sign(signers, blockhash)
+                .map_err(|e| error(e.to_string()))?;
+            // The payer may be a device or a remote signer, so it signs
+            // asynchronously and can fail without panicking
+            tx.signatures[0] = payer
+                .sign_transaction(&tx.message_data())
+                .await
+                .map_err(|e| error(e.to_string()))?;
+
+            let err = match self
+                .connection
+                .send_and_confirm(&tx, SendConfig::default())
+                .await
+            {
+                Ok(signature) => return Ok(signature),
+                Err(e) => e,
+            };
+            match RpcErrorKind::classify_connection(&err) {
+                RpcErrorKind::AlreadyProcessed => return Ok(tx.signatures[0]),
+                kind if kind.is_retryable() && attempt < max_attempts => {
+                    tokio::time::sleep(self.retry.jittered_backoff(attempt)).await;
+                    attempt += 1;
+                }
+                _ => return Err(error(err.to_string()).after(attempt)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_client::rpc_request::RpcResponseErrorData;
+
+    fn response_error(code: i64, message: &str) -> ClientError {
+        ClientErrorKind::RpcError(RpcError::RpcResponseError {
+            code,
+            message: message.to_string(),
+            data: RpcResponseErrorData::Empty,
+        })
+        .into()
+    }
+
+    #[test]
+    fn test_classify() {
+        let classify = |kind: ClientErrorKind| RpcErrorKind::classify(&kind.into());
+
+        assert_eq!(
+            classify(ClientErrorKind::TransactionError(
+                TransactionError::BlockhashNotFound
+            )),
+            RpcErrorKind::BlockhashNotFound
+        );
+        assert_eq!(
+            classify(ClientErrorKind::TransactionError(
+                TransactionError::AlreadyProcessed
+            )),
+            RpcErrorKind::AlreadyProcessed
+        );
+        assert_eq!(
+            classify(ClientErrorKind::TransactionError(
+                TransactionError::InsufficientFundsForFee
+            )),
+            RpcErrorKind::Fatal
+        );
+        assert_eq!(
+            RpcErrorKind::classify(&response_error(
+                JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY,
+                "Node is unhealthy"
+            )),
+            RpcErrorKind::NodeBehind
+        );
+        assert_eq!(
+            RpcErrorKind::classify(&response_error(-32602, "Invalid params")),
+            RpcErrorKind::Fatal
+        );
+        assert_eq!(
+            classify(ClientErrorKind::Custom(
+                "HTTP status client error (429 Too Many Requests)".into()
+            )),
+            RpcErrorKind::RateLimited
+        );
+        assert_eq!(
+            classify(ClientErrorKind::Io(std::io::ErrorKind::TimedOut.into())),
+            RpcErrorKind::Network
+        );
+    }
+
+    #[test]
+    fn test_classify_message() {
+        assert_eq!(
+            RpcErrorKind::classify_message("unable to confirm transaction. This can happen"),
+            RpcErrorKind::BlockhashNotFound
+        );
+        assert_eq!(
+            RpcErrorKind::classify_message("This transaction has already been processed"),
+            RpcErrorKind::AlreadyProcessed
+        );
+        assert_eq!(
+            RpcErrorKind::classify_message("Node is behind by 150 slots"),
+            RpcErrorKind::NodeBehind
+        );
+        assert_eq!(
+            RpcErrorKind::classify_message("custom program error: 0x1771"),
+            RpcErrorKind::Fatal
+        );
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(RpcErrorKind::RateLimited.is_retryable());
+        assert!(RpcErrorKind::BlockhashNotFound.is_retryable());
+        assert!(!RpcErrorKind::AlreadyProcessed.is_retryable());
+        assert!(!RpcErrorKind::Fatal.is_retryable());
+    }
+
+    #[test]
+    fn test_classify_connection() {
+        assert_eq!(
+            RpcErrorKind::classify_connection(&ConnectionError::RateLimited),
+            RpcErrorKind::RateLimited
+        );
+        assert_eq!(
+            RpcErrorKind::classify_connection(&ConnectionError::BlockhashExpired),
+            RpcErrorKind::BlockhashNotFound
+        );
+        assert_eq!(
+            RpcErrorKind::classify_connection(&ConnectionError::AlreadyProcessed(
+                Signature::default()
+            )),
+            RpcErrorKind::AlreadyProcessed
+        );
+        assert_eq!(
+            RpcErrorKind::classify_connection(&ConnectionError::Rpc(
+                "error sending request for url (http://localhost:8899/)".into()
+            )),
+            RpcErrorKind::Network
+        );
+        assert_eq!(
+            RpcErrorKind::classify_connection(&ConnectionError::TransactionFailed(
+                "custom program error: 0x1771".into()
+            )),
+            RpcErrorKind::Fatal
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_retries_transient_errors() {
+        use solana_actor::MockConnection;
+        use std::cell::Cell;
+        use std::future::ready;
+        use std::time::Duration;
+
+        let mut rpc = SquadsRpc::new(MockConnection::new());
+        rpc.set_retry_policy(RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::ZERO,
+            max_backoff: Duration::ZERO,
+        });
+
+        // Succeeds once the node catches up
+        let calls = Cell::new(0);
+        let value = rpc
+            .read("Failed", |_| {
+                calls.set(calls.get() + 1);
+                ready(if calls.get() < 3 {
+                    Err(ConnectionError::Rpc(
+                        "Minimum context slot has not been reached".into(),
+                    ))
+                } else {
+                    Ok(7)
+                })
+            })
+            .await
+            .unwrap();
+        assert_eq!((value, calls.get()), (7, 3));
+
+        // Gives up after the last attempt and reports the count
+        let err = rpc
+            .read("Failed", |_| {
+                ready(Err::<(), _>(ConnectionError::RateLimited))
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(err.attempts(), 3);
+        assert!(err.to_string().contains("after 3 attempts"));
+
+        // Fatal errors are not retried
+        let calls = Cell::new(0);
+        let err = rpc
+            .read("Failed", |_| {
+                calls.set(calls.get() + 1);
+                ready(Err::<(), _>(ConnectionError::Rpc("Invalid params".into())))
+            })
+            .await
+            .unwrap_err();
+        assert_eq!((calls.get(), err.attempts()), (1, 1));
+    }
+
+    #[tokio::test]
+    async fn test_account_data() {
+        use solana_actor::MockConnection;
+        use solana_sdk::account::Account;
+
+        let (present, missing) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let rpc = SquadsRpc::new(MockConnection::new().with_account(
+            present,
+            Account {
+                lamports: 1,
+                data: vec![1, 2, 3],
+                owner: Pubkey::new_unique(),
+                executable: false,
+                rent_epoch: 0,
+            },
+        ));
+
+        assert_eq!(
+            rpc.account_data("Failed", &present).await.unwrap(),
+            vec![1, 2, 3]
+        );
+        let err = rpc.account_data("Failed", &missing).await.unwrap_err();
+        assert!(err.to_string().contains("not found"));
+    }
+
+    #[tokio::test]
+    async fn test_send_prepends_compute_budget() {
+        use solana_actor::{
+            COMPUTE_BUDGET_PROGRAM_ID, MockCall, MockConnection, MockSigner, PrioritizationFee,
+        };
+        use solana_sdk::instruction::AccountMeta;
+
+        let payer = MockSigner::new();
+        let written = Pubkey::new_unique();
+        let instruction = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[1],
+            vec![AccountMeta::new(written, false)],
+        );
+        let fees = [10, 20, 5_000]
+            .into_iter()
+            .enumerate()
+            .map(|(slot, prioritization_fee)| PrioritizationFee {
+                slot: slot as u64,
+                prioritization_fee,
+            })
+            .collect();
+        let mut rpc = SquadsRpc::new(MockConnection::new().with_prioritization_fees(fees));
+
+        // No budget by default
+        rpc.send_with_signers(&[instruction.clone()], &payer, &[], SquadsError::Execution)
+            .await
+            .unwrap();
+
+        rpc.set_compute_budget(
+            ComputeBudget::default()
+                .with_unit_limit(300_000)
+                .with_unit_price(ComputeUnitPrice::Recommended { max: 1_000 }),
+        );
+        rpc.send_with_signers(&[instruction.clone()], &payer, &[], SquadsError::Execution)
+            .await
+            .unwrap();
+
+        let sent = rpc.connection().sent_transactions();
+        let budget_instructions = |index: usize| {
+            let message = &sent[index].message;
+            message
+                .instructions()
+                .iter()
+                .filter(|ix| {
+                    message.static_account_keys()[ix.program_id_index as usize]
+                        == COMPUTE_BUDGET_PROGRAM_ID
+                })
+                .map(|ix| ix.data.clone())
+                .collect::<Vec<_>>()
+        };
+        assert!(budget_instructions(0).is_empty());
+
+        // The recommended price is capped
+        let budget = budget_instructions(1);
+        assert_eq!(budget.len(), 2);
+        assert_eq!(budget[0], set_compute_unit_limit_instruction(300_000).data);
+        assert_eq!(budget[1], set_compute_unit_price_instruction(1_000).data);
+        assert_eq!(sent[1].message.instructions().len(), 3);
+
+        // Fees are looked up for the accounts the transaction writes
+        assert!(rpc.connection().calls().iter().any(|call| matches!(
+            call,
+            MockCall::GetRecentPrioritizationFees(accounts) if accounts.contains(&written)
+        )));
+    }
+}
@@ -0,0 +1,507 @@
+//! Squads v4 account state.
+//!
+//! Borsh layouts of the program's accounts, so callers can read a multisig's
+//! members, time lock or a proposal's votes without offset arithmetic. Each
+//! account starts with an 8-byte Anchor discriminator, which
+//! `from_account_data` skips; trailing bytes left by reallocation are
+//! ignored.
+
+use std::fmt;
+
+use borsh::io::{Read, Result as IoResult, Write};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_actor::SubmitResult;
+use solana_sdk::{message::v0::MessageAddressTableLookup, pubkey::Pubkey, signature::Signature};
+
+use crate::error::{Result, SquadsError};
+use crate::message::VaultTransactionMessage;
+
+/// Anchor discriminator of a [`Batch`] account.
+pub const BATCH_DISCRIMINATOR: [u8; 8] = [156, 194, 70, 44, 22, 88, 137, 44];
+
+/// Deserialize an Anchor account, skipping its discriminator.
+fn from_account_data<T: BorshDeserialize>(data: &[u8], name: &str) -> Result<T> {
+    let mut body = data
+        .get(8..)
+        .ok_or_else(|| SquadsError::InvalidAccountData(format!("{} too small", name)))?;
+    T::deserialize(&mut body)
+        .map_err(|e| SquadsError::InvalidAccountData(format!("Invalid {}: {}", name, e)))
+}
+
+/// A multisig account.
+#[derive(Debug, Clone, PartialEq, Eq, BorshDeserialize)]
+pub struct Multisig {
+    /// Key used to seed the multisig PDA.
+    #[borsh(deserialize_with = "read_pubkey")]
+    pub create_key: Pubkey,
+    /// Authority that can change the config without a vote, or the default
+    /// key if changes need a proposal.
+    #[borsh(deserialize_with = "read_pubkey")]
+    pub config_authority: Pubkey,
+    /// Approvals needed to execute a proposal.
+    pub threshold: u16,
+    /// Seconds between a proposal's approval and when it can be executed.
+    pub time_lock: u32,
+    /// Index of the last transaction created.
+    pub transaction_index: u64,
+    /// Transactions at or below this index can no longer be executed.
+    pub stale_transaction_index: u64,
+    /// Account that receives rent from closed accounts, if any.
+    #[borsh(deserialize_with = "read_optional_pubkey")]
+    pub rent_collector: Option<Pubkey>,
+    /// PDA bump.
+    pub bump: u8,
+    /// Members and their permissions.
+    pub members: Vec<Member>,
+}
+
+impl Multisig {
+    /// Parse a multisig account.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SquadsError::InvalidAccountData`] if the account is truncated
+    /// or malformed.
+    pub fn from_account_data(data: &[u8]) -> Result<Self> {
+        from_account_data(data, "Multisig")
+    }
+
+    /// Member keys.
+    pub fn member_keys(&self) -> Vec<Pubkey> {
+        self.members.iter().map(|member| member.key).collect()
+    }
+
+    /// The permissions in `required` that `key` lacks: all of them if `key`
+    /// isn't a member.
+    pub fn missing_permissions(&self, key: &Pubkey, required: &[Permission]) -> Vec<Permission> {
+        let member = self.members.iter().find(|member| member.key == *key);
+        required
+            .iter()
+            .copied()
+            .filter(|permission| !member.is_some_and(|member| member.has(*permission)))
+            .collect()
+    }
+}
+
+/// A member of a multisig.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct Member {
+    /// Member key.
+    #[borsh(serialize_with = "write_pubkey", deserialize_with = "read_pubkey")]
+    pub key: Pubkey,
+    /// Permission bitmask: 1 initiate, 2 vote, 4 execute.
+    pub permissions: u8,
+}
+
+impl Member {
+    /// A member with the given permissions.
+    pub fn new(key: Pubkey, permissions: &[Permission]) -> Self {
+        Self {
+            key,
+            permissions: Permission::mask(permissions),
+        }
+    }
+
+    /// Whether the member has `permission`.
+    pub fn has(&self, permission: Permission) -> bool {
+        permission.has(self.permissions)
+    }
+}
+
+/// A member permission, one bit of [`Member::permissions`].
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    /// Can create proposals.
+    Initiate = 1,
+    /// Can approve, reject and cancel proposals.
+    Vote = 2,
+    /// Can execute approved proposals.
+    Execute = 4,
+}
+
+impl Permission {
+    /// All permissions.
+    pub const ALL: [Permission; 3] = [Self::Initiate, Self::Vote, Self::Execute];
+
+    /// Whether `mask` includes this permission.
+    pub fn has(self, mask: u8) -> bool {
+        mask & (self as u8) != 0
+    }
+
+    /// The bitmask of `permissions`.
+    pub fn mask(permissions: &[Permission]) -> u8 {
+        permissions
+            .iter()
+            .fold(0, |mask, permission| mask | *permission as u8)
+    }
+}
+
+impl fmt::Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Initiate => "initiate",
+            Self::Vote => "vote",
+            Self::Execute => "execute",
+        })
+    }
+}
+
+/// The program's global config.
+#[derive(Debug, Clone, PartialEq, Eq, BorshDeserialize)]
+pub struct ProgramConfig {
+    /// Authority that can change the config.
+    #[borsh(deserialize_with = "read_pubkey")]
+    pub authority: Pubkey,
+    /// Lamports charged to create a multisig.
+    pub multisig_creation_fee: u64,
+    /// Account the creation fee is paid to.
+    #[borsh(deserialize_with = "read_pubkey")]
+    pub treasury: Pubkey,
+}
+
+impl ProgramConfig {
+    /// Parse the program config account.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SquadsError::InvalidAccountData`] if the account is truncated
+    /// or malformed.
+    pub fn from_account_data(data: &[u8]) -> Result<Self> {
+        from_account_data(data, "ProgramConfig")
+    }
+}
+
+/// Status of a proposal, with the time it was reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, BorshDeserialize)]
+pub enum ProposalStatus {
+    /// Created as a draft; can't be voted on until activated.
+    Draft {
+        /// Unix timestamp.
+        timestamp: i64,
+    },
+    /// Open for votes.
+    Active {
+        /// Unix timestamp.
+        timestamp: i64,
+    },
+    /// Rejected by enough members that it can't reach threshold.
+    Rejected {
+        /// Unix timestamp.
+        timestamp: i64,
+    },
+    /// Reached threshold.
+    Approved {
+        /// Unix timestamp.
+        timestamp: i64,
+    },
+    /// Deprecated status of a proposal being executed.
+    Executing,
+    /// Executed.
+    Executed {
+        /// Unix timestamp.
+        timestamp: i64,
+    },
+    /// Cancelled after approval.
+    Cancelled {
+        /// Unix timestamp.
+        timestamp: i64,
+    },
+}
+
+/// A proposal account.
+#[derive(Debug, Clone, PartialEq, Eq, BorshDeserialize)]
+pub struct Proposal {
+    /// Multisig the proposal belongs to.
+    #[borsh(deserialize_with = "read_pubkey")]
+    pub multisig: Pubkey,
+    /// Index of the transaction the proposal is for.
+    pub transaction_index: u64,
+    /// Current status.
+    pub status: ProposalStatus,
+    /// PDA bump.
+    pub bump: u8,
+    /// Members who approved.
+    #[borsh(deserialize_with = "read_pubkeys")]
+    pub approved: Vec<Pubkey>,
+    /// Members who rejected.
+    #[borsh(deserialize_with = "read_pubkeys")]
+    pub rejected: Vec<Pubkey>,
+    /// Members who cancelled.
+    #[borsh(deserialize_with = "read_pubkeys")]
+    pub cancelled: Vec<Pubkey>,
+}
+
+impl Proposal {
+    /// Parse a proposal account.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SquadsError::InvalidAccountData`] if the account is truncated
+    /// or malformed.
+    pub fn from_account_data(data: &[u8]) -> Result<Self> {
+        from_account_data(data, "Proposal")
+    }
+
+    /// Whether the proposal has enough approvals to execute and hasn't been
+    /// executed, rejected or cancelled.
+    pub fn can_execute(&self, threshold: u32) -> bool {
+        self.approved.len() as u32 >= threshold
+            && matches!(
+                self.status,
+                ProposalStatus::Active { .. } | ProposalStatus::Approved { .. }
+            )
+    }
+
+    /// The submit result for this state.
+    pub(crate) fn to_result(
+        &self,
+        proposal: Pubkey,
+        transaction_index: u64,
+        threshold: u32,
+    ) -> SubmitResult {
+        match self.status {
+            // The proposal doesn't record the execution signature; callers
+            // with RPC access look it up from the execution time
+            ProposalStatus::Executed { .. } => SubmitResult::Executed {
+                signature: Signature::default(),
+                proposal,
+            },
+            ProposalStatus::Rejected { .. } => SubmitResult::Rejected {
+                proposal,
+                reason: format!("Rejected by {} member(s)", self.rejected.len()),
+            },
+            ProposalStatus::Cancelled { .. } => SubmitResult::Rejected {
+                proposal,
+                reason: format!("Cancelled by {} member(s)", self.cancelled.len()),
+            },
+            _ => SubmitResult::Pending {
+                proposal,
+                transaction_index,
+                approvals: self.approved.len() as u32,
+                threshold,
+            },
+        }
+    }
+}
+
+/// A vault transaction account.
+#[derive(Debug, Clone, PartialEq, Eq, BorshDeserialize)]
+pub struct VaultTransaction {
+    /// Multisig the transaction belongs to.
+    #[borsh(deserialize_with = "read_pubkey")]
+    pub multisig: Pubkey,
+    /// Member who created the transaction.
+    #[borsh(deserialize_with = "read_pubkey")]
+    pub creator: Pubkey,
+    /// Transaction index.
+    pub index: u64,
+    /// PDA bump.
+    pub bump: u8,
+    /// Vault the transaction runs as.
+    pub vault_index: u8,
+    /// Bump of the vault PDA.
+    pub vault_bump: u8,
+    /// Bumps of the ephemeral signer PDAs, one per ephemeral signer.
+    pub ephemeral_signer_bumps: Vec<u8>,
+    /// The inner transaction.
+    pub message: VaultTransactionMessage,
+}
+
+impl VaultTransaction {
+    /// Parse a vault transaction account.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SquadsError::InvalidAccountData`] if the account is truncated
+    /// or malformed.
+    pub fn from_account_data(data: &[u8]) -> Result<Self> {
+        from_account_data(data, "Vault transaction")
+    }
+}
+
+/// A batch account, which lives at the transaction PDA of its index.
+#[derive(Debug, Clone, PartialEq, Eq, BorshDeserialize)]
+pub struct Batch {
+    /// Multisig the batch belongs to.
+    #[borsh(deserialize_with = "read_pubkey")]
+    pub multisig: Pubkey,
+    /// Member who created the batch.
+    #[borsh(deserialize_with = "read_pubkey")]
+    pub creator: Pubkey,
+    /// Transaction index of the batch.
+    pub index: u64,
+    /// PDA bump.
+    pub bump: u8,
+    /// Vault the batch's transactions run as.
+    pub vault_index: u8,
+    /// Bump of the vault PDA.
+    pub vault_bump: u8,
+    /// Number of transactions in the batch.
+    pub size: u32,
+    /// Index of the last executed transaction, or 0 if none has run.
+    pub executed_transaction_index: u32,
+}
+
+impl Batch {
+    /// Parse a batch account.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SquadsError::InvalidAccountData`] if the account is truncated
+    /// or malformed.
+    pub fn from_account_data(data: &[u8]) -> Result<Self> {
+        from_account_data(data, "Batch")
+    }
+}
+
+/// A transaction in a batch.
+#[derive(Debug, Clone, PartialEq, Eq, BorshDeserialize)]
+pub struct VaultBatchTransaction {
+    /// PDA bump.
+    pub bump: u8,
+    /// Bumps of the ephemeral signer PDAs, one per ephemeral signer.
+    pub ephemeral_signer_bumps: Vec<u8>,
+    /// The inner transaction.
+    pub message: VaultTransactionMessage,
+}
+
+impl VaultBatchTransaction {
+    /// Parse a batch transaction account.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SquadsError::InvalidAccountData`] if the account is truncated
+    /// or malformed.
+    pub fn from_account_data(data: &[u8]) -> Result<Self> {
+        from_account_data(data, "Batch transaction")
+    }
+}
+
+pub(crate) fn read_pubkey<R: Read>(reader: &mut R) -> IoResult<Pubkey> {
+    Ok(Pubkey::new_from_array(<[u8; 32]>::deserialize_reader(
+        reader,
+    )?))
+}
+
+pub(crate) fn read_pubkeys<R: Read>(reader: &mut R) -> IoResult<Vec<Pubkey>> {
+    Ok(Vec::<[u8; 32]>::deserialize_reader(reader)?
+        .into_iter()
+        .map(Pubkey::new_from_array)
+        .collect())
+}
+
+pub(crate) fn write_pubkey<W: Write>(key: &Pubkey, writer: &mut W) -> IoResult<()> {
+    key.to_bytes().serialize(writer)
+}
+
+pub(crate) fn write_optional_pubkey<W: Write>(
+    key: &Option<Pubkey>,
+    writer: &mut W,
+) -> IoResult<()> {
+    key.map(|key| key.to_bytes()).serialize(writer)
+}
+
+fn read_optional_pubkey<R: Read>(reader: &mut R) -> IoResult<Option<Pubkey>> {
+    Ok(Option::<[u8; 32]>::deserialize_reader(reader)?.map(Pubkey::new_from_array))
+}
+
+pub(crate) fn read_lookups<R: Read>(reader: &mut R) -> IoResult<Vec<MessageAddressTableLookup>> {
+    let lookups = Vec::<([u8; 32], Vec<u8>, Vec<u8>)>::deserialize_reader(reader)?;
+    Ok(lookups
+        .into_iter()
+        .map(
+            |(account_key, writable_indexes, readonly_indexes)| MessageAddressTableLookup {
+                account_key: Pubkey::new_from_array(account_key),
+                writable_indexes,
+                readonly_indexes,
+            },
+        )
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{MultisigData, program_config_data};
+
+    #[test]
+    fn test_parse_multisig() {
+        let members = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+        let fixture = MultisigData::new(2, &members).with_time_lock(3600);
+        let multisig = Multisig::from_account_data(&fixture.encode()).unwrap();
+        assert_eq!(multisig.threshold, 2);
+        assert_eq!(multisig.time_lock, 3600);
+        assert_eq!(multisig.transaction_index, 9);
+        assert_eq!(multisig.member_keys(), members);
+        assert_eq!(multisig.members[0].permissions, 7);
+        assert!(
+            multisig
+                .missing_permissions(&members[0], &Permission::ALL)
+                .is_empty()
+        );
+        assert_eq!(
+            multisig.missing_permissions(&Pubkey::new_unique(), &[Permission::Vote]),
+            [Permission::Vote]
+        );
+
+        // The rent collector shifts the members
+        let collector = Pubkey::new_unique();
+        let mut data = fixture.with_rent_collector(collector).encode();
+        assert_eq!(
+            Multisig::from_account_data(&data).unwrap().rent_collector,
+            Some(collector)
+        );
+
+        // Space reserved for more members is ignored
+        data.extend_from_slice(&[0u8; 66]);
+        assert_eq!(
+            Multisig::from_account_data(&data).unwrap().member_keys(),
+            members
+        );
+
+        let data = MultisigData::new(2, &members).encode();
+        assert!(Multisig::from_account_data(&data[..data.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_parse_batch() {
+        let mut data = BATCH_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&[0u8; 32 + 32 + 8 + 1 + 1 + 1]);
+        data.extend_from_slice(&50u32.to_le_bytes());
+        data.extend_from_slice(&12u32.to_le_bytes());
+
+        let batch = Batch::from_account_data(&data).unwrap();
+        assert_eq!(batch.size, 50);
+        assert_eq!(batch.executed_transaction_index, 12);
+        assert!(Batch::from_account_data(&data[..data.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_member_permissions() {
+        let key = Pubkey::new_unique();
+        let member = Member::new(key, &[Permission::Initiate, Permission::Vote]);
+        assert_eq!(member.permissions, 3);
+        assert!(member.has(Permission::Vote));
+        assert!(!member.has(Permission::Execute));
+        assert_eq!(Permission::mask(&Permission::ALL), 7);
+
+        // Members serialize in the layout they are read from
+        let data = borsh::to_vec(&member).unwrap();
+        assert_eq!(data.len(), 33);
+        assert_eq!(Member::try_from_slice(&data).unwrap(), member);
+    }
+
+    #[test]
+    fn test_parse_program_config() {
+        let treasury = Pubkey::new_unique();
+        let mut data = program_config_data(100_000_000, &treasury);
+        data.extend_from_slice(&[0u8; 64]);
+
+        let config = ProgramConfig::from_account_data(&data).unwrap();
+        assert_eq!(config.multisig_creation_fee, 100_000_000);
+        assert_eq!(config.treasury, treasury);
+        assert!(ProgramConfig::from_account_data(&data[..60]).is_err());
+    }
+}
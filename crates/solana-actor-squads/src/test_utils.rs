@@ -0,0 +1,136 @@
+//! Account fixtures shared by the crate's tests.
+//!
+//! Encoders for Squads accounts in their on-chain layout, and a
+//! [`MockConnection`] builder that adds them as program-owned accounts.
+
+use solana_actor::MockConnection;
+use solana_sdk::{account::Account, pubkey::Pubkey};
+
+use crate::SQUADS_PROGRAM_ID;
+use crate::state::{Member, Permission};
+
+/// Squads account builders for [`MockConnection`].
+pub(crate) trait MockSquadsExt {
+    /// Add an account owned by the Squads program holding `data`.
+    fn with_program_account(self, address: Pubkey, data: Vec<u8>) -> Self;
+}
+
+impl MockSquadsExt for MockConnection {
+    fn with_program_account(self, address: Pubkey, data: Vec<u8>) -> Self {
+        self.with_account(
+            address,
+            Account {
+                lamports: 1,
+                data,
+                owner: SQUADS_PROGRAM_ID.parse().unwrap(),
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+    }
+}
+
+/// A multisig account at transaction index 9.
+pub(crate) struct MultisigData {
+    threshold: u16,
+    time_lock: u32,
+    rent_collector: Option<Pubkey>,
+    members: Vec<Member>,
+}
+
+impl MultisigData {
+    /// A multisig whose `members` have every permission.
+    pub(crate) fn new(threshold: u16, members: &[Pubkey]) -> Self {
+        Self {
+            threshold,
+            time_lock: 0,
+            rent_collector: None,
+            members: members
+                .iter()
+                .map(|key| Member::new(*key, &Permission::ALL))
+                .collect(),
+        }
+    }
+
+    /// Replace the members, with their own permissions.
+    pub(crate) fn with_members(mut self, members: &[Member]) -> Self {
+        self.members = members.to_vec();
+        self
+    }
+
+    /// Set the time lock in seconds.
+    pub(crate) fn with_time_lock(mut self, time_lock: u32) -> Self {
+        self.time_lock = time_lock;
+        self
+    }
+
+    /// Set the rent collector.
+    pub(crate) fn with_rent_collector(mut self, rent_collector: Pubkey) -> Self {
+        self.rent_collector = Some(rent_collector);
+        self
+    }
+
+    /// Encode the account, with a zero discriminator, create key and config
+    /// authority.
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let mut data = vec![0u8; 8 + 32 + 32];
+        data.extend_from_slice(&self.threshold.to_le_bytes());
+        data.extend_from_slice(&self.time_lock.to_le_bytes());
+        data.extend_from_slice(&9u64.to_le_bytes());
+        data.extend_from_slice(&0u64.to_le_bytes());
+        match self.rent_collector {
+            Some(key) => {
+                data.push(1);
+                data.extend_from_slice(key.as_ref());
+            }
+            None => data.push(0),
+        }
+        data.push(255);
+        data.extend_from_slice(&(self.members.len() as u32).to_le_bytes());
+        for member in &self.members {
+            data.extend_from_slice(&borsh::to_vec(member).unwrap());
+        }
+        data
+    }
+}
+
+/// Encode a proposal account with the given status and vote lists.
+pub(crate) fn proposal_data(
+    status: u8,
+    approved: usize,
+    rejected: usize,
+    cancelled: usize,
+) -> Vec<u8> {
+    let mut data = vec![0u8; 8 + 32 + 8];
+    data.push(status);
+    if status != 4 {
+        data.extend_from_slice(&1_700_000_000i64.to_le_bytes());
+    }
+    data.push(255);
+    for count in [approved, rejected, cancelled] {
+        data.extend_from_slice(&(count as u32).to_le_bytes());
+        for _ in 0..count {
+            data.extend_from_slice(Pubkey::new_unique().as_ref());
+        }
+    }
+    data
+}
+
+/// Encode a vault transaction account with an empty message.
+pub(crate) fn vault_transaction_data() -> Vec<u8> {
+    let mut data = vec![0u8; 8 + 32 + 32 + 8 + 1 + 1 + 1];
+    data.extend_from_slice(&0u32.to_le_bytes());
+    data.extend_from_slice(&[0, 0, 0]);
+    for _ in 0..3 {
+        data.extend_from_slice(&0u32.to_le_bytes());
+    }
+    data
+}
+
+/// Encode the program config account.
+pub(crate) fn program_config_data(creation_fee: u64, treasury: &Pubkey) -> Vec<u8> {
+    let mut data = vec![0u8; 8 + 32];
+    data.extend_from_slice(&creation_fee.to_le_bytes());
+    data.extend_from_slice(treasury.as_ref());
+    data
+}
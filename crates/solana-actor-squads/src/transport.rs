@@ -3,21 +3,69 @@
 use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
-use solana_actor::{SubmitResult, TransactionSigner, TransportError, WalletTransport};
-use solana_client::rpc_client::RpcClient;
-use solana_commitment_config::CommitmentConfig;
+use solana_actor::{
+    Connection, DirectTransport, RetryPolicy, RpcConnection, SignatureInfo, SubmitResult,
+    TransactionSigner, TransportError, WalletTransport, parse_address_lookup_table,
+    resolve_loaded_addresses,
+};
 use solana_sdk::{
-    instruction::AccountMeta, pubkey::Pubkey, signature::Signature, signer::Signer,
-    transaction::Transaction,
+    instruction::{AccountMeta, Instruction},
+    message::VersionedMessage,
+    pubkey::Pubkey,
+    signature::Signature,
 };
 
 use crate::SQUADS_PROGRAM_ID;
+use crate::compute_budget::ComputeBudget;
 use crate::error::{Result, SquadsError};
 use crate::instructions::{
-    ProposalCreateArgs, ProposalVoteArgs, VaultTransactionCreateArgs, proposal_approve,
-    proposal_create, vault_transaction_create, vault_transaction_execute,
+    BatchAddTransactionArgs, BatchCreateArgs, ProposalCreateArgs, ProposalVoteArgs,
+    VaultTransactionCreateArgs, batch_add_transaction, batch_create, batch_execute_transaction,
+    proposal_activate, proposal_approve, proposal_cancel, proposal_create, proposal_reject,
+    vault_transaction_create, vault_transaction_execute,
+};
+use crate::message::VaultTransactionMessage;
+use crate::pda::{
+    get_batch_transaction_pda, get_ephemeral_signer_pda, get_proposal_pda, get_transaction_pda,
+    get_vault_pda,
 };
-use crate::pda::{get_proposal_pda, get_transaction_pda, get_vault_pda};
+use crate::rpc::{MemberAction, SquadsRpc};
+use crate::state::{
+    BATCH_DISCRIMINATOR, Batch, Multisig, Permission, Proposal, ProposalStatus,
+    VaultBatchTransaction, VaultTransaction,
+};
+
+/// Permissions a member needs to create and approve a proposal.
+pub(crate) const PROPOSER_PERMISSIONS: [Permission; 2] = [Permission::Initiate, Permission::Vote];
+
+/// Pages of a proposal's history read looking for its execution.
+const MAX_HISTORY_PAGES: usize = 10;
+
+/// Options for a submission or vote.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SubmitOptions {
+    /// Memo recorded with the vault transaction (or batch) and the member's
+    /// vote, shown in the Squads UI.
+    pub memo: Option<String>,
+    /// Create the proposal as a draft, which can't be voted on until
+    /// [`SquadsTransport::activate_proposal`] is called. The member doesn't
+    /// approve it.
+    pub draft: bool,
+}
+
+impl SubmitOptions {
+    /// Set the memo.
+    pub fn with_memo(mut self, memo: impl Into<String>) -> Self {
+        self.memo = Some(memo.into());
+        self
+    }
+
+    /// Set whether to create the proposal as a draft.
+    pub fn with_draft(mut self, draft: bool) -> Self {
+        self.draft = draft;
+        self
+    }
+}
 
 /// Squads multisig transport.
 ///
@@ -25,6 +73,21 @@ use crate::pda::{get_proposal_pda, get_transaction_pda, get_vault_pda};
 /// producing direct cryptographic signatures. It requires multiple approvals
 /// to reach threshold before execution.
 ///
+/// The member acts through a [`WalletTransport`] whose authority is a member
+/// of the multisig. [`new`](Self::new) and
+/// [`with_connection`](Self::with_connection) take a [`TransactionSigner`]
+/// and wrap it in a [`DirectTransport`];
+/// [`with_member_transport`](Self::with_member_transport) takes any other
+/// transport, such as another multisig's. Each action the member takes is
+/// one transaction submitted through it:
+///
+/// - A member that returns a signature has the transaction sent by this
+///   transport, retried on transient failures.
+/// - A member that sends the transaction itself has it count as sent.
+/// - A member that defers it, such as a multisig, has the step return
+///   [`SubmitResult::Nested`] with the member's pending result as `inner`.
+///   `check_status` and `wait_for_completion` track both layers.
+///
 /// # Note
 ///
 /// This type does NOT implement [`TransactionSigner`] or [`MessageSigner`]
@@ -59,16 +122,17 @@ use crate::pda::{get_proposal_pda, get_transaction_pda, get_vault_pda};
 ///     _ => {}
 /// }
 /// ```
-pub struct SquadsTransport<S: TransactionSigner> {
+pub struct SquadsTransport<M: WalletTransport, C: Connection = RpcConnection> {
     multisig: Pubkey,
     vault_index: u8,
     vault_pda: Pubkey,
-    rpc_client: RpcClient,
-    member: S,
+    rpc: SquadsRpc<C>,
+    member: M,
     program_id: Pubkey,
+    auto_execute: bool,
 }
 
-impl<S: TransactionSigner> SquadsTransport<S> {
+impl<S: TransactionSigner + Clone + 'static> SquadsTransport<DirectTransport<S>> {
     /// Create a new Squads transport.
     ///
     /// # Arguments
@@ -82,23 +146,130 @@ impl<S: TransactionSigner> SquadsTransport<S> {
     ///
     /// Returns an error if the program ID cannot be parsed.
     pub fn new(multisig: Pubkey, vault_index: u8, rpc_url: &str, member: S) -> Result<Self> {
+        Self::with_connection(multisig, vault_index, RpcConnection::new(rpc_url), member)
+    }
+}
+
+impl<S: TransactionSigner + Clone + 'static, C: Connection> SquadsTransport<DirectTransport<S>, C> {
+    /// Create a Squads transport that reads and sends through `connection`.
+    ///
+    /// Use it to share a rate-limited [`RpcConnection`], or to test proposal
+    /// logic against a `MockConnection`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the program ID cannot be parsed.
+    pub fn with_connection(
+        multisig: Pubkey,
+        vault_index: u8,
+        connection: C,
+        member: S,
+    ) -> Result<Self> {
+        Self::with_member_transport(
+            multisig,
+            vault_index,
+            connection,
+            DirectTransport::new(member),
+        )
+    }
+}
+
+impl<M: WalletTransport, C: Connection> SquadsTransport<M, C> {
+    /// Create a Squads transport whose member acts through `member`.
+    ///
+    /// The member is the transport's [`authority`](WalletTransport::authority):
+    /// a signer key for a [`DirectTransport`], or a vault for another
+    /// multisig's transport.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the program ID cannot be parsed.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use solana_actor::RpcConnection;
+    /// use solana_actor_squads::SquadsTransport;
+    ///
+    /// // A member that is itself a multisig, approving through its own proposals
+    /// let child = SquadsTransport::new(child_multisig, 0, url, signer)?;
+    /// let transport =
+    ///     SquadsTransport::with_member_transport(treasury, 0, RpcConnection::new(url), child)?;
+    /// ```
+    pub fn with_member_transport(
+        multisig: Pubkey,
+        vault_index: u8,
+        connection: C,
+        member: M,
+    ) -> Result<Self> {
         let program_id: Pubkey = SQUADS_PROGRAM_ID
             .parse()
             .map_err(|_| SquadsError::InvalidAddress("Invalid program ID".into()))?;
 
         let vault_pda = get_vault_pda(&multisig, vault_index, &program_id);
-        let rpc_client = RpcClient::new(rpc_url.to_string());
 
         Ok(Self {
             multisig,
             vault_index,
             vault_pda,
-            rpc_client,
+            rpc: SquadsRpc::new(connection),
             member,
             program_id,
+            auto_execute: true,
         })
     }
 
+    /// Set whether `submit` executes proposals that reach threshold.
+    ///
+    /// Defaults to `true`. When `false`, a proposal that reaches threshold is
+    /// returned as [`SubmitResult::Pending`] with `approvals >= threshold`, so
+    /// it can be reviewed (e.g. in the Squads UI) before anyone executes it.
+    /// [`retry_approval`](Self::retry_approval) follows the same setting.
+    pub fn with_auto_execute(mut self, auto_execute: bool) -> Self {
+        self.auto_execute = auto_execute;
+        self
+    }
+
+    /// Whether `submit` executes proposals that reach threshold.
+    pub fn auto_execute(&self) -> bool {
+        self.auto_execute
+    }
+
+    /// Set how transient RPC failures are retried.
+    ///
+    /// Rate limits, lagging nodes, network errors and expired blockhashes are
+    /// retried with jittered exponential backoff in every step of a
+    /// submission; other errors fail immediately. Defaults to
+    /// [`RetryPolicy::default`].
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.rpc.set_retry_policy(policy);
+        self
+    }
+
+    /// Get the retry policy for transient RPC failures.
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.rpc.retry_policy()
+    }
+
+    /// Set compute budget instructions added to every transaction the
+    /// transport sends.
+    ///
+    /// Proposal create, vote and execute transactions carry no priority fee
+    /// by default, and may not land during congestion. A
+    /// [`ComputeUnitPrice::Recommended`](crate::ComputeUnitPrice::Recommended)
+    /// price is looked up before each send.
+    /// Executing a proposal runs its inner instructions, so a unit limit must
+    /// leave room for them.
+    pub fn with_compute_budget(mut self, compute_budget: ComputeBudget) -> Self {
+        self.rpc.set_compute_budget(compute_budget);
+        self
+    }
+
+    /// Get the compute budget added to sent transactions.
+    pub fn compute_budget(&self) -> ComputeBudget {
+        self.rpc.compute_budget()
+    }
+
     /// Get the multisig account address.
     pub fn multisig(&self) -> Pubkey {
         self.multisig
@@ -114,8 +285,8 @@ impl<S: TransactionSigner> SquadsTransport<S> {
         self.vault_index
     }
 
-    /// Get a reference to the member signer.
-    pub fn member(&self) -> &S {
+    /// Get a reference to the member transport.
+    pub fn member(&self) -> &M {
         &self.member
     }
 
@@ -124,43 +295,45 @@ impl<S: TransactionSigner> SquadsTransport<S> {
         self.program_id
     }
 
-    /// Create a proposal for a transaction.
-    async fn create_proposal(&self, transaction_message: &[u8]) -> Result<(Pubkey, u64)> {
-        let member_pubkey = self.member.pubkey();
-
-        // Get the current transaction index from the multisig account
-        let multisig_data = self
-            .rpc_client
-            .get_account_data(&self.multisig)
-            .map_err(|e| SquadsError::Rpc(format!("Failed to fetch multisig: {}", e)))?;
-
-        // Parse transaction_index from multisig account data
-        // Offset = 8 (discriminator) + 32 (create_key) + 32 (config_authority) + 2 (threshold) + 4 (time_lock) = 78
-        const TX_INDEX_OFFSET: usize = 78;
-
-        if multisig_data.len() < TX_INDEX_OFFSET + 8 {
-            return Err(SquadsError::InvalidAccountData(
-                "Multisig account too small".into(),
-            ));
-        }
+    /// Get the connection used for reads and sends.
+    pub fn connection(&self) -> &C {
+        self.rpc.connection()
+    }
 
-        let transaction_index = u64::from_le_bytes(
-            multisig_data[TX_INDEX_OFFSET..TX_INDEX_OFFSET + 8]
-                .try_into()
-                .map_err(|_| SquadsError::InvalidAccountData("Failed to parse tx index".into()))?,
-        );
-        let next_index = transaction_index + 1;
+    /// Create a proposal for a transaction and, unless it's a draft, approve
+    /// it, all in one member transaction. Returns the new proposal as pending
+    /// with no approvals, and what the member did.
+    ///
+    /// Signers other than the vault become ephemeral signers of the new vault
+    /// transaction.
+    async fn create_proposal(
+        &self,
+        transaction_message: &VaultTransactionMessage,
+        options: &SubmitOptions,
+        threshold: u32,
+    ) -> Result<(SubmitResult, MemberAction)> {
+        let member_pubkey = self.member.authority();
+        let next_index = next_transaction_index(&self.rpc, &self.multisig).await?;
 
         // Derive PDAs for the new transaction and proposal
         let transaction_pda = get_transaction_pda(&self.multisig, next_index, &self.program_id);
         let proposal_pda = get_proposal_pda(&self.multisig, next_index, &self.program_id);
 
+        // Ephemeral signer PDAs depend on the transaction PDA, so they can only
+        // be assigned once the index is known
+        let mut transaction_message = transaction_message.clone();
+        let ephemeral_signers = transaction_message.assign_ephemeral_signers(
+            &self.vault_pda,
+            &transaction_pda,
+            &self.program_id,
+        );
+
         // Build vault transaction create instruction
         let vault_tx_args = VaultTransactionCreateArgs {
             vault_index: self.vault_index,
-            ephemeral_signers: 0,
-            transaction_message: transaction_message.to_vec(),
-            memo: None,
+            ephemeral_signers,
+            transaction_message: transaction_message.to_bytes()?,
+            memo: options.memo.clone(),
         };
 
         let vault_tx_ix = vault_transaction_create(
@@ -175,7 +348,7 @@ impl<S: TransactionSigner> SquadsTransport<S> {
         // Build proposal create instruction
         let proposal_args = ProposalCreateArgs {
             transaction_index: next_index,
-            draft: false,
+            draft: options.draft,
         };
 
         let proposal_ix = proposal_create(
@@ -187,32 +360,72 @@ impl<S: TransactionSigner> SquadsTransport<S> {
             self.program_id,
         );
 
-        // Get recent blockhash and sign
-        let blockhash = self
-            .rpc_client
-            .get_latest_blockhash()
-            .map_err(|e| SquadsError::Rpc(format!("Failed to get blockhash: {}", e)))?;
+        let mut instructions = vec![vault_tx_ix, proposal_ix];
+        if !options.draft {
+            instructions.push(proposal_approve(
+                self.multisig,
+                proposal_pda,
+                member_pubkey,
+                ProposalVoteArgs {
+                    memo: options.memo.clone(),
+                },
+                self.program_id,
+            ));
+        }
 
-        let mut tx = Transaction::new_with_payer(&[vault_tx_ix, proposal_ix], Some(&member_pubkey));
-        tx.partial_sign(&[&MemberSigner(&self.member)], blockhash);
+        let pending = SubmitResult::Pending {
+            proposal: proposal_pda,
+            transaction_index: next_index,
+            approvals: 0,
+            threshold,
+        };
+        let action = self
+            .act(&instructions, SquadsError::ProposalCreation)
+            .await
+            .map_err(|e| nest_partial(e, &pending))?;
 
-        // Send transaction
-        self.rpc_client
-            .send_and_confirm_transaction_with_spinner_and_commitment(
-                &tx,
-                CommitmentConfig::confirmed(),
-            )
-            .map_err(|e| SquadsError::ProposalCreation(e.to_string()))?;
+        Ok((pending, action))
+    }
 
-        Ok((proposal_pda, next_index))
+    /// Have the member take `instructions`, reporting failures with `error`.
+    async fn act(
+        &self,
+        instructions: &[Instruction],
+        error: fn(String) -> SquadsError,
+    ) -> Result<MemberAction> {
+        self.rpc
+            .submit_through(instructions, &self.member, error)
+            .await
     }
 
-    /// Approve a proposal with the member key.
-    async fn approve_proposal(&self, transaction_index: u64) -> Result<()> {
-        let member_pubkey = self.member.pubkey();
+    /// Have the member take one step of a batch, which can't wait on the
+    /// member's own approvals since later steps depend on it.
+    async fn act_now(
+        &self,
+        instructions: &[Instruction],
+        error: fn(String) -> SquadsError,
+    ) -> Result<Signature> {
+        match self.act(instructions, error).await? {
+            MemberAction::Sent(signature) => Ok(signature),
+            MemberAction::Deferred(_) => Err(error(format!(
+                "Member {} deferred a batch step; batches need a member that signs directly",
+                self.member.authority()
+            ))),
+        }
+    }
+
+    /// Approve a proposal as the member.
+    async fn approve_proposal(
+        &self,
+        transaction_index: u64,
+        memo: Option<&str>,
+    ) -> Result<MemberAction> {
+        let member_pubkey = self.member.authority();
         let proposal_pda = get_proposal_pda(&self.multisig, transaction_index, &self.program_id);
 
-        let vote_args = ProposalVoteArgs { memo: None };
+        let vote_args = ProposalVoteArgs {
+            memo: memo.map(String::from),
+        };
 
         let approve_ix = proposal_approve(
             self.multisig,
@@ -222,38 +435,38 @@ impl<S: TransactionSigner> SquadsTransport<S> {
             self.program_id,
         );
 
-        let blockhash = self
-            .rpc_client
-            .get_latest_blockhash()
-            .map_err(|e| SquadsError::Rpc(format!("Failed to get blockhash: {}", e)))?;
-
-        let mut tx = Transaction::new_with_payer(&[approve_ix], Some(&member_pubkey));
-        tx.partial_sign(&[&MemberSigner(&self.member)], blockhash);
-
-        self.rpc_client
-            .send_and_confirm_transaction_with_spinner_and_commitment(
-                &tx,
-                CommitmentConfig::confirmed(),
-            )
-            .map_err(|e| SquadsError::Approval(e.to_string()))?;
-
-        Ok(())
+        self.act(&[approve_ix], SquadsError::Approval).await
     }
 
     /// Execute a proposal that has reached threshold.
-    async fn execute_proposal(&self, transaction_index: u64) -> Result<Signature> {
-        let member_pubkey = self.member.pubkey();
+    async fn execute_proposal(&self, transaction_index: u64) -> Result<MemberAction> {
+        let member_pubkey = self.member.authority();
         let proposal_pda = get_proposal_pda(&self.multisig, transaction_index, &self.program_id);
         let transaction_pda =
             get_transaction_pda(&self.multisig, transaction_index, &self.program_id);
 
         // Fetch the vault transaction account to get the accounts list
         let tx_data = self
-            .rpc_client
-            .get_account_data(&transaction_pda)
-            .map_err(|e| SquadsError::Rpc(format!("Failed to fetch transaction: {}", e)))?;
+            .rpc
+            .account_data("Failed to fetch transaction", &transaction_pda)
+            .await?;
+
+        // Batches live at the transaction PDA too
+        if tx_data.starts_with(&BATCH_DISCRIMINATOR) {
+            return self
+                .execute_batch(transaction_index, &tx_data)
+                .await
+                .map(MemberAction::Sent);
+        }
 
-        let remaining_accounts = parse_vault_transaction_accounts(&tx_data, self.vault_pda)?;
+        let transaction = VaultTransaction::from_account_data(&tx_data)?;
+        let remaining_accounts = self
+            .remaining_accounts(
+                &transaction.message,
+                transaction.ephemeral_signer_bumps.len(),
+                &transaction_pda,
+            )
+            .await?;
 
         let execute_ix = vault_transaction_execute(
             self.multisig,
@@ -264,98 +477,506 @@ impl<S: TransactionSigner> SquadsTransport<S> {
             self.program_id,
         );
 
-        let blockhash = self
-            .rpc_client
-            .get_latest_blockhash()
-            .map_err(|e| SquadsError::Rpc(format!("Failed to get blockhash: {}", e)))?;
+        self.act(&[execute_ix], SquadsError::Execution).await
+    }
 
-        let mut tx = Transaction::new_with_payer(&[execute_ix], Some(&member_pubkey));
-        tx.partial_sign(&[&MemberSigner(&self.member)], blockhash);
+    /// Execute a batch's remaining transactions in order, returning the
+    /// signature of the last one.
+    async fn execute_batch(&self, batch_index: u64, batch_data: &[u8]) -> Result<Signature> {
+        let member_pubkey = self.member.authority();
+        let proposal_pda = get_proposal_pda(&self.multisig, batch_index, &self.program_id);
+        let batch_pda = get_transaction_pda(&self.multisig, batch_index, &self.program_id);
+        let batch = Batch::from_account_data(batch_data)?;
+
+        let mut signature = None;
+        for index in batch.executed_transaction_index + 1..=batch.size {
+            let transaction_pda =
+                get_batch_transaction_pda(&self.multisig, batch_index, index, &self.program_id);
+            let tx_data = self
+                .rpc
+                .account_data("Failed to fetch batch transaction", &transaction_pda)
+                .await?;
+
+            let transaction = VaultBatchTransaction::from_account_data(&tx_data)?;
+            let remaining_accounts = self
+                .remaining_accounts(
+                    &transaction.message,
+                    transaction.ephemeral_signer_bumps.len(),
+                    &transaction_pda,
+                )
+                .await?;
+
+            let execute_ix = batch_execute_transaction(
+                self.multisig,
+                proposal_pda,
+                batch_pda,
+                transaction_pda,
+                member_pubkey,
+                remaining_accounts,
+                self.program_id,
+            );
+            signature = Some(self.act_now(&[execute_ix], SquadsError::Execution).await?);
+        }
 
-        let signature = self
-            .rpc_client
-            .send_and_confirm_transaction_with_spinner_and_commitment(
-                &tx,
-                CommitmentConfig::confirmed(),
-            )
-            .map_err(|e| SquadsError::Execution(e.to_string()))?;
+        signature.ok_or_else(|| SquadsError::Execution("Batch has nothing left to execute".into()))
+    }
 
-        Ok(signature)
+    /// The remaining accounts to execute a vault or batch transaction with,
+    /// resolving its lookup tables.
+    async fn remaining_accounts(
+        &self,
+        message: &VaultTransactionMessage,
+        ephemeral_signers: usize,
+        transaction_pda: &Pubkey,
+    ) -> Result<Vec<AccountMeta>> {
+        // The program signs for ephemeral signers at execution, so each one must
+        // appear among the message's signers
+        let signers =
+            &message.account_keys[..(message.num_signers as usize).min(message.account_keys.len())];
+        for index in 0..ephemeral_signers {
+            let pda = get_ephemeral_signer_pda(transaction_pda, index as u8, &self.program_id);
+            if !signers.contains(&pda) {
+                return Err(SquadsError::InvalidAccountData(format!(
+                    "Ephemeral signer {} missing from vault transaction",
+                    pda
+                )));
+            }
+        }
+        // Fetch every lookup table in one request
+        let table_keys: Vec<Pubkey> = message
+            .address_table_lookups
+            .iter()
+            .map(|lookup| lookup.account_key)
+            .collect();
+        let accounts = if table_keys.is_empty() {
+            Vec::new()
+        } else {
+            self.rpc
+                .read("Failed to fetch lookup tables", |connection| {
+                    connection.get_multiple_accounts(&table_keys)
+                })
+                .await?
+        };
+        let mut tables = Vec::with_capacity(table_keys.len());
+        for (key, account) in table_keys.into_iter().zip(accounts) {
+            let account = account
+                .ok_or_else(|| SquadsError::Rpc(format!("Lookup table {} not found", key)))?;
+            tables.push(
+                parse_address_lookup_table(key, &account)
+                    .map_err(|e| SquadsError::InvalidAccountData(e.to_string()))?,
+            );
+        }
+        let loaded = resolve_loaded_addresses(&message.address_table_lookups, &tables)
+            .map_err(|e| SquadsError::InvalidAccountData(e.to_string()))?;
+        Ok(message.execute_accounts(&loaded))
     }
 
     /// Get the current proposal state.
-    async fn get_proposal_state(&self, transaction_index: u64) -> Result<ProposalState> {
-        let proposal_pda = get_proposal_pda(&self.multisig, transaction_index, &self.program_id);
+    async fn get_proposal_state(&self, transaction_index: u64) -> Result<Proposal> {
+        fetch_proposal(
+            &self.rpc,
+            &self.multisig,
+            transaction_index,
+            &self.program_id,
+        )
+        .await
+    }
 
-        let proposal_data = self
-            .rpc_client
-            .get_account_data(&proposal_pda)
-            .map_err(|e| SquadsError::Rpc(format!("Failed to fetch proposal: {}", e)))?;
+    /// Get the members of the multisig.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the multisig account cannot be fetched or parsed.
+    pub async fn members(&self) -> Result<Vec<Pubkey>> {
+        fetch_members(&self.rpc, &self.multisig).await
+    }
 
-        parse_proposal_state(&proposal_data)
+    /// Fetch the multisig account, with its threshold, time lock and members.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the multisig account cannot be fetched or parsed.
+    pub async fn multisig_account(&self) -> Result<Multisig> {
+        fetch_multisig(&self.rpc, &self.multisig).await
     }
 
-    /// Get the multisig threshold.
-    fn get_threshold(&self) -> Result<u32> {
-        let multisig_data = self
-            .rpc_client
-            .get_account_data(&self.multisig)
-            .map_err(|e| SquadsError::Rpc(format!("Failed to fetch multisig: {}", e)))?;
+    /// Fetch the proposal for a transaction, with its status and votes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the proposal account cannot be fetched or parsed.
+    pub async fn proposal(&self, transaction_index: u64) -> Result<Proposal> {
+        fetch_proposal(
+            &self.rpc,
+            &self.multisig,
+            transaction_index,
+            &self.program_id,
+        )
+        .await
+    }
+
+    /// Submit a legacy or v0 message like
+    /// [`WalletTransport::submit_versioned`], recording `options.memo` on
+    /// the vault transaction and the member's approval. With
+    /// `options.draft`, the proposal is left as an unapproved draft.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message can't be converted or the proposal
+    /// can't be created. Once it exists, failures are reported as
+    /// [`SquadsError::PartialSubmit`] for
+    /// [`retry_approval`](Self::retry_approval).
+    pub async fn submit_versioned_with(
+        &self,
+        message: &VersionedMessage,
+        options: &SubmitOptions,
+    ) -> Result<SubmitResult> {
+        let vault_message = VaultTransactionMessage::from_versioned(message)?;
+        self.propose(&vault_message, options).await
+    }
+
+    /// Propose many vault transaction messages as one batch.
+    ///
+    /// The messages share one proposal, so members vote once for all of them
+    /// instead of once per message. The batch is created with a draft
+    /// proposal, each message is added in its own transaction, and the
+    /// proposal is then activated and approved with the member key. If it
+    /// reaches threshold and auto-execution is on, the messages are executed
+    /// in order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SquadsError::ProposalCreation`] if `messages` is empty or
+    /// the batch can't be created and filled; a batch left as a draft can't
+    /// be voted on. Once the proposal is active, failures are reported as
+    /// [`SquadsError::PartialSubmit`] for
+    /// [`retry_approval`](Self::retry_approval), which also resumes a
+    /// partly executed batch.
+    pub async fn submit_batch(&self, messages: &[VaultTransactionMessage]) -> Result<SubmitResult> {
+        self.submit_batch_with(messages, &SubmitOptions::default())
+            .await
+    }
 
-        // Threshold offset = 8 (discriminator) + 32 (create_key) + 32 (config_authority) = 72
-        const THRESHOLD_OFFSET: usize = 72;
+    /// Like [`submit_batch`](Self::submit_batch), recording `options.memo`
+    /// on the batch and the member's approval. With `options.draft`, the
+    /// batch's proposal is left as an unapproved draft.
+    ///
+    /// # Errors
+    ///
+    /// See [`submit_batch`](Self::submit_batch).
+    pub async fn submit_batch_with(
+        &self,
+        messages: &[VaultTransactionMessage],
+        options: &SubmitOptions,
+    ) -> Result<SubmitResult> {
+        if messages.is_empty() {
+            return Err(SquadsError::ProposalCreation(
+                "Batch has no transactions".into(),
+            ));
+        }
+
+        let threshold = self.check_proposer(options).await?;
+        let (proposal, transaction_index) = self.create_batch(messages, options).await?;
 
-        if multisig_data.len() < THRESHOLD_OFFSET + 2 {
-            return Err(SquadsError::InvalidAccountData("Multisig too small".into()));
+        let pending = SubmitResult::Pending {
+            proposal,
+            transaction_index,
+            approvals: 0,
+            threshold,
+        };
+        if options.draft {
+            return Ok(pending);
         }
+        self.approve_and_execute(pending, true, options.memo.as_deref(), self.auto_execute)
+            .await
+    }
 
-        let threshold = u16::from_le_bytes(
-            multisig_data[THRESHOLD_OFFSET..THRESHOLD_OFFSET + 2]
-                .try_into()
-                .map_err(|_| SquadsError::InvalidAccountData("Failed to parse threshold".into()))?,
+    /// Create a batch holding `messages` and, unless it's a draft, activate
+    /// its proposal.
+    async fn create_batch(
+        &self,
+        messages: &[VaultTransactionMessage],
+        options: &SubmitOptions,
+    ) -> Result<(Pubkey, u64)> {
+        let member_pubkey = self.member.authority();
+        let batch_index = next_transaction_index(&self.rpc, &self.multisig).await?;
+        let batch_pda = get_transaction_pda(&self.multisig, batch_index, &self.program_id);
+        let proposal_pda = get_proposal_pda(&self.multisig, batch_index, &self.program_id);
+
+        // Transactions can only be added while the proposal is a draft
+        let batch_ix = batch_create(
+            self.multisig,
+            batch_pda,
+            member_pubkey,
+            member_pubkey,
+            BatchCreateArgs {
+                vault_index: self.vault_index,
+                memo: options.memo.clone(),
+            },
+            self.program_id,
         );
+        let proposal_ix = proposal_create(
+            self.multisig,
+            proposal_pda,
+            member_pubkey,
+            member_pubkey,
+            ProposalCreateArgs {
+                transaction_index: batch_index,
+                draft: true,
+            },
+            self.program_id,
+        );
+        self.act_now(&[batch_ix, proposal_ix], SquadsError::ProposalCreation)
+            .await?;
+
+        // Each message gets its own transaction, since one can fill most of
+        // the size limit
+        for (index, message) in (1..).zip(messages) {
+            let transaction_pda =
+                get_batch_transaction_pda(&self.multisig, batch_index, index, &self.program_id);
+            let mut message = message.clone();
+            let ephemeral_signers = message.assign_ephemeral_signers(
+                &self.vault_pda,
+                &transaction_pda,
+                &self.program_id,
+            );
+
+            let add_ix = batch_add_transaction(
+                self.multisig,
+                proposal_pda,
+                batch_pda,
+                transaction_pda,
+                member_pubkey,
+                member_pubkey,
+                BatchAddTransactionArgs {
+                    ephemeral_signers,
+                    transaction_message: message.to_bytes()?,
+                },
+                self.program_id,
+            );
+            self.act_now(&[add_ix], SquadsError::ProposalCreation)
+                .await?;
+        }
+
+        if !options.draft {
+            let activate_ix =
+                proposal_activate(self.multisig, proposal_pda, member_pubkey, self.program_id);
+            self.act_now(&[activate_ix], SquadsError::ProposalCreation)
+                .await?;
+        }
 
-        Ok(threshold as u32)
+        Ok((proposal_pda, batch_index))
     }
-}
 
-#[async_trait]
-impl<S: TransactionSigner + Clone + Send + Sync + 'static> WalletTransport for SquadsTransport<S> {
-    fn authority(&self) -> Pubkey {
-        self.vault_pda
+    /// Activate a draft proposal with the member key, so members can vote on
+    /// it.
+    ///
+    /// Drafts are created with [`SubmitOptions::draft`] to stage a
+    /// transaction for review. Activating doesn't approve the proposal; pass
+    /// the result to [`retry_approval`](Self::retry_approval) to approve it
+    /// with the member key. Returns the proposal's status after activation.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SquadsError::InsufficientPermissions`] if the member can't
+    /// initiate proposals, or [`SquadsError::Activation`] if the activation
+    /// fails, for example because the proposal isn't a draft.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let options = SubmitOptions::default().with_draft(true);
+    /// let draft = transport.submit_versioned_with(&message, &options).await?;
+    ///
+    /// // After review
+    /// if let SubmitResult::Pending { transaction_index, .. } = draft {
+    ///     let active = transport.activate_proposal(transaction_index).await?;
+    ///     transport.retry_approval(&active).await?;
+    /// }
+    /// ```
+    pub async fn activate_proposal(&self, transaction_index: u64) -> Result<SubmitResult> {
+        let member_pubkey = self.member.authority();
+        let multisig = fetch_multisig(&self.rpc, &self.multisig).await?;
+        check_permissions(
+            &self.multisig,
+            &multisig,
+            &member_pubkey,
+            &[Permission::Initiate],
+        )?;
+
+        let proposal_pda = get_proposal_pda(&self.multisig, transaction_index, &self.program_id);
+        let activate_ix =
+            proposal_activate(self.multisig, proposal_pda, member_pubkey, self.program_id);
+        let action = self.act(&[activate_ix], SquadsError::Activation).await?;
+
+        self.action_result(action, proposal_pda, transaction_index)
+            .await
     }
 
-    async fn submit(&self, message: &[u8]) -> std::result::Result<SubmitResult, TransportError> {
-        // 1. Create proposal
-        let (proposal, tx_index) = self.create_proposal(message).await?;
+    /// Reject a proposal with the member key.
+    ///
+    /// Once enough members reject it that the threshold can no longer be
+    /// reached, the proposal is rejected and can't be executed. Returns the
+    /// proposal's status after the vote.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SquadsError::Rejection`] if the rejection fails, for example
+    /// because the proposal is no longer active.
+    pub async fn reject_proposal(&self, transaction_index: u64) -> Result<SubmitResult> {
+        self.reject_proposal_with(transaction_index, &SubmitOptions::default())
+            .await
+    }
+
+    /// Like [`reject_proposal`](Self::reject_proposal), recording
+    /// `options.memo` with the vote.
+    ///
+    /// # Errors
+    ///
+    /// See [`reject_proposal`](Self::reject_proposal).
+    pub async fn reject_proposal_with(
+        &self,
+        transaction_index: u64,
+        options: &SubmitOptions,
+    ) -> Result<SubmitResult> {
+        let member_pubkey = self.member.authority();
+        let proposal_pda = get_proposal_pda(&self.multisig, transaction_index, &self.program_id);
 
-        // 2. Approve with member signer
-        self.approve_proposal(tx_index).await?;
+        let reject_ix = proposal_reject(
+            self.multisig,
+            proposal_pda,
+            member_pubkey,
+            ProposalVoteArgs {
+                memo: options.memo.clone(),
+            },
+            self.program_id,
+        );
 
-        // 3. Check if we can execute
-        let state = self.get_proposal_state(tx_index).await?;
-        let threshold = self.get_threshold()?;
+        let action = self.act(&[reject_ix], SquadsError::Rejection).await?;
 
-        if state.can_execute(threshold) {
-            let sig = self.execute_proposal(tx_index).await?;
-            Ok(SubmitResult::Executed {
-                signature: sig,
-                proposal,
-            })
-        } else {
-            Ok(SubmitResult::Pending {
-                proposal,
-                transaction_index: tx_index,
-                approvals: state.approval_count,
-                threshold,
-            })
-        }
+        self.action_result(action, proposal_pda, transaction_index)
+            .await
     }
 
-    async fn check_status(
+    /// Cancel an approved proposal with the member key.
+    ///
+    /// Withdraws a proposal that reached threshold but should no longer be
+    /// executed. Once the threshold of members cancel it, the proposal is
+    /// cancelled. Returns the proposal's status after the vote.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SquadsError::Cancellation`] if the cancellation fails, for
+    /// example because the proposal hasn't been approved or was executed.
+    pub async fn cancel_proposal(&self, transaction_index: u64) -> Result<SubmitResult> {
+        self.cancel_proposal_with(transaction_index, &SubmitOptions::default())
+            .await
+    }
+
+    /// Like [`cancel_proposal`](Self::cancel_proposal), recording
+    /// `options.memo` with the vote.
+    ///
+    /// # Errors
+    ///
+    /// See [`cancel_proposal`](Self::cancel_proposal).
+    pub async fn cancel_proposal_with(
         &self,
-        result: &SubmitResult,
-    ) -> std::result::Result<SubmitResult, TransportError> {
+        transaction_index: u64,
+        options: &SubmitOptions,
+    ) -> Result<SubmitResult> {
+        let member_pubkey = self.member.authority();
+        let proposal_pda = get_proposal_pda(&self.multisig, transaction_index, &self.program_id);
+
+        let cancel_ix = proposal_cancel(
+            self.multisig,
+            proposal_pda,
+            member_pubkey,
+            ProposalVoteArgs {
+                memo: options.memo.clone(),
+            },
+            self.program_id,
+        );
+
+        let action = self.act(&[cancel_ix], SquadsError::Cancellation).await?;
+
+        self.action_result(action, proposal_pda, transaction_index)
+            .await
+    }
+
+    /// The proposal's status after a member action, nested with the
+    /// member's result while it's deferred.
+    async fn action_result(
+        &self,
+        action: MemberAction,
+        proposal: Pubkey,
+        transaction_index: u64,
+    ) -> Result<SubmitResult> {
+        let status = proposal_status(
+            &self.rpc,
+            &self.multisig,
+            proposal,
+            transaction_index,
+            &self.program_id,
+        )
+        .await?;
+        Ok(match action {
+            MemberAction::Sent(_) => status,
+            MemberAction::Deferred(inner) => nested(status, inner),
+        })
+    }
+
+    /// Create, approve, and (if the threshold is met and auto-execution is
+    /// on) execute a proposal for a vault transaction message.
+    ///
+    /// Once the proposal exists, failures are reported as
+    /// [`SquadsError::PartialSubmit`] so the proposal isn't lost. A member
+    /// that defers creating it returns [`SubmitResult::Nested`].
+    pub(crate) async fn propose(
+        &self,
+        transaction_message: &VaultTransactionMessage,
+        options: &SubmitOptions,
+    ) -> Result<SubmitResult> {
+        let threshold = self.check_proposer(options).await?;
+        let (pending, action) = self
+            .create_proposal(transaction_message, options, threshold)
+            .await?;
+
+        match action {
+            MemberAction::Deferred(inner) => Ok(nested(pending, inner)),
+            MemberAction::Sent(_) if options.draft => Ok(pending),
+            // The member approved along with creating the proposal
+            MemberAction::Sent(_) => {
+                self.approve_and_execute(pending, false, None, self.auto_execute)
+                    .await
+            }
+        }
+    }
+
+    /// Resume a submission whose approval or execution failed.
+    ///
+    /// Takes the result from [`SquadsError::PartialSubmit`] (or any pending
+    /// result) and approves the existing proposal with the member key, unless
+    /// the member has already approved it. The proposal is then executed if
+    /// it has reached threshold and auto-execution is on. Results that aren't
+    /// pending are returned unchanged, including nested results whose member
+    /// action is still deferred; track those with `check_status`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SquadsError::PartialSubmit`] again if approval or execution
+    /// fails, so it can be retried later.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let result = match transport.submit(&tx_message).await {
+    ///     Err(TransportError::PartialSubmit { result, .. }) => {
+    ///         transport.retry_approval(&result).await?
+    ///     }
+    ///     result => result?,
+    /// };
+    /// ```
+    pub async fn retry_approval(&self, result: &SubmitResult) -> Result<SubmitResult> {
         let SubmitResult::Pending {
             proposal,
             transaction_index,
@@ -366,31 +987,37 @@ impl<S: TransactionSigner + Clone + Send + Sync + 'static> WalletTransport for S
         };
 
         let state = self.get_proposal_state(*transaction_index).await?;
-        let threshold = self.get_threshold()?;
-
-        if state.is_executed {
-            // If executed, we need to find the execution signature
-            // For now, return executed with a default signature
-            Ok(SubmitResult::Executed {
-                signature: Signature::default(),
-                proposal: *proposal,
-            })
-        } else {
-            Ok(SubmitResult::Pending {
-                proposal: *proposal,
-                transaction_index: *transaction_index,
-                approvals: state.approval_count,
-                threshold,
-            })
+        let threshold = self.get_threshold().await?;
+        let current =
+            resolve_status(&self.rpc, &state, *proposal, *transaction_index, threshold).await?;
+        if !current.is_pending() {
+            return Ok(current);
         }
+
+        let approve = !state.approved.contains(&self.member.authority());
+        self.approve_and_execute(current, approve, None, self.auto_execute)
+            .await
     }
 
-    async fn wait_for_completion(
+    /// Wait for a pending proposal to complete, optionally executing it.
+    ///
+    /// With `execute_when_ready`, the member executes the proposal as soon as
+    /// it reaches threshold; otherwise this waits for someone else to execute
+    /// it, like [`WalletTransport::wait_for_completion`]. Use it to execute a
+    /// proposal submitted with auto-execution off once it has been reviewed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TransportError::Timeout`] if the proposal neither completes
+    /// nor is rejected in time, or [`SquadsError::PartialSubmit`] if executing
+    /// it fails.
+    pub async fn wait_for_completion_with(
         &self,
         result: SubmitResult,
         timeout: Duration,
+        execute_when_ready: bool,
     ) -> std::result::Result<SubmitResult, TransportError> {
-        if result.is_complete() {
+        if result.is_complete() || result.is_rejected() {
             return Ok(result);
         }
 
@@ -398,8 +1025,36 @@ impl<S: TransactionSigner + Clone + Send + Sync + 'static> WalletTransport for S
         let mut current = result;
 
         while Instant::now() < deadline {
-            current = self.check_status(&current).await?;
-            if current.is_complete() {
+            current = match current {
+                SubmitResult::Pending {
+                    proposal,
+                    transaction_index,
+                    ..
+                } if !execute_when_ready => {
+                    proposal_status(
+                        &self.rpc,
+                        &self.multisig,
+                        proposal,
+                        transaction_index,
+                        &self.program_id,
+                    )
+                    .await?
+                }
+                SubmitResult::Nested { outer, inner } => {
+                    match self.nested_status(&outer, &inner).await? {
+                        // Once the member's action lands, the proposal can be
+                        // executed like any other
+                        SubmitResult::Nested { outer, inner }
+                            if execute_when_ready && inner.is_complete() =>
+                        {
+                            *outer
+                        }
+                        current => current,
+                    }
+                }
+                current => self.approve_and_execute(current, false, None, true).await?,
+            };
+            if current.is_complete() || current.is_rejected() {
                 return Ok(current);
             }
             tokio::time::sleep(Duration::from_secs(2)).await;
@@ -408,178 +1063,905 @@ impl<S: TransactionSigner + Clone + Send + Sync + 'static> WalletTransport for S
         Err(TransportError::Timeout)
     }
 
-    fn requires_network(&self) -> bool {
-        true
+    /// Refresh a proposal whose member action was deferred: the member's
+    /// result, and the proposal once that action has landed.
+    async fn nested_status(
+        &self,
+        outer: &SubmitResult,
+        inner: &SubmitResult,
+    ) -> std::result::Result<SubmitResult, TransportError> {
+        let inner = self.member.check_status(inner).await?;
+        let outer = match outer {
+            SubmitResult::Pending {
+                proposal,
+                transaction_index,
+                ..
+            } if inner.is_complete() => {
+                proposal_status(
+                    &self.rpc,
+                    &self.multisig,
+                    *proposal,
+                    *transaction_index,
+                    &self.program_id,
+                )
+                .await?
+            }
+            outer => outer.clone(),
+        };
+        Ok(nested(outer, inner))
+    }
+
+    /// Approve a pending proposal (if `approve`, recording `memo`) and, if
+    /// `execute`, execute it once the threshold is met.
+    async fn approve_and_execute(
+        &self,
+        pending: SubmitResult,
+        approve: bool,
+        memo: Option<&str>,
+        execute: bool,
+    ) -> Result<SubmitResult> {
+        let SubmitResult::Pending {
+            proposal,
+            transaction_index,
+            threshold,
+            ..
+        } = pending
+        else {
+            return Ok(pending);
+        };
+        let partial = |e: SquadsError| match e {
+            // The member's own submission is partial
+            SquadsError::PartialSubmit { .. } => nest_partial(e, &pending),
+            e => SquadsError::PartialSubmit {
+                result: Box::new(pending.clone()),
+                reason: e.to_string(),
+            },
+        };
+
+        if approve {
+            let action = self
+                .approve_proposal(transaction_index, memo)
+                .await
+                .map_err(partial)?;
+            if let MemberAction::Deferred(inner) = action {
+                return Ok(nested(pending.clone(), inner));
+            }
+        }
+
+        let state = self
+            .get_proposal_state(transaction_index)
+            .await
+            .map_err(partial)?;
+
+        if execute && state.can_execute(threshold) {
+            match self
+                .execute_proposal(transaction_index)
+                .await
+                .map_err(partial)?
+            {
+                MemberAction::Sent(signature) => Ok(SubmitResult::Executed {
+                    signature,
+                    proposal,
+                }),
+                MemberAction::Deferred(inner) => Ok(nested(
+                    state.to_result(proposal, transaction_index, threshold),
+                    inner,
+                )),
+            }
+        } else {
+            resolve_status(&self.rpc, &state, proposal, transaction_index, threshold)
+                .await
+                .map_err(partial)
+        }
+    }
+
+    /// Get the multisig threshold.
+    async fn get_threshold(&self) -> Result<u32> {
+        fetch_threshold(&self.rpc, &self.multisig).await
+    }
+
+    /// Check that the member can create and (unless it's a draft) approve
+    /// proposals, before paying for a transaction the program would reject.
+    /// Returns the threshold.
+    async fn check_proposer(&self, options: &SubmitOptions) -> Result<u32> {
+        let required: &[Permission] = if options.draft {
+            &[Permission::Initiate]
+        } else {
+            &PROPOSER_PERMISSIONS
+        };
+        let multisig = fetch_multisig(&self.rpc, &self.multisig).await?;
+        check_permissions(
+            &self.multisig,
+            &multisig,
+            &self.member.authority(),
+            required,
+        )?;
+        Ok(u32::from(multisig.threshold))
     }
 }
 
-/// Helper to wrap a TransactionSigner as a solana_sdk::signer::Signer.
-struct MemberSigner<'a, S: TransactionSigner>(&'a S);
+#[async_trait]
+impl<M, C> WalletTransport for SquadsTransport<M, C>
+where
+    M: WalletTransport + 'static,
+    C: Connection + 'static,
+{
+    fn authority(&self) -> Pubkey {
+        self.vault_pda
+    }
 
-impl<S: TransactionSigner> Signer for MemberSigner<'_, S> {
-    fn pubkey(&self) -> Pubkey {
-        TransactionSigner::pubkey(self.0)
+    async fn submit(&self, message: &[u8]) -> std::result::Result<SubmitResult, TransportError> {
+        let vault_message = VaultTransactionMessage::from_bytes(message)?;
+        Ok(self
+            .propose(&vault_message, &SubmitOptions::default())
+            .await?)
     }
 
-    fn try_pubkey(&self) -> std::result::Result<Pubkey, solana_sdk::signer::SignerError> {
-        Ok(TransactionSigner::pubkey(self.0))
+    async fn submit_versioned(
+        &self,
+        message: &VersionedMessage,
+    ) -> std::result::Result<SubmitResult, TransportError> {
+        let vault_message = VaultTransactionMessage::from_versioned(message)?;
+        Ok(self
+            .propose(&vault_message, &SubmitOptions::default())
+            .await?)
     }
 
-    fn sign_message(&self, message: &[u8]) -> Signature {
-        self.0
-            .sign_transaction(message)
-            .expect("signing should succeed")
+    async fn check_status(
+        &self,
+        result: &SubmitResult,
+    ) -> std::result::Result<SubmitResult, TransportError> {
+        match result {
+            SubmitResult::Pending {
+                proposal,
+                transaction_index,
+                ..
+            } => Ok(proposal_status(
+                &self.rpc,
+                &self.multisig,
+                *proposal,
+                *transaction_index,
+                &self.program_id,
+            )
+            .await?),
+            SubmitResult::Nested { outer, inner } => self.nested_status(outer, inner).await,
+            _ => Ok(result.clone()),
+        }
     }
 
-    fn try_sign_message(
+    async fn wait_for_completion(
         &self,
-        message: &[u8],
-    ) -> std::result::Result<Signature, solana_sdk::signer::SignerError> {
-        self.0
-            .sign_transaction(message)
-            .map_err(|e| solana_sdk::signer::SignerError::Custom(e.to_string()))
+        result: SubmitResult,
+        timeout: Duration,
+    ) -> std::result::Result<SubmitResult, TransportError> {
+        self.wait_for_completion_with(result, timeout, false).await
+    }
+
+    fn requires_network(&self) -> bool {
+        true
     }
+}
 
-    fn is_interactive(&self) -> bool {
-        TransactionSigner::is_interactive(self.0)
+/// A proposal's result while the member's action on it is deferred.
+fn nested(outer: SubmitResult, inner: SubmitResult) -> SubmitResult {
+    SubmitResult::Nested {
+        outer: Box::new(outer),
+        inner: Box::new(inner),
     }
 }
 
-/// Parsed proposal state.
-struct ProposalState {
-    approval_count: u32,
-    is_executed: bool,
+/// Wrap a partial inner submission with the outer proposal it will create.
+fn nest_partial(err: SquadsError, outer: &SubmitResult) -> SquadsError {
+    match err {
+        SquadsError::PartialSubmit { result, reason } => SquadsError::PartialSubmit {
+            result: Box::new(SubmitResult::Nested {
+                outer: Box::new(outer.clone()),
+                inner: result,
+            }),
+            reason,
+        },
+        err => err,
+    }
 }
 
-impl ProposalState {
-    fn can_execute(&self, threshold: u32) -> bool {
-        self.approval_count >= threshold && !self.is_executed
+/// Fetch a multisig account.
+pub(crate) async fn fetch_multisig<C: Connection>(
+    rpc: &SquadsRpc<C>,
+    multisig: &Pubkey,
+) -> Result<Multisig> {
+    let data = rpc
+        .account_data("Failed to fetch multisig", multisig)
+        .await?;
+    Multisig::from_account_data(&data)
+}
+
+/// Fail with [`SquadsError::InsufficientPermissions`] if `member` lacks any of
+/// `required` on `multisig`, the account at `address`.
+pub(crate) fn check_permissions(
+    address: &Pubkey,
+    multisig: &Multisig,
+    member: &Pubkey,
+    required: &[Permission],
+) -> Result<()> {
+    let missing = multisig.missing_permissions(member, required);
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(SquadsError::InsufficientPermissions {
+            member: *member,
+            multisig: *address,
+            missing,
+        })
     }
 }
 
-/// Parse proposal state from account data.
-fn parse_proposal_state(data: &[u8]) -> Result<ProposalState> {
-    // Proposal struct layout (after 8-byte Anchor discriminator):
-    // - multisig: Pubkey (32)
-    // - transaction_index: u64 (8)
-    // - status: ProposalStatus (1 byte enum)
-    // - bump: u8 (1)
-    // - approved: Vec<Pubkey> (4 + 32*n)
-    // - rejected: Vec<Pubkey> (4 + 32*n)
-    // - cancelled: Vec<Pubkey> (4 + 32*n)
-
-    const STATUS_OFFSET: usize = 8 + 32 + 8;
-    const APPROVED_OFFSET: usize = STATUS_OFFSET + 1 + 1;
-
-    if data.len() < APPROVED_OFFSET + 4 {
-        return Err(SquadsError::InvalidAccountData("Proposal too small".into()));
-    }
-
-    let status = data[STATUS_OFFSET];
-    let is_executed = status == 3; // Executed status
-
-    let approval_count = u32::from_le_bytes(
-        data[APPROVED_OFFSET..APPROVED_OFFSET + 4]
-            .try_into()
-            .map_err(|_| SquadsError::InvalidAccountData("Failed to parse approvals".into()))?,
-    );
-
-    Ok(ProposalState {
-        approval_count,
-        is_executed,
-    })
+/// Get the index the next transaction on a multisig will be created at.
+pub(crate) async fn next_transaction_index<C: Connection>(
+    rpc: &SquadsRpc<C>,
+    multisig: &Pubkey,
+) -> Result<u64> {
+    Ok(fetch_multisig(rpc, multisig).await?.transaction_index + 1)
+}
+
+/// Get a multisig's threshold.
+pub(crate) async fn fetch_threshold<C: Connection>(
+    rpc: &SquadsRpc<C>,
+    multisig: &Pubkey,
+) -> Result<u32> {
+    Ok(u32::from(fetch_multisig(rpc, multisig).await?.threshold))
+}
+
+/// Get a multisig's member keys.
+pub(crate) async fn fetch_members<C: Connection>(
+    rpc: &SquadsRpc<C>,
+    multisig: &Pubkey,
+) -> Result<Vec<Pubkey>> {
+    Ok(fetch_multisig(rpc, multisig).await?.member_keys())
+}
+
+/// Get a proposal account.
+pub(crate) async fn fetch_proposal<C: Connection>(
+    rpc: &SquadsRpc<C>,
+    multisig: &Pubkey,
+    transaction_index: u64,
+    program_id: &Pubkey,
+) -> Result<Proposal> {
+    let proposal_pda = get_proposal_pda(multisig, transaction_index, program_id);
+
+    let proposal_data = rpc
+        .account_data("Failed to fetch proposal", &proposal_pda)
+        .await?;
+
+    Proposal::from_account_data(&proposal_data)
+}
+
+/// Get the submit status of a proposal.
+pub(crate) async fn proposal_status<C: Connection>(
+    rpc: &SquadsRpc<C>,
+    multisig: &Pubkey,
+    proposal: Pubkey,
+    transaction_index: u64,
+    program_id: &Pubkey,
+) -> Result<SubmitResult> {
+    let state = fetch_proposal(rpc, multisig, transaction_index, program_id).await?;
+    let threshold = fetch_threshold(rpc, multisig).await?;
+    resolve_status(rpc, &state, proposal, transaction_index, threshold).await
+}
+
+/// The submit result for a proposal's state, with the execution signature
+/// looked up if it was executed.
+///
+/// Fails with [`SquadsError::ExecutionNotFound`] rather than reporting a
+/// made-up signature if the node's history no longer reaches the execution.
+async fn resolve_status<C: Connection>(
+    rpc: &SquadsRpc<C>,
+    state: &Proposal,
+    proposal: Pubkey,
+    transaction_index: u64,
+    threshold: u32,
+) -> Result<SubmitResult> {
+    let ProposalStatus::Executed { timestamp } = state.status else {
+        return Ok(state.to_result(proposal, transaction_index, threshold));
+    };
+
+    // Page back through the proposal's history until the execution or
+    // anything older than it turns up
+    let mut before = None;
+    for _ in 0..MAX_HISTORY_PAGES {
+        let history = rpc
+            .read("Failed to fetch proposal history", |connection| {
+                connection.get_signatures_for_address(&proposal, before.as_ref())
+            })
+            .await?;
+        if let Some(signature) = execution_signature(&history, timestamp) {
+            return Ok(SubmitResult::Executed {
+                signature,
+                proposal,
+            });
+        }
+        match history.last() {
+            Some(oldest) if oldest.block_time.is_none_or(|time| time >= timestamp) => {
+                before = Some(oldest.signature);
+            }
+            _ => break,
+        }
+    }
+    Err(SquadsError::ExecutionNotFound(proposal))
 }
 
-/// Parse the remaining accounts needed for execution from the vault transaction data.
-fn parse_vault_transaction_accounts(tx_data: &[u8], vault_pda: Pubkey) -> Result<Vec<AccountMeta>> {
-    // VaultTransaction struct layout (after 8-byte Anchor discriminator):
-    // - multisig: Pubkey (32)
-    // - creator: Pubkey (32)
-    // - index: u64 (8)
-    // - bump: u8 (1)
-    // - vault_index: u8 (1)
-    // - vault_bump: u8 (1)
-    // - ephemeral_signer_bumps: Vec<u8> (4 + n)
-    // - message: TransactionMessage (variable)
-
-    const FIXED_SIZE: usize = 8 + 32 + 32 + 8 + 1 + 1 + 1;
-
-    if tx_data.len() < FIXED_SIZE {
-        return Err(SquadsError::InvalidAccountData(
-            "Vault transaction too small".into(),
+/// Find the transaction that executed a proposal in its signature history,
+/// which is newest first.
+///
+/// The proposal records the block time of its execution, which is its last
+/// successful write at that time: for a batch, the final
+/// `batch_execute_transaction`.
+fn execution_signature(history: &[SignatureInfo], executed_at: i64) -> Option<Signature> {
+    history
+        .iter()
+        .find(|entry| entry.error.is_none() && entry.block_time == Some(executed_at))
+        .map(|entry| entry.signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::Member;
+    use crate::test_utils::{MockSquadsExt, MultisigData, proposal_data, vault_transaction_data};
+    use solana_actor::{ConnectionError, MockCall, MockConnection, MockSigner};
+    use solana_sdk::message::Message;
+
+    /// A member transport whose submissions wait on other approvers.
+    struct PendingMember(Pubkey);
+
+    #[async_trait]
+    impl WalletTransport for PendingMember {
+        fn authority(&self) -> Pubkey {
+            self.0
+        }
+
+        async fn submit(
+            &self,
+            _message: &[u8],
+        ) -> std::result::Result<SubmitResult, TransportError> {
+            Ok(SubmitResult::Pending {
+                proposal: Pubkey::new_unique(),
+                transaction_index: 1,
+                approvals: 1,
+                threshold: 2,
+            })
+        }
+
+        async fn check_status(
+            &self,
+            result: &SubmitResult,
+        ) -> std::result::Result<SubmitResult, TransportError> {
+            Ok(result.clone())
+        }
+
+        async fn wait_for_completion(
+            &self,
+            result: SubmitResult,
+            _timeout: Duration,
+        ) -> std::result::Result<SubmitResult, TransportError> {
+            Ok(result)
+        }
+
+        fn requires_network(&self) -> bool {
+            false
+        }
+    }
+
+    /// The multisig the mock transports propose on, and the proposal and
+    /// transaction PDAs of its next transaction.
+    fn mock_pdas() -> (Pubkey, Pubkey, Pubkey) {
+        let multisig = Pubkey::new_from_array([9; 32]);
+        let program_id: Pubkey = SQUADS_PROGRAM_ID.parse().unwrap();
+        (
+            multisig,
+            get_proposal_pda(&multisig, 10, &program_id),
+            get_transaction_pda(&multisig, 10, &program_id),
+        )
+    }
+
+    type MockTransport = SquadsTransport<DirectTransport<MockSigner>, MockConnection>;
+
+    /// A transport over `connection`, with the multisig added to it.
+    fn mock_transport(threshold: u16, connection: MockConnection) -> MockTransport {
+        mock_member_transport(
+            threshold,
+            connection,
+            DirectTransport::new(MockSigner::new()),
+        )
+    }
+
+    /// A transport over `connection` whose member acts through `member`,
+    /// with the multisig added to it.
+    fn mock_member_transport<M: WalletTransport>(
+        threshold: u16,
+        connection: MockConnection,
+        member: M,
+    ) -> SquadsTransport<M, MockConnection> {
+        let (multisig, _, _) = mock_pdas();
+        let connection = connection.with_program_account(
+            multisig,
+            MultisigData::new(threshold, &[member.authority()]).encode(),
+        );
+        SquadsTransport::with_member_transport(multisig, 0, connection, member)
+            .unwrap()
+            .with_retry_policy(RetryPolicy {
+                max_attempts: 2,
+                initial_backoff: Duration::ZERO,
+                max_backoff: Duration::ZERO,
+            })
+    }
+
+    fn empty_message<M: WalletTransport>(
+        transport: &SquadsTransport<M, MockConnection>,
+    ) -> VersionedMessage {
+        VersionedMessage::Legacy(Message::new(&[], Some(&transport.vault_pda())))
+    }
+
+    #[test]
+    fn test_parse_proposal_state() {
+        let proposal = Pubkey::new_unique();
+
+        let active = Proposal::from_account_data(&proposal_data(1, 1, 0, 0)).unwrap();
+        assert_eq!(active.approved.len(), 1);
+        assert_eq!(
+            active.status,
+            ProposalStatus::Active {
+                timestamp: 1_700_000_000
+            }
+        );
+        assert!(!active.can_execute(2));
+        assert!(active.to_result(proposal, 1, 2).is_pending());
+
+        let approved = Proposal::from_account_data(&proposal_data(3, 2, 1, 0)).unwrap();
+        assert!(approved.can_execute(2));
+
+        let executed = Proposal::from_account_data(&proposal_data(5, 2, 0, 0)).unwrap();
+        assert!(!executed.can_execute(2));
+        assert!(executed.to_result(proposal, 1, 2).is_complete());
+
+        let executing = Proposal::from_account_data(&proposal_data(4, 2, 0, 0)).unwrap();
+        assert_eq!(executing.status, ProposalStatus::Executing);
+        assert_eq!(executing.approved.len(), 2);
+
+        // Approvers follow the status, timestamp, bump and list length
+        let data = proposal_data(1, 2, 1, 0);
+        let approvers: Vec<Pubkey> = data[62..126]
+            .chunks_exact(32)
+            .map(|key| Pubkey::try_from(key).unwrap())
+            .collect();
+        assert_eq!(
+            Proposal::from_account_data(&data).unwrap().approved,
+            approvers
+        );
+
+        let data = proposal_data(1, 1, 0, 0);
+        assert!(Proposal::from_account_data(&data[..data.len() - 1]).is_err());
+        assert!(Proposal::from_account_data(&proposal_data(7, 0, 0, 0)).is_err());
+    }
+
+    #[test]
+    fn test_rejected_and_cancelled_status() {
+        let proposal = Pubkey::new_unique();
+
+        let rejected = Proposal::from_account_data(&proposal_data(2, 1, 2, 0)).unwrap();
+        assert_eq!(rejected.rejected.len(), 2);
+        let result = rejected.to_result(proposal, 1, 2);
+        assert!(result.is_rejected());
+        assert!(!result.is_pending());
+        assert!(matches!(
+            result,
+            SubmitResult::Rejected { reason, .. } if reason == "Rejected by 2 member(s)"
+        ));
+
+        let cancelled = Proposal::from_account_data(&proposal_data(6, 2, 0, 1)).unwrap();
+        assert!(!cancelled.can_execute(2));
+        assert!(matches!(
+            cancelled.to_result(proposal, 1, 2),
+            SubmitResult::Rejected { reason, .. } if reason == "Cancelled by 1 member(s)"
         ));
     }
 
-    // The vault is always needed as a signer for execution
-    let mut accounts = vec![AccountMeta::new(vault_pda, false)];
+    #[test]
+    fn test_execution_signature() {
+        let entry = |byte: u8, error: Option<&str>, block_time: i64| SignatureInfo {
+            signature: Signature::from([byte; 64]),
+            slot: 1,
+            error: error.map(str::to_string),
+            block_time: Some(block_time),
+        };
+
+        // Newest first: a failed retry after the execution, then the
+        // execution, then the approval in the same second
+        let history = [
+            entry(3, Some("custom program error: 0x1"), 100),
+            entry(2, None, 100),
+            entry(1, None, 100),
+            entry(4, None, 90),
+        ];
+        assert_eq!(
+            execution_signature(&history, 100),
+            Some(Signature::from([2; 64]))
+        );
+        assert_eq!(execution_signature(&history, 95), None);
+    }
 
-    // Skip the fixed fields to get to ephemeral_signer_bumps
-    let mut offset = FIXED_SIZE;
+    #[tokio::test]
+    async fn test_submit_creates_approves_and_executes() {
+        let (_, proposal, transaction) = mock_pdas();
+        let transport = mock_transport(
+            1,
+            MockConnection::new()
+                .with_program_account(proposal, proposal_data(3, 1, 0, 0))
+                .with_program_account(transaction, vault_transaction_data()),
+        );
 
-    if offset + 4 > tx_data.len() {
-        return Ok(accounts);
+        let result = transport
+            .submit_versioned(&empty_message(&transport))
+            .await
+            .unwrap();
+
+        let sent = transport.connection().sent_transactions();
+        let instructions: Vec<usize> = sent
+            .iter()
+            .map(|tx| tx.message.instructions().len())
+            .collect();
+        // Create (vault transaction, proposal and approval), execute
+        assert_eq!(instructions, [3, 1]);
+        assert!(
+            sent.iter()
+                .all(|tx| tx.verify_with_results().iter().all(|ok| *ok))
+        );
+        let member = transport.member().authority();
+        assert!(
+            sent.iter()
+                .all(|tx| tx.message.static_account_keys()[0] == member)
+        );
+        assert!(matches!(
+            result,
+            SubmitResult::Executed { signature, proposal: p }
+                if signature == sent[1].signatures[0] && p == proposal
+        ));
     }
 
-    // Read ephemeral_signer_bumps length
-    let ephemeral_len = u32::from_le_bytes(
-        tx_data[offset..offset + 4]
-            .try_into()
-            .map_err(|_| SquadsError::InvalidAccountData("Failed to parse ephemeral len".into()))?,
-    ) as usize;
-    offset += 4 + ephemeral_len;
+    #[tokio::test]
+    async fn test_submit_below_threshold_stays_pending() {
+        let (_, proposal, _) = mock_pdas();
+        // The creation is signed again after the blockhash expires
+        let transport = mock_transport(
+            2,
+            MockConnection::new()
+                .with_program_account(proposal, proposal_data(1, 1, 0, 0))
+                .expect_send(Err(ConnectionError::BlockhashExpired)),
+        );
 
-    // Now we're at the TransactionMessage
-    if offset + 3 > tx_data.len() {
-        return Ok(accounts);
+        let result = transport
+            .submit_versioned(&empty_message(&transport))
+            .await
+            .unwrap();
+
+        assert_eq!(transport.connection().sent_transactions().len(), 2);
+        assert!(transport.connection().expectations_met());
+        assert!(matches!(
+            result,
+            SubmitResult::Pending { proposal: p, transaction_index: 10, approvals: 1, threshold: 2 }
+                if p == proposal
+        ));
     }
 
-    let num_signers = tx_data[offset] as usize;
-    let num_writable_signers = tx_data[offset + 1] as usize;
-    let num_writable_non_signers = tx_data[offset + 2] as usize;
-    offset += 3;
+    #[tokio::test]
+    async fn test_submit_with_memo() {
+        let (_, proposal, _) = mock_pdas();
+        let transport = mock_transport(
+            2,
+            MockConnection::new().with_program_account(proposal, proposal_data(1, 1, 0, 0)),
+        );
 
-    if offset + 4 > tx_data.len() {
-        return Ok(accounts);
+        let options = SubmitOptions::default().with_memo("Q3 payroll");
+        transport
+            .submit_versioned_with(&empty_message(&transport), &options)
+            .await
+            .unwrap();
+
+        // Both the vault transaction and the approval carry the memo
+        let memo = borsh::to_vec(&options.memo).unwrap();
+        let sent = transport.connection().sent_transactions();
+        assert_eq!(sent.len(), 1);
+        let with_memo = sent[0]
+            .message
+            .instructions()
+            .iter()
+            .filter(|ix| {
+                ix.data
+                    .windows(memo.len())
+                    .any(|window| window == memo.as_slice())
+            })
+            .count();
+        assert_eq!(with_memo, 2);
     }
 
-    // Read account_keys length
-    let num_keys = u32::from_le_bytes(
-        tx_data[offset..offset + 4]
-            .try_into()
-            .map_err(|_| SquadsError::InvalidAccountData("Failed to parse keys len".into()))?,
-    ) as usize;
-    offset += 4;
+    #[tokio::test]
+    async fn test_draft_and_activate() {
+        let (_, proposal, _) = mock_pdas();
+        let transport = mock_transport(
+            1,
+            MockConnection::new().with_program_account(proposal, proposal_data(1, 0, 0, 0)),
+        );
 
-    // Read each account key
-    for i in 0..num_keys {
-        if offset + 32 > tx_data.len() {
-            break;
-        }
+        let options = SubmitOptions::default().with_draft(true);
+        let result = transport
+            .submit_versioned_with(&empty_message(&transport), &options)
+            .await
+            .unwrap();
+        assert!(matches!(
+            result,
+            SubmitResult::Pending { proposal: p, transaction_index: 10, approvals: 0, .. }
+                if p == proposal
+        ));
 
-        let key_bytes: [u8; 32] = tx_data[offset..offset + 32]
-            .try_into()
-            .map_err(|_| SquadsError::InvalidAccountData("Failed to parse key".into()))?;
+        // Only the creation is sent, with the proposal as a draft
+        let sent = transport.connection().sent_transactions();
+        assert_eq!(sent.len(), 1);
+        let create = &sent[0].message.instructions()[1];
+        assert_eq!(create.data[16], 1);
+
+        let result = transport.activate_proposal(10).await.unwrap();
+        assert!(result.is_pending());
+        let sent = transport.connection().sent_transactions();
+        assert_eq!(sent.len(), 2);
+        assert_eq!(sent[1].message.instructions().len(), 1);
+    }
 
-        let pubkey = Pubkey::new_from_array(key_bytes);
+    #[tokio::test]
+    async fn test_submit_requires_permissions() {
+        let member = MockSigner::new();
+        let (multisig, _, _) = mock_pdas();
+        // The member can vote but not initiate
+        let data = MultisigData::new(1, &[])
+            .with_members(&[Member::new(member.pubkey(), &[Permission::Vote])])
+            .encode();
+        let connection = MockConnection::new().with_program_account(multisig, data);
+        let transport = SquadsTransport::with_connection(multisig, 0, connection, member).unwrap();
+
+        let err = transport
+            .submit_versioned_with(&empty_message(&transport), &SubmitOptions::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            &err,
+            SquadsError::InsufficientPermissions { missing, .. }
+                if *missing == [Permission::Initiate]
+        ));
+        assert!(err.to_string().contains("lacks the initiate permission"));
+        assert!(transport.connection().sent_transactions().is_empty());
+    }
 
-        // Skip the vault itself
-        if pubkey != vault_pda {
-            let is_signer = i < num_signers;
-            let is_writable = if is_signer {
-                i < num_writable_signers
-            } else {
-                i < num_signers + num_writable_non_signers
-            };
+    #[tokio::test]
+    async fn test_failed_execution_is_partial() {
+        let (_, proposal, transaction) = mock_pdas();
+        let transport = mock_transport(
+            1,
+            MockConnection::new()
+                .with_program_account(proposal, proposal_data(3, 1, 0, 0))
+                .with_program_account(transaction, vault_transaction_data())
+                .expect_send(Ok(()))
+                .expect_send(Err(ConnectionError::TransactionFailed(
+                    "custom program error: 0x1771".into(),
+                ))),
+        );
 
-            if is_writable {
-                accounts.push(AccountMeta::new(pubkey, false));
-            } else {
-                accounts.push(AccountMeta::new_readonly(pubkey, false));
-            }
-        }
+        let err = transport
+            .submit_versioned(&empty_message(&transport))
+            .await
+            .unwrap_err();
+
+        // The proposal exists and is approved, so the error carries it for
+        // retry_approval
+        let TransportError::PartialSubmit { result, .. } = err else {
+            panic!("expected a partial submit");
+        };
+        assert_eq!(result.proposal(), Some(&proposal));
+        assert_eq!(transport.connection().sent_transactions().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_check_status_finds_execution_signature() {
+        let (_, proposal, _) = mock_pdas();
+        let execution = Signature::from([2; 64]);
+        let transport = mock_transport(
+            1,
+            MockConnection::new()
+                .with_program_account(proposal, proposal_data(5, 1, 0, 0))
+                .with_signature_history(
+                    proposal,
+                    vec![SignatureInfo {
+                        signature: execution,
+                        slot: 1,
+                        error: None,
+                        block_time: Some(1_700_000_000),
+                    }],
+                ),
+        );
 
-        offset += 32;
+        let pending = SubmitResult::Pending {
+            proposal,
+            transaction_index: 10,
+            approvals: 1,
+            threshold: 1,
+        };
+        let result = transport.check_status(&pending).await.unwrap();
+
+        assert!(matches!(
+            result,
+            SubmitResult::Executed { signature, .. } if signature == execution
+        ));
+        assert_eq!(
+            transport
+                .connection()
+                .count(|c| *c == MockCall::GetSignaturesForAddress(proposal)),
+            1
+        );
     }
 
-    Ok(accounts)
+    #[tokio::test]
+    async fn test_check_status_pages_back_to_execution() {
+        let (_, proposal, _) = mock_pdas();
+        let entry = |byte: u8, block_time: i64| SignatureInfo {
+            signature: Signature::from([byte; 64]),
+            slot: 1,
+            error: None,
+            block_time: Some(block_time),
+        };
+        let pending = SubmitResult::Pending {
+            proposal,
+            transaction_index: 10,
+            approvals: 1,
+            threshold: 1,
+        };
+
+        // Later writes push the execution onto the second page
+        let history = vec![
+            entry(4, 1_700_000_300),
+            entry(3, 1_700_000_200),
+            entry(2, 1_700_000_000),
+            entry(1, 1_600_000_000),
+        ];
+        let transport = mock_transport(
+            1,
+            MockConnection::new()
+                .with_program_account(proposal, proposal_data(5, 1, 0, 0))
+                .with_signature_history(proposal, history.clone())
+                .with_signature_page_size(2),
+        );
+        let result = transport.check_status(&pending).await.unwrap();
+        assert!(matches!(
+            result,
+            SubmitResult::Executed { signature, .. } if signature == Signature::from([2; 64])
+        ));
+        assert_eq!(
+            transport
+                .connection()
+                .count(|c| *c == MockCall::GetSignaturesForAddress(proposal)),
+            2
+        );
+
+        // History that no longer reaches the execution is an error, not a
+        // default signature
+        let transport = mock_transport(
+            1,
+            MockConnection::new()
+                .with_program_account(proposal, proposal_data(5, 1, 0, 0))
+                .with_signature_history(proposal, history[..2].to_vec())
+                .with_signature_page_size(2),
+        );
+        assert!(transport.check_status(&pending).await.is_err());
+
+        // Stops once the history is older than the execution
+        let transport = mock_transport(
+            1,
+            MockConnection::new()
+                .with_program_account(proposal, proposal_data(5, 1, 0, 0))
+                .with_signature_history(proposal, vec![entry(1, 1_600_000_000); 3])
+                .with_signature_page_size(1),
+        );
+        assert!(transport.check_status(&pending).await.is_err());
+        assert_eq!(
+            transport
+                .connection()
+                .count(|c| *c == MockCall::GetSignaturesForAddress(proposal)),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_deferring_member_is_nested() {
+        let (_, proposal, _) = mock_pdas();
+        let transport = mock_member_transport(
+            2,
+            MockConnection::new().with_program_account(proposal, proposal_data(1, 1, 0, 0)),
+            PendingMember(Pubkey::new_unique()),
+        );
+
+        let result = transport
+            .submit_versioned(&empty_message(&transport))
+            .await
+            .unwrap();
+        let SubmitResult::Nested { outer, inner } = &result else {
+            panic!("expected a nested result");
+        };
+        assert!(matches!(
+            outer.as_ref(),
+            SubmitResult::Pending {
+                proposal: p,
+                transaction_index: 10,
+                approvals: 0,
+                ..
+            } if *p == proposal
+        ));
+        assert!(inner.is_pending());
+
+        // Nothing is sent until the member's own proposal executes
+        assert!(transport.connection().sent_transactions().is_empty());
+        let status = transport.check_status(&result).await.unwrap();
+        assert!(matches!(status, SubmitResult::Nested { .. }));
+        assert!(status.is_pending());
+    }
+
+    #[tokio::test]
+    async fn test_non_member_is_refused() {
+        let (multisig, _, _) = mock_pdas();
+        let connection = MockConnection::new().with_program_account(
+            multisig,
+            MultisigData::new(1, &[Pubkey::new_unique()]).encode(),
+        );
+        let transport = SquadsTransport::with_member_transport(
+            multisig,
+            0,
+            connection,
+            PendingMember(Pubkey::new_unique()),
+        )
+        .unwrap();
+
+        let err = transport
+            .submit_versioned_with(&empty_message(&transport), &SubmitOptions::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SquadsError::InsufficientPermissions { .. }));
+    }
+
+    #[test]
+    fn test_nest_partial() {
+        let pending = |transaction_index| SubmitResult::Pending {
+            proposal: Pubkey::new_unique(),
+            transaction_index,
+            approvals: 0,
+            threshold: 2,
+        };
+        let (outer, inner) = (pending(4), pending(9));
+
+        let err = nest_partial(
+            SquadsError::PartialSubmit {
+                result: Box::new(inner.clone()),
+                reason: "timeout".into(),
+            },
+            &outer,
+        );
+        let SquadsError::PartialSubmit { result, reason } = err else {
+            panic!("expected a partial submit");
+        };
+        assert_eq!(reason, "timeout");
+        let SubmitResult::Nested { outer: o, inner: i } = *result else {
+            panic!("expected a nested result");
+        };
+        assert_eq!(o.proposal(), outer.proposal());
+        assert_eq!(i.proposal(), inner.proposal());
+
+        assert!(matches!(
+            nest_partial(SquadsError::Rpc("down".into()), &outer),
+            SquadsError::Rpc(_)
+        ));
+    }
 }
@@ -0,0 +1,70 @@
+//! Error types for Trezor operations.
+
+use thiserror::Error;
+
+/// Errors that can occur during Trezor operations.
+#[derive(Error, Debug)]
+pub enum TrezorError {
+    /// Device not found or not connected.
+    #[error("Trezor device not connected")]
+    NotConnected,
+
+    /// Communication error with the device.
+    #[error("Trezor communication error: {0}")]
+    Communication(String),
+
+    /// Invalid response from device.
+    #[error("Invalid response from Trezor: {0}")]
+    InvalidResponse(String),
+
+    /// User rejected the operation on the device.
+    #[error("User rejected operation on Trezor")]
+    UserRejected,
+
+    /// Device is locked and asked for the PIN on the host.
+    ///
+    /// Model T and Safe 3 take the PIN on the device; this happens with
+    /// older models, which have no Solana support.
+    #[error("Trezor asked for the PIN on the host; unlock it on the device")]
+    PinRequired,
+
+    /// Device reported a failure.
+    #[error("Trezor failure ({code}): {message}")]
+    Failure {
+        /// Failure code from the firmware.
+        code: u32,
+        /// Failure message from the firmware.
+        message: String,
+    },
+
+    /// Operation the Solana firmware doesn't support.
+    #[error("Not supported by Trezor: {0}")]
+    Unsupported(String),
+
+    /// Invalid derivation path.
+    #[error("Invalid derivation path: {0}")]
+    InvalidPath(String),
+
+    /// USB error.
+    #[error("USB error: {0}")]
+    Usb(String),
+}
+
+/// Result type for Trezor operations.
+pub type Result<T> = std::result::Result<T, TrezorError>;
+
+impl From<TrezorError> for solana_actor::SignerError {
+    fn from(err: TrezorError) -> Self {
+        match err {
+            TrezorError::NotConnected => Self::DeviceNotFound,
+            TrezorError::UserRejected => Self::UserCancelled,
+            TrezorError::Communication(msg) => Self::DeviceError(msg),
+            TrezorError::InvalidResponse(msg) => Self::DeviceError(msg),
+            TrezorError::PinRequired => Self::DeviceError(err.to_string()),
+            TrezorError::Failure { .. } => Self::DeviceError(err.to_string()),
+            TrezorError::Unsupported(msg) => Self::SigningFailed(msg),
+            TrezorError::InvalidPath(msg) => Self::InvalidKey(msg),
+            TrezorError::Usb(msg) => Self::DeviceError(msg),
+        }
+    }
+}
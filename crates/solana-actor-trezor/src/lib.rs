@@ -0,0 +1,71 @@
+//! Trezor hardware wallet signer for Solana.
+//!
+//! This crate provides [`TrezorSigner`], an implementation of the
+//! [`MessageSigner`] and [`TransactionSigner`] traits that communicates
+//! with Trezor hardware wallets over USB.
+//!
+//! # Features
+//!
+//! - **Hardware security** - Private keys never leave the Trezor device
+//! - **User confirmation** - All signing requires confirmation on the device
+//! - **BIP-44 paths** - Standard derivation path support
+//! - **Trait implementations** - Implements `MessageSigner` and `TransactionSigner`
+//! - **On-device PIN and passphrase** - Nothing secret is typed on the host
+//!
+//! # Requirements
+//!
+//! - Trezor Model T, Safe 3 or Safe 5 with firmware that supports Solana
+//! - USB connection to the device, with Trezor Suite and Trezor Bridge closed
+//!   so the interface can be claimed
+//!
+//! The Trezor firmware only signs transactions. [`MessageSigner::sign_message`]
+//! always fails with [`SignerError::SigningFailed`].
+//!
+//! # Example
+//!
+//! ```ignore
+//! use solana_actor_trezor::TrezorSigner;
+//! use solana_actor::TransactionSigner;
+//!
+//! // Connect with default derivation path (44'/501'/0'/0')
+//! let signer = TrezorSigner::connect()?;
+//! println!("Trezor pubkey: {}", signer.pubkey_base58());
+//!
+//! // Sign a transaction (user must confirm on device)
+//! let signature = signer.sign_transaction(&tx_message)?;
+//! ```
+//!
+//! # Custom Derivation Path
+//!
+//! ```ignore
+//! use solana_actor_trezor::TrezorSigner;
+//!
+//! // Use a different account index
+//! let signer = TrezorSigner::connect_with_path("44'/501'/1'/0'")?;
+//! ```
+//!
+//! # Using with Transports
+//!
+//! ```ignore
+//! use solana_actor_trezor::TrezorSigner;
+//! use solana_actor::{DirectTransport, WalletTransport};
+//!
+//! let signer = TrezorSigner::connect()?;
+//! let transport = DirectTransport::new(signer);
+//!
+//! // The transport will use spawn_blocking for the signing operation
+//! let result = transport.submit(&tx_message).await?;
+//! ```
+
+mod derivation;
+mod error;
+mod proto;
+mod signer;
+mod transport;
+
+pub use derivation::{DEFAULT_PATH, format_path, parse_path};
+pub use error::{Result, TrezorError};
+pub use signer::TrezorSigner;
+
+// Re-export traits for convenience
+pub use solana_actor::{MessageSigner, SignerError, TransactionSigner};
@@ -0,0 +1,257 @@
+//! Trezor protobuf messages.
+//!
+//! Only the handful of messages needed for Solana signing are supported, so
+//! they are encoded by hand instead of generating code from the Trezor
+//! protobuf definitions.
+
+use crate::error::{Result, TrezorError};
+
+// Message types from trezor-common's messages.proto
+pub const INITIALIZE: u16 = 0;
+pub const FAILURE: u16 = 3;
+pub const FEATURES: u16 = 17;
+pub const PIN_MATRIX_REQUEST: u16 = 18;
+pub const BUTTON_REQUEST: u16 = 26;
+pub const BUTTON_ACK: u16 = 27;
+pub const PASSPHRASE_REQUEST: u16 = 41;
+pub const PASSPHRASE_ACK: u16 = 42;
+pub const SOLANA_GET_PUBLIC_KEY: u16 = 900;
+pub const SOLANA_PUBLIC_KEY: u16 = 901;
+pub const SOLANA_SIGN_TX: u16 = 904;
+pub const SOLANA_TX_SIGNATURE: u16 = 905;
+
+// Failure codes
+const FAILURE_ACTION_CANCELLED: u32 = 4;
+const FAILURE_PIN_CANCELLED: u32 = 6;
+
+// Protobuf wire types
+const WIRE_VARINT: u8 = 0;
+const WIRE_FIXED64: u8 = 1;
+const WIRE_LEN: u8 = 2;
+const WIRE_FIXED32: u8 = 5;
+
+/// A message to or from the device.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    /// Message type.
+    pub kind: u16,
+    /// Protobuf-encoded body.
+    pub payload: Vec<u8>,
+}
+
+impl Message {
+    /// An empty message.
+    pub fn empty(kind: u16) -> Self {
+        Self {
+            kind,
+            payload: Vec::new(),
+        }
+    }
+
+    /// `SolanaGetPublicKey` for a derivation path.
+    pub fn get_public_key(derivation_path: &[u32]) -> Self {
+        let mut body = Encoder::default();
+        body.path(1, derivation_path);
+        body.bool(2, false);
+        body.into_message(SOLANA_GET_PUBLIC_KEY)
+    }
+
+    /// `SolanaSignTx` for a serialized transaction message.
+    pub fn sign_tx(derivation_path: &[u32], message: &[u8]) -> Self {
+        let mut body = Encoder::default();
+        body.path(1, derivation_path);
+        body.bytes(2, message);
+        body.into_message(SOLANA_SIGN_TX)
+    }
+
+    /// `PassphraseAck` telling the device to take the passphrase itself.
+    pub fn passphrase_on_device() -> Self {
+        let mut body = Encoder::default();
+        body.bool(3, true);
+        body.into_message(PASSPHRASE_ACK)
+    }
+
+    /// The `bytes` field `field` of the body.
+    pub fn bytes_field(&self, field: u32) -> Result<Vec<u8>> {
+        decode(&self.payload)?
+            .into_iter()
+            .find_map(|(number, value)| match value {
+                Value::Bytes(bytes) if number == field => Some(bytes),
+                _ => None,
+            })
+            .ok_or_else(|| TrezorError::InvalidResponse(format!("Missing field {}", field)))
+    }
+
+    /// The error a `Failure` message stands for.
+    pub fn failure(&self) -> TrezorError {
+        let mut code = 0;
+        let mut message = String::new();
+        for (number, value) in decode(&self.payload).unwrap_or_default() {
+            match (number, value) {
+                (1, Value::Varint(value)) => code = value as u32,
+                (2, Value::Bytes(bytes)) => message = String::from_utf8_lossy(&bytes).into(),
+                _ => {}
+            }
+        }
+
+        match code {
+            FAILURE_ACTION_CANCELLED | FAILURE_PIN_CANCELLED => TrezorError::UserRejected,
+            _ => TrezorError::Failure { code, message },
+        }
+    }
+}
+
+/// A decoded field value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Value {
+    Varint(u64),
+    Bytes(Vec<u8>),
+    Fixed,
+}
+
+/// Protobuf encoder for message bodies.
+#[derive(Default)]
+struct Encoder(Vec<u8>);
+
+impl Encoder {
+    fn key(&mut self, field: u32, wire_type: u8) {
+        self.varint((u64::from(field) << 3) | u64::from(wire_type));
+    }
+
+    fn varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.0.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        self.0.push(value as u8);
+    }
+
+    /// A repeated `uint32` field, unpacked as in Trezor's proto2 definitions.
+    fn path(&mut self, field: u32, components: &[u32]) {
+        for &component in components {
+            self.key(field, WIRE_VARINT);
+            self.varint(u64::from(component));
+        }
+    }
+
+    fn bool(&mut self, field: u32, value: bool) {
+        self.key(field, WIRE_VARINT);
+        self.varint(u64::from(value));
+    }
+
+    fn bytes(&mut self, field: u32, value: &[u8]) {
+        self.key(field, WIRE_LEN);
+        self.varint(value.len() as u64);
+        self.0.extend_from_slice(value);
+    }
+
+    fn into_message(self, kind: u16) -> Message {
+        Message {
+            kind,
+            payload: self.0,
+        }
+    }
+}
+
+/// Decode a message body into (field number, value) pairs.
+fn decode(mut data: &[u8]) -> Result<Vec<(u32, Value)>> {
+    let mut fields = Vec::new();
+    while !data.is_empty() {
+        let key = read_varint(&mut data)?;
+        let field = (key >> 3) as u32;
+        let value = match (key & 0x7) as u8 {
+            WIRE_VARINT => Value::Varint(read_varint(&mut data)?),
+            WIRE_LEN => {
+                let len = read_varint(&mut data)? as usize;
+                if len > data.len() {
+                    return Err(TrezorError::InvalidResponse("Truncated field".into()));
+                }
+                let (bytes, rest) = data.split_at(len);
+                data = rest;
+                Value::Bytes(bytes.to_vec())
+            }
+            WIRE_FIXED64 => skip(&mut data, 8)?,
+            WIRE_FIXED32 => skip(&mut data, 4)?,
+            other => {
+                return Err(TrezorError::InvalidResponse(format!(
+                    "Unknown wire type {}",
+                    other
+                )));
+            }
+        };
+        fields.push((field, value));
+    }
+    Ok(fields)
+}
+
+fn read_varint(data: &mut &[u8]) -> Result<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = data
+            .split_first()
+            .ok_or_else(|| TrezorError::InvalidResponse("Truncated varint".into()))?;
+        *data = rest;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(TrezorError::InvalidResponse("Varint too long".into()))
+}
+
+fn skip(data: &mut &[u8], len: usize) -> Result<Value> {
+    if data.len() < len {
+        return Err(TrezorError::InvalidResponse("Truncated field".into()));
+    }
+    *data = &data[len..];
+    Ok(Value::Fixed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_sign_tx() {
+        let message = Message::sign_tx(&[44 | 0x80000000, 501 | 0x80000000], &[1, 2, 3]);
+        assert_eq!(message.kind, SOLANA_SIGN_TX);
+        assert_eq!(
+            decode(&message.payload).unwrap(),
+            [
+                (1, Value::Varint(44 | 0x80000000)),
+                (1, Value::Varint(501 | 0x80000000)),
+                (2, Value::Bytes(vec![1, 2, 3])),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decode_response() {
+        let mut body = Encoder::default();
+        body.bytes(1, &[7; 64]);
+        let response = body.into_message(SOLANA_TX_SIGNATURE);
+        assert_eq!(response.bytes_field(1).unwrap(), [7; 64]);
+        assert!(response.bytes_field(2).is_err());
+    }
+
+    #[test]
+    fn test_decode_failure() {
+        let mut body = Encoder::default();
+        body.varint(1 << 3);
+        body.varint(u64::from(FAILURE_ACTION_CANCELLED));
+        body.bytes(2, b"Cancelled");
+        assert!(matches!(
+            body.into_message(FAILURE).failure(),
+            TrezorError::UserRejected
+        ));
+
+        let mut body = Encoder::default();
+        body.varint(1 << 3);
+        body.varint(3);
+        body.bytes(2, b"Forbidden key path");
+        assert!(matches!(
+            body.into_message(FAILURE).failure(),
+            TrezorError::Failure { code: 3, message } if message == "Forbidden key path"
+        ));
+    }
+}
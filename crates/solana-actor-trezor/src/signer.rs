@@ -0,0 +1,176 @@
+//! Trezor hardware wallet signer implementation.
+
+use solana_actor::{MessageSigner, SignerError, TransactionSigner};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+use crate::derivation::{DEFAULT_PATH, format_path, parse_path};
+use crate::error::{Result, TrezorError};
+use crate::transport;
+
+/// Trezor hardware wallet signer.
+///
+/// This signer communicates with a connected Trezor Model T, Safe 3 or Safe 5
+/// running firmware with Solana support.
+///
+/// # Security
+///
+/// - Private keys never leave the Trezor device
+/// - User must physically confirm each signing operation on the device
+/// - PIN and passphrase are entered on the device, never on the host
+/// - Implements `is_interactive() -> true` to indicate user interaction required
+///
+/// # Example
+///
+/// ```ignore
+/// use solana_actor_trezor::TrezorSigner;
+/// use solana_actor::TransactionSigner;
+///
+/// // Connect with default derivation path
+/// let signer = TrezorSigner::connect()?;
+/// println!("Trezor pubkey: {}", signer.pubkey_base58());
+///
+/// // Sign a transaction (user must confirm on device)
+/// let signature = signer.sign_transaction(&tx_message)?;
+/// ```
+pub struct TrezorSigner {
+    derivation_path: Vec<u32>,
+    pubkey: Pubkey,
+}
+
+impl TrezorSigner {
+    /// Connect to a Trezor device with the default derivation path.
+    ///
+    /// The default path is `44'/501'/0'/0'` (BIP-44 for Solana).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - No Trezor device is connected
+    /// - The device is locked and the user doesn't unlock it
+    /// - Communication with the device fails
+    pub fn connect() -> Result<Self> {
+        Self::connect_with_path(DEFAULT_PATH)
+    }
+
+    /// Connect to a Trezor device with a custom derivation path.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - BIP-44 derivation path (e.g., "44'/501'/0'/0'")
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path is invalid or device communication fails.
+    pub fn connect_with_path(path: &str) -> Result<Self> {
+        let derivation_path = parse_path(path)?;
+        Self::connect_with_parsed_path(derivation_path)
+    }
+
+    /// Connect with an already-parsed derivation path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if device communication fails or the device is not available.
+    pub fn connect_with_parsed_path(derivation_path: Vec<u32>) -> Result<Self> {
+        let pubkey_bytes = transport::get_pubkey(&derivation_path)?;
+        let pubkey = Pubkey::new_from_array(pubkey_bytes);
+
+        Ok(Self {
+            derivation_path,
+            pubkey,
+        })
+    }
+
+    /// Get the derivation path used by this signer.
+    pub fn derivation_path(&self) -> String {
+        format_path(&self.derivation_path)
+    }
+
+    /// Get the public key bytes.
+    pub fn pubkey_bytes(&self) -> [u8; 32] {
+        self.pubkey.to_bytes()
+    }
+
+    /// Get the public key as a base58 string.
+    pub fn pubkey_base58(&self) -> String {
+        self.pubkey.to_string()
+    }
+
+    /// Sign a transaction message and return raw signature bytes.
+    ///
+    /// This is a lower-level method that returns the signature as a byte array.
+    /// For most use cases, use [`TransactionSigner::sign_transaction`] instead.
+    ///
+    /// # Note
+    ///
+    /// The user must physically confirm the signing operation on the Trezor device.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if device communication fails or the user rejects the signing.
+    pub fn sign(&self, message: &[u8]) -> Result<[u8; 64]> {
+        transport::sign_message(&self.derivation_path, message)
+    }
+}
+
+impl MessageSigner for TrezorSigner {
+    fn pubkey(&self) -> Pubkey {
+        self.pubkey
+    }
+
+    /// Always fails: the Trezor Solana firmware only signs transactions, not
+    /// off-chain messages, and signing arbitrary bytes as a transaction would
+    /// show the user a meaningless review screen.
+    fn sign_message(&self, _message: &[u8]) -> std::result::Result<Signature, SignerError> {
+        Err(
+            TrezorError::Unsupported("Trezor firmware does not sign off-chain messages".into())
+                .into(),
+        )
+    }
+}
+
+impl TransactionSigner for TrezorSigner {
+    fn pubkey(&self) -> Pubkey {
+        self.pubkey
+    }
+
+    fn sign_transaction(&self, message: &[u8]) -> std::result::Result<Signature, SignerError> {
+        let sig_bytes = self.sign(message).map_err(SignerError::from)?;
+        Ok(Signature::from(sig_bytes))
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+}
+
+// Note: Tests for TrezorSigner require a physical device and are marked as ignored.
+// Run them manually with: cargo test -p solana-actor-trezor -- --ignored
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[ignore]
+    fn test_connect_default() {
+        let signer = TrezorSigner::connect().expect("Failed to connect to Trezor");
+        println!("Connected to Trezor: {}", signer.pubkey_base58());
+        println!("Derivation path: {}", signer.derivation_path());
+    }
+
+    #[test]
+    #[ignore]
+    fn test_sign_transaction() {
+        use solana_sdk::{hash::Hash, message::Message};
+
+        let signer = TrezorSigner::connect().expect("Failed to connect to Trezor");
+        let message = Message::new_with_blockhash(&[], Some(&signer.pubkey), &Hash::default());
+        let bytes = message.serialize();
+
+        println!("Please confirm on your Trezor device...");
+        let sig = signer
+            .sign_transaction(&bytes)
+            .expect("Failed to sign transaction");
+        assert!(sig.verify(signer.pubkey.as_ref(), &bytes));
+    }
+}
@@ -0,0 +1,224 @@
+//! Trezor USB transport layer.
+//!
+//! This module handles the low-level framing of protobuf messages over the
+//! Trezor's WebUSB interface, and the button and passphrase prompts the
+//! device sends while a request is in progress.
+
+use std::time::Duration;
+
+use rusb::{DeviceHandle, GlobalContext};
+
+use crate::error::{Result, TrezorError};
+use crate::proto::{self, Message};
+
+// Trezor Model T, Safe 3 and Safe 5 (firmware mode) USB IDs
+const TREZOR_VID: u16 = 0x1209;
+const TREZOR_PID: u16 = 0x53c1;
+
+// WebUSB interface and endpoints
+const INTERFACE: u8 = 0;
+const ENDPOINT_OUT: u8 = 0x01;
+const ENDPOINT_IN: u8 = 0x81;
+
+/// Size of a USB packet
+const PACKET_LEN: usize = 64;
+/// Header of a message's first packet: `?##`, type (u16) and length (u32)
+const HEADER_LEN: usize = 9;
+
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+/// Long enough for the user to review and confirm on the device
+const READ_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Get the public key from the Trezor device.
+pub fn get_pubkey(derivation_path: &[u32]) -> Result<[u8; 32]> {
+    let device = Device::open()?;
+    let response = device.call(
+        Message::get_public_key(derivation_path),
+        proto::SOLANA_PUBLIC_KEY,
+    )?;
+
+    let bytes = response.bytes_field(1)?;
+    bytes
+        .try_into()
+        .map_err(|_| TrezorError::InvalidResponse("Public key is not 32 bytes".into()))
+}
+
+/// Sign a transaction message using the Trezor device.
+pub fn sign_message(derivation_path: &[u32], message: &[u8]) -> Result<[u8; 64]> {
+    let device = Device::open()?;
+    let response = device.call(
+        Message::sign_tx(derivation_path, message),
+        proto::SOLANA_TX_SIGNATURE,
+    )?;
+
+    let bytes = response.bytes_field(1)?;
+    bytes
+        .try_into()
+        .map_err(|_| TrezorError::InvalidResponse("Signature is not 64 bytes".into()))
+}
+
+/// An open Trezor with its interface claimed.
+struct Device {
+    handle: DeviceHandle<GlobalContext>,
+}
+
+impl Device {
+    /// Open the first connected Trezor and start a session.
+    fn open() -> Result<Self> {
+        let devices = rusb::devices().map_err(|e| TrezorError::Usb(e.to_string()))?;
+
+        for device in devices.iter() {
+            let Ok(descriptor) = device.device_descriptor() else {
+                continue;
+            };
+            if descriptor.vendor_id() != TREZOR_VID || descriptor.product_id() != TREZOR_PID {
+                continue;
+            }
+
+            let mut handle = device.open().map_err(|e| TrezorError::Usb(e.to_string()))?;
+            handle
+                .claim_interface(INTERFACE)
+                .map_err(|e| TrezorError::Usb(format!("Trezor is in use: {}", e)))?;
+
+            let device = Self { handle };
+            device.call(Message::empty(proto::INITIALIZE), proto::FEATURES)?;
+            return Ok(device);
+        }
+
+        Err(TrezorError::NotConnected)
+    }
+
+    /// Send a request and wait for the `expected` response.
+    ///
+    /// Button requests are acknowledged so the device can prompt the user,
+    /// and a passphrase is always entered on the device.
+    fn call(&self, request: Message, expected: u16) -> Result<Message> {
+        self.write(&request)?;
+        loop {
+            let response = self.read()?;
+            match response.kind {
+                kind if kind == expected => return Ok(response),
+                proto::BUTTON_REQUEST => self.write(&Message::empty(proto::BUTTON_ACK))?,
+                proto::PASSPHRASE_REQUEST => self.write(&Message::passphrase_on_device())?,
+                proto::PIN_MATRIX_REQUEST => return Err(TrezorError::PinRequired),
+                proto::FAILURE => return Err(response.failure()),
+                other => {
+                    return Err(TrezorError::InvalidResponse(format!(
+                        "Unexpected message type {}",
+                        other
+                    )));
+                }
+            }
+        }
+    }
+
+    fn write(&self, message: &Message) -> Result<()> {
+        for packet in encode_packets(message) {
+            self.handle
+                .write_interrupt(ENDPOINT_OUT, &packet, WRITE_TIMEOUT)
+                .map_err(|e| TrezorError::Communication(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    fn read(&self) -> Result<Message> {
+        read_message(|| {
+            let mut packet = [0u8; PACKET_LEN];
+            let len = self
+                .handle
+                .read_interrupt(ENDPOINT_IN, &mut packet, READ_TIMEOUT)
+                .map_err(|e| match e {
+                    rusb::Error::NoDevice => TrezorError::NotConnected,
+                    e => TrezorError::Communication(e.to_string()),
+                })?;
+            if len != PACKET_LEN {
+                return Err(TrezorError::InvalidResponse("Short USB packet".into()));
+            }
+            Ok(packet)
+        })
+    }
+}
+
+impl Drop for Device {
+    fn drop(&mut self) {
+        let _ = self.handle.release_interface(INTERFACE);
+    }
+}
+
+/// Split a message into USB packets.
+fn encode_packets(message: &Message) -> Vec<[u8; PACKET_LEN]> {
+    let mut data = Vec::with_capacity(HEADER_LEN + message.payload.len());
+    data.extend_from_slice(b"##");
+    data.extend_from_slice(&message.kind.to_be_bytes());
+    data.extend_from_slice(&(message.payload.len() as u32).to_be_bytes());
+    data.extend_from_slice(&message.payload);
+
+    // Every packet starts with '?'; the first also carries the header
+    data.chunks(PACKET_LEN - 1)
+        .map(|chunk| {
+            let mut packet = [0u8; PACKET_LEN];
+            packet[0] = b'?';
+            packet[1..=chunk.len()].copy_from_slice(chunk);
+            packet
+        })
+        .collect()
+}
+
+/// Reassemble a message from USB packets.
+fn read_message(mut next_packet: impl FnMut() -> Result<[u8; PACKET_LEN]>) -> Result<Message> {
+    let first = next_packet()?;
+    if &first[..3] != b"?##" {
+        return Err(TrezorError::InvalidResponse(
+            "Missing message header".into(),
+        ));
+    }
+    let kind = u16::from_be_bytes([first[3], first[4]]);
+    let len = u32::from_be_bytes([first[5], first[6], first[7], first[8]]) as usize;
+
+    let mut payload = first[HEADER_LEN..].to_vec();
+    while payload.len() < len {
+        let packet = next_packet()?;
+        if packet[0] != b'?' {
+            return Err(TrezorError::InvalidResponse(
+                "Bad continuation packet".into(),
+            ));
+        }
+        payload.extend_from_slice(&packet[1..]);
+    }
+    payload.truncate(len);
+
+    Ok(Message { kind, payload })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_packets_round_trip() {
+        let message = Message::sign_tx(&[44 | 0x80000000, 501 | 0x80000000], &[9; 300]);
+        let packets = encode_packets(&message);
+        assert_eq!(packets.len(), 6);
+        assert_eq!(&packets[0][..5], &[b'?', b'#', b'#', 0x03, 0x88]);
+
+        let mut packets = packets.into_iter();
+        let decoded = read_message(|| {
+            packets
+                .next()
+                .ok_or_else(|| TrezorError::Communication("No more packets".into()))
+        })
+        .unwrap();
+        assert_eq!(decoded, message);
+        assert!(packets.next().is_none());
+    }
+
+    #[test]
+    fn test_rejects_missing_header() {
+        let mut packet = [0u8; PACKET_LEN];
+        packet[0] = b'?';
+        assert!(matches!(
+            read_message(|| Ok(packet)),
+            Err(TrezorError::InvalidResponse(_))
+        ));
+    }
+}
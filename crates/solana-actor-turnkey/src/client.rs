@@ -0,0 +1,431 @@
+//! Async HTTP client for the Turnkey API.
+//!
+//! Signing is a Turnkey *activity*: `sign_raw_payload` submits an
+//! `ACTIVITY_TYPE_SIGN_RAW_PAYLOAD_V2` for the message bytes, and
+//! `get_activity` polls it. When the organization's policies require
+//! approval, the activity stays in `ACTIVITY_STATUS_CONSENSUS_NEEDED` until
+//! enough approvers vote for it; the Ed25519 signature is returned in the
+//! activity result once it completes.
+
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+use crate::error::{Result, TurnkeyError};
+use crate::stamp::{ApiKey, STAMP_HEADER};
+
+/// Default Turnkey API base URL.
+pub const DEFAULT_BASE_URL: &str = "https://api.turnkey.com";
+
+/// Default interval between activity polls.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Default time to wait for approval when blocking.
+pub const DEFAULT_APPROVAL_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Default timeout for a single HTTP request.
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Configuration for a Turnkey signer.
+#[derive(Debug, Clone)]
+pub struct TurnkeyConfig {
+    /// API base URL.
+    pub base_url: String,
+    /// Turnkey organization (or sub-organization) ID.
+    pub organization_id: String,
+    /// API key that stamps requests.
+    pub api_key: ApiKey,
+    /// Public key of the Turnkey wallet account or private key.
+    pub pubkey: Pubkey,
+    /// Interval between activity polls while awaiting approval.
+    pub poll_interval: Duration,
+    /// Maximum time a blocking signer waits for approval.
+    pub approval_timeout: Duration,
+    /// Timeout for a single HTTP request.
+    pub request_timeout: Duration,
+}
+
+impl TurnkeyConfig {
+    /// Create a new config with the default API URL and timeouts.
+    pub fn new(organization_id: impl Into<String>, api_key: ApiKey, pubkey: Pubkey) -> Self {
+        Self {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            organization_id: organization_id.into(),
+            api_key,
+            pubkey,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            approval_timeout: DEFAULT_APPROVAL_TIMEOUT,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+        }
+    }
+
+    /// Set the API base URL.
+    pub fn base_url(mut self, url: impl Into<String>) -> Self {
+        self.base_url = url.into();
+        self
+    }
+
+    /// Set the interval between activity polls.
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Set the maximum time to wait for approval.
+    pub fn approval_timeout(mut self, timeout: Duration) -> Self {
+        self.approval_timeout = timeout;
+        self
+    }
+
+    /// Set the timeout for a single HTTP request.
+    pub fn request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = timeout;
+        self
+    }
+}
+
+/// Status of a Turnkey activity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum ActivityStatus {
+    /// Submitted but not yet processed.
+    #[serde(rename = "ACTIVITY_STATUS_CREATED")]
+    Created,
+    /// Being processed.
+    #[serde(rename = "ACTIVITY_STATUS_PENDING")]
+    Pending,
+    /// Awaiting approval votes required by a policy.
+    #[serde(rename = "ACTIVITY_STATUS_CONSENSUS_NEEDED")]
+    ConsensusNeeded,
+    /// Completed; the result holds the signature.
+    #[serde(rename = "ACTIVITY_STATUS_COMPLETED")]
+    Completed,
+    /// Failed, e.g. denied by a policy.
+    #[serde(rename = "ACTIVITY_STATUS_FAILED")]
+    Failed,
+    /// Rejected by an approver.
+    #[serde(rename = "ACTIVITY_STATUS_REJECTED")]
+    Rejected,
+}
+
+impl ActivityStatus {
+    /// Whether the activity may still complete.
+    pub fn is_pending(self) -> bool {
+        matches!(self, Self::Created | Self::Pending | Self::ConsensusNeeded)
+    }
+}
+
+/// A signing activity tracked by Turnkey.
+#[derive(Debug, Clone)]
+pub struct Activity {
+    /// Turnkey activity ID.
+    pub id: String,
+    /// Current status.
+    pub status: ActivityStatus,
+    /// The signature, once completed.
+    pub signature: Option<Signature>,
+    /// Approval votes cast so far.
+    pub approvals: u32,
+    /// Failure message, if the activity failed.
+    pub reason: Option<String>,
+}
+
+/// Wire format of an activity.
+#[derive(Deserialize)]
+struct RawActivity {
+    id: String,
+    status: ActivityStatus,
+    #[serde(default)]
+    result: Option<RawResult>,
+    #[serde(default)]
+    votes: Vec<Value>,
+    #[serde(default)]
+    failure: Option<RawFailure>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawResult {
+    #[serde(default)]
+    sign_raw_payload_result: Option<RawSignature>,
+}
+
+#[derive(Deserialize)]
+struct RawSignature {
+    r: String,
+    s: String,
+}
+
+#[derive(Deserialize)]
+struct RawFailure {
+    #[serde(default)]
+    message: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ActivityResponse {
+    activity: RawActivity,
+}
+
+impl TryFrom<RawActivity> for Activity {
+    type Error = TurnkeyError;
+
+    fn try_from(raw: RawActivity) -> Result<Self> {
+        let signature = match raw.result.and_then(|r| r.sign_raw_payload_result) {
+            Some(sig) => {
+                let mut bytes = hex::decode(&sig.r)
+                    .map_err(|e| TurnkeyError::InvalidResponse(e.to_string()))?;
+                bytes.extend(
+                    hex::decode(&sig.s)
+                        .map_err(|e| TurnkeyError::InvalidResponse(e.to_string()))?,
+                );
+                Some(Signature::try_from(bytes.as_slice()).map_err(|_| {
+                    TurnkeyError::InvalidResponse("Signature must be 64 bytes".into())
+                })?)
+            }
+            None => None,
+        };
+
+        if raw.status == ActivityStatus::Completed && signature.is_none() {
+            return Err(TurnkeyError::InvalidResponse(
+                "Completed activity is missing a signature".into(),
+            ));
+        }
+
+        Ok(Self {
+            id: raw.id,
+            status: raw.status,
+            signature,
+            approvals: raw.votes.len() as u32,
+            reason: raw.failure.and_then(|f| f.message),
+        })
+    }
+}
+
+/// Async client for the Turnkey API.
+#[derive(Debug, Clone)]
+pub struct TurnkeyClient {
+    config: TurnkeyConfig,
+    http: reqwest::Client,
+}
+
+impl TurnkeyClient {
+    /// Create a new client from a config.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be built.
+    pub fn new(config: TurnkeyConfig) -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(config.request_timeout)
+            .build()?;
+        Ok(Self { config, http })
+    }
+
+    /// Get the client configuration.
+    pub fn config(&self) -> &TurnkeyConfig {
+        &self.config
+    }
+
+    /// Submit a transaction message for signing.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or the response is malformed.
+    pub async fn sign_raw_payload(&self, message: &[u8]) -> Result<Activity> {
+        let response: ActivityResponse = self
+            .post(
+                "/public/v1/submit/sign_raw_payload",
+                json!({
+                    "type": "ACTIVITY_TYPE_SIGN_RAW_PAYLOAD_V2",
+                    "timestampMs": timestamp_ms(),
+                    "organizationId": self.config.organization_id,
+                    "parameters": {
+                        "signWith": self.config.pubkey.to_string(),
+                        "payload": hex::encode(message),
+                        "encoding": "PAYLOAD_ENCODING_HEXADECIMAL",
+                        "hashFunction": "HASH_FUNCTION_NOT_APPLICABLE",
+                    },
+                }),
+            )
+            .await?;
+        response.activity.try_into()
+    }
+
+    /// Fetch the current state of an activity.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or the response is malformed.
+    pub async fn get_activity(&self, activity_id: &str) -> Result<Activity> {
+        let response: ActivityResponse = self
+            .post(
+                "/public/v1/query/get_activity",
+                json!({
+                    "organizationId": self.config.organization_id,
+                    "activityId": activity_id,
+                }),
+            )
+            .await?;
+        response.activity.try_into()
+    }
+
+    /// Check a completed activity and return its verified signature.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TurnkeyError::Rejected`] if the activity failed or was
+    /// rejected, and [`TurnkeyError::InvalidSignature`] if the signature
+    /// doesn't verify for the configured public key.
+    pub fn signature(&self, activity: &Activity, message: &[u8]) -> Result<Option<Signature>> {
+        match activity.status {
+            ActivityStatus::Completed => {
+                let signature = activity
+                    .signature
+                    .ok_or_else(|| TurnkeyError::InvalidResponse("Missing signature".into()))?;
+                if !signature.verify(self.config.pubkey.as_ref(), message) {
+                    return Err(TurnkeyError::InvalidSignature(activity.id.clone()));
+                }
+                Ok(Some(signature))
+            }
+            ActivityStatus::Failed | ActivityStatus::Rejected => Err(TurnkeyError::Rejected {
+                activity_id: activity.id.clone(),
+                status: if activity.status == ActivityStatus::Failed {
+                    "failed".into()
+                } else {
+                    "rejected".into()
+                },
+                reason: activity
+                    .reason
+                    .clone()
+                    .unwrap_or_else(|| "no reason given".into()),
+            }),
+            _ => Ok(None),
+        }
+    }
+
+    /// Poll an activity until it completes, fails, or `timeout` elapses.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TurnkeyError::Rejected`] if the activity is rejected, or
+    /// [`TurnkeyError::ApprovalTimeout`] if approval is not granted in time.
+    pub async fn wait_for_signature(
+        &self,
+        activity: Activity,
+        message: &[u8],
+        timeout: Duration,
+    ) -> Result<Signature> {
+        let deadline = Instant::now() + timeout;
+        let mut current = activity;
+
+        loop {
+            if let Some(signature) = self.signature(&current, message)? {
+                return Ok(signature);
+            }
+            if Instant::now() >= deadline {
+                return Err(TurnkeyError::ApprovalTimeout(current.id));
+            }
+            tokio::time::sleep(self.config.poll_interval).await;
+            current = self.get_activity(&current.id).await?;
+        }
+    }
+
+    async fn post<T: DeserializeOwned>(&self, path: &str, body: Value) -> Result<T> {
+        // The stamp signs the exact bytes sent
+        let body =
+            serde_json::to_vec(&body).map_err(|e| TurnkeyError::InvalidResponse(e.to_string()))?;
+        let response = self
+            .http
+            .post(format!(
+                "{}{}",
+                self.config.base_url.trim_end_matches('/'),
+                path
+            ))
+            .header(STAMP_HEADER, self.config.api_key.stamp(&body))
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body)
+            .send()
+            .await?;
+
+        let status = response.status();
+        if matches!(
+            status,
+            reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN
+        ) {
+            return Err(TurnkeyError::Unauthorized);
+        }
+
+        let value: Value = response.json().await?;
+        if !status.is_success() {
+            return Err(TurnkeyError::Api {
+                code: value.get("code").and_then(Value::as_i64).unwrap_or(0),
+                message: value
+                    .get("message")
+                    .and_then(Value::as_str)
+                    .unwrap_or("unknown error")
+                    .to_string(),
+            });
+        }
+        serde_json::from_value(value).map_err(|e| TurnkeyError::InvalidResponse(e.to_string()))
+    }
+}
+
+/// Current time in milliseconds, as Turnkey expects it.
+fn timestamp_ms() -> String {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(value: Value) -> Result<Activity> {
+        serde_json::from_value::<ActivityResponse>(value)
+            .map_err(|e| TurnkeyError::InvalidResponse(e.to_string()))?
+            .activity
+            .try_into()
+    }
+
+    #[test]
+    fn test_parse_consensus_needed() {
+        let activity = parse(json!({
+            "activity": {
+                "id": "act-1",
+                "status": "ACTIVITY_STATUS_CONSENSUS_NEEDED",
+                "votes": [{ "selection": "VOTE_SELECTION_APPROVED" }],
+            }
+        }))
+        .unwrap();
+        assert_eq!(activity.status, ActivityStatus::ConsensusNeeded);
+        assert!(activity.status.is_pending());
+        assert_eq!(activity.approvals, 1);
+        assert!(activity.signature.is_none());
+    }
+
+    #[test]
+    fn test_parse_completed() {
+        let activity = parse(json!({
+            "activity": {
+                "id": "act-2",
+                "status": "ACTIVITY_STATUS_COMPLETED",
+                "result": { "signRawPayloadResult": { "r": "01".repeat(32), "s": "02".repeat(32), "v": "00" } },
+            }
+        }))
+        .unwrap();
+        let signature = activity.signature.unwrap();
+        assert_eq!(&signature.as_ref()[..32], &[1u8; 32]);
+        assert_eq!(&signature.as_ref()[32..], &[2u8; 32]);
+
+        let missing = parse(json!({
+            "activity": { "id": "act-3", "status": "ACTIVITY_STATUS_COMPLETED" }
+        }));
+        assert!(matches!(missing, Err(TurnkeyError::InvalidResponse(_))));
+    }
+}
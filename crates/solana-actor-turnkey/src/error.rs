@@ -0,0 +1,84 @@
+//! Error types for Turnkey signer operations.
+
+use thiserror::Error;
+
+/// Errors that can occur when signing through Turnkey.
+#[derive(Error, Debug)]
+pub enum TurnkeyError {
+    /// HTTP transport error.
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// Turnkey returned an error response.
+    #[error("Turnkey error {code}: {message}")]
+    Api {
+        /// Turnkey error code.
+        code: i64,
+        /// Error message from Turnkey.
+        message: String,
+    },
+
+    /// The API key's stamp was rejected.
+    #[error("Unauthorized: check the Turnkey API key and organization")]
+    Unauthorized,
+
+    /// The API private key couldn't be parsed.
+    #[error("Invalid API key: {0}")]
+    InvalidApiKey(String),
+
+    /// The Turnkey response could not be parsed.
+    #[error("Invalid response: {0}")]
+    InvalidResponse(String),
+
+    /// The activity was rejected by an approver or failed a policy.
+    #[error("Activity {activity_id} {status}: {reason}")]
+    Rejected {
+        /// Turnkey activity ID.
+        activity_id: String,
+        /// Final activity status, e.g. `rejected` or `failed`.
+        status: String,
+        /// Reason given by Turnkey.
+        reason: String,
+    },
+
+    /// The returned signature doesn't verify for the signer's public key.
+    #[error("Signature from activity {0} does not verify")]
+    InvalidSignature(String),
+
+    /// Approval was not granted before the configured timeout.
+    #[error("Timed out waiting for approval of activity {0}")]
+    ApprovalTimeout(String),
+
+    /// Unknown pending activity handle.
+    #[error("Unknown activity: {0}")]
+    UnknownActivity(u64),
+}
+
+/// Result type for Turnkey signer operations.
+pub type Result<T> = std::result::Result<T, TurnkeyError>;
+
+impl From<TurnkeyError> for solana_actor::SignerError {
+    fn from(err: TurnkeyError) -> Self {
+        match err {
+            TurnkeyError::Http(e) => Self::DeviceError(e.to_string()),
+            TurnkeyError::Unauthorized | TurnkeyError::InvalidApiKey(_) => {
+                Self::InvalidKey(err.to_string())
+            }
+            TurnkeyError::Rejected { .. } => Self::UserCancelled,
+            other => Self::SigningFailed(other.to_string()),
+        }
+    }
+}
+
+impl From<TurnkeyError> for solana_actor::TransportError {
+    fn from(err: TurnkeyError) -> Self {
+        match err {
+            TurnkeyError::Http(e) => {
+                Self::Connection(solana_actor::ConnectionError::Rpc(e.to_string()))
+            }
+            TurnkeyError::ApprovalTimeout(_) => Self::Timeout,
+            TurnkeyError::Rejected { .. } => Self::ApprovalFailed(err.to_string()),
+            other => Self::Signer(other.into()),
+        }
+    }
+}
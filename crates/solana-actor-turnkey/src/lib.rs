@@ -0,0 +1,54 @@
+//! Turnkey API signer for Solana.
+//!
+//! This crate signs with keys held in [Turnkey](https://www.turnkey.com)
+//! through the same interfaces as local keys:
+//!
+//! - [`TurnkeySigner`] implements [`TransactionSigner`], blocking until the
+//!   signing activity completes
+//! - [`TurnkeyTransport`] implements [`WalletTransport`], surfacing
+//!   activities that need policy approval as [`SubmitResult::Pending`]
+//!
+//! Requests are authenticated with a Turnkey API key (see [`ApiKey`]), and
+//! every returned signature is verified for the wallet's public key before
+//! it is used.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use solana_actor_turnkey::{ApiKey, TurnkeyConfig, TurnkeyTransport};
+//! use solana_actor::{SubmitResult, WalletTransport};
+//! use std::time::Duration;
+//!
+//! let api_key = ApiKey::from_hex(&std::env::var("TURNKEY_API_PRIVATE_KEY")?)?;
+//! let config = TurnkeyConfig::new("ORGANIZATION_ID", api_key, "WALLET_ADDRESS".parse()?)
+//!     .poll_interval(Duration::from_secs(5));
+//!
+//! let transport = TurnkeyTransport::new(config)?;
+//! let result = transport.submit(&tx_message).await?;
+//!
+//! match result {
+//!     SubmitResult::Signed(sig) => println!("Signed: {}", sig),
+//!     SubmitResult::Pending { .. } => {
+//!         let done = transport
+//!             .wait_for_completion(result, Duration::from_secs(600))
+//!             .await?;
+//!         println!("Signed: {}", done.signature().unwrap());
+//!     }
+//!     _ => {}
+//! }
+//! ```
+
+pub mod client;
+mod error;
+mod signer;
+mod stamp;
+mod transport;
+
+pub use client::{Activity, ActivityStatus, TurnkeyClient, TurnkeyConfig};
+pub use error::{Result, TurnkeyError};
+pub use signer::TurnkeySigner;
+pub use stamp::ApiKey;
+pub use transport::TurnkeyTransport;
+
+// Re-export traits for convenience
+pub use solana_actor::{SignerError, SubmitResult, TransactionSigner, WalletTransport};
@@ -0,0 +1,90 @@
+//! Blocking Turnkey signer implementation.
+
+use solana_actor::{SignerError, TransactionSigner};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+use crate::client::{TurnkeyClient, TurnkeyConfig};
+use crate::error::{Result, TurnkeyError};
+
+/// A [`TransactionSigner`] backed by a Turnkey wallet account.
+///
+/// Signing submits a sign-raw-payload activity and blocks until it completes,
+/// is rejected, or the configured approval timeout elapses. Use
+/// [`TurnkeyTransport`](crate::TurnkeyTransport) instead when the caller
+/// should not block on policy approvals.
+///
+/// Because it blocks on a private runtime, `sign_transaction` must not be
+/// called directly from an async context; wrap it in [`DirectTransport`]
+/// (which uses `spawn_blocking`) or `tokio::task::spawn_blocking`.
+///
+/// [`DirectTransport`]: solana_actor::DirectTransport
+///
+/// # Example
+///
+/// ```ignore
+/// use solana_actor_turnkey::{ApiKey, TurnkeyConfig, TurnkeySigner};
+/// use solana_actor::TransactionSigner;
+///
+/// let api_key = ApiKey::from_hex(&std::env::var("TURNKEY_API_PRIVATE_KEY")?)?;
+/// let config = TurnkeyConfig::new("ORGANIZATION_ID", api_key, "WALLET_ADDRESS".parse()?);
+/// let signer = TurnkeySigner::new(config)?;
+///
+/// // Blocks until the organization's policies allow the activity
+/// let signature = signer.sign_transaction(&tx_message)?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct TurnkeySigner {
+    client: TurnkeyClient,
+}
+
+impl TurnkeySigner {
+    /// Create a new Turnkey signer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be built.
+    pub fn new(config: TurnkeyConfig) -> Result<Self> {
+        Ok(Self {
+            client: TurnkeyClient::new(config)?,
+        })
+    }
+
+    /// Get a reference to the underlying client.
+    pub fn client(&self) -> &TurnkeyClient {
+        &self.client
+    }
+
+    /// Get the public key as a base58 string.
+    pub fn pubkey_base58(&self) -> String {
+        self.client.config().pubkey.to_string()
+    }
+
+    fn sign_blocking(&self, message: &[u8]) -> Result<Signature> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| TurnkeyError::InvalidResponse(e.to_string()))?;
+
+        runtime.block_on(async {
+            let activity = self.client.sign_raw_payload(message).await?;
+            self.client
+                .wait_for_signature(activity, message, self.client.config().approval_timeout)
+                .await
+        })
+    }
+}
+
+impl TransactionSigner for TurnkeySigner {
+    fn pubkey(&self) -> Pubkey {
+        self.client.config().pubkey
+    }
+
+    fn sign_transaction(&self, message: &[u8]) -> std::result::Result<Signature, SignerError> {
+        Ok(self.sign_blocking(message)?)
+    }
+
+    fn is_interactive(&self) -> bool {
+        // Policies may require approvers to vote on the activity.
+        true
+    }
+}
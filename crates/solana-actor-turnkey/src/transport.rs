@@ -0,0 +1,285 @@
+//! Non-blocking Turnkey transport implementation.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use solana_actor::{SubmitResult, TransportError, WalletTransport};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::client::{Activity, TurnkeyClient, TurnkeyConfig};
+use crate::error::{Result, TurnkeyError};
+
+/// An activity awaiting approval, with the message it signs.
+#[derive(Debug)]
+struct PendingActivity {
+    activity_id: String,
+    message: Vec<u8>,
+}
+
+/// A [`WalletTransport`] backed by a Turnkey wallet account.
+///
+/// Unlike [`TurnkeySigner`](crate::TurnkeySigner), submitting does not block
+/// on policy approvals. If the activity has not completed immediately, the
+/// result is [`SubmitResult::Pending`] where:
+///
+/// - `proposal` is the wallet account's public key
+/// - `transaction_index` is a local handle for the activity
+///   (see [`TurnkeyTransport::activity_id`])
+/// - `approvals` is the number of votes cast so far
+/// - `threshold` is `approvals + 1`: Turnkey doesn't report a policy's
+///   quorum, only that more votes are needed
+///
+/// Once approved, [`check_status`](WalletTransport::check_status) returns
+/// [`SubmitResult::Signed`] with the verified signature.
+///
+/// # Example
+///
+/// ```ignore
+/// use solana_actor_turnkey::TurnkeyTransport;
+/// use solana_actor::{SubmitResult, WalletTransport};
+/// use std::time::Duration;
+///
+/// let transport = TurnkeyTransport::new(config)?;
+/// let result = transport.submit(&tx_message).await?;
+///
+/// if let SubmitResult::Pending { approvals, .. } = &result {
+///     println!("Awaiting Turnkey approval ({} votes so far)", approvals);
+/// }
+///
+/// let signed = transport
+///     .wait_for_completion(result, Duration::from_secs(600))
+///     .await?;
+/// ```
+#[derive(Debug)]
+pub struct TurnkeyTransport {
+    client: TurnkeyClient,
+    next_index: AtomicU64,
+    pending: Mutex<HashMap<u64, PendingActivity>>,
+}
+
+impl TurnkeyTransport {
+    /// Create a new Turnkey transport.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be built.
+    pub fn new(config: TurnkeyConfig) -> Result<Self> {
+        Ok(Self {
+            client: TurnkeyClient::new(config)?,
+            next_index: AtomicU64::new(0),
+            pending: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Get a reference to the underlying client.
+    pub fn client(&self) -> &TurnkeyClient {
+        &self.client
+    }
+
+    /// Get the Turnkey activity ID for a pending `transaction_index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pending activity map lock is poisoned.
+    pub fn activity_id(&self, transaction_index: u64) -> Option<String> {
+        self.pending
+            .lock()
+            .unwrap()
+            .get(&transaction_index)
+            .map(|pending| pending.activity_id.clone())
+    }
+
+    fn track(&self, activity_id: String, message: &[u8]) -> u64 {
+        let index = self.next_index.fetch_add(1, Ordering::Relaxed);
+        self.pending.lock().unwrap().insert(
+            index,
+            PendingActivity {
+                activity_id,
+                message: message.to_vec(),
+            },
+        );
+        index
+    }
+
+    fn untrack(&self, transaction_index: u64) {
+        self.pending.lock().unwrap().remove(&transaction_index);
+    }
+
+    /// Convert an activity into a [`SubmitResult`].
+    fn to_result(
+        &self,
+        activity: Activity,
+        message: &[u8],
+        index: Option<u64>,
+    ) -> Result<SubmitResult> {
+        let signature = self.client.signature(&activity, message);
+        if !matches!(signature, Ok(None))
+            && let Some(index) = index
+        {
+            self.untrack(index);
+        }
+
+        match signature? {
+            Some(signature) => Ok(SubmitResult::Signed(signature)),
+            None => {
+                let transaction_index = match index {
+                    Some(index) => index,
+                    None => self.track(activity.id, message),
+                };
+                Ok(SubmitResult::Pending {
+                    proposal: self.client.config().pubkey,
+                    transaction_index,
+                    approvals: activity.approvals,
+                    threshold: activity.approvals + 1,
+                })
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl WalletTransport for TurnkeyTransport {
+    fn authority(&self) -> Pubkey {
+        self.client.config().pubkey
+    }
+
+    async fn submit(&self, message: &[u8]) -> std::result::Result<SubmitResult, TransportError> {
+        let activity = self.client.sign_raw_payload(message).await?;
+        Ok(self.to_result(activity, message, None)?)
+    }
+
+    async fn check_status(
+        &self,
+        result: &SubmitResult,
+    ) -> std::result::Result<SubmitResult, TransportError> {
+        let SubmitResult::Pending {
+            transaction_index, ..
+        } = result
+        else {
+            return Ok(result.clone());
+        };
+
+        let (activity_id, message) = {
+            let pending = self.pending.lock().unwrap();
+            let entry = pending
+                .get(transaction_index)
+                .ok_or(TurnkeyError::UnknownActivity(*transaction_index))?;
+            (entry.activity_id.clone(), entry.message.clone())
+        };
+        let activity = self.client.get_activity(&activity_id).await?;
+        Ok(self.to_result(activity, &message, Some(*transaction_index))?)
+    }
+
+    async fn wait_for_completion(
+        &self,
+        result: SubmitResult,
+        timeout: Duration,
+    ) -> std::result::Result<SubmitResult, TransportError> {
+        if result.is_complete() {
+            return Ok(result);
+        }
+
+        let deadline = Instant::now() + timeout;
+        let mut current = result;
+
+        while Instant::now() < deadline {
+            current = self.check_status(&current).await?;
+            if current.is_complete() {
+                return Ok(current);
+            }
+            tokio::time::sleep(self.client.config().poll_interval).await;
+        }
+
+        Err(TransportError::Timeout)
+    }
+
+    fn requires_network(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::ActivityStatus;
+    use crate::stamp::ApiKey;
+    use solana_sdk::signature::{Keypair, Signature, Signer};
+
+    fn transport(pubkey: Pubkey) -> TurnkeyTransport {
+        let api_key = ApiKey::from_hex(&"11".repeat(32)).unwrap();
+        TurnkeyTransport::new(TurnkeyConfig::new("org", api_key, pubkey)).unwrap()
+    }
+
+    fn activity(status: ActivityStatus, signature: Option<Signature>) -> Activity {
+        Activity {
+            id: "act-1".into(),
+            status,
+            signature,
+            approvals: 1,
+            reason: None,
+        }
+    }
+
+    #[test]
+    fn test_consensus_needed_is_pending() {
+        let keypair = Keypair::new();
+        let transport = transport(keypair.pubkey());
+        let message = b"message";
+
+        let SubmitResult::Pending {
+            transaction_index,
+            approvals,
+            threshold,
+            ..
+        } = transport
+            .to_result(
+                activity(ActivityStatus::ConsensusNeeded, None),
+                message,
+                None,
+            )
+            .unwrap()
+        else {
+            panic!("expected pending");
+        };
+        assert_eq!((approvals, threshold), (1, 2));
+        assert_eq!(
+            transport.activity_id(transaction_index).as_deref(),
+            Some("act-1")
+        );
+
+        let signature = keypair.sign_message(message);
+        let result = transport
+            .to_result(
+                activity(ActivityStatus::Completed, Some(signature)),
+                message,
+                Some(transaction_index),
+            )
+            .unwrap();
+        assert_eq!(result.signature(), Some(&signature));
+        assert!(transport.activity_id(transaction_index).is_none());
+    }
+
+    #[test]
+    fn test_rejects_bad_signature_and_rejection() {
+        let keypair = Keypair::new();
+        let transport = transport(keypair.pubkey());
+
+        let forged = Keypair::new().sign_message(b"message");
+        let err = transport
+            .to_result(
+                activity(ActivityStatus::Completed, Some(forged)),
+                b"message",
+                None,
+            )
+            .unwrap_err();
+        assert!(matches!(err, TurnkeyError::InvalidSignature(_)));
+
+        let err = transport
+            .to_result(activity(ActivityStatus::Rejected, None), b"message", None)
+            .unwrap_err();
+        assert!(matches!(err, TurnkeyError::Rejected { .. }));
+    }
+}
@@ -0,0 +1,116 @@
+//! Error types for YubiKey operations.
+
+use thiserror::Error;
+
+/// Errors that can occur during YubiKey operations.
+#[derive(Error, Debug)]
+pub enum YubiKeyError {
+    /// No YubiKey connected, or none with the requested serial.
+    #[error("YubiKey not connected")]
+    NotConnected,
+
+    /// More than one YubiKey connected and no serial given.
+    #[error("Multiple YubiKeys connected ({}); choose one by serial", serials(.0))]
+    MultipleDevices(Vec<u32>),
+
+    /// Smart card (PC/SC) error.
+    #[error("Smart card error: {0}")]
+    PcSc(String),
+
+    /// Invalid response from device.
+    #[error("Invalid response from YubiKey: {0}")]
+    InvalidResponse(String),
+
+    /// The device returned an unexpected status word.
+    #[error("YubiKey returned status {0:04X}")]
+    Status(u16),
+
+    /// The slot holds no key.
+    #[error("No key in PIV slot {0}")]
+    EmptySlot(String),
+
+    /// The slot holds a key that isn't Ed25519.
+    #[error("PIV slot {slot} holds a non-Ed25519 key (algorithm {algorithm:#04x})")]
+    UnsupportedKey {
+        /// The slot.
+        slot: String,
+        /// PIV algorithm identifier of the key.
+        algorithm: u8,
+    },
+
+    /// The key's PIN policy requires a PIN and none was given.
+    #[error("YubiKey PIN required")]
+    PinRequired,
+
+    /// Wrong PIN.
+    #[error("Wrong YubiKey PIN ({retries} attempts left)")]
+    WrongPin {
+        /// Attempts left before the PIN is blocked.
+        retries: u8,
+    },
+
+    /// PIN of the wrong length.
+    #[error("YubiKey PIN must be 6 to 8 characters")]
+    InvalidPin,
+
+    /// PIN blocked after too many wrong attempts.
+    #[error("YubiKey PIN is blocked; reset it with the PUK")]
+    PinBlocked,
+
+    /// The key requires a touch and the device timed out waiting for it.
+    #[error("YubiKey was not touched in time")]
+    TouchTimeout,
+
+    /// Operation the device's firmware doesn't support.
+    #[error("Not supported by YubiKey: {0}")]
+    Unsupported(String),
+
+    /// Invalid PIV slot.
+    #[error("Invalid PIV slot: {0}")]
+    InvalidSlot(String),
+}
+
+/// Result type for YubiKey operations.
+pub type Result<T> = std::result::Result<T, YubiKeyError>;
+
+fn serials(serials: &[u32]) -> String {
+    serials
+        .iter()
+        .map(u32::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl From<pcsc::Error> for YubiKeyError {
+    fn from(err: pcsc::Error) -> Self {
+        match err {
+            pcsc::Error::NoReadersAvailable
+            | pcsc::Error::NoSmartcard
+            | pcsc::Error::RemovedCard
+            | pcsc::Error::ReaderUnavailable => Self::NotConnected,
+            err => Self::PcSc(err.to_string()),
+        }
+    }
+}
+
+impl From<YubiKeyError> for solana_actor::SignerError {
+    fn from(err: YubiKeyError) -> Self {
+        match err {
+            YubiKeyError::NotConnected => Self::DeviceNotFound,
+            YubiKeyError::TouchTimeout => Self::UserCancelled,
+            YubiKeyError::MultipleDevices(_) => Self::DeviceError(err.to_string()),
+            YubiKeyError::PcSc(msg) => Self::DeviceError(msg),
+            YubiKeyError::InvalidResponse(msg) => Self::DeviceError(msg),
+            YubiKeyError::Status(_) => Self::DeviceError(err.to_string()),
+            YubiKeyError::PinRequired
+            | YubiKeyError::InvalidPin
+            | YubiKeyError::WrongPin { .. }
+            | YubiKeyError::PinBlocked => Self::SigningFailed(err.to_string()),
+            YubiKeyError::Unsupported(msg) => Self::SigningFailed(msg),
+            YubiKeyError::EmptySlot(_) | YubiKeyError::UnsupportedKey { .. } => {
+                Self::InvalidKey(err.to_string())
+            }
+            YubiKeyError::InvalidSlot(msg) => Self::InvalidKey(msg),
+        }
+    }
+}
@@ -0,0 +1,63 @@
+//! YubiKey PIV signer for Solana.
+//!
+//! This crate provides [`YubiKeySigner`], an implementation of the
+//! [`MessageSigner`] and [`TransactionSigner`] traits backed by an Ed25519
+//! key in a YubiKey PIV slot, over PC/SC.
+//!
+//! # Features
+//!
+//! - **Hardware security** - Private keys never leave the YubiKey
+//! - **Touch policy** - Keys generated with a touch policy wait for a touch
+//!   on every signature
+//! - **Device enumeration** - [`list_devices`] finds connected YubiKeys, and
+//!   a signer is bound to one by serial number
+//! - **Trait implementations** - Implements `MessageSigner` and `TransactionSigner`
+//!
+//! # Requirements
+//!
+//! - YubiKey 5 series with firmware 5.7 or later (Ed25519 in PIV)
+//! - An Ed25519 key in a PIV slot, e.g. generated with
+//!   `ykman piv keys generate --algorithm ED25519 --touch-policy ALWAYS 9c pub.pem`
+//! - A PC/SC service: built in on macOS and Windows, `pcscd` on Linux
+//!
+//! Unlike Ledger and Trezor, the YubiKey signs whatever bytes it is given
+//! without displaying them, so a touch confirms presence, not content.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use solana_actor_yubikey::YubiKeySigner;
+//! use solana_actor::TransactionSigner;
+//!
+//! // Connect to the only YubiKey, signature slot (9c)
+//! let signer = YubiKeySigner::connect()?.with_pin(pin);
+//! println!("YubiKey pubkey: {}", signer.pubkey_base58());
+//!
+//! // Sign a transaction (touch the YubiKey if it blinks)
+//! let signature = signer.sign_transaction(&tx_message)?;
+//! ```
+//!
+//! # Choosing a Device and Slot
+//!
+//! ```ignore
+//! use solana_actor_yubikey::{Slot, YubiKeySigner, list_devices};
+//!
+//! for device in list_devices()? {
+//!     println!("{} (firmware {:?})", device.serial, device.version);
+//! }
+//!
+//! let signer = YubiKeySigner::open(Some(12345678), Slot::AUTHENTICATION)?;
+//! ```
+
+mod error;
+mod signer;
+mod slot;
+mod transport;
+
+pub use error::{Result, YubiKeyError};
+pub use signer::YubiKeySigner;
+pub use slot::{PinPolicy, Slot, TouchPolicy};
+pub use transport::{ALGORITHM_ED25519, DeviceInfo, SlotMetadata, list_devices};
+
+// Re-export traits for convenience
+pub use solana_actor::{MessageSigner, SignerError, TransactionSigner};
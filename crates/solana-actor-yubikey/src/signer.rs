@@ -0,0 +1,222 @@
+//! YubiKey PIV signer implementation.
+
+use solana_actor::{MessageSigner, SignerError, TransactionSigner};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use zeroize::Zeroizing;
+
+use crate::error::{Result, YubiKeyError};
+use crate::slot::{PinPolicy, Slot, TouchPolicy};
+use crate::transport::{self, ALGORITHM_ED25519};
+
+/// YubiKey PIV signer.
+///
+/// This signer uses an Ed25519 key in a PIV slot of a YubiKey 5 with
+/// firmware 5.7 or later. The device is identified by serial number, so the
+/// signer keeps working when other YubiKeys are plugged in.
+///
+/// # Security
+///
+/// - Private keys never leave the YubiKey
+/// - The key's PIN policy decides whether a PIN is needed; it is held in
+///   memory that is zeroed on drop
+/// - With a touch policy of `always` or `cached`, each signature blocks until
+///   the YubiKey is touched
+/// - Implements `is_interactive() -> true` to indicate user interaction required
+///
+/// # Example
+///
+/// ```ignore
+/// use solana_actor_yubikey::{Slot, YubiKeySigner};
+/// use solana_actor::TransactionSigner;
+///
+/// // The only connected YubiKey, signature slot (9c)
+/// let signer = YubiKeySigner::connect()?.with_pin(pin);
+/// println!("YubiKey pubkey: {}", signer.pubkey_base58());
+///
+/// // Sign a transaction (touch the YubiKey if it blinks)
+/// let signature = signer.sign_transaction(&tx_message)?;
+/// ```
+pub struct YubiKeySigner {
+    serial: u32,
+    slot: Slot,
+    pubkey: Pubkey,
+    pin_policy: PinPolicy,
+    touch_policy: TouchPolicy,
+    pin: Option<Zeroizing<String>>,
+}
+
+impl YubiKeySigner {
+    /// Connect to the only connected YubiKey and use its signature slot (9c).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - No YubiKey, or more than one, is connected
+    /// - The slot holds no Ed25519 key
+    /// - Communication with the device fails
+    pub fn connect() -> Result<Self> {
+        Self::open(None, Slot::SIGNATURE)
+    }
+
+    /// Connect to the YubiKey with `serial`, or the only one connected, and
+    /// use the key in `slot`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the device isn't connected, its firmware predates
+    /// Ed25519 support, or the slot holds no Ed25519 key.
+    pub fn open(serial: Option<u32>, slot: Slot) -> Result<Self> {
+        let (device, metadata) = transport::get_metadata(serial, slot)?;
+        if !device.supports_ed25519() {
+            let (major, minor, patch) = device.version;
+            return Err(YubiKeyError::Unsupported(format!(
+                "Ed25519 keys need firmware 5.7 or later (this YubiKey has {}.{}.{})",
+                major, minor, patch
+            )));
+        }
+        if metadata.algorithm != ALGORITHM_ED25519 {
+            return Err(YubiKeyError::UnsupportedKey {
+                slot: slot.to_string(),
+                algorithm: metadata.algorithm,
+            });
+        }
+        let public_key = metadata
+            .public_key
+            .ok_or_else(|| YubiKeyError::EmptySlot(slot.to_string()))?;
+
+        Ok(Self {
+            serial: device.serial,
+            slot,
+            pubkey: Pubkey::new_from_array(public_key),
+            pin_policy: metadata.pin_policy,
+            touch_policy: metadata.touch_policy,
+            pin: None,
+        })
+    }
+
+    /// Set the PIN, for keys whose PIN policy asks for one.
+    pub fn with_pin(mut self, pin: impl Into<String>) -> Self {
+        self.pin = Some(Zeroizing::new(pin.into()));
+        self
+    }
+
+    /// Get the device's serial number.
+    pub fn serial(&self) -> u32 {
+        self.serial
+    }
+
+    /// Get the PIV slot of the key.
+    pub fn slot(&self) -> Slot {
+        self.slot
+    }
+
+    /// Get the key's PIN policy.
+    pub fn pin_policy(&self) -> PinPolicy {
+        self.pin_policy
+    }
+
+    /// Get the key's touch policy.
+    pub fn touch_policy(&self) -> TouchPolicy {
+        self.touch_policy
+    }
+
+    /// Whether signing blocks until the YubiKey is touched.
+    pub fn requires_touch(&self) -> bool {
+        self.touch_policy != TouchPolicy::Never
+    }
+
+    /// Get the public key as a base58 string.
+    pub fn pubkey_base58(&self) -> String {
+        self.pubkey.to_string()
+    }
+
+    /// Sign a message and return raw signature bytes.
+    ///
+    /// This is a lower-level method that returns the signature as a byte array.
+    /// For most use cases, use [`TransactionSigner::sign_transaction`] instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`YubiKeyError::PinRequired`] if the key needs a PIN and none
+    /// was set, [`YubiKeyError::TouchTimeout`] if the YubiKey wasn't touched
+    /// in time, or an error if device communication fails.
+    pub fn sign(&self, message: &[u8]) -> Result<[u8; 64]> {
+        let pin = match (&self.pin, self.pin_policy) {
+            (_, PinPolicy::Never) => None,
+            (Some(pin), _) => Some(pin.as_bytes()),
+            (None, _) => return Err(YubiKeyError::PinRequired),
+        };
+        let signature = transport::sign(self.serial, self.slot, pin, message)?;
+
+        // The slot may have been regenerated since the signer was opened
+        if !Signature::from(signature).verify(self.pubkey.as_ref(), message) {
+            return Err(YubiKeyError::InvalidResponse(format!(
+                "Signature is not by {}; was slot {} changed?",
+                self.pubkey, self.slot
+            )));
+        }
+        Ok(signature)
+    }
+}
+
+impl MessageSigner for YubiKeySigner {
+    fn pubkey(&self) -> Pubkey {
+        self.pubkey
+    }
+
+    fn sign_message(&self, message: &[u8]) -> std::result::Result<Signature, SignerError> {
+        let sig_bytes = self.sign(message).map_err(SignerError::from)?;
+        Ok(Signature::from(sig_bytes))
+    }
+}
+
+impl TransactionSigner for YubiKeySigner {
+    fn pubkey(&self) -> Pubkey {
+        self.pubkey
+    }
+
+    fn sign_transaction(&self, message: &[u8]) -> std::result::Result<Signature, SignerError> {
+        let sig_bytes = self.sign(message).map_err(SignerError::from)?;
+        Ok(Signature::from(sig_bytes))
+    }
+
+    /// Always `true`: the device must be present, and signing may wait for
+    /// a touch.
+    fn is_interactive(&self) -> bool {
+        true
+    }
+}
+
+// Note: Tests for YubiKeySigner require a physical device and are marked as ignored.
+// Run them manually with: cargo test -p solana-actor-yubikey -- --ignored
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[ignore]
+    fn test_list_devices() {
+        for device in transport::list_devices().expect("Failed to list YubiKeys") {
+            println!("YubiKey {} ({:?})", device.serial, device.version);
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn test_sign_transaction() {
+        use solana_sdk::{hash::Hash, message::Message};
+
+        let pin = std::env::var("YUBIKEY_PIN").unwrap_or_else(|_| "123456".into());
+        let signer = YubiKeySigner::connect()
+            .expect("Failed to connect to YubiKey")
+            .with_pin(pin);
+        let message = Message::new_with_blockhash(&[], Some(&signer.pubkey), &Hash::default());
+        let bytes = message.serialize();
+
+        println!("Touch your YubiKey if it blinks...");
+        let sig = signer
+            .sign_transaction(&bytes)
+            .expect("Failed to sign transaction");
+        assert!(sig.verify(signer.pubkey.as_ref(), &bytes));
+    }
+}
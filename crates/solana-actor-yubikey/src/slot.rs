@@ -0,0 +1,163 @@
+//! PIV slots and key policies.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::YubiKeyError;
+
+/// A PIV key slot.
+///
+/// Slots are written as their two-digit hex identifier (`9a`, `9c`, `9d`,
+/// `9e`, or `82` through `95` for the retired key management slots), or by
+/// name (`authentication`, `signature`, `key-management`,
+/// `card-authentication`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Slot(u8);
+
+impl Slot {
+    /// PIV Authentication (9a).
+    pub const AUTHENTICATION: Slot = Slot(0x9a);
+    /// Digital Signature (9c); the default for signing.
+    pub const SIGNATURE: Slot = Slot(0x9c);
+    /// Key Management (9d).
+    pub const KEY_MANAGEMENT: Slot = Slot(0x9d);
+    /// Card Authentication (9e).
+    pub const CARD_AUTHENTICATION: Slot = Slot(0x9e);
+
+    /// A slot from its identifier.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`YubiKeyError::InvalidSlot`] if `id` isn't a key slot.
+    pub fn from_id(id: u8) -> Result<Self, YubiKeyError> {
+        match id {
+            0x9a | 0x9c | 0x9d | 0x9e | 0x82..=0x95 => Ok(Slot(id)),
+            _ => Err(YubiKeyError::InvalidSlot(format!("{:02x}", id))),
+        }
+    }
+
+    /// The slot's identifier, as sent to the device.
+    pub fn id(&self) -> u8 {
+        self.0
+    }
+}
+
+impl Default for Slot {
+    fn default() -> Self {
+        Self::SIGNATURE
+    }
+}
+
+impl fmt::Display for Slot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02x}", self.0)
+    }
+}
+
+impl FromStr for Slot {
+    type Err = YubiKeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "authentication" => Ok(Self::AUTHENTICATION),
+            "signature" => Ok(Self::SIGNATURE),
+            "key-management" => Ok(Self::KEY_MANAGEMENT),
+            "card-authentication" => Ok(Self::CARD_AUTHENTICATION),
+            id => {
+                let id = id.trim_start_matches("0x");
+                u8::from_str_radix(id, 16)
+                    .map_err(|_| YubiKeyError::InvalidSlot(s.to_string()))
+                    .and_then(Self::from_id)
+            }
+        }
+    }
+}
+
+/// When the key asks for the PIN.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PinPolicy {
+    /// Never.
+    Never,
+    /// Once per session.
+    Once,
+    /// Before every signature.
+    Always,
+}
+
+impl PinPolicy {
+    /// Decode the policy byte from a slot's metadata.
+    ///
+    /// "Default" means `Always` for the signature slot, `Never` for card
+    /// authentication and `Once` elsewhere.
+    pub(crate) fn from_byte(byte: u8, slot: Slot) -> Self {
+        match byte {
+            1 => Self::Never,
+            2 => Self::Once,
+            3 => Self::Always,
+            _ if slot == Slot::SIGNATURE => Self::Always,
+            _ if slot == Slot::CARD_AUTHENTICATION => Self::Never,
+            _ => Self::Once,
+        }
+    }
+}
+
+impl fmt::Display for PinPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Never => write!(f, "never"),
+            Self::Once => write!(f, "once"),
+            Self::Always => write!(f, "always"),
+        }
+    }
+}
+
+/// When the key asks for a touch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TouchPolicy {
+    /// Never.
+    Never,
+    /// Before every signature.
+    Always,
+    /// Once, then not again for 15 seconds.
+    Cached,
+}
+
+impl TouchPolicy {
+    /// Decode the policy byte from slot metadata.
+    pub(crate) fn from_byte(byte: u8) -> Self {
+        match byte {
+            2 => Self::Always,
+            3 => Self::Cached,
+            _ => Self::Never,
+        }
+    }
+}
+
+impl fmt::Display for TouchPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Never => write!(f, "never"),
+            Self::Always => write!(f, "always"),
+            Self::Cached => write!(f, "cached"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_slot() {
+        assert_eq!("9c".parse::<Slot>().unwrap(), Slot::SIGNATURE);
+        assert_eq!("0x9A".parse::<Slot>().unwrap(), Slot::AUTHENTICATION);
+        assert_eq!("signature".parse::<Slot>().unwrap(), Slot::SIGNATURE);
+        assert_eq!("82".parse::<Slot>().unwrap().id(), 0x82);
+        assert_eq!(Slot::KEY_MANAGEMENT.to_string(), "9d");
+
+        // Management key and attestation slots hold no signing key
+        assert!("9b".parse::<Slot>().is_err());
+        assert!("f9".parse::<Slot>().is_err());
+        assert!("zz".parse::<Slot>().is_err());
+    }
+}
@@ -0,0 +1,502 @@
+//! YubiKey PIV transport over PC/SC.
+//!
+//! This module handles device enumeration, APDU chaining in both directions,
+//! and the PIV requests the signer needs: slot metadata, PIN verification
+//! and Ed25519 signing.
+
+use std::ffi::CStr;
+
+use crate::error::{Result, YubiKeyError};
+use crate::slot::{PinPolicy, Slot, TouchPolicy};
+
+/// PIV application identifier
+const PIV_AID: &[u8] = &[0xa0, 0x00, 0x00, 0x03, 0x08];
+
+const INS_SELECT: u8 = 0xa4;
+const INS_VERIFY: u8 = 0x20;
+const INS_AUTHENTICATE: u8 = 0x87;
+const INS_GET_RESPONSE: u8 = 0xc0;
+// YubiKey extensions
+const INS_GET_METADATA: u8 = 0xf7;
+const INS_GET_SERIAL: u8 = 0xf8;
+const INS_GET_VERSION: u8 = 0xfd;
+
+/// PIV algorithm identifier for Ed25519 (YubiKey 5.7+)
+pub const ALGORITHM_ED25519: u8 = 0xe0;
+
+/// Key reference of the PIV application PIN
+const PIN_REFERENCE: u8 = 0x80;
+/// PINs are padded to 8 bytes with 0xff
+const PIN_LEN: usize = 8;
+
+/// Longest command data sent in one APDU; longer data is chained
+const CHUNK_LEN: usize = 255;
+/// Class byte bit marking a chained command that isn't the last
+const CLA_CHAIN: u8 = 0x10;
+
+const SW_SUCCESS: u16 = 0x9000;
+const SW_SECURITY_STATUS: u16 = 0x6982;
+const SW_AUTH_BLOCKED: u16 = 0x6983;
+const SW_CONDITIONS_NOT_SATISFIED: u16 = 0x6985;
+const SW_NOT_FOUND: u16 = 0x6a82;
+const SW_INS_NOT_SUPPORTED: u16 = 0x6d00;
+
+/// PC/SC readers for YubiKeys have "Yubico" in their name
+const READER_NAME: &str = "yubico";
+
+/// A connected YubiKey.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceInfo {
+    /// Serial number.
+    pub serial: u32,
+    /// Firmware version (major, minor, patch).
+    pub version: (u8, u8, u8),
+    /// Name of the PC/SC reader it's attached through.
+    pub reader: String,
+}
+
+impl DeviceInfo {
+    /// Whether the firmware supports Ed25519 keys in PIV slots (5.7+).
+    pub fn supports_ed25519(&self) -> bool {
+        self.version >= (5, 7, 0)
+    }
+}
+
+/// Metadata of a key in a PIV slot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlotMetadata {
+    /// PIV algorithm identifier.
+    pub algorithm: u8,
+    /// When the key asks for the PIN.
+    pub pin_policy: PinPolicy,
+    /// When the key asks for a touch.
+    pub touch_policy: TouchPolicy,
+    /// Ed25519 public key, if the key is Ed25519.
+    pub public_key: Option<[u8; 32]>,
+}
+
+/// List connected YubiKeys.
+///
+/// Readers that aren't YubiKeys, or whose card doesn't answer, are skipped.
+pub fn list_devices() -> Result<Vec<DeviceInfo>> {
+    let context = pcsc::Context::establish(pcsc::Scope::User)?;
+    let mut devices = Vec::new();
+    for reader in yubikey_readers(&context)? {
+        let Ok(device) = Device::connect(&context, &reader) else {
+            continue;
+        };
+        if let Ok(info) = device.info(&reader) {
+            devices.push(info);
+        }
+    }
+    Ok(devices)
+}
+
+/// Read a slot's metadata from the device with `serial`, or the only one
+/// connected.
+pub fn get_metadata(serial: Option<u32>, slot: Slot) -> Result<(DeviceInfo, SlotMetadata)> {
+    let context = pcsc::Context::establish(pcsc::Scope::User)?;
+    let (device, info) = Device::open(&context, serial)?;
+    let metadata = device.metadata(slot)?;
+    Ok((info, metadata))
+}
+
+/// Sign `message` with the Ed25519 key in `slot`.
+///
+/// The PIN is verified first if given. Blocks until the key is touched if
+/// its touch policy asks for it.
+pub fn sign(serial: u32, slot: Slot, pin: Option<&[u8]>, message: &[u8]) -> Result<[u8; 64]> {
+    let context = pcsc::Context::establish(pcsc::Scope::User)?;
+    let (mut device, _) = Device::open(&context, Some(serial))?;
+
+    // Hold the card so no other client resets the PIN state in between
+    let transaction = device.card.transaction()?;
+    let piv = Piv(&transaction);
+    if let Some(pin) = pin {
+        piv.verify_pin(pin)?;
+    }
+    let response = piv.transmit(
+        0x00,
+        INS_AUTHENTICATE,
+        ALGORITHM_ED25519,
+        slot.id(),
+        &authenticate_request(message),
+    )?;
+    parse_signature(&response)
+}
+
+/// Names of readers that look like YubiKeys.
+fn yubikey_readers(context: &pcsc::Context) -> Result<Vec<std::ffi::CString>> {
+    let mut buffer = vec![0u8; context.list_readers_len()?];
+    let readers = match context.list_readers(&mut buffer) {
+        Ok(readers) => readers,
+        Err(pcsc::Error::NoReadersAvailable) => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    Ok(readers
+        .filter(|name| {
+            name.to_string_lossy()
+                .to_ascii_lowercase()
+                .contains(READER_NAME)
+        })
+        .map(CStr::to_owned)
+        .collect())
+}
+
+/// A card with the PIV application selected.
+struct Device {
+    card: pcsc::Card,
+}
+
+impl Device {
+    /// Connect to the card in `reader` and select the PIV application.
+    fn connect(context: &pcsc::Context, reader: &CStr) -> Result<Self> {
+        let card = context.connect(reader, pcsc::ShareMode::Shared, pcsc::Protocols::ANY)?;
+        let device = Self { card };
+        device
+            .piv()
+            .transmit(0x00, INS_SELECT, 0x04, 0x00, PIV_AID)?;
+        Ok(device)
+    }
+
+    /// Open the YubiKey with `serial`, or the only one connected.
+    fn open(context: &pcsc::Context, serial: Option<u32>) -> Result<(Self, DeviceInfo)> {
+        let mut found = Vec::new();
+        for reader in yubikey_readers(context)? {
+            let Ok(device) = Self::connect(context, &reader) else {
+                continue;
+            };
+            let Ok(info) = device.info(&reader) else {
+                continue;
+            };
+            if serial.is_none_or(|serial| serial == info.serial) {
+                found.push((device, info));
+            }
+        }
+
+        match found.len() {
+            0 => Err(YubiKeyError::NotConnected),
+            1 => Ok(found.remove(0)),
+            _ => Err(YubiKeyError::MultipleDevices(
+                found.iter().map(|(_, info)| info.serial).collect(),
+            )),
+        }
+    }
+
+    fn piv(&self) -> Piv<'_> {
+        Piv(&self.card)
+    }
+
+    fn info(&self, reader: &CStr) -> Result<DeviceInfo> {
+        let piv = self.piv();
+        let serial = piv.transmit(0x00, INS_GET_SERIAL, 0x00, 0x00, &[])?;
+        let serial: [u8; 4] = serial
+            .as_slice()
+            .try_into()
+            .map_err(|_| YubiKeyError::InvalidResponse("Serial is not 4 bytes".into()))?;
+        let version = piv.transmit(0x00, INS_GET_VERSION, 0x00, 0x00, &[])?;
+        let [major, minor, patch] = version[..] else {
+            return Err(YubiKeyError::InvalidResponse(
+                "Version is not 3 bytes".into(),
+            ));
+        };
+
+        Ok(DeviceInfo {
+            serial: u32::from_be_bytes(serial),
+            version: (major, minor, patch),
+            reader: reader.to_string_lossy().into_owned(),
+        })
+    }
+
+    fn metadata(&self, slot: Slot) -> Result<SlotMetadata> {
+        let response = self
+            .piv()
+            .transmit(0x00, INS_GET_METADATA, 0x00, slot.id(), &[])
+            .map_err(|e| match e {
+                YubiKeyError::Status(SW_NOT_FOUND) => YubiKeyError::EmptySlot(slot.to_string()),
+                YubiKeyError::Status(SW_INS_NOT_SUPPORTED) => YubiKeyError::Unsupported(
+                    "Reading slot metadata needs firmware 5.3 or later".into(),
+                ),
+                e => e,
+            })?;
+        parse_metadata(&response, slot)
+    }
+}
+
+/// Sends APDUs to a card, whether or not a transaction is held.
+struct Piv<'a>(&'a pcsc::Card);
+
+impl Piv<'_> {
+    fn verify_pin(&self, pin: &[u8]) -> Result<()> {
+        let mut padded = pad_pin(pin)?;
+        let result = self.transmit(0x00, INS_VERIFY, 0x00, PIN_REFERENCE, &padded);
+        padded.fill(0);
+        result.map(|_| ())
+    }
+
+    /// Send a command, chaining long data, and collect a chained response.
+    fn transmit(&self, cla: u8, ins: u8, p1: u8, p2: u8, data: &[u8]) -> Result<Vec<u8>> {
+        let mut buffer = [0u8; pcsc::MAX_BUFFER_SIZE];
+        let mut response = Vec::new();
+
+        let mut status = SW_SUCCESS;
+        for command in encode_commands(cla, ins, p1, p2, data) {
+            let (data, sw) = split_status(self.0.transmit(&command, &mut buffer)?)?;
+            response.extend_from_slice(data);
+            status = sw;
+        }
+        // 61xx: more response data to fetch
+        while status >> 8 == 0x61 {
+            let command = [0x00, INS_GET_RESPONSE, 0x00, 0x00, status as u8];
+            let (data, sw) = split_status(self.0.transmit(&command, &mut buffer)?)?;
+            response.extend_from_slice(data);
+            status = sw;
+        }
+
+        check_status(status)?;
+        Ok(response)
+    }
+}
+
+/// Split commands longer than one APDU into a chain.
+fn encode_commands(cla: u8, ins: u8, p1: u8, p2: u8, data: &[u8]) -> Vec<Vec<u8>> {
+    if data.is_empty() {
+        return vec![vec![cla, ins, p1, p2]];
+    }
+    let chunks: Vec<&[u8]> = data.chunks(CHUNK_LEN).collect();
+    let last = chunks.len() - 1;
+    chunks
+        .into_iter()
+        .enumerate()
+        .map(|(i, chunk)| {
+            let cla = if i == last { cla } else { cla | CLA_CHAIN };
+            let mut command = vec![cla, ins, p1, p2, chunk.len() as u8];
+            command.extend_from_slice(chunk);
+            command
+        })
+        .collect()
+}
+
+/// Split a response into its data and status word.
+fn split_status(response: &[u8]) -> Result<(&[u8], u16)> {
+    let Some((data, sw)) = response.split_last_chunk::<2>() else {
+        return Err(YubiKeyError::InvalidResponse(
+            "Response has no status word".into(),
+        ));
+    };
+    Ok((data, u16::from_be_bytes(*sw)))
+}
+
+/// Map a status word to an error.
+fn check_status(status: u16) -> Result<()> {
+    match status {
+        SW_SUCCESS => Ok(()),
+        SW_SECURITY_STATUS => Err(YubiKeyError::PinRequired),
+        SW_AUTH_BLOCKED => Err(YubiKeyError::PinBlocked),
+        SW_CONDITIONS_NOT_SATISFIED => Err(YubiKeyError::TouchTimeout),
+        // 63Cx: wrong PIN, x attempts left
+        sw if sw & 0xfff0 == 0x63c0 => Err(YubiKeyError::WrongPin {
+            retries: (sw & 0x0f) as u8,
+        }),
+        sw => Err(YubiKeyError::Status(sw)),
+    }
+}
+
+/// Pad a PIN to 8 bytes with 0xff.
+fn pad_pin(pin: &[u8]) -> Result<[u8; PIN_LEN]> {
+    if !(6..=PIN_LEN).contains(&pin.len()) {
+        return Err(YubiKeyError::InvalidPin);
+    }
+    let mut padded = [0xff; PIN_LEN];
+    padded[..pin.len()].copy_from_slice(pin);
+    Ok(padded)
+}
+
+/// Encode a BER-TLV length.
+fn encode_len(len: usize, out: &mut Vec<u8>) {
+    match len {
+        0..0x80 => out.push(len as u8),
+        0x80..0x100 => out.extend_from_slice(&[0x81, len as u8]),
+        _ => {
+            out.push(0x82);
+            out.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+    }
+}
+
+/// Encode a TLV.
+fn encode_tlv(tag: u8, value: &[u8], out: &mut Vec<u8>) {
+    out.push(tag);
+    encode_len(value.len(), out);
+    out.extend_from_slice(value);
+}
+
+/// Split the first TLV from `data`, returning its tag, value and the rest.
+fn next_tlv(data: &[u8]) -> Result<(u8, &[u8], &[u8])> {
+    let invalid = || YubiKeyError::InvalidResponse("Malformed TLV".into());
+    let (&tag, rest) = data.split_first().ok_or_else(invalid)?;
+    let (&first, rest) = rest.split_first().ok_or_else(invalid)?;
+    let (len, rest) = match first {
+        0x81 => {
+            let (&len, rest) = rest.split_first().ok_or_else(invalid)?;
+            (len as usize, rest)
+        }
+        0x82 => {
+            let (len, rest) = rest.split_first_chunk::<2>().ok_or_else(invalid)?;
+            (u16::from_be_bytes(*len) as usize, rest)
+        }
+        len if len < 0x80 => (len as usize, rest),
+        _ => return Err(invalid()),
+    };
+    if rest.len() < len {
+        return Err(invalid());
+    }
+    let (value, rest) = rest.split_at(len);
+    Ok((tag, value, rest))
+}
+
+/// The value of the first TLV with `tag`.
+fn find_tlv(mut data: &[u8], tag: u8) -> Result<Option<&[u8]>> {
+    while !data.is_empty() {
+        let (found, value, rest) = next_tlv(data)?;
+        if found == tag {
+            return Ok(Some(value));
+        }
+        data = rest;
+    }
+    Ok(None)
+}
+
+/// GENERAL AUTHENTICATE template asking for a signature over `message`.
+fn authenticate_request(message: &[u8]) -> Vec<u8> {
+    let mut template = Vec::with_capacity(message.len() + 8);
+    // An empty response tag (82) asks for the signature
+    encode_tlv(0x82, &[], &mut template);
+    encode_tlv(0x81, message, &mut template);
+
+    let mut request = Vec::with_capacity(template.len() + 4);
+    encode_tlv(0x7c, &template, &mut request);
+    request
+}
+
+/// The signature in a GENERAL AUTHENTICATE response.
+fn parse_signature(response: &[u8]) -> Result<[u8; 64]> {
+    let missing = || YubiKeyError::InvalidResponse("No signature in response".into());
+    let template = find_tlv(response, 0x7c)?.ok_or_else(missing)?;
+    let signature = find_tlv(template, 0x82)?.ok_or_else(missing)?;
+    signature
+        .try_into()
+        .map_err(|_| YubiKeyError::InvalidResponse("Signature is not 64 bytes".into()))
+}
+
+/// Parse a GET METADATA response.
+fn parse_metadata(response: &[u8], slot: Slot) -> Result<SlotMetadata> {
+    let missing = |field: &str| YubiKeyError::InvalidResponse(format!("Metadata has no {}", field));
+
+    let algorithm = *find_tlv(response, 0x01)?
+        .and_then(<[u8]>::first)
+        .ok_or_else(|| missing("algorithm"))?;
+    let [pin_policy, touch_policy] = find_tlv(response, 0x02)?
+        .and_then(|policy| <[u8; 2]>::try_from(policy).ok())
+        .ok_or_else(|| missing("policy"))?;
+
+    let public_key: Option<[u8; 32]> =
+        match find_tlv(response, 0x04)? {
+            Some(encoded) if algorithm == ALGORITHM_ED25519 => {
+                let point = find_tlv(encoded, 0x86)?.ok_or_else(|| missing("public key"))?;
+                Some(point.try_into().map_err(|_| {
+                    YubiKeyError::InvalidResponse("Public key is not 32 bytes".into())
+                })?)
+            }
+            _ => None,
+        };
+
+    Ok(SlotMetadata {
+        algorithm,
+        pin_policy: PinPolicy::from_byte(pin_policy, slot),
+        touch_policy: TouchPolicy::from_byte(touch_policy),
+        public_key,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chains_long_commands() {
+        let commands = encode_commands(0x00, INS_AUTHENTICATE, 0xe0, 0x9c, &[7; 600]);
+        assert_eq!(commands.len(), 3);
+        assert_eq!(&commands[0][..5], &[0x10, 0x87, 0xe0, 0x9c, 0xff]);
+        assert_eq!(&commands[1][..5], &[0x10, 0x87, 0xe0, 0x9c, 0xff]);
+        assert_eq!(&commands[2][..5], &[0x00, 0x87, 0xe0, 0x9c, 90]);
+        assert_eq!(
+            encode_commands(0x00, INS_GET_SERIAL, 0, 0, &[]),
+            vec![vec![0x00, 0xf8, 0x00, 0x00]]
+        );
+    }
+
+    #[test]
+    fn test_authenticate_round_trip() {
+        let message = [3u8; 300];
+        let request = authenticate_request(&message);
+        // 7C 82 01 32 | 82 00 | 81 82 01 2C <message>
+        assert_eq!(
+            &request[..10],
+            &[0x7c, 0x82, 0x01, 0x32, 0x82, 0x00, 0x81, 0x82, 0x01, 0x2c]
+        );
+        let template = find_tlv(&request, 0x7c).unwrap().unwrap();
+        assert_eq!(find_tlv(template, 0x81).unwrap().unwrap(), &message);
+
+        let mut template = Vec::new();
+        encode_tlv(0x82, &[9; 64], &mut template);
+        let mut response = Vec::new();
+        encode_tlv(0x7c, &template, &mut response);
+        assert_eq!(parse_signature(&response).unwrap(), [9; 64]);
+        assert!(parse_signature(&response[..20]).is_err());
+    }
+
+    #[test]
+    fn test_parse_metadata() {
+        let mut public_key = Vec::new();
+        encode_tlv(0x86, &[5; 32], &mut public_key);
+        let mut response = Vec::new();
+        encode_tlv(0x01, &[ALGORITHM_ED25519], &mut response);
+        encode_tlv(0x02, &[0x01, 0x02], &mut response);
+        encode_tlv(0x03, &[0x01], &mut response);
+        encode_tlv(0x04, &public_key, &mut response);
+
+        let metadata = parse_metadata(&response, Slot::SIGNATURE).unwrap();
+        assert_eq!(metadata.pin_policy, PinPolicy::Never);
+        assert_eq!(metadata.touch_policy, TouchPolicy::Always);
+        assert_eq!(metadata.public_key, Some([5; 32]));
+
+        // P-256 keys have no Ed25519 public key; default PIN policy in 9c
+        let mut response = Vec::new();
+        encode_tlv(0x01, &[0x11], &mut response);
+        encode_tlv(0x02, &[0x00, 0x03], &mut response);
+        let metadata = parse_metadata(&response, Slot::SIGNATURE).unwrap();
+        assert_eq!(metadata.pin_policy, PinPolicy::Always);
+        assert_eq!(metadata.touch_policy, TouchPolicy::Cached);
+        assert_eq!(metadata.public_key, None);
+    }
+
+    #[test]
+    fn test_status_words() {
+        assert!(check_status(0x9000).is_ok());
+        assert!(matches!(
+            check_status(0x63c2),
+            Err(YubiKeyError::WrongPin { retries: 2 })
+        ));
+        assert!(matches!(
+            check_status(0x6983),
+            Err(YubiKeyError::PinBlocked)
+        ));
+        assert!(matches!(
+            check_status(0x6a80),
+            Err(YubiKeyError::Status(0x6a80))
+        ));
+        assert_eq!(pad_pin(b"123456").unwrap(), *b"123456\xff\xff");
+        assert!(pad_pin(b"12345").is_err());
+    }
+}
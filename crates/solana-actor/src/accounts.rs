@@ -0,0 +1,196 @@
+//! Batched account query helpers.
+//!
+//! [`AccountFilter`] narrows
+//! [`Connection::get_program_accounts`](crate::Connection::get_program_accounts)
+//! results without depending on the RPC client's types, and
+//! [`parse_token_account`] and [`parse_mint_decimals`] read SPL Token (and
+//! Token-2022) accounts for
+//! [`Connection::get_token_account_balance`](crate::Connection::get_token_account_balance).
+
+use solana_sdk::{account::Account, pubkey::Pubkey};
+
+use crate::error::ConnectionError;
+
+/// Most accounts a single `getMultipleAccounts` request may ask for.
+pub const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+/// SPL Token program ID.
+pub const TOKEN_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+/// SPL Token-2022 program ID.
+pub const TOKEN_2022_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PhnBqCXEpPxuEb");
+
+/// Size of the base token account layout shared by both token programs.
+const TOKEN_ACCOUNT_SIZE: usize = 165;
+
+/// Size of the base mint layout shared by both token programs.
+const MINT_SIZE: usize = 82;
+
+/// A filter applied to program accounts on the server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccountFilter {
+    /// Account data is exactly this many bytes.
+    DataSize(u64),
+    /// Account data contains `bytes` at `offset`.
+    Memcmp {
+        /// Byte offset into the account data.
+        offset: usize,
+        /// Bytes to compare.
+        bytes: Vec<u8>,
+    },
+}
+
+impl AccountFilter {
+    /// Match accounts holding `pubkey` at `offset`, such as a token account's
+    /// mint (offset 0) or owner (offset 32).
+    pub fn pubkey_at(offset: usize, pubkey: &Pubkey) -> Self {
+        Self::Memcmp {
+            offset,
+            bytes: pubkey.to_bytes().to_vec(),
+        }
+    }
+
+    /// Whether the account data passes this filter.
+    pub fn matches(&self, data: &[u8]) -> bool {
+        match self {
+            Self::DataSize(size) => data.len() as u64 == *size,
+            Self::Memcmp { offset, bytes } => offset
+                .checked_add(bytes.len())
+                .and_then(|end| data.get(*offset..end))
+                .is_some_and(|window| window == bytes.as_slice()),
+        }
+    }
+}
+
+/// A token amount in base units, with the mint's decimals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenAmount {
+    /// Amount in base units.
+    pub amount: u64,
+    /// Decimals of the mint.
+    pub decimals: u8,
+}
+
+impl TokenAmount {
+    /// The amount in whole tokens.
+    pub fn ui_amount(&self) -> f64 {
+        self.amount as f64 / 10f64.powi(i32::from(self.decimals))
+    }
+}
+
+fn is_token_program(owner: &Pubkey) -> bool {
+    *owner == TOKEN_PROGRAM_ID || *owner == TOKEN_2022_PROGRAM_ID
+}
+
+/// Parse a token account, returning its mint and amount in base units.
+///
+/// # Errors
+///
+/// Returns [`ConnectionError::InvalidAccountData`] if the account is not
+/// owned by a token program or is too short to be a token account.
+pub fn parse_token_account(
+    key: Pubkey,
+    account: &Account,
+) -> Result<(Pubkey, u64), ConnectionError> {
+    if !is_token_program(&account.owner) || account.data.len() < TOKEN_ACCOUNT_SIZE {
+        return Err(ConnectionError::InvalidAccountData(format!(
+            "{} is not a token account",
+            key
+        )));
+    }
+
+    // Layout: mint, owner, amount (u64), ...
+    let data = &account.data;
+    let mut mint = [0u8; 32];
+    mint.copy_from_slice(&data[0..32]);
+    let mut amount = [0u8; 8];
+    amount.copy_from_slice(&data[64..72]);
+    Ok((Pubkey::new_from_array(mint), u64::from_le_bytes(amount)))
+}
+
+/// Parse the decimals of a mint account.
+///
+/// # Errors
+///
+/// Returns [`ConnectionError::InvalidAccountData`] if the account is not
+/// owned by a token program or is too short to be a mint.
+pub fn parse_mint_decimals(key: Pubkey, account: &Account) -> Result<u8, ConnectionError> {
+    if !is_token_program(&account.owner) || account.data.len() < MINT_SIZE {
+        return Err(ConnectionError::InvalidAccountData(format!(
+            "{} is not a mint",
+            key
+        )));
+    }
+
+    // Layout: mint authority (COption<Pubkey>), supply (u64), decimals (u8), ...
+    Ok(account.data[44])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token_account(mint: &Pubkey, amount: u64) -> Account {
+        let mut data = vec![0u8; TOKEN_ACCOUNT_SIZE];
+        data[0..32].copy_from_slice(mint.as_ref());
+        data[64..72].copy_from_slice(&amount.to_le_bytes());
+        Account {
+            lamports: 2_039_280,
+            data,
+            owner: TOKEN_PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    #[test]
+    fn test_filters() {
+        let mint = Pubkey::new_unique();
+        let account = token_account(&mint, 5);
+
+        assert!(AccountFilter::DataSize(165).matches(&account.data));
+        assert!(!AccountFilter::DataSize(82).matches(&account.data));
+        assert!(AccountFilter::pubkey_at(0, &mint).matches(&account.data));
+        assert!(!AccountFilter::pubkey_at(32, &mint).matches(&account.data));
+        assert!(!AccountFilter::pubkey_at(usize::MAX, &mint).matches(&account.data));
+    }
+
+    #[test]
+    fn test_parse_token_account() {
+        let key = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let account = token_account(&mint, 1_500_000);
+
+        assert_eq!(
+            parse_token_account(key, &account).unwrap(),
+            (mint, 1_500_000)
+        );
+
+        let mut wrong_owner = account;
+        wrong_owner.owner = Pubkey::new_unique();
+        assert!(parse_token_account(key, &wrong_owner).is_err());
+    }
+
+    #[test]
+    fn test_parse_mint_decimals() {
+        let key = Pubkey::new_unique();
+        let mut data = vec![0u8; MINT_SIZE];
+        data[44] = 6;
+        let mint = Account {
+            lamports: 1_461_600,
+            data,
+            owner: TOKEN_2022_PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        assert_eq!(parse_mint_decimals(key, &mint).unwrap(), 6);
+
+        let amount = TokenAmount {
+            amount: 1_500_000,
+            decimals: 6,
+        };
+        assert!((amount.ui_amount() - 1.5).abs() < f64::EPSILON);
+    }
+}
@@ -0,0 +1,281 @@
+//! Concurrent balance queries.
+//!
+//! Showing balances for many signers one `getBalance` call at a time makes a
+//! listing as slow as its longest tail of round trips. [`BalanceFetcher`]
+//! reads the accounts in `getMultipleAccounts` batches and keeps several
+//! batches in flight at once.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use solana_sdk::{account::Account, pubkey::Pubkey};
+use tokio::task::JoinSet;
+
+use crate::accounts::{
+    MAX_MULTIPLE_ACCOUNTS, TokenAmount, parse_mint_decimals, parse_token_account,
+};
+use crate::connection::Connection;
+use crate::error::ConnectionError;
+
+/// Default number of batches requested at once.
+pub const DEFAULT_BALANCE_CONCURRENCY: usize = 4;
+
+/// Fetches balances for many accounts in concurrent batches.
+///
+/// # Example
+///
+/// ```ignore
+/// use std::sync::Arc;
+/// use solana_actor::{BalanceFetcher, RpcConnection};
+///
+/// let fetcher = BalanceFetcher::new(Arc::new(RpcConnection::new(url)));
+/// let lamports = fetcher.lamports(&signers).await?;
+/// let tokens = fetcher.token_balances(&token_accounts).await?;
+/// ```
+#[derive(Clone)]
+pub struct BalanceFetcher {
+    connection: Arc<dyn Connection>,
+    batch_size: usize,
+    concurrency: usize,
+}
+
+impl BalanceFetcher {
+    /// Create a fetcher with full batches and the default concurrency.
+    pub fn new(connection: Arc<dyn Connection>) -> Self {
+        Self {
+            connection,
+            batch_size: MAX_MULTIPLE_ACCOUNTS,
+            concurrency: DEFAULT_BALANCE_CONCURRENCY,
+        }
+    }
+
+    /// Set the accounts per request, at most [`MAX_MULTIPLE_ACCOUNTS`].
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.clamp(1, MAX_MULTIPLE_ACCOUNTS);
+        self
+    }
+
+    /// Set how many requests may be in flight at once.
+    ///
+    /// Rate-limited endpoints may need a lower value; an [`RpcConnection`]
+    /// with a rate limit throttles the batches either way.
+    ///
+    /// [`RpcConnection`]: crate::RpcConnection
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Fetch accounts, one entry per pubkey, in order.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first [`ConnectionError`] of any batch; batches still in
+    /// flight are cancelled.
+    pub async fn accounts(
+        &self,
+        pubkeys: &[Pubkey],
+    ) -> Result<Vec<Option<Account>>, ConnectionError> {
+        let mut batches = pubkeys
+            .chunks(self.batch_size)
+            .map(<[Pubkey]>::to_vec)
+            .enumerate();
+        let mut results: Vec<Vec<Option<Account>>> = vec![Vec::new(); batches.len()];
+
+        let mut tasks = JoinSet::new();
+        loop {
+            while tasks.len() < self.concurrency {
+                let Some((index, batch)) = batches.next() else {
+                    break;
+                };
+                let connection = Arc::clone(&self.connection);
+                tasks.spawn(async move { (index, connection.get_multiple_accounts(&batch).await) });
+            }
+            let Some(joined) = tasks.join_next().await else {
+                break;
+            };
+            let (index, accounts) =
+                joined.map_err(|e| ConnectionError::Rpc(format!("Account query failed: {}", e)))?;
+            results[index] = accounts?;
+        }
+
+        Ok(results.into_iter().flatten().collect())
+    }
+
+    /// Fetch lamport balances. Accounts that don't exist have a zero balance.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConnectionError`] if a request fails.
+    pub async fn lamports(
+        &self,
+        pubkeys: &[Pubkey],
+    ) -> Result<HashMap<Pubkey, u64>, ConnectionError> {
+        let accounts = self.accounts(pubkeys).await?;
+        Ok(pubkeys
+            .iter()
+            .zip(accounts)
+            .map(|(pubkey, account)| (*pubkey, account.map_or(0, |a| a.lamports)))
+            .collect())
+    }
+
+    /// Fetch SPL token balances, reading each mint's decimals once.
+    ///
+    /// Token accounts that don't exist are left out of the result.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConnectionError::InvalidAccountData`] if an account is not a
+    /// token account or its mint is missing, or [`ConnectionError`] if a
+    /// request fails.
+    pub async fn token_balances(
+        &self,
+        token_accounts: &[Pubkey],
+    ) -> Result<HashMap<Pubkey, TokenAmount>, ConnectionError> {
+        let mut holdings = Vec::new();
+        for (key, account) in token_accounts
+            .iter()
+            .zip(self.accounts(token_accounts).await?)
+        {
+            if let Some(account) = account {
+                let (mint, amount) = parse_token_account(*key, &account)?;
+                holdings.push((*key, mint, amount));
+            }
+        }
+
+        let mut mints: Vec<Pubkey> = holdings.iter().map(|(_, mint, _)| *mint).collect();
+        mints.sort();
+        mints.dedup();
+        let mut decimals = HashMap::with_capacity(mints.len());
+        for (mint, account) in mints.iter().zip(self.accounts(&mints).await?) {
+            let account = account.ok_or_else(|| {
+                ConnectionError::InvalidAccountData(format!("{} does not exist", mint))
+            })?;
+            decimals.insert(*mint, parse_mint_decimals(*mint, &account)?);
+        }
+
+        Ok(holdings
+            .into_iter()
+            .map(|(key, mint, amount)| {
+                (
+                    key,
+                    TokenAmount {
+                        amount,
+                        decimals: decimals[&mint],
+                    },
+                )
+            })
+            .collect())
+    }
+}
+
+impl std::fmt::Debug for BalanceFetcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BalanceFetcher")
+            .field("batch_size", &self.batch_size)
+            .field("concurrency", &self.concurrency)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::accounts::TOKEN_PROGRAM_ID;
+    use crate::mock::{MockCall, MockConnection};
+
+    fn account(lamports: u64, data: Vec<u8>, owner: Pubkey) -> Account {
+        Account {
+            lamports,
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lamports_in_batches() {
+        let pubkeys: Vec<Pubkey> = (0..25).map(|_| Pubkey::new_unique()).collect();
+        let mut connection = MockConnection::new();
+        for (i, pubkey) in pubkeys.iter().enumerate().skip(1) {
+            connection =
+                connection.with_account(*pubkey, account(i as u64, vec![], Pubkey::default()));
+        }
+        let connection = Arc::new(connection);
+
+        let fetcher = BalanceFetcher::new(connection.clone())
+            .with_batch_size(10)
+            .with_concurrency(2);
+        let balances = fetcher.lamports(&pubkeys).await.unwrap();
+
+        assert_eq!(balances.len(), 25);
+        assert_eq!(balances[&pubkeys[0]], 0);
+        assert_eq!(balances[&pubkeys[24]], 24);
+        assert_eq!(
+            connection.count(|c| matches!(c, MockCall::GetMultipleAccounts(_))),
+            3
+        );
+        assert_eq!(
+            connection.count(|c| matches!(c, MockCall::GetBalance(_))),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_token_balances_share_mints() {
+        let mint = Pubkey::new_unique();
+        let mut mint_data = vec![0u8; 82];
+        mint_data[44] = 6;
+        let token_account = |amount: u64| {
+            let mut data = vec![0u8; 165];
+            data[0..32].copy_from_slice(mint.as_ref());
+            data[64..72].copy_from_slice(&amount.to_le_bytes());
+            account(2_039_280, data, TOKEN_PROGRAM_ID)
+        };
+        let (first, second, missing) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let connection = Arc::new(
+            MockConnection::new()
+                .with_account(mint, account(1, mint_data, TOKEN_PROGRAM_ID))
+                .with_account(first, token_account(1_500_000))
+                .with_account(second, token_account(7)),
+        );
+
+        let balances = BalanceFetcher::new(connection.clone())
+            .token_balances(&[first, missing, second])
+            .await
+            .unwrap();
+
+        assert_eq!(balances.len(), 2);
+        assert_eq!(balances[&first].ui_amount(), 1.5);
+        assert_eq!(
+            balances[&second],
+            TokenAmount {
+                amount: 7,
+                decimals: 6
+            }
+        );
+        // One request for the token accounts, one for their shared mint
+        assert_eq!(
+            connection.count(|c| matches!(c, MockCall::GetMultipleAccounts(_))),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejects_non_token_account() {
+        let key = Pubkey::new_unique();
+        let connection = Arc::new(
+            MockConnection::new().with_account(key, account(1, vec![0; 165], Pubkey::default())),
+        );
+
+        assert!(matches!(
+            BalanceFetcher::new(connection).token_balances(&[key]).await,
+            Err(ConnectionError::InvalidAccountData(_))
+        ));
+    }
+}
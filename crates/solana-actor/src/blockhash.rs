@@ -0,0 +1,148 @@
+//! Blockhash caching.
+//!
+//! A blockhash stays valid for about 150 slots, so batch flows that sign many
+//! transactions in a row don't need a fresh one for each. [`CachedBlockhash`]
+//! keeps the latest blockhash for a configurable age and refreshes it
+//! transparently once it goes stale;
+//! [`RpcConnection::with_blockhash_cache`](crate::RpcConnection::with_blockhash_cache)
+//! puts one in front of `get_latest_blockhash`.
+
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use solana_sdk::hash::Hash;
+
+use crate::error::ConnectionError;
+
+/// Nominal time between slots.
+pub const SLOT_DURATION: Duration = Duration::from_millis(400);
+
+/// Slots a cached blockhash is reused for by default.
+///
+/// Well inside the ~150 slots a blockhash stays valid, leaving time for
+/// transactions signed late in the window to land.
+pub const DEFAULT_BLOCKHASH_MAX_AGE_SLOTS: u64 = 60;
+
+/// The latest blockhash, reused until it is older than a maximum age.
+///
+/// # Example
+///
+/// ```ignore
+/// use solana_actor::{CachedBlockhash, RpcConnection};
+///
+/// // Reuse each blockhash for up to 30 slots (about 12 seconds)
+/// let conn = RpcConnection::new("https://api.mainnet-beta.solana.com")
+///     .with_blockhash_cache(CachedBlockhash::with_max_age_slots(30));
+/// ```
+#[derive(Debug)]
+pub struct CachedBlockhash {
+    max_age: Duration,
+    cached: Mutex<Option<(Hash, Instant)>>,
+}
+
+impl CachedBlockhash {
+    /// Cache blockhashes for `max_age`.
+    pub fn new(max_age: Duration) -> Self {
+        Self {
+            max_age,
+            cached: Mutex::new(None),
+        }
+    }
+
+    /// Cache blockhashes for `slots` slots of [`SLOT_DURATION`].
+    pub fn with_max_age_slots(slots: u64) -> Self {
+        Self::new(SLOT_DURATION.saturating_mul(u32::try_from(slots).unwrap_or(u32::MAX)))
+    }
+
+    /// How long a blockhash is reused.
+    pub fn max_age(&self) -> Duration {
+        self.max_age
+    }
+
+    /// The cached blockhash, if it is still fresh.
+    pub fn get(&self) -> Option<Hash> {
+        let cached = self.cached.lock().unwrap_or_else(|e| e.into_inner());
+        cached
+            .filter(|(_, fetched)| fetched.elapsed() < self.max_age)
+            .map(|(hash, _)| hash)
+    }
+
+    /// Cache a blockhash fetched just now.
+    pub fn set(&self, hash: Hash) {
+        *self.cached.lock().unwrap_or_else(|e| e.into_inner()) = Some((hash, Instant::now()));
+    }
+
+    /// Drop the cached blockhash, e.g. after the network reports it expired.
+    pub fn invalidate(&self) {
+        *self.cached.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    /// The cached blockhash, or a new one from `fetch` if it is stale.
+    ///
+    /// Failed fetches are not cached.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error from `fetch`.
+    pub async fn get_or_refresh<F, Fut>(&self, fetch: F) -> Result<Hash, ConnectionError>
+    where
+        F: FnOnce() -> Fut + Send,
+        Fut: Future<Output = Result<Hash, ConnectionError>> + Send,
+    {
+        if let Some(hash) = self.get() {
+            return Ok(hash);
+        }
+        let hash = fetch().await?;
+        self.set(hash);
+        Ok(hash)
+    }
+}
+
+impl Default for CachedBlockhash {
+    fn default() -> Self {
+        Self::with_max_age_slots(DEFAULT_BLOCKHASH_MAX_AGE_SLOTS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn test_reuses_until_stale() {
+        let fetches = AtomicUsize::new(0);
+        let fetch = || async {
+            fetches.fetch_add(1, Ordering::SeqCst);
+            Ok(Hash::new_unique())
+        };
+
+        let cache = CachedBlockhash::default();
+        assert_eq!(cache.max_age(), Duration::from_secs(24));
+        let first = cache.get_or_refresh(fetch).await.unwrap();
+        assert_eq!(cache.get_or_refresh(fetch).await.unwrap(), first);
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+        // Expired blockhashes are dropped and fetched again
+        cache.invalidate();
+        assert_ne!(cache.get_or_refresh(fetch).await.unwrap(), first);
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+
+        // A zero age never reuses
+        let cache = CachedBlockhash::new(Duration::ZERO);
+        cache.get_or_refresh(fetch).await.unwrap();
+        cache.get_or_refresh(fetch).await.unwrap();
+        assert_eq!(fetches.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_failed_fetch_is_not_cached() {
+        let cache = CachedBlockhash::default();
+        let err = cache
+            .get_or_refresh(|| async { Err(ConnectionError::RateLimited) })
+            .await;
+        assert!(matches!(err, Err(ConnectionError::RateLimited)));
+        assert!(cache.get().is_none());
+    }
+}
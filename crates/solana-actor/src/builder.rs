@@ -0,0 +1,573 @@
+//! Multi-signer transaction builder.
+//!
+//! This module provides [`TransactionBuilder`], which collects instructions and
+//! the [`TransactionSigner`]s needed to sign them (fee payer, authorities,
+//! ephemeral keys), resolves a blockhash through a [`Connection`] or a durable
+//! nonce, and gathers each signer's signature into a fully-signed
+//! [`Transaction`], or a [`VersionedTransaction`] compiled against address
+//! lookup tables.
+
+use solana_sdk::{
+    hash::Hash,
+    instruction::Instruction,
+    message::{AddressLookupTableAccount, Message, VersionedMessage, v0},
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::{Transaction, VersionedTransaction},
+};
+
+use crate::connection::Connection;
+use crate::error::BuildError;
+use crate::nonce::DurableNonce;
+use crate::preflight::check_transaction_size;
+use crate::registry::SignerRegistry;
+use crate::signer::TransactionSigner;
+
+/// Builder for transactions with several required signers.
+///
+/// The fee payer is the first required signer. Every signer required by the
+/// instructions must be added with [`signer`](Self::signer) or be resolvable
+/// from a [`SignerRegistry`] set with [`signers_from`](Self::signers_from);
+/// building fails with [`BuildError::MissingSigners`] listing any that are
+/// absent. A transaction too large to send fails with
+/// [`BuildError::TransactionTooLarge`]. Both are checked before a blockhash
+/// is fetched or anything is signed.
+///
+/// # Example
+///
+/// ```ignore
+/// use solana_actor::TransactionBuilder;
+///
+/// let tx = TransactionBuilder::new(payer.pubkey())
+///     .instruction(create_account_ix)
+///     .instruction(initialize_ix)
+///     .signer(&payer)
+///     .signer(&authority)
+///     .signer(&new_account)
+///     .build(&connection)
+///     .await?;
+///
+/// connection.send_and_confirm(&tx, SendConfig::default()).await?;
+/// ```
+///
+/// For offline or delayed signing, use a durable nonce instead of a recent
+/// blockhash:
+///
+/// ```ignore
+/// let nonce = connection.get_durable_nonce(&nonce_account).await?.unwrap();
+/// let tx = TransactionBuilder::new(payer.pubkey())
+///     .durable_nonce(nonce)
+///     .instruction(transfer_ix)
+///     .signer(&payer)
+///     .signer(&nonce_authority)
+///     .build(&connection)
+///     .await?;
+/// ```
+///
+/// When a transaction is too large, load some of its accounts from lookup
+/// tables with a v0 transaction:
+///
+/// ```ignore
+/// let table = connection.get_address_lookup_table(&table_key).await?.unwrap();
+/// let tx = TransactionBuilder::new(payer.pubkey())
+///     .instructions(instructions)
+///     .lookup_table(table)
+///     .signer(&payer)
+///     .build_versioned(&connection)
+///     .await?;
+/// ```
+pub struct TransactionBuilder<'a> {
+    payer: Pubkey,
+    instructions: Vec<Instruction>,
+    signers: Vec<&'a dyn TransactionSigner>,
+    registry: Option<&'a dyn SignerRegistry>,
+    blockhash: Option<Hash>,
+    nonce: Option<DurableNonce>,
+    lookup_tables: Vec<AddressLookupTableAccount>,
+}
+
+impl<'a> TransactionBuilder<'a> {
+    /// Create a new builder with the given fee payer.
+    pub fn new(payer: Pubkey) -> Self {
+        Self {
+            payer,
+            instructions: Vec::new(),
+            signers: Vec::new(),
+            registry: None,
+            blockhash: None,
+            nonce: None,
+            lookup_tables: Vec::new(),
+        }
+    }
+
+    /// Add an instruction.
+    pub fn instruction(mut self, instruction: Instruction) -> Self {
+        self.instructions.push(instruction);
+        self
+    }
+
+    /// Add several instructions.
+    pub fn instructions(mut self, instructions: impl IntoIterator<Item = Instruction>) -> Self {
+        self.instructions.extend(instructions);
+        self
+    }
+
+    /// Add a signer.
+    ///
+    /// Adding the same public key more than once has no further effect.
+    pub fn signer(mut self, signer: &'a dyn TransactionSigner) -> Self {
+        if !self.signers.iter().any(|s| s.pubkey() == signer.pubkey()) {
+            self.signers.push(signer);
+        }
+        self
+    }
+
+    /// Resolve required signers that were not added with
+    /// [`signer`](Self::signer) from `registry`.
+    ///
+    /// Only signers the transaction requires are taken from the registry, so
+    /// it may hold any number of other keys.
+    pub fn signers_from(mut self, registry: &'a dyn SignerRegistry) -> Self {
+        self.registry = Some(registry);
+        self
+    }
+
+    /// Use a specific blockhash instead of fetching the latest one.
+    pub fn blockhash(mut self, blockhash: Hash) -> Self {
+        self.blockhash = Some(blockhash);
+        self
+    }
+
+    /// Use a durable nonce instead of a recent blockhash.
+    ///
+    /// The nonce's `AdvanceNonceAccount` instruction is placed first and its
+    /// stored nonce is used as the blockhash. The nonce authority must be
+    /// added with [`signer`](Self::signer).
+    pub fn durable_nonce(mut self, nonce: DurableNonce) -> Self {
+        self.blockhash = Some(nonce.blockhash);
+        self.nonce = Some(nonce);
+        self
+    }
+
+    /// Load accounts from an address lookup table.
+    ///
+    /// Only versioned transactions can use lookup tables; build with
+    /// [`build_versioned`](Self::build_versioned) or
+    /// [`sign_versioned`](Self::sign_versioned).
+    pub fn lookup_table(mut self, table: AddressLookupTableAccount) -> Self {
+        self.lookup_tables.push(table);
+        self
+    }
+
+    /// The fee payer.
+    pub fn payer(&self) -> Pubkey {
+        self.payer
+    }
+
+    /// The signers the transaction requires, in signature order.
+    pub fn required_signers(&self) -> Vec<Pubkey> {
+        let message = Message::new(&self.message_instructions(), Some(&self.payer));
+        message.signer_keys().into_iter().copied().collect()
+    }
+
+    /// Required signers that have not been added and are not in the registry.
+    pub fn missing_signers(&self) -> Vec<Pubkey> {
+        self.required_signers()
+            .into_iter()
+            .filter(|key| self.resolve(key).is_none())
+            .collect()
+    }
+
+    /// Build and sign the transaction, fetching the latest blockhash from
+    /// `connection` unless one was set with [`blockhash`](Self::blockhash) or
+    /// [`durable_nonce`](Self::durable_nonce).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BuildError`] if a required signer is missing, an added signer
+    /// is not required, the transaction is too large, a signer fails, or the
+    /// blockhash cannot be fetched.
+    pub async fn build<C: Connection + ?Sized>(
+        self,
+        connection: &C,
+    ) -> Result<Transaction, BuildError> {
+        self.check(false)?;
+        let blockhash = match self.blockhash {
+            Some(blockhash) => blockhash,
+            None => connection.get_latest_blockhash().await?,
+        };
+        self.sign(blockhash)
+    }
+
+    /// Build and sign a v0 transaction, loading accounts from the lookup
+    /// tables added with [`lookup_table`](Self::lookup_table).
+    ///
+    /// The blockhash is resolved as in [`build`](Self::build).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BuildError`] as [`build`](Self::build) does, or if the
+    /// message can't be compiled.
+    pub async fn build_versioned<C: Connection + ?Sized>(
+        self,
+        connection: &C,
+    ) -> Result<VersionedTransaction, BuildError> {
+        self.check(true)?;
+        let blockhash = match self.blockhash {
+            Some(blockhash) => blockhash,
+            None => connection.get_latest_blockhash().await?,
+        };
+        self.sign_versioned(blockhash)
+    }
+
+    /// Build and sign the transaction with the given blockhash.
+    ///
+    /// When using a durable nonce, pass the nonce's stored blockhash.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BuildError`] if a required signer is missing, an added signer
+    /// is not required, the transaction is too large, or a signer fails.
+    pub fn sign(self, blockhash: Hash) -> Result<Transaction, BuildError> {
+        self.check(false)?;
+
+        let mut message = Message::new(&self.message_instructions(), Some(&self.payer));
+        message.recent_blockhash = blockhash;
+        let signatures = self.collect_signatures(&message.serialize())?;
+
+        Ok(Transaction {
+            signatures,
+            message,
+        })
+    }
+
+    /// Build and sign a v0 transaction with the given blockhash, loading
+    /// accounts from the lookup tables added with
+    /// [`lookup_table`](Self::lookup_table).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BuildError`] as [`sign`](Self::sign) does, or if the message
+    /// can't be compiled.
+    pub fn sign_versioned(self, blockhash: Hash) -> Result<VersionedTransaction, BuildError> {
+        self.check(true)?;
+
+        let message = VersionedMessage::V0(self.compile_v0(blockhash)?);
+        let signatures = self.collect_signatures(&message.serialize())?;
+
+        Ok(VersionedTransaction {
+            signatures,
+            message,
+        })
+    }
+
+    /// Signatures over `message_bytes` from every required signer, in order.
+    ///
+    /// Lookup tables never hold signers, so legacy and v0 messages share the
+    /// order of [`required_signers`](Self::required_signers).
+    fn collect_signatures(&self, message_bytes: &[u8]) -> Result<Vec<Signature>, BuildError> {
+        self.required_signers()
+            .iter()
+            .map(|key| {
+                let signer = self
+                    .resolve(key)
+                    .ok_or_else(|| BuildError::MissingSigners(vec![*key]))?;
+                Ok(signer.sign_transaction(message_bytes)?)
+            })
+            .collect()
+    }
+
+    /// Compile a v0 message against the lookup tables.
+    fn compile_v0(&self, blockhash: Hash) -> Result<v0::Message, BuildError> {
+        v0::Message::try_compile(
+            &self.payer,
+            &self.message_instructions(),
+            &self.lookup_tables,
+            blockhash,
+        )
+        .map_err(|e| BuildError::Compile(e.to_string()))
+    }
+
+    /// The signer for a required key: an added signer, else the registry's.
+    fn resolve(&self, key: &Pubkey) -> Option<&'a dyn TransactionSigner> {
+        self.signers
+            .iter()
+            .find(|s| s.pubkey() == *key)
+            .copied()
+            .or_else(|| self.registry.and_then(|registry| registry.get(key)))
+    }
+
+    /// The instructions to compile, including the nonce advance if any.
+    fn message_instructions(&self) -> Vec<Instruction> {
+        self.nonce
+            .iter()
+            .map(DurableNonce::advance_instruction)
+            .chain(self.instructions.iter().cloned())
+            .collect()
+    }
+
+    /// Validate instructions, signers and size before fetching a blockhash or
+    /// signing, for a v0 message if `versioned`, else a legacy one.
+    fn check(&self, versioned: bool) -> Result<(), BuildError> {
+        if self.instructions.is_empty() {
+            return Err(BuildError::NoInstructions);
+        }
+        if !versioned && !self.lookup_tables.is_empty() {
+            return Err(BuildError::Compile(
+                "lookup tables need a versioned transaction; use build_versioned".to_string(),
+            ));
+        }
+
+        let missing = self.missing_signers();
+        if !missing.is_empty() {
+            return Err(BuildError::MissingSigners(missing));
+        }
+
+        let required = self.required_signers();
+        if let Some(extra) = self
+            .signers
+            .iter()
+            .find(|s| !required.contains(&s.pubkey()))
+        {
+            return Err(BuildError::UnexpectedSigner(extra.pubkey()));
+        }
+
+        // The blockhash doesn't change the size, so the default stands in
+        let message = if versioned {
+            VersionedMessage::V0(self.compile_v0(Hash::default())?)
+        } else {
+            VersionedMessage::Legacy(Message::new(
+                &self.message_instructions(),
+                Some(&self.payer),
+            ))
+        };
+        check_transaction_size(&message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::SignerError;
+    use crate::registry::InMemorySignerRegistry;
+    use solana_sdk::instruction::AccountMeta;
+
+    /// Mock signer producing a signature filled with its tag byte
+    struct MockSigner {
+        pubkey: Pubkey,
+        tag: u8,
+    }
+
+    impl MockSigner {
+        fn new(tag: u8) -> Self {
+            Self {
+                pubkey: Pubkey::new_unique(),
+                tag,
+            }
+        }
+    }
+
+    impl TransactionSigner for MockSigner {
+        fn pubkey(&self) -> Pubkey {
+            self.pubkey
+        }
+
+        fn sign_transaction(&self, _message: &[u8]) -> Result<Signature, SignerError> {
+            Ok(Signature::from([self.tag; 64]))
+        }
+    }
+
+    fn instruction(signers: &[&MockSigner]) -> Instruction {
+        Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[],
+            signers
+                .iter()
+                .map(|s| AccountMeta::new_readonly(s.pubkey, true))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_sign_with_all_signers() {
+        let payer = MockSigner::new(1);
+        let authority = MockSigner::new(2);
+        let ephemeral = MockSigner::new(3);
+
+        let tx = TransactionBuilder::new(payer.pubkey)
+            .instruction(instruction(&[&authority, &ephemeral]))
+            .signer(&ephemeral)
+            .signer(&payer)
+            .signer(&authority)
+            .sign(Hash::new_unique())
+            .unwrap();
+
+        assert_eq!(tx.message.account_keys[0], payer.pubkey);
+        assert_eq!(tx.signatures.len(), 3);
+        for (key, sig) in tx.message.account_keys.iter().zip(&tx.signatures) {
+            let tag = [&payer, &authority, &ephemeral]
+                .iter()
+                .find(|s| s.pubkey == *key)
+                .unwrap()
+                .tag;
+            assert_eq!(*sig, Signature::from([tag; 64]));
+        }
+    }
+
+    #[test]
+    fn test_missing_signers() {
+        let payer = MockSigner::new(1);
+        let authority = MockSigner::new(2);
+
+        let err = TransactionBuilder::new(payer.pubkey)
+            .instruction(instruction(&[&authority]))
+            .signer(&payer)
+            .sign(Hash::new_unique())
+            .unwrap_err();
+
+        match err {
+            BuildError::MissingSigners(missing) => assert_eq!(missing, vec![authority.pubkey]),
+            other => panic!("unexpected error: {}", other),
+        }
+    }
+
+    #[test]
+    fn test_unexpected_signer() {
+        let payer = MockSigner::new(1);
+        let stranger = MockSigner::new(2);
+
+        let err = TransactionBuilder::new(payer.pubkey)
+            .instruction(instruction(&[]))
+            .signer(&payer)
+            .signer(&stranger)
+            .sign(Hash::new_unique())
+            .unwrap_err();
+
+        assert!(matches!(err, BuildError::UnexpectedSigner(key) if key == stranger.pubkey));
+    }
+
+    #[test]
+    fn test_signers_from_registry() {
+        let payer = MockSigner::new(1);
+        let authority = MockSigner::new(2);
+        let (authority_key, payer_key) = (authority.pubkey, payer.pubkey);
+        let registry = InMemorySignerRegistry::new()
+            .with_signer(authority)
+            .with_signer(MockSigner::new(3));
+
+        let builder = TransactionBuilder::new(payer_key)
+            .instruction(Instruction::new_with_bytes(
+                Pubkey::new_unique(),
+                &[],
+                vec![AccountMeta::new_readonly(authority_key, true)],
+            ))
+            .signers_from(&registry);
+        assert_eq!(builder.missing_signers(), vec![payer_key]);
+
+        // Unrequired registry signers are skipped
+        let tx = builder.signer(&payer).sign(Hash::new_unique()).unwrap();
+        assert_eq!(
+            tx.signatures,
+            vec![Signature::from([1; 64]), Signature::from([2; 64])]
+        );
+    }
+
+    #[test]
+    fn test_durable_nonce() {
+        let payer = MockSigner::new(1);
+        let authority = MockSigner::new(2);
+        let nonce = DurableNonce {
+            nonce_account: Pubkey::new_unique(),
+            authority: authority.pubkey,
+            blockhash: Hash::new_unique(),
+            lamports_per_signature: 5000,
+        };
+
+        let builder = TransactionBuilder::new(payer.pubkey)
+            .durable_nonce(nonce)
+            .instruction(instruction(&[]))
+            .signer(&payer);
+        assert_eq!(builder.missing_signers(), vec![authority.pubkey]);
+
+        let tx = builder.signer(&authority).sign(nonce.blockhash).unwrap();
+        assert_eq!(tx.message.recent_blockhash, nonce.blockhash);
+        assert_eq!(tx.message.instructions.len(), 2);
+        let advance = &tx.message.instructions[0];
+        assert_eq!(
+            tx.message.account_keys[advance.accounts[0] as usize],
+            nonce.nonce_account
+        );
+    }
+
+    #[test]
+    fn test_transaction_too_large() {
+        let payer = MockSigner::new(1);
+        let err = TransactionBuilder::new(payer.pubkey)
+            .instruction(Instruction::new_with_bytes(
+                Pubkey::new_unique(),
+                &[0; 1200],
+                vec![],
+            ))
+            .signer(&payer)
+            .sign(Hash::new_unique())
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            BuildError::TransactionTooLarge { size, limit: 1232, .. } if size > 1232
+        ));
+    }
+
+    #[test]
+    fn test_lookup_table_shrinks_transaction() {
+        let payer = MockSigner::new(1);
+        let accounts: Vec<Pubkey> = (0..40).map(|_| Pubkey::new_unique()).collect();
+        let ix = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[],
+            accounts
+                .iter()
+                .map(|key| AccountMeta::new(*key, false))
+                .collect(),
+        );
+
+        // 40 accounts don't fit in a legacy transaction
+        let err = TransactionBuilder::new(payer.pubkey)
+            .instruction(ix.clone())
+            .signer(&payer)
+            .sign(Hash::new_unique())
+            .unwrap_err();
+        assert!(matches!(err, BuildError::TransactionTooLarge { .. }));
+
+        let table = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: accounts,
+        };
+        let tx = TransactionBuilder::new(payer.pubkey)
+            .instruction(ix.clone())
+            .lookup_table(table.clone())
+            .signer(&payer)
+            .sign_versioned(Hash::new_unique())
+            .unwrap();
+        assert_eq!(tx.signatures, vec![Signature::from([1; 64])]);
+        assert_eq!(tx.message.address_table_lookups().unwrap().len(), 1);
+
+        // Legacy transactions can't use the table
+        let err = TransactionBuilder::new(payer.pubkey)
+            .instruction(ix)
+            .lookup_table(table)
+            .signer(&payer)
+            .sign(Hash::new_unique())
+            .unwrap_err();
+        assert!(matches!(err, BuildError::Compile(_)));
+    }
+
+    #[test]
+    fn test_no_instructions() {
+        let payer = MockSigner::new(1);
+        let err = TransactionBuilder::new(payer.pubkey)
+            .signer(&payer)
+            .sign(Hash::new_unique())
+            .unwrap_err();
+        assert!(matches!(err, BuildError::NoInstructions));
+    }
+}
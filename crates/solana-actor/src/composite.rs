@@ -0,0 +1,309 @@
+//! Composite signer holding several keys.
+//!
+//! This module provides [`CompositeSigner`], which signs a compiled message
+//! with every [`TransactionSigner`] it holds that the message requires, such
+//! as a fee payer key and a program authority key kept in the same keyring.
+
+use std::fmt;
+
+use solana_sdk::{
+    message::VersionedMessage, pubkey::Pubkey, signature::Signature,
+    transaction::VersionedTransaction,
+};
+
+use crate::error::SignerError;
+use crate::signer::TransactionSigner;
+
+/// A set of [`TransactionSigner`]s that fan out over a message's required signers.
+///
+/// Given a compiled message, each held signer whose key is among the
+/// message's required signers signs it. Signers the message does not require
+/// are skipped, and required signers the composite does not hold are left
+/// for someone else.
+///
+/// # Example
+///
+/// ```ignore
+/// use solana_actor::CompositeSigner;
+///
+/// let signers = CompositeSigner::new()
+///     .with_signer(fee_payer)
+///     .with_signer(program_authority);
+///
+/// // Both signatures are filled in; any other required slot stays default
+/// let tx = signers.sign_versioned(message)?;
+/// ```
+#[derive(Default)]
+pub struct CompositeSigner {
+    signers: Vec<Box<dyn TransactionSigner>>,
+}
+
+impl CompositeSigner {
+    /// Create an empty composite signer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a signer.
+    ///
+    /// Adding the same public key more than once has no further effect.
+    pub fn with_signer(mut self, signer: impl TransactionSigner + 'static) -> Self {
+        self.push(Box::new(signer));
+        self
+    }
+
+    /// Add a boxed signer.
+    ///
+    /// Adding the same public key more than once has no further effect.
+    pub fn push(&mut self, signer: Box<dyn TransactionSigner>) {
+        if !self.contains(&signer.pubkey()) {
+            self.signers.push(signer);
+        }
+    }
+
+    /// The public keys of the held signers, in insertion order.
+    pub fn pubkeys(&self) -> Vec<Pubkey> {
+        self.signers.iter().map(|s| s.pubkey()).collect()
+    }
+
+    /// Whether a signer for `pubkey` is held.
+    pub fn contains(&self, pubkey: &Pubkey) -> bool {
+        self.signers.iter().any(|s| s.pubkey() == *pubkey)
+    }
+
+    /// The held signer for `pubkey`, if any.
+    pub fn get(&self, pubkey: &Pubkey) -> Option<&dyn TransactionSigner> {
+        self.signers
+            .iter()
+            .find(|s| s.pubkey() == *pubkey)
+            .map(|s| s.as_ref())
+    }
+
+    /// Number of held signers.
+    pub fn len(&self) -> usize {
+        self.signers.len()
+    }
+
+    /// Whether no signers are held.
+    pub fn is_empty(&self) -> bool {
+        self.signers.is_empty()
+    }
+
+    /// Whether any held signer requires user interaction.
+    pub fn is_interactive(&self) -> bool {
+        self.signers.iter().any(|s| s.is_interactive())
+    }
+
+    /// Required signers of `message` that are not held.
+    pub fn missing_signers(&self, message: &VersionedMessage) -> Vec<Pubkey> {
+        required_signers(message)
+            .iter()
+            .filter(|key| !self.contains(key))
+            .copied()
+            .collect()
+    }
+
+    /// Sign `message` with every held signer it requires.
+    ///
+    /// Returns `(position, signature)` pairs, where `position` is the index of
+    /// the signer among the message's required signers.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SignerError::SigningFailed`] if no held signer is required by
+    /// the message, or the first error from a signer.
+    pub fn sign_message(
+        &self,
+        message: &VersionedMessage,
+    ) -> Result<Vec<(usize, Signature)>, SignerError> {
+        let message_bytes = message.serialize();
+        let signatures = required_signers(message)
+            .iter()
+            .enumerate()
+            .filter_map(|(position, key)| {
+                self.signers
+                    .iter()
+                    .find(|s| s.pubkey() == *key)
+                    .map(|signer| (position, signer))
+            })
+            .map(|(position, signer)| Ok((position, signer.sign_transaction(&message_bytes)?)))
+            .collect::<Result<Vec<_>, SignerError>>()?;
+
+        if signatures.is_empty() {
+            return Err(SignerError::SigningFailed(
+                "No held signer is a required signer of the message".to_string(),
+            ));
+        }
+        Ok(signatures)
+    }
+
+    /// Sign `message` into a transaction with every held signer it requires.
+    ///
+    /// Signature slots for required signers that are not held are left as
+    /// default signatures so other parties can fill them in.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`sign_message`](Self::sign_message).
+    pub fn sign_versioned(
+        &self,
+        message: VersionedMessage,
+    ) -> Result<VersionedTransaction, SignerError> {
+        let mut transaction = VersionedTransaction {
+            signatures: vec![Signature::default(); required_signers(&message).len()],
+            message,
+        };
+        self.partial_sign(&mut transaction)?;
+        Ok(transaction)
+    }
+
+    /// Add the held signers' signatures to a partially signed transaction.
+    ///
+    /// Signatures from other parties are kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`sign_message`](Self::sign_message).
+    pub fn partial_sign(&self, transaction: &mut VersionedTransaction) -> Result<(), SignerError> {
+        let num_signers = required_signers(&transaction.message).len();
+        transaction
+            .signatures
+            .resize(num_signers, Signature::default());
+        for (position, signature) in self.sign_message(&transaction.message)? {
+            transaction.signatures[position] = signature;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for CompositeSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CompositeSigner")
+            .field("pubkeys", &self.pubkeys())
+            .finish()
+    }
+}
+
+/// The message's required signers, in signature order.
+pub(crate) fn required_signers(message: &VersionedMessage) -> &[Pubkey] {
+    let keys = message.static_account_keys();
+    let num_signers = (message.header().num_required_signatures as usize).min(keys.len());
+    &keys[..num_signers]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{
+        hash::Hash,
+        instruction::{AccountMeta, Instruction},
+        message::Message,
+    };
+
+    #[derive(Clone)]
+    struct MockSigner {
+        pubkey: Pubkey,
+        byte: u8,
+    }
+
+    impl TransactionSigner for MockSigner {
+        fn pubkey(&self) -> Pubkey {
+            self.pubkey
+        }
+
+        fn sign_transaction(&self, _message: &[u8]) -> Result<Signature, SignerError> {
+            Ok(Signature::from([self.byte; 64]))
+        }
+    }
+
+    fn message(payer: Pubkey, authorities: &[Pubkey]) -> VersionedMessage {
+        let ix = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[],
+            authorities
+                .iter()
+                .map(|key| AccountMeta::new_readonly(*key, true))
+                .collect(),
+        );
+        VersionedMessage::Legacy(Message::new_with_blockhash(
+            &[ix],
+            Some(&payer),
+            &Hash::new_unique(),
+        ))
+    }
+
+    #[test]
+    fn test_signs_every_held_required_signer() {
+        let (payer, authority, other) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let signers = CompositeSigner::new()
+            .with_signer(MockSigner {
+                pubkey: authority,
+                byte: 2,
+            })
+            .with_signer(MockSigner {
+                pubkey: payer,
+                byte: 1,
+            })
+            .with_signer(MockSigner {
+                pubkey: Pubkey::new_unique(),
+                byte: 9,
+            });
+
+        let message = message(payer, &[authority, other]);
+        assert_eq!(signers.missing_signers(&message), vec![other]);
+
+        let tx = signers.sign_versioned(message).unwrap();
+        assert_eq!(tx.signatures.len(), 3);
+        assert_eq!(tx.signatures[0], Signature::from([1; 64]));
+        assert_eq!(tx.signatures[1], Signature::from([2; 64]));
+        assert_eq!(tx.signatures[2], Signature::default());
+    }
+
+    #[test]
+    fn test_partial_sign_keeps_other_signatures() {
+        let (payer, authority) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut tx = VersionedTransaction {
+            signatures: vec![Signature::from([1; 64]), Signature::default()],
+            message: message(payer, &[authority]),
+        };
+
+        CompositeSigner::new()
+            .with_signer(MockSigner {
+                pubkey: authority,
+                byte: 2,
+            })
+            .partial_sign(&mut tx)
+            .unwrap();
+        assert_eq!(
+            tx.signatures,
+            vec![Signature::from([1; 64]), Signature::from([2; 64])]
+        );
+    }
+
+    #[test]
+    fn test_no_required_signer_is_error() {
+        let signers = CompositeSigner::new().with_signer(MockSigner {
+            pubkey: Pubkey::new_unique(),
+            byte: 1,
+        });
+        assert!(
+            signers
+                .sign_message(&message(Pubkey::new_unique(), &[]))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_duplicate_signer_ignored() {
+        let pubkey = Pubkey::new_unique();
+        let signers = CompositeSigner::new()
+            .with_signer(MockSigner { pubkey, byte: 1 })
+            .with_signer(MockSigner { pubkey, byte: 2 });
+        assert_eq!(signers.len(), 1);
+        assert_eq!(signers.pubkeys(), vec![pubkey]);
+    }
+}
@@ -0,0 +1,137 @@
+//! Commitment levels and signature statuses.
+//!
+//! [`Connection::get_signature_statuses`](crate::Connection::get_signature_statuses)
+//! reports how far each transaction has progressed as a [`SignatureStatus`],
+//! and [`Connection::confirm_signature`](crate::Connection::confirm_signature)
+//! waits for a transaction to reach a chosen [`Commitment`].
+//! [`Connection::get_signatures_for_address`](crate::Connection::get_signatures_for_address)
+//! lists the transactions that touched an account as [`SignatureInfo`].
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use solana_sdk::signature::Signature;
+
+/// Maximum signatures per `getSignatureStatuses` request.
+pub const MAX_SIGNATURE_STATUSES: usize = 256;
+
+/// Delay between status checks while waiting for a commitment level.
+pub const CONFIRM_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How long to wait for a commitment level before giving up.
+///
+/// A little longer than a blockhash stays valid, so a transaction that can
+/// still land is not abandoned early.
+pub const CONFIRM_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// How settled a transaction is, from least to most.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Commitment {
+    /// Included in a block by the node queried; may still be rolled back.
+    Processed,
+    /// Voted on by a supermajority of the cluster.
+    #[default]
+    Confirmed,
+    /// Rooted; will not be rolled back.
+    Finalized,
+}
+
+impl Commitment {
+    /// The name used by the RPC API.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Processed => "processed",
+            Self::Confirmed => "confirmed",
+            Self::Finalized => "finalized",
+        }
+    }
+}
+
+impl fmt::Display for Commitment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Commitment {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "processed" => Ok(Self::Processed),
+            "confirmed" => Ok(Self::Confirmed),
+            "finalized" => Ok(Self::Finalized),
+            _ => Err(format!(
+                "Unknown commitment '{}' (expected processed, confirmed or finalized)",
+                s
+            )),
+        }
+    }
+}
+
+/// The status of a transaction the cluster has seen.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureStatus {
+    /// Slot the transaction was processed in.
+    pub slot: u64,
+    /// Blocks confirmed since, or `None` once rooted.
+    pub confirmations: Option<usize>,
+    /// Why the transaction failed, if it did.
+    pub error: Option<String>,
+    /// The commitment level the transaction has reached.
+    pub commitment: Commitment,
+}
+
+impl SignatureStatus {
+    /// Whether the transaction has reached at least `commitment`.
+    ///
+    /// Failed transactions reach commitment levels too; check
+    /// [`error`](Self::error) separately.
+    pub fn reached(&self, commitment: Commitment) -> bool {
+        self.commitment >= commitment
+    }
+}
+
+/// A transaction that referenced an address, from its signature history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureInfo {
+    /// The transaction's first signature.
+    pub signature: Signature,
+    /// Slot the transaction was processed in.
+    pub slot: u64,
+    /// Why the transaction failed, if it did.
+    pub error: Option<String>,
+    /// Unix time of the block, if the node recorded it.
+    pub block_time: Option<i64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commitment_order_and_parse() {
+        assert!(Commitment::Processed < Commitment::Confirmed);
+        assert!(Commitment::Confirmed < Commitment::Finalized);
+        assert_eq!(
+            "Finalized".parse::<Commitment>().unwrap(),
+            Commitment::Finalized
+        );
+        assert_eq!(Commitment::default().to_string(), "confirmed");
+        assert!("max".parse::<Commitment>().is_err());
+    }
+
+    #[test]
+    fn test_status_reached() {
+        let status = SignatureStatus {
+            slot: 1,
+            confirmations: Some(3),
+            error: None,
+            commitment: Commitment::Confirmed,
+        };
+        assert!(status.reached(Commitment::Processed));
+        assert!(status.reached(Commitment::Confirmed));
+        assert!(!status.reached(Commitment::Finalized));
+    }
+}
@@ -4,12 +4,31 @@
 //! When the `rpc` feature is enabled, it also provides [`RpcConnection`] which wraps
 //! the standard Solana RPC client.
 
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
 use async_trait::async_trait;
 use solana_sdk::{
-    account::Account, hash::Hash, pubkey::Pubkey, signature::Signature, transaction::Transaction,
+    account::Account,
+    hash::Hash,
+    message::AddressLookupTableAccount,
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::{Transaction, VersionedTransaction},
 };
 
+use crate::accounts::{AccountFilter, TokenAmount, parse_mint_decimals, parse_token_account};
+use crate::confirmation::{
+    CONFIRM_POLL_INTERVAL, CONFIRM_TIMEOUT, Commitment, SignatureInfo, SignatureStatus,
+};
 use crate::error::ConnectionError;
+use crate::lookup_table::parse_address_lookup_table;
+use crate::nonce::{DurableNonce, durable_nonce_account, parse_nonce_account};
+use crate::priority_fee::{
+    DEFAULT_PRIORITY_FEE_PERCENTILE, PrioritizationFee, recommend_compute_unit_price,
+};
+use crate::simulation::SimulationResult;
 
 /// Configuration for transaction sending.
 #[derive(Debug, Clone, Default)]
@@ -18,6 +37,85 @@ pub struct SendConfig {
     pub skip_preflight: bool,
     /// Maximum number of retries for sending.
     pub max_retries: Option<usize>,
+    /// How `send_and_confirm` waits for confirmation.
+    pub confirm: ConfirmStrategy,
+    /// How often to re-sign with a fresh blockhash when the blockhash expires.
+    ///
+    /// Used by pipelines that can re-sign, such as
+    /// [`DirectTransport::send_instructions`](crate::DirectTransport::send_instructions).
+    pub retry: RetryPolicy,
+}
+
+/// Retry policy for re-signing after a blockhash expires.
+///
+/// The delay before retry `n` (starting at 1) is `initial_backoff * 2^(n-1)`,
+/// capped at `max_backoff`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total attempts, including the first. `1` disables retries.
+    pub max_attempts: u32,
+    /// Delay before the first retry.
+    pub initial_backoff: Duration,
+    /// Upper bound on the delay between retries.
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// A policy that never retries.
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// The delay before the given retry (1 for the first retry).
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+
+    /// The delay before the given retry, jittered to between half and all of
+    /// [`backoff`](Self::backoff) so concurrent clients don't retry in step.
+    pub fn jittered_backoff(&self, retry: u32) -> Duration {
+        jitter(self.backoff(retry), random_fraction())
+    }
+}
+
+/// Scale `delay` to between half and all of itself; `fraction` is in `[0, 1)`.
+fn jitter(delay: Duration, fraction: f64) -> Duration {
+    delay.mul_f64(0.5 + fraction / 2.0)
+}
+
+/// A random number in `[0, 1)` from the standard library's hasher seeds.
+fn random_fraction() -> f64 {
+    let bits = RandomState::new().build_hasher().finish();
+    (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(4),
+        }
+    }
+}
+
+/// How a connection waits for a sent transaction to be confirmed.
+///
+/// [`RpcConnection`] always polls. `PubsubConnection` (behind the `ws`
+/// feature) honors [`ConfirmStrategy::WebSocket`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConfirmStrategy {
+    /// Poll signature statuses over HTTP.
+    #[default]
+    Polling,
+    /// Subscribe to the signature and to slots over a WebSocket.
+    WebSocket,
 }
 
 /// Trait for network connection operations.
@@ -69,6 +167,188 @@ pub trait Connection: Send + Sync {
         config: SendConfig,
     ) -> Result<Signature, ConnectionError>;
 
+    /// Send a versioned transaction to the network.
+    ///
+    /// Both legacy and v0 messages are supported, including v0 messages that
+    /// load accounts from address lookup tables.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - The signed versioned transaction to send.
+    /// * `config` - Configuration options for sending.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConnectionError`] if the transaction cannot be sent.
+    async fn send_versioned_transaction(
+        &self,
+        transaction: &VersionedTransaction,
+        config: SendConfig,
+    ) -> Result<Signature, ConnectionError>;
+
+    /// Send a versioned transaction and wait for confirmation.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - The signed versioned transaction to send.
+    /// * `config` - Configuration options for sending.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConnectionError`] if the transaction fails or times out.
+    async fn send_and_confirm_versioned_transaction(
+        &self,
+        transaction: &VersionedTransaction,
+        config: SendConfig,
+    ) -> Result<Signature, ConnectionError>;
+
+    /// Get the statuses of previously sent transactions.
+    ///
+    /// Statuses are returned in the same order as `signatures`, with `None`
+    /// for transactions the node has not seen.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConnectionError`] if the RPC call fails.
+    async fn get_signature_statuses(
+        &self,
+        signatures: &[Signature],
+    ) -> Result<Vec<Option<SignatureStatus>>, ConnectionError>;
+
+    /// Get the transactions that referenced an address, newest first.
+    ///
+    /// Returns at most the node's page size (1000 on most nodes) of the most
+    /// recent transactions, including failed ones, or of those older than
+    /// `before`. Pass the last signature of a page as `before` to read the
+    /// next one; an empty page is the end of the node's history.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConnectionError`] if the RPC call fails.
+    async fn get_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        before: Option<&Signature>,
+    ) -> Result<Vec<SignatureInfo>, ConnectionError>;
+
+    /// Wait for a sent transaction to reach `commitment`.
+    ///
+    /// Polls [`get_signature_statuses`](Self::get_signature_statuses) every
+    /// [`CONFIRM_POLL_INTERVAL`] for up to [`CONFIRM_TIMEOUT`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConnectionError::TransactionFailed`] if the transaction
+    /// landed but failed, [`ConnectionError::Timeout`] if it did not reach
+    /// `commitment` in time, or another [`ConnectionError`] if the RPC call
+    /// fails.
+    async fn confirm_signature(
+        &self,
+        signature: &Signature,
+        commitment: Commitment,
+    ) -> Result<SignatureStatus, ConnectionError> {
+        let deadline = tokio::time::Instant::now() + CONFIRM_TIMEOUT;
+        loop {
+            let status = self
+                .get_signature_statuses(std::slice::from_ref(signature))
+                .await?
+                .pop()
+                .flatten();
+            if let Some(status) = status {
+                if let Some(error) = &status.error {
+                    return Err(ConnectionError::TransactionFailed(error.clone()));
+                }
+                if status.reached(commitment) {
+                    return Ok(status);
+                }
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Err(ConnectionError::Timeout);
+            }
+            tokio::time::sleep(CONFIRM_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Check that a signed transaction has not already landed.
+    ///
+    /// Looks up the transaction's signature and, for a transaction using a
+    /// durable nonce, whether the nonce has since advanced. Sending a
+    /// transaction that already landed fails with an RPC error that doesn't
+    /// say so, which matters when retries or several tools race to submit.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConnectionError::AlreadyProcessed`] if the signature has a
+    /// successful status, [`ConnectionError::TransactionFailed`] if it landed
+    /// but failed, [`ConnectionError::NonceAdvanced`] if its durable nonce
+    /// advanced, or another [`ConnectionError`] if the RPC call fails.
+    async fn check_not_processed(
+        &self,
+        transaction: &VersionedTransaction,
+    ) -> Result<(), ConnectionError> {
+        let Some(signature) = transaction.signatures.first() else {
+            return Ok(());
+        };
+        let status = self
+            .get_signature_statuses(std::slice::from_ref(signature))
+            .await?
+            .pop()
+            .flatten();
+        if let Some(status) = status {
+            return Err(match status.error {
+                Some(error) => ConnectionError::TransactionFailed(error),
+                None => ConnectionError::AlreadyProcessed(*signature),
+            });
+        }
+
+        if let Some(nonce_account) = durable_nonce_account(&transaction.message) {
+            let nonce = self.get_durable_nonce(&nonce_account).await?;
+            if nonce.is_none_or(|nonce| nonce.blockhash != *transaction.message.recent_blockhash())
+            {
+                return Err(ConnectionError::NonceAdvanced(nonce_account));
+            }
+        }
+        Ok(())
+    }
+
+    /// Simulate a versioned transaction without submitting it.
+    ///
+    /// Signatures are not verified and the blockhash is replaced with the
+    /// latest one, so unsigned transactions can be simulated before asking
+    /// signers to approve them.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - The transaction to simulate.
+    ///
+    /// # Returns
+    ///
+    /// The simulation outcome. A transaction that would fail is reported
+    /// through [`SimulationResult::error`], not as an `Err`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConnectionError`] if the RPC call fails.
+    async fn simulate_versioned_transaction(
+        &self,
+        transaction: &VersionedTransaction,
+    ) -> Result<SimulationResult, ConnectionError>;
+
+    /// Simulate a transaction without submitting it.
+    ///
+    /// See [`simulate_versioned_transaction`](Self::simulate_versioned_transaction).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConnectionError`] if the RPC call fails.
+    async fn simulate_transaction(
+        &self,
+        transaction: &Transaction,
+    ) -> Result<SimulationResult, ConnectionError> {
+        self.simulate_versioned_transaction(&VersionedTransaction::from(transaction.clone()))
+            .await
+    }
+
     /// Get the latest blockhash.
     ///
     /// # Errors
@@ -101,14 +381,249 @@ pub trait Connection: Send + Sync {
     ///
     /// Returns [`ConnectionError`] if the RPC call fails.
     async fn get_account(&self, pubkey: &Pubkey) -> Result<Option<Account>, ConnectionError>;
+
+    /// Get several accounts at once.
+    ///
+    /// The default implementation fetches them one at a time;
+    /// [`RpcConnection`] batches them into `getMultipleAccounts` requests.
+    ///
+    /// # Arguments
+    ///
+    /// * `pubkeys` - The accounts to query.
+    ///
+    /// # Returns
+    ///
+    /// One entry per pubkey, in order, `None` where the account doesn't exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConnectionError`] if the RPC call fails.
+    async fn get_multiple_accounts(
+        &self,
+        pubkeys: &[Pubkey],
+    ) -> Result<Vec<Option<Account>>, ConnectionError> {
+        let mut accounts = Vec::with_capacity(pubkeys.len());
+        for pubkey in pubkeys {
+            accounts.push(self.get_account(pubkey).await?);
+        }
+        Ok(accounts)
+    }
+
+    /// Get the accounts owned by a program that pass every filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `program_id` - The owning program.
+    /// * `filters` - Filters applied on the server; empty returns every account.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConnectionError`] if the RPC call fails.
+    async fn get_program_accounts(
+        &self,
+        program_id: &Pubkey,
+        filters: &[AccountFilter],
+    ) -> Result<Vec<(Pubkey, Account)>, ConnectionError>;
+
+    /// Get the balance of an SPL Token or Token-2022 account.
+    ///
+    /// The default implementation reads the token account and its mint;
+    /// [`RpcConnection`] calls `getTokenAccountBalance`.
+    ///
+    /// # Arguments
+    ///
+    /// * `token_account` - The token account to query.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConnectionError::InvalidAccountData`] if the account is
+    /// missing or not a token account, or [`ConnectionError`] if the RPC call
+    /// fails.
+    async fn get_token_account_balance(
+        &self,
+        token_account: &Pubkey,
+    ) -> Result<TokenAmount, ConnectionError> {
+        let missing =
+            |key: &Pubkey| ConnectionError::InvalidAccountData(format!("{} does not exist", key));
+        let account = self
+            .get_account(token_account)
+            .await?
+            .ok_or_else(|| missing(token_account))?;
+        let (mint, amount) = parse_token_account(*token_account, &account)?;
+        let mint_account = self
+            .get_account(&mint)
+            .await?
+            .ok_or_else(|| missing(&mint))?;
+        Ok(TokenAmount {
+            amount,
+            decimals: parse_mint_decimals(mint, &mint_account)?,
+        })
+    }
+
+    /// Get an address lookup table for compiling or resolving v0 messages.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The lookup table address.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the table doesn't exist, `Some(AddressLookupTableAccount)` otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConnectionError`] if the RPC call fails or the account is not
+    /// a valid lookup table.
+    async fn get_address_lookup_table(
+        &self,
+        key: &Pubkey,
+    ) -> Result<Option<AddressLookupTableAccount>, ConnectionError> {
+        match self.get_account(key).await? {
+            Some(account) => parse_address_lookup_table(*key, &account).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Get the durable nonce stored in a nonce account.
+    ///
+    /// # Arguments
+    ///
+    /// * `nonce_account` - The nonce account address.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the account doesn't exist, `Some(DurableNonce)` otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConnectionError`] if the RPC call fails or the account is not
+    /// an initialized nonce account.
+    async fn get_durable_nonce(
+        &self,
+        nonce_account: &Pubkey,
+    ) -> Result<Option<DurableNonce>, ConnectionError> {
+        match self.get_account(nonce_account).await? {
+            Some(account) => parse_nonce_account(*nonce_account, &account).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Get the prioritization fees paid in recent slots.
+    ///
+    /// # Arguments
+    ///
+    /// * `writable_accounts` - Accounts the transaction will write. When
+    ///   non-empty, each fee is the minimum paid by transactions locking all
+    ///   of these accounts; when empty, it is the minimum across the slot.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConnectionError`] if the RPC call fails.
+    async fn get_recent_prioritization_fees(
+        &self,
+        writable_accounts: &[Pubkey],
+    ) -> Result<Vec<PrioritizationFee>, ConnectionError>;
+
+    /// Recommend a compute-unit price for a transaction writing the given
+    /// accounts.
+    ///
+    /// Returns the 75th percentile of recent prioritization fees, in
+    /// micro-lamports per compute unit. Use
+    /// [`set_compute_unit_price_instruction`](crate::set_compute_unit_price_instruction)
+    /// to attach it to a transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `writable_accounts` - Accounts the transaction will write.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConnectionError`] if the RPC call fails.
+    async fn get_recommended_compute_unit_price(
+        &self,
+        writable_accounts: &[Pubkey],
+    ) -> Result<u64, ConnectionError> {
+        let fees = self
+            .get_recent_prioritization_fees(writable_accounts)
+            .await?;
+        Ok(recommend_compute_unit_price(
+            &fees,
+            DEFAULT_PRIORITY_FEE_PERCENTILE,
+        ))
+    }
 }
 
 #[cfg(feature = "rpc")]
 mod rpc_impl {
     use super::*;
+    use std::future::Future;
+
+    use crate::accounts::MAX_MULTIPLE_ACCOUNTS;
+    use crate::blockhash::CachedBlockhash;
+    use crate::confirmation::MAX_SIGNATURE_STATUSES;
+    use crate::rate_limit::{MethodClass, RateLimitConfig, RateLimiter};
+    use crate::rpc_auth::RpcAuth;
+    use crate::simulation::balance_changes;
+    use solana_client::client_error::{ClientError, ClientErrorKind};
     use solana_client::nonblocking::rpc_client::RpcClient;
-    use solana_client::rpc_config::RpcSendTransactionConfig;
+    use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+    use solana_client::rpc_config::{
+        RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcSendTransactionConfig,
+        RpcSimulateTransactionConfig,
+    };
+    use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+    use solana_client::rpc_response::TransactionConfirmationStatus;
     use solana_commitment_config::CommitmentConfig;
+    use solana_sdk::transaction::TransactionError;
+
+    /// Whether the server rejected the request with HTTP 429.
+    fn is_rate_limited(e: &ClientError) -> bool {
+        match e.kind() {
+            ClientErrorKind::Reqwest(e) => e.status().is_some_and(|s| s.as_u16() == 429),
+            _ => e.to_string().contains("Too Many Requests"),
+        }
+    }
+
+    /// Map an RPC error, reporting HTTP 429 as [`ConnectionError::RateLimited`].
+    fn rpc_error(e: ClientError) -> ConnectionError {
+        if is_rate_limited(&e) {
+            ConnectionError::RateLimited
+        } else {
+            ConnectionError::Rpc(e.to_string())
+        }
+    }
+
+    /// Map a send error, reporting an expired blockhash as
+    /// [`ConnectionError::BlockhashExpired`] so callers can re-sign, and a
+    /// transaction that landed in the meantime as
+    /// [`ConnectionError::AlreadyProcessed`].
+    fn send_error(signature: Signature, e: ClientError) -> ConnectionError {
+        match e.get_transaction_error() {
+            Some(TransactionError::AlreadyProcessed) => {
+                ConnectionError::AlreadyProcessed(signature)
+            }
+            Some(TransactionError::BlockhashNotFound) => ConnectionError::BlockhashExpired,
+            _ if e.to_string().contains("unable to confirm transaction") => {
+                ConnectionError::BlockhashExpired
+            }
+            _ => rpc_error(e),
+        }
+    }
+
+    /// The signature identifying a transaction, or the default if unsigned.
+    fn first_signature(signatures: &[Signature]) -> Signature {
+        signatures.first().copied().unwrap_or_default()
+    }
+
+    impl From<Commitment> for CommitmentConfig {
+        fn from(commitment: Commitment) -> Self {
+            match commitment {
+                Commitment::Processed => CommitmentConfig::processed(),
+                Commitment::Confirmed => CommitmentConfig::confirmed(),
+                Commitment::Finalized => CommitmentConfig::finalized(),
+            }
+        }
+    }
 
     /// RPC-based connection implementation.
     ///
@@ -117,13 +632,16 @@ mod rpc_impl {
     /// # Example
     ///
     /// ```ignore
-    /// use solana_actor::RpcConnection;
+    /// use solana_actor::{RateLimitConfig, RpcConnection};
     ///
-    /// let conn = RpcConnection::new("https://api.mainnet-beta.solana.com");
+    /// let conn = RpcConnection::new("https://api.mainnet-beta.solana.com")
+    ///     .with_rate_limit(RateLimitConfig::default());
     /// let balance = conn.get_balance(&my_pubkey).await?;
     /// ```
     pub struct RpcConnection {
         client: RpcClient,
+        limiter: RateLimiter,
+        blockhash: Option<CachedBlockhash>,
     }
 
     impl RpcConnection {
@@ -131,6 +649,8 @@ mod rpc_impl {
         pub fn new(url: &str) -> Self {
             Self {
                 client: RpcClient::new(url.to_string()),
+                limiter: RateLimiter::new(RateLimitConfig::none()),
+                blockhash: None,
             }
         }
 
@@ -138,13 +658,93 @@ mod rpc_impl {
         pub fn new_with_commitment(url: &str, commitment: CommitmentConfig) -> Self {
             Self {
                 client: RpcClient::new_with_commitment(url.to_string(), commitment),
+                limiter: RateLimiter::new(RateLimitConfig::none()),
+                blockhash: None,
             }
         }
 
+        /// Create a connection to an endpoint that requires an API key.
+        ///
+        /// # Errors
+        ///
+        /// Returns [`ConnectionError::InvalidEndpoint`] if the URL or a
+        /// header in `auth` is invalid.
+        pub fn new_with_auth(url: &str, auth: &RpcAuth) -> Result<Self, ConnectionError> {
+            Self::new_with_auth_and_commitment(url, auth, CommitmentConfig::default())
+        }
+
+        /// Create a connection to an endpoint that requires an API key, with
+        /// a specific commitment level.
+        ///
+        /// # Errors
+        ///
+        /// Returns [`ConnectionError::InvalidEndpoint`] if the URL or a
+        /// header in `auth` is invalid.
+        pub fn new_with_auth_and_commitment(
+            url: &str,
+            auth: &RpcAuth,
+            commitment: CommitmentConfig,
+        ) -> Result<Self, ConnectionError> {
+            Ok(Self {
+                client: auth.rpc_client(url, commitment)?,
+                limiter: RateLimiter::new(RateLimitConfig::none()),
+                blockhash: None,
+            })
+        }
+
+        /// Throttle requests and back off from HTTP 429 responses.
+        ///
+        /// Connections are unlimited by default; use
+        /// [`RateLimitConfig::default`] for public endpoints.
+        pub fn with_rate_limit(mut self, config: RateLimitConfig) -> Self {
+            self.limiter = RateLimiter::new(config);
+            self
+        }
+
+        /// Reuse the latest blockhash until `cache` considers it stale.
+        ///
+        /// Every call fetches a new blockhash by default. The cache is
+        /// dropped when a send reports the blockhash expired.
+        pub fn with_blockhash_cache(mut self, cache: CachedBlockhash) -> Self {
+            self.blockhash = Some(cache);
+            self
+        }
+
         /// Get a reference to the underlying RPC client.
+        ///
+        /// Requests made directly on the client bypass the rate limit.
         pub fn client(&self) -> &RpcClient {
             &self.client
         }
+
+        /// Make a request once the rate limit allows, retrying with jittered
+        /// backoff while the server responds with HTTP 429.
+        async fn request<T, F, Fut>(&self, class: MethodClass, f: F) -> Result<T, ClientError>
+        where
+            F: Fn() -> Fut + Send + Sync,
+            Fut: Future<Output = Result<T, ClientError>> + Send,
+        {
+            let mut attempt = 1;
+            loop {
+                self.limiter.acquire(class).await;
+                match f().await {
+                    Err(e) if is_rate_limited(&e) && attempt < self.limiter.max_attempts() => {
+                        tokio::time::sleep(self.limiter.backoff(attempt)).await;
+                        attempt += 1;
+                    }
+                    result => return result,
+                }
+            }
+        }
+
+        /// Map a send error, dropping the cached blockhash if it expired.
+        fn send_failed(&self, signature: Signature, e: ClientError) -> ConnectionError {
+            let err = send_error(signature, e);
+            if let (ConnectionError::BlockhashExpired, Some(cache)) = (&err, &self.blockhash) {
+                cache.invalidate();
+            }
+            err
+        }
     }
 
     #[async_trait]
@@ -154,15 +754,19 @@ mod rpc_impl {
             transaction: &Transaction,
             config: SendConfig,
         ) -> Result<Signature, ConnectionError> {
+            self.check_not_processed(&transaction.clone().into())
+                .await?;
             let rpc_config = RpcSendTransactionConfig {
                 skip_preflight: config.skip_preflight,
                 max_retries: config.max_retries,
                 ..Default::default()
             };
-            self.client
-                .send_transaction_with_config(transaction, rpc_config)
-                .await
-                .map_err(|e| ConnectionError::Rpc(e.to_string()))
+            self.request(MethodClass::Send, || {
+                self.client
+                    .send_transaction_with_config(transaction, rpc_config)
+            })
+            .await
+            .map_err(|e| self.send_failed(first_signature(&transaction.signatures), e))
         }
 
         async fn send_and_confirm(
@@ -170,28 +774,168 @@ mod rpc_impl {
             transaction: &Transaction,
             _config: SendConfig,
         ) -> Result<Signature, ConnectionError> {
-            self.client
-                .send_and_confirm_transaction(transaction)
+            self.check_not_processed(&transaction.clone().into())
+                .await?;
+            self.request(MethodClass::Send, || {
+                self.client.send_and_confirm_transaction(transaction)
+            })
+            .await
+            .map_err(|e| self.send_failed(first_signature(&transaction.signatures), e))
+        }
+
+        async fn send_versioned_transaction(
+            &self,
+            transaction: &VersionedTransaction,
+            config: SendConfig,
+        ) -> Result<Signature, ConnectionError> {
+            self.check_not_processed(transaction).await?;
+            let rpc_config = RpcSendTransactionConfig {
+                skip_preflight: config.skip_preflight,
+                max_retries: config.max_retries,
+                ..Default::default()
+            };
+            self.request(MethodClass::Send, || {
+                self.client
+                    .send_transaction_with_config(transaction, rpc_config)
+            })
+            .await
+            .map_err(|e| self.send_failed(first_signature(&transaction.signatures), e))
+        }
+
+        async fn send_and_confirm_versioned_transaction(
+            &self,
+            transaction: &VersionedTransaction,
+            _config: SendConfig,
+        ) -> Result<Signature, ConnectionError> {
+            self.check_not_processed(transaction).await?;
+            self.request(MethodClass::Send, || {
+                self.client.send_and_confirm_transaction(transaction)
+            })
+            .await
+            .map_err(|e| self.send_failed(first_signature(&transaction.signatures), e))
+        }
+
+        async fn get_signature_statuses(
+            &self,
+            signatures: &[Signature],
+        ) -> Result<Vec<Option<SignatureStatus>>, ConnectionError> {
+            let mut statuses = Vec::with_capacity(signatures.len());
+            for chunk in signatures.chunks(MAX_SIGNATURE_STATUSES) {
+                let response = self
+                    .request(MethodClass::Read, || {
+                        self.client.get_signature_statuses(chunk)
+                    })
+                    .await
+                    .map_err(rpc_error)?;
+                statuses.extend(response.value.into_iter().map(|status| {
+                    status.map(|status| SignatureStatus {
+                        slot: status.slot,
+                        confirmations: status.confirmations,
+                        error: status.err.as_ref().map(|e| e.to_string()),
+                        commitment: match status.confirmation_status() {
+                            TransactionConfirmationStatus::Processed => Commitment::Processed,
+                            TransactionConfirmationStatus::Confirmed => Commitment::Confirmed,
+                            TransactionConfirmationStatus::Finalized => Commitment::Finalized,
+                        },
+                    })
+                }));
+            }
+            Ok(statuses)
+        }
+
+        async fn get_signatures_for_address(
+            &self,
+            address: &Pubkey,
+            before: Option<&Signature>,
+        ) -> Result<Vec<SignatureInfo>, ConnectionError> {
+            self.request(MethodClass::Read, || {
+                self.client.get_signatures_for_address_with_config(
+                    address,
+                    GetConfirmedSignaturesForAddress2Config {
+                        before: before.copied(),
+                        ..Default::default()
+                    },
+                )
+            })
+            .await
+            .map_err(rpc_error)?
+            .into_iter()
+            .map(|entry| {
+                Ok(SignatureInfo {
+                    signature: entry.signature.parse().map_err(|_| {
+                        ConnectionError::Rpc(format!("Invalid signature: {}", entry.signature))
+                    })?,
+                    slot: entry.slot,
+                    error: entry.err.map(|e| e.to_string()),
+                    block_time: entry.block_time,
+                })
+            })
+            .collect()
+        }
+
+        async fn simulate_versioned_transaction(
+            &self,
+            transaction: &VersionedTransaction,
+        ) -> Result<SimulationResult, ConnectionError> {
+            let config = RpcSimulateTransactionConfig {
+                sig_verify: false,
+                replace_recent_blockhash: true,
+                ..Default::default()
+            };
+            let result = self
+                .request(MethodClass::Read, || {
+                    self.client
+                        .simulate_transaction_with_config(transaction, config.clone())
+                })
                 .await
-                .map_err(|e| ConnectionError::Rpc(e.to_string()))
+                .map_err(rpc_error)?
+                .value;
+
+            // Balances cover static keys, then loaded writable, then loaded readonly
+            let mut accounts = transaction.message.static_account_keys().to_vec();
+            if let Some(loaded) = &result.loaded_addresses {
+                for address in loaded.writable.iter().chain(&loaded.readonly) {
+                    accounts.push(address.parse().map_err(|_| {
+                        ConnectionError::Rpc(format!("Invalid loaded address: {}", address))
+                    })?);
+                }
+            }
+            let balance_changes = match (&result.pre_balances, &result.post_balances) {
+                (Some(pre), Some(post)) => balance_changes(&accounts, pre, post),
+                _ => Vec::new(),
+            };
+
+            Ok(SimulationResult {
+                error: result.err.map(|e| e.to_string()),
+                logs: result.logs.unwrap_or_default(),
+                units_consumed: result.units_consumed,
+                balance_changes,
+            })
         }
 
         async fn get_latest_blockhash(&self) -> Result<Hash, ConnectionError> {
-            self.client
-                .get_latest_blockhash()
-                .await
-                .map_err(|e| ConnectionError::Rpc(e.to_string()))
+            let fetch = || async {
+                self.request(MethodClass::Read, || self.client.get_latest_blockhash())
+                    .await
+                    .map_err(rpc_error)
+            };
+            match &self.blockhash {
+                Some(cache) => cache.get_or_refresh(fetch).await,
+                None => fetch().await,
+            }
         }
 
         async fn get_balance(&self, pubkey: &Pubkey) -> Result<u64, ConnectionError> {
-            self.client
-                .get_balance(pubkey)
+            self.request(MethodClass::Read, || self.client.get_balance(pubkey))
                 .await
-                .map_err(|e| ConnectionError::Rpc(e.to_string()))
+                .map_err(rpc_error)
         }
 
         async fn get_account(&self, pubkey: &Pubkey) -> Result<Option<Account>, ConnectionError> {
-            match self.client.get_account(pubkey).await {
+            match self
+                .request(MethodClass::Read, || self.client.get_account(pubkey))
+                .await
+            {
                 Ok(account) => Ok(Some(account)),
                 Err(e) => {
                     let err_str = e.to_string();
@@ -200,13 +944,137 @@ mod rpc_impl {
                     {
                         Ok(None)
                     } else {
-                        Err(ConnectionError::Rpc(err_str))
+                        Err(rpc_error(e))
                     }
                 }
             }
         }
+
+        async fn get_multiple_accounts(
+            &self,
+            pubkeys: &[Pubkey],
+        ) -> Result<Vec<Option<Account>>, ConnectionError> {
+            let mut accounts = Vec::with_capacity(pubkeys.len());
+            for chunk in pubkeys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+                accounts.extend(
+                    self.request(MethodClass::Scan, || {
+                        self.client.get_multiple_accounts(chunk)
+                    })
+                    .await
+                    .map_err(rpc_error)?,
+                );
+            }
+            Ok(accounts)
+        }
+
+        async fn get_program_accounts(
+            &self,
+            program_id: &Pubkey,
+            filters: &[AccountFilter],
+        ) -> Result<Vec<(Pubkey, Account)>, ConnectionError> {
+            let filters = filters
+                .iter()
+                .map(|filter| match filter {
+                    AccountFilter::DataSize(size) => RpcFilterType::DataSize(*size),
+                    AccountFilter::Memcmp { offset, bytes } => {
+                        RpcFilterType::Memcmp(Memcmp::new_raw_bytes(*offset, bytes.clone()))
+                    }
+                })
+                .collect::<Vec<_>>();
+            let config = RpcProgramAccountsConfig {
+                filters: (!filters.is_empty()).then_some(filters),
+                account_config: RpcAccountInfoConfig {
+                    encoding: Some(solana_client::rpc_config::UiAccountEncoding::Base64),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            self.request(MethodClass::Scan, || {
+                self.client
+                    .get_program_ui_accounts_with_config(program_id, config.clone())
+            })
+            .await
+            .map_err(rpc_error)?
+            .into_iter()
+            .map(|(pubkey, account)| {
+                account
+                    .decode::<Account>()
+                    .map(|a| (pubkey, a))
+                    .ok_or_else(|| {
+                        ConnectionError::InvalidAccountData(format!(
+                            "Undecodable account {}",
+                            pubkey
+                        ))
+                    })
+            })
+            .collect()
+        }
+
+        async fn get_token_account_balance(
+            &self,
+            token_account: &Pubkey,
+        ) -> Result<TokenAmount, ConnectionError> {
+            let balance = self
+                .request(MethodClass::Read, || {
+                    self.client.get_token_account_balance(token_account)
+                })
+                .await
+                .map_err(rpc_error)?;
+            Ok(TokenAmount {
+                amount: balance.amount.parse().map_err(|_| {
+                    ConnectionError::Rpc(format!("Invalid token amount: {}", balance.amount))
+                })?,
+                decimals: balance.decimals,
+            })
+        }
+
+        async fn get_recent_prioritization_fees(
+            &self,
+            writable_accounts: &[Pubkey],
+        ) -> Result<Vec<PrioritizationFee>, ConnectionError> {
+            let fees = self
+                .request(MethodClass::Read, || {
+                    self.client
+                        .get_recent_prioritization_fees(writable_accounts)
+                })
+                .await
+                .map_err(rpc_error)?;
+            Ok(fees
+                .into_iter()
+                .map(|fee| PrioritizationFee {
+                    slot: fee.slot,
+                    prioritization_fee: fee.prioritization_fee,
+                })
+                .collect())
+        }
     }
 }
 
 #[cfg(feature = "rpc")]
 pub use rpc_impl::RpcConnection;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jitter() {
+        let delay = Duration::from_secs(4);
+        assert_eq!(jitter(delay, 0.0), Duration::from_secs(2));
+        assert!(jitter(delay, 0.999) < delay);
+        for _ in 0..100 {
+            let fraction = random_fraction();
+            assert!((0.0..1.0).contains(&fraction));
+        }
+    }
+
+    #[test]
+    fn test_retry_backoff() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.backoff(1), Duration::from_millis(500));
+        assert_eq!(policy.backoff(2), Duration::from_secs(1));
+        assert_eq!(policy.backoff(4), Duration::from_secs(4));
+        assert_eq!(policy.backoff(40), Duration::from_secs(4));
+        assert_eq!(RetryPolicy::none().max_attempts, 1);
+    }
+}
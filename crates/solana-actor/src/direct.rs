@@ -1,23 +1,39 @@
 //! Direct transport implementation for regular signers.
 //!
-//! This module provides [`DirectTransport`], which wraps any [`TransactionSigner`]
-//! to provide the [`WalletTransport`] interface. This is the simplest transport
-//! that performs synchronous signing in a blocking task.
+//! This module provides [`DirectTransport`], which wraps any
+//! [`AsyncTransactionSigner`] (including every cloneable
+//! [`TransactionSigner`](crate::TransactionSigner))
+//! to provide the [`WalletTransport`] interface. This is the simplest transport,
+//! returning the signer's signature immediately. It can optionally sign
+//! against a durable nonce account so transactions stay valid after recent
+//! blockhashes expire, or send what it signs through a [`Connection`].
 
+use std::fmt;
+use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
-use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use solana_sdk::{
+    instruction::Instruction,
+    message::{Message, VersionedMessage},
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::{Transaction, VersionedTransaction},
+};
 
-use crate::error::{SignerError, TransportError};
-use crate::signer::TransactionSigner;
+use crate::connection::{Connection, SendConfig};
+use crate::error::{ConnectionError, SignerError, TransportError};
+use crate::nonce::advance_nonce_instruction;
+use crate::preflight::{check_signers, check_transaction_size};
+use crate::signer::AsyncTransactionSigner;
 use crate::transport::{SubmitResult, WalletTransport};
 
-/// Direct transport that wraps any [`TransactionSigner`].
+/// Direct transport that wraps any [`AsyncTransactionSigner`].
 ///
-/// This transport performs synchronous signing using `spawn_blocking` to avoid
-/// blocking the async runtime. The result is always [`SubmitResult::Signed`]
-/// since direct signing produces immediate signatures.
+/// Synchronous [`TransactionSigner`](crate::TransactionSigner)s sign in `spawn_blocking` to avoid
+/// blocking the async runtime; async signers (agent, remote HSM) are awaited
+/// directly. The result is always [`SubmitResult::Signed`] since direct
+/// signing produces immediate signatures.
 ///
 /// # Example
 ///
@@ -31,15 +47,88 @@ use crate::transport::{SubmitResult, WalletTransport};
 /// let result = transport.submit(&message_bytes).await?;
 /// assert!(result.is_complete());
 /// ```
-#[derive(Debug, Clone)]
+///
+/// With a durable nonce account whose authority is the signer:
+///
+/// ```ignore
+/// let transport = DirectTransport::new(signer).with_nonce_account(nonce_account);
+/// let tx = transport.sign_instructions(&connection, &[transfer_ix]).await?;
+/// ```
+///
+/// To sign, send and confirm, re-signing with a fresh blockhash if it expires:
+///
+/// ```ignore
+/// let signature = transport
+///     .send_instructions(&connection, &[transfer_ix], SendConfig::default())
+///     .await?;
+/// ```
+///
+/// To have `submit` broadcast the signed transaction as well:
+///
+/// ```ignore
+/// let connection = Arc::new(RpcConnection::new("https://api.mainnet-beta.solana.com"));
+/// let transport = DirectTransport::with_connection(signer, connection);
+///
+/// let SubmitResult::Executed { signature, .. } = transport.submit(&message_bytes).await? else {
+///     unreachable!();
+/// };
+/// ```
+#[derive(Clone)]
 pub struct DirectTransport<S> {
     signer: S,
+    nonce_account: Option<Pubkey>,
+    connection: Option<Arc<dyn Connection>>,
+    send_config: SendConfig,
 }
 
 impl<S> DirectTransport<S> {
     /// Create a new direct transport wrapping the given signer.
     pub fn new(signer: S) -> Self {
-        Self { signer }
+        Self {
+            signer,
+            nonce_account: None,
+            connection: None,
+            send_config: SendConfig::default(),
+        }
+    }
+
+    /// Create a direct transport that sends what it signs.
+    ///
+    /// [`submit`](WalletTransport::submit) and
+    /// [`submit_versioned`](WalletTransport::submit_versioned) attach the
+    /// signature, send the transaction through `connection`, wait for
+    /// confirmation and return [`SubmitResult::Executed`]. The signer must be
+    /// the message's only required signer; this and the transaction's size
+    /// are checked before anything is sent.
+    pub fn with_connection(signer: S, connection: Arc<dyn Connection>) -> Self {
+        Self {
+            connection: Some(connection),
+            ..Self::new(signer)
+        }
+    }
+
+    /// Set how transactions are sent when a connection is attached.
+    pub fn with_send_config(mut self, config: SendConfig) -> Self {
+        self.send_config = config;
+        self
+    }
+
+    /// The connection signed transactions are sent through, if any.
+    pub fn connection(&self) -> Option<&dyn Connection> {
+        self.connection.as_deref()
+    }
+
+    /// Sign against a durable nonce account instead of a recent blockhash.
+    ///
+    /// The signer must be the nonce account's authority.
+    pub fn with_nonce_account(mut self, nonce_account: Pubkey) -> Self {
+        self.nonce_account = Some(nonce_account);
+        self
+    }
+
+    /// The durable nonce account, if any.
+    pub fn nonce_account(&self) -> Option<Pubkey> {
+        self.nonce_account
     }
 
     /// Get a reference to the underlying signer.
@@ -58,25 +147,208 @@ impl<S> DirectTransport<S> {
     }
 }
 
+impl<S> DirectTransport<S>
+where
+    S: AsyncTransactionSigner,
+{
+    /// Sign a versioned message, producing a transaction ready to send.
+    ///
+    /// The signature is placed at the signer's position among the message's
+    /// required signers. Other signature slots are left as default signatures
+    /// so that additional signers can fill them in.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TransportError::Signer`] if the signer is not a required signer
+    /// of the message or signing fails.
+    pub async fn sign_versioned(
+        &self,
+        message: VersionedMessage,
+    ) -> Result<VersionedTransaction, TransportError> {
+        let pubkey = self.signer.pubkey();
+        let num_signers = message.header().num_required_signatures as usize;
+        let position = message
+            .static_account_keys()
+            .iter()
+            .take(num_signers)
+            .position(|key| *key == pubkey)
+            .ok_or_else(|| {
+                SignerError::SigningFailed(format!("{} is not a required signer", pubkey))
+            })?;
+
+        let signature = self.signer.sign_transaction(&message.serialize()).await?;
+
+        let mut signatures = vec![Signature::default(); num_signers];
+        signatures[position] = signature;
+        Ok(VersionedTransaction {
+            signatures,
+            message,
+        })
+    }
+
+    /// Build and sign a transaction paid for by the signer.
+    ///
+    /// With a nonce account, the stored nonce is fetched, an
+    /// `AdvanceNonceAccount` instruction is placed first, and the nonce is
+    /// used as the blockhash. Otherwise the latest blockhash is fetched.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TransportError::Build`] if the instructions require other
+    /// signers or the transaction is too large, both checked before any
+    /// network call, [`TransportError::InvalidNonce`] if the nonce account
+    /// does not exist or the signer is not its authority, or
+    /// [`TransportError::Signer`] if signing fails.
+    pub async fn sign_instructions<C: Connection + ?Sized>(
+        &self,
+        connection: &C,
+        instructions: &[Instruction],
+    ) -> Result<Transaction, TransportError> {
+        let pubkey = self.signer.pubkey();
+
+        // The signer advances the nonce, so the message can be checked before
+        // the nonce is fetched
+        let instructions = self
+            .nonce_account
+            .iter()
+            .map(|nonce_account| advance_nonce_instruction(nonce_account, &pubkey))
+            .chain(instructions.iter().cloned())
+            .collect::<Vec<_>>();
+        let mut message = Message::new(&instructions, Some(&pubkey));
+        let preview = VersionedMessage::Legacy(message.clone());
+        check_signers(&preview, &[pubkey])?;
+        check_transaction_size(&preview)?;
+
+        message.recent_blockhash = match self.nonce_account {
+            Some(nonce_account) => {
+                let nonce = connection
+                    .get_durable_nonce(&nonce_account)
+                    .await?
+                    .ok_or_else(|| {
+                        TransportError::InvalidNonce(nonce_account, "account not found".to_string())
+                    })?;
+                if nonce.authority != pubkey {
+                    return Err(TransportError::InvalidNonce(
+                        nonce_account,
+                        format!("authority is {}, not {}", nonce.authority, pubkey),
+                    ));
+                }
+                nonce.blockhash
+            }
+            None => connection.get_latest_blockhash().await?,
+        };
+
+        let signature = self.signer.sign_transaction(&message.serialize()).await?;
+
+        Ok(Transaction {
+            signatures: vec![signature],
+            message,
+        })
+    }
+
+    /// Sign, send and confirm a transaction paid for by the signer.
+    ///
+    /// If the blockhash expires before the transaction lands, a new blockhash
+    /// (or nonce) is fetched, the transaction is re-signed and sent again,
+    /// waiting between attempts as set by `config.retry`. Other errors are
+    /// returned immediately.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`sign_instructions`](Self::sign_instructions),
+    /// or [`TransportError::Connection`] if sending fails or the blockhash
+    /// still expires after the last attempt.
+    pub async fn send_instructions<C: Connection + ?Sized>(
+        &self,
+        connection: &C,
+        instructions: &[Instruction],
+        config: SendConfig,
+    ) -> Result<Signature, TransportError> {
+        let max_attempts = config.retry.max_attempts.max(1);
+        let mut attempt = 1;
+        loop {
+            let transaction = self.sign_instructions(connection, instructions).await?;
+            match connection
+                .send_and_confirm(&transaction, config.clone())
+                .await
+            {
+                Err(ConnectionError::BlockhashExpired) if attempt < max_attempts => {
+                    tokio::time::sleep(config.retry.backoff(attempt)).await;
+                    attempt += 1;
+                }
+                result => return Ok(result?),
+            }
+        }
+    }
+
+    /// Sign `message`, send it through `connection` and wait for confirmation.
+    async fn send_versioned(
+        &self,
+        connection: &dyn Connection,
+        message: &VersionedMessage,
+    ) -> Result<SubmitResult, TransportError> {
+        check_signers(message, &[self.signer.pubkey()])?;
+        check_transaction_size(message)?;
+
+        let transaction = self.sign_versioned(message.clone()).await?;
+        let signature = connection
+            .send_and_confirm_versioned_transaction(&transaction, self.send_config.clone())
+            .await?;
+
+        // No proposal is involved in a direct send
+        Ok(SubmitResult::Executed {
+            signature,
+            proposal: Pubkey::default(),
+        })
+    }
+}
+
+impl<S> fmt::Debug for DirectTransport<S>
+where
+    S: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DirectTransport")
+            .field("signer", &self.signer)
+            .field("nonce_account", &self.nonce_account)
+            .field("connection", &self.connection.is_some())
+            .field("send_config", &self.send_config)
+            .finish()
+    }
+}
+
 #[async_trait]
 impl<S> WalletTransport for DirectTransport<S>
 where
-    S: TransactionSigner + Clone + 'static,
+    S: AsyncTransactionSigner,
 {
     fn authority(&self) -> Pubkey {
         self.signer.pubkey()
     }
 
     async fn submit(&self, message: &[u8]) -> Result<SubmitResult, TransportError> {
-        let message = message.to_vec();
-        let signer = self.signer.clone();
+        if self.connection.is_some() {
+            let message: VersionedMessage = bincode::deserialize(message).map_err(|e| {
+                SignerError::InvalidFormat(format!("Failed to parse message: {}", e))
+            })?;
+            return self.submit_versioned(&message).await;
+        }
 
-        let result: Result<Signature, SignerError> =
-            tokio::task::spawn_blocking(move || signer.sign_transaction(&message))
-                .await
-                .map_err(|_| TransportError::TaskPanic)?;
+        Ok(SubmitResult::Signed(
+            self.signer.sign_transaction(message).await?,
+        ))
+    }
 
-        Ok(SubmitResult::Signed(result?))
+    async fn submit_versioned(
+        &self,
+        message: &VersionedMessage,
+    ) -> Result<SubmitResult, TransportError> {
+        match &self.connection {
+            Some(connection) => self.send_versioned(connection.as_ref(), message).await,
+            None => Ok(SubmitResult::Signed(
+                self.signer.sign_transaction(&message.serialize()).await?,
+            )),
+        }
     }
 
     async fn check_status(&self, result: &SubmitResult) -> Result<SubmitResult, TransportError> {
@@ -94,13 +366,15 @@ where
     }
 
     fn requires_network(&self) -> bool {
-        false
+        self.connection.is_some()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::BuildError;
+    use crate::signer::TransactionSigner;
     use solana_sdk::pubkey::Pubkey;
 
     /// Mock signer for testing
@@ -132,6 +406,35 @@ mod tests {
         assert!(!result.is_pending());
     }
 
+    /// Signer with a natively async backend
+    struct MockAsyncSigner {
+        pubkey: Pubkey,
+    }
+
+    #[async_trait]
+    impl AsyncTransactionSigner for MockAsyncSigner {
+        fn pubkey(&self) -> Pubkey {
+            self.pubkey
+        }
+
+        async fn sign_transaction(&self, message: &[u8]) -> Result<Signature, SignerError> {
+            tokio::task::yield_now().await;
+            let mut bytes = [0u8; 64];
+            bytes[0] = message.len() as u8;
+            Ok(Signature::from(bytes))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_direct_transport_async_signer() {
+        let pubkey = Pubkey::new_unique();
+        let transport = DirectTransport::new(MockAsyncSigner { pubkey });
+
+        assert_eq!(transport.authority(), pubkey);
+        let result = transport.submit(b"test message").await.unwrap();
+        assert_eq!(result.signature().unwrap().as_ref()[0], 12);
+    }
+
     #[tokio::test]
     async fn test_direct_transport_authority() {
         let pubkey = Pubkey::new_unique();
@@ -153,6 +456,193 @@ mod tests {
         assert!(checked.is_complete());
     }
 
+    #[tokio::test]
+    async fn test_direct_transport_sign_versioned() {
+        use solana_sdk::{hash::Hash, message::v0};
+
+        let pubkey = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let transport = DirectTransport::new(MockSigner { pubkey });
+
+        let ix = solana_sdk::instruction::Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[],
+            vec![solana_sdk::instruction::AccountMeta::new_readonly(
+                pubkey, true,
+            )],
+        );
+        let message = VersionedMessage::V0(
+            v0::Message::try_compile(&other, &[ix], &[], Hash::default()).unwrap(),
+        );
+
+        let tx = transport.sign_versioned(message.clone()).await.unwrap();
+        assert_eq!(tx.signatures.len(), 2);
+        assert_eq!(tx.message, message);
+
+        let stranger = DirectTransport::new(MockSigner {
+            pubkey: Pubkey::new_unique(),
+        });
+        assert!(stranger.sign_versioned(message).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sign_instructions_with_mock_connection() {
+        let pubkey = Pubkey::new_unique();
+        let transport = DirectTransport::new(MockSigner { pubkey });
+        let blockhash = solana_sdk::hash::Hash::new_unique();
+        let connection = crate::MockConnection::new().with_blockhash(blockhash);
+
+        let ix = solana_sdk::instruction::Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[],
+            vec![solana_sdk::instruction::AccountMeta::new(pubkey, true)],
+        );
+        let tx = transport
+            .sign_instructions(&connection, std::slice::from_ref(&ix))
+            .await
+            .unwrap();
+        assert_eq!(tx.message.recent_blockhash, blockhash);
+        assert_eq!(tx.message.account_keys[0], pubkey);
+        assert_eq!(
+            connection.calls(),
+            vec![crate::MockCall::GetLatestBlockhash]
+        );
+
+        // A missing nonce account is reported rather than falling back
+        let nonce_account = Pubkey::new_unique();
+        let result = transport
+            .with_nonce_account(nonce_account)
+            .sign_instructions(&connection, &[ix])
+            .await;
+        assert!(matches!(
+            result,
+            Err(TransportError::InvalidNonce(key, _)) if key == nonce_account
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_sign_instructions_rejects_oversized_before_network() {
+        let pubkey = Pubkey::new_unique();
+        let transport = DirectTransport::new(MockSigner { pubkey });
+        let connection = crate::MockConnection::new();
+
+        let ix = solana_sdk::instruction::Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[0; 1200],
+            vec![solana_sdk::instruction::AccountMeta::new(pubkey, true)],
+        );
+        let result = transport.sign_instructions(&connection, &[ix]).await;
+        assert!(matches!(
+            result,
+            Err(TransportError::Build(
+                BuildError::TransactionTooLarge { .. }
+            ))
+        ));
+        assert!(connection.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_send_instructions_refreshes_expired_blockhash() {
+        use crate::connection::RetryPolicy;
+        use solana_sdk::hash::Hash;
+
+        let pubkey = Pubkey::new_unique();
+        let transport = DirectTransport::new(MockSigner { pubkey });
+        let ix = solana_sdk::instruction::Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[],
+            vec![solana_sdk::instruction::AccountMeta::new(pubkey, true)],
+        );
+        let config = SendConfig {
+            retry: RetryPolicy {
+                max_attempts: 3,
+                initial_backoff: Duration::ZERO,
+                max_backoff: Duration::ZERO,
+            },
+            ..Default::default()
+        };
+
+        let (first, second) = (Hash::new_unique(), Hash::new_unique());
+        let connection = crate::MockConnection::new()
+            .with_blockhashes([first, second])
+            .expect_send(Err(ConnectionError::BlockhashExpired))
+            .expect_send(Ok(()));
+        transport
+            .send_instructions(&connection, std::slice::from_ref(&ix), config.clone())
+            .await
+            .unwrap();
+        let sent = connection.sent_transactions();
+        assert_eq!(sent.len(), 2);
+        assert_eq!(*sent[0].message.recent_blockhash(), first);
+        assert_eq!(*sent[1].message.recent_blockhash(), second);
+
+        // Gives up after the last attempt
+        let connection = crate::MockConnection::new()
+            .expect_send(Err(ConnectionError::BlockhashExpired))
+            .expect_send(Err(ConnectionError::BlockhashExpired))
+            .expect_send(Err(ConnectionError::BlockhashExpired));
+        assert!(
+            transport
+                .send_instructions(&connection, std::slice::from_ref(&ix), config.clone())
+                .await
+                .is_err()
+        );
+        assert_eq!(connection.sent_transactions().len(), 3);
+
+        // Other errors are not retried
+        let connection =
+            crate::MockConnection::new().expect_send(Err(ConnectionError::InsufficientFunds));
+        assert!(
+            transport
+                .send_instructions(&connection, &[ix], config)
+                .await
+                .is_err()
+        );
+        assert_eq!(connection.sent_transactions().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_with_connection_sends_and_confirms() {
+        use solana_sdk::hash::Hash;
+        use solana_sdk::instruction::AccountMeta;
+
+        let pubkey = Pubkey::new_unique();
+        let connection = Arc::new(crate::MockConnection::new());
+        let transport = DirectTransport::with_connection(MockSigner { pubkey }, connection.clone());
+        assert!(transport.requires_network());
+
+        let ix = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[],
+            vec![AccountMeta::new(pubkey, true)],
+        );
+        let message = Message::new_with_blockhash(&[ix], Some(&pubkey), &Hash::new_unique());
+        let result = transport.submit(&message.serialize()).await.unwrap();
+        assert!(matches!(result, SubmitResult::Executed { .. }));
+        assert!(result.is_complete());
+
+        let sent = connection.sent_transactions();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].message, VersionedMessage::Legacy(message));
+
+        // Messages that need other signers are refused before sending
+        let other = Pubkey::new_unique();
+        let ix = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[],
+            vec![AccountMeta::new_readonly(other, true)],
+        );
+        let message = Message::new_with_blockhash(&[ix], Some(&pubkey), &Hash::new_unique());
+        let result = transport
+            .submit_versioned(&VersionedMessage::Legacy(message))
+            .await;
+        assert!(matches!(
+            result,
+            Err(TransportError::Build(BuildError::MissingSigners(keys))) if keys == vec![other]
+        ));
+        assert_eq!(connection.sent_transactions().len(), 1);
+    }
+
     #[test]
     fn test_direct_transport_requires_network() {
         let signer = MockSigner {
@@ -1,8 +1,10 @@
 //! Error types for wallet operations.
 
-use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{hash::Hash, pubkey::Pubkey, signature::Signature};
 use thiserror::Error;
 
+use crate::SubmitResult;
+
 /// Errors from synchronous signing operations.
 #[derive(Error, Debug)]
 pub enum SignerError {
@@ -50,6 +52,14 @@ pub enum TransportError {
     #[error("Connection error: {0}")]
     Connection(#[from] ConnectionError),
 
+    /// Transaction could not be built.
+    #[error("Build error: {0}")]
+    Build(#[from] BuildError),
+
+    /// Nonce account missing or not usable by this signer.
+    #[error("Invalid nonce account {0}: {1}")]
+    InvalidNonce(Pubkey, String),
+
     /// Proposal creation failed.
     #[error("Proposal creation failed: {0}")]
     ProposalFailed(String),
@@ -82,6 +92,66 @@ pub enum TransportError {
         /// Required number of approvals.
         required: u32,
     },
+
+    /// An offline request or its signatures could not be used.
+    #[error("Offline signing error: {0}")]
+    Offline(#[from] OfflineError),
+
+    /// Every transport in a fallback chain failed.
+    #[error("All transports failed: {}", describe_failures(.0))]
+    AllTransportsFailed(Vec<FallbackFailure>),
+
+    /// A proposal was created but a later step (approval or execution) failed.
+    ///
+    /// Submitting again would create a duplicate proposal; resume from
+    /// `result` instead.
+    #[error("Proposal created but not completed: {reason}")]
+    PartialSubmit {
+        /// The created proposal, as a pending result.
+        result: Box<SubmitResult>,
+        /// Why the submission did not complete.
+        reason: String,
+    },
+}
+
+/// Why one transport in a fallback chain was skipped.
+#[derive(Debug)]
+pub struct FallbackFailure {
+    /// The name the transport was added under.
+    pub transport: String,
+    /// The error it returned.
+    pub error: TransportError,
+}
+
+fn describe_failures(failures: &[FallbackFailure]) -> String {
+    failures
+        .iter()
+        .map(|f| format!("{}: {}", f.transport, f.error))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+impl TransportError {
+    /// Whether the error is a network hiccup that may succeed if retried.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Self::Connection(e) if e.is_transient())
+    }
+
+    /// The per-transport errors of a failed fallback chain.
+    pub fn fallback_failures(&self) -> &[FallbackFailure] {
+        match self {
+            Self::AllTransportsFailed(failures) => failures,
+            _ => &[],
+        }
+    }
+
+    /// The pending result of a partially completed submission, if any.
+    pub fn partial_result(&self) -> Option<&SubmitResult> {
+        match self {
+            Self::PartialSubmit { result, .. } => Some(result),
+            _ => None,
+        }
+    }
 }
 
 /// Errors from network connection operations.
@@ -95,10 +165,31 @@ pub enum ConnectionError {
     #[error("Transaction failed: {0}")]
     TransactionFailed(String),
 
+    /// Account data could not be parsed.
+    #[error("Invalid account data: {0}")]
+    InvalidAccountData(String),
+
+    /// The endpoint URL or its authentication headers are invalid.
+    #[error("Invalid RPC endpoint: {0}")]
+    InvalidEndpoint(String),
+
     /// Blockhash expired.
     #[error("Blockhash expired")]
     BlockhashExpired,
 
+    /// An identical transaction already landed.
+    ///
+    /// Sending it again can't succeed; treat the signature as submitted.
+    #[error("Transaction already processed: {0}")]
+    AlreadyProcessed(Signature),
+
+    /// The durable nonce the transaction was signed against has advanced.
+    ///
+    /// The transaction, or another one using the same nonce, already landed
+    /// too long ago to show up in recent signature statuses.
+    #[error("Durable nonce {0} has advanced")]
+    NonceAdvanced(Pubkey),
+
     /// Insufficient funds.
     #[error("Insufficient funds")]
     InsufficientFunds,
@@ -115,3 +206,177 @@ pub enum ConnectionError {
     #[error("Timeout")]
     Timeout,
 }
+
+impl ConnectionError {
+    /// Whether the error is a network hiccup that may succeed if retried.
+    ///
+    /// Expired blockhashes are not transient: the message must be re-signed.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            Self::Rpc(_) | Self::NetworkUnreachable | Self::RateLimited | Self::Timeout
+        )
+    }
+}
+
+/// Errors from building and signing a transaction with multiple signers.
+#[derive(Error, Debug)]
+pub enum BuildError {
+    /// Required signers that did not provide a signature.
+    #[error("Missing signatures from: {}", format_pubkeys(.0))]
+    MissingSigners(Vec<Pubkey>),
+
+    /// A signer was provided that the transaction does not require.
+    #[error("Signer is not required by the transaction: {0}")]
+    UnexpectedSigner(Pubkey),
+
+    /// A signature does not verify against the message.
+    #[error("Invalid signature from {0}")]
+    InvalidSignature(Pubkey),
+
+    /// Signatures collected for different messages were combined.
+    #[error("Signatures are for a different message")]
+    MessageMismatch,
+
+    /// The transaction has no instructions.
+    #[error("Transaction has no instructions")]
+    NoInstructions,
+
+    /// The message could not be compiled.
+    #[error("Failed to compile message: {0}")]
+    Compile(String),
+
+    /// The serialized transaction does not fit in a packet.
+    #[error("Transaction is {size} bytes, limit {limit}; {hint}")]
+    TransactionTooLarge {
+        /// Serialized size, signatures included.
+        size: usize,
+        /// Largest size accepted.
+        limit: usize,
+        /// How to make the transaction fit.
+        hint: &'static str,
+    },
+
+    /// Signing error.
+    #[error("Signing error: {0}")]
+    Signer(#[from] SignerError),
+
+    /// Connection error.
+    #[error("Connection error: {0}")]
+    Connection(#[from] ConnectionError),
+}
+
+/// Errors from signing or verifying Sign-In-With-Solana messages.
+#[derive(Error, Debug)]
+pub enum SiwsError {
+    /// The message text does not follow the SIWS format.
+    #[error("Invalid sign-in message: {0}")]
+    InvalidMessage(String),
+
+    /// The signer is not the account named in the message.
+    #[error("Message is for {expected}, not signer {actual}")]
+    AddressMismatch {
+        /// Address in the message.
+        expected: Pubkey,
+        /// Public key of the signer.
+        actual: Pubkey,
+    },
+
+    /// The message was issued for another domain.
+    #[error("Message is for domain {actual}, expected {expected}")]
+    DomainMismatch {
+        /// Domain the server expects.
+        expected: String,
+        /// Domain in the message.
+        actual: String,
+    },
+
+    /// The nonce is missing or not the one the server issued.
+    #[error("Nonce does not match")]
+    NonceMismatch,
+
+    /// The message's expiration time has passed.
+    #[error("Message has expired")]
+    Expired,
+
+    /// The message's not-before time has not been reached.
+    #[error("Message is not valid yet")]
+    NotYetValid,
+
+    /// The signature was not made by the message's address.
+    #[error("Invalid signature")]
+    InvalidSignature,
+
+    /// Signing error.
+    #[error("Signing error: {0}")]
+    Signer(#[from] SignerError),
+}
+
+/// Errors from building, parsing or verifying off-chain messages.
+#[derive(Error, Debug)]
+pub enum OffchainMessageError {
+    /// The message is empty.
+    #[error("Off-chain message is empty")]
+    Empty,
+
+    /// The message is longer than the format allows.
+    #[error("Off-chain message is {len} bytes, the maximum is {max}")]
+    TooLong {
+        /// Length of the message.
+        len: usize,
+        /// Maximum length.
+        max: usize,
+    },
+
+    /// The message is not valid in its format (UTF-8 or printable ASCII).
+    #[error("Off-chain message is not valid UTF-8 for its format")]
+    InvalidEncoding,
+
+    /// The serialized header is malformed.
+    #[error("Invalid off-chain message header: {0}")]
+    InvalidHeader(String),
+
+    /// The header version is not supported.
+    #[error("Unsupported off-chain message version {0}")]
+    UnsupportedVersion(u8),
+
+    /// The signature was not made by the expected signer.
+    #[error("Invalid signature")]
+    InvalidSignature,
+
+    /// Signing error.
+    #[error("Signing error: {0}")]
+    Signer(#[from] SignerError),
+}
+
+/// Errors from exporting, importing or signing offline requests.
+#[derive(Error, Debug)]
+pub enum OfflineError {
+    /// The file or QR payload could not be decoded.
+    #[error("Invalid offline payload: {0}")]
+    InvalidPayload(String),
+
+    /// The payload was written by an unsupported format version.
+    #[error("Unsupported offline payload version {0}")]
+    UnsupportedVersion(u8),
+
+    /// No exported request has this message ID.
+    #[error("Unknown offline request {0}")]
+    UnknownRequest(Hash),
+
+    /// A signature was provided for a key the message does not require.
+    #[error("Signer is not required by the transaction: {0}")]
+    UnexpectedSigner(Pubkey),
+
+    /// Signing error.
+    #[error("Signing error: {0}")]
+    Signer(#[from] SignerError),
+}
+
+fn format_pubkeys(pubkeys: &[Pubkey]) -> String {
+    pubkeys
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,315 @@
+//! Fallback chain of transports.
+//!
+//! [`FallbackTransport`] tries an ordered list of [`WalletTransport`]s, such
+//! as the keyring agent, then a Ledger, then a keypair prompt, and returns
+//! the first successful [`SubmitResult`]. When every transport fails, the
+//! error lists why each one did.
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use solana_sdk::{message::VersionedMessage, pubkey::Pubkey};
+
+use crate::error::{FallbackFailure, SignerError, TransportError};
+use crate::transport::{SubmitResult, WalletTransport};
+
+type SubmitFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<SubmitResult, TransportError>> + Send + 'a>>;
+
+/// Tries transports in order until one submits the message.
+///
+/// A transport's error moves on to the next one, except when continuing could
+/// surprise the user or repeat work:
+///
+/// - [`SignerError::UserCancelled`] - the user declined; they shouldn't be
+///   asked again by another wallet
+/// - [`TransportError::PartialSubmit`] - a proposal already exists
+///
+/// Those errors are returned as-is. If every transport fails, the result is
+/// [`TransportError::AllTransportsFailed`] with each transport's error.
+///
+/// All transports should act for the same authority. Status checks go to the
+/// transport that made the last successful submission.
+///
+/// # Example
+///
+/// ```ignore
+/// use solana_actor::FallbackTransport;
+///
+/// let transport = FallbackTransport::new("agent", agent)
+///     .with_fallback("ledger", ledger)
+///     .with_fallback("keypair", prompt_for_keypair);
+///
+/// match transport.submit_versioned(&message).await {
+///     Ok(result) => {
+///         for failure in transport.take_failures() {
+///             eprintln!("Skipped {}: {}", failure.transport, failure.error);
+///         }
+///         println!("Signed with {}", transport.active().unwrap());
+///     }
+///     Err(e) => eprintln!("{}", e),
+/// }
+/// ```
+pub struct FallbackTransport {
+    transports: Vec<(String, Box<dyn WalletTransport>)>,
+    /// Index of the transport that made the last successful submission
+    active: Mutex<Option<usize>>,
+    /// Transports skipped by the last successful submission
+    failures: Mutex<Vec<FallbackFailure>>,
+}
+
+impl FallbackTransport {
+    /// Start a chain with its first transport.
+    pub fn new(name: impl Into<String>, transport: impl WalletTransport + 'static) -> Self {
+        Self {
+            transports: vec![(name.into(), Box::new(transport))],
+            active: Mutex::new(None),
+            failures: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Add a transport to try after those already added.
+    pub fn with_fallback(
+        mut self,
+        name: impl Into<String>,
+        transport: impl WalletTransport + 'static,
+    ) -> Self {
+        self.push(name, Box::new(transport));
+        self
+    }
+
+    /// Add a boxed transport to try after those already added.
+    pub fn push(&mut self, name: impl Into<String>, transport: Box<dyn WalletTransport>) {
+        self.transports.push((name.into(), transport));
+    }
+
+    /// The transport names, in the order they are tried.
+    pub fn names(&self) -> Vec<&str> {
+        self.transports
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    /// The name of the transport that made the last successful submission.
+    pub fn active(&self) -> Option<&str> {
+        let index = (*self.active.lock().unwrap_or_else(|e| e.into_inner()))?;
+        Some(self.transports[index].0.as_str())
+    }
+
+    /// Take the errors of transports skipped by the last successful
+    /// submission.
+    pub fn take_failures(&self) -> Vec<FallbackFailure> {
+        std::mem::take(&mut *self.failures.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// Whether an error should stop the chain instead of trying the next
+    /// transport.
+    fn is_final(error: &TransportError) -> bool {
+        matches!(
+            error,
+            TransportError::Signer(SignerError::UserCancelled)
+                | TransportError::PartialSubmit { .. }
+        )
+    }
+
+    async fn try_each<'a, F>(&'a self, mut call: F) -> Result<SubmitResult, TransportError>
+    where
+        F: FnMut(&'a dyn WalletTransport) -> SubmitFuture<'a> + Send,
+    {
+        let mut failures = Vec::new();
+        for (index, (name, transport)) in self.transports.iter().enumerate() {
+            match call(transport.as_ref()).await {
+                Ok(result) => {
+                    *self.active.lock().unwrap_or_else(|e| e.into_inner()) = Some(index);
+                    *self.failures.lock().unwrap_or_else(|e| e.into_inner()) = failures;
+                    return Ok(result);
+                }
+                Err(error) if Self::is_final(&error) => return Err(error),
+                Err(error) => failures.push(FallbackFailure {
+                    transport: name.clone(),
+                    error,
+                }),
+            }
+        }
+        Err(TransportError::AllTransportsFailed(failures))
+    }
+
+    /// The transport that status checks go to.
+    fn current(&self) -> &dyn WalletTransport {
+        let index = self
+            .active
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .unwrap_or(0);
+        self.transports[index].1.as_ref()
+    }
+}
+
+impl fmt::Debug for FallbackTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FallbackTransport")
+            .field("transports", &self.names())
+            .field("active", &self.active())
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl WalletTransport for FallbackTransport {
+    fn authority(&self) -> Pubkey {
+        self.transports[0].1.authority()
+    }
+
+    async fn submit(&self, message: &[u8]) -> Result<SubmitResult, TransportError> {
+        self.try_each(|transport| transport.submit(message)).await
+    }
+
+    async fn submit_versioned(
+        &self,
+        message: &VersionedMessage,
+    ) -> Result<SubmitResult, TransportError> {
+        self.try_each(|transport| transport.submit_versioned(message))
+            .await
+    }
+
+    async fn check_status(&self, result: &SubmitResult) -> Result<SubmitResult, TransportError> {
+        self.current().check_status(result).await
+    }
+
+    async fn wait_for_completion(
+        &self,
+        result: SubmitResult,
+        timeout: Duration,
+    ) -> Result<SubmitResult, TransportError> {
+        self.current().wait_for_completion(result, timeout).await
+    }
+
+    fn requires_network(&self) -> bool {
+        self.current().requires_network()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ConnectionError;
+    use solana_sdk::signature::Signature;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Transport that always returns the same outcome
+    struct StubTransport {
+        error: Option<fn() -> TransportError>,
+        calls: Arc<AtomicU32>,
+    }
+
+    impl StubTransport {
+        fn ok() -> Self {
+            Self {
+                error: None,
+                calls: Arc::default(),
+            }
+        }
+
+        fn failing(error: fn() -> TransportError) -> Self {
+            Self {
+                error: Some(error),
+                calls: Arc::default(),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl WalletTransport for StubTransport {
+        fn authority(&self) -> Pubkey {
+            Pubkey::default()
+        }
+
+        async fn submit(&self, _message: &[u8]) -> Result<SubmitResult, TransportError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            match self.error {
+                Some(error) => Err(error()),
+                None => Ok(SubmitResult::Signed(Signature::default())),
+            }
+        }
+
+        async fn check_status(
+            &self,
+            result: &SubmitResult,
+        ) -> Result<SubmitResult, TransportError> {
+            Ok(result.clone())
+        }
+
+        async fn wait_for_completion(
+            &self,
+            result: SubmitResult,
+            _timeout: Duration,
+        ) -> Result<SubmitResult, TransportError> {
+            Ok(result)
+        }
+
+        fn requires_network(&self) -> bool {
+            self.error.is_none()
+        }
+    }
+
+    fn agent_down() -> TransportError {
+        ConnectionError::NetworkUnreachable.into()
+    }
+
+    fn no_device() -> TransportError {
+        SignerError::DeviceNotFound.into()
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_in_order() {
+        let transport = FallbackTransport::new("agent", StubTransport::failing(agent_down))
+            .with_fallback("ledger", StubTransport::failing(no_device))
+            .with_fallback("keypair", StubTransport::ok());
+
+        assert!(transport.submit(b"message").await.unwrap().is_complete());
+        assert_eq!(transport.active(), Some("keypair"));
+        assert!(transport.requires_network());
+
+        let failures = transport.take_failures();
+        let names: Vec<_> = failures.iter().map(|f| f.transport.as_str()).collect();
+        assert_eq!(names, ["agent", "ledger"]);
+        assert!(transport.take_failures().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reports_every_failure() {
+        let transport = FallbackTransport::new("agent", StubTransport::failing(agent_down))
+            .with_fallback("ledger", StubTransport::failing(no_device));
+
+        let err = transport.submit(b"message").await.unwrap_err();
+        assert_eq!(err.fallback_failures().len(), 2);
+        let message = err.to_string();
+        assert!(message.contains("agent: Connection error"));
+        assert!(message.contains("ledger: Signing error: Device not found"));
+        assert_eq!(transport.active(), None);
+    }
+
+    #[tokio::test]
+    async fn test_stops_when_user_cancels() {
+        let keypair = StubTransport::ok();
+        let keypair_calls = keypair.calls.clone();
+        let transport = FallbackTransport::new(
+            "ledger",
+            StubTransport::failing(|| SignerError::UserCancelled.into()),
+        )
+        .with_fallback("keypair", keypair);
+
+        let err = transport.submit(b"message").await.unwrap_err();
+        assert!(matches!(
+            err,
+            TransportError::Signer(SignerError::UserCancelled)
+        ));
+        assert_eq!(keypair_calls.load(Ordering::SeqCst), 0);
+    }
+}
@@ -46,16 +46,154 @@
 //!
 //! Both are synchronous and perform no network operations.
 //!
+//! - [`AsyncTransactionSigner`] - Sign transaction messages with an async
+//!   backend (agent, remote HSM); implemented by every cloneable
+//!   [`TransactionSigner`]
+//!
+//! # Off-Chain Messages
+//!
+//! [`OffchainMessage`] wraps text in the standard off-chain signing envelope
+//! (`\xffsolana offchain` domain and a versioned header), so a signature over
+//! it can never be mistaken for a transaction signature. It signs with any
+//! [`MessageSigner`], and [`verify_offchain_message`] checks a serialized
+//! message against a signature.
+//!
+//! # Sign-In-With-Solana
+//!
+//! [`SiwsMessage`] builds and parses sign-in messages and signs them with any
+//! [`MessageSigner`]; [`SiwsVerifier`] checks the signature, domain, nonce
+//! and expiry on the server.
+//!
 //! # Transport Trait
 //!
 //! - [`WalletTransport`] - Async submission with status tracking
 //! - [`SubmitResult`] - Captures signed, pending, or executed states
 //!
+//! [`DirectTransport::with_connection`] also sends and confirms what it signs
+//! through a [`Connection`], returning [`SubmitResult::Executed`].
+//!
+//! # Transport Middleware
+//!
+//! Wrappers that implement [`WalletTransport`] around another transport, so
+//! cross-cutting behavior composes around any of them:
+//!
+//! - [`LoggingTransport`] - Logs each call, its outcome and duration
+//! - [`RetryTransport`] - Retries transient network errors per a [`RetryPolicy`]
+//! - [`MetricsTransport`] - Counts calls and outcomes as [`TransportMetrics`]
+//!
+//! [`FallbackTransport`] tries an ordered chain of transports (agent, then
+//! Ledger, then a keypair prompt) and reports each one's error if all fail.
+//!
 //! # Connection Trait
 //!
 //! - [`Connection`] - Network operations (send, confirm, query)
 //! - [`RpcConnection`] - Standard Solana RPC implementation (with `rpc` feature)
 //!
+//! # Confirmation
+//!
+//! [`Connection::confirm_signature`] waits for a sent transaction to reach a
+//! chosen [`Commitment`] (processed, confirmed or finalized), and
+//! [`Connection::get_signature_statuses`] reports where transactions stand
+//! without waiting.
+//!
+//! # Rate Limiting
+//!
+//! [`RateLimitConfig`] throttles an [`RpcConnection`] with a token bucket per
+//! [`MethodClass`] and retries HTTP 429 responses with jittered exponential
+//! backoff, so bulk scans stay within a public endpoint's limits.
+//!
+//! # Blockhash Caching
+//!
+//! [`CachedBlockhash`] reuses the latest blockhash for a configurable number
+//! of slots, so batch flows don't fetch one per transaction. Attach it with
+//! [`RpcConnection::with_blockhash_cache`].
+//!
+//! # Account Queries
+//!
+//! [`Connection::get_multiple_accounts`] fetches many accounts in batched
+//! requests, [`Connection::get_program_accounts`] lists a program's accounts
+//! narrowed by [`AccountFilter`]s, and [`Connection::get_token_account_balance`]
+//! reads an SPL token balance as a [`TokenAmount`].
+//!
+//! [`BalanceFetcher`] reads lamport and token balances for many accounts at
+//! once, keeping several `getMultipleAccounts` batches in flight instead of
+//! making one request per account.
+//!
+//! # Transaction Builder
+//!
+//! - [`TransactionBuilder`] - Collects instructions and signatures from several
+//!   [`TransactionSigner`]s (fee payer, authorities, ephemeral keys)
+//! - [`CompositeSigner`] - Owns several [`TransactionSigner`]s and signs an
+//!   already compiled message with every one it requires
+//! - [`SignerRegistry`] - Resolves the signer for a required public key;
+//!   [`InMemorySignerRegistry`] holds owned signers, and
+//!   [`TransactionBuilder::signers_from`] fills in signers from one
+//!
+//! # Preflight Checks
+//!
+//! [`check_transaction_size`] and [`check_signers`] reject a transaction that
+//! is larger than [`PACKET_DATA_SIZE`] or whose signers don't match the ones
+//! it requires, with an error saying what to change. [`TransactionBuilder`]
+//! and [`DirectTransport`] run them before any network call.
+//!
+//! # Versioned Transactions
+//!
+//! Legacy and v0 messages are both supported. [`WalletTransport::submit_versioned`]
+//! accepts a [`VersionedMessage`](solana_sdk::message::VersionedMessage),
+//! [`Connection::send_versioned_transaction`] sends the signed result, and
+//! [`Connection::get_address_lookup_table`] fetches the lookup tables a v0
+//! message is compiled against. [`TransactionBuilder::lookup_table`] and
+//! [`TransactionBuilder::build_versioned`] compile a v0 transaction against
+//! them, and [`create_lookup_table_instruction`] and its siblings manage the
+//! tables themselves.
+//!
+//! # Durable Nonces
+//!
+//! [`Connection::get_durable_nonce`] loads a [`DurableNonce`] from a nonce
+//! account. [`TransactionBuilder::durable_nonce`] and
+//! [`DirectTransport::with_nonce_account`] sign against it instead of a recent
+//! blockhash, so offline and delayed signatures don't expire.
+//!
+//! [`Connection::check_not_processed`] reports a transaction that already
+//! landed as [`ConnectionError::AlreadyProcessed`], or
+//! [`ConnectionError::NonceAdvanced`] once its nonce has moved on.
+//! [`RpcConnection`] runs it before every send.
+//!
+//! # Offline Signing
+//!
+//! [`PartiallySignedTransaction`] tracks which required signers of a message
+//! have signed, verifies each signature as it is added, and merges signature
+//! sets collected on different machines.
+//!
+//! [`OfflineTransport`] collects signatures from air-gapped machines. It
+//! returns [`SubmitResult::AwaitingSignatures`] and exports each message as an
+//! [`OfflineRequest`], encoded as JSON or a QR payload. The offline signer
+//! returns [`OfflineSignatures`], which
+//! [`OfflineTransport::apply_signatures`] verifies and merges.
+//!
+//! # Simulation
+//!
+//! [`Connection::simulate_transaction`] reports whether a transaction would
+//! succeed, its logs, compute units and balance changes as a
+//! [`SimulationResult`], so callers can warn or refuse before signing.
+//!
+//! # Priority Fees
+//!
+//! [`Connection::get_recent_prioritization_fees`] reports the fees paid in
+//! recent slots for a set of writable accounts, and
+//! [`Connection::get_recommended_compute_unit_price`] turns them into a price
+//! to attach with [`set_compute_unit_price_instruction`];
+//! [`set_compute_unit_limit_instruction`] caps the units a transaction may
+//! use, which also lowers the fee paid at a given price.
+//!
+//! # Blocking Signer Adapter
+//!
+//! [`TransportSigner`] wraps any [`WalletTransport`] as a
+//! [`solana_sdk::signer::Signer`], for code that can't adopt the async
+//! traits yet. Each signature blocks until the transport returns, and results
+//! that aren't the authority's signature, such as a pending multisig
+//! proposal, are errors.
+//!
 //! # Example
 //!
 //! ```ignore
@@ -75,24 +213,114 @@
 //!     SubmitResult::Signed(sig) => println!("Signed: {}", sig),
 //!     SubmitResult::Pending { .. } => println!("Awaiting approvals"),
 //!     SubmitResult::Executed { signature, .. } => println!("Executed: {}", signature),
+//!     SubmitResult::Nested { inner, .. } => println!("Awaiting nested approval: {:?}", inner),
+//!     SubmitResult::Rejected { reason, .. } => println!("Rejected: {}", reason),
+//!     SubmitResult::AwaitingSignatures { missing, .. } => println!("Sign offline: {:?}", missing),
 //! }
 //! ```
 //!
 //! # Feature Flags
 //!
-//! - `rpc` (default) - Include [`RpcConnection`] implementation
+//! - `rpc` (default) - Include [`RpcConnection`] implementation and its
+//!   [`RateLimitConfig`]
+//! - `mock` - Include `MockConnection`, an in-memory [`Connection`] for
+//!   unit testing transport logic, and `MockSigner`, an in-memory keypair
+//!   signer
+//! - `ws` - Include `PubsubConnection`, which confirms transactions over
+//!   WebSocket subscriptions when [`ConfirmStrategy::WebSocket`] is selected
 
+mod accounts;
+mod balance;
+mod blockhash;
+mod builder;
+mod composite;
+mod confirmation;
 mod connection;
 mod direct;
 mod error;
+mod fallback;
+mod lookup_table;
+mod middleware;
+#[cfg(any(test, feature = "mock"))]
+mod mock;
+mod nonce;
+mod offchain;
+mod offline;
+mod partial;
+mod preflight;
+mod priority_fee;
+#[cfg(feature = "ws")]
+mod pubsub;
+#[cfg(feature = "rpc")]
+mod rate_limit;
+mod registry;
+#[cfg(feature = "rpc")]
+mod rpc_auth;
 mod signer;
+mod simulation;
+mod siws;
 mod transport;
+mod transport_signer;
 
-pub use connection::{Connection, SendConfig};
+pub use accounts::{
+    AccountFilter, MAX_MULTIPLE_ACCOUNTS, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID, TokenAmount,
+    parse_mint_decimals, parse_token_account,
+};
+pub use balance::{BalanceFetcher, DEFAULT_BALANCE_CONCURRENCY};
+pub use blockhash::{CachedBlockhash, DEFAULT_BLOCKHASH_MAX_AGE_SLOTS, SLOT_DURATION};
+pub use builder::TransactionBuilder;
+pub use composite::CompositeSigner;
+pub use confirmation::{
+    CONFIRM_POLL_INTERVAL, CONFIRM_TIMEOUT, Commitment, MAX_SIGNATURE_STATUSES, SignatureInfo,
+    SignatureStatus,
+};
+pub use connection::{ConfirmStrategy, Connection, RetryPolicy, SendConfig};
 pub use direct::DirectTransport;
-pub use error::{ConnectionError, SignerError, TransportError};
-pub use signer::{MessageSigner, TransactionSigner};
+pub use error::{
+    BuildError, ConnectionError, FallbackFailure, OffchainMessageError, OfflineError, SignerError,
+    SiwsError, TransportError,
+};
+pub use fallback::FallbackTransport;
+pub use lookup_table::{
+    ADDRESS_LOOKUP_TABLE_PROGRAM_ID, LOOKUP_TABLE_MAX_ADDRESSES, LOOKUP_TABLE_META_SIZE,
+    LoadedAddresses, MAX_EXTEND_ADDRESSES, close_lookup_table_instruction,
+    create_lookup_table_instruction, deactivate_lookup_table_instruction,
+    derive_lookup_table_address, extend_lookup_table_instruction, parse_address_lookup_table,
+    resolve_loaded_addresses,
+};
+pub use middleware::{LogFn, LoggingTransport, MetricsTransport, RetryTransport, TransportMetrics};
+pub use nonce::{
+    DurableNonce, NONCE_ACCOUNT_SIZE, advance_nonce_instruction, durable_nonce_account,
+    parse_nonce_account,
+};
+pub use offchain::{OffchainMessage, OffchainMessageFormat, verify_offchain_message};
+pub use offline::{
+    OFFLINE_FORMAT_VERSION, OFFLINE_QR_PREFIX, OfflineNonce, OfflineRequest, OfflineSignatures,
+    OfflineTransport,
+};
+pub use partial::PartiallySignedTransaction;
+pub use preflight::{PACKET_DATA_SIZE, check_signers, check_transaction_size, transaction_size};
+pub use priority_fee::{
+    COMPUTE_BUDGET_PROGRAM_ID, DEFAULT_PRIORITY_FEE_PERCENTILE, PrioritizationFee,
+    recommend_compute_unit_price, set_compute_unit_limit_instruction,
+    set_compute_unit_price_instruction,
+};
+pub use registry::{InMemorySignerRegistry, SignerRegistry};
+pub use signer::{AsyncTransactionSigner, MessageSigner, TransactionSigner};
+pub use simulation::{BalanceChange, SimulationResult, balance_changes};
+pub use siws::{SiwsMessage, SiwsVerifier};
 pub use transport::{SubmitResult, WalletTransport};
+pub use transport_signer::TransportSigner;
 
 #[cfg(feature = "rpc")]
 pub use connection::RpcConnection;
+#[cfg(feature = "rpc")]
+pub use rate_limit::{MethodClass, RateLimit, RateLimitConfig};
+#[cfg(feature = "rpc")]
+pub use rpc_auth::RpcAuth;
+
+#[cfg(any(test, feature = "mock"))]
+pub use mock::{MockCall, MockConnection, MockSigner};
+
+#[cfg(feature = "ws")]
+pub use pubsub::{MAX_PROCESSING_AGE_SLOTS, PubsubConnection, websocket_url};
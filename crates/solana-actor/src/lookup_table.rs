@@ -0,0 +1,363 @@
+//! Address lookup table helpers for versioned transactions.
+//!
+//! Version 0 messages reference accounts stored in on-chain address lookup
+//! tables. This module parses lookup table accounts into the
+//! [`AddressLookupTableAccount`] form used when compiling a [`v0::Message`],
+//! resolves the accounts a message loads from its tables, and builds the
+//! instructions that create, extend, deactivate and close tables.
+//!
+//! [`v0::Message`]: solana_sdk::message::v0::Message
+
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    message::{AddressLookupTableAccount, v0::MessageAddressTableLookup},
+    pubkey::Pubkey,
+};
+
+use crate::error::ConnectionError;
+use crate::nonce::SYSTEM_PROGRAM_ID;
+
+/// Address lookup table program ID.
+pub const ADDRESS_LOOKUP_TABLE_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("AddressLookupTab1e1111111111111111111111111");
+
+/// Size of the lookup table metadata preceding the stored addresses.
+pub const LOOKUP_TABLE_META_SIZE: usize = 56;
+
+/// Most addresses a lookup table can hold.
+pub const LOOKUP_TABLE_MAX_ADDRESSES: usize = 256;
+
+/// Most addresses one extend instruction adds while its transaction, paid
+/// for by the authority, still fits in a packet.
+pub const MAX_EXTEND_ADDRESSES: usize = 30;
+
+// Lookup table program instruction tags
+const CREATE_LOOKUP_TABLE: u32 = 0;
+const EXTEND_LOOKUP_TABLE: u32 = 2;
+const DEACTIVATE_LOOKUP_TABLE: u32 = 3;
+const CLOSE_LOOKUP_TABLE: u32 = 4;
+
+/// Derive the address of the lookup table `authority` creates at
+/// `recent_slot`, with its bump seed.
+pub fn derive_lookup_table_address(authority: &Pubkey, recent_slot: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[authority.as_ref(), &recent_slot.to_le_bytes()],
+        &ADDRESS_LOOKUP_TABLE_PROGRAM_ID,
+    )
+}
+
+/// Build a `CreateLookupTable` instruction, returning it with the new
+/// table's address.
+///
+/// # Arguments
+///
+/// * `authority` - The table's authority, which need not sign.
+/// * `payer` - Pays the table's rent and must sign.
+/// * `recent_slot` - A recent slot, which seeds the table address. It must
+///   still be in the slot hashes sysvar when the transaction lands, so use a
+///   finalized slot.
+pub fn create_lookup_table_instruction(
+    authority: &Pubkey,
+    payer: &Pubkey,
+    recent_slot: u64,
+) -> (Instruction, Pubkey) {
+    let (table, bump_seed) = derive_lookup_table_address(authority, recent_slot);
+
+    let mut data = CREATE_LOOKUP_TABLE.to_le_bytes().to_vec();
+    data.extend_from_slice(&recent_slot.to_le_bytes());
+    data.push(bump_seed);
+
+    let instruction = Instruction::new_with_bytes(
+        ADDRESS_LOOKUP_TABLE_PROGRAM_ID,
+        &data,
+        vec![
+            AccountMeta::new(table, false),
+            AccountMeta::new_readonly(*authority, false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+    );
+    (instruction, table)
+}
+
+/// Build an `ExtendLookupTable` instruction.
+///
+/// # Arguments
+///
+/// * `table` - The lookup table address.
+/// * `authority` - The table's authority, which must sign.
+/// * `payer` - Pays the rent for the added space, if the table needs more.
+/// * `addresses` - Addresses to append. At most [`MAX_EXTEND_ADDRESSES`] fit
+///   in one transaction.
+pub fn extend_lookup_table_instruction(
+    table: &Pubkey,
+    authority: &Pubkey,
+    payer: Option<&Pubkey>,
+    addresses: &[Pubkey],
+) -> Instruction {
+    let mut data = EXTEND_LOOKUP_TABLE.to_le_bytes().to_vec();
+    data.extend_from_slice(&(addresses.len() as u64).to_le_bytes());
+    for address in addresses {
+        data.extend_from_slice(address.as_ref());
+    }
+
+    let mut accounts = vec![
+        AccountMeta::new(*table, false),
+        AccountMeta::new_readonly(*authority, true),
+    ];
+    if let Some(payer) = payer {
+        accounts.push(AccountMeta::new(*payer, true));
+        accounts.push(AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false));
+    }
+
+    Instruction::new_with_bytes(ADDRESS_LOOKUP_TABLE_PROGRAM_ID, &data, accounts)
+}
+
+/// Build a `DeactivateLookupTable` instruction.
+///
+/// A deactivated table can't be extended or used by new transactions, and
+/// can be closed once its deactivation slot leaves the slot hashes sysvar
+/// (about 513 slots).
+pub fn deactivate_lookup_table_instruction(table: &Pubkey, authority: &Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        ADDRESS_LOOKUP_TABLE_PROGRAM_ID,
+        &DEACTIVATE_LOOKUP_TABLE.to_le_bytes(),
+        vec![
+            AccountMeta::new(*table, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+/// Build a `CloseLookupTable` instruction, returning the table's rent to
+/// `recipient`.
+pub fn close_lookup_table_instruction(
+    table: &Pubkey,
+    authority: &Pubkey,
+    recipient: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        ADDRESS_LOOKUP_TABLE_PROGRAM_ID,
+        &CLOSE_LOOKUP_TABLE.to_le_bytes(),
+        vec![
+            AccountMeta::new(*table, false),
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(*recipient, false),
+        ],
+    )
+}
+
+/// Parse an address lookup table account.
+///
+/// # Arguments
+///
+/// * `key` - The lookup table address.
+/// * `account` - The lookup table account as fetched from the network.
+///
+/// # Errors
+///
+/// Returns [`ConnectionError::InvalidAccountData`] if the account is not owned
+/// by the address lookup table program or its data is malformed.
+pub fn parse_address_lookup_table(
+    key: Pubkey,
+    account: &Account,
+) -> Result<AddressLookupTableAccount, ConnectionError> {
+    if account.owner != ADDRESS_LOOKUP_TABLE_PROGRAM_ID {
+        return Err(ConnectionError::InvalidAccountData(format!(
+            "{} is not an address lookup table",
+            key
+        )));
+    }
+
+    let data = account.data.get(LOOKUP_TABLE_META_SIZE..).ok_or_else(|| {
+        ConnectionError::InvalidAccountData(format!("Lookup table {} is too small", key))
+    })?;
+    let addresses = data
+        .chunks(32)
+        .map(Pubkey::try_from)
+        .collect::<Result<_, _>>()
+        .map_err(|_| {
+            ConnectionError::InvalidAccountData(format!(
+                "Lookup table {} has a partial address",
+                key
+            ))
+        })?;
+
+    Ok(AddressLookupTableAccount { key, addresses })
+}
+
+/// Accounts a versioned message loads from address lookup tables.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoadedAddresses {
+    /// Writable accounts, in message order.
+    pub writable: Vec<Pubkey>,
+    /// Readonly accounts, in message order.
+    pub readonly: Vec<Pubkey>,
+}
+
+/// Resolve the accounts loaded through a message's address table lookups.
+///
+/// Legacy messages have no lookups; pass
+/// `message.address_table_lookups().unwrap_or_default()` to handle both.
+///
+/// # Arguments
+///
+/// * `lookups` - The message's address table lookups.
+/// * `tables` - The lookup tables referenced by the lookups, in any order.
+///
+/// # Errors
+///
+/// Returns [`ConnectionError::InvalidAccountData`] if a referenced table is
+/// missing or an index is out of range.
+pub fn resolve_loaded_addresses(
+    lookups: &[MessageAddressTableLookup],
+    tables: &[AddressLookupTableAccount],
+) -> Result<LoadedAddresses, ConnectionError> {
+    let mut loaded = LoadedAddresses::default();
+
+    for lookup in lookups {
+        let table = tables
+            .iter()
+            .find(|t| t.key == lookup.account_key)
+            .ok_or_else(|| {
+                ConnectionError::InvalidAccountData(format!(
+                    "Missing lookup table {}",
+                    lookup.account_key
+                ))
+            })?;
+
+        let get = |index: &u8| {
+            table
+                .addresses
+                .get(*index as usize)
+                .copied()
+                .ok_or_else(|| {
+                    ConnectionError::InvalidAccountData(format!(
+                        "Index {} out of range for lookup table {}",
+                        index, table.key
+                    ))
+                })
+        };
+
+        for index in &lookup.writable_indexes {
+            loaded.writable.push(get(index)?);
+        }
+        for index in &lookup.readonly_indexes {
+            loaded.readonly.push(get(index)?);
+        }
+    }
+
+    Ok(loaded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{
+        hash::Hash,
+        instruction::{AccountMeta, Instruction},
+        message::{VersionedMessage, v0},
+    };
+
+    fn table_account(addresses: &[Pubkey]) -> Account {
+        let mut data = vec![0u8; LOOKUP_TABLE_META_SIZE];
+        for address in addresses {
+            data.extend_from_slice(address.as_ref());
+        }
+        Account {
+            lamports: 1,
+            data,
+            owner: ADDRESS_LOOKUP_TABLE_PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    #[test]
+    fn test_parse_address_lookup_table() {
+        let key = Pubkey::new_unique();
+        let addresses = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+
+        let table = parse_address_lookup_table(key, &table_account(&addresses)).unwrap();
+        assert_eq!(table.key, key);
+        assert_eq!(table.addresses, addresses);
+    }
+
+    #[test]
+    fn test_parse_address_lookup_table_wrong_owner() {
+        let mut account = table_account(&[Pubkey::new_unique()]);
+        account.owner = Pubkey::new_unique();
+
+        let err = parse_address_lookup_table(Pubkey::new_unique(), &account).unwrap_err();
+        assert!(matches!(err, ConnectionError::InvalidAccountData(_)));
+    }
+
+    #[test]
+    fn test_resolve_loaded_addresses() {
+        let payer = Pubkey::new_unique();
+        let program = Pubkey::new_unique();
+        let writable = Pubkey::new_unique();
+        let readonly = Pubkey::new_unique();
+
+        let table = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: vec![readonly, writable],
+        };
+        let ix = Instruction::new_with_bytes(
+            program,
+            &[],
+            vec![
+                AccountMeta::new(writable, false),
+                AccountMeta::new_readonly(readonly, false),
+            ],
+        );
+        let message = VersionedMessage::V0(
+            v0::Message::try_compile(&payer, &[ix], std::slice::from_ref(&table), Hash::default())
+                .unwrap(),
+        );
+
+        let lookups = message.address_table_lookups().unwrap_or_default();
+
+        let loaded = resolve_loaded_addresses(lookups, &[table]).unwrap();
+        assert_eq!(loaded.writable, vec![writable]);
+        assert_eq!(loaded.readonly, vec![readonly]);
+
+        assert!(resolve_loaded_addresses(lookups, &[]).is_err());
+    }
+
+    #[test]
+    fn test_create_lookup_table_instruction() {
+        let authority = Pubkey::new_unique();
+        let (ix, table) = create_lookup_table_instruction(&authority, &authority, 42);
+
+        let (expected, bump_seed) = derive_lookup_table_address(&authority, 42);
+        assert_eq!(table, expected);
+        assert_eq!(ix.accounts[0], AccountMeta::new(table, false));
+        assert_eq!(&ix.data[..4], &[0, 0, 0, 0]);
+        assert_eq!(&ix.data[4..12], &42u64.to_le_bytes());
+        assert_eq!(ix.data[12], bump_seed);
+    }
+
+    #[test]
+    fn test_extend_lookup_table_fits_in_packet() {
+        let authority = Pubkey::new_unique();
+        let table = Pubkey::new_unique();
+        let addresses: Vec<Pubkey> = (0..MAX_EXTEND_ADDRESSES)
+            .map(|_| Pubkey::new_unique())
+            .collect();
+
+        let ix = extend_lookup_table_instruction(&table, &authority, Some(&authority), &addresses);
+        assert_eq!(&ix.data[..4], &[2, 0, 0, 0]);
+        assert_eq!(
+            &ix.data[4..12],
+            &(MAX_EXTEND_ADDRESSES as u64).to_le_bytes()
+        );
+        assert_eq!(ix.data.len(), 12 + 32 * MAX_EXTEND_ADDRESSES);
+
+        let message = solana_sdk::message::Message::new(&[ix], Some(&authority));
+        assert!(
+            crate::preflight::check_transaction_size(&VersionedMessage::Legacy(message)).is_ok()
+        );
+    }
+}
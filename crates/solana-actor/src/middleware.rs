@@ -0,0 +1,548 @@
+//! Transport middleware.
+//!
+//! Each wrapper here implements [`WalletTransport`] around another transport,
+//! so cross-cutting behavior composes around [`DirectTransport`],
+//! `SquadsTransport` or any other transport:
+//!
+//! ```ignore
+//! let transport = LoggingTransport::new(MetricsTransport::new(RetryTransport::new(
+//!     SquadsTransport::new(/* ... */),
+//!     RetryPolicy::default(),
+//! )));
+//! ```
+//!
+//! [`DirectTransport`]: crate::DirectTransport
+
+use std::fmt;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use solana_sdk::{message::VersionedMessage, pubkey::Pubkey};
+
+use crate::connection::RetryPolicy;
+use crate::error::TransportError;
+use crate::transport::{SubmitResult, WalletTransport};
+
+/// Short description of a submit result for logs.
+fn describe(result: &SubmitResult) -> String {
+    match result {
+        SubmitResult::Signed(sig) => format!("signed {}", sig),
+        SubmitResult::Pending {
+            proposal,
+            approvals,
+            threshold,
+            ..
+        } => format!("pending {} ({}/{})", proposal, approvals, threshold),
+        SubmitResult::Executed { signature, .. } => format!("executed {}", signature),
+        SubmitResult::Rejected { proposal, reason } => {
+            format!("rejected {} ({})", proposal, reason)
+        }
+        SubmitResult::Nested { outer, inner } => {
+            format!("nested [{}] via [{}]", describe(outer), describe(inner))
+        }
+        SubmitResult::AwaitingSignatures {
+            message_id,
+            missing,
+        } => {
+            format!("awaiting {} signature(s) for {}", missing.len(), message_id)
+        }
+    }
+}
+
+/// Log sink used by [`LoggingTransport`].
+pub type LogFn = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// Logs every call and its outcome, with timing.
+///
+/// Lines go to stderr unless a sink is set with
+/// [`with_logger`](Self::with_logger).
+pub struct LoggingTransport<T> {
+    inner: T,
+    name: String,
+    log: LogFn,
+}
+
+impl<T: WalletTransport> LoggingTransport<T> {
+    /// Log calls to `inner` on stderr.
+    pub fn new(inner: T) -> Self {
+        let name = inner.authority().to_string();
+        Self {
+            inner,
+            name,
+            log: Arc::new(|line| eprintln!("{}", line)),
+        }
+    }
+
+    /// Prefix log lines with `name` instead of the authority.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Send log lines to `log` instead of stderr.
+    pub fn with_logger(mut self, log: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        self.log = Arc::new(log);
+        self
+    }
+
+    /// The wrapped transport.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Unwrap the transport.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    fn record(&self, call: &str, started: Instant, result: &Result<SubmitResult, TransportError>) {
+        let elapsed = started.elapsed().as_millis();
+        let outcome = match result {
+            Ok(result) => describe(result),
+            Err(e) => format!("error: {}", e),
+        };
+        (self.log)(&format!(
+            "[{}] {} -> {} in {}ms",
+            self.name, call, outcome, elapsed
+        ));
+    }
+}
+
+impl<T> fmt::Debug for LoggingTransport<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LoggingTransport")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl<T: WalletTransport> WalletTransport for LoggingTransport<T> {
+    fn authority(&self) -> Pubkey {
+        self.inner.authority()
+    }
+
+    async fn submit(&self, message: &[u8]) -> Result<SubmitResult, TransportError> {
+        let started = Instant::now();
+        let result = self.inner.submit(message).await;
+        self.record("submit", started, &result);
+        result
+    }
+
+    async fn submit_versioned(
+        &self,
+        message: &VersionedMessage,
+    ) -> Result<SubmitResult, TransportError> {
+        let started = Instant::now();
+        let result = self.inner.submit_versioned(message).await;
+        self.record("submit_versioned", started, &result);
+        result
+    }
+
+    async fn check_status(&self, result: &SubmitResult) -> Result<SubmitResult, TransportError> {
+        let started = Instant::now();
+        let status = self.inner.check_status(result).await;
+        self.record("check_status", started, &status);
+        status
+    }
+
+    async fn wait_for_completion(
+        &self,
+        result: SubmitResult,
+        timeout: Duration,
+    ) -> Result<SubmitResult, TransportError> {
+        let started = Instant::now();
+        let completed = self.inner.wait_for_completion(result, timeout).await;
+        self.record("wait_for_completion", started, &completed);
+        completed
+    }
+
+    fn requires_network(&self) -> bool {
+        self.inner.requires_network()
+    }
+}
+
+/// Retries calls that fail with a transient network error.
+///
+/// Only errors for which [`TransportError::is_transient`] holds are retried,
+/// waiting between attempts as set by the [`RetryPolicy`].
+/// `wait_for_completion` is passed through, since it already polls.
+///
+/// A retried `submit` may repeat work the failed attempt already did on-chain
+/// (for example a proposal created before the connection dropped), so wrap
+/// transports whose submission is not idempotent with care.
+#[derive(Debug)]
+pub struct RetryTransport<T> {
+    inner: T,
+    policy: RetryPolicy,
+}
+
+impl<T: WalletTransport> RetryTransport<T> {
+    /// Retry calls to `inner` according to `policy`.
+    pub fn new(inner: T, policy: RetryPolicy) -> Self {
+        Self { inner, policy }
+    }
+
+    /// The retry policy.
+    pub fn policy(&self) -> RetryPolicy {
+        self.policy
+    }
+
+    /// The wrapped transport.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Unwrap the transport.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    async fn retry<'a, F, Fut>(&'a self, mut call: F) -> Result<SubmitResult, TransportError>
+    where
+        F: FnMut() -> Fut + Send,
+        Fut: Future<Output = Result<SubmitResult, TransportError>> + Send + 'a,
+    {
+        let max_attempts = self.policy.max_attempts.max(1);
+        let mut attempt = 1;
+        loop {
+            match call().await {
+                Err(e) if e.is_transient() && attempt < max_attempts => {
+                    tokio::time::sleep(self.policy.backoff(attempt)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<T: WalletTransport> WalletTransport for RetryTransport<T> {
+    fn authority(&self) -> Pubkey {
+        self.inner.authority()
+    }
+
+    async fn submit(&self, message: &[u8]) -> Result<SubmitResult, TransportError> {
+        self.retry(|| self.inner.submit(message)).await
+    }
+
+    async fn submit_versioned(
+        &self,
+        message: &VersionedMessage,
+    ) -> Result<SubmitResult, TransportError> {
+        self.retry(|| self.inner.submit_versioned(message)).await
+    }
+
+    async fn check_status(&self, result: &SubmitResult) -> Result<SubmitResult, TransportError> {
+        self.retry(|| self.inner.check_status(result)).await
+    }
+
+    async fn wait_for_completion(
+        &self,
+        result: SubmitResult,
+        timeout: Duration,
+    ) -> Result<SubmitResult, TransportError> {
+        self.inner.wait_for_completion(result, timeout).await
+    }
+
+    fn requires_network(&self) -> bool {
+        self.inner.requires_network()
+    }
+}
+
+/// Counters collected by [`MetricsTransport`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TransportMetrics {
+    /// Calls to `submit` and `submit_versioned`.
+    pub submits: u64,
+    /// Submissions that returned an error.
+    pub submit_errors: u64,
+    /// Calls to `check_status` and `wait_for_completion`.
+    pub status_checks: u64,
+    /// Status checks that returned an error.
+    pub status_errors: u64,
+    /// Results that were signed or executed.
+    pub completed: u64,
+    /// Results still awaiting approvals.
+    pub pending: u64,
+    /// Results that were rejected.
+    pub rejected: u64,
+    /// Total time spent in submissions.
+    pub submit_time: Duration,
+}
+
+impl TransportMetrics {
+    /// Mean time per submission, if any were made.
+    pub fn mean_submit_time(&self) -> Option<Duration> {
+        u32::try_from(self.submits)
+            .ok()
+            .filter(|&n| n > 0)
+            .map(|n| self.submit_time / n)
+    }
+
+    fn record_result(&mut self, result: &SubmitResult) {
+        if result.is_complete() {
+            self.completed += 1;
+        } else if result.is_rejected() {
+            self.rejected += 1;
+        } else {
+            self.pending += 1;
+        }
+    }
+}
+
+/// Counts calls, outcomes and submission latency.
+#[derive(Debug)]
+pub struct MetricsTransport<T> {
+    inner: T,
+    metrics: Mutex<TransportMetrics>,
+}
+
+impl<T: WalletTransport> MetricsTransport<T> {
+    /// Collect metrics for calls to `inner`.
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            metrics: Mutex::new(TransportMetrics::default()),
+        }
+    }
+
+    /// A snapshot of the counters.
+    pub fn metrics(&self) -> TransportMetrics {
+        *self.lock()
+    }
+
+    /// Return the counters and reset them to zero.
+    pub fn take_metrics(&self) -> TransportMetrics {
+        std::mem::take(&mut *self.lock())
+    }
+
+    /// The wrapped transport.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Unwrap the transport.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, TransportMetrics> {
+        self.metrics.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn record_submit(&self, started: Instant, result: &Result<SubmitResult, TransportError>) {
+        let mut metrics = self.lock();
+        metrics.submits += 1;
+        metrics.submit_time += started.elapsed();
+        match result {
+            Ok(result) => metrics.record_result(result),
+            Err(_) => metrics.submit_errors += 1,
+        }
+    }
+
+    fn record_status(&self, result: &Result<SubmitResult, TransportError>) {
+        let mut metrics = self.lock();
+        metrics.status_checks += 1;
+        match result {
+            Ok(result) => metrics.record_result(result),
+            Err(_) => metrics.status_errors += 1,
+        }
+    }
+}
+
+#[async_trait]
+impl<T: WalletTransport> WalletTransport for MetricsTransport<T> {
+    fn authority(&self) -> Pubkey {
+        self.inner.authority()
+    }
+
+    async fn submit(&self, message: &[u8]) -> Result<SubmitResult, TransportError> {
+        let started = Instant::now();
+        let result = self.inner.submit(message).await;
+        self.record_submit(started, &result);
+        result
+    }
+
+    async fn submit_versioned(
+        &self,
+        message: &VersionedMessage,
+    ) -> Result<SubmitResult, TransportError> {
+        let started = Instant::now();
+        let result = self.inner.submit_versioned(message).await;
+        self.record_submit(started, &result);
+        result
+    }
+
+    async fn check_status(&self, result: &SubmitResult) -> Result<SubmitResult, TransportError> {
+        let status = self.inner.check_status(result).await;
+        self.record_status(&status);
+        status
+    }
+
+    async fn wait_for_completion(
+        &self,
+        result: SubmitResult,
+        timeout: Duration,
+    ) -> Result<SubmitResult, TransportError> {
+        let completed = self.inner.wait_for_completion(result, timeout).await;
+        self.record_status(&completed);
+        completed
+    }
+
+    fn requires_network(&self) -> bool {
+        self.inner.requires_network()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ConnectionError;
+    use solana_sdk::signature::Signature;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Transport that fails a set number of times before signing
+    struct FlakyTransport {
+        failures: u32,
+        calls: AtomicU32,
+        error: fn() -> TransportError,
+    }
+
+    impl FlakyTransport {
+        fn new(failures: u32, error: fn() -> TransportError) -> Self {
+            Self {
+                failures,
+                calls: AtomicU32::new(0),
+                error,
+            }
+        }
+
+        fn calls(&self) -> u32 {
+            self.calls.load(Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait]
+    impl WalletTransport for FlakyTransport {
+        fn authority(&self) -> Pubkey {
+            Pubkey::default()
+        }
+
+        async fn submit(&self, _message: &[u8]) -> Result<SubmitResult, TransportError> {
+            if self.calls.fetch_add(1, Ordering::SeqCst) < self.failures {
+                return Err((self.error)());
+            }
+            Ok(SubmitResult::Signed(Signature::default()))
+        }
+
+        async fn check_status(
+            &self,
+            result: &SubmitResult,
+        ) -> Result<SubmitResult, TransportError> {
+            Ok(result.clone())
+        }
+
+        async fn wait_for_completion(
+            &self,
+            result: SubmitResult,
+            _timeout: Duration,
+        ) -> Result<SubmitResult, TransportError> {
+            Ok(result)
+        }
+
+        fn requires_network(&self) -> bool {
+            true
+        }
+    }
+
+    fn rate_limited() -> TransportError {
+        ConnectionError::RateLimited.into()
+    }
+
+    fn no_backoff(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            initial_backoff: Duration::ZERO,
+            max_backoff: Duration::ZERO,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_recovers_from_transient_errors() {
+        let transport = RetryTransport::new(FlakyTransport::new(2, rate_limited), no_backoff(3));
+        let result = transport.submit(b"message").await.unwrap();
+        assert!(result.is_complete());
+        assert_eq!(transport.inner().calls(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_gives_up_after_max_attempts() {
+        let transport = RetryTransport::new(FlakyTransport::new(5, rate_limited), no_backoff(3));
+        let err = transport.submit(b"message").await.unwrap_err();
+        assert!(err.is_transient());
+        assert_eq!(transport.inner().calls(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_skips_permanent_errors() {
+        let transport = RetryTransport::new(
+            FlakyTransport::new(1, || TransportError::ProposalFailed("bad".into())),
+            no_backoff(3),
+        );
+        assert!(transport.submit(b"message").await.is_err());
+        assert_eq!(transport.inner().calls(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_metrics_counts_outcomes() {
+        let transport = MetricsTransport::new(FlakyTransport::new(1, rate_limited));
+        assert!(transport.submit(b"message").await.is_err());
+        let result = transport.submit(b"message").await.unwrap();
+        transport.check_status(&result).await.unwrap();
+
+        let metrics = transport.metrics();
+        assert_eq!(metrics.submits, 2);
+        assert_eq!(metrics.submit_errors, 1);
+        assert_eq!(metrics.status_checks, 1);
+        assert_eq!(metrics.completed, 2);
+        assert!(metrics.mean_submit_time().is_some());
+
+        assert_eq!(transport.take_metrics(), metrics);
+        assert_eq!(transport.metrics(), TransportMetrics::default());
+    }
+
+    #[tokio::test]
+    async fn test_logging_reports_calls() {
+        let lines = Arc::new(Mutex::new(Vec::new()));
+        let sink = lines.clone();
+        let transport = LoggingTransport::new(FlakyTransport::new(1, rate_limited))
+            .with_name("test")
+            .with_logger(move |line| sink.lock().unwrap().push(line.to_string()));
+
+        assert!(transport.submit(b"message").await.is_err());
+        assert!(transport.submit(b"message").await.is_ok());
+
+        let lines = lines.lock().unwrap();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("[test] submit -> error: Connection error: Rate limited"));
+        assert!(lines[1].starts_with("[test] submit -> signed"));
+    }
+
+    #[tokio::test]
+    async fn test_layers_compose() {
+        let transport = LoggingTransport::new(MetricsTransport::new(RetryTransport::new(
+            FlakyTransport::new(1, rate_limited),
+            no_backoff(2),
+        )))
+        .with_logger(|_| {});
+
+        assert!(transport.submit(b"message").await.unwrap().is_complete());
+        let metrics = transport.inner().metrics();
+        assert_eq!(metrics.submits, 1);
+        assert_eq!(metrics.submit_errors, 0);
+    }
+}
@@ -0,0 +1,743 @@
+//! In-memory [`Connection`] for tests.
+//!
+//! [`MockConnection`] answers balance, account, blockhash and fee queries from
+//! values configured up front, queues the outcome of each send or simulation,
+//! and records every call so tests can assert on what transport logic did
+//! without reaching an RPC node. [`MockSigner`] signs with an in-memory
+//! keypair.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use async_trait::async_trait;
+use solana_sdk::{
+    account::Account,
+    hash::Hash,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer as _,
+    transaction::{Transaction, VersionedTransaction},
+};
+
+use crate::accounts::AccountFilter;
+use crate::confirmation::{SignatureInfo, SignatureStatus};
+use crate::connection::{Connection, SendConfig};
+use crate::error::{ConnectionError, SignerError};
+use crate::priority_fee::PrioritizationFee;
+use crate::signer::{MessageSigner, TransactionSigner};
+use crate::simulation::SimulationResult;
+
+/// A call made to a [`MockConnection`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MockCall {
+    /// A transaction was sent, with or without waiting for confirmation.
+    Send {
+        /// The transaction's first signature.
+        signature: Signature,
+        /// Whether confirmation was requested.
+        confirm: bool,
+    },
+    /// A transaction was simulated.
+    Simulate,
+    /// The latest blockhash was requested.
+    GetLatestBlockhash,
+    /// A balance was requested.
+    GetBalance(Pubkey),
+    /// An account was requested.
+    GetAccount(Pubkey),
+    /// Several accounts were requested at once.
+    GetMultipleAccounts(Vec<Pubkey>),
+    /// A program's accounts were requested.
+    GetProgramAccounts(Pubkey),
+    /// Recent prioritization fees were requested.
+    GetRecentPrioritizationFees(Vec<Pubkey>),
+    /// Signature statuses were requested.
+    GetSignatureStatuses(Vec<Signature>),
+    /// An address's signature history was requested.
+    GetSignaturesForAddress(Pubkey),
+}
+
+#[derive(Default)]
+struct MockState {
+    balances: HashMap<Pubkey, u64>,
+    accounts: HashMap<Pubkey, Account>,
+    blockhash: Hash,
+    blockhashes: VecDeque<Hash>,
+    send_results: VecDeque<Result<(), ConnectionError>>,
+    simulations: VecDeque<Result<SimulationResult, ConnectionError>>,
+    prioritization_fees: Vec<PrioritizationFee>,
+    signature_statuses: HashMap<Signature, VecDeque<SignatureStatus>>,
+    signature_history: HashMap<Pubkey, Vec<SignatureInfo>>,
+    signature_page_size: Option<usize>,
+    calls: Vec<MockCall>,
+    sent: Vec<VersionedTransaction>,
+}
+
+/// A [`Connection`] with programmable responses and call recording.
+///
+/// Unknown accounts are missing and have a zero balance. Sends succeed and
+/// simulations report success unless an outcome was queued; queued outcomes
+/// are used once each, in order.
+///
+/// Requires the `mock` feature outside this crate's own tests.
+///
+/// # Example
+///
+/// ```ignore
+/// use solana_actor::{ConnectionError, MockCall, MockConnection};
+///
+/// let connection = MockConnection::new()
+///     .with_balance(payer, 1_000_000_000)
+///     .with_blockhashes([first, second])
+///     .expect_send(Err(ConnectionError::BlockhashExpired))
+///     .expect_send(Ok(()));
+///
+/// run_transport_logic(&connection).await;
+///
+/// assert_eq!(connection.sent_transactions().len(), 2);
+/// assert_eq!(connection.count(|c| matches!(c, MockCall::GetLatestBlockhash)), 2);
+/// ```
+#[derive(Default)]
+pub struct MockConnection {
+    state: Mutex<MockState>,
+}
+
+impl MockConnection {
+    /// Create a connection with no accounts and a default blockhash.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Set an account's balance.
+    pub fn with_balance(self, pubkey: Pubkey, lamports: u64) -> Self {
+        self.state().balances.insert(pubkey, lamports);
+        self
+    }
+
+    /// Add an account. Its lamports are also reported as its balance unless a
+    /// balance was set separately.
+    pub fn with_account(self, pubkey: Pubkey, account: Account) -> Self {
+        self.state().accounts.insert(pubkey, account);
+        self
+    }
+
+    /// Set the blockhash returned once any queued blockhashes are used up.
+    pub fn with_blockhash(self, blockhash: Hash) -> Self {
+        self.state().blockhash = blockhash;
+        self
+    }
+
+    /// Queue blockhashes returned by successive `get_latest_blockhash` calls.
+    ///
+    /// The last one keeps being returned afterwards.
+    pub fn with_blockhashes(self, blockhashes: impl IntoIterator<Item = Hash>) -> Self {
+        self.state().blockhashes.extend(blockhashes);
+        self
+    }
+
+    /// Set the fees returned by `get_recent_prioritization_fees`.
+    pub fn with_prioritization_fees(self, fees: Vec<PrioritizationFee>) -> Self {
+        self.state().prioritization_fees = fees;
+        self
+    }
+
+    /// Queue statuses returned for `signature` by successive
+    /// `get_signature_statuses` calls.
+    ///
+    /// The last one keeps being returned afterwards. Signatures without
+    /// statuses are reported as unseen.
+    pub fn with_signature_statuses(
+        self,
+        signature: Signature,
+        statuses: impl IntoIterator<Item = SignatureStatus>,
+    ) -> Self {
+        self.state()
+            .signature_statuses
+            .entry(signature)
+            .or_default()
+            .extend(statuses);
+        self
+    }
+
+    /// Set the signature history returned for `address`, newest first.
+    ///
+    /// Addresses without a history have no transactions.
+    pub fn with_signature_history(self, address: Pubkey, history: Vec<SignatureInfo>) -> Self {
+        self.state().signature_history.insert(address, history);
+        self
+    }
+
+    /// Return signature histories in pages of at most `size` entries.
+    ///
+    /// Histories are returned whole by default.
+    pub fn with_signature_page_size(self, size: usize) -> Self {
+        self.state().signature_page_size = Some(size);
+        self
+    }
+
+    /// Queue the outcome of the next send.
+    pub fn expect_send(self, result: Result<(), ConnectionError>) -> Self {
+        self.state().send_results.push_back(result);
+        self
+    }
+
+    /// Queue the outcome of the next simulation.
+    pub fn expect_simulation(self, result: Result<SimulationResult, ConnectionError>) -> Self {
+        self.state().simulations.push_back(result);
+        self
+    }
+
+    /// Update an account's balance after construction.
+    pub fn set_balance(&self, pubkey: Pubkey, lamports: u64) {
+        self.state().balances.insert(pubkey, lamports);
+    }
+
+    /// Every call made so far, in order.
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.state().calls.clone()
+    }
+
+    /// The number of calls matching a predicate.
+    pub fn count(&self, predicate: impl Fn(&MockCall) -> bool) -> usize {
+        self.state().calls.iter().filter(|c| predicate(c)).count()
+    }
+
+    /// Every transaction passed to a send method, including failed sends.
+    pub fn sent_transactions(&self) -> Vec<VersionedTransaction> {
+        self.state().sent.clone()
+    }
+
+    /// Whether every queued send and simulation outcome has been used.
+    pub fn expectations_met(&self) -> bool {
+        let state = self.state();
+        state.send_results.is_empty() && state.simulations.is_empty()
+    }
+
+    fn send(
+        &self,
+        transaction: VersionedTransaction,
+        confirm: bool,
+    ) -> Result<Signature, ConnectionError> {
+        let mut state = self.state();
+        let signature = transaction.signatures.first().copied().unwrap_or_default();
+        state.calls.push(MockCall::Send { signature, confirm });
+        state.sent.push(transaction);
+        state
+            .send_results
+            .pop_front()
+            .unwrap_or(Ok(()))
+            .map(|()| signature)
+    }
+}
+
+#[async_trait]
+impl Connection for MockConnection {
+    async fn send_transaction(
+        &self,
+        transaction: &Transaction,
+        _config: SendConfig,
+    ) -> Result<Signature, ConnectionError> {
+        self.send(transaction.clone().into(), false)
+    }
+
+    async fn send_and_confirm(
+        &self,
+        transaction: &Transaction,
+        _config: SendConfig,
+    ) -> Result<Signature, ConnectionError> {
+        self.send(transaction.clone().into(), true)
+    }
+
+    async fn send_versioned_transaction(
+        &self,
+        transaction: &VersionedTransaction,
+        _config: SendConfig,
+    ) -> Result<Signature, ConnectionError> {
+        self.send(transaction.clone(), false)
+    }
+
+    async fn send_and_confirm_versioned_transaction(
+        &self,
+        transaction: &VersionedTransaction,
+        _config: SendConfig,
+    ) -> Result<Signature, ConnectionError> {
+        self.send(transaction.clone(), true)
+    }
+
+    async fn get_signature_statuses(
+        &self,
+        signatures: &[Signature],
+    ) -> Result<Vec<Option<SignatureStatus>>, ConnectionError> {
+        let mut state = self.state();
+        state
+            .calls
+            .push(MockCall::GetSignatureStatuses(signatures.to_vec()));
+        Ok(signatures
+            .iter()
+            .map(|signature| {
+                let statuses = state.signature_statuses.get_mut(signature)?;
+                if statuses.len() > 1 {
+                    statuses.pop_front()
+                } else {
+                    statuses.front().cloned()
+                }
+            })
+            .collect())
+    }
+
+    async fn get_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        before: Option<&Signature>,
+    ) -> Result<Vec<SignatureInfo>, ConnectionError> {
+        let mut state = self.state();
+        state
+            .calls
+            .push(MockCall::GetSignaturesForAddress(*address));
+        let history = state.signature_history.get(address).map_or(&[][..], |h| h);
+        let start = match before {
+            Some(before) => history
+                .iter()
+                .position(|entry| entry.signature == *before)
+                .map_or(history.len(), |i| i + 1),
+            None => 0,
+        };
+        let end = state
+            .signature_page_size
+            .map_or(history.len(), |size| (start + size).min(history.len()));
+        Ok(history[start..end].to_vec())
+    }
+
+    async fn simulate_versioned_transaction(
+        &self,
+        _transaction: &VersionedTransaction,
+    ) -> Result<SimulationResult, ConnectionError> {
+        let mut state = self.state();
+        state.calls.push(MockCall::Simulate);
+        state
+            .simulations
+            .pop_front()
+            .unwrap_or_else(|| Ok(SimulationResult::default()))
+    }
+
+    async fn get_latest_blockhash(&self) -> Result<Hash, ConnectionError> {
+        let mut state = self.state();
+        state.calls.push(MockCall::GetLatestBlockhash);
+        if let Some(blockhash) = state.blockhashes.pop_front() {
+            state.blockhash = blockhash;
+        }
+        Ok(state.blockhash)
+    }
+
+    async fn get_balance(&self, pubkey: &Pubkey) -> Result<u64, ConnectionError> {
+        let mut state = self.state();
+        state.calls.push(MockCall::GetBalance(*pubkey));
+        Ok(state
+            .balances
+            .get(pubkey)
+            .copied()
+            .or_else(|| state.accounts.get(pubkey).map(|a| a.lamports))
+            .unwrap_or(0))
+    }
+
+    async fn get_account(&self, pubkey: &Pubkey) -> Result<Option<Account>, ConnectionError> {
+        let mut state = self.state();
+        state.calls.push(MockCall::GetAccount(*pubkey));
+        Ok(state.accounts.get(pubkey).cloned())
+    }
+
+    async fn get_multiple_accounts(
+        &self,
+        pubkeys: &[Pubkey],
+    ) -> Result<Vec<Option<Account>>, ConnectionError> {
+        let mut state = self.state();
+        state
+            .calls
+            .push(MockCall::GetMultipleAccounts(pubkeys.to_vec()));
+        Ok(pubkeys
+            .iter()
+            .map(|pubkey| state.accounts.get(pubkey).cloned())
+            .collect())
+    }
+
+    async fn get_program_accounts(
+        &self,
+        program_id: &Pubkey,
+        filters: &[AccountFilter],
+    ) -> Result<Vec<(Pubkey, Account)>, ConnectionError> {
+        let mut state = self.state();
+        state.calls.push(MockCall::GetProgramAccounts(*program_id));
+        let mut accounts: Vec<_> = state
+            .accounts
+            .iter()
+            .filter(|(_, account)| {
+                account.owner == *program_id
+                    && filters.iter().all(|filter| filter.matches(&account.data))
+            })
+            .map(|(pubkey, account)| (*pubkey, account.clone()))
+            .collect();
+        accounts.sort_by_key(|(pubkey, _)| *pubkey);
+        Ok(accounts)
+    }
+
+    async fn get_recent_prioritization_fees(
+        &self,
+        writable_accounts: &[Pubkey],
+    ) -> Result<Vec<PrioritizationFee>, ConnectionError> {
+        let mut state = self.state();
+        state.calls.push(MockCall::GetRecentPrioritizationFees(
+            writable_accounts.to_vec(),
+        ));
+        Ok(state.prioritization_fees.clone())
+    }
+}
+
+/// A [`TransactionSigner`] and [`MessageSigner`] over an in-memory keypair.
+///
+/// Clones share the keypair, so a clone can be handed to a transport while
+/// the test keeps the original to check signatures.
+///
+/// Requires the `mock` feature outside this crate's own tests.
+#[derive(Clone)]
+pub struct MockSigner(Arc<Keypair>);
+
+impl MockSigner {
+    /// Create a signer with a new random keypair.
+    pub fn new() -> Self {
+        Self::from_keypair(Keypair::new())
+    }
+
+    /// Create a signer for `keypair`.
+    pub fn from_keypair(keypair: Keypair) -> Self {
+        Self(Arc::new(keypair))
+    }
+
+    /// Get the keypair.
+    pub fn keypair(&self) -> &Keypair {
+        &self.0
+    }
+}
+
+impl Default for MockSigner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TransactionSigner for MockSigner {
+    fn pubkey(&self) -> Pubkey {
+        self.0.pubkey()
+    }
+
+    fn sign_transaction(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        Ok(self.0.sign_message(message))
+    }
+}
+
+impl MessageSigner for MockSigner {
+    fn pubkey(&self) -> Pubkey {
+        self.0.pubkey()
+    }
+
+    fn sign_message(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        Ok(self.0.sign_message(message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::message::Message;
+
+    #[tokio::test]
+    async fn test_queries() {
+        let funded = Pubkey::new_unique();
+        let program = Pubkey::new_unique();
+        let account = Account {
+            lamports: 42,
+            data: vec![1, 2, 3],
+            owner: Pubkey::new_unique(),
+            executable: false,
+            rent_epoch: 0,
+        };
+        let (first, second) = (Hash::new_unique(), Hash::new_unique());
+
+        let connection = MockConnection::new()
+            .with_balance(funded, 1_000)
+            .with_account(program, account.clone())
+            .with_blockhashes([first, second]);
+
+        assert_eq!(connection.get_balance(&funded).await.unwrap(), 1_000);
+        assert_eq!(connection.get_balance(&program).await.unwrap(), 42);
+        assert_eq!(
+            connection.get_balance(&Pubkey::new_unique()).await.unwrap(),
+            0
+        );
+        assert_eq!(
+            connection.get_account(&program).await.unwrap(),
+            Some(account)
+        );
+        assert_eq!(connection.get_latest_blockhash().await.unwrap(), first);
+        assert_eq!(connection.get_latest_blockhash().await.unwrap(), second);
+        assert_eq!(connection.get_latest_blockhash().await.unwrap(), second);
+
+        assert_eq!(connection.calls()[0], MockCall::GetBalance(funded));
+        assert_eq!(
+            connection.count(|c| matches!(c, MockCall::GetLatestBlockhash)),
+            3
+        );
+    }
+
+    #[tokio::test]
+    async fn test_account_queries() {
+        use crate::accounts::TOKEN_PROGRAM_ID;
+
+        let mint = Pubkey::new_unique();
+        let mut mint_data = vec![0u8; 82];
+        mint_data[44] = 9;
+        let mut token_data = vec![0u8; 165];
+        token_data[0..32].copy_from_slice(mint.as_ref());
+        token_data[64..72].copy_from_slice(&2_500_000_000u64.to_le_bytes());
+        let token = Pubkey::new_unique();
+        let account = |data: Vec<u8>| Account {
+            lamports: 1,
+            data,
+            owner: TOKEN_PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+
+        let connection = MockConnection::new()
+            .with_account(mint, account(mint_data))
+            .with_account(token, account(token_data));
+
+        let missing = Pubkey::new_unique();
+        let accounts = connection
+            .get_multiple_accounts(&[token, missing, mint])
+            .await
+            .unwrap();
+        assert!(accounts[0].is_some() && accounts[1].is_none() && accounts[2].is_some());
+
+        let holders = connection
+            .get_program_accounts(
+                &TOKEN_PROGRAM_ID,
+                &[
+                    AccountFilter::DataSize(165),
+                    AccountFilter::pubkey_at(0, &mint),
+                ],
+            )
+            .await
+            .unwrap();
+        assert_eq!(holders.len(), 1);
+        assert_eq!(holders[0].0, token);
+
+        let balance = connection.get_token_account_balance(&token).await.unwrap();
+        assert_eq!(balance.amount, 2_500_000_000);
+        assert_eq!(balance.decimals, 9);
+        assert!(
+            connection
+                .get_token_account_balance(&missing)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_queued_sends() {
+        let payer = Pubkey::new_unique();
+        let tx = Transaction {
+            signatures: vec![Signature::new_unique()],
+            message: Message::new(&[], Some(&payer)),
+        };
+
+        let connection = MockConnection::new()
+            .expect_send(Err(ConnectionError::BlockhashExpired))
+            .expect_simulation(Ok(SimulationResult {
+                error: Some("custom program error: 0x1".to_string()),
+                ..Default::default()
+            }));
+        assert!(!connection.expectations_met());
+
+        assert!(matches!(
+            connection
+                .send_and_confirm(&tx, SendConfig::default())
+                .await,
+            Err(ConnectionError::BlockhashExpired)
+        ));
+        assert_eq!(
+            connection
+                .send_transaction(&tx, SendConfig::default())
+                .await
+                .unwrap(),
+            tx.signatures[0]
+        );
+        assert!(
+            !connection
+                .simulate_transaction(&tx)
+                .await
+                .unwrap()
+                .is_success()
+        );
+        assert!(connection.expectations_met());
+
+        assert_eq!(connection.sent_transactions().len(), 2);
+        assert_eq!(
+            connection.calls()[..2],
+            [
+                MockCall::Send {
+                    signature: tx.signatures[0],
+                    confirm: true
+                },
+                MockCall::Send {
+                    signature: tx.signatures[0],
+                    confirm: false
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_confirm_signature() {
+        use crate::confirmation::Commitment;
+
+        let status = |commitment, error: Option<&str>| SignatureStatus {
+            slot: 10,
+            confirmations: Some(1),
+            error: error.map(str::to_string),
+            commitment,
+        };
+        let (landed, failed, unseen) = (
+            Signature::new_unique(),
+            Signature::new_unique(),
+            Signature::new_unique(),
+        );
+
+        let connection = MockConnection::new()
+            .with_signature_statuses(
+                landed,
+                [
+                    status(Commitment::Processed, None),
+                    status(Commitment::Confirmed, None),
+                ],
+            )
+            .with_signature_statuses(
+                failed,
+                [status(Commitment::Processed, Some("custom program error"))],
+            );
+
+        let statuses = connection
+            .get_signature_statuses(&[unseen, failed])
+            .await
+            .unwrap();
+        assert!(statuses[0].is_none());
+        assert_eq!(
+            statuses[1].as_ref().unwrap().commitment,
+            Commitment::Processed
+        );
+
+        // Polls until the queued statuses reach the requested level
+        let confirmed = connection
+            .confirm_signature(&landed, Commitment::Confirmed)
+            .await
+            .unwrap();
+        assert_eq!(confirmed.commitment, Commitment::Confirmed);
+        assert_eq!(
+            connection.count(|c| *c == MockCall::GetSignatureStatuses(vec![landed])),
+            2
+        );
+
+        assert!(matches!(
+            connection
+                .confirm_signature(&failed, Commitment::Processed)
+                .await,
+            Err(ConnectionError::TransactionFailed(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_signature_history_pages() {
+        let address = Pubkey::new_unique();
+        let history: Vec<SignatureInfo> = (0..5)
+            .map(|slot| SignatureInfo {
+                signature: Signature::new_unique(),
+                slot,
+                error: None,
+                block_time: None,
+            })
+            .collect();
+        let connection = MockConnection::new()
+            .with_signature_history(address, history.clone())
+            .with_signature_page_size(2);
+
+        let first = connection
+            .get_signatures_for_address(&address, None)
+            .await
+            .unwrap();
+        assert_eq!(first, history[..2]);
+        let second = connection
+            .get_signatures_for_address(&address, Some(&first[1].signature))
+            .await
+            .unwrap();
+        assert_eq!(second, history[2..4]);
+        let last = connection
+            .get_signatures_for_address(&address, Some(&history[4].signature))
+            .await
+            .unwrap();
+        assert!(last.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_check_not_processed() {
+        use crate::advance_nonce_instruction;
+        use crate::confirmation::Commitment;
+
+        let payer = Pubkey::new_unique();
+        let transaction = |message: Message| -> VersionedTransaction {
+            Transaction {
+                signatures: vec![Signature::new_unique()],
+                message,
+            }
+            .into()
+        };
+        let (landed, failed, fresh) = (
+            transaction(Message::new(&[], Some(&payer))),
+            transaction(Message::new(&[], Some(&payer))),
+            transaction(Message::new(&[], Some(&payer))),
+        );
+        let status = |error: Option<&str>| SignatureStatus {
+            slot: 10,
+            confirmations: None,
+            error: error.map(str::to_string),
+            commitment: Commitment::Finalized,
+        };
+
+        let connection = MockConnection::new()
+            .with_signature_statuses(landed.signatures[0], [status(None)])
+            .with_signature_statuses(failed.signatures[0], [status(Some("custom program error"))]);
+
+        assert!(matches!(
+            connection.check_not_processed(&landed).await,
+            Err(ConnectionError::AlreadyProcessed(s)) if s == landed.signatures[0]
+        ));
+        assert!(matches!(
+            connection.check_not_processed(&failed).await,
+            Err(ConnectionError::TransactionFailed(_))
+        ));
+        assert!(connection.check_not_processed(&fresh).await.is_ok());
+
+        // A closed nonce account can no longer hold the signed nonce
+        let nonce_account = Pubkey::new_unique();
+        let nonce_tx = transaction(Message::new(
+            &[advance_nonce_instruction(&nonce_account, &payer)],
+            Some(&payer),
+        ));
+        assert!(matches!(
+            connection.check_not_processed(&nonce_tx).await,
+            Err(ConnectionError::NonceAdvanced(key)) if key == nonce_account
+        ));
+    }
+}
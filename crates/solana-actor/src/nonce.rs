@@ -0,0 +1,200 @@
+//! Durable nonce helpers.
+//!
+//! A transaction using a durable nonce takes its blockhash from a nonce
+//! account instead of a recent block, and begins with an `AdvanceNonceAccount`
+//! instruction signed by the nonce authority. It stays valid until the nonce
+//! is advanced, which makes offline and delayed signing (Ledger, Squads,
+//! air-gapped machines) reliable.
+
+use solana_sdk::{
+    account::Account, hash::Hash, instruction::Instruction, message::VersionedMessage,
+    pubkey::Pubkey,
+};
+
+use crate::error::ConnectionError;
+
+/// System program ID, the owner of nonce accounts.
+pub(crate) const SYSTEM_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("11111111111111111111111111111111");
+
+/// Size of a nonce account.
+pub const NONCE_ACCOUNT_SIZE: usize = 80;
+
+/// `State::Initialized` tag in a nonce account.
+const NONCE_INITIALIZED: u32 = 1;
+
+/// `SystemInstruction::AdvanceNonceAccount` discriminant.
+const ADVANCE_NONCE_ACCOUNT: u32 = 4;
+
+/// A durable nonce loaded from a nonce account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DurableNonce {
+    /// The nonce account address.
+    pub nonce_account: Pubkey,
+    /// The authority allowed to advance the nonce.
+    pub authority: Pubkey,
+    /// The stored nonce, used in place of a recent blockhash.
+    pub blockhash: Hash,
+    /// Fee per signature when the nonce was stored.
+    pub lamports_per_signature: u64,
+}
+
+impl DurableNonce {
+    /// The `AdvanceNonceAccount` instruction that must come first in a
+    /// transaction using this nonce.
+    pub fn advance_instruction(&self) -> Instruction {
+        advance_nonce_instruction(&self.nonce_account, &self.authority)
+    }
+}
+
+/// Build an `AdvanceNonceAccount` instruction.
+///
+/// # Arguments
+///
+/// * `nonce_account` - The nonce account address.
+/// * `authority` - The nonce authority, which must sign the transaction.
+pub fn advance_nonce_instruction(nonce_account: &Pubkey, authority: &Pubkey) -> Instruction {
+    solana_system_interface::instruction::advance_nonce_account(nonce_account, authority)
+}
+
+/// The nonce account a message takes its blockhash from.
+///
+/// Returns `None` unless the first instruction advances a nonce account, as
+/// it must in a transaction using a durable nonce.
+pub fn durable_nonce_account(message: &VersionedMessage) -> Option<Pubkey> {
+    let keys = message.static_account_keys();
+    let ix = message.instructions().first()?;
+    if *keys.get(ix.program_id_index as usize)? != SYSTEM_PROGRAM_ID
+        || ix.data.get(..4)? != ADVANCE_NONCE_ACCOUNT.to_le_bytes()
+    {
+        return None;
+    }
+    keys.get(*ix.accounts.first()? as usize).copied()
+}
+
+/// Parse a nonce account.
+///
+/// # Arguments
+///
+/// * `key` - The nonce account address.
+/// * `account` - The nonce account as fetched from the network.
+///
+/// # Errors
+///
+/// Returns [`ConnectionError::InvalidAccountData`] if the account is not an
+/// initialized nonce account owned by the system program.
+pub fn parse_nonce_account(
+    key: Pubkey,
+    account: &Account,
+) -> Result<DurableNonce, ConnectionError> {
+    let invalid =
+        |reason: &str| ConnectionError::InvalidAccountData(format!("{} is not {}", key, reason));
+
+    if account.owner != SYSTEM_PROGRAM_ID || account.data.len() != NONCE_ACCOUNT_SIZE {
+        return Err(invalid("a nonce account"));
+    }
+
+    // Layout: version (u32), state (u32), authority, durable nonce, fee calculator
+    let data = &account.data;
+    if data[4..8] != NONCE_INITIALIZED.to_le_bytes() {
+        return Err(invalid("an initialized nonce account"));
+    }
+
+    let mut authority = [0u8; 32];
+    authority.copy_from_slice(&data[8..40]);
+    let mut blockhash = [0u8; 32];
+    blockhash.copy_from_slice(&data[40..72]);
+    let mut lamports_per_signature = [0u8; 8];
+    lamports_per_signature.copy_from_slice(&data[72..80]);
+
+    Ok(DurableNonce {
+        nonce_account: key,
+        authority: Pubkey::new_from_array(authority),
+        blockhash: Hash::new_from_array(blockhash),
+        lamports_per_signature: u64::from_le_bytes(lamports_per_signature),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nonce_account(authority: &Pubkey, nonce: &Hash) -> Account {
+        let mut data = Vec::with_capacity(NONCE_ACCOUNT_SIZE);
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(&NONCE_INITIALIZED.to_le_bytes());
+        data.extend_from_slice(authority.as_ref());
+        data.extend_from_slice(nonce.as_ref());
+        data.extend_from_slice(&5000u64.to_le_bytes());
+        Account {
+            lamports: 1,
+            data,
+            owner: SYSTEM_PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    #[test]
+    fn test_parse_nonce_account() {
+        let key = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let nonce = Hash::new_unique();
+
+        let parsed = parse_nonce_account(key, &nonce_account(&authority, &nonce)).unwrap();
+        assert_eq!(parsed.nonce_account, key);
+        assert_eq!(parsed.authority, authority);
+        assert_eq!(parsed.blockhash, nonce);
+        assert_eq!(parsed.lamports_per_signature, 5000);
+    }
+
+    #[test]
+    fn test_parse_uninitialized_nonce_account() {
+        let mut account = nonce_account(&Pubkey::new_unique(), &Hash::new_unique());
+        account.data[4..8].copy_from_slice(&0u32.to_le_bytes());
+        assert!(parse_nonce_account(Pubkey::new_unique(), &account).is_err());
+
+        let mut account = nonce_account(&Pubkey::new_unique(), &Hash::new_unique());
+        account.owner = Pubkey::new_unique();
+        assert!(parse_nonce_account(Pubkey::new_unique(), &account).is_err());
+    }
+
+    #[test]
+    fn test_advance_instruction() {
+        let authority = Pubkey::new_unique();
+        let nonce = parse_nonce_account(
+            Pubkey::new_unique(),
+            &nonce_account(&authority, &Hash::new_unique()),
+        )
+        .unwrap();
+
+        let ix = nonce.advance_instruction();
+        assert_eq!(ix.program_id, SYSTEM_PROGRAM_ID);
+        assert_eq!(ix.accounts[0].pubkey, nonce.nonce_account);
+        assert!(ix.accounts[0].is_writable);
+        assert_eq!(ix.accounts[2].pubkey, authority);
+        assert!(ix.accounts[2].is_signer);
+    }
+
+    #[test]
+    fn test_durable_nonce_account() {
+        use solana_sdk::message::Message;
+
+        let payer = Pubkey::new_unique();
+        let nonce_key = Pubkey::new_unique();
+        let advance = advance_nonce_instruction(&nonce_key, &payer);
+        let message = Message::new(&[advance.clone()], Some(&payer));
+        assert_eq!(
+            durable_nonce_account(&VersionedMessage::Legacy(message)),
+            Some(nonce_key)
+        );
+
+        let transfer =
+            solana_system_interface::instruction::transfer(&payer, &Pubkey::new_unique(), 1);
+        let message = Message::new(&[transfer, advance], Some(&payer));
+        assert_eq!(
+            durable_nonce_account(&VersionedMessage::Legacy(message)),
+            None
+        );
+    }
+}
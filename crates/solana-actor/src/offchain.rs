@@ -0,0 +1,362 @@
+//! Solana off-chain messages.
+//!
+//! [`OffchainMessage`] implements the standard envelope for signing text that
+//! is not a transaction: a `\xffsolana offchain` signing domain, a header
+//! version, the message format and length, then the message itself. The
+//! leading `0xff` byte can never start a serialized transaction message, so a
+//! signature over an off-chain message can't be replayed as a transaction
+//! signature. Ledger's Solana app only signs messages in this format.
+//!
+//! ```text
+//! ┌──────────────────────┬─────────┬────────┬────────────┬─────────┐
+//! │ "\xffsolana offchain"│ version │ format │ length (LE)│ message │
+//! │       16 bytes       │ 1 byte  │ 1 byte │  2 bytes   │         │
+//! └──────────────────────┴─────────┴────────┴────────────┴─────────┘
+//! ```
+
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+use crate::error::OffchainMessageError;
+use crate::preflight::PACKET_DATA_SIZE;
+use crate::signer::MessageSigner;
+
+/// How the message bytes are encoded, which limits where they can be signed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OffchainMessageFormat {
+    /// Printable ASCII up to [`OffchainMessage::MAX_LEN_LEDGER`] bytes.
+    RestrictedAscii,
+    /// UTF-8 up to [`OffchainMessage::MAX_LEN_LEDGER`] bytes.
+    LimitedUtf8,
+    /// UTF-8 up to [`OffchainMessage::MAX_LEN`] bytes; not signable on Ledger.
+    ExtendedUtf8,
+}
+
+impl OffchainMessageFormat {
+    /// The most restrictive format that fits `message`.
+    fn detect(message: &[u8]) -> Result<Self, OffchainMessageError> {
+        if message.is_empty() {
+            return Err(OffchainMessageError::Empty);
+        }
+        if message.len() > OffchainMessage::MAX_LEN {
+            return Err(OffchainMessageError::TooLong {
+                len: message.len(),
+                max: OffchainMessage::MAX_LEN,
+            });
+        }
+        if std::str::from_utf8(message).is_err() {
+            return Err(OffchainMessageError::InvalidEncoding);
+        }
+
+        Ok(if message.len() > OffchainMessage::MAX_LEN_LEDGER {
+            Self::ExtendedUtf8
+        } else if is_printable_ascii(message) {
+            Self::RestrictedAscii
+        } else {
+            Self::LimitedUtf8
+        })
+    }
+
+    /// Whether `message` may be sent in this format.
+    fn allows(self, message: &[u8]) -> bool {
+        let utf8 = std::str::from_utf8(message).is_ok();
+        match self {
+            Self::RestrictedAscii => {
+                message.len() <= OffchainMessage::MAX_LEN_LEDGER && is_printable_ascii(message)
+            }
+            Self::LimitedUtf8 => message.len() <= OffchainMessage::MAX_LEN_LEDGER && utf8,
+            Self::ExtendedUtf8 => message.len() <= OffchainMessage::MAX_LEN && utf8,
+        }
+    }
+}
+
+impl TryFrom<u8> for OffchainMessageFormat {
+    type Error = OffchainMessageError;
+
+    fn try_from(byte: u8) -> Result<Self, Self::Error> {
+        match byte {
+            0 => Ok(Self::RestrictedAscii),
+            1 => Ok(Self::LimitedUtf8),
+            2 => Ok(Self::ExtendedUtf8),
+            _ => Err(OffchainMessageError::InvalidHeader(format!(
+                "unknown message format {}",
+                byte
+            ))),
+        }
+    }
+}
+
+impl From<OffchainMessageFormat> for u8 {
+    fn from(format: OffchainMessageFormat) -> Self {
+        match format {
+            OffchainMessageFormat::RestrictedAscii => 0,
+            OffchainMessageFormat::LimitedUtf8 => 1,
+            OffchainMessageFormat::ExtendedUtf8 => 2,
+        }
+    }
+}
+
+/// A version 0 off-chain message.
+///
+/// Always holds a non-empty UTF-8 message in a format that fits it.
+///
+/// # Example
+///
+/// ```ignore
+/// use solana_actor::OffchainMessage;
+///
+/// let message = OffchainMessage::new("Transfer ownership of example.sol")?;
+/// let signature = message.sign(&signer)?;
+/// assert!(message.verify(&signer.pubkey(), &signature));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OffchainMessage {
+    format: OffchainMessageFormat,
+    message: Vec<u8>,
+}
+
+impl OffchainMessage {
+    /// Prefix of every serialized off-chain message.
+    pub const SIGNING_DOMAIN: &'static [u8] = b"\xffsolana offchain";
+    /// Header version written by [`serialize`](Self::serialize).
+    pub const VERSION: u8 = 0;
+    /// Serialized header length: signing domain, version, format and length.
+    pub const HEADER_LEN: usize = Self::SIGNING_DOMAIN.len() + 4;
+    /// Longest message that fits the header's length field.
+    pub const MAX_LEN: usize = u16::MAX as usize - Self::HEADER_LEN;
+    /// Longest message a Ledger device can sign.
+    pub const MAX_LEN_LEDGER: usize = PACKET_DATA_SIZE - Self::HEADER_LEN;
+
+    /// Wrap a message, choosing the most restrictive format that fits it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OffchainMessageError`] if the message is empty, too long, or
+    /// not UTF-8.
+    pub fn new(message: impl AsRef<[u8]>) -> Result<Self, OffchainMessageError> {
+        let message = message.as_ref();
+        Ok(Self {
+            format: OffchainMessageFormat::detect(message)?,
+            message: message.to_vec(),
+        })
+    }
+
+    /// The message's format.
+    pub fn format(&self) -> OffchainMessageFormat {
+        self.format
+    }
+
+    /// The message bytes, without the header.
+    pub fn message(&self) -> &[u8] {
+        &self.message
+    }
+
+    /// The message as text.
+    pub fn text(&self) -> &str {
+        // Every format is valid UTF-8, checked on construction
+        std::str::from_utf8(&self.message).unwrap_or_default()
+    }
+
+    /// Whether the message can be signed on a Ledger device.
+    pub fn fits_ledger(&self) -> bool {
+        self.format != OffchainMessageFormat::ExtendedUtf8
+    }
+
+    /// Serialize the message with its full header; these are the signed bytes.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(Self::HEADER_LEN + self.message.len());
+        data.extend_from_slice(Self::SIGNING_DOMAIN);
+        data.push(Self::VERSION);
+        data.push(self.format.into());
+        data.extend_from_slice(&(self.message.len() as u16).to_le_bytes());
+        data.extend_from_slice(&self.message);
+        data
+    }
+
+    /// Parse a serialized message, checking its header and format.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OffchainMessageError`] if the bytes are not a well-formed
+    /// version 0 off-chain message.
+    pub fn deserialize(data: &[u8]) -> Result<Self, OffchainMessageError> {
+        let Some(rest) = data.strip_prefix(Self::SIGNING_DOMAIN) else {
+            return Err(OffchainMessageError::InvalidHeader(
+                "missing signing domain".to_string(),
+            ));
+        };
+        let [version, format, len_lo, len_hi, message @ ..] = rest else {
+            return Err(OffchainMessageError::InvalidHeader(
+                "header too short".to_string(),
+            ));
+        };
+        if *version != Self::VERSION {
+            return Err(OffchainMessageError::UnsupportedVersion(*version));
+        }
+        let format = OffchainMessageFormat::try_from(*format)?;
+        let len = usize::from(u16::from_le_bytes([*len_lo, *len_hi]));
+        if len != message.len() {
+            return Err(OffchainMessageError::InvalidHeader(format!(
+                "length {} does not match {} message bytes",
+                len,
+                message.len()
+            )));
+        }
+        if message.is_empty() {
+            return Err(OffchainMessageError::Empty);
+        }
+        if !format.allows(message) {
+            return Err(OffchainMessageError::InvalidEncoding);
+        }
+
+        Ok(Self {
+            format,
+            message: message.to_vec(),
+        })
+    }
+
+    /// Whether `data` starts with the off-chain signing domain.
+    ///
+    /// Signers that accept both transactions and off-chain messages use this
+    /// to tell them apart, since no transaction message starts with `0xff`.
+    pub fn is_offchain_message(data: &[u8]) -> bool {
+        data.starts_with(Self::SIGNING_DOMAIN)
+    }
+
+    /// Sign the serialized message.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OffchainMessageError::Signer`] if signing fails. Ledger
+    /// devices refuse messages that don't [`fit_ledger`](Self::fits_ledger).
+    pub fn sign<S: MessageSigner + ?Sized>(
+        &self,
+        signer: &S,
+    ) -> Result<Signature, OffchainMessageError> {
+        Ok(signer.sign_message(&self.serialize())?)
+    }
+
+    /// Whether `signature` is `pubkey`'s signature over the serialized message.
+    pub fn verify(&self, pubkey: &Pubkey, signature: &Signature) -> bool {
+        signature.verify(pubkey.as_ref(), &self.serialize())
+    }
+}
+
+/// Parse a serialized off-chain message and check its signature.
+///
+/// # Errors
+///
+/// Returns [`OffchainMessageError::InvalidSignature`] if `signature` is not
+/// `pubkey`'s, or another [`OffchainMessageError`] if `data` is malformed.
+pub fn verify_offchain_message(
+    data: &[u8],
+    pubkey: &Pubkey,
+    signature: &Signature,
+) -> Result<OffchainMessage, OffchainMessageError> {
+    let message = OffchainMessage::deserialize(data)?;
+    if !signature.verify(pubkey.as_ref(), data) {
+        return Err(OffchainMessageError::InvalidSignature);
+    }
+    Ok(message)
+}
+
+fn is_printable_ascii(data: &[u8]) -> bool {
+    data.iter().all(|b| (0x20..=0x7e).contains(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::SignerError;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    struct TestSigner(Keypair);
+
+    impl MessageSigner for TestSigner {
+        fn pubkey(&self) -> Pubkey {
+            self.0.pubkey()
+        }
+
+        fn sign_message(&self, message: &[u8]) -> Result<Signature, SignerError> {
+            Ok(self.0.sign_message(message))
+        }
+    }
+
+    #[test]
+    fn test_header_constants() {
+        assert_eq!(OffchainMessage::HEADER_LEN, 20);
+        assert_eq!(OffchainMessage::MAX_LEN, 65515);
+        assert_eq!(OffchainMessage::MAX_LEN_LEDGER, 1212);
+    }
+
+    #[test]
+    fn test_format_detection() {
+        let ascii = OffchainMessage::new("Hello, world!").unwrap();
+        assert_eq!(ascii.format(), OffchainMessageFormat::RestrictedAscii);
+
+        let utf8 = OffchainMessage::new("Olá, mundo\n").unwrap();
+        assert_eq!(utf8.format(), OffchainMessageFormat::LimitedUtf8);
+
+        let long = OffchainMessage::new("a".repeat(OffchainMessage::MAX_LEN_LEDGER + 1)).unwrap();
+        assert_eq!(long.format(), OffchainMessageFormat::ExtendedUtf8);
+        assert!(!long.fits_ledger());
+
+        assert!(matches!(
+            OffchainMessage::new(""),
+            Err(OffchainMessageError::Empty)
+        ));
+        assert!(matches!(
+            OffchainMessage::new([0xc3, 0x28]),
+            Err(OffchainMessageError::InvalidEncoding)
+        ));
+        assert!(matches!(
+            OffchainMessage::new("a".repeat(OffchainMessage::MAX_LEN + 1)),
+            Err(OffchainMessageError::TooLong { .. })
+        ));
+    }
+
+    #[test]
+    fn test_serialize_round_trip() {
+        let message = OffchainMessage::new("Hi").unwrap();
+        let bytes = message.serialize();
+        assert_eq!(&bytes[..16], b"\xffsolana offchain");
+        assert_eq!(&bytes[16..], &[0, 0, 2, 0, b'H', b'i']);
+        assert!(OffchainMessage::is_offchain_message(&bytes));
+        assert_eq!(OffchainMessage::deserialize(&bytes).unwrap(), message);
+
+        // Wrong length, version and format
+        let mut bad = bytes.clone();
+        bad[18] = 3;
+        assert!(OffchainMessage::deserialize(&bad).is_err());
+        let mut bad = bytes.clone();
+        bad[16] = 1;
+        assert!(matches!(
+            OffchainMessage::deserialize(&bad),
+            Err(OffchainMessageError::UnsupportedVersion(1))
+        ));
+        let mut bad = OffchainMessage::new("Olá").unwrap().serialize();
+        bad[17] = 0;
+        assert!(matches!(
+            OffchainMessage::deserialize(&bad),
+            Err(OffchainMessageError::InvalidEncoding)
+        ));
+        assert!(!OffchainMessage::is_offchain_message(b"Hi"));
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let signer = TestSigner(Keypair::new());
+        let message = OffchainMessage::new("Sign me").unwrap();
+        let signature = message.sign(&signer).unwrap();
+
+        assert!(message.verify(&signer.pubkey(), &signature));
+        assert!(!message.verify(&Pubkey::new_unique(), &signature));
+
+        let bytes = message.serialize();
+        let verified = verify_offchain_message(&bytes, &signer.pubkey(), &signature).unwrap();
+        assert_eq!(verified.text(), "Sign me");
+        assert!(matches!(
+            verify_offchain_message(&bytes, &Pubkey::new_unique(), &signature),
+            Err(OffchainMessageError::InvalidSignature)
+        ));
+    }
+}
@@ -0,0 +1,640 @@
+//! Offline signing through exported requests.
+//!
+//! [`OfflineTransport`] doesn't sign or send anything. Submitting stores the
+//! unsigned message and returns [`SubmitResult::AwaitingSignatures`]; the
+//! message is then exported as an [`OfflineRequest`] (a JSON file or a QR
+//! payload) listing the required signers and the blockhash or durable nonce
+//! it was built against. On the air-gapped machine, [`OfflineRequest::sign`]
+//! produces [`OfflineSignatures`], which travel back the same way and are
+//! merged with [`OfflineTransport::apply_signatures`].
+//!
+//! Recent blockhashes expire after about a minute, so requests that cross an
+//! air gap should use a durable nonce (see
+//! [`TransactionBuilder::durable_nonce`](crate::TransactionBuilder::durable_nonce)).
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use base64::Engine as _;
+use base64::engine::general_purpose::{STANDARD as BASE64, URL_SAFE_NO_PAD as BASE64_URL};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use solana_sdk::{
+    hash::Hash, message::VersionedMessage, pubkey::Pubkey, signature::Signature,
+    transaction::VersionedTransaction,
+};
+
+use crate::error::{OfflineError, SignerError, TransportError};
+use crate::nonce::SYSTEM_PROGRAM_ID;
+use crate::partial::PartiallySignedTransaction;
+use crate::signer::TransactionSigner;
+use crate::transport::{SubmitResult, WalletTransport};
+
+/// Version of the JSON and QR formats.
+pub const OFFLINE_FORMAT_VERSION: u8 = 1;
+
+/// Prefix of QR payloads, followed by URL-safe base64 of the JSON form.
+pub const OFFLINE_QR_PREFIX: &str = "solana-offline:";
+
+/// How often [`OfflineTransport::wait_for_completion`] checks for signatures.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// `SystemInstruction::AdvanceNonceAccount` discriminant.
+const ADVANCE_NONCE_ACCOUNT: u32 = 4;
+
+/// The durable nonce a message is built against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OfflineNonce {
+    /// The nonce account address.
+    pub nonce_account: Pubkey,
+    /// The authority that must sign to advance the nonce.
+    pub authority: Pubkey,
+}
+
+/// An unsigned transaction message exported for signing elsewhere.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OfflineRequest {
+    message: VersionedMessage,
+}
+
+impl OfflineRequest {
+    /// Wrap a message for export.
+    pub fn new(message: VersionedMessage) -> Self {
+        Self { message }
+    }
+
+    /// The message to sign.
+    pub fn message(&self) -> &VersionedMessage {
+        &self.message
+    }
+
+    /// Identifies the message: SHA-256 of its serialized bytes.
+    pub fn message_id(&self) -> Hash {
+        message_id(&self.message.serialize())
+    }
+
+    /// Public keys that must sign, in signature order.
+    pub fn signers(&self) -> &[Pubkey] {
+        let keys = self.message.static_account_keys();
+        let num_signers = self.message.header().num_required_signatures as usize;
+        &keys[..num_signers.min(keys.len())]
+    }
+
+    /// The recent blockhash, or the stored nonce for durable nonce messages.
+    pub fn recent_blockhash(&self) -> Hash {
+        *self.message.recent_blockhash()
+    }
+
+    /// The durable nonce the message uses, if its first instruction advances
+    /// one.
+    pub fn nonce(&self) -> Option<OfflineNonce> {
+        let keys = self.message.static_account_keys();
+        let ix = self.message.instructions().first()?;
+        if keys.get(ix.program_id_index as usize) != Some(&SYSTEM_PROGRAM_ID)
+            || ix.data.get(..4) != Some(&ADVANCE_NONCE_ACCOUNT.to_le_bytes())
+        {
+            return None;
+        }
+        Some(OfflineNonce {
+            nonce_account: *keys.get(*ix.accounts.first()? as usize)?,
+            authority: *keys.get(*ix.accounts.get(2)? as usize)?,
+        })
+    }
+
+    /// Sign the message with a required signer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OfflineError::UnexpectedSigner`] if the signer is not
+    /// required by the message, or [`OfflineError::Signer`] if signing fails.
+    pub fn sign<S: TransactionSigner + ?Sized>(
+        &self,
+        signer: &S,
+    ) -> Result<OfflineSignatures, OfflineError> {
+        let pubkey = signer.pubkey();
+        if !self.signers().contains(&pubkey) {
+            return Err(OfflineError::UnexpectedSigner(pubkey));
+        }
+        let signature = signer.sign_transaction(&self.message.serialize())?;
+        Ok(OfflineSignatures {
+            message_id: self.message_id(),
+            signatures: vec![(pubkey, signature)],
+        })
+    }
+
+    /// Encode as JSON, for a file.
+    pub fn to_json(&self) -> String {
+        let file = RequestFile {
+            version: OFFLINE_FORMAT_VERSION,
+            message_id: self.message_id().to_string(),
+            message: BASE64.encode(self.message.serialize()),
+            signers: self.signers().iter().map(ToString::to_string).collect(),
+            recent_blockhash: self.recent_blockhash().to_string(),
+            nonce: self.nonce().map(|nonce| NonceFile {
+                nonce_account: nonce.nonce_account.to_string(),
+                authority: nonce.authority.to_string(),
+            }),
+        };
+        serde_json::to_string_pretty(&file).expect("request serializes")
+    }
+
+    /// Decode from [`to_json`](Self::to_json) output.
+    ///
+    /// The listed signers, blockhash and nonce are checked against the
+    /// message.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OfflineError::InvalidPayload`] if the JSON or message is
+    /// malformed or its details don't match the message, or
+    /// [`OfflineError::UnsupportedVersion`] for other format versions.
+    pub fn from_json(json: &str) -> Result<Self, OfflineError> {
+        let file: RequestFile = serde_json::from_str(json).map_err(invalid)?;
+        check_version(file.version)?;
+
+        let bytes = BASE64.decode(&file.message).map_err(invalid)?;
+        let message: VersionedMessage = bincode::deserialize(&bytes).map_err(invalid)?;
+        let request = Self::new(message);
+
+        let signers: Vec<String> = request.signers().iter().map(ToString::to_string).collect();
+        let nonce = request.nonce().map(|nonce| NonceFile {
+            nonce_account: nonce.nonce_account.to_string(),
+            authority: nonce.authority.to_string(),
+        });
+        if file.message_id != request.message_id().to_string()
+            || file.signers != signers
+            || file.recent_blockhash != request.recent_blockhash().to_string()
+            || file.nonce != nonce
+        {
+            return Err(OfflineError::InvalidPayload(
+                "details don't match the message".to_string(),
+            ));
+        }
+
+        Ok(request)
+    }
+
+    /// Encode as a QR payload.
+    pub fn to_qr_payload(&self) -> String {
+        encode_qr(&self.to_json())
+    }
+
+    /// Decode from [`to_qr_payload`](Self::to_qr_payload) output.
+    ///
+    /// # Errors
+    ///
+    /// See [`from_json`](Self::from_json).
+    pub fn from_qr_payload(payload: &str) -> Result<Self, OfflineError> {
+        Self::from_json(&decode_qr(payload)?)
+    }
+}
+
+/// Signatures produced offline for an [`OfflineRequest`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OfflineSignatures {
+    /// The signed request's [`message_id`](OfflineRequest::message_id).
+    pub message_id: Hash,
+    /// Signatures by signer.
+    pub signatures: Vec<(Pubkey, Signature)>,
+}
+
+impl OfflineSignatures {
+    /// Add signatures made for the same request, e.g. by another cold wallet.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OfflineError::InvalidPayload`] if `other` is for a different
+    /// message.
+    pub fn merge(&mut self, other: OfflineSignatures) -> Result<(), OfflineError> {
+        if other.message_id != self.message_id {
+            return Err(OfflineError::InvalidPayload(format!(
+                "signatures are for {}, not {}",
+                other.message_id, self.message_id
+            )));
+        }
+        for (pubkey, signature) in other.signatures {
+            if !self.signatures.iter().any(|(key, _)| *key == pubkey) {
+                self.signatures.push((pubkey, signature));
+            }
+        }
+        Ok(())
+    }
+
+    /// Encode as JSON, for a file.
+    pub fn to_json(&self) -> String {
+        let file = SignaturesFile {
+            version: OFFLINE_FORMAT_VERSION,
+            message_id: self.message_id.to_string(),
+            signatures: self
+                .signatures
+                .iter()
+                .map(|(pubkey, signature)| SignatureFile {
+                    signer: pubkey.to_string(),
+                    signature: signature.to_string(),
+                })
+                .collect(),
+        };
+        serde_json::to_string_pretty(&file).expect("signatures serialize")
+    }
+
+    /// Decode from [`to_json`](Self::to_json) output.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OfflineError::InvalidPayload`] if the JSON is malformed, or
+    /// [`OfflineError::UnsupportedVersion`] for other format versions.
+    pub fn from_json(json: &str) -> Result<Self, OfflineError> {
+        let file: SignaturesFile = serde_json::from_str(json).map_err(invalid)?;
+        check_version(file.version)?;
+
+        let signatures = file
+            .signatures
+            .iter()
+            .map(|entry| {
+                Ok((
+                    entry.signer.parse().map_err(invalid)?,
+                    entry.signature.parse().map_err(invalid)?,
+                ))
+            })
+            .collect::<Result<_, OfflineError>>()?;
+
+        Ok(Self {
+            message_id: file.message_id.parse().map_err(invalid)?,
+            signatures,
+        })
+    }
+
+    /// Encode as a QR payload.
+    pub fn to_qr_payload(&self) -> String {
+        encode_qr(&self.to_json())
+    }
+
+    /// Decode from [`to_qr_payload`](Self::to_qr_payload) output.
+    ///
+    /// # Errors
+    ///
+    /// See [`from_json`](Self::from_json).
+    pub fn from_qr_payload(payload: &str) -> Result<Self, OfflineError> {
+        Self::from_json(&decode_qr(payload)?)
+    }
+}
+
+/// Transport that exports messages for signing on another machine.
+///
+/// `submit` returns [`SubmitResult::AwaitingSignatures`]. Export the request
+/// with [`export`](Self::export), and once the signatures come back, merge
+/// them with [`apply_signatures`](Self::apply_signatures). When every required
+/// signer has signed, the result becomes [`SubmitResult::Signed`] with the
+/// fee payer's signature, and [`transaction`](Self::transaction) returns the
+/// transaction ready to send.
+///
+/// # Example
+///
+/// ```ignore
+/// use solana_actor::{OfflineRequest, OfflineSignatures, OfflineTransport, WalletTransport};
+///
+/// // Online machine
+/// let transport = OfflineTransport::new(cold_pubkey);
+/// let result = transport.submit_versioned(&message).await?;
+/// std::fs::write("request.json", transport.export(&result).unwrap().to_json())?;
+///
+/// // Air-gapped machine
+/// let request = OfflineRequest::from_json(&std::fs::read_to_string("request.json")?)?;
+/// std::fs::write("signatures.json", request.sign(&cold_signer)?.to_json())?;
+///
+/// // Online machine
+/// let signatures = OfflineSignatures::from_json(&std::fs::read_to_string("signatures.json")?)?;
+/// let result = transport.apply_signatures(&signatures)?;
+/// let tx = transport.transaction(&request.message_id()).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct OfflineTransport {
+    authority: Pubkey,
+    requests: Mutex<HashMap<Hash, PartiallySignedTransaction>>,
+}
+
+/// Where a request stands: signed once every required signer has signed.
+fn request_status(pending: &PartiallySignedTransaction) -> SubmitResult {
+    let fee_payer = pending
+        .signers()
+        .first()
+        .and_then(|key| pending.signature(key));
+    match fee_payer {
+        Some(fee_payer) if pending.is_complete() => SubmitResult::Signed(fee_payer),
+        _ => SubmitResult::AwaitingSignatures {
+            message_id: message_id(&pending.message().serialize()),
+            missing: pending.missing_signers(),
+        },
+    }
+}
+
+impl OfflineTransport {
+    /// Create a transport for the offline signer `authority`.
+    pub fn new(authority: Pubkey) -> Self {
+        Self {
+            authority,
+            requests: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<Hash, PartiallySignedTransaction>> {
+        self.requests.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Store a message for export, keeping signatures already applied if
+    /// the same message was submitted before.
+    fn queue(&self, message: VersionedMessage) -> SubmitResult {
+        let id = message_id(&message.serialize());
+        request_status(
+            self.lock()
+                .entry(id)
+                .or_insert_with(|| PartiallySignedTransaction::new(message)),
+        )
+    }
+
+    /// The request to export for a result returned by this transport.
+    pub fn export(&self, result: &SubmitResult) -> Option<OfflineRequest> {
+        let SubmitResult::AwaitingSignatures { message_id, .. } = result else {
+            return None;
+        };
+        self.lock()
+            .get(message_id)
+            .map(|pending| OfflineRequest::new(pending.message().clone()))
+    }
+
+    /// Merge signatures produced offline.
+    ///
+    /// Signatures are verified against the message before any is applied.
+    /// Returns [`SubmitResult::Signed`] once every required signer has signed,
+    /// and [`SubmitResult::AwaitingSignatures`] with the remaining signers
+    /// otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OfflineError::UnknownRequest`] if the request wasn't
+    /// submitted to this transport, or the errors of
+    /// [`PartiallySignedTransaction::add_signatures`].
+    pub fn apply_signatures(
+        &self,
+        signatures: &OfflineSignatures,
+    ) -> Result<SubmitResult, TransportError> {
+        let mut requests = self.lock();
+        let pending = requests
+            .get_mut(&signatures.message_id)
+            .ok_or(OfflineError::UnknownRequest(signatures.message_id))?;
+        pending.add_signatures(&signatures.signatures)?;
+        Ok(request_status(pending))
+    }
+
+    /// The signatures collected so far for a request.
+    pub fn partial(&self, message_id: &Hash) -> Option<PartiallySignedTransaction> {
+        self.lock().get(message_id).cloned()
+    }
+
+    /// The fully signed transaction for a request, once every signature has
+    /// been applied.
+    pub fn transaction(&self, message_id: &Hash) -> Option<VersionedTransaction> {
+        self.partial(message_id)?.into_transaction().ok()
+    }
+}
+
+#[async_trait]
+impl WalletTransport for OfflineTransport {
+    fn authority(&self) -> Pubkey {
+        self.authority
+    }
+
+    async fn submit(&self, message: &[u8]) -> Result<SubmitResult, TransportError> {
+        let message: VersionedMessage = bincode::deserialize(message)
+            .map_err(|e| SignerError::InvalidFormat(format!("Failed to parse message: {}", e)))?;
+        Ok(self.queue(message))
+    }
+
+    async fn submit_versioned(
+        &self,
+        message: &VersionedMessage,
+    ) -> Result<SubmitResult, TransportError> {
+        Ok(self.queue(message.clone()))
+    }
+
+    async fn check_status(&self, result: &SubmitResult) -> Result<SubmitResult, TransportError> {
+        let SubmitResult::AwaitingSignatures { message_id, .. } = result else {
+            return Ok(result.clone());
+        };
+        self.lock()
+            .get(message_id)
+            .map(request_status)
+            .ok_or_else(|| OfflineError::UnknownRequest(*message_id).into())
+    }
+
+    async fn wait_for_completion(
+        &self,
+        result: SubmitResult,
+        timeout: Duration,
+    ) -> Result<SubmitResult, TransportError> {
+        let deadline = Instant::now() + timeout;
+        let mut current = result;
+
+        loop {
+            current = self.check_status(&current).await?;
+            if current.is_complete() {
+                return Ok(current);
+            }
+            if Instant::now() >= deadline {
+                return Err(TransportError::Timeout);
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    fn requires_network(&self) -> bool {
+        false
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct RequestFile {
+    version: u8,
+    message_id: String,
+    /// Base64 encoded message
+    message: String,
+    signers: Vec<String>,
+    recent_blockhash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    nonce: Option<NonceFile>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq)]
+struct NonceFile {
+    nonce_account: String,
+    authority: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SignaturesFile {
+    version: u8,
+    message_id: String,
+    signatures: Vec<SignatureFile>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SignatureFile {
+    signer: String,
+    signature: String,
+}
+
+fn message_id(message: &[u8]) -> Hash {
+    Hash::new_from_array(Sha256::digest(message).into())
+}
+
+fn check_version(version: u8) -> Result<(), OfflineError> {
+    if version != OFFLINE_FORMAT_VERSION {
+        return Err(OfflineError::UnsupportedVersion(version));
+    }
+    Ok(())
+}
+
+fn encode_qr(json: &str) -> String {
+    format!("{}{}", OFFLINE_QR_PREFIX, BASE64_URL.encode(json))
+}
+
+fn decode_qr(payload: &str) -> Result<String, OfflineError> {
+    let encoded = payload
+        .trim()
+        .strip_prefix(OFFLINE_QR_PREFIX)
+        .ok_or_else(|| OfflineError::InvalidPayload("missing QR prefix".to_string()))?;
+    let bytes = BASE64_URL.decode(encoded).map_err(invalid)?;
+    String::from_utf8(bytes).map_err(invalid)
+}
+
+fn invalid(e: impl std::fmt::Display) -> OfflineError {
+    OfflineError::InvalidPayload(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockSigner;
+    use crate::nonce::advance_nonce_instruction;
+    use solana_sdk::{
+        instruction::{AccountMeta, Instruction},
+        message::Message,
+    };
+
+    fn message(payer: &Pubkey, authority: &Pubkey, nonce: Option<Pubkey>) -> VersionedMessage {
+        let mut instructions = Vec::new();
+        if let Some(nonce_account) = nonce {
+            instructions.push(advance_nonce_instruction(&nonce_account, payer));
+        }
+        instructions.push(Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[],
+            vec![AccountMeta::new_readonly(*authority, true)],
+        ));
+        VersionedMessage::Legacy(Message::new_with_blockhash(
+            &instructions,
+            Some(payer),
+            &Hash::new_unique(),
+        ))
+    }
+
+    #[test]
+    fn test_request_round_trip() {
+        let payer = Pubkey::new_unique();
+        let nonce_account = Pubkey::new_unique();
+        let request =
+            OfflineRequest::new(message(&payer, &Pubkey::new_unique(), Some(nonce_account)));
+
+        assert_eq!(request.signers().len(), 2);
+        assert_eq!(request.signers()[0], payer);
+        assert_eq!(
+            request.nonce(),
+            Some(OfflineNonce {
+                nonce_account,
+                authority: payer,
+            })
+        );
+
+        assert_eq!(
+            OfflineRequest::from_json(&request.to_json()).unwrap(),
+            request
+        );
+        let payload = request.to_qr_payload();
+        assert!(payload.starts_with(OFFLINE_QR_PREFIX));
+        assert_eq!(OfflineRequest::from_qr_payload(&payload).unwrap(), request);
+
+        // Listed details must match the message
+        let tampered = request
+            .to_json()
+            .replace(&payer.to_string(), &Pubkey::new_unique().to_string());
+        assert!(OfflineRequest::from_json(&tampered).is_err());
+        assert!(matches!(
+            OfflineRequest::from_json(
+                &request
+                    .to_json()
+                    .replace("\"version\": 1", "\"version\": 9")
+            ),
+            Err(OfflineError::UnsupportedVersion(9))
+        ));
+
+        let without_nonce = OfflineRequest::new(message(&payer, &payer, None));
+        assert_eq!(without_nonce.nonce(), None);
+        assert_eq!(without_nonce.signers(), [payer]);
+    }
+
+    #[tokio::test]
+    async fn test_sign_offline_and_apply() {
+        let payer = MockSigner::new();
+        let authority = MockSigner::new();
+        let transport = OfflineTransport::new(payer.pubkey());
+
+        let result = transport
+            .submit_versioned(&message(&payer.pubkey(), &authority.pubkey(), None))
+            .await
+            .unwrap();
+        assert!(result.is_pending());
+        assert!(!transport.requires_network());
+
+        // Each cold wallet signs its own copy of the request
+        let payload = transport.export(&result).unwrap().to_qr_payload();
+        let request = OfflineRequest::from_qr_payload(&payload).unwrap();
+        assert!(matches!(
+            request.sign(&MockSigner::new()),
+            Err(OfflineError::UnexpectedSigner(_))
+        ));
+
+        let payer_signatures = request.sign(&payer).unwrap();
+        let partial = transport
+            .apply_signatures(&OfflineSignatures::from_json(&payer_signatures.to_json()).unwrap())
+            .unwrap();
+        assert!(matches!(
+            &partial,
+            SubmitResult::AwaitingSignatures { missing, .. } if missing == &[authority.pubkey()]
+        ));
+        assert!(transport.transaction(&request.message_id()).is_none());
+
+        // Signatures over another message are refused
+        let mut forged = request.sign(&authority).unwrap();
+        forged.signatures[0].1 = payer_signatures.signatures[0].1;
+        assert!(transport.apply_signatures(&forged).is_err());
+
+        let mut signatures = request.sign(&authority).unwrap();
+        signatures.merge(payer_signatures).unwrap();
+        let signed = transport.apply_signatures(&signatures).unwrap();
+        assert!(signed.is_complete());
+        assert_eq!(
+            transport
+                .wait_for_completion(partial, Duration::ZERO)
+                .await
+                .unwrap()
+                .signature(),
+            signed.signature()
+        );
+
+        let tx = transport.transaction(&request.message_id()).unwrap();
+        assert_eq!(tx.signatures[0], *signed.signature().unwrap());
+        assert!(tx.verify_with_results().iter().all(|ok| *ok));
+    }
+}
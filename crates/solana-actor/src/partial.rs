@@ -0,0 +1,303 @@
+//! Partially signed transactions.
+//!
+//! This module provides [`PartiallySignedTransaction`], which tracks the
+//! signatures collected for a message whose required signers are spread
+//! across machines or people, such as a hot fee payer and an offline
+//! authority.
+
+use solana_sdk::{
+    message::VersionedMessage, pubkey::Pubkey, signature::Signature,
+    transaction::VersionedTransaction,
+};
+
+use crate::composite::required_signers;
+use crate::error::BuildError;
+use crate::signer::TransactionSigner;
+
+/// A versioned message with the signatures collected so far.
+///
+/// Every added signature is checked against the message, so a complete
+/// transaction always verifies. Signature sets gathered on different machines
+/// are combined with [`merge`](Self::merge).
+///
+/// # Example
+///
+/// ```ignore
+/// use solana_actor::PartiallySignedTransaction;
+///
+/// let mut tx = PartiallySignedTransaction::new(message);
+/// tx.sign(&fee_payer)?;
+///
+/// // Elsewhere, the authority signs its own copy
+/// let mut theirs = PartiallySignedTransaction::from_transaction(received);
+/// theirs.sign(&authority)?;
+///
+/// tx.merge(&theirs)?;
+/// assert!(tx.missing_signers().is_empty());
+/// let transaction = tx.into_transaction()?;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartiallySignedTransaction {
+    message: VersionedMessage,
+    /// Signatures in signer order; `None` until provided
+    signatures: Vec<Option<Signature>>,
+}
+
+impl PartiallySignedTransaction {
+    /// Start collecting signatures for `message`.
+    pub fn new(message: VersionedMessage) -> Self {
+        let signatures = vec![None; required_signers(&message).len()];
+        Self {
+            message,
+            signatures,
+        }
+    }
+
+    /// Collect the signatures already in a transaction.
+    ///
+    /// Default (all-zero) signatures and signatures that don't verify are
+    /// treated as missing.
+    pub fn from_transaction(transaction: VersionedTransaction) -> Self {
+        let mut partial = Self::new(transaction.message);
+        let message = partial.message.serialize();
+        for (slot, (pubkey, signature)) in partial.signatures.iter_mut().zip(
+            required_signers(&partial.message)
+                .iter()
+                .zip(&transaction.signatures),
+        ) {
+            if signature.verify(pubkey.as_ref(), &message) {
+                *slot = Some(*signature);
+            }
+        }
+        partial
+    }
+
+    /// The message being signed.
+    pub fn message(&self) -> &VersionedMessage {
+        &self.message
+    }
+
+    /// Public keys that must sign, in signature order.
+    pub fn signers(&self) -> &[Pubkey] {
+        required_signers(&self.message)
+    }
+
+    /// The signature collected for `pubkey`, if any.
+    pub fn signature(&self, pubkey: &Pubkey) -> Option<Signature> {
+        let position = self.position(pubkey)?;
+        self.signatures[position]
+    }
+
+    /// Required signers that have signed, in signature order.
+    pub fn signed_signers(&self) -> Vec<Pubkey> {
+        self.signers_where(|signature| signature.is_some())
+    }
+
+    /// Required signers that have not signed yet, in signature order.
+    pub fn missing_signers(&self) -> Vec<Pubkey> {
+        self.signers_where(|signature| signature.is_none())
+    }
+
+    /// Whether every required signer has signed.
+    pub fn is_complete(&self) -> bool {
+        self.signatures.iter().all(Option::is_some)
+    }
+
+    /// Add a signature made elsewhere.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BuildError::UnexpectedSigner`] if `pubkey` is not a required
+    /// signer, or [`BuildError::InvalidSignature`] if the signature doesn't
+    /// verify.
+    pub fn add_signature(
+        &mut self,
+        pubkey: &Pubkey,
+        signature: Signature,
+    ) -> Result<(), BuildError> {
+        self.add_signatures(&[(*pubkey, signature)])
+    }
+
+    /// Add several signatures made elsewhere.
+    ///
+    /// All of them are checked before any is added.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`add_signature`](Self::add_signature).
+    pub fn add_signatures(&mut self, signatures: &[(Pubkey, Signature)]) -> Result<(), BuildError> {
+        let message = self.message.serialize();
+        let mut verified = Vec::with_capacity(signatures.len());
+        for (pubkey, signature) in signatures {
+            let position = self
+                .position(pubkey)
+                .ok_or(BuildError::UnexpectedSigner(*pubkey))?;
+            if !signature.verify(pubkey.as_ref(), &message) {
+                return Err(BuildError::InvalidSignature(*pubkey));
+            }
+            verified.push((position, *signature));
+        }
+
+        for (position, signature) in verified {
+            self.signatures[position] = Some(signature);
+        }
+        Ok(())
+    }
+
+    /// Sign with a required signer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BuildError::UnexpectedSigner`] if the signer is not required,
+    /// or [`BuildError::Signer`] if signing fails.
+    pub fn sign<S: TransactionSigner + ?Sized>(&mut self, signer: &S) -> Result<(), BuildError> {
+        let pubkey = signer.pubkey();
+        let position = self
+            .position(&pubkey)
+            .ok_or(BuildError::UnexpectedSigner(pubkey))?;
+        self.signatures[position] = Some(signer.sign_transaction(&self.message.serialize())?);
+        Ok(())
+    }
+
+    /// Add the signatures another party collected for the same message.
+    ///
+    /// Signatures already present are kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BuildError::MessageMismatch`] if `other` is for a different
+    /// message.
+    pub fn merge(&mut self, other: &PartiallySignedTransaction) -> Result<(), BuildError> {
+        if other.message != self.message {
+            return Err(BuildError::MessageMismatch);
+        }
+        for (slot, signature) in self.signatures.iter_mut().zip(&other.signatures) {
+            if slot.is_none() {
+                *slot = *signature;
+            }
+        }
+        Ok(())
+    }
+
+    /// The transaction with default signatures in the missing slots, for
+    /// handing to the next signer.
+    pub fn to_transaction(&self) -> VersionedTransaction {
+        VersionedTransaction {
+            signatures: self
+                .signatures
+                .iter()
+                .map(|signature| signature.unwrap_or_default())
+                .collect(),
+            message: self.message.clone(),
+        }
+    }
+
+    /// The fully signed transaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BuildError::MissingSigners`] if any required signer hasn't
+    /// signed.
+    pub fn into_transaction(self) -> Result<VersionedTransaction, BuildError> {
+        let missing = self.missing_signers();
+        if !missing.is_empty() {
+            return Err(BuildError::MissingSigners(missing));
+        }
+        Ok(self.to_transaction())
+    }
+
+    fn position(&self, pubkey: &Pubkey) -> Option<usize> {
+        self.signers().iter().position(|key| key == pubkey)
+    }
+
+    fn signers_where(&self, keep: impl Fn(&Option<Signature>) -> bool) -> Vec<Pubkey> {
+        self.signers()
+            .iter()
+            .zip(&self.signatures)
+            .filter(|(_, signature)| keep(signature))
+            .map(|(pubkey, _)| *pubkey)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockSigner;
+    use solana_sdk::{
+        hash::Hash,
+        instruction::{AccountMeta, Instruction},
+        message::Message,
+    };
+
+    fn message(payer: &Pubkey, authority: &Pubkey) -> VersionedMessage {
+        let ix = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[],
+            vec![AccountMeta::new_readonly(*authority, true)],
+        );
+        VersionedMessage::Legacy(Message::new_with_blockhash(
+            &[ix],
+            Some(payer),
+            &Hash::new_unique(),
+        ))
+    }
+
+    #[test]
+    fn test_merge_signatures_from_two_machines() {
+        let payer = MockSigner::new();
+        let authority = MockSigner::new();
+        let message = message(&payer.pubkey(), &authority.pubkey());
+
+        let mut hot = PartiallySignedTransaction::new(message);
+        hot.sign(&payer).unwrap();
+        assert_eq!(hot.signed_signers(), [payer.pubkey()]);
+        assert_eq!(hot.missing_signers(), [authority.pubkey()]);
+        assert!(matches!(
+            hot.clone().into_transaction(),
+            Err(BuildError::MissingSigners(missing)) if missing == [authority.pubkey()]
+        ));
+
+        // The cold machine receives the transaction with the payer's signature
+        let mut cold = PartiallySignedTransaction::from_transaction(hot.to_transaction());
+        assert_eq!(cold.signed_signers(), [payer.pubkey()]);
+        cold.sign(&authority).unwrap();
+
+        hot.merge(&cold).unwrap();
+        assert!(hot.is_complete());
+        assert_eq!(
+            hot.signature(&authority.pubkey()),
+            cold.signature(&authority.pubkey())
+        );
+
+        let transaction = hot.into_transaction().unwrap();
+        assert!(transaction.verify_with_results().iter().all(|ok| *ok));
+    }
+
+    #[test]
+    fn test_rejects_bad_signatures() {
+        let payer = MockSigner::new();
+        let authority = MockSigner::new();
+        let mut tx = PartiallySignedTransaction::new(message(&payer.pubkey(), &authority.pubkey()));
+
+        let stranger = MockSigner::new();
+        assert!(matches!(
+            tx.sign(&stranger),
+            Err(BuildError::UnexpectedSigner(_))
+        ));
+
+        // A valid payer signature and a forged authority one: neither is added
+        let payer_signature = payer.sign_transaction(&tx.message().serialize()).unwrap();
+        assert!(matches!(
+            tx.add_signatures(&[
+                (payer.pubkey(), payer_signature),
+                (authority.pubkey(), payer_signature),
+            ]),
+            Err(BuildError::InvalidSignature(key)) if key == authority.pubkey()
+        ));
+        assert!(tx.signed_signers().is_empty());
+
+        let other = PartiallySignedTransaction::new(message(&payer.pubkey(), &authority.pubkey()));
+        assert!(matches!(tx.merge(&other), Err(BuildError::MessageMismatch)));
+    }
+}
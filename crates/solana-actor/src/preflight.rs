@@ -0,0 +1,167 @@
+//! Preflight checks on transactions before they are signed or sent.
+//!
+//! A transaction that is too large or lacks a signer fails at the RPC node
+//! with a generic error, after blockhashes have been fetched and hardware
+//! wallets have prompted. [`check_transaction_size`] and [`check_signers`]
+//! catch both locally and say what to change.
+
+use solana_sdk::{message::VersionedMessage, pubkey::Pubkey};
+
+use crate::error::BuildError;
+
+/// Maximum size of a serialized transaction, signatures included.
+pub const PACKET_DATA_SIZE: usize = 1232;
+
+/// Size of one signature in a serialized transaction.
+const SIGNATURE_LEN: usize = 64;
+
+/// Serialized size of a transaction for `message`, signatures included.
+pub fn transaction_size(message: &VersionedMessage) -> usize {
+    let num_signatures = usize::from(message.header().num_required_signatures);
+    short_vec_len(num_signatures) + num_signatures * SIGNATURE_LEN + message.serialize().len()
+}
+
+/// Check that a transaction for `message` fits in a packet.
+///
+/// # Errors
+///
+/// Returns [`BuildError::TransactionTooLarge`] with the size, the limit and a
+/// hint: a lookup table for legacy messages, splitting the transaction for
+/// v0 messages, which may already use one.
+pub fn check_transaction_size(message: &VersionedMessage) -> Result<(), BuildError> {
+    let size = transaction_size(message);
+    if size <= PACKET_DATA_SIZE {
+        return Ok(());
+    }
+
+    let hint = match message {
+        VersionedMessage::Legacy(_) => "consider using a lookup table",
+        VersionedMessage::V0(_) => "consider splitting it into several transactions",
+    };
+    Err(BuildError::TransactionTooLarge {
+        size,
+        limit: PACKET_DATA_SIZE,
+        hint,
+    })
+}
+
+/// Check that the `provided` signers are exactly the ones `message` requires.
+///
+/// # Errors
+///
+/// Returns [`BuildError::MissingSigners`] listing every required signer not
+/// provided, or [`BuildError::UnexpectedSigner`] for the first provided
+/// signer the message does not require.
+pub fn check_signers(message: &VersionedMessage, provided: &[Pubkey]) -> Result<(), BuildError> {
+    let required = required_signers(message);
+
+    let missing: Vec<Pubkey> = required
+        .iter()
+        .filter(|key| !provided.contains(key))
+        .copied()
+        .collect();
+    if !missing.is_empty() {
+        return Err(BuildError::MissingSigners(missing));
+    }
+
+    if let Some(extra) = provided.iter().find(|key| !required.contains(key)) {
+        return Err(BuildError::UnexpectedSigner(*extra));
+    }
+
+    Ok(())
+}
+
+/// The keys that must sign `message`, in signature order.
+fn required_signers(message: &VersionedMessage) -> &[Pubkey] {
+    let keys = message.static_account_keys();
+    let num_signatures = usize::from(message.header().num_required_signatures);
+    &keys[..num_signatures.min(keys.len())]
+}
+
+/// Length of a compact-u16 (short vec) length prefix.
+fn short_vec_len(len: usize) -> usize {
+    match len {
+        0..=0x7f => 1,
+        0x80..=0x3fff => 2,
+        _ => 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{
+        hash::Hash,
+        instruction::{AccountMeta, Instruction},
+        message::Message,
+        signature::Signature,
+        transaction::Transaction,
+    };
+
+    fn message(signers: &[Pubkey], data_len: usize) -> Message {
+        let instruction = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &vec![0; data_len],
+            signers
+                .iter()
+                .map(|key| AccountMeta::new_readonly(*key, true))
+                .collect(),
+        );
+        Message::new_with_blockhash(&[instruction], signers.first(), &Hash::new_unique())
+    }
+
+    #[test]
+    fn test_transaction_size_matches_serialized() {
+        let signers = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let message = message(&signers, 100);
+        let tx = Transaction {
+            signatures: vec![Signature::default(); 2],
+            message: message.clone(),
+        };
+
+        assert_eq!(
+            transaction_size(&VersionedMessage::Legacy(message)),
+            bincode::serialize(&tx).unwrap().len()
+        );
+    }
+
+    #[test]
+    fn test_rejects_oversized_transaction() {
+        let payer = Pubkey::new_unique();
+        assert!(check_transaction_size(&VersionedMessage::Legacy(message(&[payer], 900))).is_ok());
+
+        let oversized = VersionedMessage::Legacy(message(&[payer], 1200));
+        let err = check_transaction_size(&oversized).unwrap_err();
+        let size = transaction_size(&oversized);
+        assert!(matches!(
+            err,
+            BuildError::TransactionTooLarge { size: s, limit: PACKET_DATA_SIZE, .. } if s == size
+        ));
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Transaction is {} bytes, limit {}; consider using a lookup table",
+                size, PACKET_DATA_SIZE
+            )
+        );
+    }
+
+    #[test]
+    fn test_check_signers() {
+        let payer = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let message = VersionedMessage::Legacy(message(&[payer, authority], 0));
+
+        assert!(check_signers(&message, &[authority, payer]).is_ok());
+        assert!(matches!(
+            check_signers(&message, &[payer]),
+            Err(BuildError::MissingSigners(missing)) if missing == vec![authority]
+        ));
+
+        let stranger = Pubkey::new_unique();
+        assert!(matches!(
+            check_signers(&message, &[payer, authority, stranger]),
+            Err(BuildError::UnexpectedSigner(key)) if key == stranger
+        ));
+    }
+}
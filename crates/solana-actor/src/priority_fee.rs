@@ -0,0 +1,135 @@
+//! Priority fee estimation.
+//!
+//! On congested networks, transactions that pay a compute-unit price above
+//! what recently landed transactions paid are scheduled first. This module
+//! turns the recent prioritization fees reported by
+//! [`Connection::get_recent_prioritization_fees`] into a recommended
+//! compute-unit price and builds the compute budget instructions that set it
+//! and the compute-unit limit.
+//!
+//! [`Connection::get_recent_prioritization_fees`]: crate::Connection::get_recent_prioritization_fees
+
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+
+/// Compute budget program ID.
+pub const COMPUTE_BUDGET_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("ComputeBudget111111111111111111111111111111");
+
+/// Percentile of recent fees recommended by default.
+pub const DEFAULT_PRIORITY_FEE_PERCENTILE: u8 = 75;
+
+/// Discriminator of the `SetComputeUnitLimit` compute budget instruction.
+const SET_COMPUTE_UNIT_LIMIT: u8 = 2;
+
+/// Discriminator of the `SetComputeUnitPrice` compute budget instruction.
+const SET_COMPUTE_UNIT_PRICE: u8 = 3;
+
+/// The minimum prioritization fee paid in a recent slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrioritizationFee {
+    /// The slot the fee was observed in.
+    pub slot: u64,
+    /// The fee in micro-lamports per compute unit.
+    pub prioritization_fee: u64,
+}
+
+/// Recommend a compute-unit price from recent prioritization fees.
+///
+/// # Arguments
+///
+/// * `fees` - Recent prioritization fees, in any order.
+/// * `percentile` - The percentile of fees to match, from 0 to 100. Values
+///   above 100 are treated as 100.
+///
+/// # Returns
+///
+/// The price in micro-lamports per compute unit, or 0 if `fees` is empty.
+pub fn recommend_compute_unit_price(fees: &[PrioritizationFee], percentile: u8) -> u64 {
+    if fees.is_empty() {
+        return 0;
+    }
+
+    let mut sorted: Vec<u64> = fees.iter().map(|f| f.prioritization_fee).collect();
+    sorted.sort_unstable();
+
+    // Nearest-rank percentile
+    let percentile = usize::from(percentile.min(100));
+    let rank = (percentile * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+/// Build a compute budget instruction setting the compute-unit price.
+///
+/// # Arguments
+///
+/// * `micro_lamports` - The price in micro-lamports per compute unit.
+pub fn set_compute_unit_price_instruction(micro_lamports: u64) -> Instruction {
+    let mut data = Vec::with_capacity(9);
+    data.push(SET_COMPUTE_UNIT_PRICE);
+    data.extend_from_slice(&micro_lamports.to_le_bytes());
+    Instruction::new_with_bytes(COMPUTE_BUDGET_PROGRAM_ID, &data, vec![])
+}
+
+/// Build a compute budget instruction setting the compute-unit limit.
+///
+/// # Arguments
+///
+/// * `units` - The most compute units the transaction may consume.
+pub fn set_compute_unit_limit_instruction(units: u32) -> Instruction {
+    let mut data = Vec::with_capacity(5);
+    data.push(SET_COMPUTE_UNIT_LIMIT);
+    data.extend_from_slice(&units.to_le_bytes());
+    Instruction::new_with_bytes(COMPUTE_BUDGET_PROGRAM_ID, &data, vec![])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fees(values: &[u64]) -> Vec<PrioritizationFee> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(slot, fee)| PrioritizationFee {
+                slot: slot as u64,
+                prioritization_fee: *fee,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_recommend_empty() {
+        assert_eq!(recommend_compute_unit_price(&[], 75), 0);
+    }
+
+    #[test]
+    fn test_recommend_percentiles() {
+        let fees = fees(&[400, 0, 100, 300, 200]);
+        assert_eq!(recommend_compute_unit_price(&fees, 0), 0);
+        assert_eq!(recommend_compute_unit_price(&fees, 50), 200);
+        assert_eq!(recommend_compute_unit_price(&fees, 75), 300);
+        assert_eq!(recommend_compute_unit_price(&fees, 100), 400);
+        assert_eq!(recommend_compute_unit_price(&fees, 200), 400);
+    }
+
+    #[test]
+    fn test_set_compute_unit_price_instruction() {
+        let ix = set_compute_unit_price_instruction(5_000);
+        assert_eq!(ix.program_id, COMPUTE_BUDGET_PROGRAM_ID);
+        assert!(ix.accounts.is_empty());
+        assert_eq!(ix.data[0], SET_COMPUTE_UNIT_PRICE);
+        assert_eq!(u64::from_le_bytes(ix.data[1..].try_into().unwrap()), 5_000);
+    }
+
+    #[test]
+    fn test_set_compute_unit_limit_instruction() {
+        let ix = set_compute_unit_limit_instruction(400_000);
+        assert_eq!(ix.program_id, COMPUTE_BUDGET_PROGRAM_ID);
+        assert!(ix.accounts.is_empty());
+        assert_eq!(ix.data[0], SET_COMPUTE_UNIT_LIMIT);
+        assert_eq!(
+            u32::from_le_bytes(ix.data[1..].try_into().unwrap()),
+            400_000
+        );
+    }
+}
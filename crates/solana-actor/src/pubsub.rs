@@ -0,0 +1,340 @@
+//! WebSocket-based confirmation tracking.
+//!
+//! [`RpcConnection::send_and_confirm`](crate::Connection::send_and_confirm)
+//! polls signature statuses over HTTP. [`PubsubConnection`] instead subscribes
+//! to `signatureSubscribe` before sending, so confirmation arrives as soon as
+//! the node sees it, and to `slotSubscribe` to give up once the transaction's
+//! blockhash can no longer land. Select it per call with
+//! [`ConfirmStrategy::WebSocket`].
+
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::rpc_config::RpcSignatureSubscribeConfig;
+use solana_client::rpc_response::RpcSignatureResult;
+use solana_sdk::{
+    account::Account,
+    hash::Hash,
+    message::AddressLookupTableAccount,
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::{Transaction, VersionedTransaction},
+};
+
+use crate::accounts::{AccountFilter, TokenAmount};
+use crate::confirmation::{Commitment, SignatureInfo, SignatureStatus};
+use crate::connection::{ConfirmStrategy, Connection, RpcConnection, SendConfig};
+use crate::error::ConnectionError;
+use crate::priority_fee::PrioritizationFee;
+use crate::simulation::SimulationResult;
+
+/// Slots after which a transaction's blockhash is too old to land.
+pub const MAX_PROCESSING_AGE_SLOTS: u64 = 150;
+
+/// Derive the WebSocket URL for an RPC URL.
+///
+/// `http` becomes `ws` and `https` becomes `wss`. An explicit port is
+/// incremented, following the validator convention of serving WebSockets on
+/// the RPC port plus one (8899 → 8900).
+pub fn websocket_url(rpc_url: &str) -> String {
+    let (scheme, rest) = match rpc_url.split_once("://") {
+        Some(("https", rest)) => ("wss", rest),
+        Some((_, rest)) => ("ws", rest),
+        None => ("ws", rpc_url),
+    };
+
+    let (authority, path) = match rest.find('/') {
+        Some(i) => rest.split_at(i),
+        None => (rest, ""),
+    };
+    let authority = match authority.rsplit_once(':') {
+        Some((host, port)) => match port.parse::<u16>() {
+            Ok(port) => format!("{}:{}", host, port.saturating_add(1)),
+            Err(_) => authority.to_string(),
+        },
+        None => authority.to_string(),
+    };
+
+    format!("{}://{}{}", scheme, authority, path)
+}
+
+/// Connection that confirms transactions over WebSocket subscriptions.
+///
+/// Sending and queries go through an [`RpcConnection`]. `send_and_confirm`
+/// with [`ConfirmStrategy::WebSocket`] subscribes to the signature before
+/// sending and waits for its notification; [`ConfirmStrategy::Polling`] falls
+/// back to the RPC connection.
+///
+/// A new WebSocket is opened for each confirmation and closed afterwards.
+///
+/// # Example
+///
+/// ```ignore
+/// use solana_actor::{ConfirmStrategy, PubsubConnection, SendConfig};
+///
+/// let conn = PubsubConnection::new("https://api.mainnet-beta.solana.com");
+/// let config = SendConfig {
+///     confirm: ConfirmStrategy::WebSocket,
+///     ..Default::default()
+/// };
+/// let sig = conn.send_and_confirm(&tx, config).await?;
+/// ```
+pub struct PubsubConnection {
+    rpc: RpcConnection,
+    ws_url: String,
+}
+
+impl PubsubConnection {
+    /// Create a connection for an RPC URL, deriving the WebSocket URL.
+    pub fn new(rpc_url: &str) -> Self {
+        Self::with_websocket_url(RpcConnection::new(rpc_url), &websocket_url(rpc_url))
+    }
+
+    /// Create a connection with an explicit WebSocket URL.
+    pub fn with_websocket_url(rpc: RpcConnection, ws_url: &str) -> Self {
+        Self {
+            rpc,
+            ws_url: ws_url.to_string(),
+        }
+    }
+
+    /// The underlying RPC connection.
+    pub fn rpc(&self) -> &RpcConnection {
+        &self.rpc
+    }
+
+    /// The WebSocket URL.
+    pub fn websocket_url(&self) -> &str {
+        &self.ws_url
+    }
+
+    /// Subscribe to `signature`, run `send`, and wait for the notification.
+    async fn confirm_with_subscription<F>(
+        &self,
+        signature: Signature,
+        send: F,
+    ) -> Result<Signature, ConnectionError>
+    where
+        F: Future<Output = Result<Signature, ConnectionError>>,
+    {
+        let ws_error = |e: solana_client::pubsub_client::PubsubClientError| {
+            ConnectionError::Rpc(format!("WebSocket error: {}", e))
+        };
+
+        let client = PubsubClient::new(self.ws_url.as_str())
+            .await
+            .map_err(ws_error)?;
+
+        let outcome = {
+            let config = RpcSignatureSubscribeConfig {
+                commitment: Some(self.rpc.client().commitment()),
+                enable_received_notification: Some(false),
+            };
+            let (mut signatures, signature_unsubscribe) = client
+                .signature_subscribe(&signature, Some(config))
+                .await
+                .map_err(ws_error)?;
+            let (mut slots, slot_unsubscribe) = client.slot_subscribe().await.map_err(ws_error)?;
+
+            // Send only once subscribed so a fast confirmation isn't missed
+            let outcome = match send.await {
+                Ok(_) => {
+                    let mut slots_seen = 0u64;
+                    loop {
+                        tokio::select! {
+                            Some(response) = signatures.next() => match response.value {
+                                RpcSignatureResult::ProcessedSignature(result) => {
+                                    break match result.err {
+                                        None => Ok(signature),
+                                        Some(e) => Err(ConnectionError::TransactionFailed(e.to_string())),
+                                    };
+                                }
+                                RpcSignatureResult::ReceivedSignature(_) => {}
+                            },
+                            Some(_) = slots.next() => {
+                                slots_seen += 1;
+                                if slots_seen > MAX_PROCESSING_AGE_SLOTS {
+                                    break Err(ConnectionError::BlockhashExpired);
+                                }
+                            }
+                            else => break Err(ConnectionError::NetworkUnreachable),
+                        }
+                    }
+                }
+                Err(e) => Err(e),
+            };
+
+            drop(signatures);
+            drop(slots);
+            signature_unsubscribe().await;
+            slot_unsubscribe().await;
+            outcome
+        };
+
+        let _ = client.shutdown().await;
+        outcome
+    }
+}
+
+#[async_trait]
+impl Connection for PubsubConnection {
+    async fn send_transaction(
+        &self,
+        transaction: &Transaction,
+        config: SendConfig,
+    ) -> Result<Signature, ConnectionError> {
+        self.rpc.send_transaction(transaction, config).await
+    }
+
+    async fn send_and_confirm(
+        &self,
+        transaction: &Transaction,
+        config: SendConfig,
+    ) -> Result<Signature, ConnectionError> {
+        match config.confirm {
+            ConfirmStrategy::Polling => self.rpc.send_and_confirm(transaction, config).await,
+            ConfirmStrategy::WebSocket => {
+                let signature = transaction.signatures.first().copied().unwrap_or_default();
+                self.confirm_with_subscription(
+                    signature,
+                    self.rpc.send_transaction(transaction, config),
+                )
+                .await
+            }
+        }
+    }
+
+    async fn send_versioned_transaction(
+        &self,
+        transaction: &VersionedTransaction,
+        config: SendConfig,
+    ) -> Result<Signature, ConnectionError> {
+        self.rpc
+            .send_versioned_transaction(transaction, config)
+            .await
+    }
+
+    async fn send_and_confirm_versioned_transaction(
+        &self,
+        transaction: &VersionedTransaction,
+        config: SendConfig,
+    ) -> Result<Signature, ConnectionError> {
+        match config.confirm {
+            ConfirmStrategy::Polling => {
+                self.rpc
+                    .send_and_confirm_versioned_transaction(transaction, config)
+                    .await
+            }
+            ConfirmStrategy::WebSocket => {
+                let signature = transaction.signatures.first().copied().unwrap_or_default();
+                self.confirm_with_subscription(
+                    signature,
+                    self.rpc.send_versioned_transaction(transaction, config),
+                )
+                .await
+            }
+        }
+    }
+
+    async fn get_signature_statuses(
+        &self,
+        signatures: &[Signature],
+    ) -> Result<Vec<Option<SignatureStatus>>, ConnectionError> {
+        self.rpc.get_signature_statuses(signatures).await
+    }
+
+    async fn get_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        before: Option<&Signature>,
+    ) -> Result<Vec<SignatureInfo>, ConnectionError> {
+        self.rpc.get_signatures_for_address(address, before).await
+    }
+
+    async fn confirm_signature(
+        &self,
+        signature: &Signature,
+        commitment: Commitment,
+    ) -> Result<SignatureStatus, ConnectionError> {
+        // Already sent, so a new subscription could miss the notification
+        self.rpc.confirm_signature(signature, commitment).await
+    }
+
+    async fn simulate_versioned_transaction(
+        &self,
+        transaction: &VersionedTransaction,
+    ) -> Result<SimulationResult, ConnectionError> {
+        self.rpc.simulate_versioned_transaction(transaction).await
+    }
+
+    async fn get_latest_blockhash(&self) -> Result<Hash, ConnectionError> {
+        self.rpc.get_latest_blockhash().await
+    }
+
+    async fn get_balance(&self, pubkey: &Pubkey) -> Result<u64, ConnectionError> {
+        self.rpc.get_balance(pubkey).await
+    }
+
+    async fn get_account(&self, pubkey: &Pubkey) -> Result<Option<Account>, ConnectionError> {
+        self.rpc.get_account(pubkey).await
+    }
+
+    async fn get_multiple_accounts(
+        &self,
+        pubkeys: &[Pubkey],
+    ) -> Result<Vec<Option<Account>>, ConnectionError> {
+        self.rpc.get_multiple_accounts(pubkeys).await
+    }
+
+    async fn get_program_accounts(
+        &self,
+        program_id: &Pubkey,
+        filters: &[AccountFilter],
+    ) -> Result<Vec<(Pubkey, Account)>, ConnectionError> {
+        self.rpc.get_program_accounts(program_id, filters).await
+    }
+
+    async fn get_token_account_balance(
+        &self,
+        token_account: &Pubkey,
+    ) -> Result<TokenAmount, ConnectionError> {
+        self.rpc.get_token_account_balance(token_account).await
+    }
+
+    async fn get_address_lookup_table(
+        &self,
+        key: &Pubkey,
+    ) -> Result<Option<AddressLookupTableAccount>, ConnectionError> {
+        self.rpc.get_address_lookup_table(key).await
+    }
+
+    async fn get_recent_prioritization_fees(
+        &self,
+        writable_accounts: &[Pubkey],
+    ) -> Result<Vec<PrioritizationFee>, ConnectionError> {
+        self.rpc
+            .get_recent_prioritization_fees(writable_accounts)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_websocket_url() {
+        assert_eq!(
+            websocket_url("https://api.mainnet-beta.solana.com"),
+            "wss://api.mainnet-beta.solana.com"
+        );
+        assert_eq!(
+            websocket_url("http://127.0.0.1:8899"),
+            "ws://127.0.0.1:8900"
+        );
+        assert_eq!(
+            websocket_url("https://rpc.example.com:443/v1/key"),
+            "wss://rpc.example.com:444/v1/key"
+        );
+    }
+}
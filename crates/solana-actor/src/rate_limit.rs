@@ -0,0 +1,236 @@
+//! Client-side RPC rate limiting.
+//!
+//! Public RPC endpoints throttle aggressively and ban clients that keep
+//! exceeding their limits. [`RateLimitConfig`] gives
+//! [`RpcConnection`](crate::RpcConnection) a token bucket per [`MethodClass`],
+//! so bulk reads such as proposal scans can't starve sends, and backs off with
+//! jitter when a request is rate limited anyway.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::connection::RetryPolicy;
+
+/// Groups of RPC methods that share a token bucket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MethodClass {
+    /// Sending and confirming transactions.
+    Send,
+    /// Cheap reads: balances, single accounts, blockhashes, fees.
+    Read,
+    /// Expensive reads: `getProgramAccounts` and `getMultipleAccounts`.
+    Scan,
+}
+
+/// A sustained request rate with room for short bursts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    /// Requests allowed per second once the burst is spent.
+    pub per_second: u32,
+    /// Requests that may be made back to back before throttling.
+    pub burst: u32,
+}
+
+impl RateLimit {
+    /// A limit of `per_second` requests with a burst of the same size.
+    pub fn per_second(per_second: u32) -> Self {
+        Self {
+            per_second,
+            burst: per_second,
+        }
+    }
+}
+
+/// Rate limits per [`MethodClass`] and the backoff for rate-limited requests.
+///
+/// The default stays under the public mainnet endpoint's limits (100 requests
+/// per 10 seconds per IP, 40 per method).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitConfig {
+    /// Limit for [`MethodClass::Send`]; `None` is unlimited.
+    pub send: Option<RateLimit>,
+    /// Limit for [`MethodClass::Read`]; `None` is unlimited.
+    pub read: Option<RateLimit>,
+    /// Limit for [`MethodClass::Scan`]; `None` is unlimited.
+    pub scan: Option<RateLimit>,
+    /// Retries for requests the server rejects with HTTP 429. Each delay is
+    /// jittered to between half and all of [`RetryPolicy::backoff`].
+    pub backoff: RetryPolicy,
+}
+
+impl RateLimitConfig {
+    /// No limits and no retries.
+    pub fn none() -> Self {
+        Self {
+            send: None,
+            read: None,
+            scan: None,
+            backoff: RetryPolicy::none(),
+        }
+    }
+
+    fn limit(&self, class: MethodClass) -> Option<RateLimit> {
+        match class {
+            MethodClass::Send => self.send,
+            MethodClass::Read => self.read,
+            MethodClass::Scan => self.scan,
+        }
+    }
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            send: Some(RateLimit::per_second(2)),
+            read: Some(RateLimit {
+                per_second: 4,
+                burst: 8,
+            }),
+            scan: Some(RateLimit::per_second(1)),
+            backoff: RetryPolicy {
+                max_attempts: 5,
+                initial_backoff: Duration::from_secs(1),
+                max_backoff: Duration::from_secs(16),
+            },
+        }
+    }
+}
+
+/// A token bucket refilled continuously at a fixed rate.
+#[derive(Debug)]
+struct TokenBucket {
+    capacity: f64,
+    per_second: f64,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(limit: RateLimit, now: Instant) -> Self {
+        let capacity = f64::from(limit.burst.max(1));
+        Self {
+            capacity,
+            per_second: f64::from(limit.per_second.max(1)),
+            tokens: capacity,
+            updated: now,
+        }
+    }
+
+    /// Take a token, or return how long until one is available.
+    fn take(&mut self, now: Instant) -> Option<Duration> {
+        let refilled = now.saturating_duration_since(self.updated).as_secs_f64() * self.per_second;
+        self.tokens = (self.tokens + refilled).min(self.capacity);
+        self.updated = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64(
+                (1.0 - self.tokens) / self.per_second,
+            ))
+        }
+    }
+}
+
+/// Token buckets for each [`MethodClass`], shared by every request on a
+/// connection.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    config: RateLimitConfig,
+    send: Option<Mutex<TokenBucket>>,
+    read: Option<Mutex<TokenBucket>>,
+    scan: Option<Mutex<TokenBucket>>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(config: RateLimitConfig) -> Self {
+        let now = Instant::now();
+        let bucket = |class| {
+            config
+                .limit(class)
+                .map(|limit| Mutex::new(TokenBucket::new(limit, now)))
+        };
+        Self {
+            send: bucket(MethodClass::Send),
+            read: bucket(MethodClass::Read),
+            scan: bucket(MethodClass::Scan),
+            config,
+        }
+    }
+
+    /// Wait until a request of `class` may be made.
+    pub(crate) async fn acquire(&self, class: MethodClass) {
+        let bucket = match class {
+            MethodClass::Send => &self.send,
+            MethodClass::Read => &self.read,
+            MethodClass::Scan => &self.scan,
+        };
+        let Some(bucket) = bucket else {
+            return;
+        };
+        loop {
+            let wait = bucket
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .take(Instant::now());
+            match wait {
+                Some(wait) => tokio::time::sleep(wait).await,
+                None => return,
+            }
+        }
+    }
+
+    /// Total attempts for a request that keeps getting rate limited.
+    pub(crate) fn max_attempts(&self) -> u32 {
+        self.config.backoff.max_attempts.max(1)
+    }
+
+    /// The jittered delay before the given retry (1 for the first retry).
+    pub(crate) fn backoff(&self, retry: u32) -> Duration {
+        self.config.backoff.jittered_backoff(retry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_bucket() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(
+            RateLimit {
+                per_second: 4,
+                burst: 2,
+            },
+            start,
+        );
+
+        assert_eq!(bucket.take(start), None);
+        assert_eq!(bucket.take(start), None);
+        let wait = bucket.take(start).unwrap();
+        assert_eq!(wait, Duration::from_millis(250));
+
+        // A refill never exceeds the burst
+        let later = start + Duration::from_secs(10);
+        assert_eq!(bucket.take(later), None);
+        assert_eq!(bucket.take(later), None);
+        assert!(bucket.take(later).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_unlimited_classes_do_not_wait() {
+        let limiter = RateLimiter::new(RateLimitConfig {
+            scan: Some(RateLimit::per_second(1)),
+            ..RateLimitConfig::none()
+        });
+        let started = Instant::now();
+        for _ in 0..50 {
+            limiter.acquire(MethodClass::Read).await;
+        }
+        limiter.acquire(MethodClass::Scan).await;
+        assert!(started.elapsed() < Duration::from_millis(100));
+        assert_eq!(limiter.max_attempts(), 1);
+    }
+}
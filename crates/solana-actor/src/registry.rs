@@ -0,0 +1,222 @@
+//! Signer lookup by public key.
+//!
+//! This module provides the [`SignerRegistry`] trait, which resolves the
+//! [`TransactionSigner`] that can provide a required signature, and
+//! [`InMemorySignerRegistry`], a registry backed by a map of owned signers.
+
+use std::collections::HashMap;
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::composite::CompositeSigner;
+use crate::signer::TransactionSigner;
+
+/// Resolves which signer satisfies a required signature.
+///
+/// Implementations hold or load signers and hand them out by public key, so
+/// code that builds or signs transactions (such as
+/// [`TransactionBuilder::signers_from`](crate::TransactionBuilder::signers_from))
+/// can find every signer a message needs without the caller wiring each one.
+///
+/// # Example
+///
+/// ```ignore
+/// use solana_actor::{InMemorySignerRegistry, SignerRegistry};
+///
+/// let registry = InMemorySignerRegistry::new()
+///     .with_signer(fee_payer)
+///     .with_signer(program_authority);
+///
+/// if let Some(signer) = registry.get(&authority_pubkey) {
+///     let signature = signer.sign_transaction(&message_bytes)?;
+/// }
+/// ```
+pub trait SignerRegistry: Send + Sync {
+    /// The signer for `pubkey`, if one is registered.
+    fn get(&self, pubkey: &Pubkey) -> Option<&dyn TransactionSigner>;
+
+    /// The public keys of every registered signer.
+    fn pubkeys(&self) -> Vec<Pubkey>;
+
+    /// Whether a signer for `pubkey` is registered.
+    fn contains(&self, pubkey: &Pubkey) -> bool {
+        self.get(pubkey).is_some()
+    }
+
+    /// Keys in `required` that no registered signer can sign for.
+    fn missing(&self, required: &[Pubkey]) -> Vec<Pubkey> {
+        required
+            .iter()
+            .filter(|key| !self.contains(key))
+            .copied()
+            .collect()
+    }
+}
+
+/// A [`SignerRegistry`] holding owned signers in memory.
+///
+/// # Example
+///
+/// ```ignore
+/// use solana_actor::{InMemorySignerRegistry, TransactionBuilder};
+///
+/// let mut registry = InMemorySignerRegistry::new();
+/// registry.insert(Box::new(KeypairSigner::from_file("payer.json")?));
+/// registry.insert(Box::new(LedgerSigner::connect()?));
+///
+/// let tx = TransactionBuilder::new(payer)
+///     .instruction(transfer_ix)
+///     .signers_from(&registry)
+///     .build(&connection)
+///     .await?;
+/// ```
+#[derive(Default)]
+pub struct InMemorySignerRegistry {
+    signers: HashMap<Pubkey, Box<dyn TransactionSigner>>,
+}
+
+impl InMemorySignerRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a signer, replacing any registered for the same public key.
+    pub fn with_signer(mut self, signer: impl TransactionSigner + 'static) -> Self {
+        self.insert(Box::new(signer));
+        self
+    }
+
+    /// Add a boxed signer.
+    ///
+    /// Returns the signer previously registered for the same public key.
+    pub fn insert(
+        &mut self,
+        signer: Box<dyn TransactionSigner>,
+    ) -> Option<Box<dyn TransactionSigner>> {
+        self.signers.insert(signer.pubkey(), signer)
+    }
+
+    /// Remove and return the signer for `pubkey`.
+    pub fn remove(&mut self, pubkey: &Pubkey) -> Option<Box<dyn TransactionSigner>> {
+        self.signers.remove(pubkey)
+    }
+
+    /// Number of registered signers.
+    pub fn len(&self) -> usize {
+        self.signers.len()
+    }
+
+    /// Whether no signers are registered.
+    pub fn is_empty(&self) -> bool {
+        self.signers.is_empty()
+    }
+}
+
+impl SignerRegistry for InMemorySignerRegistry {
+    fn get(&self, pubkey: &Pubkey) -> Option<&dyn TransactionSigner> {
+        self.signers.get(pubkey).map(|signer| signer.as_ref())
+    }
+
+    fn pubkeys(&self) -> Vec<Pubkey> {
+        self.signers.keys().copied().collect()
+    }
+}
+
+impl FromIterator<Box<dyn TransactionSigner>> for InMemorySignerRegistry {
+    fn from_iter<I: IntoIterator<Item = Box<dyn TransactionSigner>>>(iter: I) -> Self {
+        let mut registry = Self::new();
+        for signer in iter {
+            registry.insert(signer);
+        }
+        registry
+    }
+}
+
+impl SignerRegistry for CompositeSigner {
+    fn get(&self, pubkey: &Pubkey) -> Option<&dyn TransactionSigner> {
+        CompositeSigner::get(self, pubkey)
+    }
+
+    fn pubkeys(&self) -> Vec<Pubkey> {
+        CompositeSigner::pubkeys(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::SignerError;
+    use solana_sdk::signature::Signature;
+
+    struct MockSigner {
+        pubkey: Pubkey,
+        tag: u8,
+    }
+
+    impl MockSigner {
+        fn new(pubkey: Pubkey, tag: u8) -> Self {
+            Self { pubkey, tag }
+        }
+    }
+
+    impl TransactionSigner for MockSigner {
+        fn pubkey(&self) -> Pubkey {
+            self.pubkey
+        }
+
+        fn sign_transaction(&self, _message: &[u8]) -> Result<Signature, SignerError> {
+            Ok(Signature::from([self.tag; 64]))
+        }
+    }
+
+    #[test]
+    fn test_lookup_by_pubkey() {
+        let (payer, authority, stranger) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let registry = InMemorySignerRegistry::new()
+            .with_signer(MockSigner::new(payer, 1))
+            .with_signer(MockSigner::new(authority, 2));
+
+        assert_eq!(registry.len(), 2);
+        let signer = registry.get(&authority).unwrap();
+        assert_eq!(signer.pubkey(), authority);
+        assert_eq!(
+            signer.sign_transaction(&[]).unwrap(),
+            Signature::from([2; 64])
+        );
+        assert!(registry.get(&stranger).is_none());
+        assert_eq!(registry.missing(&[payer, stranger]), vec![stranger]);
+    }
+
+    #[test]
+    fn test_insert_replaces_and_remove() {
+        let key = Pubkey::new_unique();
+        let mut registry = InMemorySignerRegistry::new();
+
+        assert!(registry.insert(Box::new(MockSigner::new(key, 1))).is_none());
+        let previous = registry.insert(Box::new(MockSigner::new(key, 2))).unwrap();
+        assert_eq!(
+            previous.sign_transaction(&[]).unwrap(),
+            Signature::from([1; 64])
+        );
+        assert_eq!(registry.len(), 1);
+
+        assert!(registry.remove(&key).is_some());
+        assert!(registry.is_empty());
+        assert!(!registry.contains(&key));
+    }
+
+    #[test]
+    fn test_composite_signer_registry() {
+        let key = Pubkey::new_unique();
+        let composite = CompositeSigner::new().with_signer(MockSigner::new(key, 3));
+
+        let registry: &dyn SignerRegistry = &composite;
+        assert!(registry.contains(&key));
+        assert_eq!(registry.pubkeys(), vec![key]);
+    }
+}
@@ -0,0 +1,179 @@
+//! Authentication for private RPC endpoints.
+//!
+//! Paid RPC providers identify clients by an API key, sent either as a
+//! request header (`Authorization: Bearer ...`, `x-api-key: ...`) or as a
+//! query parameter on the endpoint URL (`?api-key=...`). [`RpcAuth`] holds
+//! both kinds and builds an [`RpcClient`] that sends them with every request.
+
+use std::time::Duration;
+
+use reqwest::Url;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use solana_client::http_sender::HttpSender;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_client::RpcClientConfig;
+use solana_commitment_config::CommitmentConfig;
+
+use crate::error::ConnectionError;
+
+/// Request timeout, matching the Solana client's default.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Headers and query parameters that authenticate requests to an endpoint.
+///
+/// Values are secrets: [`Debug`] shows only the header and parameter names.
+///
+/// # Example
+///
+/// ```ignore
+/// use solana_actor::{RpcAuth, RpcConnection};
+///
+/// let auth = RpcAuth::new()
+///     .with_header("x-api-key", api_key)
+///     .with_query("client", "sign-agent");
+/// let conn = RpcConnection::new_with_auth("https://rpc.example.com", &auth)?;
+/// ```
+#[derive(Clone, Default)]
+pub struct RpcAuth {
+    headers: Vec<(String, String)>,
+    query: Vec<(String, String)>,
+}
+
+impl RpcAuth {
+    /// No authentication.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Authenticate with an `Authorization: Bearer` token.
+    pub fn bearer(token: impl AsRef<str>) -> Self {
+        Self::new().with_header("Authorization", format!("Bearer {}", token.as_ref()))
+    }
+
+    /// Send a header with every request.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Add a query parameter to the endpoint URL.
+    pub fn with_query(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.query.push((name.into(), value.into()));
+        self
+    }
+
+    /// Whether no headers or query parameters are set.
+    pub fn is_empty(&self) -> bool {
+        self.headers.is_empty() && self.query.is_empty()
+    }
+
+    /// The endpoint URL with the query parameters appended.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConnectionError::InvalidEndpoint`] if `url` isn't a valid URL.
+    pub fn url(&self, url: &str) -> Result<String, ConnectionError> {
+        if self.query.is_empty() {
+            return Ok(url.to_string());
+        }
+        let mut url =
+            Url::parse(url).map_err(|e| ConnectionError::InvalidEndpoint(e.to_string()))?;
+        url.query_pairs_mut().extend_pairs(&self.query);
+        Ok(url.into())
+    }
+
+    /// The headers to send, validated.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConnectionError::InvalidEndpoint`] naming the first header
+    /// whose name or value isn't valid in HTTP.
+    pub fn header_map(&self) -> Result<HeaderMap, ConnectionError> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            let invalid = || ConnectionError::InvalidEndpoint(format!("invalid header '{}'", name));
+            let name = HeaderName::from_bytes(name.as_bytes()).map_err(|_| invalid())?;
+            let mut value = HeaderValue::from_str(value).map_err(|_| invalid())?;
+            value.set_sensitive(true);
+            headers.append(name, value);
+        }
+        Ok(headers)
+    }
+
+    /// Build an RPC client that authenticates every request.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConnectionError::InvalidEndpoint`] if the URL or a header
+    /// is invalid.
+    pub fn rpc_client(
+        &self,
+        url: &str,
+        commitment: CommitmentConfig,
+    ) -> Result<RpcClient, ConnectionError> {
+        let url = self.url(url)?;
+        if self.headers.is_empty() {
+            return Ok(RpcClient::new_with_commitment(url, commitment));
+        }
+
+        let client = reqwest::Client::builder()
+            .default_headers(self.header_map()?)
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| ConnectionError::InvalidEndpoint(e.to_string()))?;
+        Ok(RpcClient::new_sender(
+            HttpSender::new_with_client(url, client),
+            RpcClientConfig::with_commitment(commitment),
+        ))
+    }
+}
+
+impl std::fmt::Debug for RpcAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names = |pairs: &[(String, String)]| -> Vec<String> {
+            pairs.iter().map(|(name, _)| name.clone()).collect()
+        };
+        f.debug_struct("RpcAuth")
+            .field("headers", &names(&self.headers))
+            .field("query", &names(&self.query))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_appends_query() {
+        let auth = RpcAuth::new().with_query("api-key", "s3cr3t");
+        assert_eq!(
+            auth.url("https://rpc.example.com/?cluster=mainnet")
+                .unwrap(),
+            "https://rpc.example.com/?cluster=mainnet&api-key=s3cr3t"
+        );
+        assert_eq!(
+            RpcAuth::new().url("http://localhost:8899").unwrap(),
+            "http://localhost:8899"
+        );
+        assert!(matches!(
+            auth.url("not a url"),
+            Err(ConnectionError::InvalidEndpoint(_))
+        ));
+    }
+
+    #[test]
+    fn test_headers_are_validated_and_redacted() {
+        let auth = RpcAuth::bearer("s3cr3t");
+        let headers = auth.header_map().unwrap();
+        assert_eq!(headers["authorization"], "Bearer s3cr3t");
+        assert!(headers["authorization"].is_sensitive());
+        assert!(!format!("{:?}", auth).contains("s3cr3t"));
+
+        let bad = RpcAuth::new().with_header("x-api-key", "line\nbreak");
+        assert!(matches!(
+            bad.header_map(),
+            Err(ConnectionError::InvalidEndpoint(_))
+        ));
+    }
+}
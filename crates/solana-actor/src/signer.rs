@@ -1,4 +1,4 @@
-//! Signer traits for synchronous signing operations.
+//! Signer traits for signing operations.
 //!
 //! This module defines two core signer traits:
 //! - [`MessageSigner`] for off-chain message signing (SIWS, etc.)
@@ -6,7 +6,12 @@
 //!
 //! Both traits are synchronous and pure - they perform no network operations.
 //! For async submission and network operations, see [`crate::transport::WalletTransport`].
+//!
+//! Backends that are inherently async (an agent, a remote HSM) implement
+//! [`AsyncTransactionSigner`] instead. Every cloneable [`TransactionSigner`]
+//! is also an [`AsyncTransactionSigner`], signing in a blocking task.
 
+use async_trait::async_trait;
 use solana_sdk::{pubkey::Pubkey, signature::Signature};
 
 use crate::error::SignerError;
@@ -85,3 +90,81 @@ pub trait TransactionSigner: Send + Sync {
         false
     }
 }
+
+/// Signs serialized transaction messages asynchronously.
+///
+/// Implement this for signers whose backend is naturally async, such as an
+/// agent reached over a socket or a signer reached over the network, rather
+/// than blocking on a private runtime inside [`TransactionSigner`].
+///
+/// Every [`TransactionSigner`] that is `Clone + 'static` implements this
+/// trait, running the synchronous signer with `spawn_blocking`. A type
+/// should therefore implement one trait or the other, not both.
+///
+/// # Example
+///
+/// ```ignore
+/// use async_trait::async_trait;
+/// use solana_actor::{AsyncTransactionSigner, DirectTransport, SignerError};
+///
+/// struct RemoteSigner { client: HsmClient, pubkey: Pubkey }
+///
+/// #[async_trait]
+/// impl AsyncTransactionSigner for RemoteSigner {
+///     fn pubkey(&self) -> Pubkey {
+///         self.pubkey
+///     }
+///
+///     async fn sign_transaction(&self, message: &[u8]) -> Result<Signature, SignerError> {
+///         self.client.sign(message).await.map_err(|e| SignerError::SigningFailed(e.to_string()))
+///     }
+/// }
+///
+/// let transport = DirectTransport::new(RemoteSigner { client, pubkey });
+/// ```
+#[async_trait]
+pub trait AsyncTransactionSigner: Send + Sync {
+    /// The public key of this signer.
+    fn pubkey(&self) -> Pubkey;
+
+    /// Sign a serialized transaction message.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The serialized transaction message bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SignerError`] if signing fails.
+    async fn sign_transaction(&self, message: &[u8]) -> Result<Signature, SignerError>;
+
+    /// Whether signing requires user interaction.
+    ///
+    /// See [`TransactionSigner::is_interactive`].
+    fn is_interactive(&self) -> bool {
+        false
+    }
+}
+
+#[async_trait]
+impl<S> AsyncTransactionSigner for S
+where
+    S: TransactionSigner + Clone + 'static,
+{
+    fn pubkey(&self) -> Pubkey {
+        TransactionSigner::pubkey(self)
+    }
+
+    async fn sign_transaction(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        let message = message.to_vec();
+        let signer = self.clone();
+
+        tokio::task::spawn_blocking(move || TransactionSigner::sign_transaction(&signer, &message))
+            .await
+            .map_err(|e| SignerError::SigningFailed(format!("Signing task failed: {}", e)))?
+    }
+
+    fn is_interactive(&self) -> bool {
+        TransactionSigner::is_interactive(self)
+    }
+}
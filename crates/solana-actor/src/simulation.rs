@@ -0,0 +1,106 @@
+//! Transaction simulation results.
+//!
+//! [`Connection::simulate_transaction`] runs a transaction against current
+//! network state without submitting it. The [`SimulationResult`] reports
+//! whether it would succeed, its program logs, the compute units it used and
+//! how it would change account balances, so callers can refuse or warn before
+//! asking a signer to approve a transaction that would fail.
+//!
+//! [`Connection::simulate_transaction`]: crate::Connection::simulate_transaction
+
+use solana_sdk::pubkey::Pubkey;
+
+/// Outcome of simulating a transaction.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SimulationResult {
+    /// The error the transaction would fail with, if any.
+    pub error: Option<String>,
+    /// Program log messages.
+    pub logs: Vec<String>,
+    /// Compute units consumed, if reported.
+    pub units_consumed: Option<u64>,
+    /// Lamport balances before and after, for accounts whose balance changes.
+    pub balance_changes: Vec<BalanceChange>,
+}
+
+impl SimulationResult {
+    /// Whether the transaction would succeed.
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// The balance change of an account, if it changes.
+    pub fn balance_change(&self, address: &Pubkey) -> Option<&BalanceChange> {
+        self.balance_changes.iter().find(|c| c.address == *address)
+    }
+}
+
+/// An account's lamport balance before and after a simulated transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BalanceChange {
+    /// The account address.
+    pub address: Pubkey,
+    /// Balance before the transaction.
+    pub pre: u64,
+    /// Balance after the transaction.
+    pub post: u64,
+}
+
+impl BalanceChange {
+    /// The change in lamports (negative when the balance decreases).
+    pub fn delta(&self) -> i128 {
+        i128::from(self.post) - i128::from(self.pre)
+    }
+}
+
+/// Pair pre- and post-transaction balances with their accounts, keeping only
+/// accounts whose balance changes.
+///
+/// # Arguments
+///
+/// * `accounts` - The transaction's accounts, including any loaded from
+///   lookup tables, in the order balances are reported.
+/// * `pre` - Balances before the transaction.
+/// * `post` - Balances after the transaction.
+pub fn balance_changes(accounts: &[Pubkey], pre: &[u64], post: &[u64]) -> Vec<BalanceChange> {
+    accounts
+        .iter()
+        .zip(pre.iter().zip(post))
+        .filter(|(_, (pre, post))| pre != post)
+        .map(|(address, (pre, post))| BalanceChange {
+            address: *address,
+            pre: *pre,
+            post: *post,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_balance_changes() {
+        let payer = Pubkey::new_unique();
+        let program = Pubkey::new_unique();
+        let recipient = Pubkey::new_unique();
+
+        let changes = balance_changes(
+            &[payer, program, recipient],
+            &[10_000, 1, 0],
+            &[3_000, 1, 5_000],
+        );
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].address, payer);
+        assert_eq!(changes[0].delta(), -7_000);
+        assert_eq!(changes[1].delta(), 5_000);
+
+        let result = SimulationResult {
+            balance_changes: changes,
+            ..Default::default()
+        };
+        assert!(result.is_success());
+        assert!(result.balance_change(&program).is_none());
+        assert_eq!(result.balance_change(&recipient).unwrap().post, 5_000);
+    }
+}
@@ -0,0 +1,652 @@
+//! Sign-In-With-Solana messages.
+//!
+//! [`SiwsMessage`] builds and parses the plain-text sign-in message defined by
+//! the Solana wallet standard (modeled on EIP-4361), so any [`MessageSigner`]
+//! can authenticate to an app. [`SiwsVerifier`] checks a signed message on the
+//! server: the signature, the domain, the nonce the server issued, and the
+//! expiration and not-before times. Signatures over the text wrapped as an
+//! [`OffchainMessage`], which is all a Ledger device can sign, are accepted
+//! too.
+//!
+//! ```text
+//! example.com wants you to sign in with your Solana account:
+//! 7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU
+//!
+//! Sign in to Example
+//!
+//! URI: https://example.com/login
+//! Version: 1
+//! Chain ID: mainnet
+//! Nonce: 32891756
+//! Issued At: 2024-01-01T00:00:00.000Z
+//! ```
+
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+use crate::error::SiwsError;
+use crate::offchain::OffchainMessage;
+use crate::signer::MessageSigner;
+
+/// Ending of the first line, after the domain.
+const HEADER_SUFFIX: &str = " wants you to sign in with your Solana account:";
+
+/// Labels of the optional fields, in the order they are written.
+const FIELDS: [&str; 8] = [
+    "URI",
+    "Version",
+    "Chain ID",
+    "Nonce",
+    "Issued At",
+    "Expiration Time",
+    "Not Before",
+    "Request ID",
+];
+
+/// Label introducing the resource list.
+const RESOURCES: &str = "Resources:";
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// A Sign-In-With-Solana message.
+///
+/// Optional fields left as `None` (or empty `resources`) are omitted from the
+/// text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SiwsMessage {
+    /// Domain requesting the sign-in, e.g. `example.com`.
+    pub domain: String,
+    /// Account signing in.
+    pub address: Pubkey,
+    /// Human-readable statement; must be a single line.
+    pub statement: Option<String>,
+    /// URI of the resource being signed in to.
+    pub uri: Option<String>,
+    /// Message version, `1`.
+    pub version: Option<String>,
+    /// Cluster, e.g. `mainnet` or `devnet`.
+    pub chain_id: Option<String>,
+    /// Random value issued by the server to prevent replay.
+    pub nonce: Option<String>,
+    /// When the message was created.
+    pub issued_at: Option<SystemTime>,
+    /// When the message stops being valid.
+    pub expiration_time: Option<SystemTime>,
+    /// When the message becomes valid.
+    pub not_before: Option<SystemTime>,
+    /// Identifier the server can use to refer to the sign-in.
+    pub request_id: Option<String>,
+    /// Resources the user is asked to authorize.
+    pub resources: Vec<String>,
+}
+
+impl SiwsMessage {
+    /// A version 1 message issued now, with no other optional fields.
+    pub fn new(domain: impl Into<String>, address: Pubkey) -> Self {
+        Self {
+            domain: domain.into(),
+            address,
+            statement: None,
+            uri: None,
+            version: Some("1".to_string()),
+            chain_id: None,
+            nonce: None,
+            issued_at: Some(SystemTime::now()),
+            expiration_time: None,
+            not_before: None,
+            request_id: None,
+            resources: Vec::new(),
+        }
+    }
+
+    /// Set the statement shown to the user.
+    pub fn with_statement(mut self, statement: impl Into<String>) -> Self {
+        self.statement = Some(statement.into());
+        self
+    }
+
+    /// Set the URI.
+    pub fn with_uri(mut self, uri: impl Into<String>) -> Self {
+        self.uri = Some(uri.into());
+        self
+    }
+
+    /// Set the chain ID.
+    pub fn with_chain_id(mut self, chain_id: impl Into<String>) -> Self {
+        self.chain_id = Some(chain_id.into());
+        self
+    }
+
+    /// Set the nonce issued by the server.
+    pub fn with_nonce(mut self, nonce: impl Into<String>) -> Self {
+        self.nonce = Some(nonce.into());
+        self
+    }
+
+    /// Set when the message was created.
+    pub fn with_issued_at(mut self, issued_at: SystemTime) -> Self {
+        self.issued_at = Some(issued_at);
+        self
+    }
+
+    /// Expire the message `ttl` after it was issued (or after now, if it has
+    /// no issue time).
+    pub fn expires_in(mut self, ttl: Duration) -> Self {
+        self.expiration_time = Some(self.issued_at.unwrap_or_else(SystemTime::now) + ttl);
+        self
+    }
+
+    /// Set when the message becomes valid.
+    pub fn with_not_before(mut self, not_before: SystemTime) -> Self {
+        self.not_before = Some(not_before);
+        self
+    }
+
+    /// Set the request ID.
+    pub fn with_request_id(mut self, request_id: impl Into<String>) -> Self {
+        self.request_id = Some(request_id.into());
+        self
+    }
+
+    /// Add a resource.
+    pub fn with_resource(mut self, resource: impl Into<String>) -> Self {
+        self.resources.push(resource.into());
+        self
+    }
+
+    /// Parse the text of a sign-in message.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SiwsError::InvalidMessage`] if the text does not follow the
+    /// SIWS format.
+    pub fn parse(text: &str) -> Result<Self, SiwsError> {
+        let mut lines = text.split('\n');
+        let domain = lines
+            .next()
+            .and_then(|line| line.strip_suffix(HEADER_SUFFIX))
+            .filter(|domain| !domain.is_empty())
+            .ok_or_else(|| invalid("missing header line"))?;
+        let address = lines
+            .next()
+            .and_then(|line| line.parse().ok())
+            .ok_or_else(|| invalid("missing or invalid address"))?;
+
+        let mut message = Self {
+            domain: domain.to_string(),
+            address,
+            statement: None,
+            uri: None,
+            version: None,
+            chain_id: None,
+            nonce: None,
+            issued_at: None,
+            expiration_time: None,
+            not_before: None,
+            request_id: None,
+            resources: Vec::new(),
+        };
+
+        // Blank line, optional statement, blank line, fields
+        let rest: Vec<&str> = lines.collect();
+        let mut rest = match rest.split_first() {
+            None => return Ok(message),
+            Some((&"", rest)) => rest,
+            Some(_) => return Err(invalid("expected a blank line after the address")),
+        };
+        if let Some((statement, after)) = rest.split_first()
+            && !is_field(statement)
+        {
+            message.statement = Some(statement.to_string());
+            rest = match after.split_first() {
+                None => after,
+                Some((&"", fields)) if !fields.is_empty() => fields,
+                Some(_) => return Err(invalid("expected a blank line after the statement")),
+            };
+        }
+
+        let mut fields = rest.iter();
+        while let Some(line) = fields.next() {
+            if *line == RESOURCES {
+                for resource in fields.by_ref() {
+                    let resource = resource
+                        .strip_prefix("- ")
+                        .ok_or_else(|| invalid("resources must be listed as \"- <uri>\""))?;
+                    message.resources.push(resource.to_string());
+                }
+                break;
+            }
+
+            let (label, value) = line
+                .split_once(": ")
+                .ok_or_else(|| invalid(&format!("unexpected line \"{}\"", line)))?;
+            match label {
+                "URI" => set_once(&mut message.uri, label, value.to_string())?,
+                "Version" => set_once(&mut message.version, label, value.to_string())?,
+                "Chain ID" => set_once(&mut message.chain_id, label, value.to_string())?,
+                "Nonce" => set_once(&mut message.nonce, label, value.to_string())?,
+                "Issued At" => set_once(&mut message.issued_at, label, parse_timestamp(value)?)?,
+                "Expiration Time" => {
+                    set_once(&mut message.expiration_time, label, parse_timestamp(value)?)?
+                }
+                "Not Before" => set_once(&mut message.not_before, label, parse_timestamp(value)?)?,
+                "Request ID" => set_once(&mut message.request_id, label, value.to_string())?,
+                _ => return Err(invalid(&format!("unknown field \"{}\"", label))),
+            }
+        }
+
+        Ok(message)
+    }
+
+    /// Sign the message text.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SiwsError::AddressMismatch`] if `signer` is not the message's
+    /// address, or [`SiwsError::Signer`] if signing fails.
+    pub fn sign<S: MessageSigner + ?Sized>(&self, signer: &S) -> Result<Signature, SiwsError> {
+        let signer_pubkey = signer.pubkey();
+        if signer_pubkey != self.address {
+            return Err(SiwsError::AddressMismatch {
+                expected: self.address,
+                actual: signer_pubkey,
+            });
+        }
+        Ok(signer.sign_message(self.to_string().as_bytes())?)
+    }
+}
+
+impl fmt::Display for SiwsMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}\n{}", self.domain, HEADER_SUFFIX, self.address)?;
+        if let Some(statement) = &self.statement {
+            write!(f, "\n\n{}", statement)?;
+        }
+
+        let timestamp = |t: &Option<SystemTime>| t.map(format_timestamp);
+        let values = [
+            self.uri.clone(),
+            self.version.clone(),
+            self.chain_id.clone(),
+            self.nonce.clone(),
+            timestamp(&self.issued_at),
+            timestamp(&self.expiration_time),
+            timestamp(&self.not_before),
+            self.request_id.clone(),
+        ];
+        let mut lines: Vec<String> = FIELDS
+            .iter()
+            .zip(values)
+            .filter_map(|(label, value)| value.map(|value| format!("{}: {}", label, value)))
+            .collect();
+        if !self.resources.is_empty() {
+            lines.push(RESOURCES.to_string());
+            lines.extend(self.resources.iter().map(|r| format!("- {}", r)));
+        }
+        if !lines.is_empty() {
+            write!(f, "\n\n{}", lines.join("\n"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Server-side checks for a signed sign-in message.
+///
+/// # Example
+///
+/// ```ignore
+/// use solana_actor::SiwsVerifier;
+///
+/// let message = SiwsVerifier::new("example.com", &session.nonce)
+///     .verify(&request.message, &request.signature)?;
+/// log_in(message.address);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SiwsVerifier {
+    domain: String,
+    nonce: String,
+    now: Option<SystemTime>,
+}
+
+impl SiwsVerifier {
+    /// Expect messages for `domain` carrying the `nonce` the server issued.
+    pub fn new(domain: impl Into<String>, nonce: impl Into<String>) -> Self {
+        Self {
+            domain: domain.into(),
+            nonce: nonce.into(),
+            now: None,
+        }
+    }
+
+    /// Check expiry against `now` instead of the system clock.
+    pub fn at(mut self, now: SystemTime) -> Self {
+        self.now = Some(now);
+        self
+    }
+
+    /// Parse a signed message and check it, returning the message if valid.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SiwsError`] if the message is malformed, the signature is not
+    /// from the message's address, the domain or nonce differ, or the message
+    /// is expired or not yet valid.
+    pub fn verify(&self, text: &str, signature: &Signature) -> Result<SiwsMessage, SiwsError> {
+        let message = SiwsMessage::parse(text)?;
+        // Ledger devices sign the text wrapped as an off-chain message
+        let signed = signature.verify(message.address.as_ref(), text.as_bytes())
+            || OffchainMessage::new(text).is_ok_and(|m| m.verify(&message.address, signature));
+        if !signed {
+            return Err(SiwsError::InvalidSignature);
+        }
+        if message.domain != self.domain {
+            return Err(SiwsError::DomainMismatch {
+                expected: self.domain.clone(),
+                actual: message.domain,
+            });
+        }
+        if message.nonce.as_deref() != Some(self.nonce.as_str()) {
+            return Err(SiwsError::NonceMismatch);
+        }
+
+        let now = self.now.unwrap_or_else(SystemTime::now);
+        if message.expiration_time.is_some_and(|expiry| now >= expiry) {
+            return Err(SiwsError::Expired);
+        }
+        if message
+            .not_before
+            .is_some_and(|not_before| now < not_before)
+        {
+            return Err(SiwsError::NotYetValid);
+        }
+        Ok(message)
+    }
+}
+
+fn invalid(reason: &str) -> SiwsError {
+    SiwsError::InvalidMessage(reason.to_string())
+}
+
+fn is_field(line: &str) -> bool {
+    line == RESOURCES
+        || FIELDS.iter().any(|label| {
+            line.strip_prefix(label)
+                .is_some_and(|rest| rest.starts_with(": "))
+        })
+}
+
+fn set_once<T>(slot: &mut Option<T>, label: &str, value: T) -> Result<(), SiwsError> {
+    if slot.replace(value).is_some() {
+        return Err(invalid(&format!("duplicate field \"{}\"", label)));
+    }
+    Ok(())
+}
+
+/// Format a time as RFC 3339 UTC with milliseconds, like JavaScript's
+/// `Date.toISOString`.
+fn format_timestamp(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs() as i64;
+    let (year, month, day) = civil_from_days(secs.div_euclid(SECONDS_PER_DAY));
+    let secs_of_day = secs.rem_euclid(SECONDS_PER_DAY);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+/// Parse an RFC 3339 time, e.g. `2024-01-01T00:00:00.000Z` or
+/// `2024-01-01T09:00:00+09:00`.
+fn parse_timestamp(value: &str) -> Result<SystemTime, SiwsError> {
+    let bad = || invalid(&format!("invalid timestamp \"{}\"", value));
+    let number = |range: std::ops::Range<usize>| -> Result<i64, SiwsError> {
+        value
+            .get(range)
+            .filter(|digits| digits.bytes().all(|b| b.is_ascii_digit()))
+            .and_then(|digits| digits.parse().ok())
+            .ok_or_else(bad)
+    };
+
+    let bytes = value.as_bytes();
+    if bytes.len() < 20
+        || bytes[4] != b'-'
+        || bytes[7] != b'-'
+        || !matches!(bytes[10], b'T' | b't')
+        || bytes[13] != b':'
+        || bytes[16] != b':'
+    {
+        return Err(bad());
+    }
+    let (year, month, day) = (number(0..4)?, number(5..7)?, number(8..10)?);
+    let (hour, minute, second) = (number(11..13)?, number(14..16)?, number(17..19)?);
+    if !(1..=12).contains(&month) || hour > 23 || minute > 59 || second > 59 {
+        return Err(bad());
+    }
+    let days = days_from_civil(year, month, day);
+    if civil_from_days(days) != (year, month, day) {
+        return Err(bad());
+    }
+
+    // Fractional seconds, kept to nanosecond precision
+    let mut rest = &value[19..];
+    let mut nanos = 0u32;
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let digits = fraction.bytes().take_while(u8::is_ascii_digit).count();
+        if digits == 0 {
+            return Err(bad());
+        }
+        for (i, digit) in fraction.bytes().take(digits.min(9)).enumerate() {
+            nanos += u32::from(digit - b'0') * 10u32.pow(8 - i as u32);
+        }
+        rest = &fraction[digits..];
+    }
+
+    let offset = match rest {
+        "Z" | "z" => 0,
+        _ => {
+            let sign = match rest.as_bytes().first() {
+                Some(b'+') => 1,
+                Some(b'-') => -1,
+                _ => return Err(bad()),
+            };
+            let (hours, minutes) = rest[1..].split_once(':').ok_or_else(bad)?;
+            let hours: i64 = hours.parse().map_err(|_| bad())?;
+            let minutes: i64 = minutes.parse().map_err(|_| bad())?;
+            if hours > 23 || minutes > 59 {
+                return Err(bad());
+            }
+            sign * (hours * 3600 + minutes * 60)
+        }
+    };
+
+    let secs = days * SECONDS_PER_DAY + hour * 3600 + minute * 60 + second - offset;
+    let secs = u64::try_from(secs).map_err(|_| bad())?;
+    Ok(UNIX_EPOCH + Duration::new(secs, nanos))
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Proleptic Gregorian date for days since 1970-01-01.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockSigner;
+
+    /// 2024-01-01T00:00:00Z
+    fn new_year() -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(1_704_067_200)
+    }
+
+    fn message(address: Pubkey) -> SiwsMessage {
+        SiwsMessage::new("example.com", address)
+            .with_statement("Sign in to Example")
+            .with_uri("https://example.com/login")
+            .with_chain_id("mainnet")
+            .with_nonce("32891756")
+            .with_issued_at(new_year())
+            .expires_in(Duration::from_secs(600))
+            .with_resource("https://example.com/terms")
+    }
+
+    #[test]
+    fn test_message_text() {
+        let address = Pubkey::new_unique();
+        let text = message(address).to_string();
+        assert_eq!(
+            text,
+            format!(
+                "example.com wants you to sign in with your Solana account:\n\
+                 {}\n\
+                 \n\
+                 Sign in to Example\n\
+                 \n\
+                 URI: https://example.com/login\n\
+                 Version: 1\n\
+                 Chain ID: mainnet\n\
+                 Nonce: 32891756\n\
+                 Issued At: 2024-01-01T00:00:00.000Z\n\
+                 Expiration Time: 2024-01-01T00:10:00.000Z\n\
+                 Resources:\n\
+                 - https://example.com/terms",
+                address
+            )
+        );
+        assert_eq!(SiwsMessage::parse(&text).unwrap(), message(address));
+
+        // Only the header and address are required
+        let minimal = SiwsMessage::parse(&format!(
+            "example.com wants you to sign in with your Solana account:\n{}",
+            address
+        ))
+        .unwrap();
+        assert_eq!(minimal.domain, "example.com");
+        assert_eq!(minimal.version, None);
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed() {
+        let address = Pubkey::new_unique();
+        let header = "example.com wants you to sign in with your Solana account:";
+        for text in [
+            format!("{}\nnot-a-pubkey", header),
+            format!("Sign in:\n{}", address),
+            format!("{}\n{}\nVersion: 1", header, address),
+            format!("{}\n{}\n\nVersion: 1\nVersion: 1", header, address),
+            format!("{}\n{}\n\nHi\n\nFavorite Color: blue", header, address),
+            format!("{}\n{}\n\nIssued At: yesterday", header, address),
+            format!("{}\n{}\n\nIssued At: 2024-02-30T00:00:00Z", header, address),
+        ] {
+            assert!(SiwsMessage::parse(&text).is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_timestamps() {
+        assert_eq!(format_timestamp(UNIX_EPOCH), "1970-01-01T00:00:00.000Z");
+        let leap_day = parse_timestamp("2024-02-29T12:34:56.789Z").unwrap();
+        assert_eq!(format_timestamp(leap_day), "2024-02-29T12:34:56.789Z");
+        assert_eq!(
+            parse_timestamp("2024-01-01T09:00:00+09:00").unwrap(),
+            new_year()
+        );
+        assert!(parse_timestamp("1969-12-31T23:59:59Z").is_err());
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let signer = MockSigner::new();
+        let message = message(signer.pubkey());
+        let text = message.to_string();
+        let signature = message.sign(&signer).unwrap();
+        let verifier = SiwsVerifier::new("example.com", "32891756").at(new_year());
+
+        assert_eq!(verifier.verify(&text, &signature).unwrap(), message);
+
+        // As signed by a Ledger, wrapped in an off-chain message
+        let wrapped = OffchainMessage::new(&text).unwrap().sign(&signer).unwrap();
+        assert_eq!(verifier.verify(&text, &wrapped).unwrap(), message);
+
+        let other = MockSigner::new();
+        assert!(matches!(
+            message.sign(&other),
+            Err(SiwsError::AddressMismatch { .. })
+        ));
+        assert!(matches!(
+            verifier.verify(&text, &other.sign_message(text.as_bytes()).unwrap()),
+            Err(SiwsError::InvalidSignature)
+        ));
+        assert!(matches!(
+            SiwsVerifier::new("evil.com", "32891756")
+                .at(new_year())
+                .verify(&text, &signature),
+            Err(SiwsError::DomainMismatch { .. })
+        ));
+        assert!(matches!(
+            SiwsVerifier::new("example.com", "other")
+                .at(new_year())
+                .verify(&text, &signature),
+            Err(SiwsError::NonceMismatch)
+        ));
+        assert!(matches!(
+            verifier
+                .clone()
+                .at(new_year() + Duration::from_secs(600))
+                .verify(&text, &signature),
+            Err(SiwsError::Expired)
+        ));
+    }
+
+    #[test]
+    fn test_not_before() {
+        let signer = MockSigner::new();
+        let message = SiwsMessage::new("example.com", signer.pubkey())
+            .with_nonce("n")
+            .with_not_before(new_year());
+        let signature = message.sign(&signer).unwrap();
+        let verifier = SiwsVerifier::new("example.com", "n");
+
+        assert!(matches!(
+            verifier
+                .clone()
+                .at(new_year() - Duration::from_secs(1))
+                .verify(&message.to_string(), &signature),
+            Err(SiwsError::NotYetValid)
+        ));
+        assert!(
+            verifier
+                .at(new_year())
+                .verify(&message.to_string(), &signature)
+                .is_ok()
+        );
+    }
+}
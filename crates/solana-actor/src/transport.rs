@@ -10,11 +10,14 @@
 //! - [`SubmitResult::Signed`] - Direct signature produced (from regular signers)
 //! - [`SubmitResult::Pending`] - Multisig proposal awaiting additional approvals
 //! - [`SubmitResult::Executed`] - Multisig proposal was executed on-chain
+//! - [`SubmitResult::Nested`] - Proposal on a multisig whose member is another multisig
+//! - [`SubmitResult::Rejected`] - Multisig proposal was voted down or cancelled
+//! - [`SubmitResult::AwaitingSignatures`] - Exported for signing on another machine
 
 use std::time::Duration;
 
 use async_trait::async_trait;
-use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use solana_sdk::{hash::Hash, message::VersionedMessage, pubkey::Pubkey, signature::Signature};
 
 use crate::error::TransportError;
 
@@ -45,45 +48,109 @@ pub enum SubmitResult {
     /// Multisig proposal was executed on-chain.
     ///
     /// This is returned when a multisig transaction reaches threshold
-    /// and is executed, producing an on-chain signature.
+    /// and is executed, producing an on-chain signature. A
+    /// [`DirectTransport`](crate::DirectTransport) with a connection also
+    /// returns it once the transaction it sent is confirmed.
     Executed {
         /// The transaction signature from execution.
         signature: Signature,
-        /// The proposal account that was executed.
+        /// The proposal account that was executed (the default pubkey for
+        /// direct sends).
         proposal: Pubkey,
     },
+
+    /// Multisig proposal was voted down or cancelled.
+    ///
+    /// The proposal will never execute; a new submission is needed.
+    Rejected {
+        /// The proposal account that was rejected.
+        proposal: Pubkey,
+        /// Why the proposal was rejected.
+        reason: String,
+    },
+
+    /// Submitted to a multisig through a member that is itself a multisig.
+    ///
+    /// The proposal on the outer multisig is approved once the inner proposal
+    /// (which approves it) executes.
+    Nested {
+        /// Status of the proposal on the outer multisig.
+        outer: Box<SubmitResult>,
+        /// Status of the inner multisig's proposal approving the outer one.
+        inner: Box<SubmitResult>,
+    },
+
+    /// Exported for signing on another machine, awaiting the signatures.
+    ///
+    /// Returned by [`OfflineTransport`](crate::OfflineTransport) until every
+    /// required signer's signature has been applied.
+    AwaitingSignatures {
+        /// Identifies the exported message (SHA-256 of its bytes).
+        message_id: Hash,
+        /// Required signers that haven't signed yet.
+        missing: Vec<Pubkey>,
+    },
 }
 
 impl SubmitResult {
     /// Get the signature if available.
     ///
     /// Returns `Some(&Signature)` for `Signed` and `Executed` variants,
-    /// `None` for `Pending`.
+    /// `None` for `Pending`. Nested results report the outer proposal.
     pub fn signature(&self) -> Option<&Signature> {
         match self {
             Self::Signed(sig) => Some(sig),
             Self::Executed { signature, .. } => Some(signature),
-            Self::Pending { .. } => None,
+            Self::Pending { .. } | Self::Rejected { .. } | Self::AwaitingSignatures { .. } => None,
+            Self::Nested { outer, .. } => outer.signature(),
         }
     }
 
     /// Whether this result represents a completed transaction.
     ///
     /// Returns `true` for `Signed` and `Executed`, `false` for `Pending`.
+    /// Nested results are complete once the outer proposal is executed.
     pub fn is_complete(&self) -> bool {
-        matches!(self, Self::Signed(_) | Self::Executed { .. })
+        match self {
+            Self::Signed(_) | Self::Executed { .. } => true,
+            Self::Pending { .. } | Self::Rejected { .. } | Self::AwaitingSignatures { .. } => false,
+            Self::Nested { outer, .. } => outer.is_complete(),
+        }
     }
 
-    /// Whether this result is pending additional approvals.
+    /// Whether this result is pending additional approvals or signatures.
     pub fn is_pending(&self) -> bool {
-        matches!(self, Self::Pending { .. })
+        match self {
+            Self::Pending { .. } | Self::AwaitingSignatures { .. } => true,
+            Self::Signed(_) | Self::Executed { .. } | Self::Rejected { .. } => false,
+            Self::Nested { outer, .. } => outer.is_pending(),
+        }
+    }
+
+    /// Whether this result was rejected and will never complete.
+    ///
+    /// Nested results are rejected if either layer is.
+    pub fn is_rejected(&self) -> bool {
+        match self {
+            Self::Rejected { .. } => true,
+            Self::Signed(_)
+            | Self::Executed { .. }
+            | Self::Pending { .. }
+            | Self::AwaitingSignatures { .. } => false,
+            Self::Nested { outer, inner } => outer.is_rejected() || inner.is_rejected(),
+        }
     }
 
     /// Get the proposal pubkey if this is a multisig result.
+    ///
+    /// Nested results report the outer proposal.
     pub fn proposal(&self) -> Option<&Pubkey> {
         match self {
-            Self::Pending { proposal, .. } | Self::Executed { proposal, .. } => Some(proposal),
-            Self::Signed(_) => None,
+            Self::Pending { proposal, .. }
+            | Self::Executed { proposal, .. }
+            | Self::Rejected { proposal, .. } => Some(proposal),
+            Self::Signed(_) | Self::AwaitingSignatures { .. } => None,
+            Self::Nested { outer, .. } => outer.proposal(),
         }
     }
 }
@@ -109,7 +176,10 @@ impl SubmitResult {
 ///     match result {
 ///         SubmitResult::Signed(sig) => Ok(sig),
 ///         SubmitResult::Executed { signature, .. } => Ok(signature),
-///         SubmitResult::Pending { .. } => {
+///         SubmitResult::Rejected { reason, .. } => Err(TransportError::ApprovalFailed(reason)),
+///         SubmitResult::Pending { .. }
+///         | SubmitResult::Nested { .. }
+///         | SubmitResult::AwaitingSignatures { .. } => {
 ///             // Wait for other signers
 ///             let final_result = transport
 ///                 .wait_for_completion(result, Duration::from_secs(300))
@@ -143,6 +213,26 @@ pub trait WalletTransport: Send + Sync {
     /// Returns [`TransportError`] if submission fails.
     async fn submit(&self, message: &[u8]) -> Result<SubmitResult, TransportError>;
 
+    /// Submit a versioned (legacy or v0) transaction message.
+    ///
+    /// The default implementation submits the serialized message, which is
+    /// what direct signers sign. Transports that need the message structure,
+    /// such as multisigs that re-encode it on-chain, override this.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The versioned transaction message.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TransportError`] if submission fails.
+    async fn submit_versioned(
+        &self,
+        message: &VersionedMessage,
+    ) -> Result<SubmitResult, TransportError> {
+        self.submit(&message.serialize()).await
+    }
+
     /// Check the current status of a previous submission.
     ///
     /// For direct signers, this simply returns the same result (always complete).
@@ -159,7 +249,8 @@ pub trait WalletTransport: Send + Sync {
 
     /// Wait for a pending submission to complete.
     ///
-    /// Polls the status until the transaction is complete or the timeout is reached.
+    /// Polls the status until the transaction is complete, is rejected, or the
+    /// timeout is reached.
     /// For direct signers, this returns immediately since signatures are always complete.
     ///
     /// # Arguments
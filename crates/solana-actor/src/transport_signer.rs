@@ -0,0 +1,275 @@
+//! Blocking [`Signer`] adapter for transports.
+//!
+//! This module provides [`TransportSigner`], which wraps any
+//! [`WalletTransport`] and implements [`solana_sdk::signer::Signer`], so code
+//! built around `Transaction::try_sign` and `&dyn Signer` can use a transport
+//! before it moves to the async traits.
+
+use std::future::Future;
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::Signature,
+    signer::{Signer, SignerError as SdkSignerError},
+};
+
+use crate::error::{BuildError, SignerError, TransportError};
+use crate::transport::{SubmitResult, WalletTransport};
+
+/// A [`WalletTransport`] as a blocking [`Signer`].
+///
+/// Each signature submits the message and blocks until the transport
+/// returns. Only a signature by the transport's authority over the message is
+/// accepted: a multisig proposal awaiting approvals, an offline export, or a
+/// rejection is an error, since a [`Signer`] can't wait for other parties.
+///
+/// Blocking works from synchronous code and from inside an async runtime; in
+/// the latter case the transport is driven on a separate thread, so the
+/// runtime's own thread is never re-entered.
+///
+/// # Example
+///
+/// ```ignore
+/// use solana_actor::{DirectTransport, TransportSigner};
+///
+/// let signer = TransportSigner::new(DirectTransport::new(ledger));
+///
+/// // Legacy code that takes `&dyn Signer`
+/// let mut tx = Transaction::new_unsigned(message);
+/// tx.try_sign(&[&signer], blockhash)?;
+/// ```
+pub struct TransportSigner<T> {
+    transport: T,
+    interactive: bool,
+}
+
+impl<T: WalletTransport> TransportSigner<T> {
+    /// Wrap a transport.
+    pub fn new(transport: T) -> Self {
+        Self {
+            transport,
+            interactive: false,
+        }
+    }
+
+    /// Report the signer as interactive, for transports that prompt the user
+    /// (hardware wallets, approval requests).
+    pub fn with_interactive(mut self, interactive: bool) -> Self {
+        self.interactive = interactive;
+        self
+    }
+
+    /// Get a reference to the underlying transport.
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Consume the adapter and return the underlying transport.
+    pub fn into_inner(self) -> T {
+        self.transport
+    }
+
+    /// Submit `message` and block until the transport returns a signature.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TransportError::InsufficientApprovals`] for a pending
+    /// multisig proposal, [`TransportError::ApprovalFailed`] for a rejected
+    /// one, [`BuildError::MissingSigners`] for an offline export, or
+    /// [`SignerError::SigningFailed`] if the returned signature isn't the
+    /// authority's over `message`.
+    pub fn sign_blocking(&self, message: &[u8]) -> Result<Signature, TransportError> {
+        let result = block_on(self.transport.submit(message))??;
+        self.signature_from(result, message)
+    }
+
+    /// The authority's signature in a submit result.
+    fn signature_from(
+        &self,
+        result: SubmitResult,
+        message: &[u8],
+    ) -> Result<Signature, TransportError> {
+        match result {
+            SubmitResult::Signed(signature) | SubmitResult::Executed { signature, .. } => {
+                // An executed multisig proposal returns the execution
+                // transaction's signature, not one over this message
+                let authority = self.transport.authority();
+                if !signature.verify(authority.as_ref(), message) {
+                    return Err(SignerError::SigningFailed(format!(
+                        "Transport did not return a signature by {}",
+                        authority
+                    ))
+                    .into());
+                }
+                Ok(signature)
+            }
+            SubmitResult::Pending {
+                approvals,
+                threshold,
+                ..
+            } => Err(TransportError::InsufficientApprovals {
+                current: approvals,
+                required: threshold,
+            }),
+            SubmitResult::Nested { outer, .. } => match *outer {
+                SubmitResult::Pending { .. } => Err(SignerError::SigningFailed(
+                    "Nested multisig proposal is awaiting approval".to_string(),
+                )
+                .into()),
+                outer => self.signature_from(outer, message),
+            },
+            SubmitResult::Rejected { reason, .. } => Err(TransportError::ApprovalFailed(reason)),
+            SubmitResult::AwaitingSignatures { missing, .. } => {
+                Err(BuildError::MissingSigners(missing).into())
+            }
+        }
+    }
+}
+
+impl<T: WalletTransport> Signer for TransportSigner<T> {
+    fn pubkey(&self) -> Pubkey {
+        self.transport.authority()
+    }
+
+    fn try_pubkey(&self) -> Result<Pubkey, SdkSignerError> {
+        Ok(self.transport.authority())
+    }
+
+    fn try_sign_message(&self, message: &[u8]) -> Result<Signature, SdkSignerError> {
+        self.sign_blocking(message)
+            .map_err(|e| SdkSignerError::Custom(e.to_string()))
+    }
+
+    fn is_interactive(&self) -> bool {
+        self.interactive
+    }
+}
+
+/// Run `future` to completion from synchronous code.
+fn block_on<F>(future: F) -> Result<F::Output, TransportError>
+where
+    F: Future + Send,
+    F::Output: Send,
+{
+    let run = move || {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map(|runtime| runtime.block_on(future))
+            .map_err(|e| {
+                TransportError::from(SignerError::SigningFailed(format!(
+                    "Failed to start runtime: {}",
+                    e
+                )))
+            })
+    };
+
+    // A thread already inside a runtime can't block on another one
+    if tokio::runtime::Handle::try_current().is_err() {
+        return run();
+    }
+    std::thread::scope(|scope| {
+        scope
+            .spawn(run)
+            .join()
+            .map_err(|_| TransportError::TaskPanic)?
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::direct::DirectTransport;
+    use crate::mock::MockSigner;
+    use crate::signer::TransactionSigner;
+    use async_trait::async_trait;
+    use solana_sdk::{
+        hash::Hash,
+        instruction::{AccountMeta, Instruction},
+        message::Message,
+        transaction::Transaction,
+    };
+    use std::time::Duration;
+
+    /// Multisig transport whose proposals never reach threshold
+    struct PendingTransport;
+
+    #[async_trait]
+    impl WalletTransport for PendingTransport {
+        fn authority(&self) -> Pubkey {
+            Pubkey::new_unique()
+        }
+
+        async fn submit(&self, _message: &[u8]) -> Result<SubmitResult, TransportError> {
+            Ok(SubmitResult::Pending {
+                proposal: Pubkey::new_unique(),
+                transaction_index: 1,
+                approvals: 1,
+                threshold: 2,
+            })
+        }
+
+        async fn check_status(
+            &self,
+            result: &SubmitResult,
+        ) -> Result<SubmitResult, TransportError> {
+            Ok(result.clone())
+        }
+
+        async fn wait_for_completion(
+            &self,
+            result: SubmitResult,
+            _timeout: Duration,
+        ) -> Result<SubmitResult, TransportError> {
+            Ok(result)
+        }
+
+        fn requires_network(&self) -> bool {
+            true
+        }
+    }
+
+    fn transaction(payer: &Pubkey) -> Transaction {
+        let ix = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[],
+            vec![AccountMeta::new(*payer, true)],
+        );
+        Transaction::new_unsigned(Message::new(&[ix], Some(payer)))
+    }
+
+    #[test]
+    fn test_signs_transaction() {
+        let member = MockSigner::new();
+        let signer = TransportSigner::new(DirectTransport::new(member.clone()));
+        assert_eq!(Signer::pubkey(&signer), member.pubkey());
+
+        let mut tx = transaction(&member.pubkey());
+        tx.try_sign(&[&signer], Hash::new_unique()).unwrap();
+        assert!(tx.verify().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_signs_inside_runtime() {
+        let member = MockSigner::new();
+        let signer = TransportSigner::new(DirectTransport::new(member.clone()));
+
+        let signature = signer.try_sign_message(b"message").unwrap();
+        assert!(signature.verify(member.pubkey().as_ref(), b"message"));
+    }
+
+    #[test]
+    fn test_pending_multisig_is_an_error() {
+        let signer = TransportSigner::new(PendingTransport).with_interactive(true);
+        assert!(signer.is_interactive());
+
+        assert!(matches!(
+            signer.sign_blocking(b"message"),
+            Err(TransportError::InsufficientApprovals {
+                current: 1,
+                required: 2
+            })
+        ));
+        assert!(signer.try_sign_message(b"message").is_err());
+    }
+}
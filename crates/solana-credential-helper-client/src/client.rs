@@ -4,6 +4,7 @@ use std::path::PathBuf;
 use std::process::Stdio;
 
 use base64::Engine;
+use solana_keyring_errors::ErrorCode;
 use solana_sdk::signature::Signature;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::UnixStream;
@@ -104,7 +105,15 @@ impl CredentialHelperClient {
             ))
         })?;
 
-        // Build request
+        // Scoped connections authenticate before anything else
+        if let Some(token) = &self.config.agent_token {
+            let request = serde_json::json!({
+                "method": "Authenticate",
+                "params": { "token": token }
+            });
+            agent_request(&mut stream, &request).await?;
+        }
+
         let request = serde_json::json!({
             "method": "SignTransaction",
             "params": {
@@ -112,32 +121,7 @@ impl CredentialHelperClient {
                 "signer": self.config.public_key
             }
         });
-        let request_bytes = serde_json::to_vec(&request)?;
-
-        // Send request (length-prefixed)
-        stream
-            .write_all(&(request_bytes.len() as u32).to_be_bytes())
-            .await?;
-        stream.write_all(&request_bytes).await?;
-
-        // Read response (length-prefixed)
-        let mut len_buf = [0u8; 4];
-        stream.read_exact(&mut len_buf).await?;
-        let len = u32::from_be_bytes(len_buf) as usize;
-
-        let mut buf = vec![0u8; len];
-        stream.read_exact(&mut buf).await?;
-
-        let response: serde_json::Value = serde_json::from_slice(&buf)?;
-
-        if response["status"] == "error" {
-            return Err(Error::Agent(
-                response["message"]
-                    .as_str()
-                    .unwrap_or("Unknown error")
-                    .to_string(),
-            ));
-        }
+        let response = agent_request(&mut stream, &request).await?;
 
         // Decode signature
         let sig_b64 = response["result"]
@@ -243,6 +227,40 @@ impl CredentialHelperClient {
     }
 }
 
+/// Send one length-prefixed request to the agent and read its response.
+async fn agent_request(
+    stream: &mut UnixStream,
+    request: &serde_json::Value,
+) -> Result<serde_json::Value> {
+    let request_bytes = serde_json::to_vec(request)?;
+    stream
+        .write_all(&(request_bytes.len() as u32).to_be_bytes())
+        .await?;
+    stream.write_all(&request_bytes).await?;
+
+    let mut len_buf = [0u8; 4];
+    stream.read_exact(&mut len_buf).await?;
+    let len = u32::from_be_bytes(len_buf) as usize;
+
+    let mut buf = vec![0u8; len];
+    stream.read_exact(&mut buf).await?;
+
+    let response: serde_json::Value = serde_json::from_slice(&buf)?;
+    if response["status"] == "error" {
+        return Err(Error::Agent {
+            code: response["code"]
+                .as_str()
+                .and_then(ErrorCode::from_name)
+                .unwrap_or(ErrorCode::InternalError),
+            message: response["message"]
+                .as_str()
+                .unwrap_or("Unknown error")
+                .to_string(),
+        });
+    }
+    Ok(response)
+}
+
 /// Get the default agent socket path.
 fn default_agent_socket_path() -> PathBuf {
     dirs::home_dir()
@@ -2,6 +2,8 @@
 
 use std::io;
 
+use solana_keyring_errors::ErrorCode;
+
 /// Error type for credential helper client operations.
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -18,8 +20,13 @@ pub enum Error {
     Base64(#[from] base64::DecodeError),
 
     /// Agent returned an error
-    #[error("Agent error: {0}")]
-    Agent(String),
+    #[error("Agent error: {message}")]
+    Agent {
+        /// Error code reported by the agent
+        code: ErrorCode,
+        /// Error message from the agent
+        message: String,
+    },
 
     /// CLI process error
     #[error("CLI error (exit code {code}): {message}")]
@@ -39,5 +46,24 @@ pub enum Error {
     Connection(String),
 }
 
+impl Error {
+    /// The shared error code for this error.
+    ///
+    /// Agent errors keep the agent's code and CLI errors are mapped from the
+    /// process exit status, so callers see the same code either way.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Error::Io(_) => ErrorCode::IoError,
+            Error::Json(_) | Error::Base64(_) => ErrorCode::InternalError,
+            Error::Agent { code, .. } => *code,
+            Error::Cli { code, .. } => {
+                ErrorCode::from_exit_status(Some(*code)).unwrap_or(ErrorCode::InternalError)
+            }
+            Error::InvalidSignature(_) => ErrorCode::InvalidSignature,
+            Error::Connection(_) => ErrorCode::ConnectionFailed,
+        }
+    }
+}
+
 /// Result type alias for credential helper operations.
 pub type Result<T> = std::result::Result<T, Error>;
@@ -42,6 +42,11 @@
 //!    signature. More portable but requires password entry each time (unless agent is running).
 //!
 //! Use [`CredentialHelperConfig::use_agent`] to choose which method to use.
+//!
+//! # Error Codes
+//!
+//! [`Error::code`] reports every failure as an [`ErrorCode`], whether it came
+//! from the agent or from the CLI's exit status.
 
 mod client;
 mod error;
@@ -49,4 +54,5 @@ mod types;
 
 pub use client::{CredentialHelperClient, default_db_path, default_socket_path};
 pub use error::{Error, Result};
+pub use solana_keyring_errors::ErrorCode;
 pub use types::{CredentialHelperConfig, SignerType};
@@ -51,6 +51,9 @@ pub struct CredentialHelperConfig {
     /// Agent socket path (default: ~/.solana-keyring/agent.sock).
     pub agent_socket_path: Option<PathBuf>,
 
+    /// Scoped session token to authenticate agent connections with.
+    pub agent_token: Option<String>,
+
     /// Database path (default: ~/.solana-keyring/keyring.db).
     pub db_path: Option<PathBuf>,
 }
@@ -100,6 +103,12 @@ impl CredentialHelperConfig {
         self
     }
 
+    /// Authenticate agent connections with a scoped session token.
+    pub fn agent_token(mut self, token: impl Into<String>) -> Self {
+        self.agent_token = Some(token.into());
+        self
+    }
+
     /// Set the database path.
     pub fn db_path(mut self, path: impl Into<PathBuf>) -> Self {
         self.db_path = Some(path.into());
@@ -3,6 +3,7 @@
 use std::path::PathBuf;
 
 use clap::{Parser, Subcommand, ValueEnum};
+use solana_keyring::tx_encoding::TxEncoding;
 
 #[derive(Parser)]
 #[command(
@@ -11,6 +12,10 @@ use clap::{Parser, Subcommand, ValueEnum};
     version
 )]
 pub struct Cli {
+    /// Disable colors and symbols (also honors NO_COLOR)
+    #[arg(long, global = true)]
+    pub plain: bool,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -19,6 +24,20 @@ pub struct Cli {
 pub enum Commands {
     /// Sign a transaction from stdin
     SignTransaction(SignTransactionArgs),
+
+    /// Simulate a transaction from stdin without signing it
+    Simulate(SimulateArgs),
+}
+
+#[derive(clap::Args)]
+pub struct SimulateArgs {
+    /// Encoding of the input message (base64 or base58)
+    #[arg(long, default_value = "base64")]
+    pub encoding: Encoding,
+
+    /// RPC URL to simulate against
+    #[arg(long, default_value = "https://api.mainnet-beta.solana.com")]
+    pub rpc_url: String,
 }
 
 #[derive(clap::Args)]
@@ -35,10 +54,28 @@ pub struct SignTransactionArgs {
     #[arg(long, conflicts_with = "squads")]
     pub ledger: bool,
 
-    /// Sign via Squads multisig (creates/approves proposal)
+    /// Sign via Squads multisig (creates/approves proposal); an address or a
+    /// label from the keyring
     #[arg(long, conflicts_with = "ledger")]
     pub squads: Option<String>,
 
+    /// Squads vault name or index (default: the multisig's default vault)
+    #[arg(long, value_name = "VAULT", requires = "squads")]
+    pub vault: Option<String>,
+
+    /// Memo shown with the Squads proposal and approval
+    #[arg(long, requires = "squads")]
+    pub memo: Option<String>,
+
+    /// Output the signed transaction instead of the signature, as base64,
+    /// base58 or json (RPC-style)
+    #[arg(long, value_name = "ENCODING", conflicts_with = "squads")]
+    pub tx_encoding: Option<TxEncoding>,
+
+    /// Write the signed transaction to a file instead of stdout
+    #[arg(long, value_name = "PATH", conflicts_with = "squads")]
+    pub tx_out: Option<PathBuf>,
+
     /// RPC URL for Squads operations
     #[arg(long, default_value = "https://api.mainnet-beta.solana.com")]
     pub rpc_url: String,
@@ -1,3 +1,4 @@
 //! Command implementations
 
 pub mod sign_transaction;
+pub mod simulate;
@@ -4,7 +4,15 @@ use std::io::{self, Read, Write};
 
 use anyhow::Result;
 use base64::Engine;
+use solana_keyring::cluster::{BindingMode, ClusterBindings};
+use solana_keyring::high_value::HighValueKeys;
+use solana_keyring::i18n::t_with;
+use solana_keyring::output;
+use solana_keyring::progress::Spinner;
+use solana_keyring::tx_encoding::{attach_signature, encode_transaction};
 use solana_keyring::{Database, default_agent_socket_path, default_db_path};
+use solana_keyring_errors::{CodedError, ErrorCode};
+use solana_sdk::signature::Signature;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::UnixStream;
 
@@ -18,10 +26,23 @@ pub async fn run(args: SignTransactionArgs) -> Result<()> {
 
     // Decode transaction
     let tx_bytes = match args.encoding {
-        Encoding::Base64 => base64::engine::general_purpose::STANDARD.decode(input)?,
-        Encoding::Base58 => bs58::decode(input).into_vec()?,
+        Encoding::Base64 => base64::engine::general_purpose::STANDARD
+            .decode(input)
+            .map_err(|e| CodedError::new(ErrorCode::InvalidTransaction, e.to_string()))?,
+        Encoding::Base58 => bs58::decode(input)
+            .into_vec()
+            .map_err(|e| CodedError::new(ErrorCode::InvalidTransaction, e.to_string()))?,
     };
 
+    // Show the summary hash on stderr so it can be compared with the
+    // approval prompt on another device
+    if let Ok(summary) = solana_keyring::transaction::summarize_transaction(&tx_bytes) {
+        eprintln!(
+            "{}",
+            output::dim(t_with("summary-hash", &[("hash", &summary.fingerprint())]))
+        );
+    }
+
     // Sign the transaction
     let signature = if args.use_agent {
         sign_via_agent(&args, &tx_bytes).await?
@@ -33,6 +54,26 @@ pub async fn run(args: SignTransactionArgs) -> Result<()> {
         sign_with_keypair(&args, &tx_bytes)?
     };
 
+    // Output the signed transaction if asked, otherwise just the signature
+    if args.tx_encoding.is_some() || args.tx_out.is_some() {
+        let tx = attach_signature(&tx_bytes, Signature::from(signature))?;
+        let encoded = encode_transaction(&tx, args.tx_encoding.unwrap_or_default())?;
+        match &args.tx_out {
+            Some(path) => {
+                std::fs::write(path, encoded)?;
+                eprintln!(
+                    "{}",
+                    output::success(format!("Wrote signed transaction to {}", path.display()))
+                );
+            }
+            None => {
+                io::stdout().write_all(encoded.as_bytes())?;
+                io::stdout().flush()?;
+            }
+        }
+        return Ok(());
+    }
+
     // Encode and output signature
     let output = match args.encoding {
         Encoding::Base64 => base64::engine::general_purpose::STANDARD.encode(signature),
@@ -51,7 +92,16 @@ async fn sign_via_agent(args: &SignTransactionArgs, tx_bytes: &[u8]) -> Result<[
         .clone()
         .unwrap_or_else(default_agent_socket_path);
 
-    let mut stream = UnixStream::connect(&socket_path).await?;
+    let mut stream = UnixStream::connect(&socket_path).await.map_err(|e| {
+        CodedError::new(
+            ErrorCode::ConnectionFailed,
+            format!(
+                "Failed to connect to agent at {}: {}",
+                socket_path.display(),
+                e
+            ),
+        )
+    })?;
 
     // Build request
     let request = serde_json::json!({
@@ -80,10 +130,18 @@ async fn sign_via_agent(args: &SignTransactionArgs, tx_bytes: &[u8]) -> Result<[
     let response: serde_json::Value = serde_json::from_slice(&buf)?;
 
     if response["status"] == "error" {
-        anyhow::bail!(
-            "Agent error: {}",
-            response["message"].as_str().unwrap_or("Unknown error")
-        );
+        let code = response["code"]
+            .as_str()
+            .and_then(ErrorCode::from_name)
+            .unwrap_or(ErrorCode::InternalError);
+        return Err(CodedError::new(
+            code,
+            format!(
+                "Agent error: {}",
+                response["message"].as_str().unwrap_or("Unknown error")
+            ),
+        )
+        .into());
     }
 
     // Decode signature
@@ -94,28 +152,65 @@ async fn sign_via_agent(args: &SignTransactionArgs, tx_bytes: &[u8]) -> Result<[
     let sig_bytes = base64::engine::general_purpose::STANDARD.decode(sig_b64)?;
     let sig: [u8; 64] = sig_bytes
         .try_into()
-        .map_err(|_| anyhow::anyhow!("Invalid signature length"))?;
+        .map_err(|_| CodedError::new(ErrorCode::InvalidSignature, "Invalid signature length"))?;
 
     Ok(sig)
 }
 
+/// Refuse, or warn about, signing for a cluster the signer isn't bound to
+fn check_cluster(db: &Database, signer_pubkey: &str, tx_bytes: &[u8]) -> Result<()> {
+    let Some(binding) = ClusterBindings::new(db).get(signer_pubkey)? else {
+        return Ok(());
+    };
+
+    if let Some(mismatch) = binding.check(tx_bytes)? {
+        match mismatch.binding.mode {
+            BindingMode::Refuse => {
+                return Err(
+                    CodedError::new(ErrorCode::ClusterMismatch, mismatch.to_string()).into(),
+                );
+            }
+            BindingMode::Warn => eprintln!("{}", output::warning(mismatch.to_string())),
+        }
+    }
+
+    Ok(())
+}
+
+/// Refuse high-value keys, which only sign through the agent's cool-down and prompts
+fn check_high_value(db: &Database, signer_pubkey: &str) -> Result<()> {
+    if HighValueKeys::new(db).get(signer_pubkey)?.is_some() {
+        return Err(CodedError::new(
+            ErrorCode::ConfirmationRequired,
+            format!(
+                "{} is a high-value key; sign through the agent",
+                signer_pubkey
+            ),
+        )
+        .into());
+    }
+    Ok(())
+}
+
 fn sign_with_keypair(args: &SignTransactionArgs, tx_bytes: &[u8]) -> Result<[u8; 64]> {
     let db_path = args.db_path.clone().unwrap_or_else(default_db_path);
     let db = Database::open(&db_path)?;
 
     if !db.is_initialized()? {
-        anyhow::bail!("Keyring not initialized. Run 'solana-keyring new' first.");
+        return Err(solana_keyring::Error::NotInitialized.into());
     }
 
     // Prompt for passphrase
-    let passphrase = rpassword::prompt_password("Enter master passphrase: ")?;
+    let passphrase = rpassword::prompt_password(output::prompt("Enter master passphrase"))?;
 
     if !db.verify_passphrase(passphrase.as_bytes())? {
-        anyhow::bail!("Invalid passphrase");
+        return Err(solana_keyring::Error::InvalidPassphrase.into());
     }
 
     // Load keypair
     let keypair = db.load_keypair(&args.signer, passphrase.as_bytes())?;
+    check_high_value(&db, &keypair.pubkey_base58())?;
+    check_cluster(&db, &keypair.pubkey_base58(), tx_bytes)?;
 
     // Sign
     let signature = keypair.sign(tx_bytes);
@@ -132,9 +227,6 @@ fn sign_with_keypair(args: &SignTransactionArgs, tx_bytes: &[u8]) -> Result<[u8;
 fn sign_with_ledger(args: &SignTransactionArgs, tx_bytes: &[u8]) -> Result<[u8; 64]> {
     use solana_keyring::ledger::LedgerSigner;
 
-    eprintln!("Connecting to Ledger device...");
-    eprintln!("Please confirm the transaction on your device.");
-
     let db_path = args.db_path.clone().unwrap_or_else(default_db_path);
     let db = Database::open(&db_path)?;
 
@@ -143,10 +235,22 @@ fn sign_with_ledger(args: &SignTransactionArgs, tx_bytes: &[u8]) -> Result<[u8;
     let wallet = wallets
         .iter()
         .find(|w| w.pubkey == args.signer || w.label == args.signer)
-        .ok_or_else(|| anyhow::anyhow!("Ledger wallet not found: {}", args.signer))?;
-
+        .ok_or_else(|| {
+            CodedError::new(
+                ErrorCode::SignerNotFound,
+                format!("Ledger wallet not found: {}", args.signer),
+            )
+        })?;
+    check_high_value(&db, &wallet.pubkey)?;
+    check_cluster(&db, &wallet.pubkey, tx_bytes)?;
+
+    let spinner = Spinner::start("Connecting to Ledger device...");
     let signer = LedgerSigner::connect(&wallet.derivation_path)?;
+    spinner.set_message(output::heading(
+        "Please confirm the transaction on your device.",
+    ));
     let signature = signer.sign(tx_bytes)?;
+    spinner.finish("Signed on Ledger");
 
     // Notify
     solana_keyring::notify(
@@ -158,50 +262,67 @@ fn sign_with_ledger(args: &SignTransactionArgs, tx_bytes: &[u8]) -> Result<[u8;
 }
 
 async fn sign_with_squads(args: &SignTransactionArgs, tx_bytes: &[u8]) -> Result<[u8; 64]> {
-    use solana_keyring::squads::SquadsSigner;
+    use solana_keyring::squads::{SquadsSigner, SquadsVaults, VaultSelector};
 
     let multisig_address = args
         .squads
         .as_ref()
-        .ok_or_else(|| anyhow::anyhow!("Squads address required"))?;
+        .ok_or_else(|| CodedError::new(ErrorCode::InvalidArguments, "Squads address required"))?;
 
     let db_path = args.db_path.clone().unwrap_or_else(default_db_path);
     let db = Database::open(&db_path)?;
 
     if !db.is_initialized()? {
-        anyhow::bail!("Keyring not initialized. Run 'solana-keyring new' first.");
+        return Err(solana_keyring::Error::NotInitialized.into());
     }
 
+    // Resolve the multisig label and vault before prompting
+    let selector = args
+        .vault
+        .as_deref()
+        .map(str::parse::<VaultSelector>)
+        .transpose()?;
+    let vault = SquadsVaults::new(&db).resolve(multisig_address, selector.as_ref())?;
+
     // Prompt for passphrase
-    let passphrase = rpassword::prompt_password("Enter master passphrase: ")?;
+    let passphrase = rpassword::prompt_password(output::prompt("Enter master passphrase"))?;
 
     if !db.verify_passphrase(passphrase.as_bytes())? {
-        anyhow::bail!("Invalid passphrase");
+        return Err(solana_keyring::Error::InvalidPassphrase.into());
     }
 
     // Load member keypair (the signer is the member who will sign the proposal)
     let member_keypair = db.load_keypair(&args.signer, passphrase.as_bytes())?;
 
-    eprintln!("Creating Squads proposal for transaction...");
+    let multisig_address = vault.multisig.to_string();
     eprintln!("Multisig: {}", multisig_address);
+    match &vault.name {
+        Some(name) => eprintln!("Vault: {} ({})", vault.index, name),
+        None => eprintln!("Vault: {}", vault.index),
+    }
     eprintln!("Member: {}", member_keypair.pubkey_base58());
 
     // Create Squads signer
-    let signer = SquadsSigner::new(multisig_address, 0, &args.rpc_url, member_keypair)?;
+    let signer = SquadsSigner::new(
+        &multisig_address,
+        vault.index,
+        &args.rpc_url,
+        member_keypair,
+    )?
+    .with_memo(args.memo.clone());
 
     // Create proposal
+    let spinner = Spinner::start("Creating Squads proposal for transaction...");
     let (proposal_pda, transaction_index) = signer.create_proposal(tx_bytes).await?;
-
-    eprintln!(
+    spinner.finish(format!(
         "Created proposal #{} at {}",
         transaction_index, proposal_pda
-    );
+    ));
 
     // Approve the proposal
-    eprintln!("Approving proposal...");
+    let spinner = Spinner::start("Approving proposal...");
     signer.approve_proposal(transaction_index).await?;
-
-    eprintln!("Proposal approved!");
+    spinner.finish("Proposal approved!");
     eprintln!("Note: If threshold is met, use Squads UI or CLI to execute the transaction.");
 
     // Notify
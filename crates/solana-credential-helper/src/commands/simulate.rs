@@ -0,0 +1,76 @@
+//! Simulate transaction command
+
+use std::io::{self, Read};
+
+use anyhow::Result;
+use base64::Engine;
+use solana_actor::{Connection, RpcConnection};
+use solana_keyring::transaction::summarize_transaction;
+use solana_keyring_errors::{CodedError, ErrorCode};
+use solana_sdk::{
+    message::VersionedMessage, signature::Signature, transaction::VersionedTransaction,
+};
+
+use crate::cli::{Encoding, SimulateArgs};
+
+pub async fn run(args: SimulateArgs) -> Result<()> {
+    // Read the message from stdin
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+    let input = input.trim();
+
+    let message_bytes = match args.encoding {
+        Encoding::Base64 => base64::engine::general_purpose::STANDARD
+            .decode(input)
+            .map_err(|e| CodedError::new(ErrorCode::InvalidTransaction, e.to_string()))?,
+        Encoding::Base58 => bs58::decode(input)
+            .into_vec()
+            .map_err(|e| CodedError::new(ErrorCode::InvalidTransaction, e.to_string()))?,
+    };
+    let message: VersionedMessage = bincode::deserialize(&message_bytes).map_err(|e| {
+        CodedError::new(
+            ErrorCode::InvalidTransaction,
+            format!("Failed to parse message: {}", e),
+        )
+    })?;
+
+    // Signatures are not verified during simulation
+    let transaction = VersionedTransaction {
+        signatures: vec![Signature::default(); message.header().num_required_signatures as usize],
+        message,
+    };
+
+    let connection = RpcConnection::new(&args.rpc_url);
+    let result = connection
+        .simulate_versioned_transaction(&transaction)
+        .await?;
+
+    // Summaries are only available for legacy messages
+    let summary = summarize_transaction(&message_bytes).ok();
+
+    let report = serde_json::json!({
+        "success": result.is_success(),
+        "error": result.error,
+        "units_consumed": result.units_consumed,
+        "logs": result.logs,
+        "balance_changes": result
+            .balance_changes
+            .iter()
+            .map(|change| serde_json::json!({
+                "address": change.address.to_string(),
+                "pre": change.pre,
+                "post": change.post,
+                "delta": change.delta(),
+            }))
+            .collect::<Vec<_>>(),
+        "summary_hash": summary.as_ref().map(|s| s.fingerprint()),
+        "summary": summary,
+    });
+    println!("{}", serde_json::to_string_pretty(&report)?);
+
+    if let Some(error) = result.error {
+        anyhow::bail!("Simulation failed: {}", error);
+    }
+
+    Ok(())
+}
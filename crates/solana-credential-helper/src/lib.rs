@@ -0,0 +1,39 @@
+//! Solana Credential Helper CLI
+//!
+//! The `solana-credential-helper` binary, as a library so it can be bundled
+//! into a combined executable.
+
+mod cli;
+mod commands;
+
+use std::ffi::OsString;
+use std::process::ExitCode;
+
+use clap::Parser;
+
+use cli::{Cli, Commands};
+
+/// Run the CLI with `args`, starting with the program name
+pub async fn run<I, T>(args: I) -> ExitCode
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let cli = Cli::parse_from(args);
+    solana_keyring::output::init(cli.plain);
+    solana_keyring::i18n::init_for_db(&solana_keyring::default_db_path());
+
+    let result = match cli.command {
+        Commands::SignTransaction(args) => commands::sign_transaction::run(args).await,
+        Commands::Simulate(args) => commands::simulate::run(args).await,
+    };
+
+    // Exit with the shared error code so callers can tell failures apart
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {:?}", e);
+            solana_keyring::error_code(e.as_ref()).exit_code()
+        }
+    }
+}
@@ -1,18 +1,8 @@
 //! Solana Credential Helper CLI
 
-mod cli;
-mod commands;
-
-use anyhow::Result;
-use clap::Parser;
-
-use cli::{Cli, Commands};
+use std::process::ExitCode;
 
 #[tokio::main]
-async fn main() -> Result<()> {
-    let cli = Cli::parse();
-
-    match cli.command {
-        Commands::SignTransaction(args) => commands::sign_transaction::run(args).await,
-    }
+async fn main() -> ExitCode {
+    solana_credential_helper::run(std::env::args_os()).await
 }
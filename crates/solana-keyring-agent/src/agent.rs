@@ -1,7 +1,8 @@
 //! Agent implementation
 
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 use base64::Engine as _;
@@ -10,29 +11,100 @@ use tokio::net::{UnixListener, UnixStream};
 use tokio::sync::RwLock;
 use zeroize::Zeroizing;
 
-use solana_keyring::{Database, default_db_path, list_signers};
+use solana_actor::OffchainMessage;
+use solana_keyring::allowlist::{Allowlist, DestinationPolicy};
+use solana_keyring::audit::{AuditLog, AuditSealKey};
+use solana_keyring::backup::backup_status;
+use solana_keyring::biometric::{self, AuthResult};
+use solana_keyring::cluster::{BindingMode, ClusterBindings, ClusterMismatch};
+use solana_keyring::crypto::verify_password;
+use solana_keyring::db::{CustomSignerRow, DelegationRow};
+use solana_keyring::delegation::{DelegatedRequest, Delegations};
+use solana_keyring::driver;
+use solana_keyring::high_value::{HighValueKey, HighValueKeys, amount_matches};
+use solana_keyring::i18n::{t, t_with};
+use solana_keyring::keypair::fingerprint;
+use solana_keyring::ledger::{self, LedgerSigner};
+use solana_keyring::lockout;
+use solana_keyring::maintenance::Maintenance;
+use solana_keyring::output;
+use solana_keyring::spending::{SpendingPolicy, unix_now};
+use solana_keyring::{
+    AddressBook, Database, SecureKeypair, Signer as _, default_db_path, list_signers,
+};
 
+use crate::approval::{Confirmation, Confirmer};
+use crate::db::SharedDatabase;
 use crate::protocol::{
-    AgentStatus, ErrorCode, GeneratedKeypairInfo, Request, Response, ResponseResult, SignerInfo,
+    AddressInfo, AgentStatus, ErrorCode, GeneratedKeypairInfo, HelloInfo, IssuedToken, Request,
+    ResolvedLabel, Response, ResponseResult, SessionInfo, SignerInfo, TokenList,
 };
+use crate::queue::SigningQueue;
+use crate::remote::RemoteServer;
+use crate::session::{AgentIdentity, ENCRYPTED_UNLOCK, Handshake, SessionKey};
+use crate::telemetry;
+use crate::tokens::TokenStore;
+
+/// How long a Ledger request waits for the device to be connected
+const LEDGER_CONNECT_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// How often the audit log retention policy is applied, and the log sealed
+/// while unlocked
+const AUDIT_PRUNE_INTERVAL: Duration = Duration::from_secs(3600);
 
 /// Agent state
 pub struct AgentState {
     pub passphrase: Option<Zeroizing<Vec<u8>>>,
-    pub db_path: PathBuf,
+    /// Connection reused across requests
+    pub db: Arc<SharedDatabase>,
     pub unlocked_at: Option<Instant>,
     pub started_at: Instant,
     pub lock_timeout: Duration,
+    /// Refuse unlock and signing requests
+    pub read_only: bool,
+    /// Which transfer destinations may be approved without a prompt
+    pub destination_policy: DestinationPolicy,
+    /// Serializes spending limit checks with counter updates
+    pub spending_lock: Mutex<()>,
+    /// Serializes unlock attempts, so throttling sees each failure before
+    /// the next attempt is checked
+    pub unlocking: Arc<tokio::sync::Mutex<()>>,
+    /// How signing requests are confirmed
+    pub confirmer: Confirmer,
+    /// Serializes signing requests per signer
+    pub queue: Arc<SigningQueue>,
+    /// Scoped session tokens issued by the owner
+    pub tokens: Arc<TokenStore>,
+    /// Ledger requests waiting for the device to be connected
+    pub awaiting_device: Arc<AtomicUsize>,
+    /// Long-term key authenticating encrypted sessions
+    pub identity: Arc<AgentIdentity>,
 }
 
 impl AgentState {
-    pub fn new(db_path: Option<PathBuf>, lock_timeout: Duration) -> Self {
+    pub fn new(
+        db_path: Option<PathBuf>,
+        lock_timeout: Duration,
+        read_only: bool,
+        destination_policy: DestinationPolicy,
+        confirmer: Confirmer,
+        identity: AgentIdentity,
+    ) -> Self {
         Self {
             passphrase: None,
-            db_path: db_path.unwrap_or_else(default_db_path),
+            db: Arc::new(SharedDatabase::new(db_path.unwrap_or_else(default_db_path))),
             unlocked_at: None,
             started_at: Instant::now(),
             lock_timeout,
+            read_only,
+            destination_policy,
+            spending_lock: Mutex::new(()),
+            unlocking: Arc::new(tokio::sync::Mutex::new(())),
+            confirmer,
+            queue: Arc::new(SigningQueue::new()),
+            tokens: Arc::new(TokenStore::default()),
+            awaiting_device: Arc::new(AtomicUsize::new(0)),
+            identity: Arc::new(identity),
         }
     }
 
@@ -51,6 +123,10 @@ impl AgentState {
     }
 
     pub fn check_timeout(&mut self) {
+        // Nobody could unlock a headless agent again
+        if matches!(self.confirmer, Confirmer::Headless) {
+            return;
+        }
         if let Some(unlocked_at) = self.unlocked_at
             && unlocked_at.elapsed() > self.lock_timeout
         {
@@ -63,17 +139,46 @@ impl AgentState {
 pub struct Agent {
     state: Arc<RwLock<AgentState>>,
     socket_path: PathBuf,
+    /// Remote signing listener, if enabled
+    remote: Option<RemoteServer>,
 }
 
 impl Agent {
-    pub fn new(socket_path: PathBuf, db_path: Option<PathBuf>, lock_timeout: Duration) -> Self {
+    pub fn new(
+        socket_path: PathBuf,
+        db_path: Option<PathBuf>,
+        lock_timeout: Duration,
+        read_only: bool,
+        destination_policy: DestinationPolicy,
+        confirmer: Confirmer,
+        identity: AgentIdentity,
+    ) -> Self {
         Self {
-            state: Arc::new(RwLock::new(AgentState::new(db_path, lock_timeout))),
+            state: Arc::new(RwLock::new(AgentState::new(
+                db_path,
+                lock_timeout,
+                read_only,
+                destination_policy,
+                confirmer,
+                identity,
+            ))),
             socket_path,
+            remote: None,
         }
     }
 
-    pub async fn run(self) -> anyhow::Result<()> {
+    /// Unlock with a passphrase that was already verified, before serving
+    pub async fn unlock(&self, passphrase: &[u8]) {
+        self.state.write().await.unlock(passphrase.to_vec());
+    }
+
+    /// Also serve remote signing requests over mutual TLS
+    pub fn with_remote(mut self, remote: RemoteServer) -> Self {
+        self.remote = Some(remote);
+        self
+    }
+
+    pub async fn run(mut self) -> anyhow::Result<()> {
         // Remove existing socket file
         let _ = std::fs::remove_file(&self.socket_path);
 
@@ -91,7 +196,57 @@ impl Agent {
             std::fs::set_permissions(&self.socket_path, std::fs::Permissions::from_mode(0o600))?;
         }
 
+        // Open the database up front so the first request doesn't pay for it
+        if let Err(e) = self.state.read().await.db.warm() {
+            eprintln!(
+                "{}",
+                output::warning(format!("Failed to open database: {}", e))
+            );
+        }
+
+        // Surface policy mistakes before the first request relies on them
+        {
+            let state = self.state.read().await;
+            let findings = state
+                .db
+                .with(|db| solana_keyring::policy::lint(db, &state.destination_policy));
+            for finding in findings.unwrap_or_default() {
+                eprintln!(
+                    "{}",
+                    output::warning(format!("Policy {}: {}", finding.rule, finding.message))
+                );
+            }
+        }
+
         println!("Agent listening on {}", self.socket_path.display());
+        if self.state.read().await.read_only {
+            println!("Running in read-only mode");
+        }
+
+        // Serve chat approval callbacks
+        if let Confirmer::Chat(chat) = self.state.read().await.confirmer.clone() {
+            tokio::spawn(async move {
+                if let Err(e) = chat.serve().await {
+                    eprintln!(
+                        "{}",
+                        output::error(format!("Approval callback server error: {}", e))
+                    );
+                }
+            });
+        }
+
+        // Serve remote signing requests
+        if let Some(remote) = self.remote.take() {
+            let state = self.state.clone();
+            tokio::spawn(async move {
+                if let Err(e) = remote.serve(state).await {
+                    eprintln!(
+                        "{}",
+                        output::error(format!("Remote signing server error: {}", e))
+                    );
+                }
+            });
+        }
 
         // Spawn timeout checker
         let state_clone = self.state.clone();
@@ -103,6 +258,34 @@ impl Agent {
             }
         });
 
+        // Apply the audit log retention policy and seal the log while
+        // unlocked; read-only agents don't write
+        if !self.state.read().await.read_only {
+            let state = self.state.clone();
+            let db = self.state.read().await.db.clone();
+            tokio::spawn(async move {
+                loop {
+                    let passphrase = state.read().await.passphrase.clone();
+                    if let Some(passphrase) = passphrase {
+                        seal_audit_log(&db, passphrase).await;
+                    }
+                    let pruned = db.with(|db| match Maintenance::new(db).audit_retention()? {
+                        Some(retention) => {
+                            AuditLog::new(db).prune(unix_now().saturating_sub(retention as i64))
+                        }
+                        None => Ok(0),
+                    });
+                    if let Err(e) = pruned {
+                        eprintln!(
+                            "{}",
+                            output::warning(format!("Failed to prune audit log: {}", e))
+                        );
+                    }
+                    tokio::time::sleep(AUDIT_PRUNE_INTERVAL).await;
+                }
+            });
+        }
+
         // Accept connections
         loop {
             let (stream, _) = listener.accept().await?;
@@ -110,7 +293,7 @@ impl Agent {
 
             tokio::spawn(async move {
                 if let Err(e) = handle_connection(stream, state).await {
-                    eprintln!("Connection error: {}", e);
+                    eprintln!("{}", output::error(format!("Connection error: {}", e)));
                 }
             });
         }
@@ -122,6 +305,10 @@ async fn handle_connection(
     state: Arc<RwLock<AgentState>>,
 ) -> anyhow::Result<()> {
     let mut len_buf = [0u8; 4];
+    // Encrypted passphrase session, scoped to this connection
+    let mut session = None;
+    // Session token the connection authenticated with, if any
+    let mut token = None;
 
     loop {
         // Read length prefix
@@ -145,7 +332,14 @@ async fn handle_connection(
 
         // Parse and process request
         let response = match serde_json::from_slice::<Request>(&buf) {
-            Ok(request) => process_request(request, &state).await,
+            Ok(request) => {
+                let method = request.method();
+                telemetry::request(
+                    method,
+                    process_request(request, &state, &mut session, &mut token),
+                )
+                .await
+            }
             Err(e) => Response::error(ErrorCode::InternalError, e.to_string()),
         };
 
@@ -168,39 +362,243 @@ async fn handle_connection(
     Ok(())
 }
 
-async fn process_request(request: Request, state: &Arc<RwLock<AgentState>>) -> Response {
+/// Run key derivation or decryption on a blocking thread
+///
+/// Argon2id takes a while, so it holds neither a tokio worker nor the shared
+/// database connection.
+async fn blocking<T: Send + 'static>(
+    f: impl FnOnce() -> solana_keyring::Result<T> + Send + 'static,
+) -> Result<solana_keyring::Result<T>, Response> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| Response::error(ErrorCode::InternalError, e.to_string()))
+}
+
+/// Seal the audit log under the master passphrase, warning on failure
+async fn seal_audit_log(db: &SharedDatabase, passphrase: Zeroizing<Vec<u8>>) {
+    let sealed = match blocking(move || AuditSealKey::derive(&passphrase)).await {
+        Ok(key) => key
+            .and_then(|key| db.with(|db| AuditLog::new(db).seal_with(&key)))
+            .map_err(|e| e.to_string()),
+        Err(_) => Err("key derivation was interrupted".to_string()),
+    };
+    if let Err(e) = sealed {
+        eprintln!(
+            "{}",
+            output::warning(format!("Failed to seal audit log: {}", e))
+        );
+    }
+}
+
+/// Verify a passphrase and unlock the agent
+async fn unlock(state: &Arc<RwLock<AgentState>>, passphrase: Vec<u8>) -> Response {
+    let passphrase = Zeroizing::new(passphrase);
+    let (db, unlocking) = {
+        let state = state.read().await;
+        (state.db.clone(), state.unlocking.clone())
+    };
+    let _unlocking = unlocking.lock().await;
+
+    // Refuse without checking the passphrase while attempts are throttled
+    let now = unix_now();
+    if let Err(e) = db.with(|db| lockout::gate(db, now)?.check()) {
+        return e.into();
+    }
+
+    // Verify passphrase
+    let (salt, hash) = match db.with(|db| db.passphrase_hash()) {
+        Ok(stored) => stored,
+        Err(e) => return Response::error(ErrorCode::InternalError, e.to_string()),
+    };
+    let candidate = passphrase.clone();
+    let verified = match blocking(move || verify_password(&candidate, &salt, &hash)).await {
+        Ok(verified) => verified,
+        Err(response) => return response,
+    };
+    match verified {
+        Ok(true) => {
+            if let Err(e) = db.with(lockout::record_success) {
+                eprintln!(
+                    "{}",
+                    output::warning(format!("Failed to clear unlock failures: {}", e))
+                );
+            }
+            // Cover whatever was recorded while locked
+            seal_audit_log(&db, passphrase.clone()).await;
+            state.write().await.unlock(passphrase.to_vec());
+            Response::ok(ResponseResult::Unit)
+        }
+        Ok(false) => {
+            match db.with(|db| lockout::record_failure(db, now)) {
+                Ok(failed) if failed.hard_locked => {
+                    let _ = solana_keyring::notify(
+                        "Keyring Hard-Locked",
+                        &format!(
+                            "Locked after {} failed attempts; use the recovery code to lift it",
+                            failed.attempts
+                        ),
+                    );
+                }
+                Ok(failed) if failed.notify => {
+                    let _ = solana_keyring::notify(
+                        "Failed Unlock Attempts",
+                        &format!(
+                            "{} wrong passphrases were sent to the agent",
+                            failed.attempts
+                        ),
+                    );
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!(
+                    "{}",
+                    output::error(format!("Failed to record unlock failure: {}", e))
+                ),
+            }
+            Response::error(ErrorCode::InvalidPassphrase, "Invalid passphrase")
+        }
+        Err(e) => Response::error(ErrorCode::InternalError, e.to_string()),
+    }
+}
+
+/// Decrypt a stored keypair with the agent's passphrase
+///
+/// Only reading the encrypted row holds the shared database; the key is
+/// derived and the secret decrypted on a blocking thread.
+async fn load_keypair(
+    state: &Arc<RwLock<AgentState>>,
+    db: &SharedDatabase,
+    signer: &str,
+) -> Result<SecureKeypair, Response> {
+    let Some(passphrase) = state.read().await.passphrase.clone() else {
+        return Err(Response::error(
+            ErrorCode::Locked,
+            "Agent was locked before signing",
+        ));
+    };
+    let encrypted = db
+        .with(|db| db.load_encrypted_keypair(signer))
+        .map_err(|e| Response::error(ErrorCode::SignerNotFound, e.to_string()))?;
+    blocking(move || SecureKeypair::decrypt(&encrypted, &passphrase))
+        .await?
+        .map_err(|e| Response::error(ErrorCode::SignerNotFound, e.to_string()))
+}
+
+/// Encrypt and store a new keypair, returning its pubkey
+async fn store_keypair(
+    state: &Arc<RwLock<AgentState>>,
+    keypair: SecureKeypair,
+    label: &str,
+    tags: &[String],
+) -> Result<String, Response> {
+    let (db, passphrase) = {
+        let state = state.read().await;
+        let Some(passphrase) = state.passphrase.clone() else {
+            return Err(Response::error(ErrorCode::Locked, "Agent is locked"));
+        };
+        (state.db.clone(), passphrase)
+    };
+
+    let pubkey = keypair.pubkey_base58();
+    let encrypted = blocking(move || keypair.encrypt(&passphrase))
+        .await?
+        .map_err(|e| Response::error(ErrorCode::InternalError, e.to_string()))?;
+    let tag_refs: Vec<&str> = tags.iter().map(|s| s.as_str()).collect();
+    db.with(|db| db.store_encrypted_keypair(&pubkey, label, &encrypted, &tag_refs))
+        .map_err(|e| Response::error(ErrorCode::InternalError, e.to_string()))?;
+    Ok(pubkey)
+}
+
+pub(crate) async fn process_request(
+    request: Request,
+    state: &Arc<RwLock<AgentState>>,
+    session: &mut Option<SessionKey>,
+    token: &mut Option<String>,
+) -> Response {
+    if request.requires_write() && state.read().await.read_only {
+        return Response::error(ErrorCode::ReadOnly, "Agent is running in read-only mode");
+    }
+
+    // Connections authenticated with a token are limited to its scope
+    if let Some(id) = token.as_deref() {
+        let (db, tokens) = {
+            let state = state.read().await;
+            (state.db.clone(), state.tokens.clone())
+        };
+        let signer = match &request {
+            Request::SignTransaction { signer, .. } | Request::SignMessage { signer, .. } => Some(
+                db.with(|db| find_signer(db, signer))
+                    .ok()
+                    .flatten()
+                    .map_or_else(|| signer.clone(), |(pubkey, _)| pubkey),
+            ),
+            _ => None,
+        };
+        if let Err(response) = tokens.authorize(id, &request, signer.as_deref()) {
+            return response;
+        }
+    }
+
     match request {
         Request::Ping => Response::ok(ResponseResult::Pong),
 
         Request::Status => {
             let state = state.read().await;
+            let unlock_status = state.db.with(|db| lockout::status(db, unix_now())).ok();
+            let backup = state.db.with(backup_status).ok();
             Response::ok(ResponseResult::Status(AgentStatus {
                 unlocked: state.is_unlocked(),
                 uptime_seconds: state.started_at.elapsed().as_secs(),
                 signer_count: 0, // TODO: count signers
                 lock_timeout_seconds: state.lock_timeout.as_secs(),
+                read_only: state.read_only,
+                destination_policy: state.destination_policy.to_string(),
+                confirmer: state.confirmer.name().to_string(),
+                queued_requests: state.queue.len(),
+                failed_unlocks: unlock_status.as_ref().map_or(0, |l| l.failed_attempts),
+                unlock_hard_locked: unlock_status
+                    .is_some_and(|l| l.gate == lockout::UnlockGate::HardLocked),
+                awaiting_device: state.awaiting_device.load(Ordering::SeqCst),
+                last_backup_at: backup.and_then(|b| b.last_backup_at),
+                unbacked_keys: backup.map_or(0, |b| b.unbacked_keys),
             }))
         }
 
-        Request::Unlock { passphrase } => {
-            let mut state = state.write().await;
+        Request::Hello => Response::ok(ResponseResult::Hello(HelloInfo {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            capabilities: vec![ENCRYPTED_UNLOCK.to_string()],
+            identity: Some(state.read().await.identity.public_key()),
+        })),
 
-            // Verify passphrase
-            let db = match Database::open(&state.db_path) {
-                Ok(db) => db,
-                Err(e) => return Response::error(ErrorCode::InternalError, e.to_string()),
-            };
-
-            match db.verify_passphrase(passphrase.as_bytes()) {
-                Ok(true) => {
-                    state.unlock(passphrase.into_bytes());
-                    Response::ok(ResponseResult::Unit)
+        Request::OpenSession { public_key } => {
+            let identity = state.read().await.identity.clone();
+            let handshake = Handshake::new();
+            let agent_public = handshake.public_key();
+            match handshake.finish_agent(&public_key, &identity) {
+                Ok(key) => {
+                    *session = Some(key);
+                    Response::ok(ResponseResult::Session(SessionInfo {
+                        public_key: agent_public,
+                    }))
                 }
-                Ok(false) => Response::error(ErrorCode::InvalidPassphrase, "Invalid passphrase"),
                 Err(e) => Response::error(ErrorCode::InternalError, e.to_string()),
             }
         }
 
+        Request::SecureUnlock { nonce, ciphertext } => {
+            // Session keys are single-use
+            let Some(key) = session.take() else {
+                return Response::error(ErrorCode::InternalError, "No encrypted session open");
+            };
+            let passphrase = match key.open(&nonce, &ciphertext) {
+                Ok(p) => p,
+                Err(e) => return Response::error(ErrorCode::InternalError, e.to_string()),
+            };
+
+            unlock(state, passphrase.to_vec()).await
+        }
+
+        Request::Unlock { passphrase } => unlock(state, passphrase.into_bytes()).await,
+
         Request::Lock => {
             let mut state = state.write().await;
             state.lock();
@@ -209,20 +607,21 @@ async fn process_request(request: Request, state: &Arc<RwLock<AgentState>>) -> R
 
         Request::ListSigners { tag } => {
             let state = state.read().await;
+            let scope = token.as_deref().and_then(|id| state.tokens.scope(id));
 
-            let db = match Database::open(&state.db_path) {
-                Ok(db) => db,
-                Err(e) => return Response::error(ErrorCode::InternalError, e.to_string()),
-            };
-
-            match list_signers(&db, tag.as_deref()) {
+            match state.db.with(|db| list_signers(db, tag.as_deref())) {
                 Ok(signers) => {
                     let infos: Vec<SignerInfo> = signers
                         .into_iter()
+                        .filter(|s| {
+                            scope
+                                .as_ref()
+                                .is_none_or(|scope| scope.allows_signer(&s.pubkey))
+                        })
                         .map(|s| SignerInfo {
                             pubkey: s.pubkey,
                             label: s.label,
-                            signer_type: s.signer_type.to_string(),
+                            signer_type: s.driver.unwrap_or_else(|| s.signer_type.to_string()),
                             tags: s.tags,
                         })
                         .collect();
@@ -232,111 +631,221 @@ async fn process_request(request: Request, state: &Arc<RwLock<AgentState>>) -> R
             }
         }
 
-        Request::SignTransaction {
-            transaction,
-            signer,
-        } => {
+        Request::ResolveLabel { identifier } => {
             let state = state.read().await;
+            let scope = token.as_deref().and_then(|id| state.tokens.scope(id));
 
-            if !state.is_unlocked() {
-                return Response::error(ErrorCode::Locked, "Agent is locked");
-            }
-
-            let passphrase = state.passphrase.as_ref().unwrap();
-
-            let db = match Database::open(&state.db_path) {
-                Ok(db) => db,
+            let (signers, addresses) = match state.db.with(|db| {
+                Ok::<_, solana_keyring::Error>((
+                    list_signers(db, None)?,
+                    AddressBook::new(db).list()?,
+                ))
+            }) {
+                Ok(found) => found,
                 Err(e) => return Response::error(ErrorCode::InternalError, e.to_string()),
             };
 
-            // Decode transaction
-            let tx_bytes: Vec<u8> =
-                match base64::engine::general_purpose::STANDARD.decode(&transaction) {
-                    Ok(b) => b,
-                    Err(e) => return Response::error(ErrorCode::InvalidTransaction, e.to_string()),
-                };
-
-            // Parse transaction to show details to user
-            let summary = match solana_keyring::transaction::summarize_transaction(&tx_bytes) {
-                Ok(s) => s.to_string(),
-                Err(_) => "Unable to parse transaction details".to_string(),
-            };
-
-            // Get signer label for display
-            let signer_label = db
-                .list_keypairs(None)
-                .ok()
-                .and_then(|keypairs| {
-                    keypairs
-                        .into_iter()
-                        .find(|k| k.pubkey == signer || k.label == signer)
-                        .map(|k| k.label)
-                })
-                .unwrap_or_else(|| signer.clone());
-
-            // Request biometric/user confirmation
-            use solana_keyring::biometric::AuthResult;
-            match solana_keyring::biometric::confirm_signing(&signer_label, &summary) {
-                Ok(AuthResult::Authenticated) => {
-                    // User confirmed, proceed with signing
-                }
-                Ok(AuthResult::Denied) => {
-                    return Response::error(ErrorCode::InternalError, "User cancelled signing");
-                }
-                Ok(AuthResult::NotAvailable) => {
-                    // Biometrics not available, proceed without confirmation
-                    eprintln!(
-                        "Biometric authentication not available, proceeding without confirmation"
+            if let Some(s) = signers
+                .into_iter()
+                .find(|s| s.pubkey == identifier || s.label == identifier)
+            {
+                // Signers outside the token's scope don't exist for it
+                if scope
+                    .as_ref()
+                    .is_some_and(|scope| !scope.allows_signer(&s.pubkey))
+                {
+                    return Response::error(
+                        ErrorCode::SignerNotFound,
+                        format!("Signer not found: {}", identifier),
                     );
                 }
-                Err(e) => {
-                    // If biometric fails, log but continue (non-fatal)
-                    eprintln!("Biometric check failed: {}", e);
+                return Response::ok(ResponseResult::Resolved(ResolvedLabel {
+                    pubkey: s.pubkey,
+                    label: s.label,
+                    kind: s.driver.unwrap_or_else(|| s.signer_type.to_string()),
+                }));
+            }
+
+            match addresses
+                .into_iter()
+                .find(|a| a.pubkey == identifier || a.label == identifier)
+            {
+                Some(a) => Response::ok(ResponseResult::Resolved(ResolvedLabel {
+                    pubkey: a.pubkey,
+                    label: a.label,
+                    kind: "address".to_string(),
+                })),
+                None => Response::error(
+                    ErrorCode::NotFound,
+                    format!("No signer or address found for '{}'", identifier),
+                ),
+            }
+        }
+
+        Request::ListAddresses => {
+            let state = state.read().await;
+
+            match state.db.with(|db| AddressBook::new(db).list()) {
+                Ok(addresses) => {
+                    let infos: Vec<AddressInfo> = addresses
+                        .into_iter()
+                        .map(|a| AddressInfo {
+                            pubkey: a.pubkey,
+                            label: a.label,
+                            notes: a.notes,
+                        })
+                        .collect();
+                    Response::ok(ResponseResult::Addresses(infos))
                 }
+                Err(e) => Response::error(ErrorCode::InternalError, e.to_string()),
             }
+        }
+
+        Request::SignTransaction {
+            transaction,
+            signer,
+        } => {
+            // Count the transfer against the token's cap before prompting
+            let reservation = match token.as_deref() {
+                Some(id) => match reserve_outflow(state, id, &transaction, &signer).await {
+                    Ok(lamports) => Some((id, lamports)),
+                    Err(response) => return response,
+                },
+                None => None,
+            };
 
-            // Load keypair and sign
-            match db.load_keypair(&signer, passphrase) {
-                Ok(keypair) => {
-                    let signature = keypair.sign(&tx_bytes);
-                    let sig_b64 = base64::engine::general_purpose::STANDARD.encode(signature);
+            let response = sign_transaction(state, &transaction, &signer).await;
+            if let (Some((id, lamports)), Response::Error { .. }) = (reservation, &response) {
+                state.read().await.tokens.release(id, lamports);
+            }
+            response
+        }
 
-                    // Send notification
-                    let _ = solana_keyring::notify(
-                        "Transaction Signed",
-                        &format!("Signed with {}", signer_label),
-                    );
+        Request::SignMessage { message, signer } => sign_message(state, &message, &signer).await,
 
-                    Response::ok(ResponseResult::SignedTransaction(sig_b64))
+        Request::Authenticate { token: secret } => {
+            match state.read().await.tokens.authenticate(&secret) {
+                Some(id) => {
+                    *token = Some(id);
+                    Response::ok(ResponseResult::Unit)
                 }
-                Err(e) => Response::error(ErrorCode::SignerNotFound, e.to_string()),
+                None => Response::error(
+                    ErrorCode::Unauthorized,
+                    "Invalid, expired or revoked session token",
+                ),
             }
         }
 
-        Request::GenerateKeypair { label, tags } => {
+        Request::IssueToken {
+            label,
+            mut scope,
+            ttl_seconds,
+        } => {
             let state = state.read().await;
+            if scope.permissions.is_empty() {
+                return Response::error(
+                    ErrorCode::InternalError,
+                    "A session token needs at least one permission",
+                );
+            }
 
-            if !state.is_unlocked() {
-                return Response::error(ErrorCode::Locked, "Agent is locked");
+            // Pin signers to public keys so relabeling a key can't widen the scope
+            let mut signers = Vec::with_capacity(scope.signers.len());
+            for signer in &scope.signers {
+                match state.db.with(|db| find_signer(db, signer)) {
+                    Ok(Some((pubkey, _))) => signers.push(pubkey),
+                    Ok(None) => {
+                        return Response::error(
+                            ErrorCode::SignerNotFound,
+                            format!("Signer not found: {}", signer),
+                        );
+                    }
+                    Err(e) => return Response::error(ErrorCode::InternalError, e.to_string()),
+                }
             }
+            scope.signers = signers;
 
-            let passphrase = state.passphrase.as_ref().unwrap();
+            let (id, secret) = state.tokens.issue(&label, scope, ttl_seconds);
+            Response::ok(ResponseResult::IssuedToken(IssuedToken {
+                id,
+                token: secret,
+            }))
+        }
 
-            let db = match Database::open(&state.db_path) {
-                Ok(db) => db,
-                Err(e) => return Response::error(ErrorCode::InternalError, e.to_string()),
+        Request::ListTokens => Response::ok(ResponseResult::Tokens(TokenList {
+            tokens: state.read().await.tokens.list(),
+        })),
+
+        Request::RevokeToken { id } => {
+            if state.read().await.tokens.revoke(&id) {
+                Response::ok(ResponseResult::Unit)
+            } else {
+                Response::error(ErrorCode::NotFound, format!("No session token {}", id))
+            }
+        }
+
+        Request::DelegatedSign {
+            transaction,
+            signer,
+            bot,
+            nonce,
+            signature,
+        } => {
+            let (db, queue) = {
+                let state = state.read().await;
+                (state.db.clone(), state.queue.clone())
             };
+            let signer_pubkey = db
+                .with(|db| find_signer(db, &signer))
+                .ok()
+                .flatten()
+                .map_or_else(|| signer.clone(), |(pubkey, _)| pubkey);
+            let _ticket = queue.enter(&signer_pubkey).await;
 
-            // Generate keypair
-            let keypair = solana_keyring::keypair::generate_keypair();
-            let pubkey = keypair.pubkey_base58();
+            // Nobody is prompted for delegated requests, so warnings are only logged
+            match check_cluster(&db, &signer_pubkey, &transaction).await {
+                Ok(None) => {}
+                Ok(Some(mismatch)) => {
+                    eprintln!("{}", output::warning(mismatch.to_string()));
+                }
+                Err(response) => return response,
+            }
 
-            // Convert tags to &str slice
-            let tag_refs: Vec<&str> = tags.iter().map(|s| s.as_str()).collect();
+            if !state.read().await.is_unlocked() {
+                return Response::error(ErrorCode::Locked, "Agent is locked");
+            }
+            let (prepared, delegation) = match db
+                .with(|db| authorize_delegation(db, &transaction, &signer, &bot, nonce, &signature))
+            {
+                Ok(authorized) => authorized,
+                Err(response) => return response,
+            };
+            // The nonce is consumed only once the request is signed, so a
+            // request refused at signing can be retried
+            let request = DelegatedRequest {
+                bot_pubkey: &bot,
+                signer_pubkey: &prepared.signer_pubkey,
+                message_bytes: &prepared.tx_bytes,
+                nonce,
+                signature: &signature,
+            };
+            let consume = |db: &Database| Delegations::new(db).consume(&delegation, &request);
+            match sign_prepared(state, &db, &signer, &prepared, consume).await {
+                Ok(sig_b64) => {
+                    let _ = solana_keyring::notify(
+                        "Transaction Signed",
+                        &format!("Signed with {} for bot {}", prepared.signer_label, bot),
+                    );
+                    Response::ok(ResponseResult::SignedTransaction(sig_b64))
+                }
+                Err(response) => response,
+            }
+        }
 
-            // Store in database
-            match db.store_keypair(&keypair, &label, passphrase, &tag_refs) {
-                Ok(()) => {
+        Request::GenerateKeypair { label, tags } => {
+            let keypair = solana_keyring::keypair::generate_keypair();
+            match store_keypair(state, keypair, &label, &tags).await {
+                Ok(pubkey) => {
                     let _ = solana_keyring::notify(
                         "Keypair Generated",
                         &format!("Generated keypair '{}': {}", label, pubkey),
@@ -346,7 +855,7 @@ async fn process_request(request: Request, state: &Arc<RwLock<AgentState>>) -> R
                         label,
                     }))
                 }
-                Err(e) => Response::error(ErrorCode::InternalError, e.to_string()),
+                Err(response) => response,
             }
         }
 
@@ -355,32 +864,17 @@ async fn process_request(request: Request, state: &Arc<RwLock<AgentState>>) -> R
             secret_key,
             tags,
         } => {
-            let state = state.read().await;
-
-            if !state.is_unlocked() {
+            if !state.read().await.is_unlocked() {
                 return Response::error(ErrorCode::Locked, "Agent is locked");
             }
 
-            let passphrase = state.passphrase.as_ref().unwrap();
-
-            let db = match Database::open(&state.db_path) {
-                Ok(db) => db,
-                Err(e) => return Response::error(ErrorCode::InternalError, e.to_string()),
-            };
-
             // Import keypair from base58
             let keypair = match solana_keyring::keypair::import_base58(&secret_key) {
                 Ok(k) => k,
                 Err(e) => return Response::error(ErrorCode::InternalError, e.to_string()),
             };
-            let pubkey = keypair.pubkey_base58();
-
-            // Convert tags to &str slice
-            let tag_refs: Vec<&str> = tags.iter().map(|s| s.as_str()).collect();
-
-            // Store in database
-            match db.store_keypair(&keypair, &label, passphrase, &tag_refs) {
-                Ok(()) => {
+            match store_keypair(state, keypair, &label, &tags).await {
+                Ok(pubkey) => {
                     let _ = solana_keyring::notify(
                         "Keypair Imported",
                         &format!("Imported keypair '{}': {}", label, pubkey),
@@ -390,7 +884,7 @@ async fn process_request(request: Request, state: &Arc<RwLock<AgentState>>) -> R
                         label,
                     }))
                 }
-                Err(e) => Response::error(ErrorCode::InternalError, e.to_string()),
+                Err(response) => response,
             }
         }
 
@@ -400,3 +894,887 @@ async fn process_request(request: Request, state: &Arc<RwLock<AgentState>>) -> R
         }
     }
 }
+
+/// A signing request that has passed policy checks and awaits confirmation
+struct PreparedSigning {
+    tx_bytes: Vec<u8>,
+    summary: String,
+    signer_pubkey: String,
+    signer_label: String,
+    outflow: u64,
+    new_destinations: Vec<String>,
+    /// The signer has a spending limit that can't count everything the
+    /// transaction may move, so it needs an explicit approval
+    uncounted: bool,
+    /// Transfers only to trusted destinations are approved without a prompt
+    auto_approved: bool,
+}
+
+async fn sign_transaction(
+    state: &Arc<RwLock<AgentState>>,
+    transaction: &str,
+    signer: &str,
+) -> Response {
+    // Copy what we need so no lock or database is held while awaiting approval
+    let (db, destination_policy, confirmer, queue) = {
+        let state = state.read().await;
+        if !state.is_unlocked() {
+            return Response::error(ErrorCode::Locked, "Agent is locked");
+        }
+        (
+            state.db.clone(),
+            state.destination_policy.clone(),
+            state.confirmer.clone(),
+            state.queue.clone(),
+        )
+    };
+
+    // Wait for earlier requests for this signer, so prompts don't stack up
+    let signer_pubkey = db
+        .with(|db| find_signer(db, signer))
+        .ok()
+        .flatten()
+        .map_or_else(|| signer.to_string(), |(pubkey, _)| pubkey);
+    let ticket = queue.enter(&signer_pubkey).await;
+
+    let mut prepared =
+        match db.with(|db| prepare_signing(db, &destination_policy, transaction, signer)) {
+            Ok(prepared) => prepared,
+            Err(response) => return response,
+        };
+
+    // Catch keys bound to another cluster; warnings always need a prompt
+    match check_cluster(&db, &prepared.signer_pubkey, transaction).await {
+        Ok(None) => {}
+        Ok(Some(mismatch)) => {
+            let warning = t_with(
+                "summary-cluster-mismatch",
+                &[
+                    ("signer", &prepared.signer_label),
+                    ("bound", &mismatch.binding.cluster),
+                    ("found", &mismatch.found),
+                ],
+            );
+            prepared.summary.push_str(&format!("\n{}\n", warning));
+            prepared.auto_approved = false;
+        }
+        Err(response) => return response,
+    }
+
+    // High-value keys always prompt, and only once per cool-down
+    let high_value = match db.with(|db| check_cooldown(db, &prepared.signer_pubkey)) {
+        Ok(high_value) => high_value,
+        Err(response) => return response,
+    };
+    if high_value.is_some() {
+        prepared.auto_approved = false;
+    }
+
+    // Request user confirmation, with a fingerprint to make key substitution obvious
+    if !prepared.auto_approved {
+        let mut summary = prepared.summary.clone();
+        let behind = ticket.behind();
+        if behind > 0 {
+            summary.push_str(&format!(
+                "\n{} more request(s) queued for this signer\n",
+                behind
+            ));
+        }
+        let signer_display = format!(
+            "{} [{}]",
+            prepared.signer_label,
+            fingerprint(&prepared.signer_pubkey)
+        );
+        match confirmer.confirm_signing(&signer_display, &summary).await {
+            Confirmation::Approved => {
+                // User confirmed, proceed with signing
+            }
+            Confirmation::Denied(reason) => {
+                return Response::error(ErrorCode::InternalError, reason);
+            }
+            Confirmation::Unavailable(reason) if !prepared.new_destinations.is_empty() => {
+                return Response::error(
+                    ErrorCode::ConfirmationRequired,
+                    format!("New destinations require confirmation: {}", reason),
+                );
+            }
+            Confirmation::Unavailable(reason) if prepared.uncounted => {
+                return Response::error(
+                    ErrorCode::ConfirmationRequired,
+                    format!(
+                        "Transactions a spending limit can't count require confirmation: {}",
+                        reason
+                    ),
+                );
+            }
+            Confirmation::Unavailable(reason) => {
+                // No confirmation mechanism, proceed without confirmation
+                eprintln!(
+                    "{}",
+                    output::warning(format!("{}, proceeding without confirmation", reason))
+                );
+            }
+        }
+    }
+
+    if high_value.is_some()
+        && let Err(response) = confirm_high_value(
+            &prepared.signer_label,
+            &prepared.summary,
+            Some(prepared.outflow),
+        )
+        .await
+    {
+        return response;
+    }
+
+    // The high-value check already covers the destinations in its summary
+    if high_value.is_none()
+        && !prepared.new_destinations.is_empty()
+        && let Err(response) =
+            confirm_new_destinations(&prepared.signer_label, &prepared.summary).await
+    {
+        return response;
+    }
+
+    // Ledger wallets sign on the device, which may need to be connected first
+    let ledger_path = match db.with(|db| find_ledger(db, &prepared.signer_pubkey)) {
+        Ok(path) => path,
+        Err(e) => return Response::error(ErrorCode::InternalError, e.to_string()),
+    };
+    // Custom signers sign through their registered driver
+    let custom = match db.with(|db| find_custom(db, &prepared.signer_pubkey)) {
+        Ok(row) => row,
+        Err(e) => return Response::error(ErrorCode::InternalError, e.to_string()),
+    };
+    let sig_b64 = if let Some(derivation_path) = ledger_path {
+        match sign_with_ledger(state, &db, &derivation_path, &prepared).await {
+            Ok(sig_b64) => sig_b64,
+            Err(response) => return response,
+        }
+    } else if let Some(row) = custom {
+        match sign_with_driver(state, &db, row, &prepared).await {
+            Ok(sig_b64) => sig_b64,
+            Err(response) => return response,
+        }
+    } else {
+        match sign_prepared(state, &db, signer, &prepared, |_| Ok(())).await {
+            Ok(sig_b64) => sig_b64,
+            Err(response) => return response,
+        }
+    };
+
+    // Offer to remember new destinations
+    if !prepared.new_destinations.is_empty() {
+        let reason = t_with(
+            "approval-learn-destinations",
+            &[("count", &prepared.new_destinations.len())],
+        );
+        if let Confirmation::Approved = confirmer.confirm(&reason).await {
+            db.with(|db| {
+                learn_destinations(db, &destination_policy, &prepared.new_destinations);
+                Ok::<_, solana_keyring::Error>(())
+            })
+            .unwrap_or_else(|e| {
+                eprintln!(
+                    "{}",
+                    output::error(format!("Failed to open database: {}", e))
+                );
+            });
+        }
+    }
+
+    // Send notification
+    let _ = solana_keyring::notify(
+        "Transaction Signed",
+        &format!("Signed with {}", prepared.signer_label),
+    );
+
+    Response::ok(ResponseResult::SignedTransaction(sig_b64))
+}
+
+/// Decode a transaction and run spending limit and allowlist checks
+fn prepare_signing(
+    db: &Database,
+    destination_policy: &DestinationPolicy,
+    transaction: &str,
+    signer: &str,
+) -> Result<PreparedSigning, Response> {
+    // Decode transaction
+    let tx_bytes: Vec<u8> = base64::engine::general_purpose::STANDARD
+        .decode(transaction)
+        .map_err(|e| Response::error(ErrorCode::InvalidTransaction, e.to_string()))?;
+
+    // Parse transaction to show details to user
+    let mut summary = match solana_keyring::transaction::summarize_transaction(&tx_bytes) {
+        Ok(s) => s.to_string(),
+        Err(_) => t("summary-unparsed"),
+    };
+
+    // Get signer label for display
+    let (signer_pubkey, signer_label) = find_signer(db, signer)
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| (signer.to_string(), signer.to_string()));
+
+    // Enforce spending limits before prompting the user
+    let outflow = determine_outflow(db, &tx_bytes, &signer_pubkey)?;
+    check_spending(db, &signer_pubkey, outflow)?;
+    let uncounted = uncounted_outflow(db, &tx_bytes, &signer_pubkey)?;
+    if uncounted {
+        summary.push_str(&format!("\n{}\n", t("summary-uncounted-outflow")));
+    }
+
+    // Check destinations against the allowlist
+    let new_destinations = if destination_policy.is_enabled() {
+        let destinations =
+            solana_keyring::transaction::transfer_destinations(&tx_bytes).unwrap_or_default();
+        let untrusted = Allowlist::new(db, destination_policy)
+            .untrusted(&destinations)
+            .map_err(|e| Response::error(ErrorCode::InternalError, e.to_string()))?;
+        // Only transfers to trusted destinations skip the prompt; anything
+        // else in the transaction could move funds some other way
+        let transfer_only =
+            solana_keyring::transaction::is_transfer_only(&tx_bytes).unwrap_or(false);
+        if transfer_only && !uncounted && untrusted.is_empty() {
+            return Ok(PreparedSigning {
+                tx_bytes,
+                summary,
+                signer_pubkey,
+                signer_label,
+                outflow,
+                new_destinations: Vec::new(),
+                uncounted,
+                auto_approved: true,
+            });
+        }
+        untrusted
+    } else {
+        Vec::new()
+    };
+
+    if !new_destinations.is_empty() {
+        summary.push_str(&format!("\n{}\n", t("summary-new-destinations")));
+        for dest in &new_destinations {
+            summary.push_str(&format!("  {}\n", dest));
+        }
+    }
+
+    Ok(PreparedSigning {
+        tx_bytes,
+        summary,
+        signer_pubkey,
+        signer_label,
+        outflow,
+        new_destinations,
+        uncounted,
+        auto_approved: false,
+    })
+}
+
+/// Sign an off-chain message after confirmation
+///
+/// The message is wrapped in the standard off-chain envelope before signing,
+/// so the signature can never be replayed as a transaction signature.
+async fn sign_message(state: &Arc<RwLock<AgentState>>, message: &str, signer: &str) -> Response {
+    let (db, confirmer, queue) = {
+        let state = state.read().await;
+        if !state.is_unlocked() {
+            return Response::error(ErrorCode::Locked, "Agent is locked");
+        }
+        (
+            state.db.clone(),
+            state.confirmer.clone(),
+            state.queue.clone(),
+        )
+    };
+
+    let message = match OffchainMessage::new(message) {
+        Ok(message) => message,
+        Err(e) => return Response::error(ErrorCode::InternalError, e.to_string()),
+    };
+    let (signer_pubkey, signer_label) = db
+        .with(|db| find_signer(db, signer))
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| (signer.to_string(), signer.to_string()));
+    let _ticket = queue.enter(&signer_pubkey).await;
+
+    let high_value = match db.with(|db| check_cooldown(db, &signer_pubkey)) {
+        Ok(high_value) => high_value,
+        Err(response) => return response,
+    };
+
+    let signer_display = format!("{} [{}]", signer_label, fingerprint(&signer_pubkey));
+    let summary = format!("{}\n{}\n", t("summary-offchain-message"), message.text());
+    match confirmer.confirm_signing(&signer_display, &summary).await {
+        Confirmation::Approved => {}
+        Confirmation::Denied(reason) => return Response::error(ErrorCode::InternalError, reason),
+        Confirmation::Unavailable(reason) => {
+            eprintln!(
+                "{}",
+                output::warning(format!("{}, proceeding without confirmation", reason))
+            );
+        }
+    }
+    if high_value.is_some()
+        && let Err(response) = confirm_high_value(&signer_label, &summary, None).await
+    {
+        return response;
+    }
+
+    let keypair = match load_keypair(state, &db, signer).await {
+        Ok(keypair) => keypair,
+        Err(response) => return response,
+    };
+
+    let state = state.read().await;
+    let _spending_guard = state
+        .spending_lock
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    if let Err(response) = db.with(|db| check_cooldown(db, &signer_pubkey)) {
+        return response;
+    }
+    let signature = keypair.sign(&message.serialize());
+    if let Err(e) =
+        db.with(|db| HighValueKeys::new(db).record_signature(&signer_pubkey, unix_now()))
+    {
+        eprintln!(
+            "{}",
+            output::error(format!("Failed to record signature: {}", e))
+        );
+    }
+    Response::ok(ResponseResult::SignedMessage(
+        base64::engine::general_purpose::STANDARD.encode(signature),
+    ))
+}
+
+/// Reserve a transaction's SOL outflow against a session token's cap
+///
+/// Returns the lamports reserved, for release if the request isn't signed.
+async fn reserve_outflow(
+    state: &Arc<RwLock<AgentState>>,
+    id: &str,
+    transaction: &str,
+    signer: &str,
+) -> Result<u64, Response> {
+    let (db, tokens) = {
+        let state = state.read().await;
+        (state.db.clone(), state.tokens.clone())
+    };
+    let outflow = db
+        .with(|db| {
+            let signer_pubkey =
+                find_signer(db, signer)?.map_or_else(|| signer.to_string(), |(pubkey, _)| pubkey);
+            Ok::<_, solana_keyring::Error>(
+                base64::engine::general_purpose::STANDARD
+                    .decode(transaction)
+                    .ok()
+                    .and_then(|bytes| {
+                        solana_keyring::transaction::sol_outflow(&bytes, &signer_pubkey).ok()
+                    }),
+            )
+        })
+        .map_err(|e| Response::error(ErrorCode::InternalError, e.to_string()))?;
+    tokens.reserve(id, outflow)?;
+    Ok(outflow.unwrap_or(0))
+}
+
+/// Check a request against the signer's cluster binding
+///
+/// The blockhash lookup runs on a blocking thread without holding the
+/// database. Mismatches are refused unless the binding only warns, in which
+/// case the mismatch is returned for the caller to surface.
+async fn check_cluster(
+    db: &SharedDatabase,
+    signer_pubkey: &str,
+    transaction: &str,
+) -> Result<Option<ClusterMismatch>, Response> {
+    let binding = db
+        .with(|db| ClusterBindings::new(db).get(signer_pubkey))
+        .map_err(|e| Response::error(ErrorCode::InternalError, e.to_string()))?;
+    let Some(binding) = binding else {
+        return Ok(None);
+    };
+    let tx_bytes: Vec<u8> = base64::engine::general_purpose::STANDARD
+        .decode(transaction)
+        .map_err(|e| Response::error(ErrorCode::InvalidTransaction, e.to_string()))?;
+
+    let mismatch = tokio::task::spawn_blocking(move || binding.check(&tx_bytes))
+        .await
+        .map_err(|e| Response::error(ErrorCode::InternalError, e.to_string()))?
+        .map_err(|e| Response::error(ErrorCode::InvalidTransaction, e.to_string()))?;
+
+    match mismatch {
+        Some(mismatch) if mismatch.binding.mode == BindingMode::Refuse => Err(Response::error(
+            ErrorCode::ClusterMismatch,
+            mismatch.to_string(),
+        )),
+        mismatch => Ok(mismatch),
+    }
+}
+
+/// Refuse a high-value key during its cool-down
+///
+/// Returns the key's designation, if it is high-value.
+fn check_cooldown(db: &Database, signer_pubkey: &str) -> Result<Option<HighValueKey>, Response> {
+    let key = HighValueKeys::new(db)
+        .get(signer_pubkey)
+        .map_err(|e| Response::error(ErrorCode::InternalError, e.to_string()))?;
+    match key {
+        Some(key) if key.remaining(unix_now()) > 0 => Err(Response::error(
+            ErrorCode::CooldownActive,
+            format!(
+                "{} is a high-value key and may sign again in {} seconds",
+                signer_pubkey,
+                key.remaining(unix_now())
+            ),
+        )),
+        key => Ok(key),
+    }
+}
+
+/// Require local biometric approval and, for transactions, the SOL amount
+/// typed in
+///
+/// This runs after the configured confirmer, so a chat approval alone can't
+/// release a high-value signature.
+async fn confirm_high_value(
+    signer_label: &str,
+    summary: &str,
+    outflow: Option<u64>,
+) -> Result<(), Response> {
+    let refused = |reason: String| Response::error(ErrorCode::ConfirmationRequired, reason);
+    let reason = format!(
+        "Approve a high-value signature with {}\n{}",
+        signer_label, summary
+    );
+    let signer_label = signer_label.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        authenticate_locally(&reason, || {
+            format!(
+                "{} is a high-value key, which needs biometric approval on this machine",
+                signer_label
+            )
+        })?;
+
+        let Some(outflow) = outflow else {
+            return Ok(());
+        };
+        let prompt = format!(
+            "Type the amount of SOL this transaction sends from {}",
+            signer_label
+        );
+        match biometric::prompt_text("Confirm amount", &prompt) {
+            Ok(Some(typed)) if amount_matches(&typed, outflow) => Ok(()),
+            Ok(Some(_)) => Err(refused(
+                "Typed amount does not match the transaction".into(),
+            )),
+            Ok(None) => Err(refused("Amount confirmation was cancelled".into())),
+            Err(e) => Err(refused(format!("Amount confirmation failed: {}", e))),
+        }
+    })
+    .await
+    .map_err(|e| Response::error(ErrorCode::InternalError, e.to_string()))?
+}
+
+/// Require local biometric approval to send to destinations not on the
+/// allowlist
+///
+/// Like [`confirm_high_value`], this runs after the configured confirmer, so
+/// a chat approval alone can't send funds somewhere new.
+async fn confirm_new_destinations(signer_label: &str, summary: &str) -> Result<(), Response> {
+    let reason = format!(
+        "Approve sending to new destinations with {}\n{}",
+        signer_label, summary
+    );
+    tokio::task::spawn_blocking(move || {
+        authenticate_locally(&reason, || {
+            "New destinations need biometric approval on this machine".to_string()
+        })
+    })
+    .await
+    .map_err(|e| Response::error(ErrorCode::InternalError, e.to_string()))?
+}
+
+/// Ask for biometric (or passcode) approval at this machine, blocking
+///
+/// `unavailable` explains the refusal when the machine can't ask.
+fn authenticate_locally(
+    reason: &str,
+    unavailable: impl FnOnce() -> String,
+) -> Result<(), Response> {
+    let refused = |reason: String| Response::error(ErrorCode::ConfirmationRequired, reason);
+    if !biometric::is_available() && !biometric::is_passcode_available() {
+        return Err(refused(unavailable()));
+    }
+    match biometric::authenticate(reason) {
+        Ok(AuthResult::Authenticated) => Ok(()),
+        Ok(_) => Err(refused("Biometric approval was not given".into())),
+        Err(e) => Err(refused(format!("Biometric check failed: {}", e))),
+    }
+}
+
+/// Look up a keypair, Ledger wallet or custom signer by public key or label,
+/// returning its pubkey and label
+fn find_signer(db: &Database, signer: &str) -> solana_keyring::Result<Option<(String, String)>> {
+    if let Some(keypair) = db
+        .list_keypairs(None)?
+        .into_iter()
+        .find(|k| k.pubkey == signer || k.label == signer)
+    {
+        return Ok(Some((keypair.pubkey, keypair.label)));
+    }
+    if let Some(wallet) = db
+        .list_ledger_wallets(None)?
+        .into_iter()
+        .find(|w| w.pubkey == signer || w.label == signer)
+    {
+        return Ok(Some((wallet.pubkey, wallet.label)));
+    }
+    Ok(db
+        .get_custom_signer(signer)?
+        .map(|row| (row.pubkey, row.label)))
+}
+
+/// The derivation path of a Ledger wallet, if the signer is one
+fn find_ledger(db: &Database, signer_pubkey: &str) -> solana_keyring::Result<Option<String>> {
+    Ok(db
+        .list_ledger_wallets(None)?
+        .into_iter()
+        .find(|w| w.pubkey == signer_pubkey)
+        .map(|w| w.derivation_path))
+}
+
+/// The stored custom signer, if the signer is one
+fn find_custom(
+    db: &Database,
+    signer_pubkey: &str,
+) -> solana_keyring::Result<Option<CustomSignerRow>> {
+    Ok(db
+        .list_custom_signers(None)?
+        .into_iter()
+        .find(|row| row.pubkey == signer_pubkey))
+}
+
+/// The SOL a transaction moves out of the signer
+///
+/// An unparseable transaction counts as zero only for signers without
+/// spending limits; limited signers are refused, since their outflow can't
+/// be checked.
+fn determine_outflow(db: &Database, tx_bytes: &[u8], signer_pubkey: &str) -> Result<u64, Response> {
+    let e = match solana_keyring::transaction::sol_outflow(tx_bytes, signer_pubkey) {
+        Ok(outflow) => return Ok(outflow),
+        Err(e) => e,
+    };
+    let tags = db.get_signer_tags(signer_pubkey).unwrap_or_default();
+    match SpendingPolicy::new(db).is_limited(signer_pubkey, &tags) {
+        Ok(false) => Ok(0),
+        Ok(true) => Err(Response::error(
+            ErrorCode::SpendingLimitExceeded,
+            format!(
+                "Can't determine the transaction's outflow for a spending limit: {}",
+                e
+            ),
+        )),
+        Err(e) => Err(Response::error(ErrorCode::InternalError, e.to_string())),
+    }
+}
+
+/// Whether a spending-limited signer's transaction could move SOL that
+/// [`determine_outflow`] doesn't count
+fn uncounted_outflow(
+    db: &Database,
+    tx_bytes: &[u8],
+    signer_pubkey: &str,
+) -> Result<bool, Response> {
+    if solana_keyring::transaction::is_outflow_countable(tx_bytes).unwrap_or(false) {
+        return Ok(false);
+    }
+    let tags = db.get_signer_tags(signer_pubkey).unwrap_or_default();
+    SpendingPolicy::new(db)
+        .is_limited(signer_pubkey, &tags)
+        .map_err(|e| Response::error(ErrorCode::InternalError, e.to_string()))
+}
+
+fn check_spending(db: &Database, signer_pubkey: &str, outflow: u64) -> Result<(), Response> {
+    let tags = db.get_signer_tags(signer_pubkey).unwrap_or_default();
+    match SpendingPolicy::new(db).check(signer_pubkey, &tags, outflow, unix_now()) {
+        Ok(None) => Ok(()),
+        Ok(Some(denial)) => Err(Response::error(
+            ErrorCode::SpendingLimitExceeded,
+            denial.to_string(),
+        )),
+        Err(e) => Err(Response::error(ErrorCode::InternalError, e.to_string())),
+    }
+}
+
+/// Sign a confirmed request with a stored keypair and record the spend
+///
+/// The key is decrypted first, without holding the database. Spending
+/// limits are then checked again under the spending lock, since other
+/// requests may have been signed while this one was awaiting confirmation.
+/// `commit` runs in the same transaction as the spend once the signature
+/// exists; if it fails, the signature is withheld.
+async fn sign_prepared(
+    state: &Arc<RwLock<AgentState>>,
+    db: &SharedDatabase,
+    signer: &str,
+    prepared: &PreparedSigning,
+    commit: impl FnOnce(&Database) -> solana_keyring::Result<()>,
+) -> Result<String, Response> {
+    let keypair = load_keypair(state, db, signer).await?;
+
+    let state = state.read().await;
+    let _spending_guard = state
+        .spending_lock
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    db.with(|db| {
+        check_spending(db, &prepared.signer_pubkey, prepared.outflow)?;
+        check_cooldown(db, &prepared.signer_pubkey)?;
+        let signature = keypair.sign(&prepared.tx_bytes);
+        db.immediate(|db| {
+            commit(db)?;
+            record_spend(db, prepared);
+            Ok(())
+        })
+        .map_err(|e| match e {
+            solana_keyring::Error::Policy(_) => {
+                Response::error(ErrorCode::Unauthorized, e.to_string())
+            }
+            e => Response::error(ErrorCode::InternalError, e.to_string()),
+        })?;
+        Ok(base64::engine::general_purpose::STANDARD.encode(signature))
+    })
+}
+
+/// Record a signed request against spending limits, the signer's last use and
+/// its cool-down
+fn record_spend(db: &Database, prepared: &PreparedSigning) {
+    let policy = SpendingPolicy::new(db);
+    let now = unix_now();
+    if let Err(e) = policy
+        .record(&prepared.signer_pubkey, prepared.outflow, now)
+        .and_then(|_| policy.prune(now))
+    {
+        eprintln!(
+            "{}",
+            output::error(format!("Failed to record spend: {}", e))
+        );
+    }
+    if let Err(e) = db.record_signer_use(&prepared.signer_pubkey, now) {
+        eprintln!(
+            "{}",
+            output::error(format!("Failed to record signer use: {}", e))
+        );
+    }
+    if let Err(e) = HighValueKeys::new(db).record_signature(&prepared.signer_pubkey, now) {
+        eprintln!(
+            "{}",
+            output::error(format!("Failed to record signature: {}", e))
+        );
+    }
+}
+
+/// Sign a confirmed request on a Ledger
+///
+/// If no device is attached, the request stays parked (holding its place in
+/// the signer's queue) while the user is asked to connect one, and resumes
+/// when it appears. Spending limits are checked before and after, since the
+/// user may take a while to connect and approve on the device.
+async fn sign_with_ledger(
+    state: &Arc<RwLock<AgentState>>,
+    db: &SharedDatabase,
+    derivation_path: &str,
+    prepared: &PreparedSigning,
+) -> Result<String, Response> {
+    db.with(|db| check_spending(db, &prepared.signer_pubkey, prepared.outflow))?;
+
+    if !ledger::is_connected() {
+        let awaiting = state.read().await.awaiting_device.clone();
+        awaiting.fetch_add(1, Ordering::SeqCst);
+        eprintln!(
+            "Signing request for {} is waiting for its Ledger to be connected",
+            prepared.signer_label
+        );
+        let _ = solana_keyring::notify(
+            "Connect Your Ledger",
+            &format!(
+                "A signing request for {} is waiting; connect and unlock the device",
+                prepared.signer_label
+            ),
+        );
+
+        let connected =
+            tokio::task::spawn_blocking(|| ledger::wait_for_device(LEDGER_CONNECT_TIMEOUT)).await;
+        awaiting.fetch_sub(1, Ordering::SeqCst);
+        if !matches!(connected, Ok(true)) {
+            return Err(Response::error(
+                ErrorCode::HardwareError,
+                format!(
+                    "Ledger was not connected within {} seconds",
+                    LEDGER_CONNECT_TIMEOUT.as_secs()
+                ),
+            ));
+        }
+    }
+
+    let path = derivation_path.to_string();
+    let tx_bytes = prepared.tx_bytes.clone();
+    let signed = tokio::task::spawn_blocking(move || {
+        let ledger = LedgerSigner::connect(&path)?;
+        Ok::<_, solana_keyring::Error>((ledger.pubkey().to_string(), ledger.sign(&tx_bytes)?))
+    })
+    .await
+    .map_err(|e| Response::error(ErrorCode::InternalError, e.to_string()))?;
+    let (pubkey, signature) = signed.map_err(Response::from)?;
+
+    // Another device or account may have been plugged in
+    if pubkey != prepared.signer_pubkey {
+        return Err(Response::error(
+            ErrorCode::HardwareError,
+            format!(
+                "Connected Ledger has {} at {}, expected {}",
+                pubkey, derivation_path, prepared.signer_pubkey
+            ),
+        ));
+    }
+
+    record_external_signature(state, db, prepared).await?;
+
+    Ok(base64::engine::general_purpose::STANDARD.encode(signature))
+}
+
+/// Sign a confirmed request with a custom signer's driver
+///
+/// The driver may call out to a remote provider and take a while, so
+/// spending limits are checked before and after, as for Ledgers.
+async fn sign_with_driver(
+    state: &Arc<RwLock<AgentState>>,
+    db: &SharedDatabase,
+    row: CustomSignerRow,
+    prepared: &PreparedSigning,
+) -> Result<String, Response> {
+    db.with(|db| check_spending(db, &prepared.signer_pubkey, prepared.outflow))?;
+
+    let tx_bytes = prepared.tx_bytes.clone();
+    let signature = tokio::task::spawn_blocking(move || driver::connect(&row)?.sign(&tx_bytes))
+        .await
+        .map_err(|e| Response::error(ErrorCode::InternalError, e.to_string()))?
+        .map_err(Response::from)?;
+
+    record_external_signature(state, db, prepared).await?;
+
+    Ok(base64::engine::general_purpose::STANDARD.encode(signature))
+}
+
+/// Recheck limits and record a signature made outside the agent, on a device
+/// or by a driver
+async fn record_external_signature(
+    state: &Arc<RwLock<AgentState>>,
+    db: &SharedDatabase,
+    prepared: &PreparedSigning,
+) -> Result<(), Response> {
+    let state = state.read().await;
+    let _spending_guard = state
+        .spending_lock
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    db.with(|db| {
+        check_spending(db, &prepared.signer_pubkey, prepared.outflow)?;
+        check_cooldown(db, &prepared.signer_pubkey)?;
+        record_spend(db, prepared);
+        Ok(())
+    })
+}
+
+/// Authorize a bot's request under a delegation, without a confirmation prompt
+///
+/// The delegation is checked on every request, so a revoked delegation stops
+/// working immediately; the returned delegation consumes the request's nonce
+/// once it is signed. Spending limits still apply.
+fn authorize_delegation(
+    db: &Database,
+    transaction: &str,
+    signer: &str,
+    bot: &str,
+    nonce: u64,
+    signature: &str,
+) -> Result<(PreparedSigning, DelegationRow), Response> {
+    let tx_bytes: Vec<u8> = base64::engine::general_purpose::STANDARD
+        .decode(transaction)
+        .map_err(|e| Response::error(ErrorCode::InvalidTransaction, e.to_string()))?;
+
+    let (signer_pubkey, signer_label) =
+        find_signer(db, signer).ok().flatten().ok_or_else(|| {
+            Response::error(
+                ErrorCode::SignerNotFound,
+                format!("Signer not found: {}", signer),
+            )
+        })?;
+
+    // High-value keys need someone at the machine for every signature
+    let high_value = HighValueKeys::new(db)
+        .get(&signer_pubkey)
+        .map_err(|e| Response::error(ErrorCode::InternalError, e.to_string()))?;
+    if high_value.is_some() {
+        return Err(Response::error(
+            ErrorCode::Unauthorized,
+            format!(
+                "{} is a high-value key and can't sign through a delegation",
+                signer_label
+            ),
+        ));
+    }
+
+    // Check limits before consuming the nonce, so a refused request can be retried
+    let outflow = determine_outflow(db, &tx_bytes, &signer_pubkey)?;
+    check_spending(db, &signer_pubkey, outflow)?;
+    // Nobody is asked, so a limit that can't count the outflow refuses it
+    if uncounted_outflow(db, &tx_bytes, &signer_pubkey)? {
+        return Err(Response::error(
+            ErrorCode::SpendingLimitExceeded,
+            format!(
+                "{} has a spending limit, which can't count what this transaction may move",
+                signer_label
+            ),
+        ));
+    }
+
+    let delegation = Delegations::new(db)
+        .check(&DelegatedRequest {
+            bot_pubkey: bot,
+            signer_pubkey: &signer_pubkey,
+            message_bytes: &tx_bytes,
+            nonce,
+            signature,
+        })
+        .map_err(|e| Response::error(ErrorCode::Unauthorized, e.to_string()))?;
+
+    let prepared = PreparedSigning {
+        tx_bytes,
+        summary: String::new(),
+        signer_pubkey,
+        signer_label,
+        outflow,
+        new_destinations: Vec::new(),
+        uncounted: false,
+        auto_approved: true,
+    };
+    Ok((prepared, delegation))
+}
+
+fn learn_destinations(db: &Database, policy: &DestinationPolicy, destinations: &[String]) {
+    let allowlist = Allowlist::new(db, policy);
+    for dest in destinations {
+        if let Err(e) = allowlist.learn(dest) {
+            eprintln!(
+                "{}",
+                output::error(format!("Failed to add {} to address book: {}", dest, e))
+            );
+        }
+    }
+}
@@ -0,0 +1,297 @@
+//! Discord approval backend
+//!
+//! Requests are posted to a channel as messages with button components.
+//! Button presses arrive at the application's interactions endpoint, verified
+//! with the application's Ed25519 public key (`X-Signature-Ed25519`).
+
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde_json::{Value, json};
+
+use super::http::{HttpRequest, HttpResponse};
+use super::{Action, ApprovalConfig, ChatApproval};
+
+/// Interaction type: PING
+const INTERACTION_PING: u64 = 1;
+/// Interaction type: MESSAGE_COMPONENT
+const INTERACTION_COMPONENT: u64 = 3;
+
+/// Post an approval request to the configured channel
+pub async fn post(
+    http: &reqwest::Client,
+    config: &ApprovalConfig,
+    request_id: &str,
+    title: &str,
+    text: &str,
+) -> anyhow::Result<()> {
+    let body = json!({
+        "content": format!("**{}**\n```\n{}\n```", title, text),
+        "components": [{
+            "type": 1,
+            "components": [
+                { "type": 2, "style": 3, "label": "Approve", "custom_id": format!("approve:{}", request_id) },
+                { "type": 2, "style": 4, "label": "Deny", "custom_id": format!("deny:{}", request_id) }
+            ]
+        }]
+    });
+
+    http.post(format!(
+        "https://discord.com/api/v10/channels/{}/messages",
+        config.channel
+    ))
+    .header("Authorization", format!("Bot {}", config.bot_token))
+    .json(&body)
+    .send()
+    .await?
+    .error_for_status()?;
+
+    Ok(())
+}
+
+/// Handle an interaction callback
+pub fn handle(chat: &ChatApproval, request: &HttpRequest) -> HttpResponse {
+    let public_key = chat.config.public_key.as_deref().unwrap_or_default();
+    let verified = match (
+        request.header("x-signature-timestamp"),
+        request.header("x-signature-ed25519"),
+    ) {
+        (Some(ts), Some(sig)) => verify(public_key, ts, &request.body, sig),
+        _ => false,
+    };
+    if !verified {
+        return HttpResponse::status(401);
+    }
+
+    let Ok(interaction) = serde_json::from_slice::<Value>(&request.body) else {
+        return HttpResponse::status(400);
+    };
+
+    match interaction.get("type").and_then(Value::as_u64) {
+        Some(INTERACTION_PING) => HttpResponse::json(json!({ "type": 1 })),
+        Some(INTERACTION_COMPONENT) => {
+            let Some(action) = parse_action(&interaction) else {
+                return HttpResponse::status(400);
+            };
+
+            if !chat.is_approver(&action.user) {
+                // Ephemeral reply, leave the buttons in place
+                return HttpResponse::json(json!({
+                    "type": 4,
+                    "data": { "content": "You are not an authorized approver", "flags": 64 }
+                }));
+            }
+
+            let content = if chat.resolve(&action) {
+                let verb = if action.approved {
+                    "Approved"
+                } else {
+                    "Denied"
+                };
+                format!("{} by <@{}>", verb, action.user)
+            } else {
+                "This request has already been decided or has expired".to_string()
+            };
+
+            // Update the original message and remove the buttons
+            HttpResponse::json(json!({
+                "type": 7,
+                "data": { "content": content, "components": [] }
+            }))
+        }
+        _ => HttpResponse::status(400),
+    }
+}
+
+/// Verify a Discord interaction signature
+fn verify(public_key_hex: &str, timestamp: &str, body: &[u8], signature_hex: &str) -> bool {
+    let Some(key) = hex::decode(public_key_hex)
+        .ok()
+        .and_then(|k| <[u8; 32]>::try_from(k).ok())
+        .and_then(|k| VerifyingKey::from_bytes(&k).ok())
+    else {
+        return false;
+    };
+    let Some(signature) = hex::decode(signature_hex)
+        .ok()
+        .and_then(|s| <[u8; 64]>::try_from(s).ok())
+        .map(|s| Signature::from_bytes(&s))
+    else {
+        return false;
+    };
+
+    let mut message = timestamp.as_bytes().to_vec();
+    message.extend_from_slice(body);
+    key.verify(&message, &signature).is_ok()
+}
+
+/// Parse a component interaction into an action
+fn parse_action(interaction: &Value) -> Option<Action> {
+    let custom_id = interaction.get("data")?.get("custom_id")?.as_str()?;
+    let (verb, request_id) = custom_id.split_once(':')?;
+    let approved = match verb {
+        "approve" => true,
+        "deny" => false,
+        _ => return None,
+    };
+
+    // Guild interactions carry `member.user`, DMs carry `user`
+    let user = interaction
+        .get("member")
+        .and_then(|m| m.get("user"))
+        .or_else(|| interaction.get("user"))?
+        .get("id")?
+        .as_str()?;
+
+    Some(Action {
+        request_id: request_id.to_string(),
+        approved,
+        user: user.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::{Signer, SigningKey};
+
+    use super::*;
+
+    /// RFC 8032 test vector 1 secret key
+    const SEED: &str = "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60";
+    const PUBLIC_KEY: &str = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+    const TIMESTAMP: &str = "1700000000";
+    const BODY: &str =
+        r#"{"type":3,"data":{"custom_id":"approve:req1"},"member":{"user":{"id":"42"}}}"#;
+    const SIGNATURE: &str = "006b262a45760a6f65f1081cf2b72f8a0cf175945ccb3d2ac8da86dd06220025ce5dda388c9cb7af1ddda8388c45135c036fe8eb8a3cecb742acf25acc8d620c";
+
+    fn signing_key() -> SigningKey {
+        SigningKey::from_bytes(&hex::decode(SEED).unwrap().try_into().unwrap())
+    }
+
+    fn chat() -> ChatApproval {
+        ChatApproval::new(
+            serde_json::from_value(json!({
+                "platform": "discord",
+                "bot_token": "bot-test",
+                "channel": "1",
+                "public_key": PUBLIC_KEY,
+                "approvers": ["42"],
+            }))
+            .unwrap(),
+        )
+    }
+
+    fn callback(interaction: Value) -> HttpRequest {
+        let body = interaction.to_string();
+        let mut message = TIMESTAMP.as_bytes().to_vec();
+        message.extend_from_slice(body.as_bytes());
+        HttpRequest {
+            method: "POST".to_string(),
+            path: "/".to_string(),
+            headers: [
+                ("x-signature-timestamp".to_string(), TIMESTAMP.to_string()),
+                (
+                    "x-signature-ed25519".to_string(),
+                    hex::encode(signing_key().sign(&message).to_bytes()),
+                ),
+            ]
+            .into(),
+            body: body.into_bytes(),
+        }
+    }
+
+    fn button(custom_id: &str, user: &str) -> Value {
+        json!({
+            "type": INTERACTION_COMPONENT,
+            "data": { "custom_id": custom_id },
+            "member": { "user": { "id": user } },
+        })
+    }
+
+    #[test]
+    fn test_verify_known_vector() {
+        assert_eq!(
+            hex::encode(signing_key().verifying_key().to_bytes()),
+            PUBLIC_KEY
+        );
+        assert!(verify(PUBLIC_KEY, TIMESTAMP, BODY.as_bytes(), SIGNATURE));
+    }
+
+    #[test]
+    fn test_verify_tampered() {
+        let tampered = BODY.replace("approve", "deny");
+        assert!(!verify(
+            PUBLIC_KEY,
+            TIMESTAMP,
+            tampered.as_bytes(),
+            SIGNATURE
+        ));
+        assert!(!verify(
+            PUBLIC_KEY,
+            "1700000001",
+            BODY.as_bytes(),
+            SIGNATURE
+        ));
+
+        let other_key = hex::encode(SigningKey::from_bytes(&[7; 32]).verifying_key().to_bytes());
+        assert!(!verify(&other_key, TIMESTAMP, BODY.as_bytes(), SIGNATURE));
+        assert!(!verify(
+            PUBLIC_KEY,
+            TIMESTAMP,
+            BODY.as_bytes(),
+            &SIGNATURE[..64]
+        ));
+        assert!(!verify("not-hex", TIMESTAMP, BODY.as_bytes(), SIGNATURE));
+    }
+
+    #[test]
+    fn test_parse_action() {
+        let interaction: Value = serde_json::from_str(BODY).unwrap();
+        assert_eq!(
+            parse_action(&interaction),
+            Some(Action {
+                request_id: "req1".to_string(),
+                approved: true,
+                user: "42".to_string(),
+            })
+        );
+
+        // DMs carry `user` instead of `member.user`
+        let dm = json!({ "data": { "custom_id": "deny:req2" }, "user": { "id": "7" } });
+        let action = parse_action(&dm).unwrap();
+        assert!(!action.approved);
+        assert_eq!(action.user, "7");
+
+        assert!(parse_action(&button("maybe:req1", "42")).is_none());
+        assert!(parse_action(&button("approve", "42")).is_none());
+    }
+
+    #[test]
+    fn test_handle_ping() {
+        let response = handle(&chat(), &callback(json!({ "type": INTERACTION_PING })));
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, r#"{"type":1}"#);
+    }
+
+    #[test]
+    fn test_handle_unsigned() {
+        let chat = chat();
+        let mut request = callback(button("approve:req1", "42"));
+        request.body = button("approve:req1", "99").to_string().into_bytes();
+        assert_eq!(handle(&chat, &request).status, 401);
+    }
+
+    #[test]
+    fn test_handle_non_approver() {
+        let chat = chat();
+        let (tx, mut rx) = tokio::sync::oneshot::channel();
+        chat.pending.lock().unwrap().insert("req1".to_string(), tx);
+
+        let response = handle(&chat, &callback(button("approve:req1", "99")));
+        assert!(response.body.contains("not an authorized approver"));
+        assert!(rx.try_recv().is_err());
+        assert!(chat.pending.lock().unwrap().contains_key("req1"));
+
+        let response = handle(&chat, &callback(button("approve:req1", "42")));
+        assert!(response.body.contains("Approved by <@42>"));
+        assert!(rx.try_recv().unwrap().approved);
+    }
+}
@@ -0,0 +1,215 @@
+//! Minimal HTTP/1.1 handling for interaction callbacks and remote signing
+
+use std::collections::HashMap;
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+const MAX_HEADER_BYTES: usize = 16 * 1024;
+const MAX_BODY_BYTES: usize = 1_048_576;
+
+/// A parsed HTTP request
+pub struct HttpRequest {
+    pub method: String,
+    pub path: String,
+    /// Header values keyed by lowercased name
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+impl HttpRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(String::as_str)
+    }
+}
+
+/// An HTTP response to send back
+pub struct HttpResponse {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn ok() -> Self {
+        Self::status(200)
+    }
+
+    pub fn status(status: u16) -> Self {
+        Self {
+            status,
+            content_type: "text/plain",
+            body: String::new(),
+        }
+    }
+
+    pub fn json(value: serde_json::Value) -> Self {
+        Self {
+            status: 200,
+            content_type: "application/json",
+            body: value.to_string(),
+        }
+    }
+}
+
+/// Read a request (headers and `Content-Length` body) from the stream
+pub async fn read_request<S: AsyncRead + Unpin>(stream: &mut S) -> anyhow::Result<HttpRequest> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+
+    let header_end = loop {
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+        if buf.len() > MAX_HEADER_BYTES {
+            anyhow::bail!("Request headers too large");
+        }
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            anyhow::bail!("Connection closed before headers were complete");
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+
+    let head = std::str::from_utf8(&buf[..header_end])?;
+    let mut request_line = head.lines().next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default().to_string();
+    let path = request_line.next().unwrap_or_default().to_string();
+    let headers: HashMap<String, String> = head
+        .lines()
+        .skip(1)
+        .filter_map(|line| line.split_once(':'))
+        .map(|(k, v)| (k.trim().to_ascii_lowercase(), v.trim().to_string()))
+        .collect();
+
+    let content_length: usize = headers
+        .get("content-length")
+        .map(|v| v.parse())
+        .transpose()?
+        .unwrap_or(0);
+    if content_length > MAX_BODY_BYTES {
+        anyhow::bail!("Request body too large");
+    }
+
+    let mut body = buf[header_end + 4..].to_vec();
+    while body.len() < content_length {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            anyhow::bail!("Connection closed before body was complete");
+        }
+        body.extend_from_slice(&chunk[..n]);
+    }
+    body.truncate(content_length);
+
+    Ok(HttpRequest {
+        method,
+        path,
+        headers,
+        body,
+    })
+}
+
+/// Write a response and close the connection
+pub async fn write_response<S: AsyncWrite + Unpin>(
+    stream: &mut S,
+    response: HttpResponse,
+) -> anyhow::Result<()> {
+    let reason = match response.status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Error",
+    };
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        reason,
+        response.content_type,
+        response.body.len()
+    );
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(response.body.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn read(raw: &[u8]) -> anyhow::Result<HttpRequest> {
+        read_request(&mut &raw[..]).await
+    }
+
+    #[tokio::test]
+    async fn test_read_request() {
+        let request = read(b"POST /callback HTTP/1.1\r\nX-Slack-Signature: v0=ab\r\nContent-Length: 5\r\n\r\nhello")
+            .await
+            .unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/callback");
+        assert_eq!(request.header("x-slack-signature"), Some("v0=ab"));
+        assert_eq!(request.body, b"hello");
+    }
+
+    #[tokio::test]
+    async fn test_body_beyond_content_length() {
+        let request = read(b"POST / HTTP/1.1\r\nContent-Length: 2\r\n\r\nhello")
+            .await
+            .unwrap();
+        assert_eq!(request.body, b"he");
+    }
+
+    #[tokio::test]
+    async fn test_missing_content_length() {
+        let request = read(b"POST / HTTP/1.1\r\nHost: x\r\n\r\nhello")
+            .await
+            .unwrap();
+        assert!(request.body.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_content_length() {
+        assert!(
+            read(b"POST / HTTP/1.1\r\nContent-Length: abc\r\n\r\n")
+                .await
+                .is_err()
+        );
+        assert!(
+            read(b"POST / HTTP/1.1\r\nContent-Length: -1\r\n\r\n")
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_oversized() {
+        let raw = format!(
+            "POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+            MAX_BODY_BYTES + 1
+        );
+        assert!(read(raw.as_bytes()).await.is_err());
+
+        let mut raw = b"POST / HTTP/1.1\r\nX-Padding: ".to_vec();
+        raw.resize(MAX_HEADER_BYTES + 4096, b'a');
+        raw.extend_from_slice(b"\r\n\r\n");
+        assert!(read(&raw).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_truncated() {
+        assert!(
+            read(b"POST / HTTP/1.1\r\nContent-Length: 10\r\n\r\nhello")
+                .await
+                .is_err()
+        );
+        assert!(
+            read(b"POST / HTTP/1.1\r\nContent-Length: 5\r\n")
+                .await
+                .is_err()
+        );
+        assert!(read(b"").await.is_err());
+    }
+}
@@ -0,0 +1,308 @@
+//! Signing approval backends
+//!
+//! A [`Confirmer`] decides whether a signing request may proceed. The default
+//! asks for biometric confirmation locally; the chat backends post the request
+//! to Slack or Discord with approve/deny buttons and hold it until an authorized
+//! approver responds or the timeout expires. A headless agent has nobody to
+//! ask and relies on policy checks alone.
+
+mod discord;
+pub(crate) mod http;
+mod slack;
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::Deserialize;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+
+use solana_keyring::biometric::{self, AuthResult, BiometricError};
+use solana_keyring::i18n::t_with;
+use solana_keyring::output;
+
+use crate::telemetry;
+
+/// Outcome of asking for confirmation
+pub enum Confirmation {
+    /// The request was approved
+    Approved,
+    /// The request was denied (or could not be approved)
+    Denied(String),
+    /// No confirmation mechanism is available
+    Unavailable(String),
+}
+
+/// Backend used to confirm signing requests
+#[derive(Clone)]
+pub enum Confirmer {
+    /// Local biometric prompt (TouchID)
+    Biometric,
+    /// Remote approval through Slack or Discord
+    Chat(Arc<ChatApproval>),
+    /// No approver; requests pass on policy checks alone
+    Headless,
+}
+
+impl Confirmer {
+    /// Ask for confirmation of a signing request
+    pub async fn confirm_signing(&self, signer_label: &str, summary: &str) -> Confirmation {
+        telemetry::confirmation(self.name(), async {
+            match self {
+                Confirmer::Biometric => {
+                    let reason = format!(
+                        "{}\n{}",
+                        t_with("approval-sign", &[("signer", &signer_label)]),
+                        summary
+                    );
+                    from_auth_result(biometric::authenticate(&reason))
+                }
+                Confirmer::Chat(chat) => {
+                    let title = t_with("approval-sign-request", &[("signer", &signer_label)]);
+                    chat.request(&title, summary).await
+                }
+                Confirmer::Headless => headless(),
+            }
+        })
+        .await
+    }
+
+    /// Ask a yes/no follow-up question
+    pub async fn confirm(&self, reason: &str) -> Confirmation {
+        telemetry::confirmation(self.name(), async {
+            match self {
+                Confirmer::Biometric => from_auth_result(biometric::authenticate(reason)),
+                Confirmer::Chat(chat) => chat.request("Follow-up", reason).await,
+                Confirmer::Headless => headless(),
+            }
+        })
+        .await
+    }
+
+    /// Human-readable backend name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Confirmer::Biometric => "biometric",
+            Confirmer::Chat(chat) => match chat.config.platform {
+                Platform::Slack => "slack",
+                Platform::Discord => "discord",
+            },
+            Confirmer::Headless => "headless",
+        }
+    }
+}
+
+/// Headless agents never prompt, so callers fall back to policy
+fn headless() -> Confirmation {
+    Confirmation::Unavailable("No approver in headless mode".into())
+}
+
+fn from_auth_result(result: Result<AuthResult, BiometricError>) -> Confirmation {
+    match result {
+        Ok(AuthResult::Authenticated) => Confirmation::Approved,
+        Ok(AuthResult::Denied) => Confirmation::Denied("User cancelled signing".into()),
+        Ok(AuthResult::NotAvailable) => {
+            Confirmation::Unavailable("Biometric authentication not available".into())
+        }
+        Err(e) => Confirmation::Unavailable(format!("Biometric check failed: {}", e)),
+    }
+}
+
+/// Chat platform used for approvals
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Platform {
+    Slack,
+    Discord,
+}
+
+/// Chat approval configuration (loaded from a JSON file)
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApprovalConfig {
+    /// Chat platform
+    pub platform: Platform,
+    /// Bot token used to post approval requests
+    pub bot_token: String,
+    /// Channel ID to post approval requests to
+    pub channel: String,
+    /// Slack signing secret, used to verify interaction callbacks
+    #[serde(default)]
+    pub signing_secret: Option<String>,
+    /// Discord application public key (hex), used to verify interaction callbacks
+    #[serde(default)]
+    pub public_key: Option<String>,
+    /// User IDs allowed to approve or deny requests
+    pub approvers: Vec<String>,
+    /// Address to listen on for interaction callbacks
+    #[serde(default = "default_listen")]
+    pub listen: SocketAddr,
+    /// Seconds to wait for a decision before denying
+    #[serde(default = "default_timeout_seconds")]
+    pub timeout_seconds: u64,
+}
+
+fn default_listen() -> SocketAddr {
+    SocketAddr::from(([127, 0, 0, 1], 8787))
+}
+
+fn default_timeout_seconds() -> u64 {
+    300
+}
+
+impl ApprovalConfig {
+    /// Load and validate a config file
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let config: Self = serde_json::from_slice(&std::fs::read(path)?)?;
+
+        if config.approvers.is_empty() {
+            anyhow::bail!("Approval config must list at least one approver");
+        }
+        match config.platform {
+            Platform::Slack if config.signing_secret.is_none() => {
+                anyhow::bail!("Slack approval config requires signing_secret")
+            }
+            Platform::Discord if config.public_key.is_none() => {
+                anyhow::bail!("Discord approval config requires public_key")
+            }
+            _ => {}
+        }
+
+        Ok(config)
+    }
+}
+
+/// An approver's decision on a request
+#[derive(Debug)]
+struct Decision {
+    approved: bool,
+    user: String,
+}
+
+/// A button press parsed from an interaction callback
+#[derive(Debug, PartialEq, Eq)]
+struct Action {
+    request_id: String,
+    approved: bool,
+    user: String,
+}
+
+/// Pending chat approvals and the callback server state
+pub struct ChatApproval {
+    config: ApprovalConfig,
+    http: reqwest::Client,
+    pending: Mutex<HashMap<String, oneshot::Sender<Decision>>>,
+}
+
+impl ChatApproval {
+    /// Create a new chat approval backend
+    pub fn new(config: ApprovalConfig) -> Self {
+        Self {
+            config,
+            http: reqwest::Client::new(),
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Post an approval request and wait for a decision
+    async fn request(&self, title: &str, text: &str) -> Confirmation {
+        let request_id = hex::encode(rand::random::<[u8; 16]>());
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().unwrap().insert(request_id.clone(), tx);
+
+        let posted = match self.config.platform {
+            Platform::Slack => {
+                telemetry::outbound(
+                    "slack",
+                    slack::post(&self.http, &self.config, &request_id, title, text),
+                )
+                .await
+            }
+            Platform::Discord => {
+                telemetry::outbound(
+                    "discord",
+                    discord::post(&self.http, &self.config, &request_id, title, text),
+                )
+                .await
+            }
+        };
+        if let Err(e) = posted {
+            self.pending.lock().unwrap().remove(&request_id);
+            return Confirmation::Denied(format!("Failed to post approval request: {}", e));
+        }
+
+        let timeout = Duration::from_secs(self.config.timeout_seconds);
+        match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(Decision {
+                approved: true,
+                user,
+            })) => {
+                eprintln!("Request {} approved by {}", request_id, user);
+                Confirmation::Approved
+            }
+            Ok(Ok(Decision {
+                approved: false,
+                user,
+            })) => Confirmation::Denied(format!("Denied by {}", user)),
+            Ok(Err(_)) | Err(_) => {
+                self.pending.lock().unwrap().remove(&request_id);
+                Confirmation::Denied(format!(
+                    "No approval within {} seconds",
+                    self.config.timeout_seconds
+                ))
+            }
+        }
+    }
+
+    /// Deliver a decision to a waiting request
+    ///
+    /// Returns `false` if the request is unknown or already decided.
+    fn resolve(&self, action: &Action) -> bool {
+        match self.pending.lock().unwrap().remove(&action.request_id) {
+            Some(tx) => tx
+                .send(Decision {
+                    approved: action.approved,
+                    user: action.user.clone(),
+                })
+                .is_ok(),
+            None => false,
+        }
+    }
+
+    fn is_approver(&self, user: &str) -> bool {
+        self.config.approvers.iter().any(|a| a == user)
+    }
+
+    /// Serve interaction callbacks from the chat platform
+    pub async fn serve(self: Arc<Self>) -> anyhow::Result<()> {
+        let listener = TcpListener::bind(self.config.listen).await?;
+        println!(
+            "Approval callbacks listening on http://{}",
+            self.config.listen
+        );
+
+        loop {
+            let (stream, _) = listener.accept().await?;
+            let chat = self.clone();
+            tokio::spawn(async move {
+                if let Err(e) = chat.handle_callback(stream).await {
+                    eprintln!(
+                        "{}",
+                        output::error(format!("Approval callback error: {}", e))
+                    );
+                }
+            });
+        }
+    }
+
+    async fn handle_callback(&self, mut stream: TcpStream) -> anyhow::Result<()> {
+        let request = http::read_request(&mut stream).await?;
+        let response = match self.config.platform {
+            Platform::Slack => slack::handle(self, &request).await,
+            Platform::Discord => discord::handle(self, &request),
+        };
+        http::write_response(&mut stream, response).await
+    }
+}
@@ -0,0 +1,346 @@
+//! Slack approval backend
+//!
+//! Requests are posted with `chat.postMessage` as Block Kit buttons. Button
+//! presses arrive as signed interaction callbacks, verified with the app's
+//! signing secret (`X-Slack-Signature`).
+
+use hmac::{Hmac, Mac};
+use serde_json::{Value, json};
+use sha2::Sha256;
+
+use super::http::{HttpRequest, HttpResponse};
+use super::{Action, ApprovalConfig, ChatApproval};
+
+/// Maximum age of a callback timestamp, to prevent replays
+const MAX_SKEW_SECONDS: i64 = 300;
+
+/// Post an approval request to the configured channel
+pub async fn post(
+    http: &reqwest::Client,
+    config: &ApprovalConfig,
+    request_id: &str,
+    title: &str,
+    text: &str,
+) -> anyhow::Result<()> {
+    let body = json!({
+        "channel": config.channel,
+        "text": title,
+        "blocks": [
+            {
+                "type": "section",
+                "text": { "type": "mrkdwn", "text": format!("*{}*\n```{}```", title, text) }
+            },
+            {
+                "type": "actions",
+                "elements": [
+                    {
+                        "type": "button",
+                        "style": "primary",
+                        "text": { "type": "plain_text", "text": "Approve" },
+                        "action_id": "approve",
+                        "value": request_id
+                    },
+                    {
+                        "type": "button",
+                        "style": "danger",
+                        "text": { "type": "plain_text", "text": "Deny" },
+                        "action_id": "deny",
+                        "value": request_id
+                    }
+                ]
+            }
+        ]
+    });
+
+    let response: Value = http
+        .post("https://slack.com/api/chat.postMessage")
+        .bearer_auth(&config.bot_token)
+        .json(&body)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    if response.get("ok").and_then(Value::as_bool) != Some(true) {
+        anyhow::bail!(
+            "Slack error: {}",
+            response
+                .get("error")
+                .and_then(Value::as_str)
+                .unwrap_or("unknown")
+        );
+    }
+    Ok(())
+}
+
+/// Handle an interaction callback
+pub async fn handle(chat: &ChatApproval, request: &HttpRequest) -> HttpResponse {
+    let secret = chat.config.signing_secret.as_deref().unwrap_or_default();
+    let verified = match (
+        request.header("x-slack-request-timestamp"),
+        request.header("x-slack-signature"),
+    ) {
+        (Some(ts), Some(sig)) => verify(
+            secret,
+            ts,
+            &request.body,
+            sig,
+            solana_keyring::spending::unix_now(),
+        ),
+        _ => false,
+    };
+    if !verified {
+        return HttpResponse::status(401);
+    }
+
+    let Some((action, response_url)) = parse_action(&request.body) else {
+        return HttpResponse::ok();
+    };
+
+    let message = if !chat.is_approver(&action.user) {
+        format!("<@{}> is not an authorized approver", action.user)
+    } else if chat.resolve(&action) {
+        let verb = if action.approved {
+            "Approved"
+        } else {
+            "Denied"
+        };
+        format!("{} by <@{}>", verb, action.user)
+    } else {
+        "This request has already been decided or has expired".to_string()
+    };
+
+    // Update the original message (best effort)
+    if let Some(url) = response_url {
+        let _ = chat
+            .http
+            .post(url)
+            .json(&json!({ "replace_original": true, "text": message }))
+            .send()
+            .await;
+    }
+
+    HttpResponse::ok()
+}
+
+/// Verify a Slack request signature
+fn verify(secret: &str, timestamp: &str, body: &[u8], signature: &str, now: i64) -> bool {
+    let Ok(ts) = timestamp.parse::<i64>() else {
+        return false;
+    };
+    if (now - ts).abs() > MAX_SKEW_SECONDS {
+        return false;
+    }
+    let Some(expected) = signature
+        .strip_prefix("v0=")
+        .and_then(|hex_sig| hex::decode(hex_sig).ok())
+    else {
+        return false;
+    };
+
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(b"v0:");
+    mac.update(timestamp.as_bytes());
+    mac.update(b":");
+    mac.update(body);
+    mac.verify_slice(&expected).is_ok()
+}
+
+/// Parse a `block_actions` payload into an action and its `response_url`
+fn parse_action(body: &[u8]) -> Option<(Action, Option<String>)> {
+    let form: Vec<(String, String)> = serde_urlencoded::from_bytes(body).ok()?;
+    let payload = form.into_iter().find(|(k, _)| k == "payload")?.1;
+    let payload: Value = serde_json::from_str(&payload).ok()?;
+
+    let action = payload.get("actions")?.get(0)?;
+    let approved = match action.get("action_id")?.as_str()? {
+        "approve" => true,
+        "deny" => false,
+        _ => return None,
+    };
+
+    Some((
+        Action {
+            request_id: action.get("value")?.as_str()?.to_string(),
+            approved,
+            user: payload.get("user")?.get("id")?.as_str()?.to_string(),
+        },
+        payload
+            .get("response_url")
+            .and_then(Value::as_str)
+            .map(String::from),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "8f742231b10e8888abcd99yyyzzz85a5";
+    const TIMESTAMP: &str = "1700000000";
+    const BODY: &str = "payload=%7B%22type%22%3A%22block_actions%22%2C%22user%22%3A%7B%22id%22%3A%22U123%22%7D%2C%22response_url%22%3A%22https%3A%2F%2Fhooks.slack.com%2Factions%2FT1%2F1%2Fabc%22%2C%22actions%22%3A%5B%7B%22action_id%22%3A%22approve%22%2C%22value%22%3A%22req1%22%7D%5D%7D";
+    const SIGNATURE: &str = "v0=7e25bf75bdd3e9a2a3a8fdc98967616f94ad55850c55abcc8ace55bffbe02685";
+
+    fn sign(body: &str, timestamp: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(SECRET.as_bytes()).unwrap();
+        mac.update(format!("v0:{}:{}", timestamp, body).as_bytes());
+        format!("v0={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    fn chat() -> ChatApproval {
+        ChatApproval::new(
+            serde_json::from_value(json!({
+                "platform": "slack",
+                "bot_token": "xoxb-test",
+                "channel": "C1",
+                "signing_secret": SECRET,
+                "approvers": ["U123"],
+            }))
+            .unwrap(),
+        )
+    }
+
+    fn callback(body: &str) -> HttpRequest {
+        let now = solana_keyring::spending::unix_now().to_string();
+        HttpRequest {
+            method: "POST".to_string(),
+            path: "/".to_string(),
+            headers: [
+                ("x-slack-request-timestamp".to_string(), now.clone()),
+                ("x-slack-signature".to_string(), sign(body, &now)),
+            ]
+            .into(),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    fn button(action_id: &str, user: &str) -> String {
+        let payload = json!({
+            "type": "block_actions",
+            "user": { "id": user },
+            "actions": [{ "action_id": action_id, "value": "req1" }],
+        });
+        serde_urlencoded::to_string([("payload", payload.to_string())]).unwrap()
+    }
+
+    #[test]
+    fn test_verify_known_vector() {
+        let now = TIMESTAMP.parse().unwrap();
+        assert_eq!(sign(BODY, TIMESTAMP), SIGNATURE);
+        assert!(verify(SECRET, TIMESTAMP, BODY.as_bytes(), SIGNATURE, now));
+    }
+
+    #[test]
+    fn test_verify_tampered() {
+        let now = TIMESTAMP.parse().unwrap();
+        let tampered = BODY.replace("approve", "deny");
+        assert!(!verify(
+            SECRET,
+            TIMESTAMP,
+            tampered.as_bytes(),
+            SIGNATURE,
+            now
+        ));
+        assert!(!verify("wrong", TIMESTAMP, BODY.as_bytes(), SIGNATURE, now));
+        assert!(!verify(
+            SECRET,
+            "1700000001",
+            BODY.as_bytes(),
+            SIGNATURE,
+            now
+        ));
+        assert!(!verify(
+            SECRET,
+            TIMESTAMP,
+            BODY.as_bytes(),
+            SIGNATURE.trim_start_matches("v0="),
+            now
+        ));
+    }
+
+    #[test]
+    fn test_verify_stale_timestamp() {
+        let ts: i64 = TIMESTAMP.parse().unwrap();
+        let body = BODY.as_bytes();
+        assert!(verify(
+            SECRET,
+            TIMESTAMP,
+            body,
+            SIGNATURE,
+            ts + MAX_SKEW_SECONDS
+        ));
+        assert!(verify(
+            SECRET,
+            TIMESTAMP,
+            body,
+            SIGNATURE,
+            ts - MAX_SKEW_SECONDS
+        ));
+        assert!(!verify(
+            SECRET,
+            TIMESTAMP,
+            body,
+            SIGNATURE,
+            ts + MAX_SKEW_SECONDS + 1
+        ));
+        assert!(!verify(
+            SECRET,
+            TIMESTAMP,
+            body,
+            SIGNATURE,
+            ts - MAX_SKEW_SECONDS - 1
+        ));
+    }
+
+    #[test]
+    fn test_parse_action() {
+        let (action, response_url) = parse_action(BODY.as_bytes()).unwrap();
+        assert_eq!(
+            action,
+            Action {
+                request_id: "req1".to_string(),
+                approved: true,
+                user: "U123".to_string(),
+            }
+        );
+        assert_eq!(
+            response_url.as_deref(),
+            Some("https://hooks.slack.com/actions/T1/1/abc")
+        );
+
+        assert!(
+            !parse_action(button("deny", "U123").as_bytes())
+                .unwrap()
+                .0
+                .approved
+        );
+        assert!(parse_action(button("other", "U123").as_bytes()).is_none());
+        assert!(parse_action(b"payload=not-json").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_handle_unsigned() {
+        let chat = chat();
+        let mut request = callback(&button("approve", "U123"));
+        request.headers.remove("x-slack-signature");
+        assert_eq!(handle(&chat, &request).await.status, 401);
+    }
+
+    #[tokio::test]
+    async fn test_handle_non_approver() {
+        let chat = chat();
+        let (tx, mut rx) = tokio::sync::oneshot::channel();
+        chat.pending.lock().unwrap().insert("req1".to_string(), tx);
+
+        let response = handle(&chat, &callback(&button("approve", "U999"))).await;
+        assert_eq!(response.status, 200);
+        assert!(rx.try_recv().is_err());
+        assert!(chat.pending.lock().unwrap().contains_key("req1"));
+
+        handle(&chat, &callback(&button("approve", "U123"))).await;
+        assert!(rx.try_recv().unwrap().approved);
+    }
+}
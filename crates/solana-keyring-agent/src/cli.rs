@@ -1,8 +1,12 @@
 //! CLI definitions for agent
 
+use std::net::SocketAddr;
 use std::path::PathBuf;
 
 use clap::{Parser, Subcommand};
+use solana_keyring::allowlist::DestinationPolicy;
+
+use crate::tokens::TokenPermission;
 
 #[derive(Parser)]
 #[command(
@@ -15,6 +19,10 @@ pub struct Cli {
     #[arg(long, global = true)]
     pub socket: Option<PathBuf>,
 
+    /// Disable colors and symbols (also honors NO_COLOR)
+    #[arg(long, global = true)]
+    pub plain: bool,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -31,6 +39,43 @@ pub enum Commands {
     Lock,
     /// Check agent status
     Status,
+    /// Issue, list and revoke scoped session tokens
+    #[command(subcommand)]
+    Token(TokenCommands),
+}
+
+#[derive(Subcommand)]
+pub enum TokenCommands {
+    /// Issue a token; it is printed once and can't be recovered
+    Issue {
+        /// Name to tell the token apart in listings
+        label: String,
+
+        /// Signer the token may use (label or public key); repeat for more.
+        /// Defaults to every signer.
+        #[arg(long = "signer")]
+        signers: Vec<String>,
+
+        /// Permission to grant: read, sign-transaction or sign-message;
+        /// repeat for more
+        #[arg(long = "permission", required = true)]
+        permissions: Vec<TokenPermission>,
+
+        /// Total SOL the token's signed transactions may move (e.g. 1.5)
+        #[arg(long)]
+        max_sol: Option<String>,
+
+        /// Time until the token expires (e.g. 30m, 24h, 7d)
+        #[arg(long)]
+        ttl: Option<String>,
+    },
+    /// List issued tokens
+    List,
+    /// Revoke a token by ID
+    Revoke {
+        /// Token ID, as shown by `token list`
+        id: String,
+    },
 }
 
 #[derive(clap::Args)]
@@ -46,4 +91,84 @@ pub struct StartArgs {
     /// Database path
     #[arg(long)]
     pub db_path: Option<PathBuf>,
+
+    /// Serve listing and resolution requests only (refuse unlock and signing)
+    #[arg(long)]
+    pub read_only: bool,
+
+    /// Destination allowlist: off, address-book, or tag:<name>
+    ///
+    /// Transfers to trusted destinations are approved without a prompt; new
+    /// destinations always require biometric confirmation.
+    #[arg(long, default_value = "off")]
+    pub destinations: DestinationPolicy,
+
+    /// Approve signing requests through Slack or Discord (JSON config file)
+    ///
+    /// Requests are posted with approve/deny buttons and held until an
+    /// authorized approver responds or the timeout expires.
+    #[arg(long)]
+    pub approval_config: Option<PathBuf>,
+
+    /// Set the audit log retention (e.g. 90d), or "off" to keep everything
+    ///
+    /// The setting is stored in the database; the agent prunes older events
+    /// hourly.
+    #[arg(long)]
+    pub audit_retention: Option<String>,
+
+    /// Serve remote signing requests over HTTPS on this address (e.g. 0.0.0.0:8443)
+    ///
+    /// Clients must present a certificate issued by --remote-client-ca.
+    #[arg(long, requires_all = ["remote_cert", "remote_key", "remote_client_ca"])]
+    pub remote_listen: Option<SocketAddr>,
+
+    /// PEM certificate chain for the remote signing server
+    #[arg(long)]
+    pub remote_cert: Option<PathBuf>,
+
+    /// PEM private key for the remote signing server
+    #[arg(long)]
+    pub remote_key: Option<PathBuf>,
+
+    /// PEM CA certificates that remote client certificates must chain to
+    #[arg(long)]
+    pub remote_client_ca: Option<PathBuf>,
+
+    /// Run unattended, e.g. in a container: unlock at startup and never prompt
+    ///
+    /// The passphrase comes from --passphrase-fd, --passphrase-file or
+    /// --passphrase-credential, never from arguments or the environment.
+    /// Implies --foreground and disables the lock timeout. Requests are
+    /// approved by policy alone: spending limits and the destination allowlist
+    /// still apply, and high-value keys are refused.
+    #[arg(
+        long,
+        requires = "passphrase_source",
+        conflicts_with_all = ["read_only", "approval_config"]
+    )]
+    pub headless: bool,
+
+    /// Read the master passphrase from this open file descriptor (--headless)
+    #[arg(long, group = "passphrase_source", requires = "headless")]
+    pub passphrase_fd: Option<u32>,
+
+    /// Read the master passphrase from this file, e.g. a mounted secret (--headless)
+    #[arg(long, group = "passphrase_source", requires = "headless")]
+    pub passphrase_file: Option<PathBuf>,
+
+    /// Read the master passphrase from this systemd credential (--headless)
+    ///
+    /// Looked up in $CREDENTIALS_DIRECTORY, as set up by LoadCredential= or
+    /// LoadCredentialEncrypted= in the unit file.
+    #[arg(long, group = "passphrase_source", requires = "headless")]
+    pub passphrase_credential: Option<String>,
+
+    /// Export traces and metrics to this OTLP/HTTP endpoint
+    ///
+    /// Defaults to OTEL_EXPORTER_OTLP_ENDPOINT; nothing is exported when
+    /// neither is set.
+    #[cfg(feature = "otel")]
+    #[arg(long)]
+    pub otlp_endpoint: Option<String>,
 }
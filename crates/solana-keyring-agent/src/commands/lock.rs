@@ -3,6 +3,7 @@
 use std::path::PathBuf;
 
 use anyhow::Result;
+use solana_keyring::output;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::UnixStream;
 
@@ -37,7 +38,7 @@ pub async fn run(socket_path: &Option<PathBuf>) -> Result<()> {
 
     match response {
         Response::Ok { .. } => {
-            println!("Agent locked.");
+            println!("{}", output::success("Agent locked."));
         }
         Response::Error { code, message } => {
             anyhow::bail!("Failed to lock agent: {} - {}", code, message);
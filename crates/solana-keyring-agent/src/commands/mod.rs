@@ -4,6 +4,7 @@ pub mod lock;
 pub mod start;
 pub mod status;
 pub mod stop;
+pub mod token;
 pub mod unlock;
 
 use std::path::PathBuf;
@@ -1,17 +1,42 @@
 //! Start the agent daemon
 
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
+use std::sync::Arc;
 use std::time::Duration;
 
-use anyhow::Result;
-use solana_keyring::default_agent_socket_path;
+use anyhow::{Context, Result};
+use solana_keyring::maintenance::Maintenance;
+use solana_keyring::spending::parse_window;
+use solana_keyring::{Database, default_agent_socket_path, default_db_path};
+use zeroize::Zeroizing;
 
 use crate::agent::Agent;
+use crate::approval::{ApprovalConfig, ChatApproval, Confirmer};
 use crate::cli::StartArgs;
+use crate::remote::{RemoteConfig, RemoteServer};
+use crate::session::{AgentIdentity, identity_path, public_identity_path};
 
-pub async fn run(args: StartArgs) -> Result<()> {
+pub async fn run(args: StartArgs, prefix: &[&str]) -> Result<()> {
     let socket_path = default_agent_socket_path();
 
+    // Validate before detaching so errors reach the terminal
+    let audit_retention = match args.audit_retention.as_deref() {
+        None => None,
+        Some("off") => Some(None),
+        Some(window) => Some(Some(parse_window(window)?)),
+    };
+    let remote = remote_config(&args);
+    if let Some(ref remote) = remote {
+        remote.acceptor()?;
+    }
+    let db_path = args.db_path.clone().unwrap_or_else(default_db_path);
+    let passphrase = if args.headless {
+        Some(headless_passphrase(&args, &db_path)?)
+    } else {
+        None
+    };
+
     // Check if agent is already running
     if socket_path.exists() {
         // Try to connect
@@ -22,17 +47,47 @@ pub async fn run(args: StartArgs) -> Result<()> {
         std::fs::remove_file(&socket_path)?;
     }
 
-    if !args.foreground {
-        // Spawn ourselves in the background with --foreground flag
-        let exe = std::env::current_exe()?;
+    // Headless agents hold the passphrase, which can't be handed to a child
+    if !args.foreground && !args.headless {
+        // Spawn ourselves in the background with --foreground flag, resolving
+        // links so a combined binary is re-run under its own name
+        let exe = std::env::current_exe()?.canonicalize()?;
         let mut cmd = Command::new(exe);
-        cmd.arg("start").arg("--foreground");
+        cmd.args(prefix).arg("start").arg("--foreground");
         cmd.arg("--lock-timeout").arg(args.lock_timeout.to_string());
 
         if let Some(ref db_path) = args.db_path {
             cmd.arg("--db-path").arg(db_path);
         }
 
+        if args.read_only {
+            cmd.arg("--read-only");
+        }
+
+        cmd.arg("--destinations").arg(args.destinations.to_string());
+
+        if let Some(ref approval_config) = args.approval_config {
+            // Validate before detaching so errors reach the terminal
+            ApprovalConfig::load(approval_config)?;
+            cmd.arg("--approval-config").arg(approval_config);
+        }
+
+        if let Some(ref retention) = args.audit_retention {
+            cmd.arg("--audit-retention").arg(retention);
+        }
+
+        if let Some(ref remote) = remote {
+            cmd.arg("--remote-listen").arg(remote.listen.to_string());
+            cmd.arg("--remote-cert").arg(&remote.cert);
+            cmd.arg("--remote-key").arg(&remote.key);
+            cmd.arg("--remote-client-ca").arg(&remote.client_ca);
+        }
+
+        #[cfg(feature = "otel")]
+        if let Some(ref endpoint) = args.otlp_endpoint {
+            cmd.arg("--otlp-endpoint").arg(endpoint);
+        }
+
         // Detach from terminal
         cmd.stdin(Stdio::null());
         cmd.stdout(Stdio::null());
@@ -41,12 +96,97 @@ pub async fn run(args: StartArgs) -> Result<()> {
         let child = cmd.spawn()?;
         println!("Agent started in background (PID: {})", child.id());
         println!("Socket: {}", socket_path.display());
+        if let Some(ref remote) = remote {
+            println!("Remote signing: https://{}", remote.listen);
+        }
         println!();
-        println!("Run 'solana-keyring-agent unlock' to unlock the agent.");
+        if args.read_only {
+            println!("Agent is read-only: signing and unlock requests will be refused.");
+        } else {
+            println!("Run 'solana-keyring-agent unlock' to unlock the agent.");
+        }
         return Ok(());
     }
 
+    let confirmer = match args.approval_config {
+        Some(ref path) => Confirmer::Chat(Arc::new(ChatApproval::new(ApprovalConfig::load(path)?))),
+        None if args.headless => Confirmer::Headless,
+        None => Confirmer::Biometric,
+    };
+
+    solana_keyring::i18n::init_for_db(&db_path);
+
+    if let Some(retention) = audit_retention {
+        Maintenance::new(&Database::open(&db_path)?).set_audit_retention(retention)?;
+    }
+
+    #[cfg(feature = "otel")]
+    let _telemetry = crate::telemetry::init(args.otlp_endpoint.as_deref())?;
+
     let lock_timeout = Duration::from_secs(args.lock_timeout);
-    let agent = Agent::new(socket_path, args.db_path, lock_timeout);
+    let identity = AgentIdentity::load_or_create(
+        &identity_path(&socket_path),
+        &public_identity_path(&socket_path),
+    )
+    .context("Failed to load agent identity key")?;
+    let mut agent = Agent::new(
+        socket_path,
+        args.db_path,
+        lock_timeout,
+        args.read_only,
+        args.destinations,
+        confirmer,
+        identity,
+    );
+    if let Some(ref remote) = remote {
+        agent = agent.with_remote(RemoteServer::new(remote)?);
+    }
+    if let Some(passphrase) = passphrase {
+        agent.unlock(&passphrase).await;
+        println!("Running headless: unlocked, approving by policy only");
+    }
     agent.run().await
 }
+
+/// Read and verify the master passphrase for a headless agent
+fn headless_passphrase(args: &StartArgs, db_path: &Path) -> Result<Zeroizing<Vec<u8>>> {
+    let (source, contents) = if let Some(fd) = args.passphrase_fd {
+        let path = PathBuf::from(format!("/dev/fd/{}", fd));
+        (format!("file descriptor {}", fd), std::fs::read(path))
+    } else if let Some(ref path) = args.passphrase_file {
+        (path.display().to_string(), std::fs::read(path))
+    } else if let Some(ref name) = args.passphrase_credential {
+        let dir = std::env::var_os("CREDENTIALS_DIRECTORY")
+            .context("CREDENTIALS_DIRECTORY is not set; is the agent running under systemd?")?;
+        let path = PathBuf::from(dir).join(name);
+        (format!("credential {}", name), std::fs::read(path))
+    } else {
+        anyhow::bail!("--headless needs a passphrase source");
+    };
+    let mut passphrase = Zeroizing::new(
+        contents.with_context(|| format!("Failed to read passphrase from {}", source))?,
+    );
+
+    // Secret files usually end with a newline that isn't part of the passphrase
+    if passphrase.ends_with(b"\n") {
+        passphrase.pop();
+        if passphrase.ends_with(b"\r") {
+            passphrase.pop();
+        }
+    }
+
+    if !Database::open(db_path)?.verify_passphrase(&passphrase)? {
+        anyhow::bail!("Invalid passphrase from {}", source);
+    }
+    Ok(passphrase)
+}
+
+/// Remote signing settings, when `--remote-listen` is given
+fn remote_config(args: &StartArgs) -> Option<RemoteConfig> {
+    Some(RemoteConfig {
+        listen: args.remote_listen?,
+        cert: args.remote_cert.clone()?,
+        key: args.remote_key.clone()?,
+        client_ca: args.remote_client_ca.clone()?,
+    })
+}
@@ -3,6 +3,8 @@
 use std::path::PathBuf;
 
 use anyhow::Result;
+use solana_keyring::backup::BackupStatus;
+use solana_keyring::output;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::UnixStream;
 
@@ -43,11 +45,46 @@ pub async fn run(socket_path: &Option<PathBuf>) -> Result<()> {
         Response::Ok {
             result: ResponseResult::Status(status),
         } => {
-            println!("Agent Status:");
+            println!("{}", output::heading("Agent Status:"));
             println!("  Running: yes");
             println!("  Unlocked: {}", if status.unlocked { "yes" } else { "no" });
+            if status.read_only {
+                println!("  Mode: read-only");
+            }
+            if !status.destination_policy.is_empty() && status.destination_policy != "off" {
+                println!("  Destinations: {}", status.destination_policy);
+            }
+            if !status.confirmer.is_empty() && status.confirmer != "biometric" {
+                println!("  Approvals: {}", status.confirmer);
+            }
+            if status.unlock_hard_locked {
+                println!("  Unlocking: hard-locked (use the recovery code)");
+            } else if status.failed_unlocks > 0 {
+                println!("  Failed unlock attempts: {}", status.failed_unlocks);
+            }
+            if status.queued_requests > 0 {
+                println!("  Queued signing requests: {}", status.queued_requests);
+            }
+            if status.awaiting_device > 0 {
+                println!(
+                    "  Waiting for Ledger: {} request(s)",
+                    status.awaiting_device
+                );
+            }
             println!("  Uptime: {} seconds", status.uptime_seconds);
-            println!("  Lock timeout: {} seconds", status.lock_timeout_seconds);
+            if status.confirmer == "headless" {
+                println!("  Lock timeout: never (headless)");
+            } else {
+                println!("  Lock timeout: {} seconds", status.lock_timeout_seconds);
+            }
+
+            let backup = BackupStatus {
+                last_backup_at: status.last_backup_at,
+                unbacked_keys: status.unbacked_keys,
+            };
+            if let Some(reminder) = backup.reminder() {
+                eprintln!("{}", output::warning(reminder));
+            }
         }
         Response::Ok { .. } => {
             println!("Unexpected response from agent");
@@ -3,6 +3,7 @@
 use std::path::PathBuf;
 
 use anyhow::Result;
+use solana_keyring::output;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::UnixStream;
 
@@ -35,7 +36,7 @@ pub async fn run(socket_path: &Option<PathBuf>) -> Result<()> {
 
     match response {
         Response::Ok { .. } => {
-            println!("Agent stopped.");
+            println!("{}", output::success("Agent stopped."));
             Ok(())
         }
         Response::Error { message, .. } => {
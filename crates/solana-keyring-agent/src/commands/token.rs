@@ -0,0 +1,122 @@
+//! Session token commands
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use solana_keyring::output;
+use solana_keyring::spending::{format_sol, format_window, parse_sol, parse_window, unix_now};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::UnixStream;
+
+use super::get_socket_path;
+use crate::cli::TokenCommands;
+use crate::protocol::{Request, Response, ResponseResult};
+use crate::tokens::TokenScope;
+
+pub async fn run(socket_path: &Option<PathBuf>, command: TokenCommands) -> Result<()> {
+    match command {
+        TokenCommands::Issue {
+            label,
+            signers,
+            permissions,
+            max_sol,
+            ttl,
+        } => {
+            let scope = TokenScope {
+                signers,
+                permissions,
+                max_lamports: max_sol.as_deref().map(parse_sol).transpose()?,
+            };
+            let ttl_seconds = ttl.as_deref().map(parse_window).transpose()?;
+            let request = Request::IssueToken {
+                label,
+                scope,
+                ttl_seconds,
+            };
+
+            match send(socket_path, &request).await? {
+                ResponseResult::IssuedToken(issued) => {
+                    println!("{}", output::success(format!("Issued token {}", issued.id)));
+                    println!();
+                    println!("  {}", issued.token);
+                    println!();
+                    println!("Store it now; the agent can't show it again.");
+                }
+                _ => anyhow::bail!("Unexpected response from agent"),
+            }
+        }
+        TokenCommands::List => match send(socket_path, &Request::ListTokens).await? {
+            ResponseResult::Tokens(list) => {
+                if list.tokens.is_empty() {
+                    println!("No session tokens.");
+                    return Ok(());
+                }
+                let now = unix_now();
+                println!("{}", output::heading("Session Tokens:"));
+                for token in list.tokens {
+                    let permissions: Vec<String> = token
+                        .scope
+                        .permissions
+                        .iter()
+                        .map(|p| p.to_string())
+                        .collect();
+                    println!("  {} {}", token.id, token.label);
+                    println!("    Permissions: {}", permissions.join(", "));
+                    if token.scope.signers.is_empty() {
+                        println!("    Signers: all");
+                    } else {
+                        println!("    Signers: {}", token.scope.signers.join(", "));
+                    }
+                    if let Some(max) = token.scope.max_lamports {
+                        println!(
+                            "    Spent: {} of {} SOL",
+                            format_sol(token.spent_lamports),
+                            format_sol(max)
+                        );
+                    }
+                    match token.expires_at {
+                        _ if token.expired => println!("    Expired"),
+                        Some(at) => println!(
+                            "    Expires in {}",
+                            format_window(at.saturating_sub(now).max(0) as u64)
+                        ),
+                        None => {}
+                    }
+                }
+            }
+            _ => anyhow::bail!("Unexpected response from agent"),
+        },
+        TokenCommands::Revoke { id } => {
+            send(socket_path, &Request::RevokeToken { id: id.clone() }).await?;
+            println!("{}", output::success(format!("Revoked token {}", id)));
+        }
+    }
+
+    Ok(())
+}
+
+/// Send one request and return its result
+async fn send(socket_path: &Option<PathBuf>, request: &Request) -> Result<ResponseResult> {
+    let path = get_socket_path(socket_path);
+    let mut stream = UnixStream::connect(&path).await?;
+
+    let request_bytes = serde_json::to_vec(request)?;
+    stream
+        .write_all(&(request_bytes.len() as u32).to_be_bytes())
+        .await?;
+    stream.write_all(&request_bytes).await?;
+
+    let mut len_buf = [0u8; 4];
+    stream.read_exact(&mut len_buf).await?;
+    let len = u32::from_be_bytes(len_buf) as usize;
+
+    let mut buf = vec![0u8; len];
+    stream.read_exact(&mut buf).await?;
+
+    match serde_json::from_slice(&buf)? {
+        Response::Ok { result } => Ok(result),
+        Response::Error { code, message } => {
+            anyhow::bail!("{} - {}", code, message)
+        }
+    }
+}
@@ -3,24 +3,86 @@
 use std::path::PathBuf;
 
 use anyhow::Result;
+use solana_keyring::output;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::UnixStream;
+use zeroize::Zeroizing;
 
 use super::get_socket_path;
-use crate::protocol::{Request, Response};
+use crate::protocol::{Request, Response, ResponseResult};
+use crate::session::{AgentIdentity, ENCRYPTED_UNLOCK, Handshake, public_identity_path};
 
 pub async fn run(socket_path: &Option<PathBuf>) -> Result<()> {
     let path = get_socket_path(socket_path);
 
     // Prompt for passphrase
-    let passphrase = rpassword::prompt_password("Enter master passphrase: ")?;
+    let passphrase = Zeroizing::new(rpassword::prompt_password(output::prompt(
+        "Enter master passphrase",
+    ))?);
 
     // Connect to agent
     let mut stream = UnixStream::connect(&path).await?;
 
-    // Build unlock request
-    let request = Request::Unlock { passphrase };
-    let request_bytes = serde_json::to_vec(&request)?;
+    // Seal the passphrase if the agent supports encrypted sessions, falling
+    // back to plaintext only for older agents that never created an identity
+    let pinned = AgentIdentity::read_public(&public_identity_path(&path))?;
+    let request = match exchange(&mut stream, &Request::Hello).await? {
+        Response::Ok {
+            result: ResponseResult::Hello(hello),
+        } if hello.capabilities.iter().any(|c| c == ENCRYPTED_UNLOCK) => {
+            let Some(identity) = hello.identity else {
+                anyhow::bail!("Agent did not advertise an identity key");
+            };
+            if pinned.as_deref() != Some(identity.as_str()) {
+                anyhow::bail!(
+                    "Agent identity does not match {}; refusing to send the passphrase",
+                    public_identity_path(&path).display()
+                );
+            }
+
+            let handshake = Handshake::new();
+            let open = Request::OpenSession {
+                public_key: handshake.public_key(),
+            };
+            let agent_public = match exchange(&mut stream, &open).await? {
+                Response::Ok {
+                    result: ResponseResult::Session(session),
+                } => session.public_key,
+                Response::Ok { .. } => anyhow::bail!("Unexpected response from agent"),
+                Response::Error { code, message } => {
+                    anyhow::bail!("Failed to open session: {} - {}", code, message)
+                }
+            };
+
+            let key = handshake.finish_client(&agent_public, &identity)?;
+            let (nonce, ciphertext) = key.seal(passphrase.as_bytes())?;
+            Request::SecureUnlock { nonce, ciphertext }
+        }
+        _ if pinned.is_some() => {
+            anyhow::bail!(
+                "Agent did not offer an encrypted session; refusing to send the passphrase"
+            )
+        }
+        _ => Request::Unlock {
+            passphrase: passphrase.to_string(),
+        },
+    };
+
+    match exchange(&mut stream, &request).await? {
+        Response::Ok { .. } => {
+            println!("{}", output::success("Agent unlocked successfully."));
+        }
+        Response::Error { code, message } => {
+            anyhow::bail!("Failed to unlock agent: {} - {}", code, message);
+        }
+    }
+
+    Ok(())
+}
+
+/// Send a request and read its response
+async fn exchange(stream: &mut UnixStream, request: &Request) -> Result<Response> {
+    let request_bytes = serde_json::to_vec(request)?;
 
     // Send request
     stream
@@ -36,16 +98,5 @@ pub async fn run(socket_path: &Option<PathBuf>) -> Result<()> {
     let mut buf = vec![0u8; len];
     stream.read_exact(&mut buf).await?;
 
-    let response: Response = serde_json::from_slice(&buf)?;
-
-    match response {
-        Response::Ok { .. } => {
-            println!("Agent unlocked successfully.");
-        }
-        Response::Error { code, message } => {
-            anyhow::bail!("Failed to unlock agent: {} - {}", code, message);
-        }
-    }
-
-    Ok(())
+    Ok(serde_json::from_slice(&buf)?)
 }
@@ -0,0 +1,89 @@
+//! Long-lived database connection shared by agent requests
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use solana_keyring::{Database, Result};
+
+/// A database connection opened once and reused across requests
+///
+/// The connection is reopened when the file on disk is replaced (for example
+/// by `solana-keyring restore`) or when a request fails and the connection no
+/// longer passes an integrity check.
+pub struct SharedDatabase {
+    path: PathBuf,
+    conn: Mutex<Option<OpenDatabase>>,
+}
+
+struct OpenDatabase {
+    db: Database,
+    file_id: Option<FileId>,
+}
+
+impl SharedDatabase {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            conn: Mutex::new(None),
+        }
+    }
+
+    /// Run `f` against the shared connection, opening it first if needed
+    ///
+    /// Requests are serialized on the connection, so `f` must not block on
+    /// anything but the database; derive keys and decrypt outside it
+    pub fn with<T, E>(
+        &self,
+        f: impl FnOnce(&Database) -> std::result::Result<T, E>,
+    ) -> std::result::Result<T, E>
+    where
+        E: From<solana_keyring::Error>,
+    {
+        let mut conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+
+        let current = file_id(&self.path);
+        if conn
+            .as_ref()
+            .is_some_and(|open| current.is_none() || open.file_id != current)
+        {
+            *conn = None;
+        }
+
+        let open = match conn.as_mut() {
+            Some(open) => open,
+            None => conn.insert(OpenDatabase {
+                db: Database::open(&self.path)?,
+                file_id: file_id(&self.path),
+            }),
+        };
+
+        let result = f(&open.db);
+        if result.is_err() && open.db.check_integrity().is_err() {
+            *conn = None;
+        }
+        result
+    }
+
+    /// Open the connection ahead of the first request
+    pub fn warm(&self) -> Result<()> {
+        self.with(|_| Ok(()))
+    }
+}
+
+/// Identity of the file at `path`, which changes if it is replaced
+#[cfg(unix)]
+type FileId = (u64, u64);
+
+#[cfg(unix)]
+fn file_id(path: &Path) -> Option<FileId> {
+    use std::os::unix::fs::MetadataExt;
+    std::fs::metadata(path).ok().map(|m| (m.dev(), m.ino()))
+}
+
+#[cfg(not(unix))]
+type FileId = std::time::SystemTime;
+
+#[cfg(not(unix))]
+fn file_id(path: &Path) -> Option<FileId> {
+    std::fs::metadata(path).ok()?.created().ok()
+}
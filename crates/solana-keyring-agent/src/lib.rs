@@ -0,0 +1,50 @@
+//! Solana Keyring Agent daemon
+//!
+//! The `solana-keyring-agent` binary, as a library so it can be bundled into
+//! a combined executable.
+
+mod agent;
+mod approval;
+mod cli;
+mod commands;
+mod db;
+mod protocol;
+mod queue;
+mod remote;
+mod session;
+mod telemetry;
+mod tokens;
+
+use std::ffi::OsString;
+
+use anyhow::Result;
+use clap::Parser;
+
+use cli::{Cli, Commands};
+
+/// Run the CLI with `args`, starting with the program name
+///
+/// `prefix` holds the arguments that select the agent in a combined
+/// executable, such as `["agent"]`. The daemon passes them first when it
+/// re-executes itself in the background.
+///
+/// # Errors
+///
+/// Returns the error of the command that failed.
+pub async fn run<I, T>(args: I, prefix: &[&str]) -> Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let cli = Cli::parse_from(args);
+    solana_keyring::output::init(cli.plain);
+
+    match cli.command {
+        Commands::Start(args) => commands::start::run(args, prefix).await,
+        Commands::Stop => commands::stop::run(&cli.socket).await,
+        Commands::Unlock => commands::unlock::run(&cli.socket).await,
+        Commands::Lock => commands::lock::run(&cli.socket).await,
+        Commands::Status => commands::status::run(&cli.socket).await,
+        Commands::Token(command) => commands::token::run(&cli.socket, command).await,
+    }
+}
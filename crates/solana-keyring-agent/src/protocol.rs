@@ -2,6 +2,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::tokens::TokenScope;
+
 /// Request message from client to agent
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(tag = "method", content = "params")]
@@ -9,12 +11,38 @@ pub enum Request {
     /// Ping to check if agent is alive
     Ping,
 
+    /// Handshake: ask for the agent version and capabilities
+    Hello,
+
+    /// Start an encrypted session for passphrase entry
+    OpenSession {
+        /// Base64 encoded ephemeral X25519 public key
+        public_key: String,
+    },
+
+    /// Unlock the keyring with a passphrase sealed to the session key
+    SecureUnlock {
+        /// Base64 encoded 12-byte AES-GCM nonce
+        nonce: String,
+        /// Base64 encoded sealed passphrase
+        ciphertext: String,
+    },
+
     /// Request list of available signers
     ListSigners {
         /// Optional tag filter
         tag: Option<String>,
     },
 
+    /// Resolve a label or public key to a known signer or address book entry
+    ResolveLabel {
+        /// Label or public key to resolve
+        identifier: String,
+    },
+
+    /// Request list of address book entries
+    ListAddresses,
+
     /// Sign a transaction
     SignTransaction {
         /// Base64 encoded transaction message
@@ -23,6 +51,28 @@ pub enum Request {
         signer: String,
     },
 
+    /// Sign a UTF-8 message in the standard off-chain message envelope
+    SignMessage {
+        /// Message text
+        message: String,
+        /// Public key of signer to use
+        signer: String,
+    },
+
+    /// Sign a transaction on behalf of a bot holding a delegation
+    DelegatedSign {
+        /// Base64 encoded transaction message
+        transaction: String,
+        /// Public key of signer to use
+        signer: String,
+        /// Base58 public key of the bot
+        bot: String,
+        /// Request nonce, within the delegation's range and above any used before
+        nonce: u64,
+        /// Base58 bot signature over the delegation request message
+        signature: String,
+    },
+
     /// Generate a new keypair and store it
     GenerateKeypair {
         /// Label for the new keypair
@@ -53,10 +103,96 @@ pub enum Request {
     /// Get agent status
     Status,
 
+    /// Limit this connection to a session token's scope
+    Authenticate {
+        /// Token returned by `IssueToken`
+        token: String,
+    },
+
+    /// Issue a scoped session token
+    IssueToken {
+        /// Label to recognize the token by
+        label: String,
+        /// What the token allows; signers may be given by label
+        scope: TokenScope,
+        /// Seconds until the token expires; `None` lasts until the agent stops
+        ttl_seconds: Option<u64>,
+    },
+
+    /// List session tokens
+    ListTokens,
+
+    /// Revoke a session token
+    RevokeToken {
+        /// Token ID
+        id: String,
+    },
+
     /// Shutdown the agent
     Shutdown,
 }
 
+impl Request {
+    /// Whether this request is refused by a read-only agent
+    pub fn requires_write(&self) -> bool {
+        matches!(
+            self,
+            Request::Unlock { .. }
+                | Request::SecureUnlock { .. }
+                | Request::SignTransaction { .. }
+                | Request::SignMessage { .. }
+                | Request::DelegatedSign { .. }
+                | Request::GenerateKeypair { .. }
+                | Request::ImportKeypair { .. }
+        )
+    }
+
+    /// Whether this request may be sent over the remote signing channel
+    ///
+    /// Unlocking, key management and token administration stay on the local
+    /// socket.
+    pub fn allowed_remotely(&self) -> bool {
+        matches!(
+            self,
+            Request::Ping
+                | Request::Hello
+                | Request::Status
+                | Request::ListSigners { .. }
+                | Request::ResolveLabel { .. }
+                | Request::ListAddresses
+                | Request::SignTransaction { .. }
+                | Request::SignMessage { .. }
+                | Request::DelegatedSign { .. }
+        )
+    }
+
+    /// Method name, as sent on the wire
+    pub fn method(&self) -> &'static str {
+        match self {
+            Request::Ping => "Ping",
+            Request::Hello => "Hello",
+            Request::OpenSession { .. } => "OpenSession",
+            Request::SecureUnlock { .. } => "SecureUnlock",
+            Request::ListSigners { .. } => "ListSigners",
+            Request::ResolveLabel { .. } => "ResolveLabel",
+            Request::ListAddresses => "ListAddresses",
+            Request::SignTransaction { .. } => "SignTransaction",
+            Request::SignMessage { .. } => "SignMessage",
+            Request::DelegatedSign { .. } => "DelegatedSign",
+            Request::GenerateKeypair { .. } => "GenerateKeypair",
+            Request::ImportKeypair { .. } => "ImportKeypair",
+            Request::Unlock { .. } => "Unlock",
+            Request::Lock => "Lock",
+            Request::Status => "Status",
+            Request::Authenticate { .. } => "Authenticate",
+            Request::IssueToken { .. } => "IssueToken",
+            Request::ListTokens => "ListTokens",
+            Request::RevokeToken { .. } => "RevokeToken",
+            Request::Shutdown => "Shutdown",
+        }
+    }
+}
+
 /// Response message from agent to client
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(tag = "status")]
@@ -81,18 +217,76 @@ impl Response {
     }
 }
 
+impl From<solana_keyring::Error> for Response {
+    fn from(e: solana_keyring::Error) -> Self {
+        Response::error(e.code().into(), e.to_string())
+    }
+}
+
 /// Response result variants
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum ResponseResult {
     Pong,
     Signers(Vec<SignerInfo>),
+    Resolved(ResolvedLabel),
+    Addresses(Vec<AddressInfo>),
     SignedTransaction(String), // Base64 encoded signed transaction
+    SignedMessage(String),     // Base64 encoded signature over the off-chain message
     GeneratedKeypair(GeneratedKeypairInfo),
     Status(AgentStatus),
+    Hello(HelloInfo),
+    Session(SessionInfo),
+    IssuedToken(IssuedToken),
+    Tokens(TokenList),
     Unit,
 }
 
+/// Handshake response
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HelloInfo {
+    pub version: String,
+    /// Optional protocol features, e.g. "encrypted-unlock"
+    pub capabilities: Vec<String>,
+    /// Base64 encoded X25519 identity public key, for encrypted sessions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identity: Option<String>,
+}
+
+/// Encrypted session response
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionInfo {
+    /// Base64 encoded ephemeral X25519 public key of the agent
+    pub public_key: String,
+}
+
+/// A newly issued session token
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IssuedToken {
+    pub id: String,
+    /// The token itself; the agent never returns it again
+    pub token: String,
+}
+
+/// Session tokens
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TokenList {
+    pub tokens: Vec<TokenInfo>,
+}
+
+/// A session token, without the token itself
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenInfo {
+    pub id: String,
+    pub label: String,
+    pub scope: TokenScope,
+    pub created_at: i64,
+    pub expires_at: Option<i64>,
+    pub expired: bool,
+    /// Lamports moved by transactions signed with the token
+    pub spent_lamports: u64,
+}
+
 /// Generated keypair information
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GeneratedKeypairInfo {
@@ -109,6 +303,23 @@ pub struct SignerInfo {
     pub tags: Vec<String>,
 }
 
+/// Result of resolving a label or public key
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResolvedLabel {
+    pub pubkey: String,
+    pub label: String,
+    /// Signer type, or "address" for address book entries
+    pub kind: String,
+}
+
+/// Address book entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddressInfo {
+    pub pubkey: String,
+    pub label: String,
+    pub notes: Option<String>,
+}
+
 /// Agent status information
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AgentStatus {
@@ -116,28 +327,108 @@ pub struct AgentStatus {
     pub uptime_seconds: u64,
     pub signer_count: usize,
     pub lock_timeout_seconds: u64,
+    #[serde(default)]
+    pub read_only: bool,
+    /// Destination allowlist policy ("off", "address-book" or "tag:<name>")
+    #[serde(default)]
+    pub destination_policy: String,
+    /// Confirmation backend ("biometric", "slack", "discord" or "headless")
+    #[serde(default)]
+    pub confirmer: String,
+    /// Signing requests running or waiting in the per-signer queue
+    #[serde(default)]
+    pub queued_requests: usize,
+    /// Wrong passphrases since the last successful unlock
+    #[serde(default)]
+    pub failed_unlocks: u32,
+    /// Unlocking is refused until the recovery code is used
+    #[serde(default)]
+    pub unlock_hard_locked: bool,
+    /// Ledger signing requests parked until the device is connected
+    #[serde(default)]
+    pub awaiting_device: usize,
+    /// Unix timestamp of the last backup verified complete
+    #[serde(default)]
+    pub last_backup_at: Option<i64>,
+    /// Keys created since the last verified backup (all keys if none)
+    #[serde(default)]
+    pub unbacked_keys: usize,
 }
 
-/// Error codes
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+/// Error codes sent over the socket
+///
+/// Each is one of the shared [`solana_keyring_errors::ErrorCode`]s, serialized
+/// by variant name for compatibility with existing clients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ErrorCode {
     Locked,
     InvalidPassphrase,
+    TooManyAttempts,
     SignerNotFound,
     InvalidTransaction,
     HardwareError,
+    ReadOnly,
+    NotFound,
+    SpendingLimitExceeded,
+    ConfirmationRequired,
+    Unauthorized,
+    ClusterMismatch,
+    CooldownActive,
     InternalError,
 }
 
 impl std::fmt::Display for ErrorCode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            ErrorCode::Locked => write!(f, "LOCKED"),
-            ErrorCode::InvalidPassphrase => write!(f, "INVALID_PASSPHRASE"),
-            ErrorCode::SignerNotFound => write!(f, "SIGNER_NOT_FOUND"),
-            ErrorCode::InvalidTransaction => write!(f, "INVALID_TRANSACTION"),
-            ErrorCode::HardwareError => write!(f, "HARDWARE_ERROR"),
-            ErrorCode::InternalError => write!(f, "INTERNAL_ERROR"),
+        solana_keyring_errors::ErrorCode::from(*self).fmt(f)
+    }
+}
+
+impl From<ErrorCode> for solana_keyring_errors::ErrorCode {
+    fn from(code: ErrorCode) -> Self {
+        use solana_keyring_errors::ErrorCode as Shared;
+        match code {
+            ErrorCode::Locked => Shared::Locked,
+            ErrorCode::InvalidPassphrase => Shared::InvalidPassphrase,
+            ErrorCode::TooManyAttempts => Shared::TooManyAttempts,
+            ErrorCode::SignerNotFound => Shared::SignerNotFound,
+            ErrorCode::InvalidTransaction => Shared::InvalidTransaction,
+            ErrorCode::HardwareError => Shared::HardwareError,
+            ErrorCode::ReadOnly => Shared::ReadOnly,
+            ErrorCode::NotFound => Shared::NotFound,
+            ErrorCode::SpendingLimitExceeded => Shared::SpendingLimitExceeded,
+            ErrorCode::ConfirmationRequired => Shared::ConfirmationRequired,
+            ErrorCode::Unauthorized => Shared::Unauthorized,
+            ErrorCode::ClusterMismatch => Shared::ClusterMismatch,
+            ErrorCode::CooldownActive => Shared::CooldownActive,
+            ErrorCode::InternalError => Shared::InternalError,
+        }
+    }
+}
+
+/// Shared codes the agent never reports are sent as `InternalError`.
+impl From<solana_keyring_errors::ErrorCode> for ErrorCode {
+    fn from(code: solana_keyring_errors::ErrorCode) -> Self {
+        use solana_keyring_errors::ErrorCode as Shared;
+        match code {
+            Shared::Locked => ErrorCode::Locked,
+            Shared::InvalidPassphrase => ErrorCode::InvalidPassphrase,
+            Shared::TooManyAttempts => ErrorCode::TooManyAttempts,
+            Shared::SignerNotFound => ErrorCode::SignerNotFound,
+            Shared::InvalidTransaction => ErrorCode::InvalidTransaction,
+            Shared::HardwareError => ErrorCode::HardwareError,
+            Shared::ReadOnly => ErrorCode::ReadOnly,
+            Shared::NotFound => ErrorCode::NotFound,
+            Shared::SpendingLimitExceeded => ErrorCode::SpendingLimitExceeded,
+            Shared::ConfirmationRequired => ErrorCode::ConfirmationRequired,
+            Shared::Unauthorized => ErrorCode::Unauthorized,
+            Shared::ClusterMismatch => ErrorCode::ClusterMismatch,
+            Shared::CooldownActive => ErrorCode::CooldownActive,
+            Shared::InternalError
+            | Shared::InvalidArguments
+            | Shared::ConnectionFailed
+            | Shared::IoError
+            | Shared::NotInitialized
+            | Shared::InvalidSignature => ErrorCode::InternalError,
         }
     }
 }
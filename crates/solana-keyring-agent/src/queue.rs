@@ -0,0 +1,166 @@
+//! Per-signer signing queue
+//!
+//! Requests for the same signer run one at a time, in arrival order, so
+//! confirmation prompts and spending checks for a key never interleave.
+//! Requests for different signers still run in parallel.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::OwnedMutexGuard;
+
+#[derive(Default)]
+struct Slot {
+    turn: Arc<tokio::sync::Mutex<()>>,
+    /// Requests holding or waiting for the turn
+    waiting: AtomicUsize,
+}
+
+/// Queues signing requests by signer public key
+#[derive(Default)]
+pub struct SigningQueue {
+    slots: Mutex<HashMap<String, Arc<Slot>>>,
+}
+
+impl SigningQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wait for the signer's turn, reporting the queue position if not first
+    pub async fn enter(&self, signer: &str) -> QueueTicket {
+        // Counted under the lock, so a concurrent enter can't prune the slot
+        // before this request holds it, and before awaiting, so a cancelled
+        // wait still leaves the queue
+        let (slot, ahead) = {
+            let mut slots = self.slots.lock().unwrap_or_else(|e| e.into_inner());
+            slots.retain(|_, slot| slot.waiting.load(Ordering::SeqCst) > 0);
+            let slot = slots.entry(signer.to_string()).or_default().clone();
+            let ahead = slot.waiting.fetch_add(1, Ordering::SeqCst);
+            (slot, ahead)
+        };
+        let waiting = Waiting(slot.clone());
+        if ahead > 0 {
+            eprintln!(
+                "Signing request for {} queued at position {}",
+                signer,
+                ahead + 1
+            );
+        }
+
+        let turn = slot.turn.clone().lock_owned().await;
+        QueueTicket {
+            _turn: turn,
+            waiting,
+        }
+    }
+
+    /// Requests running or waiting across all signers
+    pub fn len(&self) -> usize {
+        self.slots
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .map(|slot| slot.waiting.load(Ordering::SeqCst))
+            .sum()
+    }
+}
+
+struct Waiting(Arc<Slot>);
+
+impl Drop for Waiting {
+    fn drop(&mut self) {
+        self.0.waiting.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// A signer's turn in the queue, released on drop
+pub struct QueueTicket {
+    // Field order matters: the turn is released before the count drops
+    _turn: OwnedMutexGuard<()>,
+    waiting: Waiting,
+}
+
+impl QueueTicket {
+    /// Requests queued for the same signer behind this one
+    pub fn behind(&self) -> usize {
+        self.waiting
+            .0
+            .waiting
+            .load(Ordering::SeqCst)
+            .saturating_sub(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_same_signer_serialized() {
+        let queue = Arc::new(SigningQueue::new());
+        let active = Arc::new(AtomicUsize::new(0));
+        let overlapped = Arc::new(AtomicUsize::new(0));
+
+        let mut tasks = Vec::new();
+        for i in 0..64 {
+            let queue = queue.clone();
+            let active = active.clone();
+            let overlapped = overlapped.clone();
+            tasks.push(tokio::spawn(async move {
+                // Requests for other signers churn the slot map meanwhile
+                if i % 2 == 1 {
+                    let _other = queue.enter(&format!("other-{}", i)).await;
+                    tokio::task::yield_now().await;
+                    return;
+                }
+
+                let _ticket = queue.enter("alice").await;
+                if active.fetch_add(1, Ordering::SeqCst) > 0 {
+                    overlapped.fetch_add(1, Ordering::SeqCst);
+                }
+                tokio::task::yield_now().await;
+                active.fetch_sub(1, Ordering::SeqCst);
+            }));
+        }
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        assert_eq!(overlapped.load(Ordering::SeqCst), 0);
+        assert_eq!(queue.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_different_signers_parallel() {
+        let queue = SigningQueue::new();
+        let alice = queue.enter("alice").await;
+        let bob = queue.enter("bob").await;
+        assert_eq!(queue.len(), 2);
+        assert_eq!(alice.behind(), 0);
+        drop((alice, bob));
+        assert_eq!(queue.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_behind_counts_waiters() {
+        let queue = Arc::new(SigningQueue::new());
+        let first = queue.enter("alice").await;
+
+        let waiter = tokio::spawn({
+            let queue = queue.clone();
+            async move {
+                let _ticket = queue.enter("alice").await;
+            }
+        });
+        while first.behind() == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        assert_eq!(first.behind(), 1);
+        drop(first);
+        waiter.await.unwrap();
+        assert_eq!(queue.len(), 0);
+    }
+}
@@ -0,0 +1,190 @@
+//! Remote signing over HTTPS with mutual TLS
+//!
+//! CI runners and servers that can't reach the local socket send agent
+//! requests to `POST /v1/agent` over TLS. Clients must present a certificate
+//! issued by the configured client CA; the request body is a JSON [`Request`]
+//! and the reply a JSON [`Response`]. Only the methods allowed by
+//! [`Request::allowed_remotely`] are served, and an
+//! `Authorization: Bearer <token>` header limits the request to a session
+//! token's scope.
+
+use std::fs::File;
+use std::io::BufReader;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use solana_keyring::output;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::RwLock;
+use tokio_rustls::TlsAcceptor;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::server::WebPkiClientVerifier;
+use tokio_rustls::rustls::{RootCertStore, ServerConfig};
+
+use crate::agent::{AgentState, process_request};
+use crate::approval::http::{self, HttpRequest, HttpResponse};
+use crate::protocol::{ErrorCode, Request, Response};
+use crate::telemetry;
+
+/// Path remote requests are sent to
+const AGENT_PATH: &str = "/v1/agent";
+
+/// Remote signing listener settings
+pub struct RemoteConfig {
+    /// Address to listen on
+    pub listen: SocketAddr,
+    /// PEM server certificate chain
+    pub cert: PathBuf,
+    /// PEM server private key
+    pub key: PathBuf,
+    /// PEM CA certificates that client certificates must chain to
+    pub client_ca: PathBuf,
+}
+
+impl RemoteConfig {
+    /// Load the certificates and build a TLS acceptor requiring client certs
+    pub fn acceptor(&self) -> anyhow::Result<TlsAcceptor> {
+        let certs = load_certs(&self.cert)?;
+        let key = load_key(&self.key)?;
+
+        let mut roots = RootCertStore::empty();
+        for ca in load_certs(&self.client_ca)? {
+            roots.add(ca)?;
+        }
+        let verifier = WebPkiClientVerifier::builder(Arc::new(roots)).build()?;
+
+        let config = ServerConfig::builder()
+            .with_client_cert_verifier(verifier)
+            .with_single_cert(certs, key)?;
+        Ok(TlsAcceptor::from(Arc::new(config)))
+    }
+}
+
+/// A configured remote signing listener
+pub struct RemoteServer {
+    pub listen: SocketAddr,
+    pub acceptor: TlsAcceptor,
+}
+
+impl RemoteServer {
+    pub fn new(config: &RemoteConfig) -> anyhow::Result<Self> {
+        Ok(Self {
+            listen: config.listen,
+            acceptor: config.acceptor()?,
+        })
+    }
+
+    /// Accept remote signing connections
+    pub async fn serve(self, state: Arc<RwLock<AgentState>>) -> anyhow::Result<()> {
+        let listener = TcpListener::bind(self.listen).await?;
+        println!("Remote signing listening on https://{}", self.listen);
+
+        loop {
+            let (stream, peer) = listener.accept().await?;
+            let acceptor = self.acceptor.clone();
+            let state = state.clone();
+            tokio::spawn(async move {
+                if let Err(e) = handle_connection(stream, acceptor, state).await {
+                    eprintln!(
+                        "{}",
+                        output::error(format!("Remote connection error from {}: {}", peer, e))
+                    );
+                }
+            });
+        }
+    }
+}
+
+async fn handle_connection(
+    stream: TcpStream,
+    acceptor: TlsAcceptor,
+    state: Arc<RwLock<AgentState>>,
+) -> anyhow::Result<()> {
+    // The handshake fails unless the client presents a trusted certificate
+    let mut stream = acceptor.accept(stream).await?;
+    let request = http::read_request(&mut stream).await?;
+    let response = handle_request(&request, &state).await;
+    http::write_response(&mut stream, response).await
+}
+
+async fn handle_request(request: &HttpRequest, state: &Arc<RwLock<AgentState>>) -> HttpResponse {
+    if request.path != AGENT_PATH {
+        return HttpResponse::status(404);
+    }
+    if request.method != "POST" {
+        return HttpResponse::status(405);
+    }
+
+    let agent_request = match serde_json::from_slice::<Request>(&request.body) {
+        Ok(r) => r,
+        Err(e) => {
+            return reply(
+                400,
+                Response::error(ErrorCode::InternalError, e.to_string()),
+            );
+        }
+    };
+    if !agent_request.allowed_remotely() {
+        return reply(
+            403,
+            Response::error(
+                ErrorCode::Unauthorized,
+                format!(
+                    "{} is only available on the local socket",
+                    agent_request.method()
+                ),
+            ),
+        );
+    }
+
+    let mut token = None;
+    if let Some(auth) = request.header("authorization") {
+        let Some(secret) = auth.strip_prefix("Bearer ") else {
+            return HttpResponse::status(401);
+        };
+        match state.read().await.tokens.authenticate(secret.trim()) {
+            Some(id) => token = Some(id),
+            None => {
+                return reply(
+                    401,
+                    Response::error(
+                        ErrorCode::Unauthorized,
+                        "Invalid, expired or revoked session token",
+                    ),
+                );
+            }
+        }
+    }
+
+    let method = agent_request.method();
+    let response = telemetry::request(
+        method,
+        process_request(agent_request, state, &mut None, &mut token),
+    )
+    .await;
+    reply(200, response)
+}
+
+fn reply(status: u16, response: Response) -> HttpResponse {
+    HttpResponse {
+        status,
+        content_type: "application/json",
+        body: serde_json::to_string(&response).unwrap_or_default(),
+    }
+}
+
+fn load_certs(path: &Path) -> anyhow::Result<Vec<CertificateDer<'static>>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let certs = rustls_pemfile::certs(&mut reader).collect::<Result<Vec<_>, _>>()?;
+    if certs.is_empty() {
+        anyhow::bail!("No certificates found in {}", path.display());
+    }
+    Ok(certs)
+}
+
+fn load_key(path: &Path) -> anyhow::Result<PrivateKeyDer<'static>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    rustls_pemfile::private_key(&mut reader)?
+        .ok_or_else(|| anyhow::anyhow!("No private key found in {}", path.display()))
+}
@@ -0,0 +1,401 @@
+//! Encrypted session for passphrase entry
+//!
+//! Frontends (the `unlock` command, GUI pinentry helpers) that see
+//! [`ENCRYPTED_UNLOCK`] in the agent's `Hello` capabilities can send the
+//! passphrase sealed to a per-connection key instead of as plaintext JSON:
+//!
+//! 1. The agent's `Hello` advertises its long-term [`AgentIdentity`], which
+//!    the client checks against the public key file the agent publishes
+//! 2. The client sends `OpenSession` with an ephemeral X25519 public key
+//! 3. The agent replies with its own ephemeral public key
+//! 4. Both sides derive an AES-256-GCM key from the ephemeral-ephemeral and
+//!    ephemeral-identity shared secrets and all three public keys
+//! 5. The client sends `SecureUnlock` with the sealed passphrase
+//!
+//! Only the holder of the identity secret can derive the session key, so a
+//! process impersonating the agent on its socket can't read the passphrase.
+//! A session key seals or opens a single message and is then discarded.
+
+use std::path::{Path, PathBuf};
+
+use aes_gcm::{
+    Aes256Gcm, Nonce,
+    aead::{Aead, KeyInit, Payload},
+};
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use x25519_dalek::{PublicKey, ReusableSecret, SharedSecret, StaticSecret};
+use zeroize::Zeroizing;
+
+/// Capability advertised by agents that accept `SecureUnlock`
+pub const ENCRYPTED_UNLOCK: &str = "encrypted-unlock";
+
+/// Domain separation for session key derivation
+const KDF_CONTEXT: &[u8] = b"solana-keyring-agent/unlock/v2";
+
+/// Associated data binding sealed messages to their purpose
+const UNLOCK_AAD: &[u8] = b"unlock";
+
+/// Where the agent listening on `socket_path` keeps its identity key
+pub fn identity_path(socket_path: &Path) -> PathBuf {
+    socket_path.with_extension("key")
+}
+
+/// Where the agent listening on `socket_path` publishes its identity's
+/// public key, for clients to pin
+pub fn public_identity_path(socket_path: &Path) -> PathBuf {
+    socket_path.with_extension("pub")
+}
+
+/// The agent's long-term X25519 key
+pub struct AgentIdentity {
+    secret: StaticSecret,
+    public: PublicKey,
+}
+
+impl AgentIdentity {
+    /// Generate a new identity
+    pub fn generate() -> Self {
+        Self::from_secret(StaticSecret::random_from_rng(rand::rngs::OsRng))
+    }
+
+    /// Load the identity key file, creating it (owner-only) if missing
+    ///
+    /// The public key is (re)published at `public_path`, so clients can pin
+    /// the agent without reading its secret.
+    pub fn load_or_create(path: &Path, public_path: &Path) -> anyhow::Result<Self> {
+        let identity = match read_key_file(path)? {
+            Some(bytes) => {
+                restrict_key_file(path)?;
+                Self::from_secret(StaticSecret::from(*bytes))
+            }
+            None => {
+                let identity = Self::generate();
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                write_key_file(path, identity.secret.as_bytes())?;
+                identity
+            }
+        };
+
+        if Self::read_public(public_path).ok().flatten() != Some(identity.public_key()) {
+            write_public_file(public_path, &identity.public_key())?;
+        }
+        Ok(identity)
+    }
+
+    /// Base64 encoded public key published at `path`, if any
+    pub fn read_public(path: &Path) -> anyhow::Result<Option<String>> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let public_key = contents.trim();
+        decode_public_key(public_key).map_err(|_| {
+            anyhow::anyhow!("Agent public key {} is not a valid key", path.display())
+        })?;
+        Ok(Some(public_key.to_string()))
+    }
+
+    /// Base64 encoded public key, advertised in `Hello`
+    pub fn public_key(&self) -> String {
+        BASE64.encode(self.public.as_bytes())
+    }
+
+    fn from_secret(secret: StaticSecret) -> Self {
+        let public = PublicKey::from(&secret);
+        Self { secret, public }
+    }
+}
+
+fn read_key_file(path: &Path) -> anyhow::Result<Option<Zeroizing<[u8; 32]>>> {
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => Zeroizing::new(bytes),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let key: [u8; 32] = bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Agent identity key {} must be 32 bytes", path.display()))?;
+    Ok(Some(Zeroizing::new(key)))
+}
+
+fn write_key_file(path: &Path, secret: &[u8; 32]) -> anyhow::Result<()> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600); // Owner read/write only
+    }
+    options.open(path)?.write_all(secret)?;
+    Ok(())
+}
+
+/// Make an existing identity key file owner-only
+fn restrict_key_file(path: &Path) -> anyhow::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if std::fs::metadata(path)?.permissions().mode() & 0o077 != 0 {
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+/// Replace the public key file, readable by anyone
+fn write_public_file(path: &Path, public_key: &str) -> anyhow::Result<()> {
+    let partial = path.with_extension("pub.tmp");
+    std::fs::write(&partial, format!("{}\n", public_key))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&partial, std::fs::Permissions::from_mode(0o644))?;
+    }
+    std::fs::rename(&partial, path)?;
+    Ok(())
+}
+
+/// One side of a key exchange
+pub struct Handshake {
+    secret: ReusableSecret,
+    public: PublicKey,
+}
+
+impl Handshake {
+    /// Generate an ephemeral key pair
+    pub fn new() -> Self {
+        let secret = ReusableSecret::random_from_rng(rand::rngs::OsRng);
+        let public = PublicKey::from(&secret);
+        Self { secret, public }
+    }
+
+    /// Base64 encoded public key to send to the peer
+    pub fn public_key(&self) -> String {
+        BASE64.encode(self.public.as_bytes())
+    }
+
+    /// Complete the exchange as the client (the side that sent `OpenSession`)
+    ///
+    /// `identity` is the agent's verified identity public key.
+    pub fn finish_client(self, agent_public: &str, identity: &str) -> anyhow::Result<SessionKey> {
+        let agent_public = decode_public_key(agent_public)?;
+        let identity = decode_public_key(identity)?;
+        derive(
+            [
+                self.secret.diffie_hellman(&agent_public),
+                self.secret.diffie_hellman(&identity),
+            ],
+            [&self.public, &agent_public, &identity],
+        )
+    }
+
+    /// Complete the exchange as the agent
+    pub fn finish_agent(
+        self,
+        client_public: &str,
+        identity: &AgentIdentity,
+    ) -> anyhow::Result<SessionKey> {
+        let client_public = decode_public_key(client_public)?;
+        derive(
+            [
+                self.secret.diffie_hellman(&client_public),
+                identity.secret.diffie_hellman(&client_public),
+            ],
+            [&client_public, &self.public, &identity.public],
+        )
+    }
+}
+
+/// Hash both shared secrets and the client, agent and identity public keys
+fn derive(shared: [SharedSecret; 2], publics: [&PublicKey; 3]) -> anyhow::Result<SessionKey> {
+    if shared.iter().any(|secret| !secret.was_contributory()) {
+        anyhow::bail!("Invalid session public key");
+    }
+
+    let mut hasher = Sha256::new();
+    hasher.update(KDF_CONTEXT);
+    for secret in &shared {
+        hasher.update(secret.as_bytes());
+    }
+    for public in publics {
+        hasher.update(public.as_bytes());
+    }
+    Ok(SessionKey(Zeroizing::new(hasher.finalize().into())))
+}
+
+/// Symmetric key for a session
+pub struct SessionKey(Zeroizing<[u8; 32]>);
+
+impl SessionKey {
+    /// Seal a passphrase, returning base64 `(nonce, ciphertext)`
+    pub fn seal(self, plaintext: &[u8]) -> anyhow::Result<(String, String)> {
+        let mut nonce = [0u8; 12];
+        rand::thread_rng().fill_bytes(&mut nonce);
+
+        let ciphertext = self
+            .cipher()
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: plaintext,
+                    aad: UNLOCK_AAD,
+                },
+            )
+            .map_err(|_| anyhow::anyhow!("Failed to seal message"))?;
+
+        Ok((BASE64.encode(nonce), BASE64.encode(ciphertext)))
+    }
+
+    /// Open a sealed passphrase
+    pub fn open(self, nonce: &str, ciphertext: &str) -> anyhow::Result<Zeroizing<Vec<u8>>> {
+        let nonce = BASE64.decode(nonce)?;
+        if nonce.len() != 12 {
+            anyhow::bail!("Nonce must be 12 bytes");
+        }
+        let ciphertext = BASE64.decode(ciphertext)?;
+
+        let plaintext = self
+            .cipher()
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: UNLOCK_AAD,
+                },
+            )
+            .map_err(|_| anyhow::anyhow!("Failed to open sealed message"))?;
+
+        Ok(Zeroizing::new(plaintext))
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new_from_slice(self.0.as_slice()).expect("AES-256-GCM key should be 32 bytes")
+    }
+}
+
+fn decode_public_key(encoded: &str) -> anyhow::Result<PublicKey> {
+    let bytes: [u8; 32] = BASE64
+        .decode(encoded)?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Session public key must be 32 bytes"))?;
+    Ok(PublicKey::from(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Run a handshake, with the client trusting `trusted` as the identity
+    fn session(identity: &AgentIdentity, trusted: &str) -> (SessionKey, SessionKey) {
+        let client = Handshake::new();
+        let agent = Handshake::new();
+        let client_public = client.public_key();
+        let agent_public = agent.public_key();
+
+        let agent_key = agent.finish_agent(&client_public, identity).unwrap();
+        let client_key = client.finish_client(&agent_public, trusted).unwrap();
+        (client_key, agent_key)
+    }
+
+    #[test]
+    fn test_seal_open() {
+        let identity = AgentIdentity::generate();
+        let (client, agent) = session(&identity, &identity.public_key());
+
+        let (nonce, ciphertext) = client.seal(b"hunter2").unwrap();
+        assert_eq!(
+            agent.open(&nonce, &ciphertext).unwrap().as_slice(),
+            b"hunter2"
+        );
+    }
+
+    #[test]
+    fn test_wrong_identity() {
+        let identity = AgentIdentity::generate();
+        let impostor = AgentIdentity::generate();
+        let (client, agent) = session(&impostor, &identity.public_key());
+
+        let (nonce, ciphertext) = client.seal(b"hunter2").unwrap();
+        assert!(agent.open(&nonce, &ciphertext).is_err());
+    }
+
+    #[test]
+    fn test_tampered_ciphertext() {
+        let identity = AgentIdentity::generate();
+        let (client, agent) = session(&identity, &identity.public_key());
+
+        let (nonce, ciphertext) = client.seal(b"hunter2").unwrap();
+        let mut bytes = BASE64.decode(&ciphertext).unwrap();
+        bytes[0] ^= 1;
+        assert!(agent.open(&nonce, &BASE64.encode(bytes)).is_err());
+    }
+
+    #[test]
+    fn test_replayed_nonce() {
+        let identity = AgentIdentity::generate();
+        let (client, _) = session(&identity, &identity.public_key());
+        let (nonce, ciphertext) = client.seal(b"hunter2").unwrap();
+
+        // A sealed message opens only under the session it was sealed for
+        let (_, agent) = session(&identity, &identity.public_key());
+        assert!(agent.open(&nonce, &ciphertext).is_err());
+    }
+
+    #[test]
+    fn test_identity_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join("agent.sock");
+        let (path, public_path) = (identity_path(&socket), public_identity_path(&socket));
+        assert!(AgentIdentity::read_public(&public_path).unwrap().is_none());
+
+        let identity = AgentIdentity::load_or_create(&path, &public_path).unwrap();
+        assert_eq!(
+            AgentIdentity::read_public(&public_path).unwrap(),
+            Some(identity.public_key())
+        );
+        assert_eq!(
+            AgentIdentity::load_or_create(&path, &public_path)
+                .unwrap()
+                .public_key(),
+            identity.public_key()
+        );
+
+        // Only the agent can read the secret; the public key is published
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+            assert_eq!(mode(&path), 0o600);
+            assert_eq!(mode(&public_path), 0o644);
+        }
+
+        // A key file others could read is made owner-only again
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+            AgentIdentity::load_or_create(&path, &public_path).unwrap();
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode() & 0o777;
+            assert_eq!(mode, 0o600);
+        }
+
+        // A missing or stale public key file is republished from the secret
+        std::fs::write(&public_path, AgentIdentity::generate().public_key()).unwrap();
+        AgentIdentity::load_or_create(&path, &public_path).unwrap();
+        assert_eq!(
+            AgentIdentity::read_public(&public_path).unwrap(),
+            Some(identity.public_key())
+        );
+    }
+}
@@ -0,0 +1,268 @@
+//! OpenTelemetry export
+//!
+//! With the `otel` feature and an OTLP endpoint configured, every request the
+//! agent handles becomes a trace. Confirmation prompts (biometric or chat) and
+//! outbound calls to chat APIs are recorded as child spans, so a slow or denied
+//! signature can be followed from the socket request down to the prompt that
+//! held it. Request and confirmation counts and request latency are exported
+//! as metrics.
+//!
+//! Without the feature the wrappers below just await the wrapped future.
+
+use std::future::Future;
+
+use crate::approval::Confirmation;
+use crate::protocol::Response;
+
+#[cfg(feature = "otel")]
+pub use otel::init;
+
+/// Run a socket request in its own trace
+pub async fn request(method: &'static str, fut: impl Future<Output = Response>) -> Response {
+    #[cfg(feature = "otel")]
+    {
+        otel::request(method, fut).await
+    }
+    #[cfg(not(feature = "otel"))]
+    {
+        let _ = method;
+        fut.await
+    }
+}
+
+/// Run a confirmation prompt in a child span
+pub async fn confirmation(
+    backend: &'static str,
+    fut: impl Future<Output = Confirmation>,
+) -> Confirmation {
+    #[cfg(feature = "otel")]
+    {
+        otel::confirmation(backend, fut).await
+    }
+    #[cfg(not(feature = "otel"))]
+    {
+        let _ = backend;
+        fut.await
+    }
+}
+
+/// Run an outbound HTTP call in a child span
+pub async fn outbound<T>(
+    service: &'static str,
+    fut: impl Future<Output = anyhow::Result<T>>,
+) -> anyhow::Result<T> {
+    #[cfg(feature = "otel")]
+    {
+        otel::outbound(service, fut).await
+    }
+    #[cfg(not(feature = "otel"))]
+    {
+        let _ = service;
+        fut.await
+    }
+}
+
+#[cfg(feature = "otel")]
+mod otel {
+    use std::future::Future;
+    use std::sync::OnceLock;
+    use std::time::Instant;
+
+    use opentelemetry::metrics::{Counter, Histogram, MeterProvider as _};
+    use opentelemetry::trace::{
+        FutureExt as _, Span as _, SpanKind, Status, TraceContextExt as _, Tracer as _,
+        TracerProvider as _,
+    };
+    use opentelemetry::{Context, KeyValue};
+    use opentelemetry_otlp::{MetricExporter, SpanExporter, WithExportConfig as _};
+    use opentelemetry_sdk::Resource;
+    use opentelemetry_sdk::metrics::SdkMeterProvider;
+    use opentelemetry_sdk::trace::{SdkTracer, SdkTracerProvider};
+
+    use crate::approval::Confirmation;
+    use crate::protocol::Response;
+
+    /// Instrumentation scope and service name
+    const NAME: &str = "solana-keyring-agent";
+
+    /// Standard environment variable for the OTLP endpoint
+    const ENDPOINT_ENV: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+
+    static INSTRUMENTS: OnceLock<Instruments> = OnceLock::new();
+
+    struct Instruments {
+        tracer: SdkTracer,
+        requests: Counter<u64>,
+        request_duration: Histogram<f64>,
+        confirmations: Counter<u64>,
+    }
+
+    /// Installed exporters, flushed and shut down on drop
+    pub struct Telemetry {
+        tracer_provider: SdkTracerProvider,
+        meter_provider: SdkMeterProvider,
+    }
+
+    impl Drop for Telemetry {
+        fn drop(&mut self) {
+            let _ = self.tracer_provider.shutdown();
+            let _ = self.meter_provider.shutdown();
+        }
+    }
+
+    /// Start exporting over OTLP/HTTP
+    ///
+    /// Uses `endpoint` if given, otherwise `OTEL_EXPORTER_OTLP_ENDPOINT`.
+    /// Returns `None` (and exports nothing) when neither is set.
+    pub fn init(endpoint: Option<&str>) -> anyhow::Result<Option<Telemetry>> {
+        if endpoint.is_none() && std::env::var_os(ENDPOINT_ENV).is_none() {
+            return Ok(None);
+        }
+
+        let mut spans = SpanExporter::builder().with_http();
+        let mut metrics = MetricExporter::builder().with_http();
+        if let Some(endpoint) = endpoint {
+            let base = endpoint.trim_end_matches('/');
+            spans = spans.with_endpoint(format!("{}/v1/traces", base));
+            metrics = metrics.with_endpoint(format!("{}/v1/metrics", base));
+        }
+
+        let resource = Resource::builder()
+            .with_service_name(NAME)
+            .with_attribute(KeyValue::new("service.version", env!("CARGO_PKG_VERSION")))
+            .build();
+        let tracer_provider = SdkTracerProvider::builder()
+            .with_batch_exporter(spans.build()?)
+            .with_resource(resource.clone())
+            .build();
+        let meter_provider = SdkMeterProvider::builder()
+            .with_periodic_exporter(metrics.build()?)
+            .with_resource(resource)
+            .build();
+
+        let meter = meter_provider.meter(NAME);
+        let _ = INSTRUMENTS.set(Instruments {
+            tracer: tracer_provider.tracer(NAME),
+            requests: meter
+                .u64_counter("keyring_agent.requests")
+                .with_description("Requests handled, by method and outcome")
+                .build(),
+            request_duration: meter
+                .f64_histogram("keyring_agent.request.duration")
+                .with_description("Time to handle a request, including confirmation")
+                .with_unit("s")
+                .build(),
+            confirmations: meter
+                .u64_counter("keyring_agent.confirmations")
+                .with_description("Confirmation prompts, by backend and outcome")
+                .build(),
+        });
+
+        Ok(Some(Telemetry {
+            tracer_provider,
+            meter_provider,
+        }))
+    }
+
+    pub async fn request(method: &'static str, fut: impl Future<Output = Response>) -> Response {
+        let Some(instruments) = INSTRUMENTS.get() else {
+            return fut.await;
+        };
+
+        let started = Instant::now();
+        let span = instruments
+            .tracer
+            .span_builder(format!("agent {}", method))
+            .with_kind(SpanKind::Server)
+            .with_attributes([KeyValue::new("rpc.method", method)])
+            .start_with_context(&instruments.tracer, &Context::new());
+        let cx = Context::current_with_span(span);
+
+        let response = fut.with_context(cx.clone()).await;
+
+        let outcome = match &response {
+            Response::Ok { .. } => "ok".to_string(),
+            Response::Error { code, message } => {
+                cx.span().set_status(Status::error(message.clone()));
+                code.to_string()
+            }
+        };
+        cx.span()
+            .set_attribute(KeyValue::new("keyring.outcome", outcome.clone()));
+        cx.span().end();
+
+        let attributes = [
+            KeyValue::new("rpc.method", method),
+            KeyValue::new("keyring.outcome", outcome),
+        ];
+        instruments.requests.add(1, &attributes);
+        instruments
+            .request_duration
+            .record(started.elapsed().as_secs_f64(), &attributes[..1]);
+        response
+    }
+
+    pub async fn confirmation(
+        backend: &'static str,
+        fut: impl Future<Output = Confirmation>,
+    ) -> Confirmation {
+        let Some(instruments) = INSTRUMENTS.get() else {
+            return fut.await;
+        };
+
+        let span = instruments
+            .tracer
+            .span_builder("confirmation")
+            .with_attributes([KeyValue::new("keyring.confirmer", backend)])
+            .start(&instruments.tracer);
+        // Chat API calls made while prompting nest under the prompt
+        let cx = Context::current_with_span(span);
+
+        let confirmation = fut.with_context(cx.clone()).await;
+
+        let outcome = match &confirmation {
+            Confirmation::Approved => "approved",
+            Confirmation::Denied(reason) => {
+                cx.span().set_status(Status::error(reason.clone()));
+                "denied"
+            }
+            Confirmation::Unavailable(_) => "unavailable",
+        };
+        cx.span()
+            .set_attribute(KeyValue::new("keyring.outcome", outcome));
+        cx.span().end();
+
+        instruments.confirmations.add(
+            1,
+            &[
+                KeyValue::new("keyring.confirmer", backend),
+                KeyValue::new("keyring.outcome", outcome),
+            ],
+        );
+        confirmation
+    }
+
+    pub async fn outbound<T>(
+        service: &'static str,
+        fut: impl Future<Output = anyhow::Result<T>>,
+    ) -> anyhow::Result<T> {
+        let Some(instruments) = INSTRUMENTS.get() else {
+            return fut.await;
+        };
+
+        let mut span = instruments
+            .tracer
+            .span_builder(format!("{} post", service))
+            .with_kind(SpanKind::Client)
+            .with_attributes([KeyValue::new("peer.service", service)])
+            .start(&instruments.tracer);
+
+        let result = fut.await;
+
+        if let Err(e) = &result {
+            span.set_status(Status::error(e.to_string()));
+        }
+        span.end();
+        result
+    }
+}
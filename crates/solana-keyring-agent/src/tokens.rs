@@ -0,0 +1,468 @@
+//! Scoped session tokens
+//!
+//! The owner's own connections to the socket can do anything. A bridge that
+//! forwards requests from somewhere less trusted (a browser extension, a web
+//! dashboard) instead authenticates each connection with a token the owner
+//! issued, and that connection is then limited to the token's scope:
+//!
+//! - which signers it may use
+//! - which kinds of request it may make (e.g. sign-message only)
+//! - how many lamports its signed transactions may move in total
+//!
+//! Tokens live in agent memory only; restarting the agent revokes them all.
+//! The agent keeps a hash of each token, so listing them never reveals one.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use solana_keyring::spending::unix_now;
+
+use crate::protocol::{ErrorCode, Request, Response, TokenInfo};
+
+/// Prefix of issued tokens, so they are recognizable in configs and logs
+const TOKEN_PREFIX: &str = "ska_";
+
+/// What a scoped connection may do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TokenPermission {
+    /// List signers and addresses, resolve labels
+    Read,
+    /// Sign transactions
+    SignTransaction,
+    /// Sign off-chain messages
+    SignMessage,
+}
+
+impl TokenPermission {
+    /// The permission a request needs, or `None` if scoped connections may
+    /// never make it (unlocking, key management, token management)
+    fn required_by(request: &Request) -> Option<Self> {
+        match request {
+            Request::ListSigners { .. } | Request::ResolveLabel { .. } | Request::ListAddresses => {
+                Some(TokenPermission::Read)
+            }
+            Request::SignTransaction { .. } => Some(TokenPermission::SignTransaction),
+            Request::SignMessage { .. } => Some(TokenPermission::SignMessage),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for TokenPermission {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TokenPermission::Read => write!(f, "read"),
+            TokenPermission::SignTransaction => write!(f, "sign-transaction"),
+            TokenPermission::SignMessage => write!(f, "sign-message"),
+        }
+    }
+}
+
+impl std::str::FromStr for TokenPermission {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "read" => Ok(TokenPermission::Read),
+            "sign-transaction" => Ok(TokenPermission::SignTransaction),
+            "sign-message" => Ok(TokenPermission::SignMessage),
+            _ => Err(format!(
+                "Unknown permission: {} (use read, sign-transaction or sign-message)",
+                s
+            )),
+        }
+    }
+}
+
+/// Restrictions on a token's connections
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TokenScope {
+    /// Public keys of the signers it may use; empty allows every signer
+    #[serde(default)]
+    pub signers: Vec<String>,
+    /// Requests it may make
+    pub permissions: Vec<TokenPermission>,
+    /// Lamports its signed transactions may move in total; `None` is uncapped
+    #[serde(default)]
+    pub max_lamports: Option<u64>,
+}
+
+impl TokenScope {
+    /// Whether the scope allows `signer` (a public key)
+    pub fn allows_signer(&self, signer: &str) -> bool {
+        self.signers.is_empty() || self.signers.iter().any(|s| s == signer)
+    }
+}
+
+struct StoredToken {
+    label: String,
+    hash: [u8; 32],
+    scope: TokenScope,
+    created_at: i64,
+    expires_at: Option<i64>,
+    spent_lamports: u64,
+}
+
+impl StoredToken {
+    fn is_expired(&self, now: i64) -> bool {
+        self.expires_at.is_some_and(|at| now >= at)
+    }
+}
+
+/// Tokens issued by the owner, by ID
+#[derive(Default)]
+pub struct TokenStore {
+    tokens: Mutex<HashMap<String, StoredToken>>,
+}
+
+impl TokenStore {
+    /// Issue a token, returning its ID and the token itself
+    ///
+    /// The token is only ever returned here.
+    pub fn issue(
+        &self,
+        label: &str,
+        scope: TokenScope,
+        ttl_seconds: Option<u64>,
+    ) -> (String, String) {
+        let mut secret = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut secret);
+        let token = format!("{}{}", TOKEN_PREFIX, hex::encode(secret));
+        let hash = hash_token(&token);
+        let id = token_id(&hash);
+
+        let now = unix_now();
+        self.lock().insert(
+            id.clone(),
+            StoredToken {
+                label: label.to_string(),
+                hash,
+                scope,
+                created_at: now,
+                expires_at: ttl_seconds.map(|ttl| now.saturating_add(ttl as i64)),
+                spent_lamports: 0,
+            },
+        );
+        (id, token)
+    }
+
+    /// The ID of a valid, unexpired token
+    pub fn authenticate(&self, token: &str) -> Option<String> {
+        let hash = hash_token(token);
+        let id = token_id(&hash);
+        let tokens = self.lock();
+        let stored = tokens.get(&id)?;
+        (stored.hash == hash && !stored.is_expired(unix_now())).then_some(id)
+    }
+
+    /// The scope of a token, if it is still valid
+    pub fn scope(&self, id: &str) -> Option<TokenScope> {
+        let tokens = self.lock();
+        let stored = tokens.get(id)?;
+        (!stored.is_expired(unix_now())).then(|| stored.scope.clone())
+    }
+
+    /// Check a request against a token's scope
+    ///
+    /// `signer` is the public key the request would use, if any.
+    pub fn authorize(
+        &self,
+        id: &str,
+        request: &Request,
+        signer: Option<&str>,
+    ) -> Result<(), Response> {
+        let Some(scope) = self.scope(id) else {
+            return Err(Response::error(
+                ErrorCode::Unauthorized,
+                "Session token has expired or was revoked",
+            ));
+        };
+
+        // Liveness and handshakes are always allowed
+        if matches!(request, Request::Ping | Request::Hello | Request::Status) {
+            return Ok(());
+        }
+
+        match TokenPermission::required_by(request) {
+            Some(permission) if scope.permissions.contains(&permission) => {}
+            _ => {
+                return Err(Response::error(
+                    ErrorCode::Unauthorized,
+                    format!("Session token does not allow {}", request.method()),
+                ));
+            }
+        }
+
+        match signer {
+            Some(signer) if !scope.allows_signer(signer) => Err(Response::error(
+                ErrorCode::Unauthorized,
+                format!("Session token does not allow signer {}", signer),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Reserve `lamports` against a token's cap
+    ///
+    /// Reservations count immediately, so concurrent requests can't overrun
+    /// the cap; release them if the request isn't signed. `None` is an
+    /// outflow that couldn't be determined, which a capped token refuses.
+    pub fn reserve(&self, id: &str, lamports: Option<u64>) -> Result<(), Response> {
+        let mut tokens = self.lock();
+        let Some(stored) = tokens.get_mut(id) else {
+            return Err(Response::error(
+                ErrorCode::Unauthorized,
+                "Session token has expired or was revoked",
+            ));
+        };
+        let Some(lamports) = lamports else {
+            if stored.scope.max_lamports.is_some() {
+                return Err(Response::error(
+                    ErrorCode::SpendingLimitExceeded,
+                    "Session token cap can't be checked: transaction outflow is unknown",
+                ));
+            }
+            return Ok(());
+        };
+        if let Some(max) = stored.scope.max_lamports {
+            let spent = stored.spent_lamports.saturating_add(lamports);
+            if spent > max {
+                return Err(Response::error(
+                    ErrorCode::SpendingLimitExceeded,
+                    format!(
+                        "Session token cap exceeded: requested {} lamports, {} of {} remaining",
+                        lamports,
+                        max.saturating_sub(stored.spent_lamports),
+                        max
+                    ),
+                ));
+            }
+        }
+        stored.spent_lamports = stored.spent_lamports.saturating_add(lamports);
+        Ok(())
+    }
+
+    /// Return a reservation for a request that wasn't signed
+    pub fn release(&self, id: &str, lamports: u64) {
+        if let Some(stored) = self.lock().get_mut(id) {
+            stored.spent_lamports = stored.spent_lamports.saturating_sub(lamports);
+        }
+    }
+
+    /// All tokens, expired ones included, ordered by creation
+    pub fn list(&self) -> Vec<TokenInfo> {
+        let now = unix_now();
+        let mut infos: Vec<TokenInfo> = self
+            .lock()
+            .iter()
+            .map(|(id, stored)| TokenInfo {
+                id: id.clone(),
+                label: stored.label.clone(),
+                scope: stored.scope.clone(),
+                created_at: stored.created_at,
+                expires_at: stored.expires_at,
+                expired: stored.is_expired(now),
+                spent_lamports: stored.spent_lamports,
+            })
+            .collect();
+        infos.sort_by(|a, b| (a.created_at, &a.id).cmp(&(b.created_at, &b.id)));
+        infos
+    }
+
+    /// Revoke a token; its connections are refused from the next request
+    pub fn revoke(&self, id: &str) -> bool {
+        self.lock().remove(id).is_some()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, StoredToken>> {
+        self.tokens.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn hash_token(token: &str) -> [u8; 32] {
+    Sha256::digest(token.as_bytes()).into()
+}
+
+/// Short public ID derived from the token's hash
+fn token_id(hash: &[u8; 32]) -> String {
+    hex::encode(&hash[..6])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scope(signers: &[&str], permissions: &[TokenPermission], max: Option<u64>) -> TokenScope {
+        TokenScope {
+            signers: signers.iter().map(|s| s.to_string()).collect(),
+            permissions: permissions.to_vec(),
+            max_lamports: max,
+        }
+    }
+
+    fn sign_transaction(signer: &str) -> Request {
+        Request::SignTransaction {
+            transaction: String::new(),
+            signer: signer.to_string(),
+        }
+    }
+
+    fn code(result: Result<(), Response>) -> ErrorCode {
+        match result {
+            Err(Response::Error { code, .. }) => code,
+            other => panic!("expected an error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_issue_authenticate() {
+        let store = TokenStore::default();
+        let (id, token) = store.issue("bridge", TokenScope::default(), None);
+
+        assert!(token.starts_with(TOKEN_PREFIX));
+        assert_eq!(store.authenticate(&token), Some(id.clone()));
+        assert_eq!(store.authenticate(&format!("{}0", token)), None);
+        assert_eq!(store.authenticate("ska_nope"), None);
+
+        // Listing never reveals the token
+        let listed = store.list();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, id);
+        assert!(!serde_json::to_string(&listed).unwrap().contains(&token));
+    }
+
+    #[test]
+    fn test_required_by() {
+        assert_eq!(
+            TokenPermission::required_by(&Request::ListSigners { tag: None }),
+            Some(TokenPermission::Read)
+        );
+        assert_eq!(
+            TokenPermission::required_by(&sign_transaction("alice")),
+            Some(TokenPermission::SignTransaction)
+        );
+        assert_eq!(
+            TokenPermission::required_by(&Request::SignMessage {
+                message: String::new(),
+                signer: "alice".to_string(),
+            }),
+            Some(TokenPermission::SignMessage)
+        );
+        assert_eq!(TokenPermission::required_by(&Request::Lock), None);
+    }
+
+    #[test]
+    fn test_authorize_request_type() {
+        let store = TokenStore::default();
+        let (id, _) = store.issue(
+            "bridge",
+            scope(&[], &[TokenPermission::SignMessage], None),
+            None,
+        );
+
+        assert!(store.authorize(&id, &Request::Ping, None).is_ok());
+        assert_eq!(
+            code(store.authorize(&id, &sign_transaction("alice"), Some("alice"))),
+            ErrorCode::Unauthorized
+        );
+        assert_eq!(
+            code(store.authorize(&id, &Request::Lock, None)),
+            ErrorCode::Unauthorized
+        );
+    }
+
+    #[test]
+    fn test_authorize_signer() {
+        let store = TokenStore::default();
+        let (id, _) = store.issue(
+            "bridge",
+            scope(&["alice"], &[TokenPermission::SignTransaction], None),
+            None,
+        );
+
+        let request = sign_transaction("alice");
+        assert!(store.authorize(&id, &request, Some("alice")).is_ok());
+        assert_eq!(
+            code(store.authorize(&id, &request, Some("bob"))),
+            ErrorCode::Unauthorized
+        );
+    }
+
+    #[test]
+    fn test_reserve_release() {
+        let store = TokenStore::default();
+        let (id, _) = store.issue(
+            "bridge",
+            scope(&[], &[TokenPermission::SignTransaction], Some(1_000)),
+            None,
+        );
+
+        // Reservations are cumulative
+        store.reserve(&id, Some(600)).unwrap();
+        assert_eq!(
+            code(store.reserve(&id, Some(500))),
+            ErrorCode::SpendingLimitExceeded
+        );
+        store.reserve(&id, Some(400)).unwrap();
+        assert_eq!(store.list()[0].spent_lamports, 1_000);
+
+        // A failed signature returns its reservation
+        store.release(&id, 400);
+        store.reserve(&id, Some(400)).unwrap();
+
+        // An unknown outflow can't be checked against the cap
+        assert_eq!(
+            code(store.reserve(&id, None)),
+            ErrorCode::SpendingLimitExceeded
+        );
+    }
+
+    #[test]
+    fn test_reserve_uncapped() {
+        let store = TokenStore::default();
+        let (id, _) = store.issue("bridge", TokenScope::default(), None);
+        store.reserve(&id, Some(u64::MAX)).unwrap();
+        store.reserve(&id, None).unwrap();
+    }
+
+    #[test]
+    fn test_expiry() {
+        let store = TokenStore::default();
+        let (id, token) = store.issue(
+            "bridge",
+            scope(&[], &[TokenPermission::Read], None),
+            Some(0),
+        );
+
+        assert_eq!(store.authenticate(&token), None);
+        assert!(store.scope(&id).is_none());
+        assert_eq!(
+            code(store.authorize(&id, &Request::ListAddresses, None)),
+            ErrorCode::Unauthorized
+        );
+        assert!(store.list()[0].expired);
+    }
+
+    #[test]
+    fn test_revoke() {
+        let store = TokenStore::default();
+        let (id, token) = store.issue(
+            "bridge",
+            scope(&[], &[TokenPermission::Read], Some(10)),
+            None,
+        );
+
+        assert!(store.revoke(&id));
+        assert!(!store.revoke(&id));
+        assert_eq!(store.authenticate(&token), None);
+        assert_eq!(
+            code(store.authorize(&id, &Request::ListAddresses, None)),
+            ErrorCode::Unauthorized
+        );
+        assert_eq!(code(store.reserve(&id, Some(1))), ErrorCode::Unauthorized);
+    }
+}
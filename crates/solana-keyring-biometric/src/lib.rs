@@ -37,6 +37,10 @@ pub enum Error {
     /// Invalid response from authentication
     #[error("Invalid authentication response: {0}")]
     InvalidResponse(String),
+
+    /// The operation is not supported on this platform
+    #[error("Not supported: {0}")]
+    Unsupported(String),
 }
 
 /// Result type for biometric operations
@@ -372,6 +376,85 @@ pub fn confirm_signing_with_config(
     authenticate_with_config(&reason, config)
 }
 
+/// Ask the user to type a value into a dialog
+///
+/// On macOS, this shows a dialog with a text field and returns what was
+/// typed, or `None` if the user cancelled. Other platforms have no dialog to
+/// show, so this returns [`Error::Unsupported`].
+///
+/// # Errors
+///
+/// Returns an error if the Swift runtime fails or the response is invalid.
+///
+/// # Example
+///
+/// ```no_run
+/// use solana_keyring_biometric::prompt_text;
+///
+/// match prompt_text("Confirm amount", "Type the amount in SOL")? {
+///     Some(text) => println!("Entered {}", text),
+///     None => println!("Cancelled"),
+/// }
+/// # Ok::<(), solana_keyring_biometric::Error>(())
+/// ```
+#[cfg(target_os = "macos")]
+pub fn prompt_text(title: &str, message: &str) -> Result<Option<String>> {
+    let swift_code = format!(
+        r#"
+import AppKit
+
+let app = NSApplication.shared
+app.setActivationPolicy(.accessory)
+app.activate(ignoringOtherApps: true)
+
+let alert = NSAlert()
+alert.messageText = "{title}"
+alert.informativeText = "{message}"
+alert.addButton(withTitle: "OK")
+alert.addButton(withTitle: "Cancel")
+let field = NSTextField(frame: NSRect(x: 0, y: 0, width: 240, height: 24))
+alert.accessoryView = field
+alert.window.initialFirstResponder = field
+
+if alert.runModal() == .alertFirstButtonReturn {{
+    print("entered:" + field.stringValue)
+}} else {{
+    print("cancelled")
+}}
+"#,
+        title = escape_swift_string(title),
+        message = escape_swift_string(message)
+    );
+
+    let output = run_swift(&swift_code)?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let result = stdout.trim();
+
+    if let Some(text) = result.strip_prefix("entered:") {
+        Ok(Some(text.trim().to_string()))
+    } else if result == "cancelled" {
+        Ok(None)
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !stderr.is_empty() {
+            Err(Error::SwiftExecution(stderr.to_string()))
+        } else {
+            Err(Error::InvalidResponse(format!(
+                "Unexpected response: '{}', exit code: {:?}",
+                result,
+                output.status.code()
+            )))
+        }
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn prompt_text(_title: &str, _message: &str) -> Result<Option<String>> {
+    Err(Error::Unsupported(
+        "Text prompts are only available on macOS".into(),
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
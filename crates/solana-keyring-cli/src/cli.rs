@@ -3,6 +3,7 @@
 use std::path::PathBuf;
 
 use clap::{Parser, Subcommand, ValueEnum};
+use solana_keyring::allowlist::DestinationPolicy;
 
 #[derive(Parser)]
 #[command(
@@ -23,6 +24,10 @@ pub struct Cli {
     #[arg(long, global = true)]
     pub agent_socket: Option<PathBuf>,
 
+    /// Disable colors and symbols (also honors NO_COLOR)
+    #[arg(long, global = true)]
+    pub plain: bool,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -58,13 +63,67 @@ pub enum Commands {
     #[command(subcommand)]
     Ledger(LedgerCommands),
 
+    /// YubiKey PIV key commands
+    #[command(subcommand)]
+    Yubikey(YubikeyCommands),
+
     /// Squads multisig commands
     #[command(subcommand)]
     Squads(SquadsCommands),
 
+    /// Address lookup tables, with keyring-held authorities
+    #[command(subcommand)]
+    Alt(AltCommands),
+
     /// Address book management
     #[command(subcommand)]
     AddressBook(AddressBookCommands),
+
+    /// Spending limit management
+    #[command(subcommand)]
+    Limit(LimitCommands),
+
+    /// Bind signers to a cluster so they can't sign for another one
+    #[command(subcommand)]
+    Cluster(ClusterCommands),
+
+    /// Enforce a signing cool-down and typed amount confirmation on a key
+    #[command(subcommand)]
+    HighValue(HighValueCommands),
+
+    /// Dry-run signing rules against a transaction, or lint them
+    #[command(subcommand)]
+    Policy(PolicyCommands),
+
+    /// Let a bot key request signatures for one transaction template
+    #[command(subcommand)]
+    Delegate(DelegateCommands),
+
+    /// Create a high-value key through a guided ceremony
+    Ceremony(CeremonyArgs),
+
+    /// Backup management
+    #[command(subcommand)]
+    Backup(BackupCommands),
+
+    /// Vacuum the database, prune old records and report storage
+    Maintenance(MaintenanceArgs),
+
+    /// Export and verify the audit log
+    #[command(subcommand)]
+    Audit(AuditCommands),
+
+    /// Language of transaction summaries and prompts
+    #[command(subcommand)]
+    Locale(LocaleCommands),
+
+    /// Failed unlock attempts, hard-lock and recovery code
+    #[command(subcommand)]
+    Lockout(LockoutCommands),
+
+    /// Encrypted secrets, such as RPC provider API keys
+    #[command(subcommand)]
+    Secret(SecretCommands),
 }
 
 #[derive(clap::Args)]
@@ -83,6 +142,123 @@ pub struct GenerateArgs {
     /// Tags to add to the keypair
     #[arg(short, long)]
     pub tag: Vec<String>,
+
+    /// Derive the keypair from a new seed phrase (as `solana-keygen new`)
+    #[arg(long)]
+    pub seed_phrase: bool,
+
+    /// Number of words in the seed phrase
+    #[arg(long, default_value = "12", requires = "seed_phrase", value_parser = ["12", "15", "18", "21", "24"])]
+    pub word_count: String,
+
+    /// Do not prompt for a BIP39 passphrase
+    #[arg(long, requires = "seed_phrase")]
+    pub no_bip39_passphrase: bool,
+
+    /// Grind for a public key starting with these base58 characters
+    #[arg(long, value_name = "PREFIX", conflicts_with = "seed_phrase")]
+    pub starts_with: Option<String>,
+
+    /// Grind for a public key ending with these base58 characters
+    #[arg(long, value_name = "SUFFIX", conflicts_with = "seed_phrase")]
+    pub ends_with: Option<String>,
+
+    /// Ignore case in --starts-with and --ends-with
+    #[arg(long)]
+    pub ignore_case: bool,
+
+    /// Also write an offline bundle to this directory: the key encrypted under
+    /// the keyring passphrase and a public manifest for `import --public-manifest`
+    #[arg(long, value_name = "DIR")]
+    pub offline_bundle: Option<PathBuf>,
+}
+
+#[derive(clap::Args)]
+pub struct CeremonyArgs {
+    /// Label for the new keypair
+    #[arg(short, long)]
+    pub label: String,
+
+    /// Tags to add to the keypair
+    #[arg(short, long)]
+    pub tag: Vec<String>,
+
+    /// Also mix in dice rolls as an entropy source
+    #[arg(long)]
+    pub dice: bool,
+
+    /// Shamir shares required to recover the key
+    #[arg(long, default_value = "2")]
+    pub threshold: u8,
+
+    /// Shamir shares to print
+    #[arg(long, default_value = "3")]
+    pub shares: u8,
+
+    /// Register the key as watch-only in another keyring database (repeatable)
+    #[arg(long)]
+    pub watch_db: Vec<PathBuf>,
+}
+
+#[derive(clap::Args)]
+pub struct MaintenanceArgs {
+    /// Set the audit log retention (e.g. 90d), or "off" to keep everything
+    #[arg(long)]
+    pub audit_retention: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum AuditCommands {
+    /// Export audit events with their chain verification status
+    Export(AuditExportArgs),
+    /// Seal the audit log up to its newest event under the master passphrase
+    Seal,
+}
+
+#[derive(clap::Args)]
+pub struct AuditExportArgs {
+    /// Only export events from this long ago (e.g. 30d)
+    #[arg(long)]
+    pub since: Option<String>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "jsonl")]
+    pub format: AuditExportFormat,
+
+    /// Write to a file instead of stdout
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum AuditExportFormat {
+    Jsonl,
+    Csv,
+}
+
+#[derive(Subcommand)]
+pub enum LocaleCommands {
+    /// Show the active locale and bundled translations
+    Show,
+    /// Set the preferred locale (e.g. es)
+    Set(LocaleSetArgs),
+    /// Clear the preferred locale and follow the system locale
+    Unset,
+    /// Print English messages as a catalog template for translators
+    Extract(LocaleExtractArgs),
+}
+
+#[derive(clap::Args)]
+pub struct LocaleSetArgs {
+    /// Locale code
+    pub locale: String,
+}
+
+#[derive(clap::Args)]
+pub struct LocaleExtractArgs {
+    /// Only print messages this locale is missing
+    #[arg(long)]
+    pub missing: Option<String>,
 }
 
 #[derive(clap::Args)]
@@ -99,6 +275,42 @@ pub struct ImportArgs {
     #[arg(short, long, conflicts_with = "file")]
     pub base58: Option<String>,
 
+    /// Recover from a seed phrase and optional passphrase (as `solana-keygen recover`)
+    #[arg(long, conflicts_with_all = ["file", "base58"])]
+    pub seed_phrase: bool,
+
+    /// Accept seed phrases that are not valid BIP39 mnemonics
+    #[arg(long, requires = "seed_phrase")]
+    pub skip_seed_phrase_validation: bool,
+
+    /// Derive the account at this path from the seed phrase (e.g. "m/44'/501'/0'/0'"),
+    /// as wallets like Phantom do, instead of using the seed itself
+    #[arg(long, requires = "seed_phrase", conflicts_with = "pick_account")]
+    pub derivation_path: Option<String>,
+
+    /// List the first accounts derived from the seed phrase and pick one to import
+    #[arg(long, requires = "seed_phrase")]
+    pub pick_account: bool,
+
+    /// Import the default keypair from a Solana CLI config file
+    /// (~/.config/solana/cli/config.yml if no path is given)
+    #[arg(
+        long,
+        value_name = "CONFIG",
+        num_args = 0..=1,
+        conflicts_with_all = ["file", "base58", "seed_phrase"]
+    )]
+    pub from_solana_config: Option<Option<PathBuf>>,
+
+    /// Register a key generated offline as watch-only from its public
+    /// manifest (a manifest.json file or bundle directory)
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["file", "base58", "seed_phrase", "from_solana_config"]
+    )]
+    pub public_manifest: Option<PathBuf>,
+
     /// Tags to add to the keypair
     #[arg(short, long)]
     pub tag: Vec<String>,
@@ -134,16 +346,85 @@ pub struct ListArgs {
     #[arg(long)]
     pub tag: Option<String>,
 
+    /// Filter as key=value (type=ledger, tag=treasury, label=ops); may be repeated
+    #[arg(long, value_parser = parse_list_filter)]
+    pub filter: Vec<ListFilter>,
+
+    /// Sort order
+    #[arg(long)]
+    pub sort: Option<ListSort>,
+
+    /// Reverse the sort order
+    #[arg(long)]
+    pub reverse: bool,
+
+    /// Columns to show in table output (comma-separated)
+    #[arg(
+        long,
+        value_delimiter = ',',
+        default_value = "type,pubkey,fingerprint,label,tags"
+    )]
+    pub columns: Vec<ListColumn>,
+
+    /// RPC URL used to fetch balances
+    #[arg(long, default_value = "https://api.mainnet-beta.solana.com")]
+    pub rpc_url: String,
+
     /// Output format
     #[arg(short, long, default_value = "table")]
     pub format: OutputFormat,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ListSort {
+    Label,
+    Created,
+    LastUsed,
+    Balance,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ListColumn {
+    Type,
+    Pubkey,
+    Fingerprint,
+    Label,
+    Tags,
+    Created,
+    LastUsed,
+    Balance,
+}
+
+/// A `--filter key=value` condition on listed signers
+#[derive(Clone)]
+pub enum ListFilter {
+    Type(SignerTypeFilter),
+    Tag(String),
+    Label(String),
+}
+
+fn parse_list_filter(s: &str) -> Result<ListFilter, String> {
+    let (key, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("Invalid filter '{}' (expected key=value)", s))?;
+    match key {
+        "type" => SignerTypeFilter::from_str(value, true).map(ListFilter::Type),
+        "tag" => Ok(ListFilter::Tag(value.to_string())),
+        "label" => Ok(ListFilter::Label(value.to_string())),
+        _ => Err(format!(
+            "Unknown filter key '{}' (use type, tag or label)",
+            key
+        )),
+    }
+}
+
 #[derive(Clone, ValueEnum)]
 pub enum SignerTypeFilter {
     Keypair,
     Ledger,
+    Yubikey,
     Squads,
+    Custom,
     All,
 }
 
@@ -231,6 +512,10 @@ pub struct LedgerAddArgs {
     /// Tags to add
     #[arg(short, long)]
     pub tag: Vec<String>,
+
+    /// Show the address on the Ledger and add it only once approved there
+    #[arg(long)]
+    pub verify: bool,
 }
 
 #[derive(clap::Args)]
@@ -239,9 +524,49 @@ pub struct LedgerRemoveArgs {
     pub identifier: String,
 }
 
+// YubiKey commands
+#[derive(Subcommand)]
+pub enum YubikeyCommands {
+    /// Add a key from a YubiKey PIV slot
+    Add(YubikeyAddArgs),
+    /// List YubiKey wallets
+    List,
+    /// List connected YubiKeys
+    Devices,
+    /// Remove a YubiKey wallet
+    Remove(YubikeyRemoveArgs),
+}
+
+#[derive(clap::Args)]
+pub struct YubikeyAddArgs {
+    /// Label for the YubiKey wallet
+    #[arg(short, long)]
+    pub label: String,
+
+    /// PIV slot holding the Ed25519 key (9a, 9c, 9d, 9e, 82-95)
+    #[arg(short, long, default_value = "9c")]
+    pub slot: String,
+
+    /// Serial number of the YubiKey (default: the only one connected)
+    #[arg(long)]
+    pub serial: Option<u32>,
+
+    /// Tags to add
+    #[arg(short, long)]
+    pub tag: Vec<String>,
+}
+
+#[derive(clap::Args)]
+pub struct YubikeyRemoveArgs {
+    /// Public key or label to remove
+    pub identifier: String,
+}
+
 // Squads commands
 #[derive(Subcommand)]
 pub enum SquadsCommands {
+    /// Create a new Squads multisig and add it
+    Create(SquadsCreateArgs),
     /// Add a Squads multisig
     Add(SquadsAddArgs),
     /// List Squads multisigs
@@ -250,6 +575,58 @@ pub enum SquadsCommands {
     Remove(SquadsRemoveArgs),
     /// Sync multisig members from on-chain
     Sync(SquadsSyncArgs),
+    /// List a multisig's vaults with their addresses and balances
+    Vaults(SquadsVaultsArgs),
+    /// Name a vault so it can be picked by name
+    NameVault(SquadsNameVaultArgs),
+    /// Set the vault used when none is given
+    DefaultVault(SquadsDefaultVaultArgs),
+    /// Check a proposal's transaction against a local intent file
+    Verify(SquadsVerifyArgs),
+    /// Create a proposal from an unsigned transaction file
+    Propose(SquadsProposeArgs),
+    /// List proposals created from this keyring
+    Pending(SquadsPendingArgs),
+    /// Vote against an active proposal
+    Reject(SquadsVoteArgs),
+    /// Withdraw an approved proposal before it is executed
+    Cancel(SquadsVoteArgs),
+}
+
+#[derive(clap::Args)]
+pub struct SquadsCreateArgs {
+    /// Label for the multisig
+    #[arg(short, long)]
+    pub label: String,
+
+    /// Member as PUBKEY or PUBKEY:PERMISSIONS, e.g. KEY:vote,execute
+    /// (permissions: initiate, vote, execute, all; default all)
+    #[arg(short, long, required = true)]
+    pub member: Vec<String>,
+
+    /// Approvals needed to execute a proposal
+    #[arg(long)]
+    pub threshold: u16,
+
+    /// Seconds between approval and when a proposal can be executed
+    #[arg(long, default_value_t = 0)]
+    pub time_lock: u32,
+
+    /// Authority that can change the config without a vote
+    #[arg(long)]
+    pub config_authority: Option<String>,
+
+    /// Keypair that pays rent and the creation fee (public key or label)
+    #[arg(long)]
+    pub payer: String,
+
+    /// RPC URL
+    #[arg(long, default_value = "https://api.mainnet-beta.solana.com")]
+    pub rpc_url: String,
+
+    /// Tags to add
+    #[arg(short, long)]
+    pub tag: Vec<String>,
 }
 
 #[derive(clap::Args)]
@@ -261,6 +638,10 @@ pub struct SquadsAddArgs {
     #[arg(short, long)]
     pub label: String,
 
+    /// Default vault index
+    #[arg(long, default_value_t = 0)]
+    pub vault_index: u8,
+
     /// RPC URL
     #[arg(long, default_value = "https://api.mainnet-beta.solana.com")]
     pub rpc_url: String,
@@ -286,6 +667,190 @@ pub struct SquadsSyncArgs {
     pub rpc_url: String,
 }
 
+#[derive(clap::Args)]
+pub struct SquadsVaultsArgs {
+    /// Multisig address or label
+    pub identifier: String,
+
+    /// RPC URL
+    #[arg(long, default_value = "https://api.mainnet-beta.solana.com")]
+    pub rpc_url: String,
+}
+
+#[derive(clap::Args)]
+pub struct SquadsNameVaultArgs {
+    /// Multisig address or label
+    pub identifier: String,
+
+    /// Vault index
+    pub vault_index: u8,
+
+    /// Name for the vault
+    pub name: String,
+}
+
+#[derive(clap::Args)]
+pub struct SquadsDefaultVaultArgs {
+    /// Multisig address or label
+    pub identifier: String,
+
+    /// Vault name or index
+    pub vault: String,
+}
+
+#[derive(clap::Args)]
+pub struct SquadsVerifyArgs {
+    /// Multisig address or label
+    pub identifier: String,
+
+    /// Transaction index of the proposal
+    pub index: u64,
+
+    /// JSON file describing what the proposal should do
+    #[arg(long)]
+    pub intent: PathBuf,
+
+    /// RPC URL
+    #[arg(long, default_value = "https://api.mainnet-beta.solana.com")]
+    pub rpc_url: String,
+}
+
+#[derive(clap::Args)]
+pub struct SquadsProposeArgs {
+    /// Multisig address or label
+    #[arg(long)]
+    pub multisig: String,
+
+    /// Unsigned message or transaction (raw bytes, base64 or base58)
+    #[arg(long)]
+    pub tx: PathBuf,
+
+    /// Member keypair that creates the proposal (public key or label)
+    #[arg(long)]
+    pub member: String,
+
+    /// Vault name or index; defaults to the vault that signs the message
+    #[arg(long)]
+    pub vault: Option<String>,
+
+    /// Memo shown with the proposal in the Squads UI
+    #[arg(long)]
+    pub memo: Option<String>,
+
+    /// RPC URL
+    #[arg(long, default_value = "https://api.mainnet-beta.solana.com")]
+    pub rpc_url: String,
+}
+
+#[derive(clap::Args)]
+pub struct SquadsPendingArgs {
+    /// Multisig address or label (default: all)
+    pub identifier: Option<String>,
+}
+
+#[derive(clap::Args)]
+pub struct SquadsVoteArgs {
+    /// Multisig address or label
+    pub identifier: String,
+
+    /// Transaction index of the proposal
+    pub index: u64,
+
+    /// Member keypair that votes (public key or label)
+    #[arg(long)]
+    pub member: String,
+
+    /// Memo shown with the vote in the Squads UI
+    #[arg(long)]
+    pub memo: Option<String>,
+
+    /// RPC URL
+    #[arg(long, default_value = "https://api.mainnet-beta.solana.com")]
+    pub rpc_url: String,
+}
+
+// Address lookup table commands
+#[derive(Subcommand)]
+pub enum AltCommands {
+    /// Create an empty lookup table
+    Create(AltCreateArgs),
+    /// Add addresses to a lookup table
+    Extend(AltExtendArgs),
+    /// Deactivate a lookup table so it can be closed
+    Deactivate(AltDeactivateArgs),
+    /// Close a deactivated lookup table and reclaim its rent
+    Close(AltCloseArgs),
+}
+
+#[derive(clap::Args)]
+pub struct AltCreateArgs {
+    /// Public key or label of the table's authority
+    #[arg(long)]
+    pub authority: String,
+
+    /// Public key or label of the fee payer (defaults to the authority)
+    #[arg(long)]
+    pub payer: Option<String>,
+
+    /// RPC URL
+    #[arg(long, default_value = "https://api.mainnet-beta.solana.com")]
+    pub rpc_url: String,
+}
+
+#[derive(clap::Args)]
+pub struct AltExtendArgs {
+    /// Lookup table address
+    pub table: String,
+
+    /// Addresses to add; ones already in the table are skipped
+    #[arg(required = true)]
+    pub addresses: Vec<String>,
+
+    /// Public key or label of the table's authority
+    #[arg(long)]
+    pub authority: String,
+
+    /// Public key or label of the fee payer (defaults to the authority)
+    #[arg(long)]
+    pub payer: Option<String>,
+
+    /// RPC URL
+    #[arg(long, default_value = "https://api.mainnet-beta.solana.com")]
+    pub rpc_url: String,
+}
+
+#[derive(clap::Args)]
+pub struct AltDeactivateArgs {
+    /// Lookup table address
+    pub table: String,
+
+    /// Public key or label of the table's authority
+    #[arg(long)]
+    pub authority: String,
+
+    /// RPC URL
+    #[arg(long, default_value = "https://api.mainnet-beta.solana.com")]
+    pub rpc_url: String,
+}
+
+#[derive(clap::Args)]
+pub struct AltCloseArgs {
+    /// Lookup table address
+    pub table: String,
+
+    /// Public key or label of the table's authority
+    #[arg(long)]
+    pub authority: String,
+
+    /// Address that receives the table's rent (defaults to the authority)
+    #[arg(long)]
+    pub recipient: Option<String>,
+
+    /// RPC URL
+    #[arg(long, default_value = "https://api.mainnet-beta.solana.com")]
+    pub rpc_url: String,
+}
+
 // Address book commands
 #[derive(Subcommand)]
 pub enum AddressBookCommands {
@@ -297,6 +862,10 @@ pub enum AddressBookCommands {
     Remove(AddressBookRemoveArgs),
     /// Update an address label
     Label(AddressBookLabelArgs),
+    /// Add a tag to an address
+    Tag(AddressBookTagArgs),
+    /// Remove a tag from an address
+    Untag(AddressBookTagArgs),
 }
 
 #[derive(clap::Args)]
@@ -311,6 +880,10 @@ pub struct AddressBookAddArgs {
     /// Optional notes
     #[arg(short, long)]
     pub notes: Option<String>,
+
+    /// Tags to add (e.g. "verified")
+    #[arg(short, long)]
+    pub tag: Vec<String>,
 }
 
 #[derive(clap::Args)]
@@ -327,3 +900,251 @@ pub struct AddressBookLabelArgs {
     /// New label
     pub label: String,
 }
+
+// Spending limit commands
+#[derive(Subcommand)]
+pub enum LimitCommands {
+    /// Add a spending limit for a key or tag
+    Add(LimitAddArgs),
+    /// List spending limits and remaining allowances
+    List,
+    /// Remove a spending limit
+    Remove(LimitRemoveArgs),
+}
+
+#[derive(clap::Args)]
+pub struct LimitAddArgs {
+    /// Public key or label of the signer to limit
+    #[arg(long, conflicts_with = "tag", required_unless_present = "tag")]
+    pub key: Option<String>,
+
+    /// Tag to limit (applies to all tagged signers combined)
+    #[arg(long)]
+    pub tag: Option<String>,
+
+    /// Maximum SOL allowed per window
+    #[arg(long)]
+    pub sol: String,
+
+    /// Rolling window (e.g. 30m, 24h, 7d)
+    #[arg(long, default_value = "24h")]
+    pub window: String,
+}
+
+#[derive(clap::Args)]
+pub struct LimitRemoveArgs {
+    /// Limit ID (see `solana-keyring limit list`)
+    pub id: i64,
+}
+
+// Cluster binding commands
+#[derive(Subcommand)]
+pub enum ClusterCommands {
+    /// Bind a signer to a cluster
+    Bind(ClusterBindArgs),
+    /// List cluster bindings
+    List,
+    /// Remove a signer's cluster binding
+    Unbind(ClusterUnbindArgs),
+}
+
+#[derive(clap::Args)]
+pub struct ClusterBindArgs {
+    /// Public key or label of the signer
+    pub signer: String,
+
+    /// Cluster: mainnet, devnet, testnet or custom:<genesis hash>
+    pub cluster: String,
+
+    /// RPC URL for looking up blockhashes (required for custom clusters)
+    #[arg(long)]
+    pub url: Option<String>,
+
+    /// Warn instead of refusing when asked to sign for another cluster
+    #[arg(long)]
+    pub warn: bool,
+}
+
+#[derive(clap::Args)]
+pub struct ClusterUnbindArgs {
+    /// Public key or label of the signer
+    pub signer: String,
+}
+
+// High-value key commands
+#[derive(Subcommand)]
+pub enum HighValueCommands {
+    /// Designate a signer high-value, or change its cool-down
+    Set(HighValueSetArgs),
+    /// List high-value signers
+    List,
+    /// Remove a signer's high-value designation
+    Remove(HighValueRemoveArgs),
+}
+
+#[derive(clap::Args)]
+pub struct HighValueSetArgs {
+    /// Public key or label of the signer
+    pub signer: String,
+
+    /// Minimum time between signatures (e.g. 10m, 1h)
+    #[arg(long, default_value = "10m")]
+    pub cooldown: String,
+}
+
+#[derive(clap::Args)]
+pub struct HighValueRemoveArgs {
+    /// Public key or label of the signer
+    pub signer: String,
+}
+
+// Policy commands
+#[derive(Subcommand)]
+pub enum PolicyCommands {
+    /// Explain which rules would allow or deny a transaction
+    Check(PolicyCheckArgs),
+    /// Check spending limits, cluster bindings and the allowlist for mistakes
+    Lint(PolicyLintArgs),
+}
+
+#[derive(clap::Args)]
+pub struct PolicyCheckArgs {
+    /// File containing a serialized transaction message
+    pub transaction_file: PathBuf,
+
+    /// Public key or label of the signer
+    #[arg(long)]
+    pub signer: String,
+
+    /// Destination allowlist the agent runs with: off, address-book, or tag:<name>
+    #[arg(long, default_value = "off")]
+    pub destinations: DestinationPolicy,
+
+    /// Don't look up the blockhash for cluster bindings
+    #[arg(long)]
+    pub offline: bool,
+}
+
+#[derive(clap::Args)]
+pub struct PolicyLintArgs {
+    /// Destination allowlist the agent runs with: off, address-book, or tag:<name>
+    #[arg(long, default_value = "off")]
+    pub destinations: DestinationPolicy,
+}
+
+// Delegation commands
+#[derive(Subcommand)]
+pub enum DelegateCommands {
+    /// Allow a bot to have a signer sign transactions matching a template
+    Grant(DelegateGrantArgs),
+    /// List delegations and the nonces they have used
+    List(DelegateListArgs),
+    /// Revoke a delegation, or every delegation to a bot
+    Revoke(DelegateRevokeArgs),
+}
+
+#[derive(clap::Args)]
+pub struct DelegateGrantArgs {
+    /// Base58 public key of the bot
+    #[arg(long)]
+    pub bot: String,
+
+    /// Public key or label of the signer
+    #[arg(long)]
+    pub signer: String,
+
+    /// File containing a serialized transaction message to use as the template
+    #[arg(
+        long,
+        conflicts_with = "template_hash",
+        required_unless_present = "template_hash"
+    )]
+    pub template: Option<PathBuf>,
+
+    /// Hex template hash, as printed by a previous grant
+    #[arg(long)]
+    pub template_hash: Option<String>,
+
+    /// First nonce the bot may use
+    #[arg(long, default_value = "1")]
+    pub first_nonce: u64,
+
+    /// Last nonce the bot may use
+    #[arg(long)]
+    pub last_nonce: u64,
+}
+
+#[derive(clap::Args)]
+pub struct DelegateListArgs {
+    /// Include revoked delegations
+    #[arg(long)]
+    pub all: bool,
+}
+
+#[derive(clap::Args)]
+pub struct DelegateRevokeArgs {
+    /// Delegation ID (see `solana-keyring delegate list`)
+    #[arg(conflicts_with = "bot", required_unless_present = "bot")]
+    pub id: Option<i64>,
+
+    /// Revoke every delegation to this bot
+    #[arg(long)]
+    pub bot: Option<String>,
+}
+
+#[derive(clap::Args)]
+pub struct AddressBookTagArgs {
+    /// Public key or label
+    pub identifier: String,
+
+    /// Tag name
+    pub tag: String,
+}
+
+// Backup commands
+#[derive(Subcommand)]
+pub enum BackupCommands {
+    /// Verify a backup decrypts and covers every key in the keyring
+    Verify(BackupVerifyArgs),
+}
+
+#[derive(clap::Args)]
+pub struct BackupVerifyArgs {
+    /// Path to the backup database file
+    pub file: PathBuf,
+}
+
+#[derive(Subcommand)]
+pub enum LockoutCommands {
+    /// Show failed unlock attempts and whether unlocking is throttled
+    Status,
+    /// Hard-lock unlocking after repeated failures and print a recovery code
+    RecoveryCode(LockoutRecoveryCodeArgs),
+    /// Turn hard-locking off and forget the recovery code
+    Disable,
+    /// Lift a hard-lock with the recovery code
+    Recover,
+}
+
+#[derive(clap::Args)]
+pub struct LockoutRecoveryCodeArgs {
+    /// Failed attempts that hard-lock unlocking
+    #[arg(long, default_value = "10")]
+    pub hard_lock_after: u32,
+}
+
+#[derive(Subcommand)]
+pub enum SecretCommands {
+    /// Store a secret (prompts for the value), replacing one with the same name
+    Set(SecretNameArgs),
+    /// List secret names
+    List,
+    /// Remove a secret
+    Remove(SecretNameArgs),
+}
+
+#[derive(clap::Args)]
+pub struct SecretNameArgs {
+    /// Secret name, referenced as {{secret:NAME}} in rpc.json
+    pub name: String,
+}
@@ -4,6 +4,7 @@ use std::path::PathBuf;
 
 use anyhow::Result;
 use solana_keyring::AddressBook;
+use solana_keyring::output;
 
 use super::open_db;
 use crate::cli::AddressBookCommands;
@@ -15,13 +16,19 @@ pub fn run(cmd: AddressBookCommands, db_path: &Option<PathBuf>) -> Result<()> {
     match cmd {
         AddressBookCommands::Add(args) => {
             book.add(&args.pubkey, &args.label, args.notes.as_deref())?;
+            for tag in &args.tag {
+                book.add_tag(&args.pubkey, tag)?;
+            }
 
-            println!("Added address:");
+            println!("{}", output::success("Added address:"));
             println!("  Public key: {}", args.pubkey);
             println!("  Label: {}", args.label);
             if let Some(notes) = &args.notes {
                 println!("  Notes: {}", notes);
             }
+            if !args.tag.is_empty() {
+                println!("  Tags: {}", args.tag.join(", "));
+            }
         }
 
         AddressBookCommands::List => {
@@ -32,14 +39,16 @@ pub fn run(cmd: AddressBookCommands, db_path: &Option<PathBuf>) -> Result<()> {
                 return Ok(());
             }
 
-            println!("{:<44} {:<20} NOTES", "PUBLIC KEY", "LABEL");
-            println!("{}", "-".repeat(80));
+            println!("{:<44} {:<20} {:<20} NOTES", "PUBLIC KEY", "LABEL", "TAGS");
+            println!("{}", "-".repeat(100));
 
             for addr in addresses {
+                let tags = book.tags(&addr.pubkey)?;
                 println!(
-                    "{:<44} {:<20} {}",
+                    "{:<44} {:<20} {:<20} {}",
                     addr.pubkey,
                     addr.label,
+                    tags.join(","),
                     addr.notes.unwrap_or_default()
                 );
             }
@@ -49,7 +58,10 @@ pub fn run(cmd: AddressBookCommands, db_path: &Option<PathBuf>) -> Result<()> {
             let removed = book.remove(&args.identifier)?;
 
             if removed {
-                println!("Removed address '{}'", args.identifier);
+                println!(
+                    "{}",
+                    output::success(format!("Removed address '{}'", args.identifier))
+                );
             } else {
                 anyhow::bail!("Address not found: {}", args.identifier);
             }
@@ -59,11 +71,36 @@ pub fn run(cmd: AddressBookCommands, db_path: &Option<PathBuf>) -> Result<()> {
             let updated = book.update_label(&args.identifier, &args.label)?;
 
             if updated {
-                println!("Updated label to '{}'", args.label);
+                println!(
+                    "{}",
+                    output::success(format!("Updated label to '{}'", args.label))
+                );
             } else {
                 anyhow::bail!("Address not found: {}", args.identifier);
             }
         }
+
+        AddressBookCommands::Tag(args) => {
+            book.add_tag(&args.identifier, &args.tag)?;
+            println!(
+                "{}",
+                output::success(format!("Added tag '{}' to '{}'", args.tag, args.identifier))
+            );
+        }
+
+        AddressBookCommands::Untag(args) => {
+            if book.remove_tag(&args.identifier, &args.tag)? {
+                println!(
+                    "{}",
+                    output::success(format!(
+                        "Removed tag '{}' from '{}'",
+                        args.tag, args.identifier
+                    ))
+                );
+            } else {
+                println!("Tag '{}' not found on '{}'", args.tag, args.identifier);
+            }
+        }
     }
 
     Ok(())
@@ -0,0 +1,204 @@
+//! Address lookup table commands
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use solana_actor::{
+    LOOKUP_TABLE_MAX_ADDRESSES, MAX_EXTEND_ADDRESSES, check_transaction_size,
+    close_lookup_table_instruction, create_lookup_table_instruction,
+    deactivate_lookup_table_instruction, extend_lookup_table_instruction,
+    parse_address_lookup_table,
+};
+use solana_client::rpc_client::RpcClient;
+use solana_keyring::Database;
+use solana_keyring::output;
+use solana_keyring::progress::with_spinner;
+use solana_sdk::{
+    instruction::Instruction,
+    message::{Message, VersionedMessage},
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+    transaction::Transaction,
+};
+
+use super::{get_verified_passphrase, open_db};
+use crate::cli::AltCommands;
+
+pub fn run(cmd: AltCommands, db_path: &Option<PathBuf>) -> Result<()> {
+    let db = open_db(db_path)?;
+
+    match cmd {
+        AltCommands::Create(args) => {
+            let passphrase = get_verified_passphrase(&db)?;
+            let authority = load_signer(&db, &args.authority, &passphrase)?;
+            let payer = args
+                .payer
+                .as_deref()
+                .map(|payer| load_signer(&db, payer, &passphrase))
+                .transpose()?;
+            let payer = payer.as_ref().unwrap_or(&authority);
+            let rpc = RpcClient::new(args.rpc_url.clone());
+
+            // The table address is derived from a slot that must be in the
+            // slot hashes sysvar; at the client's default (finalized)
+            // commitment it can't be skipped
+            let recent_slot = rpc.get_slot()?;
+            let (instruction, table) =
+                create_lookup_table_instruction(&authority.pubkey(), &payer.pubkey(), recent_slot);
+            let signature = send(&rpc, &[instruction], payer, &[])?;
+
+            println!("{}", output::success("Created lookup table:"));
+            println!("  Address: {}", table);
+            println!("  Authority: {}", authority.pubkey());
+            println!("  Signature: {}", signature);
+        }
+
+        AltCommands::Extend(args) => {
+            let table_key = parse_pubkey(&args.table)?;
+            let addresses = args
+                .addresses
+                .iter()
+                .map(|address| parse_pubkey(address))
+                .collect::<Result<Vec<_>>>()?;
+
+            let rpc = RpcClient::new(args.rpc_url.clone());
+            let table = parse_address_lookup_table(table_key, &rpc.get_account(&table_key)?)?;
+
+            // Skip addresses already in the table, and repeats
+            let mut new_addresses: Vec<Pubkey> = Vec::new();
+            for address in addresses {
+                if !table.addresses.contains(&address) && !new_addresses.contains(&address) {
+                    new_addresses.push(address);
+                }
+            }
+            if new_addresses.is_empty() {
+                println!("All addresses are already in the table.");
+                return Ok(());
+            }
+            let total = table.addresses.len() + new_addresses.len();
+            if total > LOOKUP_TABLE_MAX_ADDRESSES {
+                anyhow::bail!(
+                    "Table would hold {} addresses, the maximum is {}",
+                    total,
+                    LOOKUP_TABLE_MAX_ADDRESSES
+                );
+            }
+
+            let passphrase = get_verified_passphrase(&db)?;
+            let authority = load_signer(&db, &args.authority, &passphrase)?;
+            let payer = args
+                .payer
+                .as_deref()
+                .map(|payer| load_signer(&db, payer, &passphrase))
+                .transpose()?;
+            let payer = payer.as_ref().unwrap_or(&authority);
+
+            // One transaction per chunk that fits in a packet
+            for chunk in new_addresses.chunks(MAX_EXTEND_ADDRESSES) {
+                let instruction = extend_lookup_table_instruction(
+                    &table_key,
+                    &authority.pubkey(),
+                    Some(&payer.pubkey()),
+                    chunk,
+                );
+                let signature = send(&rpc, &[instruction], payer, &[&authority])?;
+                println!("  Added {} addresses ({})", chunk.len(), signature);
+            }
+
+            println!(
+                "{}",
+                output::success(format!(
+                    "Extended lookup table {} to {} addresses",
+                    table_key, total
+                ))
+            );
+        }
+
+        AltCommands::Deactivate(args) => {
+            let table = parse_pubkey(&args.table)?;
+            let passphrase = get_verified_passphrase(&db)?;
+            let authority = load_signer(&db, &args.authority, &passphrase)?;
+            let rpc = RpcClient::new(args.rpc_url.clone());
+
+            let instruction = deactivate_lookup_table_instruction(&table, &authority.pubkey());
+            let signature = send(&rpc, &[instruction], &authority, &[])?;
+
+            println!(
+                "{}",
+                output::success(format!("Deactivated lookup table {}", table))
+            );
+            println!("  Signature: {}", signature);
+            println!(
+                "\nNote: The table can be closed in about 513 slots (a few minutes) with\n  'solana-keyring alt close {} --authority {}'",
+                table, args.authority
+            );
+        }
+
+        AltCommands::Close(args) => {
+            let table = parse_pubkey(&args.table)?;
+            let passphrase = get_verified_passphrase(&db)?;
+            let authority = load_signer(&db, &args.authority, &passphrase)?;
+            let recipient = match &args.recipient {
+                Some(recipient) => parse_pubkey(recipient)?,
+                None => authority.pubkey(),
+            };
+            let rpc = RpcClient::new(args.rpc_url.clone());
+
+            let instruction =
+                close_lookup_table_instruction(&table, &authority.pubkey(), &recipient);
+            let signature = send(&rpc, &[instruction], &authority, &[]).context(
+                "Failed to close lookup table (it must be deactivated about 513 slots before)",
+            )?;
+
+            println!(
+                "{}",
+                output::success(format!("Closed lookup table {}", table))
+            );
+            println!("  Rent sent to: {}", recipient);
+            println!("  Signature: {}", signature);
+        }
+    }
+
+    Ok(())
+}
+
+/// Load a keyring-held keypair by public key or label
+fn load_signer(db: &Database, identifier: &str, passphrase: &str) -> Result<Keypair> {
+    Ok(db
+        .load_keypair(identifier, passphrase.as_bytes())?
+        .to_solana_keypair())
+}
+
+fn parse_pubkey(address: &str) -> Result<Pubkey> {
+    address
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid address: {}", address))
+}
+
+/// Sign with the payer and any other required signers, then send and confirm
+fn send(
+    rpc: &RpcClient,
+    instructions: &[Instruction],
+    payer: &Keypair,
+    others: &[&Keypair],
+) -> Result<Signature> {
+    let message = Message::new(instructions, Some(&payer.pubkey()));
+    check_transaction_size(&VersionedMessage::Legacy(message.clone()))?;
+
+    // The authority may also be the payer; sign once per key
+    let mut signers: Vec<&Keypair> = vec![payer];
+    for signer in others {
+        if !signers.iter().any(|s| s.pubkey() == signer.pubkey()) {
+            signers.push(signer);
+        }
+    }
+
+    let blockhash = rpc.get_latest_blockhash()?;
+    let mut tx = Transaction::new_unsigned(message);
+    tx.try_sign(&signers, blockhash)?;
+
+    let signature = with_spinner("Sending transaction...", || {
+        rpc.send_and_confirm_transaction(&tx)
+    })?;
+    Ok(signature)
+}
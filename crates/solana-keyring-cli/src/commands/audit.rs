@@ -0,0 +1,92 @@
+//! Audit log commands
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+use anyhow::Result;
+use solana_keyring::audit::{AuditLog, AuditStatus, write_csv, write_jsonl};
+use solana_keyring::output;
+use solana_keyring::spending::{parse_window, unix_now};
+
+use super::{get_verified_passphrase, open_db};
+use crate::cli::{AuditCommands, AuditExportFormat};
+
+pub fn run(cmd: AuditCommands, db_path: &Option<PathBuf>) -> Result<()> {
+    let db = open_db(db_path)?;
+
+    match cmd {
+        AuditCommands::Export(args) => {
+            let since = args
+                .since
+                .as_deref()
+                .map(parse_window)
+                .transpose()?
+                .map(|window| unix_now().saturating_sub(window as i64));
+            let passphrase = get_verified_passphrase(&db)?;
+            let events = AuditLog::new(&db).export(passphrase.as_bytes(), since)?;
+
+            let mut out: Box<dyn Write> = match &args.output {
+                Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+                None => Box::new(std::io::stdout().lock()),
+            };
+            match args.format {
+                AuditExportFormat::Jsonl => write_jsonl(&events, &mut out)?,
+                AuditExportFormat::Csv => write_csv(&events, &mut out)?,
+            }
+            out.flush()?;
+
+            let invalid = events
+                .iter()
+                .filter(|e| e.status == AuditStatus::Invalid)
+                .count();
+            let unsealed = events
+                .iter()
+                .filter(|e| e.status == AuditStatus::Unsealed)
+                .count();
+            let unchained = events
+                .iter()
+                .filter(|e| e.status == AuditStatus::Unchained)
+                .count();
+
+            // Keep stdout clean for the export itself
+            eprintln!("Exported {} audit event(s)", events.len());
+            if unchained > 0 {
+                eprintln!(
+                    "{}",
+                    output::warning(format!(
+                        "{} event(s) predate chaining and can't be verified",
+                        unchained
+                    ))
+                );
+            }
+            if unsealed > 0 {
+                eprintln!(
+                    "{}",
+                    output::warning(format!(
+                        "{} event(s) were recorded after the last seal; run 'solana-keyring audit seal' to cover them",
+                        unsealed
+                    ))
+                );
+            }
+            if invalid > 0 {
+                anyhow::bail!(
+                    "{} audit event(s) failed verification; the log may have been tampered with",
+                    invalid
+                );
+            }
+        }
+        AuditCommands::Seal => {
+            let passphrase = get_verified_passphrase(&db)?;
+            match AuditLog::new(&db).seal(passphrase.as_bytes())? {
+                Some(event_id) => println!(
+                    "{}",
+                    output::success(format!("Sealed the audit log through event {}", event_id))
+                ),
+                None => println!("The audit log is empty; nothing to seal"),
+            }
+        }
+    }
+
+    Ok(())
+}
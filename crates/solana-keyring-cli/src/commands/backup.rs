@@ -0,0 +1,87 @@
+//! Backup commands
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use solana_keyring::Database;
+use solana_keyring::backup::{KeyStatus, record_backup, verify_backup};
+use solana_keyring::output;
+use solana_keyring::spending::unix_now;
+
+use super::{get_db_path, open_db, prompt_passphrase};
+use crate::cli::BackupCommands;
+
+pub fn run(cmd: BackupCommands, db_path: &Option<PathBuf>) -> Result<()> {
+    match cmd {
+        BackupCommands::Verify(args) => {
+            if !args.file.exists() {
+                anyhow::bail!("Backup not found: {}", args.file.display());
+            }
+            let backup = Database::open_read_only(&args.file)?;
+            if !backup.is_initialized()? {
+                anyhow::bail!("{} is not an initialized keyring", args.file.display());
+            }
+
+            // Compare against the live keyring if there is one
+            let live = if get_db_path(db_path).exists() {
+                Some(open_db(db_path)?)
+            } else {
+                println!("No live keyring found; verifying the backup on its own.");
+                None
+            };
+
+            let passphrase = prompt_passphrase("Enter backup master passphrase: ")?;
+            let report = verify_backup(&backup, passphrase.as_bytes(), live.as_ref())?;
+
+            println!("{:<44} {:<20} STATUS", "PUBKEY", "LABEL");
+            println!("{}", "-".repeat(80));
+            for key in &report.keys {
+                let status = match &key.status {
+                    KeyStatus::Ok => "ok".to_string(),
+                    KeyStatus::DecryptionFailed(e) => format!("decryption failed: {}", e),
+                    KeyStatus::PubkeyMismatch { derived } => {
+                        format!("secret derives {}", derived)
+                    }
+                };
+                println!("{:<44} {:<20} {}", key.pubkey, key.label, status);
+            }
+
+            let failures = report.failures().count();
+            println!();
+            println!(
+                "{} of {} keys verified",
+                report.keys.len() - failures,
+                report.keys.len()
+            );
+
+            if live.is_some() {
+                if !report.unprotected.is_empty() {
+                    println!();
+                    println!("Keys that would be lost if the live keyring disappeared:");
+                    for key in &report.unprotected {
+                        println!("  {} ({})", key.pubkey, key.label);
+                    }
+                }
+                if !report.only_in_backup.is_empty() {
+                    println!();
+                    println!(
+                        "{} key(s) in the backup are no longer in the live keyring.",
+                        report.only_in_backup.len()
+                    );
+                }
+            }
+
+            if !report.is_complete() {
+                anyhow::bail!("Backup verification failed");
+            }
+            println!("{}", output::success("Backup is complete."));
+
+            // Keys created from now on are flagged until the next backup
+            if let Some(live) = &live {
+                record_backup(live, unix_now())?;
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,228 @@
+//! Guided key ceremony
+
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::Instant;
+
+use anyhow::Result;
+use solana_keyring::ceremony::{
+    CeremonyReport, EntropyPool, ShareScheme, parse_dice, quiz_positions,
+};
+use solana_keyring::crypto::shamir;
+use solana_keyring::keypair::{keypair_from_mnemonic, mnemonic_from_entropy};
+use solana_keyring::offline::WATCH_ONLY_TAG;
+use solana_keyring::output;
+use solana_keyring::spending::unix_now;
+use solana_keyring::{AddressBook, Database};
+
+use super::{get_verified_passphrase, open_db, prompt_passphrase};
+use crate::cli::CeremonyArgs;
+
+/// Minimum characters of random typing
+const MIN_KEYBOARD_CHARS: usize = 32;
+
+/// Minimum dice rolls (~2.58 bits each, 100 rolls is ~256 bits)
+const MIN_DICE_ROLLS: usize = 100;
+
+/// Words asked in the backup quiz
+const QUIZ_WORDS: usize = 3;
+
+/// Attempts allowed for the backup quiz
+const QUIZ_ATTEMPTS: usize = 3;
+
+pub fn run(args: CeremonyArgs, db_path: &Option<PathBuf>) -> Result<()> {
+    if args.threshold < 2 || args.threshold > args.shares {
+        anyhow::bail!("--threshold must be between 2 and --shares");
+    }
+
+    let db = open_db(db_path)?;
+    let passphrase = get_verified_passphrase(&db)?;
+
+    if db
+        .list_keypairs(None)?
+        .iter()
+        .any(|k| k.label == args.label)
+    {
+        anyhow::bail!("A keypair labeled '{}' already exists", args.label);
+    }
+
+    // Open watch-only databases up front so a bad path fails before key creation
+    let watch_dbs = args
+        .watch_db
+        .iter()
+        .map(|path| {
+            let watch_db = Database::open(path)?;
+            if !watch_db.is_initialized()? {
+                anyhow::bail!("Keyring at {} is not initialized", path.display());
+            }
+            Ok((path, watch_db))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    println!("Key ceremony for '{}'", args.label);
+    println!("Make sure nobody can see this screen and no one is recording it.");
+
+    // Step 1: entropy
+    step(1, "Gather entropy");
+    let mut pool = EntropyPool::new();
+
+    let started = Instant::now();
+    let typed = prompt_passphrase(&format!(
+        "Type at least {} random characters (hidden), then press Enter: ",
+        MIN_KEYBOARD_CHARS
+    ))?;
+    if typed.chars().count() < MIN_KEYBOARD_CHARS {
+        anyhow::bail!("At least {} characters are required", MIN_KEYBOARD_CHARS);
+    }
+    pool.add("keyboard", typed.as_bytes());
+    pool.add(
+        "keyboard-timing",
+        &started.elapsed().as_nanos().to_le_bytes(),
+    );
+
+    if args.dice {
+        let rolls = parse_dice(&read_line(&format!(
+            "Roll a die at least {} times and enter the results (1-6): ",
+            MIN_DICE_ROLLS
+        ))?)?;
+        if rolls.len() < MIN_DICE_ROLLS {
+            anyhow::bail!("At least {} dice rolls are required", MIN_DICE_ROLLS);
+        }
+        pool.add("dice", &rolls);
+    }
+
+    let sources = pool.sources().to_vec();
+    let entropy = pool.finish();
+    println!("Mixed entropy from: {}", sources.join(", "));
+
+    // Step 2: seed phrase backup
+    step(2, "Write down the seed phrase");
+    let phrase = mnemonic_from_entropy(&*entropy)?;
+    let words: Vec<&str> = phrase.split_whitespace().collect();
+    for (i, word) in words.iter().enumerate() {
+        println!("  {:>2}. {}", i + 1, word);
+    }
+    println!();
+    read_line("Write these words down on paper, then press Enter to continue: ")?;
+    clear_screen();
+
+    // Step 3: backup verification quiz
+    step(3, "Verify the seed phrase backup");
+    verify_backup(&words)?;
+    println!("{}", output::success("Backup verified."));
+
+    // Step 4: Shamir shares
+    step(4, "Print Shamir shares");
+    let shares = shamir::split(&*entropy, args.threshold, args.shares)?;
+    println!(
+        "The seed phrase is split into {} shares; any {} recover it.",
+        args.shares, args.threshold
+    );
+    println!("Give each share to a different custodian.");
+    for share in &shares {
+        println!();
+        read_line(&format!(
+            "Press Enter to show share {} of {}: ",
+            share.index, args.shares
+        ))?;
+        println!("  Share {}: {}", share.index, share);
+        read_line("Copy the share, then press Enter to hide it: ")?;
+        clear_screen();
+    }
+
+    // Create and store the key
+    let keypair = keypair_from_mnemonic(&phrase, "")?;
+    let pubkey = keypair.pubkey_base58();
+    let tags: Vec<&str> = args.tag.iter().map(|s| s.as_str()).collect();
+    db.store_keypair(&keypair, &args.label, passphrase.as_bytes(), &tags)?;
+
+    // Step 5: watch-only registration
+    step(5, "Register watch-only copies");
+    let mut watch_only = Vec::new();
+    for (path, watch_db) in &watch_dbs {
+        let address_book = AddressBook::new(watch_db);
+        address_book.add(
+            &pubkey,
+            &args.label,
+            Some("Watch-only: created in a key ceremony"),
+        )?;
+        address_book.add_tag(&pubkey, WATCH_ONLY_TAG)?;
+        println!("  Registered in {}", path.display());
+        watch_only.push(path.display().to_string());
+    }
+    if watch_only.is_empty() {
+        println!("  No other keyrings given (use --watch-db), skipping.");
+    }
+
+    // Step 6: signed report
+    step(6, "Record the ceremony report");
+    let report = CeremonyReport {
+        pubkey: pubkey.clone(),
+        label: args.label.clone(),
+        created_at: unix_now(),
+        entropy_sources: sources,
+        mnemonic_verified: true,
+        shamir: Some(ShareScheme {
+            threshold: args.threshold,
+            shares: args.shares,
+        }),
+        watch_only,
+    }
+    .sign(&keypair)?;
+    let event_id = report.store(&db)?;
+
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    println!();
+    println!("{}", output::success("Ceremony complete."));
+    println!("  Public key: {}", pubkey);
+    println!("  Label: {}", args.label);
+    println!("  Audit log entry: #{}", event_id);
+
+    Ok(())
+}
+
+fn step(number: usize, title: &str) {
+    println!();
+    println!(
+        "{}",
+        output::heading(format!("== Step {}: {} ==", number, title))
+    );
+}
+
+fn read_line(prompt: &str) -> Result<String> {
+    print!("{}", prompt);
+    io::stdout().flush()?;
+    let mut line = String::new();
+    io::stdin().read_line(&mut line)?;
+    Ok(line.trim().to_string())
+}
+
+fn clear_screen() {
+    print!("\x1B[2J\x1B[H");
+    let _ = io::stdout().flush();
+}
+
+/// Quiz the user on random words until they pass or run out of attempts
+fn verify_backup(words: &[&str]) -> Result<()> {
+    for attempt in 1..=QUIZ_ATTEMPTS {
+        let mut passed = true;
+        for position in quiz_positions(words.len(), QUIZ_WORDS) {
+            let answer = read_line(&format!("Word #{}: ", position + 1))?;
+            if !answer.eq_ignore_ascii_case(words[position]) {
+                passed = false;
+            }
+        }
+
+        if passed {
+            return Ok(());
+        }
+        if attempt < QUIZ_ATTEMPTS {
+            eprintln!(
+                "{}",
+                output::warning("Incorrect. Check your backup and try again.")
+            );
+        }
+    }
+
+    anyhow::bail!("Seed phrase backup could not be verified; no key was created")
+}
@@ -0,0 +1,81 @@
+//! Cluster binding commands
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use solana_keyring::cluster::{BindingMode, Cluster, ClusterBindings};
+use solana_keyring::output;
+use solana_keyring::{Database, list_signers};
+
+use super::open_db;
+use crate::cli::ClusterCommands;
+
+pub fn run(cmd: ClusterCommands, db_path: &Option<PathBuf>) -> Result<()> {
+    let db = open_db(db_path)?;
+    let bindings = ClusterBindings::new(&db);
+
+    match cmd {
+        ClusterCommands::Bind(args) => {
+            let pubkey = resolve_signer(&db, &args.signer)?;
+            let cluster: Cluster = args.cluster.parse()?;
+            let mode = if args.warn {
+                BindingMode::Warn
+            } else {
+                BindingMode::Refuse
+            };
+
+            let binding = bindings.bind(&pubkey, &cluster, args.url.as_deref(), mode)?;
+
+            println!(
+                "{}",
+                output::success(format!("Bound {} to {}", args.signer, binding.cluster))
+            );
+            println!("  RPC: {}", binding.rpc_url);
+            println!("  On mismatch: {}", binding.mode);
+        }
+
+        ClusterCommands::List => {
+            let bindings = bindings.list()?;
+
+            if bindings.is_empty() {
+                println!("No cluster bindings configured.");
+                return Ok(());
+            }
+
+            println!("{:<44} {:<52} {:<6} RPC", "SIGNER", "CLUSTER", "MODE");
+            println!("{}", "-".repeat(130));
+
+            for binding in bindings {
+                println!(
+                    "{:<44} {:<52} {:<6} {}",
+                    binding.pubkey,
+                    binding.cluster.to_string(),
+                    binding.mode.as_str(),
+                    binding.rpc_url,
+                );
+            }
+        }
+
+        ClusterCommands::Unbind(args) => {
+            let pubkey = resolve_signer(&db, &args.signer)?;
+            if bindings.unbind(&pubkey)? {
+                println!(
+                    "{}",
+                    output::success(format!("Removed cluster binding for {}", args.signer))
+                );
+            } else {
+                anyhow::bail!("No cluster binding for {}", args.signer);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn resolve_signer(db: &Database, identifier: &str) -> Result<String> {
+    list_signers(db, None)?
+        .into_iter()
+        .find(|s| s.pubkey == identifier || s.label == identifier)
+        .map(|s| s.pubkey)
+        .ok_or_else(|| anyhow::anyhow!("Signer not found: {}", identifier))
+}
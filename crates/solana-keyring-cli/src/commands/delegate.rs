@@ -0,0 +1,101 @@
+//! Delegated signing commands
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use solana_keyring::delegation::Delegations;
+use solana_keyring::list_signers;
+use solana_keyring::output;
+use solana_keyring::transaction::template_hash;
+
+use super::open_db;
+use crate::cli::DelegateCommands;
+
+pub fn run(cmd: DelegateCommands, db_path: &Option<PathBuf>) -> Result<()> {
+    let db = open_db(db_path)?;
+    let delegations = Delegations::new(&db);
+
+    match cmd {
+        DelegateCommands::Grant(args) => {
+            let signers = list_signers(&db, None)?;
+            let signer = signers
+                .iter()
+                .find(|s| s.pubkey == args.signer || s.label == args.signer)
+                .ok_or_else(|| anyhow::anyhow!("Signer not found: {}", args.signer))?;
+
+            let hash: [u8; 32] = match (&args.template, &args.template_hash) {
+                (Some(path), _) => template_hash(&std::fs::read(path)?)?,
+                (None, Some(hex_hash)) => hex::decode(hex_hash)?
+                    .try_into()
+                    .map_err(|_| anyhow::anyhow!("Template hash must be 32 bytes"))?,
+                (None, None) => anyhow::bail!("Specify --template or --template-hash"),
+            };
+
+            let id = delegations.grant(
+                &args.bot,
+                &signer.pubkey,
+                &hash,
+                args.first_nonce,
+                args.last_nonce,
+            )?;
+
+            println!("{}", output::success(format!("Granted delegation {}:", id)));
+            println!("  Bot: {}", args.bot);
+            println!("  Signer: {} ({})", signer.label, signer.pubkey);
+            println!("  Template: {}", hex::encode(hash));
+            println!("  Nonces: {} to {}", args.first_nonce, args.last_nonce);
+        }
+
+        DelegateCommands::List(args) => {
+            let rows = delegations.list(!args.all)?;
+
+            if rows.is_empty() {
+                println!("No delegations configured.");
+                return Ok(());
+            }
+
+            println!(
+                "{:<4} {:<44} {:<44} {:<16} {:>21} {:>10}",
+                "ID", "BOT", "SIGNER", "TEMPLATE", "NONCES", "LAST USED"
+            );
+            println!("{}", "-".repeat(144));
+
+            for row in rows {
+                let last = match (row.revoked_at, row.last_nonce) {
+                    (Some(_), _) => "revoked".to_string(),
+                    (None, Some(nonce)) => nonce.to_string(),
+                    (None, None) => "-".to_string(),
+                };
+                println!(
+                    "{:<4} {:<44} {:<44} {:<16} {:>21} {:>10}",
+                    row.id,
+                    row.bot_pubkey,
+                    row.signer_pubkey,
+                    &row.template_hash[..row.template_hash.len().min(16)],
+                    format!("{}-{}", row.nonce_start, row.nonce_end),
+                    last,
+                );
+            }
+        }
+
+        DelegateCommands::Revoke(args) => match (args.id, &args.bot) {
+            (Some(id), _) => {
+                if delegations.revoke(id)? {
+                    println!("{}", output::success(format!("Revoked delegation {}", id)));
+                } else {
+                    anyhow::bail!("Active delegation not found: {}", id);
+                }
+            }
+            (None, Some(bot)) => {
+                let count = delegations.revoke_bot(bot)?;
+                println!(
+                    "{}",
+                    output::success(format!("Revoked {} delegation(s) to {}", count, bot))
+                );
+            }
+            (None, None) => anyhow::bail!("Specify a delegation ID or --bot"),
+        },
+    }
+
+    Ok(())
+}
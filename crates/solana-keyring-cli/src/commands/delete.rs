@@ -4,6 +4,9 @@ use std::io::{self, Write};
 use std::path::PathBuf;
 
 use anyhow::Result;
+use solana_keyring::i18n::{t, t_with};
+use solana_keyring::keypair::fingerprint;
+use solana_keyring::output;
 
 use super::open_db;
 use crate::cli::DeleteArgs;
@@ -11,19 +14,24 @@ use crate::cli::DeleteArgs;
 pub fn run(args: DeleteArgs, db_path: &Option<PathBuf>) -> Result<()> {
     let db = open_db(db_path)?;
 
-    // Confirm deletion
+    // Confirm deletion, showing the fingerprint so the right key is removed
     if !args.force {
-        print!(
-            "Are you sure you want to delete '{}'? This cannot be undone. [y/N] ",
-            args.identifier
-        );
+        let target = db
+            .list_keypairs(None)?
+            .into_iter()
+            .find(|k| k.pubkey == args.identifier || k.label == args.identifier)
+            .map_or_else(
+                || args.identifier.clone(),
+                |k| format!("{} [{}]", k.label, fingerprint(&k.pubkey)),
+            );
+        print!("{} ", t_with("confirm-delete", &[("identifier", &target)]));
         io::stdout().flush()?;
 
         let mut input = String::new();
         io::stdin().read_line(&mut input)?;
 
         if !input.trim().eq_ignore_ascii_case("y") {
-            println!("Cancelled.");
+            println!("{}", t("confirm-cancelled"));
             return Ok(());
         }
     }
@@ -31,7 +39,10 @@ pub fn run(args: DeleteArgs, db_path: &Option<PathBuf>) -> Result<()> {
     let deleted = db.delete_keypair(&args.identifier)?;
 
     if deleted {
-        println!("Deleted keypair '{}'", args.identifier);
+        println!(
+            "{}",
+            output::success(format!("Deleted keypair '{}'", args.identifier))
+        );
     } else {
         anyhow::bail!("Keypair not found: {}", args.identifier);
     }
@@ -2,10 +2,22 @@
 
 use std::path::PathBuf;
 
-use anyhow::Result;
-use solana_keyring::keypair::generate_keypair;
+use std::time::Duration;
 
-use super::{AgentConfig, agent_client, get_verified_passphrase, open_db};
+use anyhow::{Result, anyhow};
+use solana_actor_keypair::{GrindPattern, Grinder};
+use solana_keyring::SecureKeypair;
+use solana_keyring::crypto::ct_eq_str;
+use solana_keyring::keypair::{
+    fingerprint, generate_keypair, generate_mnemonic, keypair_from_mnemonic,
+};
+use solana_keyring::offline::{KEY_FILE, MANIFEST_FILE, PublicManifest, write_bundle};
+use solana_keyring::output;
+use solana_keyring::progress::Spinner;
+use solana_keyring::spending::unix_now;
+use zeroize::Zeroizing;
+
+use super::{AgentConfig, agent_client, get_verified_passphrase, open_db, prompt_passphrase};
 use crate::cli::GenerateArgs;
 
 pub fn run(
@@ -13,8 +25,10 @@ pub fn run(
     db_path: &Option<PathBuf>,
     agent_config: &AgentConfig,
 ) -> Result<()> {
-    // Try using agent first if requested
-    if agent_config.use_agent {
+    // Try using agent first if requested (seed phrases, vanity keys and
+    // offline bundles are generated locally)
+    let grinding = args.starts_with.is_some() || args.ends_with.is_some();
+    if agent_config.use_agent && !args.seed_phrase && args.offline_bundle.is_none() && !grinding {
         let socket_path = agent_config.socket_path();
 
         // Use tokio runtime to run async code
@@ -31,8 +45,9 @@ pub fn run(
                     &args.tag,
                 ))?;
 
-                println!("Generated keypair:");
+                println!("{}", output::success("Generated keypair:"));
                 println!("  Public key: {}", result.pubkey);
+                println!("  Fingerprint: {}", fingerprint(&result.pubkey));
                 println!("  Label: {}", result.label);
                 if !args.tag.is_empty() {
                     println!("  Tags: {}", args.tag.join(", "));
@@ -40,12 +55,22 @@ pub fn run(
                 return Ok(());
             }
             agent_client::AgentAvailability::Locked => {
-                println!("Agent is running but locked. Run 'solana-keyring-agent unlock' first.");
-                println!("Falling back to passphrase prompt...");
+                eprintln!(
+                    "{}",
+                    output::warning(
+                        "Agent is running but locked. Run 'solana-keyring-agent unlock' first."
+                    )
+                );
+                eprintln!("Falling back to passphrase prompt...");
             }
             agent_client::AgentAvailability::NotRunning => {
-                println!("Agent is not running. Run 'solana-keyring-agent start' to start it.");
-                println!("Falling back to passphrase prompt...");
+                eprintln!(
+                    "{}",
+                    output::warning(
+                        "Agent is not running. Run 'solana-keyring-agent start' to start it."
+                    )
+                );
+                eprintln!("Falling back to passphrase prompt...");
             }
         }
     }
@@ -55,21 +80,115 @@ pub fn run(
     let passphrase = get_verified_passphrase(&db)?;
 
     // Generate keypair
-    let keypair = generate_keypair();
+    let keypair = if args.seed_phrase {
+        generate_from_seed_phrase(&args)?
+    } else if grinding {
+        grind_keypair(&args)?
+    } else {
+        generate_keypair()
+    };
     let pubkey = keypair.pubkey_base58();
 
     // Convert tags to &str slice
     let tags: Vec<&str> = args.tag.iter().map(|s| s.as_str()).collect();
 
+    // Write the bundle first so an existing one fails before anything is stored
+    if let Some(dir) = &args.offline_bundle {
+        let manifest = PublicManifest::new(&keypair, &args.label, &tags, unix_now());
+        write_bundle(dir, &keypair, manifest, passphrase.as_bytes())?;
+    }
+
     // Store in database
     db.store_keypair(&keypair, &args.label, passphrase.as_bytes(), &tags)?;
 
-    println!("Generated keypair:");
+    println!("{}", output::success("Generated keypair:"));
     println!("  Public key: {}", pubkey);
+    println!("  Fingerprint: {}", fingerprint(&pubkey));
     println!("  Label: {}", args.label);
     if !args.tag.is_empty() {
         println!("  Tags: {}", args.tag.join(", "));
     }
 
+    if let Some(dir) = &args.offline_bundle {
+        println!();
+        println!("{}", output::success("Wrote offline bundle:"));
+        println!(
+            "  {} (encrypted key, keep on this machine)",
+            dir.join(KEY_FILE).display()
+        );
+        println!(
+            "  {} (public, copy to online machines)",
+            dir.join(MANIFEST_FILE).display()
+        );
+        println!("Register it online with:");
+        println!(
+            "  solana-keyring import --label {} --public-manifest {}",
+            args.label, MANIFEST_FILE
+        );
+    }
+
     Ok(())
 }
+
+/// Generate a seed phrase and derive a keypair from it, as `solana-keygen new`
+/// Search for a keypair matching --starts-with and --ends-with
+fn grind_keypair(args: &GenerateArgs) -> Result<SecureKeypair> {
+    let mut pattern = GrindPattern::new(
+        args.starts_with.as_deref().unwrap_or(""),
+        args.ends_with.as_deref().unwrap_or(""),
+    )?;
+    if args.ignore_case {
+        pattern = pattern.case_insensitive();
+    }
+
+    let expected = pattern.expected_attempts();
+    let spinner = Spinner::start(format!(
+        "Searching for a matching key (about {:.0} attempts expected)...",
+        expected
+    ));
+    let signer = Grinder::new(pattern)
+        .with_progress_interval(Duration::from_secs(5))
+        .run(|progress| {
+            let rate = progress.rate();
+            // Each attempt is independent, so the expected wait never shrinks
+            spinner.set_message(format!(
+                "Searching for a matching key: {} tried at {:.0}/s, a match takes about {:.0}s on average",
+                progress.attempts,
+                rate,
+                expected / rate.max(1.0)
+            ));
+        })
+        .ok_or_else(|| anyhow!("Search was cancelled"))?;
+    spinner.finish("Found a matching key");
+
+    Ok(SecureKeypair::from_bytes(&signer.secret_bytes())?)
+}
+
+fn generate_from_seed_phrase(args: &GenerateArgs) -> Result<SecureKeypair> {
+    let word_count: usize = args.word_count.parse()?;
+    let phrase = generate_mnemonic(word_count)?;
+
+    let bip39_passphrase = if args.no_bip39_passphrase {
+        Zeroizing::new(String::new())
+    } else {
+        println!("For added security, enter a BIP39 passphrase");
+        println!("NOTE! This passphrase protects the recovery seed phrase only. The keypair");
+        println!("itself is stored encrypted under your master passphrase");
+        println!();
+        let passphrase = Zeroizing::new(prompt_passphrase("BIP39 Passphrase (empty for none): ")?);
+        let confirm = Zeroizing::new(prompt_passphrase("Enter same passphrase again: ")?);
+        if !ct_eq_str(&passphrase, &confirm) {
+            anyhow::bail!("Passphrases did not match");
+        }
+        passphrase
+    };
+
+    let keypair = keypair_from_mnemonic(&phrase, &bip39_passphrase)?;
+
+    println!();
+    println!("Save this seed phrase and your BIP39 passphrase to recover your new keypair:");
+    println!("{}", &*phrase);
+    println!();
+
+    Ok(keypair)
+}
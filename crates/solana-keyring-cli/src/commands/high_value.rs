@@ -0,0 +1,84 @@
+//! High-value key commands
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use solana_keyring::high_value::HighValueKeys;
+use solana_keyring::output;
+use solana_keyring::spending::{format_window, parse_window, unix_now};
+use solana_keyring::{Database, list_signers};
+
+use super::{get_verified_passphrase, open_db};
+use crate::cli::HighValueCommands;
+
+pub fn run(cmd: HighValueCommands, db_path: &Option<PathBuf>) -> Result<()> {
+    let db = open_db(db_path)?;
+    let keys = HighValueKeys::new(&db);
+
+    match cmd {
+        HighValueCommands::Set(args) => {
+            let pubkey = resolve_signer(&db, &args.signer)?;
+            let cooldown = parse_window(&args.cooldown)?;
+
+            keys.designate(&pubkey, cooldown)?;
+
+            println!(
+                "{}",
+                output::success(format!("{} is now a high-value key", args.signer))
+            );
+            println!("  Cool-down: {}", format_window(cooldown));
+            println!("  Each signature needs biometric approval and the amount typed in");
+        }
+
+        HighValueCommands::List => {
+            let keys = keys.list()?;
+
+            if keys.is_empty() {
+                println!("No high-value keys configured.");
+                return Ok(());
+            }
+
+            let now = unix_now();
+            println!("{:<44} {:<10} NEXT SIGNATURE", "SIGNER", "COOL-DOWN");
+            println!("{}", "-".repeat(72));
+
+            for key in keys {
+                let next = match key.remaining(now) {
+                    0 => "now".to_string(),
+                    remaining => format!("in {}s", remaining),
+                };
+                println!(
+                    "{:<44} {:<10} {}",
+                    key.pubkey,
+                    format_window(key.cooldown_seconds),
+                    next
+                );
+            }
+        }
+
+        HighValueCommands::Remove(args) => {
+            let pubkey = resolve_signer(&db, &args.signer)?;
+            // Lifting the protection needs the same proof as using the key
+            get_verified_passphrase(&db)?;
+
+            if keys.remove(&pubkey)? {
+                println!(
+                    "{}",
+                    output::success(format!("{} is no longer a high-value key", args.signer))
+                );
+            } else {
+                anyhow::bail!("{} is not a high-value key", args.signer);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn resolve_signer(db: &Database, identifier: &str) -> Result<String> {
+    list_signers(db, None)?
+        .into_iter()
+        .find(|s| s.pubkey == identifier || s.label == identifier)
+        .map(|s| s.pubkey)
+        .ok_or_else(|| anyhow::anyhow!("Signer not found: {}", identifier))
+}
@@ -1,9 +1,19 @@
 //! Import a keypair
 
-use std::path::PathBuf;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 
-use anyhow::Result;
-use solana_keyring::keypair::{import_base58, import_json};
+use anyhow::{Result, anyhow};
+use solana_actor_keypair::{DerivationPath, HdSeed};
+use solana_keyring::AddressBook;
+use solana_keyring::keypair::{
+    SecureKeypair, fingerprint, import_base58, import_json, keypair_from_mnemonic,
+    keypair_from_seed_phrase_unchecked, seed_from_mnemonic, seed_from_seed_phrase_unchecked,
+};
+use solana_keyring::offline::{WATCH_ONLY_TAG, read_manifest};
+use solana_keyring::output;
+use solana_keyring::solana_config::SolanaCliConfig;
+use zeroize::Zeroizing;
 
 use super::{AgentConfig, agent_client, get_verified_passphrase, open_db, prompt_passphrase};
 use crate::cli::ImportArgs;
@@ -26,8 +36,9 @@ pub fn run(args: ImportArgs, db_path: &Option<PathBuf>, agent_config: &AgentConf
                     &args.tag,
                 ))?;
 
-                println!("Imported keypair:");
+                println!("{}", output::success("Imported keypair:"));
                 println!("  Public key: {}", result.pubkey);
+                println!("  Fingerprint: {}", fingerprint(&result.pubkey));
                 println!("  Label: {}", result.label);
                 if !args.tag.is_empty() {
                     println!("  Tags: {}", args.tag.join(", "));
@@ -35,16 +46,31 @@ pub fn run(args: ImportArgs, db_path: &Option<PathBuf>, agent_config: &AgentConf
                 return Ok(());
             }
             agent_client::AgentAvailability::Locked => {
-                println!("Agent is running but locked. Run 'solana-keyring-agent unlock' first.");
-                println!("Falling back to passphrase prompt...");
+                eprintln!(
+                    "{}",
+                    output::warning(
+                        "Agent is running but locked. Run 'solana-keyring-agent unlock' first."
+                    )
+                );
+                eprintln!("Falling back to passphrase prompt...");
             }
             agent_client::AgentAvailability::NotRunning => {
-                println!("Agent is not running. Run 'solana-keyring-agent start' to start it.");
-                println!("Falling back to passphrase prompt...");
+                eprintln!(
+                    "{}",
+                    output::warning(
+                        "Agent is not running. Run 'solana-keyring-agent start' to start it."
+                    )
+                );
+                eprintln!("Falling back to passphrase prompt...");
             }
         }
     }
 
+    // Watch-only registration needs no secret, so no passphrase either
+    if let Some(path) = &args.public_manifest {
+        return import_public_manifest(&args, path, db_path);
+    }
+
     let db = open_db(db_path)?;
     let passphrase = get_verified_passphrase(&db)?;
 
@@ -53,6 +79,23 @@ pub fn run(args: ImportArgs, db_path: &Option<PathBuf>, agent_config: &AgentConf
         import_json(file_path)?
     } else if let Some(base58) = &args.base58 {
         import_base58(base58)?
+    } else if let Some(config_path) = &args.from_solana_config {
+        let config = SolanaCliConfig::load_or_default(config_path.as_deref())?;
+        println!("Importing default keypair {}", config.keypair_path);
+        config.keypair()?
+    } else if args.seed_phrase {
+        // Same prompts and derivation as `solana-keygen recover`
+        let phrase = Zeroizing::new(prompt_passphrase("Seed phrase: ")?);
+        let bip39_passphrase = Zeroizing::new(prompt_passphrase(
+            "If this seed phrase has an associated passphrase, enter it now. Otherwise, press ENTER to continue: ",
+        )?);
+        if args.derivation_path.is_some() || args.pick_account {
+            derive_hd_account(&args, &phrase, &bip39_passphrase)?
+        } else if args.skip_seed_phrase_validation {
+            keypair_from_seed_phrase_unchecked(&phrase, &bip39_passphrase)?
+        } else {
+            keypair_from_mnemonic(&phrase, &bip39_passphrase)?
+        }
     } else {
         // Read from stdin
         let input = prompt_passphrase("Enter base58 encoded secret key: ")?;
@@ -67,8 +110,9 @@ pub fn run(args: ImportArgs, db_path: &Option<PathBuf>, agent_config: &AgentConf
     // Store in database
     db.store_keypair(&keypair, &args.label, passphrase.as_bytes(), &tags)?;
 
-    println!("Imported keypair:");
+    println!("{}", output::success("Imported keypair:"));
     println!("  Public key: {}", pubkey);
+    println!("  Fingerprint: {}", fingerprint(&pubkey));
     println!("  Label: {}", args.label);
     if !args.tag.is_empty() {
         println!("  Tags: {}", args.tag.join(", "));
@@ -76,3 +120,75 @@ pub fn run(args: ImportArgs, db_path: &Option<PathBuf>, agent_config: &AgentConf
 
     Ok(())
 }
+
+/// Number of derived accounts `--pick-account` lists
+const PICK_ACCOUNTS: u32 = 10;
+
+/// Derive an HD account from a seed phrase, by path or by picking from a list
+///
+/// Only public keys are shown while picking; the chosen account's secret is
+/// derived once.
+fn derive_hd_account(
+    args: &ImportArgs,
+    phrase: &str,
+    bip39_passphrase: &str,
+) -> Result<SecureKeypair> {
+    let seed = if args.skip_seed_phrase_validation {
+        HdSeed::from_bytes(&seed_from_seed_phrase_unchecked(phrase, bip39_passphrase))?
+    } else {
+        HdSeed::from_bytes(&*seed_from_mnemonic(phrase, bip39_passphrase)?)?
+    };
+
+    let path = match &args.derivation_path {
+        Some(path) => path.parse::<DerivationPath>()?,
+        None => {
+            let accounts = seed.accounts(0..PICK_ACCOUNTS)?;
+            println!("{}", output::heading("Derived accounts:"));
+            for account in &accounts {
+                println!("  [{}] {}  {}", account.index, account.pubkey, account.path);
+            }
+
+            print!(
+                "{}",
+                output::prompt(format!("Account to import [0-{}]: ", PICK_ACCOUNTS - 1))
+            );
+            io::stdout().flush()?;
+            let mut input = String::new();
+            io::stdin().read_line(&mut input)?;
+            let choice = input.trim();
+            let account = choice
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| accounts.get(index))
+                .ok_or_else(|| anyhow!("Invalid account: {}", choice))?;
+            account.path.clone()
+        }
+    };
+
+    println!("Derivation path: {}", path);
+    let signer = seed.derive(&path)?;
+    Ok(SecureKeypair::from_bytes(&signer.secret_bytes())?)
+}
+
+/// Register a key generated offline as a watch-only address book entry
+fn import_public_manifest(args: &ImportArgs, path: &Path, db_path: &Option<PathBuf>) -> Result<()> {
+    let db = open_db(db_path)?;
+    let signed = read_manifest(path)?;
+    signed.register_watch_only(&db, &args.label)?;
+    for tag in &args.tag {
+        AddressBook::new(&db).add_tag(&signed.manifest.pubkey, tag)?;
+    }
+
+    let mut tags = vec![WATCH_ONLY_TAG.to_string()];
+    tags.extend(signed.manifest.tags.iter().cloned());
+    tags.extend(args.tag.iter().cloned());
+
+    println!("{}", output::success("Registered watch-only key:"));
+    println!("  Public key: {}", signed.manifest.pubkey);
+    println!("  Fingerprint: {}", fingerprint(&signed.manifest.pubkey));
+    println!("  Label: {}", args.label);
+    println!("  Tags: {}", tags.join(", "));
+    println!("Compare the fingerprint with the one shown on the air-gapped machine.");
+
+    Ok(())
+}
@@ -3,6 +3,7 @@
 use std::path::PathBuf;
 
 use anyhow::Result;
+use solana_keyring::output;
 
 use super::open_db;
 use crate::cli::LabelArgs;
@@ -13,7 +14,10 @@ pub fn run(args: LabelArgs, db_path: &Option<PathBuf>) -> Result<()> {
     let updated = db.update_keypair_label(&args.identifier, &args.label)?;
 
     if updated {
-        println!("Updated label to '{}'", args.label);
+        println!(
+            "{}",
+            output::success(format!("Updated label to '{}'", args.label))
+        );
     } else {
         anyhow::bail!("Keypair not found: {}", args.identifier);
     }
@@ -4,6 +4,8 @@ use std::path::PathBuf;
 
 use anyhow::Result;
 use solana_keyring::ledger::LedgerSigner;
+use solana_keyring::output;
+use solana_keyring::progress::with_spinner;
 
 use super::open_db;
 use crate::cli::LedgerCommands;
@@ -13,19 +15,28 @@ pub fn run(cmd: LedgerCommands, db_path: &Option<PathBuf>) -> Result<()> {
 
     match cmd {
         LedgerCommands::Add(args) => {
-            println!("Connecting to Ledger device...");
-
             // Connect and get public key
-            let signer = LedgerSigner::connect(&args.derivation_path)?;
+            let signer = with_spinner("Connecting to Ledger device...", || {
+                LedgerSigner::connect(&args.derivation_path)
+            })?;
             let pubkey = signer.pubkey();
 
+            // Have the user check the address on the device's own screen
+            if args.verify {
+                println!("Verify the address on your Ledger: {}", pubkey);
+                signer.confirm_pubkey_on_device().map_err(|e| {
+                    anyhow::anyhow!("Address not verified on the device, not added: {}", e)
+                })?;
+                println!("{}", output::success("Address verified on the device"));
+            }
+
             // Convert tags to &str slice
             let tags: Vec<&str> = args.tag.iter().map(|s| s.as_str()).collect();
 
             // Store in database
             db.store_ledger_wallet(pubkey, &args.label, &args.derivation_path, &tags)?;
 
-            println!("Added Ledger wallet:");
+            println!("{}", output::success("Added Ledger wallet:"));
             println!("  Public key: {}", pubkey);
             println!("  Label: {}", args.label);
             println!("  Derivation path: {}", args.derivation_path);
@@ -57,7 +68,10 @@ pub fn run(cmd: LedgerCommands, db_path: &Option<PathBuf>) -> Result<()> {
             let deleted = db.delete_ledger_wallet(&args.identifier)?;
 
             if deleted {
-                println!("Removed Ledger wallet '{}'", args.identifier);
+                println!(
+                    "{}",
+                    output::success(format!("Removed Ledger wallet '{}'", args.identifier))
+                );
             } else {
                 anyhow::bail!("Ledger wallet not found: {}", args.identifier);
             }
@@ -0,0 +1,94 @@
+//! Spending limit commands
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use solana_keyring::list_signers;
+use solana_keyring::output;
+use solana_keyring::spending::{
+    LimitScope, SpendingPolicy, format_sol, format_window, parse_sol, parse_window, unix_now,
+};
+
+use super::open_db;
+use crate::cli::LimitCommands;
+
+pub fn run(cmd: LimitCommands, db_path: &Option<PathBuf>) -> Result<()> {
+    let db = open_db(db_path)?;
+    let policy = SpendingPolicy::new(&db);
+
+    match cmd {
+        LimitCommands::Add(args) => {
+            let max_lamports = parse_sol(&args.sol)?;
+            let window_seconds = parse_window(&args.window)?;
+
+            let (scope, target) = match (&args.key, &args.tag) {
+                (Some(key), _) => {
+                    let signers = list_signers(&db, None)?;
+                    let signer = signers
+                        .iter()
+                        .find(|s| s.pubkey == *key || s.label == *key)
+                        .ok_or_else(|| anyhow::anyhow!("Signer not found: {}", key))?;
+                    (LimitScope::Key, signer.pubkey.clone())
+                }
+                (None, Some(tag)) => (LimitScope::Tag, tag.clone()),
+                (None, None) => anyhow::bail!("Specify --key or --tag"),
+            };
+
+            let id = policy.add(scope, &target, max_lamports, window_seconds)?;
+
+            println!(
+                "{}",
+                output::success(format!("Added spending limit {}:", id))
+            );
+            println!("  Applies to: {} {}", scope, target);
+            println!(
+                "  Limit: {} SOL per {}",
+                format_sol(max_lamports),
+                format_window(window_seconds)
+            );
+        }
+
+        LimitCommands::List => {
+            let limits = policy.list()?;
+
+            if limits.is_empty() {
+                println!("No spending limits configured.");
+                return Ok(());
+            }
+
+            let now = unix_now();
+
+            println!(
+                "{:<4} {:<5} {:<44} {:>12} {:>7} {:>12}",
+                "ID", "SCOPE", "TARGET", "LIMIT (SOL)", "WINDOW", "REMAINING"
+            );
+            println!("{}", "-".repeat(90));
+
+            for limit in limits {
+                let spent = policy.spent(&limit, now)?;
+                println!(
+                    "{:<4} {:<5} {:<44} {:>12} {:>7} {:>12}",
+                    limit.id,
+                    limit.scope,
+                    limit.target,
+                    format_sol(limit.max_lamports),
+                    format_window(limit.window_seconds),
+                    format_sol(limit.max_lamports.saturating_sub(spent)),
+                );
+            }
+        }
+
+        LimitCommands::Remove(args) => {
+            if policy.remove(args.id)? {
+                println!(
+                    "{}",
+                    output::success(format!("Removed spending limit {}", args.id))
+                );
+            } else {
+                anyhow::bail!("Spending limit not found: {}", args.id);
+            }
+        }
+    }
+
+    Ok(())
+}
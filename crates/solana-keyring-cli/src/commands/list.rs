@@ -1,56 +1,107 @@
 //! List signers
 
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Arc;
 
-use anyhow::Result;
-use solana_keyring::{SignerType, list_signers};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use solana_actor::BalanceFetcher;
+use solana_keyring::backup::backup_status;
+use solana_keyring::keypair::fingerprint;
+use solana_keyring::output;
+use solana_keyring::spending::{format_sol, unix_now};
+use solana_keyring::{SignerInfo, SignerType, list_signers};
+use solana_sdk::pubkey::Pubkey;
 
-use super::open_db;
-use crate::cli::{ListArgs, OutputFormat, SignerTypeFilter};
+use super::{open_db, rpc_connection};
+use crate::cli::{ListArgs, ListColumn, ListFilter, ListSort, OutputFormat, SignerTypeFilter};
+
+/// A listed signer, with its balance when requested
+#[derive(Serialize)]
+struct ListEntry {
+    #[serde(flatten)]
+    signer: SignerInfo,
+    fingerprint: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    balance: Option<u64>,
+}
 
 pub fn run(args: ListArgs, db_path: &Option<PathBuf>) -> Result<()> {
     let db = open_db(db_path)?;
 
     let signers = list_signers(&db, args.tag.as_deref())?;
+    let backup = backup_status(&db)?;
+
+    let mut filtered: Vec<_> = signers
+        .into_iter()
+        .filter(|s| args.signer_type.as_ref().is_none_or(|t| type_matches(t, s)))
+        .filter(|s| args.filter.iter().all(|f| filter_matches(f, s)))
+        .collect();
+
+    let want_balances =
+        args.sort == Some(ListSort::Balance) || args.columns.contains(&ListColumn::Balance);
+    let balances = if want_balances && !filtered.is_empty() {
+        fetch_balances(&args.rpc_url, db_path, &filtered)?
+    } else {
+        HashMap::new()
+    };
+
+    if let Some(sort) = args.sort {
+        match sort {
+            ListSort::Label => filtered.sort_by_key(|s| s.label.to_lowercase()),
+            ListSort::Created => filtered.sort_by(|a, b| a.created_at.cmp(&b.created_at)),
+            // Most recently used first, never-used signers last
+            ListSort::LastUsed => filtered.sort_by_key(|s| std::cmp::Reverse(s.last_used)),
+            // Largest balance first
+            ListSort::Balance => filtered
+                .sort_by_key(|s| std::cmp::Reverse(balances.get(&s.pubkey).copied().unwrap_or(0))),
+        }
+    }
+    if args.reverse {
+        filtered.reverse();
+    }
 
-    // Filter by type if specified
-    let filtered: Vec<_> = signers
+    let entries: Vec<ListEntry> = filtered
         .into_iter()
-        .filter(|s| match &args.signer_type {
-            Some(SignerTypeFilter::Keypair) => s.signer_type == SignerType::Keypair,
-            Some(SignerTypeFilter::Ledger) => s.signer_type == SignerType::Ledger,
-            Some(SignerTypeFilter::Squads) => s.signer_type == SignerType::Squads,
-            Some(SignerTypeFilter::All) | None => true,
+        .map(|signer| ListEntry {
+            balance: balances.get(&signer.pubkey).copied(),
+            fingerprint: fingerprint(&signer.pubkey),
+            signer,
         })
         .collect();
 
     match args.format {
         OutputFormat::Json => {
-            println!("{}", serde_json::to_string_pretty(&filtered)?);
+            println!("{}", serde_json::to_string_pretty(&entries)?);
         }
         OutputFormat::Table => {
-            if filtered.is_empty() {
+            if entries.is_empty() {
                 println!("No signers found.");
                 return Ok(());
             }
 
-            println!("{:<8} {:<44} {:<20} TAGS", "TYPE", "PUBLIC KEY", "LABEL");
-            println!("{}", "-".repeat(90));
-
-            for signer in filtered {
-                let tags = if signer.tags.is_empty() {
-                    String::new()
-                } else {
-                    signer.tags.join(", ")
-                };
-
-                println!(
-                    "{:<8} {:<44} {:<20} {}",
-                    signer.signer_type.to_string(),
-                    signer.pubkey,
-                    truncate(&signer.label, 20),
-                    tags
-                );
+            let header: Vec<String> = args
+                .columns
+                .iter()
+                .map(|c| pad(column_title(*c), *c))
+                .collect();
+            println!("{}", header.join(" ").trim_end());
+            println!("{}", "-".repeat(108));
+
+            let now = unix_now();
+            for entry in &entries {
+                let cells: Vec<String> = args
+                    .columns
+                    .iter()
+                    .map(|c| pad(&cell(entry, *c, now), *c))
+                    .collect();
+                println!("{}", cells.join(" ").trim_end());
+            }
+
+            if let Some(reminder) = backup.reminder() {
+                eprintln!();
+                eprintln!("{}", output::warning(reminder));
             }
         }
     }
@@ -58,6 +109,124 @@ pub fn run(args: ListArgs, db_path: &Option<PathBuf>) -> Result<()> {
     Ok(())
 }
 
+fn type_matches(filter: &SignerTypeFilter, signer: &SignerInfo) -> bool {
+    match filter {
+        SignerTypeFilter::Keypair => signer.signer_type == SignerType::Keypair,
+        SignerTypeFilter::Ledger => signer.signer_type == SignerType::Ledger,
+        SignerTypeFilter::Yubikey => signer.signer_type == SignerType::Yubikey,
+        SignerTypeFilter::Squads => signer.signer_type == SignerType::Squads,
+        SignerTypeFilter::Custom => signer.signer_type == SignerType::Custom,
+        SignerTypeFilter::All => true,
+    }
+}
+
+fn filter_matches(filter: &ListFilter, signer: &SignerInfo) -> bool {
+    match filter {
+        ListFilter::Type(t) => type_matches(t, signer),
+        ListFilter::Tag(tag) => signer.tags.contains(tag),
+        ListFilter::Label(label) => signer.label.to_lowercase().contains(&label.to_lowercase()),
+    }
+}
+
+/// Fetch lamport balances for the listed signers
+fn fetch_balances(
+    rpc_url: &str,
+    db_path: &Option<PathBuf>,
+    signers: &[SignerInfo],
+) -> Result<HashMap<String, u64>> {
+    let pubkeys = signers
+        .iter()
+        .map(|s| {
+            s.pubkey
+                .parse::<Pubkey>()
+                .with_context(|| format!("Invalid public key: {}", s.pubkey))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let fetcher = BalanceFetcher::new(Arc::new(rpc_connection(rpc_url, db_path)?));
+    let rt = tokio::runtime::Runtime::new()?;
+    let balances = rt
+        .block_on(fetcher.lamports(&pubkeys))
+        .with_context(|| format!("Failed to fetch balances from {}", rpc_url))?;
+    Ok(balances
+        .into_iter()
+        .map(|(pubkey, lamports)| (pubkey.to_string(), lamports))
+        .collect())
+}
+
+fn column_title(column: ListColumn) -> &'static str {
+    match column {
+        ListColumn::Type => "TYPE",
+        ListColumn::Pubkey => "PUBLIC KEY",
+        ListColumn::Fingerprint => "FINGERPRINT",
+        ListColumn::Label => "LABEL",
+        ListColumn::Tags => "TAGS",
+        ListColumn::Created => "CREATED",
+        ListColumn::LastUsed => "LAST USED",
+        ListColumn::Balance => "BALANCE (SOL)",
+    }
+}
+
+fn column_width(column: ListColumn) -> Option<usize> {
+    match column {
+        ListColumn::Type => Some(8),
+        ListColumn::Pubkey => Some(44),
+        ListColumn::Fingerprint => Some(17),
+        ListColumn::Label => Some(20),
+        ListColumn::Created => Some(19),
+        ListColumn::LastUsed => Some(10),
+        ListColumn::Balance => Some(14),
+        ListColumn::Tags => None,
+    }
+}
+
+fn pad(value: &str, column: ListColumn) -> String {
+    match column_width(column) {
+        Some(width) => {
+            let padding = width.saturating_sub(display_width(value));
+            format!("{}{}", value, " ".repeat(padding))
+        }
+        None => value.to_string(),
+    }
+}
+
+/// Terminal columns taken by a string, counting emoji as two columns
+fn display_width(s: &str) -> usize {
+    s.chars()
+        .map(|c| if c.len_utf8() == 4 { 2 } else { 1 })
+        .sum()
+}
+
+fn cell(entry: &ListEntry, column: ListColumn, now: i64) -> String {
+    let signer = &entry.signer;
+    match column {
+        ListColumn::Type => signer
+            .driver
+            .clone()
+            .unwrap_or_else(|| signer.signer_type.to_string()),
+        ListColumn::Pubkey => signer.pubkey.clone(),
+        ListColumn::Fingerprint => entry.fingerprint.clone(),
+        ListColumn::Label => truncate(&signer.label, 20),
+        ListColumn::Tags => signer.tags.join(", "),
+        ListColumn::Created => signer.created_at.clone(),
+        ListColumn::LastUsed => match signer.last_used {
+            Some(at) => format_age(now.saturating_sub(at)),
+            None => "never".to_string(),
+        },
+        ListColumn::Balance => entry.balance.map(format_sol).unwrap_or_default(),
+    }
+}
+
+/// Format an age in seconds as e.g. "5m ago" using the largest whole unit
+fn format_age(seconds: i64) -> String {
+    match seconds {
+        s if s < 60 => "just now".to_string(),
+        s if s < 3600 => format!("{}m ago", s / 60),
+        s if s < 86400 => format!("{}h ago", s / 3600),
+        s => format!("{}d ago", s / 86400),
+    }
+}
+
 fn truncate(s: &str, max_len: usize) -> String {
     if s.len() <= max_len {
         s.to_string()
@@ -0,0 +1,49 @@
+//! Locale selection and message extraction
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use solana_keyring::i18n::{self, LOCALE_SETTING};
+use solana_keyring::output;
+
+use super::open_db;
+use crate::cli::LocaleCommands;
+
+pub fn run(cmd: LocaleCommands, db_path: &Option<PathBuf>) -> Result<()> {
+    match cmd {
+        LocaleCommands::Show => {
+            let db = open_db(db_path)?;
+            let preferred = db.get_setting(LOCALE_SETTING)?;
+
+            println!("Active locale: {}", i18n::resolve_locale(Some(&db)));
+            match preferred {
+                Some(locale) => println!("Preferred locale: {}", locale),
+                None => println!("Preferred locale: system default"),
+            }
+            println!("Available: {}", i18n::available_locales().join(", "));
+        }
+        LocaleCommands::Set(args) => {
+            let Some(locale) = i18n::negotiate(&args.locale) else {
+                anyhow::bail!(
+                    "No translation for '{}' (available: {})",
+                    args.locale,
+                    i18n::available_locales().join(", ")
+                );
+            };
+            let db = open_db(db_path)?;
+            db.set_setting(LOCALE_SETTING, locale)?;
+            println!("{}", output::success(format!("Locale set to {}", locale)));
+            println!("Restart the agent for the change to apply to approval prompts.");
+        }
+        LocaleCommands::Unset => {
+            let db = open_db(db_path)?;
+            db.delete_setting(LOCALE_SETTING)?;
+            println!("{}", output::success("Locale follows the system default"));
+        }
+        LocaleCommands::Extract(args) => {
+            print!("{}", i18n::extract(args.missing.as_deref()));
+        }
+    }
+
+    Ok(())
+}
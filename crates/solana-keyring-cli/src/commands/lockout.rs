@@ -0,0 +1,82 @@
+//! Failed unlock attempts and hard-lock recovery
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use solana_keyring::lockout::{self, UnlockGate};
+use solana_keyring::output;
+use solana_keyring::spending::unix_now;
+
+use super::{get_verified_passphrase, open_db, prompt_passphrase};
+use crate::cli::LockoutCommands;
+
+pub fn run(cmd: LockoutCommands, db_path: &Option<PathBuf>) -> Result<()> {
+    let db = open_db(db_path)?;
+
+    match cmd {
+        LockoutCommands::Status => {
+            let status = lockout::status(&db, unix_now())?;
+
+            println!("Failed unlock attempts: {}", status.failed_attempts);
+            match status.gate {
+                UnlockGate::Open => println!("Unlocking: open"),
+                UnlockGate::Delayed { retry_in } => {
+                    println!("Unlocking: throttled for {}s", retry_in)
+                }
+                UnlockGate::HardLocked => println!(
+                    "Unlocking: {}",
+                    output::error("hard-locked (run 'solana-keyring lockout recover')")
+                ),
+            }
+            match status.hard_lock_after {
+                Some(limit) => println!("Hard-lock: after {} failed attempts", limit),
+                None => println!("Hard-lock: off"),
+            }
+        }
+
+        LockoutCommands::RecoveryCode(args) => {
+            get_verified_passphrase(&db)?;
+            let code = lockout::set_recovery_code(&db, args.hard_lock_after)?;
+
+            println!(
+                "{}",
+                output::success(format!(
+                    "Unlocking hard-locks after {} failed attempts",
+                    args.hard_lock_after
+                ))
+            );
+            println!();
+            println!("Recovery code: {}", code);
+            println!();
+            println!(
+                "{}",
+                output::warning(
+                    "Store it offline. It is shown once, works once, and replaces any earlier code."
+                )
+            );
+        }
+
+        LockoutCommands::Disable => {
+            get_verified_passphrase(&db)?;
+            if lockout::disable_hard_lock(&db)? {
+                println!("{}", output::success("Hard-lock turned off"));
+            } else {
+                println!("Hard-lock was not enabled.");
+            }
+        }
+
+        LockoutCommands::Recover => {
+            let code = prompt_passphrase("Enter recovery code: ")?;
+            if !lockout::recover(&db, &code, unix_now())? {
+                anyhow::bail!("Invalid recovery code");
+            }
+
+            println!("{}", output::success("Unlocking is open again"));
+            println!(
+                "Hard-lock is off until you run 'solana-keyring lockout recovery-code' again."
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+//! Database maintenance command
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use solana_keyring::maintenance::{Maintenance, format_bytes};
+use solana_keyring::spending::{format_window, parse_window, unix_now};
+
+use super::{get_verified_passphrase, open_db};
+use crate::cli::MaintenanceArgs;
+
+pub fn run(args: MaintenanceArgs, db_path: &Option<PathBuf>) -> Result<()> {
+    let db = open_db(db_path)?;
+    // Pruning removes audit records, so require the passphrase
+    get_verified_passphrase(&db)?;
+
+    let maintenance = Maintenance::new(&db);
+
+    if let Some(retention) = &args.audit_retention {
+        let seconds = match retention.as_str() {
+            "off" => None,
+            window => Some(parse_window(window)?),
+        };
+        maintenance.set_audit_retention(seconds)?;
+    }
+
+    match maintenance.audit_retention()? {
+        Some(seconds) => println!("Audit log retention: {}", format_window(seconds)),
+        None => println!("Audit log retention: keep everything"),
+    }
+
+    let report = maintenance.run(unix_now())?;
+
+    println!("Pruned {} audit event(s)", report.audit_events_pruned);
+    println!("Pruned {} spending counter(s)", report.spends_pruned);
+    println!(
+        "Database size: {} -> {}",
+        format_bytes(report.size_before),
+        format_bytes(report.size_after)
+    );
+    println!();
+
+    println!("{:<24} {:>10} {:>12}", "TABLE", "ROWS", "SIZE");
+    println!("{}", "-".repeat(48));
+    for table in &report.tables {
+        println!(
+            "{:<24} {:>10} {:>12}",
+            table.name,
+            table.rows,
+            format_bytes(table.bytes)
+        );
+    }
+
+    Ok(())
+}
@@ -2,21 +2,39 @@
 
 pub mod address_book;
 pub mod agent_client;
+pub mod alt;
+pub mod audit;
+pub mod backup;
+pub mod ceremony;
+pub mod cluster;
+pub mod delegate;
 pub mod delete;
 pub mod export;
 pub mod generate;
+pub mod high_value;
 pub mod import;
 pub mod label;
 pub mod ledger;
+pub mod limit;
 pub mod list;
+pub mod locale;
+pub mod lockout;
+pub mod maintenance;
 pub mod new;
+pub mod policy;
+pub mod secret;
 pub mod squads;
 pub mod tag;
+pub mod yubikey;
 
 use std::path::PathBuf;
 
 use anyhow::Result;
-use solana_keyring::{Database, default_db_path};
+use solana_actor::{RpcAuth, RpcConnection};
+use solana_keyring::output;
+use solana_keyring::rpc_endpoints::RpcEndpoints;
+use solana_keyring::{Database, Error, default_db_path};
+use zeroize::Zeroizing;
 
 /// Agent configuration for commands
 #[derive(Clone, Default)]
@@ -52,7 +70,7 @@ pub fn open_db(path: &Option<PathBuf>) -> Result<Database> {
 
 /// Prompt for the master passphrase
 pub fn prompt_passphrase(prompt: &str) -> Result<String> {
-    rpassword::prompt_password(prompt).map_err(Into::into)
+    rpassword::prompt_password(output::prompt(prompt)).map_err(Into::into)
 }
 
 /// Prompt for passphrase and verify it
@@ -65,3 +83,35 @@ pub fn get_verified_passphrase(db: &Database) -> Result<String> {
 
     Ok(passphrase)
 }
+
+/// Connect to an RPC endpoint with the auth configured for it in `rpc.json`
+///
+/// `rpc.json` sits next to the keyring database. Prompts for the master
+/// passphrase only if the endpoint refers to stored secrets.
+pub fn rpc_connection(rpc_url: &str, db_path: &Option<PathBuf>) -> Result<RpcConnection> {
+    let endpoints = RpcEndpoints::load(&get_db_path(db_path).with_file_name("rpc.json"))?;
+    let Some(endpoint) = endpoints.find(rpc_url) else {
+        return Ok(RpcConnection::new(rpc_url));
+    };
+
+    let unlocked = if endpoint.secret_names()?.is_empty() {
+        None
+    } else {
+        let db = open_db(db_path)?;
+        let passphrase = Zeroizing::new(get_verified_passphrase(&db)?);
+        Some((db, passphrase))
+    };
+    let resolved = endpoint.resolve(|name| match &unlocked {
+        Some((db, passphrase)) => db.load_secret(name, passphrase.as_bytes()),
+        None => Err(Error::SecretNotFound(name.to_string())),
+    })?;
+
+    let mut auth = RpcAuth::new();
+    for (name, value) in &resolved.headers {
+        auth = auth.with_header(name.as_str(), value.as_str());
+    }
+    for (name, value) in &resolved.query {
+        auth = auth.with_query(name.as_str(), value.as_str());
+    }
+    Ok(RpcConnection::new_with_auth(rpc_url, &auth)?)
+}
@@ -3,6 +3,8 @@
 use std::path::PathBuf;
 
 use anyhow::Result;
+use solana_keyring::crypto::ct_eq_str;
+use solana_keyring::output;
 use solana_keyring::{Database, default_db_path};
 
 use super::prompt_passphrase;
@@ -33,7 +35,7 @@ pub fn run(args: NewArgs, db_path: &Option<PathBuf>) -> Result<()> {
     let passphrase = prompt_passphrase("Enter new master passphrase: ")?;
     let confirm = prompt_passphrase("Confirm master passphrase: ")?;
 
-    if passphrase != confirm {
+    if !ct_eq_str(&passphrase, &confirm) {
         anyhow::bail!("Passphrases do not match");
     }
 
@@ -45,6 +47,9 @@ pub fn run(args: NewArgs, db_path: &Option<PathBuf>) -> Result<()> {
     let db = Database::open(&path)?;
     db.initialize(passphrase.as_bytes())?;
 
-    println!("Keyring initialized at {}", path.display());
+    println!(
+        "{}",
+        output::success(format!("Keyring initialized at {}", path.display()))
+    );
     Ok(())
 }
@@ -0,0 +1,94 @@
+//! Policy dry-run and lint commands
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use solana_keyring::output;
+use solana_keyring::policy::{Decision, PolicyEngine, Severity, Verdict, lint};
+use solana_keyring::spending::{format_sol, unix_now};
+use solana_keyring::{Database, list_signers};
+
+use super::open_db;
+use crate::cli::PolicyCommands;
+
+pub fn run(cmd: PolicyCommands, db_path: &Option<PathBuf>) -> Result<()> {
+    let db = open_db(db_path)?;
+
+    match cmd {
+        PolicyCommands::Check(args) => {
+            let pubkey = resolve_signer(&db, &args.signer)?;
+            let message_bytes = std::fs::read(&args.transaction_file)?;
+
+            let report = PolicyEngine::new(&db, &args.destinations)
+                .with_cluster_check(!args.offline)
+                .evaluate(&message_bytes, &pubkey, unix_now())?;
+
+            println!("Signer: {}", report.signer);
+            println!("Outflow: {} SOL", format_sol(report.outflow));
+            if !report.destinations.is_empty() {
+                println!("Destinations:");
+                for dest in &report.destinations {
+                    println!("  {}", dest);
+                }
+            }
+
+            println!();
+            for rule in &report.rules {
+                let verdict = format!("{:<7}", rule.verdict.to_string().to_uppercase());
+                let verdict = match rule.verdict {
+                    Verdict::Skip => output::dim(verdict),
+                    _ => output::heading(verdict),
+                };
+                println!("{} {}", verdict, rule.rule);
+                println!("        {}", output::dim(&rule.reason));
+            }
+
+            println!();
+            let decision = format!("Decision: {}", report.decision);
+            match report.decision {
+                Decision::Denied => println!("{}", output::error(decision)),
+                Decision::AutoApproved => println!("{}", output::success(decision)),
+                Decision::NeedsConfirmation => println!("{}", output::warning(decision)),
+            }
+            match report.deciding_rule() {
+                Some(rule) => println!("  Decided by {}: {}", rule.rule, rule.reason),
+                None => println!("  No rule applies; the agent asks as usual"),
+            }
+        }
+
+        PolicyCommands::Lint(args) => {
+            let findings = lint(&db, &args.destinations)?;
+
+            if findings.is_empty() {
+                println!("{}", output::success("No problems found"));
+                return Ok(());
+            }
+
+            for finding in &findings {
+                let line = format!("{}: {}", finding.rule, finding.message);
+                match finding.severity {
+                    Severity::Error => println!("{}", output::error(line)),
+                    Severity::Warning => println!("{}", output::warning(line)),
+                }
+            }
+
+            let errors = findings
+                .iter()
+                .filter(|f| f.severity == Severity::Error)
+                .count();
+            if errors > 0 {
+                anyhow::bail!("{} error(s) in policy", errors);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn resolve_signer(db: &Database, identifier: &str) -> Result<String> {
+    list_signers(db, None)?
+        .into_iter()
+        .find(|s| s.pubkey == identifier || s.label == identifier)
+        .map(|s| s.pubkey)
+        .ok_or_else(|| anyhow::anyhow!("Signer not found: {}", identifier))
+}
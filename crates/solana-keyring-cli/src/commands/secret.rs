@@ -0,0 +1,63 @@
+//! Encrypted secrets commands
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use solana_keyring::output;
+use zeroize::Zeroizing;
+
+use super::{get_verified_passphrase, open_db, prompt_passphrase};
+use crate::cli::SecretCommands;
+
+pub fn run(cmd: SecretCommands, db_path: &Option<PathBuf>) -> Result<()> {
+    let db = open_db(db_path)?;
+
+    match cmd {
+        SecretCommands::Set(args) => {
+            let passphrase = Zeroizing::new(get_verified_passphrase(&db)?);
+            let value = Zeroizing::new(prompt_passphrase(&format!(
+                "Value for secret '{}': ",
+                args.name
+            ))?);
+            if value.is_empty() {
+                anyhow::bail!("Secret value cannot be empty");
+            }
+
+            db.store_secret(&args.name, &value, passphrase.as_bytes())?;
+            println!(
+                "{}",
+                output::success(format!("Stored secret '{}'", args.name))
+            );
+            println!("  Reference it in rpc.json as {{{{secret:{}}}}}", args.name);
+        }
+
+        SecretCommands::List => {
+            let secrets = db.list_secrets()?;
+
+            if secrets.is_empty() {
+                println!("No secrets found.");
+                return Ok(());
+            }
+
+            println!("{:<30} UPDATED", "NAME");
+            println!("{}", "-".repeat(60));
+
+            for secret in secrets {
+                println!("{:<30} {}", secret.name, secret.updated_at);
+            }
+        }
+
+        SecretCommands::Remove(args) => {
+            if db.delete_secret(&args.name)? {
+                println!(
+                    "{}",
+                    output::success(format!("Removed secret '{}'", args.name))
+                );
+            } else {
+                anyhow::bail!("Secret not found: {}", args.name);
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -2,15 +2,84 @@
 
 use std::path::PathBuf;
 
-use anyhow::Result;
+use std::collections::HashMap;
 
-use super::open_db;
-use crate::cli::SquadsCommands;
+use anyhow::{Context, Result};
+use solana_actor::parse_address_lookup_table;
+use solana_client::rpc_client::RpcClient;
+use solana_keyring::Database;
+use solana_keyring::output;
+use solana_keyring::progress::Spinner;
+use solana_keyring::spending::format_sol;
+use solana_keyring::squads::state::Member;
+use solana_keyring::squads::{
+    MultisigConfig, ProposalIntent, SQUADS_PROGRAM_ID, SquadsSigner, SquadsVaults, VaultInfo,
+    VaultSelector, VaultTransaction, create_multisig, discover_vaults, get_transaction_pda,
+    get_vault_pda, message_hash, read_unsigned_message, transfers, vault_authority,
+    vault_message_bytes, verify_intent,
+};
+use solana_sdk::pubkey::Pubkey;
+
+use super::{get_verified_passphrase, open_db};
+use crate::cli::{SquadsCommands, SquadsVoteArgs};
 
 pub fn run(cmd: SquadsCommands, db_path: &Option<PathBuf>) -> Result<()> {
     let db = open_db(db_path)?;
 
     match cmd {
+        SquadsCommands::Create(args) => {
+            let members = args
+                .member
+                .iter()
+                .map(|member| member.parse())
+                .collect::<solana_keyring::Result<Vec<Member>>>()?;
+            let config_authority = args
+                .config_authority
+                .as_deref()
+                .map(str::parse::<Pubkey>)
+                .transpose()
+                .context("Invalid config authority")?;
+            let config = MultisigConfig {
+                members,
+                threshold: args.threshold,
+                time_lock: args.time_lock,
+                config_authority,
+                rent_collector: None,
+            };
+            config.validate()?;
+
+            let passphrase = get_verified_passphrase(&db)?;
+            let payer = db.load_keypair(&args.payer, passphrase.as_bytes())?;
+            println!("Payer: {}", payer.pubkey_base58());
+
+            let rt = tokio::runtime::Runtime::new()?;
+            let spinner = Spinner::start("Creating Squads multisig...");
+            let created = rt.block_on(create_multisig(&args.rpc_url, &payer, &config))?;
+            spinner.finish(format!("Created multisig {}", created.multisig));
+
+            let tags: Vec<&str> = args.tag.iter().map(|s| s.as_str()).collect();
+            db.store_squads_multisig(
+                &created.multisig.to_string(),
+                &args.label,
+                0,
+                args.threshold as u32,
+                &tags,
+            )?;
+
+            println!("{}", output::success("Added Squads multisig:"));
+            println!("  Address: {}", created.multisig);
+            println!("  Label: {}", args.label);
+            println!("  Vault: {}", created.vault);
+            println!("  Threshold: {}/{}", args.threshold, config.members.len());
+            if args.time_lock > 0 {
+                println!("  Time lock: {}s", args.time_lock);
+            }
+            if !args.tag.is_empty() {
+                println!("  Tags: {}", args.tag.join(", "));
+            }
+            println!("  Signature: {}", created.signature);
+        }
+
         SquadsCommands::Add(args) => {
             // TODO: Fetch multisig info from chain to get threshold
             let threshold = 1; // Placeholder
@@ -21,14 +90,15 @@ pub fn run(cmd: SquadsCommands, db_path: &Option<PathBuf>) -> Result<()> {
             db.store_squads_multisig(
                 &args.multisig_address,
                 &args.label,
-                0, // vault_index
+                args.vault_index as u32,
                 threshold,
                 &tags,
             )?;
 
-            println!("Added Squads multisig:");
+            println!("{}", output::success("Added Squads multisig:"));
             println!("  Address: {}", args.multisig_address);
             println!("  Label: {}", args.label);
+            println!("  Default vault: {}", args.vault_index);
             if !args.tag.is_empty() {
                 println!("  Tags: {}", args.tag.join(", "));
             }
@@ -46,13 +116,22 @@ pub fn run(cmd: SquadsCommands, db_path: &Option<PathBuf>) -> Result<()> {
                 return Ok(());
             }
 
-            println!("{:<44} {:<20} THRESHOLD", "ADDRESS", "LABEL");
-            println!("{}", "-".repeat(70));
+            println!(
+                "{:<44} {:<20} {:<10} THRESHOLD",
+                "ADDRESS", "LABEL", "VAULT"
+            );
+            println!("{}", "-".repeat(80));
 
+            let vaults = SquadsVaults::new(&db);
             for ms in multisigs {
+                let vault = vaults.resolve(&ms.multisig_pubkey, None)?;
                 println!(
-                    "{:<44} {:<20} {}/{}",
-                    ms.multisig_pubkey, ms.label, ms.threshold, ms.threshold
+                    "{:<44} {:<20} {:<10} {}/{}",
+                    ms.multisig_pubkey,
+                    ms.label,
+                    vault.name.unwrap_or_else(|| vault.index.to_string()),
+                    ms.threshold,
+                    ms.threshold
                 );
             }
         }
@@ -61,7 +140,10 @@ pub fn run(cmd: SquadsCommands, db_path: &Option<PathBuf>) -> Result<()> {
             let deleted = db.delete_squads_multisig(&args.identifier)?;
 
             if deleted {
-                println!("Removed Squads multisig '{}'", args.identifier);
+                println!(
+                    "{}",
+                    output::success(format!("Removed Squads multisig '{}'", args.identifier))
+                );
             } else {
                 anyhow::bail!("Squads multisig not found: {}", args.identifier);
             }
@@ -75,7 +157,295 @@ pub fn run(cmd: SquadsCommands, db_path: &Option<PathBuf>) -> Result<()> {
             );
             println!("Note: Sync not yet implemented.");
         }
+
+        SquadsCommands::Vaults(args) => {
+            let vaults = SquadsVaults::new(&db);
+            let default = vaults.resolve(&args.identifier, None)?;
+            let names = match db.get_squads_multisig(&args.identifier)? {
+                Some(_) => vaults.names(&args.identifier)?,
+                None => Vec::new(),
+            };
+            let program_id: Pubkey = SQUADS_PROGRAM_ID.parse()?;
+
+            let rpc = RpcClient::new(args.rpc_url.clone());
+            let mut found = discover_vaults(&rpc, &default.multisig, &program_id)?;
+
+            // Show the default and named vaults even before they're funded
+            let mut indexes: Vec<u8> = names.iter().map(|(index, _)| *index).collect();
+            indexes.push(default.index);
+            for index in indexes {
+                if !found.iter().any(|vault| vault.index == index) {
+                    found.push(VaultInfo {
+                        index,
+                        address: get_vault_pda(&default.multisig, index, &program_id),
+                        lamports: 0,
+                    });
+                }
+            }
+            found.sort_by_key(|vault| vault.index);
+
+            println!(
+                "{:<7} {:<20} {:<44} BALANCE (SOL)",
+                "INDEX", "NAME", "ADDRESS"
+            );
+            println!("{}", "-".repeat(90));
+
+            for vault in found {
+                let name = names
+                    .iter()
+                    .find(|(index, _)| *index == vault.index)
+                    .map(|(_, name)| name.as_str())
+                    .unwrap_or("");
+                let marker = if vault.index == default.index {
+                    " (default)"
+                } else {
+                    ""
+                };
+                println!(
+                    "{:<7} {:<20} {:<44} {}{}",
+                    vault.index,
+                    name,
+                    vault.address,
+                    format_sol(vault.lamports),
+                    marker
+                );
+            }
+        }
+
+        SquadsCommands::NameVault(args) => {
+            SquadsVaults::new(&db).name(&args.identifier, args.vault_index, &args.name)?;
+            println!(
+                "{}",
+                output::success(format!(
+                    "Named vault {} of '{}' \"{}\"",
+                    args.vault_index, args.identifier, args.name
+                ))
+            );
+        }
+
+        SquadsCommands::DefaultVault(args) => {
+            let selector: VaultSelector = args.vault.parse()?;
+            let vault = SquadsVaults::new(&db).set_default(&args.identifier, &selector)?;
+            let program_id: Pubkey = SQUADS_PROGRAM_ID.parse()?;
+            println!(
+                "{}",
+                output::success(format!(
+                    "Default vault of '{}' is now {}",
+                    args.identifier, vault.index
+                ))
+            );
+            println!("  Address: {}", vault.address(&program_id));
+        }
+
+        SquadsCommands::Verify(args) => {
+            let intent: ProposalIntent = serde_json::from_str(
+                &std::fs::read_to_string(&args.intent)
+                    .with_context(|| format!("Failed to read {}", args.intent.display()))?,
+            )
+            .with_context(|| format!("Invalid intent file {}", args.intent.display()))?;
+
+            let multisig = SquadsVaults::new(&db)
+                .resolve(&args.identifier, None)?
+                .multisig;
+            let program_id: Pubkey = SQUADS_PROGRAM_ID.parse()?;
+            let address = get_transaction_pda(&multisig, args.index, &program_id);
+
+            let rpc = RpcClient::new(args.rpc_url.clone());
+            let account = rpc.get_account(&address).with_context(|| {
+                format!("No transaction {} for multisig {}", args.index, multisig)
+            })?;
+            if account.owner != program_id {
+                anyhow::bail!("Transaction account {} is not owned by Squads", address);
+            }
+            let transaction = VaultTransaction::parse(&account.data)?;
+
+            // Accounts loaded from lookup tables are compared like any other
+            let mut tables = HashMap::new();
+            for lookup in &transaction.lookups {
+                let table =
+                    parse_address_lookup_table(lookup.table, &rpc.get_account(&lookup.table)?)?;
+                tables.insert(lookup.table, table.addresses);
+            }
+            let instructions = transaction.instructions(&tables)?;
+            let report = verify_intent(&intent, transaction.vault_index, &instructions)?;
+
+            println!("Proposal {} of {}", transaction.index, multisig);
+            println!("  Created by: {}", transaction.creator);
+            println!(
+                "  Vault: {} ({})",
+                transaction.vault_index,
+                get_vault_pda(&multisig, transaction.vault_index, &program_id)
+            );
+            println!("  Instructions: {}", instructions.len());
+            for transfer in transfers(&instructions) {
+                println!("  Transfer: {}", transfer);
+            }
+            println!();
+
+            for warning in &report.warnings {
+                eprintln!("{}", output::warning(warning));
+            }
+            if !report.matches() {
+                for mismatch in &report.mismatches {
+                    eprintln!("{}", output::error(mismatch));
+                }
+                anyhow::bail!(
+                    "Proposal {} does not match {}",
+                    args.index,
+                    args.intent.display()
+                );
+            }
+            println!(
+                "{}",
+                output::success(format!(
+                    "Proposal {} matches {}",
+                    args.index,
+                    args.intent.display()
+                ))
+            );
+        }
+
+        SquadsCommands::Propose(args) => {
+            let data = std::fs::read(&args.tx)
+                .with_context(|| format!("Failed to read {}", args.tx.display()))?;
+            let message = read_unsigned_message(&data)?;
+
+            let selector = args
+                .vault
+                .as_deref()
+                .map(str::parse::<VaultSelector>)
+                .transpose()?;
+            let vaults = SquadsVaults::new(&db);
+            let resolved = vaults.resolve(&args.multisig, selector.as_ref())?;
+            let program_id: Pubkey = SQUADS_PROGRAM_ID.parse()?;
+
+            // The message must be signed by the vault it will run as
+            let vault_index = vault_authority(&message, &resolved.multisig, &program_id)?;
+            if selector.is_some() && vault_index != resolved.index {
+                anyhow::bail!(
+                    "Message is signed by vault {}, not vault {}",
+                    vault_index,
+                    resolved.index
+                );
+            }
+            let hash = message_hash(&message);
+
+            println!("Multisig: {}", resolved.multisig);
+            println!(
+                "Vault: {} ({})",
+                vault_index,
+                get_vault_pda(&resolved.multisig, vault_index, &program_id)
+            );
+            println!("Instructions: {}", message.instructions().len());
+            println!("Message hash: {}", hash);
+
+            let passphrase = get_verified_passphrase(&db)?;
+            let member = db.load_keypair(&args.member, passphrase.as_bytes())?;
+            let member_pubkey = member.pubkey_base58();
+            println!("Member: {}", member_pubkey);
+
+            let signer = SquadsSigner::new(
+                &resolved.multisig.to_string(),
+                vault_index,
+                &args.rpc_url,
+                member,
+            )?
+            .with_memo(args.memo);
+            let transaction_message = vault_message_bytes(&message)?;
+
+            let rt = tokio::runtime::Runtime::new()?;
+            let spinner = Spinner::start("Creating Squads proposal...");
+            let (proposal, transaction_index) =
+                rt.block_on(signer.create_proposal(&transaction_message))?;
+            spinner.finish(format!(
+                "Created proposal #{} at {}",
+                transaction_index, proposal
+            ));
+
+            db.record_pending_submission(
+                &resolved.multisig.to_string(),
+                vault_index as u32,
+                transaction_index,
+                &proposal.to_string(),
+                &member_pubkey,
+                &hash,
+                Some(&args.tx.display().to_string()),
+            )?;
+            println!(
+                "{}",
+                output::success(format!(
+                    "Proposal {} is awaiting approval",
+                    transaction_index
+                ))
+            );
+        }
+
+        SquadsCommands::Reject(args) => vote(&db, args, false)?,
+
+        SquadsCommands::Cancel(args) => vote(&db, args, true)?,
+
+        SquadsCommands::Pending(args) => {
+            let multisig = args
+                .identifier
+                .as_deref()
+                .map(|identifier| SquadsVaults::new(&db).resolve(identifier, None))
+                .transpose()?
+                .map(|vault| vault.multisig.to_string());
+            let pending = db.list_pending_submissions(multisig.as_deref())?;
+
+            if pending.is_empty() {
+                println!("No pending proposals found.");
+                return Ok(());
+            }
+
+            println!(
+                "{:<44} {:<7} {:<7} {:<16} CREATED",
+                "MULTISIG", "INDEX", "VAULT", "MESSAGE"
+            );
+            println!("{}", "-".repeat(100));
+
+            for submission in pending {
+                println!(
+                    "{:<44} {:<7} {:<7} {:<16} {}",
+                    submission.multisig_pubkey,
+                    submission.transaction_index,
+                    submission.vault_index,
+                    &submission.message_hash[..16],
+                    submission.created_at
+                );
+            }
+        }
     }
 
     Ok(())
 }
+
+/// Reject or cancel a proposal with a keyring member key
+fn vote(db: &Database, args: SquadsVoteArgs, cancel: bool) -> Result<()> {
+    let resolved = SquadsVaults::new(db).resolve(&args.identifier, None)?;
+
+    let passphrase = get_verified_passphrase(db)?;
+    let member = db.load_keypair(&args.member, passphrase.as_bytes())?;
+    println!("Multisig: {}", resolved.multisig);
+    println!("Member: {}", member.pubkey_base58());
+
+    let signer = SquadsSigner::new(
+        &resolved.multisig.to_string(),
+        resolved.index,
+        &args.rpc_url,
+        member,
+    )?
+    .with_memo(args.memo);
+
+    let rt = tokio::runtime::Runtime::new()?;
+    if cancel {
+        let spinner = Spinner::start(format!("Cancelling proposal #{}...", args.index));
+        rt.block_on(signer.cancel_proposal(args.index))?;
+        spinner.finish(format!("Cancelled proposal #{}", args.index));
+    } else {
+        let spinner = Spinner::start(format!("Rejecting proposal #{}...", args.index));
+        rt.block_on(signer.reject_proposal(args.index))?;
+        spinner.finish(format!("Rejected proposal #{}", args.index));
+    }
+    Ok(())
+}
@@ -3,6 +3,7 @@
 use std::path::PathBuf;
 
 use anyhow::Result;
+use solana_keyring::output;
 
 use super::open_db;
 use crate::cli::TagCommands;
@@ -36,7 +37,10 @@ pub fn run(cmd: TagCommands, db_path: &Option<PathBuf>) -> Result<()> {
                 .ok_or_else(|| anyhow::anyhow!("Keypair not found: {}", args.identifier))?;
 
             db.add_tag_to_keypair(&keypair.pubkey, &args.tag)?;
-            println!("Added tag '{}' to '{}'", args.tag, keypair.label);
+            println!(
+                "{}",
+                output::success(format!("Added tag '{}' to '{}'", args.tag, keypair.label))
+            );
         }
 
         TagCommands::Remove(args) => {
@@ -49,7 +53,13 @@ pub fn run(cmd: TagCommands, db_path: &Option<PathBuf>) -> Result<()> {
 
             let removed = db.remove_tag_from_keypair(&keypair.pubkey, &args.tag)?;
             if removed {
-                println!("Removed tag '{}' from '{}'", args.tag, keypair.label);
+                println!(
+                    "{}",
+                    output::success(format!(
+                        "Removed tag '{}' from '{}'",
+                        args.tag, keypair.label
+                    ))
+                );
             } else {
                 println!("Tag '{}' not found on '{}'", args.tag, keypair.label);
             }
@@ -58,7 +68,7 @@ pub fn run(cmd: TagCommands, db_path: &Option<PathBuf>) -> Result<()> {
         TagCommands::Delete(args) => {
             let deleted = db.delete_tag(&args.tag)?;
             if deleted {
-                println!("Deleted tag '{}'", args.tag);
+                println!("{}", output::success(format!("Deleted tag '{}'", args.tag)));
             } else {
                 println!("Tag '{}' not found", args.tag);
             }
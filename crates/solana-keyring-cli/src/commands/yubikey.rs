@@ -0,0 +1,122 @@
+//! YubiKey wallet commands
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use solana_actor_yubikey::{Slot, YubiKeySigner, list_devices};
+use solana_keyring::output;
+use solana_keyring::progress::with_spinner;
+
+use super::open_db;
+use crate::cli::YubikeyCommands;
+
+pub fn run(cmd: YubikeyCommands, db_path: &Option<PathBuf>) -> Result<()> {
+    let db = open_db(db_path)?;
+
+    match cmd {
+        YubikeyCommands::Add(args) => {
+            let slot: Slot = args.slot.parse()?;
+
+            // Read the public key and policies from the slot; no PIN needed
+            let signer = with_spinner("Connecting to YubiKey...", || {
+                YubiKeySigner::open(args.serial, slot)
+            })?;
+            let pubkey = signer.pubkey_base58();
+
+            // Convert tags to &str slice
+            let tags: Vec<&str> = args.tag.iter().map(|s| s.as_str()).collect();
+
+            // Store in database
+            db.store_yubikey_wallet(
+                &pubkey,
+                &args.label,
+                signer.serial(),
+                &slot.to_string(),
+                &tags,
+            )?;
+
+            println!("{}", output::success("Added YubiKey wallet:"));
+            println!("  Public key: {}", pubkey);
+            println!("  Label: {}", args.label);
+            println!("  Serial: {}", signer.serial());
+            println!("  Slot: {}", slot);
+            println!("  PIN policy: {}", signer.pin_policy());
+            println!("  Touch policy: {}", signer.touch_policy());
+            if !args.tag.is_empty() {
+                println!("  Tags: {}", args.tag.join(", "));
+            }
+            if !signer.requires_touch() {
+                eprintln!(
+                    "{}",
+                    output::warning(
+                        "This key signs without a touch; anything with the PIN can use it."
+                    )
+                );
+            }
+        }
+
+        YubikeyCommands::List => {
+            let wallets = db.list_yubikey_wallets(None)?;
+
+            if wallets.is_empty() {
+                println!("No YubiKey wallets found.");
+                return Ok(());
+            }
+
+            println!("{:<44} {:<20} {:<10} SLOT", "PUBLIC KEY", "LABEL", "SERIAL");
+            println!("{}", "-".repeat(80));
+
+            for wallet in wallets {
+                println!(
+                    "{:<44} {:<20} {:<10} {}",
+                    wallet.pubkey, wallet.label, wallet.serial, wallet.slot
+                );
+            }
+        }
+
+        YubikeyCommands::Devices => {
+            let devices = list_devices()?;
+
+            if devices.is_empty() {
+                println!("No YubiKeys connected.");
+                return Ok(());
+            }
+
+            println!(
+                "{:<10} {:<10} {:<10} READER",
+                "SERIAL", "FIRMWARE", "ED25519"
+            );
+            println!("{}", "-".repeat(80));
+
+            for device in devices {
+                let (major, minor, patch) = device.version;
+                println!(
+                    "{:<10} {:<10} {:<10} {}",
+                    device.serial,
+                    format!("{}.{}.{}", major, minor, patch),
+                    if device.supports_ed25519() {
+                        "yes"
+                    } else {
+                        "no"
+                    },
+                    device.reader
+                );
+            }
+        }
+
+        YubikeyCommands::Remove(args) => {
+            let deleted = db.delete_yubikey_wallet(&args.identifier)?;
+
+            if deleted {
+                println!(
+                    "{}",
+                    output::success(format!("Removed YubiKey wallet '{}'", args.identifier))
+                );
+            } else {
+                anyhow::bail!("YubiKey wallet not found: {}", args.identifier);
+            }
+        }
+    }
+
+    Ok(())
+}
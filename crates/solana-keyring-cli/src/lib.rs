@@ -0,0 +1,68 @@
+//! Solana Keyring CLI
+//!
+//! The `solana-keyring` binary, as a library so it can be bundled into a
+//! combined executable.
+
+mod cli;
+mod commands;
+
+use std::ffi::OsString;
+use std::process::ExitCode;
+
+use clap::Parser;
+
+use cli::{Cli, Commands};
+use commands::AgentConfig;
+
+/// Run the CLI with `args`, starting with the program name
+pub fn run<I, T>(args: I) -> ExitCode
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let cli = Cli::parse_from(args);
+    solana_keyring::output::init(cli.plain);
+    solana_keyring::i18n::init_for_db(&commands::get_db_path(&cli.db_path));
+
+    let agent_config = AgentConfig {
+        use_agent: !cli.no_agent,
+        socket_path: cli.agent_socket,
+    };
+
+    let result = match cli.command {
+        Commands::New(args) => commands::new::run(args, &cli.db_path),
+        Commands::Generate(args) => commands::generate::run(args, &cli.db_path, &agent_config),
+        Commands::Import(args) => commands::import::run(args, &cli.db_path, &agent_config),
+        Commands::Export(args) => commands::export::run(args, &cli.db_path, &agent_config),
+        Commands::List(args) => commands::list::run(args, &cli.db_path),
+        Commands::Label(args) => commands::label::run(args, &cli.db_path),
+        Commands::Delete(args) => commands::delete::run(args, &cli.db_path),
+        Commands::Tag(cmd) => commands::tag::run(cmd, &cli.db_path),
+        Commands::Ledger(cmd) => commands::ledger::run(cmd, &cli.db_path),
+        Commands::Yubikey(cmd) => commands::yubikey::run(cmd, &cli.db_path),
+        Commands::Squads(cmd) => commands::squads::run(cmd, &cli.db_path),
+        Commands::Alt(cmd) => commands::alt::run(cmd, &cli.db_path),
+        Commands::AddressBook(cmd) => commands::address_book::run(cmd, &cli.db_path),
+        Commands::Limit(cmd) => commands::limit::run(cmd, &cli.db_path),
+        Commands::Cluster(cmd) => commands::cluster::run(cmd, &cli.db_path),
+        Commands::HighValue(cmd) => commands::high_value::run(cmd, &cli.db_path),
+        Commands::Policy(cmd) => commands::policy::run(cmd, &cli.db_path),
+        Commands::Delegate(cmd) => commands::delegate::run(cmd, &cli.db_path),
+        Commands::Ceremony(args) => commands::ceremony::run(args, &cli.db_path),
+        Commands::Backup(cmd) => commands::backup::run(cmd, &cli.db_path),
+        Commands::Maintenance(args) => commands::maintenance::run(args, &cli.db_path),
+        Commands::Audit(cmd) => commands::audit::run(cmd, &cli.db_path),
+        Commands::Locale(cmd) => commands::locale::run(cmd, &cli.db_path),
+        Commands::Lockout(cmd) => commands::lockout::run(cmd, &cli.db_path),
+        Commands::Secret(cmd) => commands::secret::run(cmd, &cli.db_path),
+    };
+
+    // Exit with the shared error code so scripts can tell failures apart
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {:?}", e);
+            solana_keyring::error_code(e.as_ref()).exit_code()
+        }
+    }
+}
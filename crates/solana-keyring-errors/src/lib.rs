@@ -0,0 +1,326 @@
+//! Error codes shared by the Solana keyring CLIs, agent and client.
+//!
+//! Every layer reports failures with the same [`ErrorCode`]: the agent sends
+//! its name over the socket, the CLIs exit with its number, and the client maps
+//! both back so callers can match on one enum.
+//!
+//! # Codes
+//!
+//! | Code | Name | Meaning |
+//! |------|------|---------|
+//! | 1 | `INTERNAL_ERROR` | Unexpected failure |
+//! | 2 | `INVALID_ARGUMENTS` | Invalid command-line arguments or input |
+//! | 3 | `CONNECTION_FAILED` | The agent or RPC endpoint could not be reached |
+//! | 4 | `IO_ERROR` | Reading or writing a file or stream failed |
+//! | 10 | `NOT_INITIALIZED` | The keyring has not been created |
+//! | 11 | `LOCKED` | The agent is locked |
+//! | 12 | `INVALID_PASSPHRASE` | The passphrase is wrong |
+//! | 13 | `TOO_MANY_ATTEMPTS` | Unlocking is paused after repeated wrong passphrases |
+//! | 20 | `NOT_FOUND` | The requested item does not exist |
+//! | 21 | `SIGNER_NOT_FOUND` | No signer matches the requested key or label |
+//! | 22 | `INVALID_TRANSACTION` | The transaction could not be decoded or is malformed |
+//! | 23 | `INVALID_SIGNATURE` | A signature was missing or malformed |
+//! | 30 | `READ_ONLY` | The agent refuses changes in read-only mode |
+//! | 31 | `UNAUTHORIZED` | The caller may not use this signer |
+//! | 32 | `SPENDING_LIMIT_EXCEEDED` | The transaction exceeds a spending limit |
+//! | 33 | `CONFIRMATION_REQUIRED` | The transaction was not approved |
+//! | 34 | `CLUSTER_MISMATCH` | The transaction targets a cluster the signer is not bound to |
+//! | 35 | `COOLDOWN_ACTIVE` | The signer is cooling down after its last signature |
+//! | 40 | `HARDWARE_ERROR` | A hardware wallet failed or is not connected |
+//!
+//! Codes double as process exit statuses. `2` matches the status clap uses
+//! for usage errors.
+//!
+//! # Example
+//!
+//! ```
+//! use solana_keyring_errors::ErrorCode;
+//!
+//! let code = ErrorCode::from_code(11).unwrap();
+//! assert_eq!(code, ErrorCode::Locked);
+//! assert_eq!(code.name(), "LOCKED");
+//!
+//! // Names parse in either the documented or the agent's wire form
+//! assert_eq!(ErrorCode::from_name("SignerNotFound"), Some(ErrorCode::SignerNotFound));
+//! ```
+
+use std::fmt;
+use std::process::ExitCode;
+
+/// A failure reported by any keyring component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    /// Unexpected failure.
+    InternalError,
+    /// Invalid command-line arguments or input.
+    InvalidArguments,
+    /// The agent or RPC endpoint could not be reached.
+    ConnectionFailed,
+    /// Reading or writing a file or stream failed.
+    IoError,
+    /// The keyring has not been created.
+    NotInitialized,
+    /// The agent is locked.
+    Locked,
+    /// The passphrase is wrong.
+    InvalidPassphrase,
+    /// Unlocking is paused after repeated wrong passphrases.
+    TooManyAttempts,
+    /// The requested item does not exist.
+    NotFound,
+    /// No signer matches the requested key or label.
+    SignerNotFound,
+    /// The transaction could not be decoded or is malformed.
+    InvalidTransaction,
+    /// A signature was missing or malformed.
+    InvalidSignature,
+    /// The agent refuses changes in read-only mode.
+    ReadOnly,
+    /// The caller may not use this signer.
+    Unauthorized,
+    /// The transaction exceeds a spending limit.
+    SpendingLimitExceeded,
+    /// The transaction was not approved.
+    ConfirmationRequired,
+    /// The transaction targets a cluster the signer is not bound to.
+    ClusterMismatch,
+    /// The signer is cooling down after its last signature.
+    CooldownActive,
+    /// A hardware wallet failed or is not connected.
+    HardwareError,
+}
+
+impl ErrorCode {
+    /// Every code, in numeric order.
+    pub const ALL: [ErrorCode; 19] = [
+        ErrorCode::InternalError,
+        ErrorCode::InvalidArguments,
+        ErrorCode::ConnectionFailed,
+        ErrorCode::IoError,
+        ErrorCode::NotInitialized,
+        ErrorCode::Locked,
+        ErrorCode::InvalidPassphrase,
+        ErrorCode::TooManyAttempts,
+        ErrorCode::NotFound,
+        ErrorCode::SignerNotFound,
+        ErrorCode::InvalidTransaction,
+        ErrorCode::InvalidSignature,
+        ErrorCode::ReadOnly,
+        ErrorCode::Unauthorized,
+        ErrorCode::SpendingLimitExceeded,
+        ErrorCode::ConfirmationRequired,
+        ErrorCode::ClusterMismatch,
+        ErrorCode::CooldownActive,
+        ErrorCode::HardwareError,
+    ];
+
+    /// The numeric code, also used as the process exit status.
+    pub fn code(self) -> u8 {
+        match self {
+            ErrorCode::InternalError => 1,
+            ErrorCode::InvalidArguments => 2,
+            ErrorCode::ConnectionFailed => 3,
+            ErrorCode::IoError => 4,
+            ErrorCode::NotInitialized => 10,
+            ErrorCode::Locked => 11,
+            ErrorCode::InvalidPassphrase => 12,
+            ErrorCode::TooManyAttempts => 13,
+            ErrorCode::NotFound => 20,
+            ErrorCode::SignerNotFound => 21,
+            ErrorCode::InvalidTransaction => 22,
+            ErrorCode::InvalidSignature => 23,
+            ErrorCode::ReadOnly => 30,
+            ErrorCode::Unauthorized => 31,
+            ErrorCode::SpendingLimitExceeded => 32,
+            ErrorCode::ConfirmationRequired => 33,
+            ErrorCode::ClusterMismatch => 34,
+            ErrorCode::CooldownActive => 35,
+            ErrorCode::HardwareError => 40,
+        }
+    }
+
+    /// The stable name, e.g. `SIGNER_NOT_FOUND`.
+    pub fn name(self) -> &'static str {
+        match self {
+            ErrorCode::InternalError => "INTERNAL_ERROR",
+            ErrorCode::InvalidArguments => "INVALID_ARGUMENTS",
+            ErrorCode::ConnectionFailed => "CONNECTION_FAILED",
+            ErrorCode::IoError => "IO_ERROR",
+            ErrorCode::NotInitialized => "NOT_INITIALIZED",
+            ErrorCode::Locked => "LOCKED",
+            ErrorCode::InvalidPassphrase => "INVALID_PASSPHRASE",
+            ErrorCode::TooManyAttempts => "TOO_MANY_ATTEMPTS",
+            ErrorCode::NotFound => "NOT_FOUND",
+            ErrorCode::SignerNotFound => "SIGNER_NOT_FOUND",
+            ErrorCode::InvalidTransaction => "INVALID_TRANSACTION",
+            ErrorCode::InvalidSignature => "INVALID_SIGNATURE",
+            ErrorCode::ReadOnly => "READ_ONLY",
+            ErrorCode::Unauthorized => "UNAUTHORIZED",
+            ErrorCode::SpendingLimitExceeded => "SPENDING_LIMIT_EXCEEDED",
+            ErrorCode::ConfirmationRequired => "CONFIRMATION_REQUIRED",
+            ErrorCode::ClusterMismatch => "CLUSTER_MISMATCH",
+            ErrorCode::CooldownActive => "COOLDOWN_ACTIVE",
+            ErrorCode::HardwareError => "HARDWARE_ERROR",
+        }
+    }
+
+    /// A short description for documentation and help output.
+    pub fn description(self) -> &'static str {
+        match self {
+            ErrorCode::InternalError => "Unexpected failure",
+            ErrorCode::InvalidArguments => "Invalid command-line arguments or input",
+            ErrorCode::ConnectionFailed => "The agent or RPC endpoint could not be reached",
+            ErrorCode::IoError => "Reading or writing a file or stream failed",
+            ErrorCode::NotInitialized => "The keyring has not been created",
+            ErrorCode::Locked => "The agent is locked",
+            ErrorCode::InvalidPassphrase => "The passphrase is wrong",
+            ErrorCode::TooManyAttempts => "Unlocking is paused after repeated wrong passphrases",
+            ErrorCode::NotFound => "The requested item does not exist",
+            ErrorCode::SignerNotFound => "No signer matches the requested key or label",
+            ErrorCode::InvalidTransaction => "The transaction could not be decoded or is malformed",
+            ErrorCode::InvalidSignature => "A signature was missing or malformed",
+            ErrorCode::ReadOnly => "The agent refuses changes in read-only mode",
+            ErrorCode::Unauthorized => "The caller may not use this signer",
+            ErrorCode::SpendingLimitExceeded => "The transaction exceeds a spending limit",
+            ErrorCode::ConfirmationRequired => "The transaction was not approved",
+            ErrorCode::ClusterMismatch => {
+                "The transaction targets a cluster the signer is not bound to"
+            }
+            ErrorCode::CooldownActive => "The signer is cooling down after its last signature",
+            ErrorCode::HardwareError => "A hardware wallet failed or is not connected",
+        }
+    }
+
+    /// Look up a code by number.
+    pub fn from_code(code: u8) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.code() == code)
+    }
+
+    /// Look up a code by a process exit status.
+    ///
+    /// Unknown nonzero statuses (including signals, reported as `None` by
+    /// [`std::process::ExitStatus::code`]) are [`ErrorCode::InternalError`];
+    /// a zero status is not an error.
+    pub fn from_exit_status(status: Option<i32>) -> Option<Self> {
+        match status {
+            Some(0) => None,
+            Some(code) => Some(
+                u8::try_from(code)
+                    .ok()
+                    .and_then(Self::from_code)
+                    .unwrap_or(ErrorCode::InternalError),
+            ),
+            None => Some(ErrorCode::InternalError),
+        }
+    }
+
+    /// Look up a code by name.
+    ///
+    /// Case and underscores are ignored, so both `SIGNER_NOT_FOUND` and the
+    /// agent's `SignerNotFound` parse.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let normalize = |s: &str| {
+            s.chars()
+                .filter(|c| *c != '_')
+                .map(|c| c.to_ascii_lowercase())
+                .collect::<String>()
+        };
+        let name = normalize(name);
+        Self::ALL.into_iter().find(|c| normalize(c.name()) == name)
+    }
+
+    /// The code as a process exit status.
+    pub fn exit_code(self) -> ExitCode {
+        ExitCode::from(self.code())
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// An error message tagged with an [`ErrorCode`].
+///
+/// Use it where a failure has no typed error of its own, so callers and exit
+/// statuses still see the right code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodedError {
+    code: ErrorCode,
+    message: String,
+}
+
+impl CodedError {
+    /// Create an error with a code and message.
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// The error code.
+    pub fn code(&self) -> ErrorCode {
+        self.code
+    }
+
+    /// The message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for CodedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for CodedError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_codes_and_names_are_unique() {
+        let codes: HashSet<_> = ErrorCode::ALL.iter().map(|c| c.code()).collect();
+        let names: HashSet<_> = ErrorCode::ALL.iter().map(|c| c.name()).collect();
+        assert_eq!(codes.len(), ErrorCode::ALL.len());
+        assert_eq!(names.len(), ErrorCode::ALL.len());
+        assert!(!codes.contains(&0));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for code in ErrorCode::ALL {
+            assert_eq!(ErrorCode::from_code(code.code()), Some(code));
+            assert_eq!(ErrorCode::from_name(code.name()), Some(code));
+            assert_eq!(code.to_string(), code.name());
+        }
+        assert_eq!(
+            ErrorCode::from_name("SpendingLimitExceeded"),
+            Some(ErrorCode::SpendingLimitExceeded)
+        );
+        assert_eq!(ErrorCode::from_name("BOGUS"), None);
+    }
+
+    #[test]
+    fn test_from_exit_status() {
+        assert_eq!(ErrorCode::from_exit_status(Some(0)), None);
+        assert_eq!(
+            ErrorCode::from_exit_status(Some(12)),
+            Some(ErrorCode::InvalidPassphrase)
+        );
+        assert_eq!(
+            ErrorCode::from_exit_status(Some(99)),
+            Some(ErrorCode::InternalError)
+        );
+        assert_eq!(
+            ErrorCode::from_exit_status(None),
+            Some(ErrorCode::InternalError)
+        );
+    }
+}
@@ -0,0 +1,44 @@
+# English messages (source locale)
+#
+# Syntax: `id = text`, with `{ $name }` placeholders. Indented lines continue
+# the previous message on a new line. Print this file with
+# `solana-keyring locale extract` to start a translation.
+
+## Transaction summaries
+
+summary-programs = Programs: { $programs }
+summary-accounts = Accounts:
+summary-flag-signer-writable = [signer, writable]
+summary-flag-signer = [signer]
+summary-flag-writable = [writable]
+summary-call = Call to { $program }
+summary-transfer-sol = Transfer { $amount } SOL to { $to }
+summary-create-account = Create account with { $amount } SOL
+summary-token-transfer = Token transfer: { $amount } units
+summary-token-mint = Mint tokens
+summary-token-burn = Burn tokens
+summary-token-close = Close token account
+summary-unparsed = Unable to parse transaction details
+summary-lookup-accounts = Also loads { $count } account(s) from address lookup tables
+summary-new-destinations = New destinations:
+summary-uncounted-outflow = This transaction calls programs that could move SOL beyond what the spending limit counts
+summary-cluster-mismatch = Wrong cluster: { $signer } is bound to { $bound }, but the blockhash is from { $found }
+summary-offchain-message = Off-chain message:
+summary-hash = Summary hash: { $hash }
+
+## Approval prompts
+
+approval-sign = Sign transaction with '{ $signer }':
+approval-sign-request = Signing request for { $signer }
+approval-learn-destinations = add { $count } new destination(s) to the address book
+
+## CLI confirmations
+
+confirm-delete = Are you sure you want to delete '{ $identifier }'? This cannot be undone. [y/N]
+confirm-cancelled = Cancelled.
+transfer-details = Transfer Details:
+transfer-from = From: { $label } ({ $address })
+transfer-to = To:   { $label } ({ $address })
+transfer-amount = Amount: { $amount } SOL ({ $lamports } lamports)
+transfer-balance = Current balance: { $balance } SOL
+confirm-transfer = Proceed with transfer? [y/N]
@@ -0,0 +1,40 @@
+# Spanish messages
+
+## Transaction summaries
+
+summary-programs = Programas: { $programs }
+summary-accounts = Cuentas:
+summary-flag-signer-writable = [firmante, escribible]
+summary-flag-signer = [firmante]
+summary-flag-writable = [escribible]
+summary-call = Llamada a { $program }
+summary-transfer-sol = Transferir { $amount } SOL a { $to }
+summary-create-account = Crear cuenta con { $amount } SOL
+summary-token-transfer = Transferencia de tokens: { $amount } unidades
+summary-token-mint = Acuñar tokens
+summary-token-burn = Quemar tokens
+summary-token-close = Cerrar cuenta de tokens
+summary-unparsed = No se pudieron leer los detalles de la transacción
+summary-lookup-accounts = También carga { $count } cuenta(s) desde tablas de búsqueda de direcciones
+summary-new-destinations = Destinos nuevos:
+summary-uncounted-outflow = Esta transacción llama a programas que podrían mover SOL más allá de lo que cuenta el límite de gasto
+summary-cluster-mismatch = Clúster incorrecto: { $signer } está vinculado a { $bound }, pero el blockhash es de { $found }
+summary-offchain-message = Mensaje fuera de cadena:
+summary-hash = Hash del resumen: { $hash }
+
+## Approval prompts
+
+approval-sign = Firmar transacción con '{ $signer }':
+approval-sign-request = Solicitud de firma para { $signer }
+approval-learn-destinations = añadir { $count } destino(s) nuevo(s) a la libreta de direcciones
+
+## CLI confirmations
+
+confirm-delete = ¿Seguro que quieres eliminar '{ $identifier }'? No se puede deshacer. [y/N]
+confirm-cancelled = Cancelado.
+transfer-details = Detalles de la transferencia:
+transfer-from = Desde: { $label } ({ $address })
+transfer-to = Hacia: { $label } ({ $address })
+transfer-amount = Cantidad: { $amount } SOL ({ $lamports } lamports)
+transfer-balance = Saldo actual: { $balance } SOL
+confirm-transfer = ¿Continuar con la transferencia? [y/N]
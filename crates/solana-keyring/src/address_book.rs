@@ -34,6 +34,21 @@ impl<'a> AddressBook<'a> {
         self.db.update_address_label(identifier, new_label)
     }
 
+    /// Tag an address
+    pub fn add_tag(&self, identifier: &str, tag: &str) -> Result<()> {
+        self.db.add_tag_to_address(identifier, tag)
+    }
+
+    /// Remove a tag from an address
+    pub fn remove_tag(&self, identifier: &str, tag: &str) -> Result<bool> {
+        self.db.remove_tag_from_address(identifier, tag)
+    }
+
+    /// Get the tags of an address
+    pub fn tags(&self, pubkey: &str) -> Result<Vec<String>> {
+        self.db.get_address_tags(pubkey)
+    }
+
     /// Resolve a label or pubkey to a pubkey
     pub fn resolve(&self, identifier: &str) -> Result<Option<String>> {
         let addresses = self.list()?;
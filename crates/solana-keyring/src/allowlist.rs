@@ -0,0 +1,165 @@
+//! Destination allowlist backed by the address book
+//!
+//! When enabled, transfers to destinations already in the address book (or
+//! carrying a specific tag, e.g. `verified`) can be approved without a prompt,
+//! while new destinations always require explicit confirmation. Only
+//! transactions made up entirely of transfers qualify; anything else in the
+//! transaction is always confirmed.
+
+use crate::db::Database;
+use crate::error::{Error, Result};
+
+/// Which destinations are trusted
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum DestinationPolicy {
+    /// No allowlist; every request is confirmed as usual
+    #[default]
+    Off,
+    /// Any address book entry is trusted
+    AddressBook,
+    /// Only address book entries with this tag are trusted
+    Tagged(String),
+}
+
+impl DestinationPolicy {
+    /// Whether the allowlist is enabled
+    pub fn is_enabled(&self) -> bool {
+        !matches!(self, DestinationPolicy::Off)
+    }
+}
+
+impl std::fmt::Display for DestinationPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DestinationPolicy::Off => write!(f, "off"),
+            DestinationPolicy::AddressBook => write!(f, "address-book"),
+            DestinationPolicy::Tagged(tag) => write!(f, "tag:{}", tag),
+        }
+    }
+}
+
+impl std::str::FromStr for DestinationPolicy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "off" => Ok(DestinationPolicy::Off),
+            "address-book" => Ok(DestinationPolicy::AddressBook),
+            _ => match s.strip_prefix("tag:") {
+                Some(tag) if !tag.is_empty() => Ok(DestinationPolicy::Tagged(tag.to_string())),
+                _ => Err(Error::Policy(format!(
+                    "Unknown destination policy: {} (use off, address-book or tag:<name>)",
+                    s
+                ))),
+            },
+        }
+    }
+}
+
+/// Destination allowlist operations
+pub struct Allowlist<'a> {
+    db: &'a Database,
+    policy: &'a DestinationPolicy,
+}
+
+impl<'a> Allowlist<'a> {
+    /// Create a new allowlist handle
+    pub fn new(db: &'a Database, policy: &'a DestinationPolicy) -> Self {
+        Self { db, policy }
+    }
+
+    /// Whether a destination is trusted under the policy
+    pub fn is_trusted(&self, pubkey: &str) -> Result<bool> {
+        match self.policy {
+            DestinationPolicy::Off => Ok(false),
+            DestinationPolicy::AddressBook => {
+                Ok(self.db.list_addresses()?.iter().any(|a| a.pubkey == pubkey))
+            }
+            DestinationPolicy::Tagged(tag) => Ok(self.db.get_address_tags(pubkey)?.contains(tag)),
+        }
+    }
+
+    /// Destinations that are not trusted under the policy
+    pub fn untrusted(&self, destinations: &[String]) -> Result<Vec<String>> {
+        let mut untrusted = Vec::new();
+        for dest in destinations {
+            if !self.is_trusted(dest)? {
+                untrusted.push(dest.clone());
+            }
+        }
+        Ok(untrusted)
+    }
+
+    /// Add a destination to the address book with a generated label
+    ///
+    /// Does nothing if the address is already in the address book.
+    pub fn learn(&self, pubkey: &str) -> Result<()> {
+        if self.db.list_addresses()?.iter().any(|a| a.pubkey == pubkey) {
+            return Ok(());
+        }
+
+        let label = format!("learned-{}", &pubkey[..pubkey.len().min(8)]);
+        self.db
+            .add_address(pubkey, &label, Some("Added after a confirmed transfer"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_policy() {
+        assert_eq!(
+            "off".parse::<DestinationPolicy>().unwrap(),
+            DestinationPolicy::Off
+        );
+        assert_eq!(
+            "address-book".parse::<DestinationPolicy>().unwrap(),
+            DestinationPolicy::AddressBook
+        );
+        assert_eq!(
+            "tag:verified".parse::<DestinationPolicy>().unwrap(),
+            DestinationPolicy::Tagged("verified".into())
+        );
+        assert!("tag:".parse::<DestinationPolicy>().is_err());
+        assert!("everything".parse::<DestinationPolicy>().is_err());
+    }
+
+    #[test]
+    fn test_untrusted_destinations() {
+        let db = Database::open_in_memory().unwrap();
+        db.add_address("known", "Known", None).unwrap();
+        db.add_address("verified", "Verified", None).unwrap();
+        db.add_tag_to_address("verified", "verified").unwrap();
+
+        let dests = vec!["known".to_string(), "verified".into(), "new".into()];
+
+        let policy = DestinationPolicy::AddressBook;
+        assert_eq!(
+            Allowlist::new(&db, &policy).untrusted(&dests).unwrap(),
+            vec!["new".to_string()]
+        );
+
+        let policy = DestinationPolicy::Tagged("verified".into());
+        assert_eq!(
+            Allowlist::new(&db, &policy).untrusted(&dests).unwrap(),
+            vec!["known".to_string(), "new".into()]
+        );
+    }
+
+    #[test]
+    fn test_learn() {
+        let db = Database::open_in_memory().unwrap();
+        let policy = DestinationPolicy::AddressBook;
+        let allowlist = Allowlist::new(&db, &policy);
+
+        allowlist.learn("NewDestination111").unwrap();
+        allowlist.learn("NewDestination111").unwrap();
+
+        assert!(allowlist.is_trusted("NewDestination111").unwrap());
+        let addresses = db.list_addresses().unwrap();
+        assert_eq!(addresses.len(), 1);
+        assert_eq!(addresses[0].label, "learned-NewDesti");
+    }
+}
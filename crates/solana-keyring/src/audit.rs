@@ -0,0 +1,512 @@
+//! Audit log of security-relevant events
+//!
+//! Each event is chained to the one before it with a SHA-256 hash, so
+//! editing or removing an event breaks the chain at that point. Hashes need
+//! no secret, so anything can record an event, but anyone able to write the
+//! database could also recompute them. [`AuditLog::seal`] closes that gap: it
+//! stores an HMAC of the newest event's hash under a key derived from the
+//! master passphrase, in a table of its own. [`AuditLog::verify`] checks the
+//! chain and the seal, so an exported log carries evidence of tampering up to
+//! the last seal. Pruning removes the oldest events and keeps the hash of the
+//! last one removed as the chain's new anchor.
+
+use std::io::Write;
+
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::crypto::{DerivedKey, ct_eq_str};
+use crate::db::{AuditEventRow, AuditSealRow, Database};
+use crate::error::{Error, Result};
+use crate::spending::unix_now;
+
+/// Setting holding the chain hash of the last pruned event
+pub const AUDIT_ANCHOR_SETTING: &str = "audit_chain_anchor";
+
+/// Result of checking an event against the chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditStatus {
+    /// Linked to the event before it and covered by the seal
+    Valid,
+    /// Linked to the event before it, but recorded after the last seal
+    Unsealed,
+    /// Recorded before chaining; nothing to check
+    Unchained,
+    /// The event, or one before it, was changed or removed
+    Invalid,
+}
+
+/// An event with its chain verification result
+#[derive(Debug, Clone, Serialize)]
+pub struct VerifiedAuditEvent {
+    /// The event
+    #[serde(flatten)]
+    pub event: AuditEventRow,
+    /// Whether the event checks out
+    pub status: AuditStatus,
+}
+
+/// Key sealing the audit log, derived from the master passphrase
+pub struct AuditSealKey {
+    salt: [u8; 32],
+    key: DerivedKey,
+}
+
+impl AuditSealKey {
+    /// Derive a key under a fresh salt
+    ///
+    /// Argon2id makes this slow, and it needs no database, so a caller
+    /// sharing a connection can derive the key without holding it. The
+    /// passphrase is not checked.
+    pub fn derive(passphrase: &[u8]) -> Result<Self> {
+        let mut salt = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut salt);
+        Ok(Self {
+            salt,
+            key: DerivedKey::derive(passphrase, &salt)?,
+        })
+    }
+}
+
+/// Audit log operations
+pub struct AuditLog<'a> {
+    db: &'a Database,
+}
+
+impl<'a> AuditLog<'a> {
+    /// Create a new audit log handle
+    pub fn new(db: &'a Database) -> Self {
+        Self { db }
+    }
+
+    /// Append an event with JSON details
+    pub fn record(
+        &self,
+        event: &str,
+        pubkey: Option<&str>,
+        details: &impl Serialize,
+    ) -> Result<i64> {
+        let details = serde_json::to_string(details)?;
+        let created_at = unix_now();
+
+        self.db.immediate(|db| {
+            let previous = match db.last_audit_event()? {
+                Some((_, previous)) => previous,
+                None => db.get_setting(AUDIT_ANCHOR_SETTING)?,
+            };
+
+            let id = db.append_audit_event(event, pubkey, &details, created_at)?;
+            let hash = chain_hash(previous.as_deref(), id, event, pubkey, &details, created_at);
+            db.set_audit_hash(id, &hash)?;
+            Ok(id)
+        })
+    }
+
+    /// List events, optionally filtered by event name
+    pub fn list(&self, event: Option<&str>) -> Result<Vec<AuditEventRow>> {
+        self.db.list_audit_events(event)
+    }
+
+    /// Seal the chain up to the newest event under the master passphrase
+    ///
+    /// Returns the sealed event's ID, or `None` if there is nothing to seal.
+    pub fn seal(&self, passphrase: &[u8]) -> Result<Option<i64>> {
+        if !self.db.verify_passphrase(passphrase)? {
+            return Err(Error::InvalidPassphrase);
+        }
+        self.seal_with(&AuditSealKey::derive(passphrase)?)
+    }
+
+    /// Seal the chain with a key derived from an already verified passphrase
+    pub fn seal_with(&self, key: &AuditSealKey) -> Result<Option<i64>> {
+        self.db.immediate(|db| {
+            let Some((event_id, Some(hash))) = db.last_audit_event()? else {
+                return Ok(None);
+            };
+            db.set_audit_seal(&AuditSealRow {
+                event_id,
+                salt: key.salt,
+                mac: seal_mac(key.key.as_bytes(), event_id, &hash),
+                sealed_at: unix_now(),
+            })?;
+            Ok(Some(event_id))
+        })
+    }
+
+    /// Check every event against the chain and the seal, oldest first
+    pub fn verify(&self, passphrase: &[u8]) -> Result<Vec<VerifiedAuditEvent>> {
+        if !self.db.verify_passphrase(passphrase)? {
+            return Err(Error::InvalidPassphrase);
+        }
+
+        let events = self.db.list_audit_events(None)?;
+
+        // A seal older than every event was pruned with them
+        let seal = match self.db.audit_seal()? {
+            Some(seal) if events.first().is_some_and(|e| seal.event_id >= e.id) => {
+                let key = DerivedKey::derive(passphrase, &seal.salt)?;
+                let sealed = events
+                    .iter()
+                    .find(|e| e.id == seal.event_id)
+                    .and_then(|e| e.hash.as_deref())
+                    .is_some_and(|hash| {
+                        ct_eq_str(&seal.mac, &seal_mac(key.as_bytes(), seal.event_id, hash))
+                    });
+                Some((seal.event_id, sealed))
+            }
+            _ => None,
+        };
+
+        let mut previous = self.db.get_setting(AUDIT_ANCHOR_SETTING)?;
+        let mut chained = false;
+        Ok(events
+            .into_iter()
+            .map(|event| {
+                let status = match &event.hash {
+                    // A gap after chaining started means a hash was removed
+                    None if chained => AuditStatus::Invalid,
+                    None => AuditStatus::Unchained,
+                    Some(hash) => {
+                        let expected = chain_hash(
+                            previous.as_deref(),
+                            event.id,
+                            &event.event,
+                            event.pubkey.as_deref(),
+                            &event.details,
+                            event.created_at,
+                        );
+                        match seal {
+                            _ if *hash != expected => AuditStatus::Invalid,
+                            Some((sealed_id, true)) if event.id <= sealed_id => AuditStatus::Valid,
+                            Some((sealed_id, false)) if event.id <= sealed_id => {
+                                AuditStatus::Invalid
+                            }
+                            _ => AuditStatus::Unsealed,
+                        }
+                    }
+                };
+                chained |= event.hash.is_some();
+                previous = event.hash.clone();
+                VerifiedAuditEvent { event, status }
+            })
+            .collect())
+    }
+
+    /// Verified events recorded at or after `since`, for export
+    ///
+    /// The whole chain is checked, so an edit before `since` still shows on
+    /// the first exported event after it.
+    pub fn export(&self, passphrase: &[u8], since: Option<i64>) -> Result<Vec<VerifiedAuditEvent>> {
+        let mut events = self.verify(passphrase)?;
+        if let Some(since) = since {
+            events.retain(|e| e.event.created_at >= since);
+        }
+        Ok(events)
+    }
+
+    /// Delete events recorded before `before`, keeping the chain verifiable
+    ///
+    /// Events are removed oldest first up to the newest one before `before`,
+    /// whose hash becomes the anchor for the event after it.
+    pub fn prune(&self, before: i64) -> Result<usize> {
+        self.db.immediate(|db| {
+            let Some((id, hash)) = db.last_audit_event_before(before)? else {
+                return Ok(0);
+            };
+            match hash {
+                Some(hash) => db.set_setting(AUDIT_ANCHOR_SETTING, &hash)?,
+                None => {
+                    db.delete_setting(AUDIT_ANCHOR_SETTING)?;
+                }
+            }
+            db.delete_audit_events_through(id)
+        })
+    }
+}
+
+/// Hash of an event and the hash of the event before it
+fn chain_hash(
+    previous: Option<&str>,
+    id: i64,
+    event: &str,
+    pubkey: Option<&str>,
+    details: &str,
+    created_at: i64,
+) -> String {
+    // JSON framing keeps field boundaries unambiguous
+    let message = serde_json::json!([previous, id, event, pubkey, details, created_at]);
+    hex::encode(Sha256::digest(message.to_string().as_bytes()))
+}
+
+/// HMAC sealing the chain up to an event
+fn seal_mac(key: &[u8], event_id: i64, hash: &str) -> String {
+    let message = serde_json::json!([event_id, hash]);
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(message.to_string().as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Write events as JSON Lines, one object per event
+pub fn write_jsonl(events: &[VerifiedAuditEvent], out: &mut impl Write) -> Result<()> {
+    for event in events {
+        serde_json::to_writer(&mut *out, event)?;
+        out.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+/// Write events as CSV with a header row
+pub fn write_csv(events: &[VerifiedAuditEvent], out: &mut impl Write) -> Result<()> {
+    writeln!(out, "id,event,pubkey,details,created_at,hash,status")?;
+    for VerifiedAuditEvent { event, status } in events {
+        let status = match status {
+            AuditStatus::Valid => "valid",
+            AuditStatus::Unsealed => "unsealed",
+            AuditStatus::Unchained => "unchained",
+            AuditStatus::Invalid => "invalid",
+        };
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{}",
+            event.id,
+            csv_field(&event.event),
+            csv_field(event.pubkey.as_deref().unwrap_or("")),
+            csv_field(&event.details),
+            event.created_at,
+            event.hash.as_deref().unwrap_or(""),
+            status
+        )?;
+    }
+    Ok(())
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PASSPHRASE: &[u8] = b"test passphrase";
+
+    fn initialized(db: Database) -> Database {
+        db.initialize(PASSPHRASE).unwrap();
+        db
+    }
+
+    fn statuses(log: &AuditLog) -> Vec<AuditStatus> {
+        log.verify(PASSPHRASE)
+            .unwrap()
+            .iter()
+            .map(|e| e.status)
+            .collect()
+    }
+
+    fn record_events(log: &AuditLog, count: usize) {
+        for i in 0..count {
+            log.record("test", None, &serde_json::json!({ "i": i }))
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_chain_verifies() {
+        let db = initialized(Database::open_in_memory().unwrap());
+        let log = AuditLog::new(&db);
+        record_events(&log, 3);
+        assert_eq!(statuses(&log), vec![AuditStatus::Unsealed; 3]);
+
+        assert_eq!(log.seal(PASSPHRASE).unwrap(), Some(3));
+        record_events(&log, 1);
+        assert_eq!(
+            statuses(&log),
+            vec![
+                AuditStatus::Valid,
+                AuditStatus::Valid,
+                AuditStatus::Valid,
+                AuditStatus::Unsealed
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wrong_passphrase_is_refused() {
+        let db = initialized(Database::open_in_memory().unwrap());
+        let log = AuditLog::new(&db);
+        record_events(&log, 1);
+
+        assert!(matches!(log.seal(b"wrong"), Err(Error::InvalidPassphrase)));
+        assert!(matches!(
+            log.verify(b"wrong"),
+            Err(Error::InvalidPassphrase)
+        ));
+    }
+
+    #[test]
+    fn test_detects_tampering() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keyring.db");
+        let db = initialized(Database::open(&path).unwrap());
+        let log = AuditLog::new(&db);
+        record_events(&log, 4);
+        log.seal(PASSPHRASE).unwrap();
+
+        // Edit one event and remove another behind the keyring's back
+        let conn = rusqlite::Connection::open(&path).unwrap();
+        conn.execute(
+            "UPDATE audit_log SET details = '{\"i\":99}' WHERE id = 2",
+            [],
+        )
+        .unwrap();
+        conn.execute("DELETE FROM audit_log WHERE id = 3", [])
+            .unwrap();
+
+        assert_eq!(
+            statuses(&log),
+            vec![
+                AuditStatus::Valid,
+                AuditStatus::Invalid,
+                AuditStatus::Invalid
+            ]
+        );
+    }
+
+    #[test]
+    fn test_detects_rewritten_chain() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keyring.db");
+        let db = initialized(Database::open(&path).unwrap());
+        let log = AuditLog::new(&db);
+        record_events(&log, 3);
+        log.seal(PASSPHRASE).unwrap();
+
+        // Edit an event and recompute every hash after it, which needs no
+        // secret, then reseal under a guessed passphrase
+        let conn = rusqlite::Connection::open(&path).unwrap();
+        conn.execute(
+            "UPDATE audit_log SET details = '{\"i\":99}' WHERE id = 2",
+            [],
+        )
+        .unwrap();
+        let mut previous = None;
+        for event in db.list_audit_events(None).unwrap() {
+            let hash = chain_hash(
+                previous.as_deref(),
+                event.id,
+                &event.event,
+                event.pubkey.as_deref(),
+                &event.details,
+                event.created_at,
+            );
+            conn.execute(
+                "UPDATE audit_chain SET hash = ?2 WHERE event_id = ?1",
+                rusqlite::params![event.id, hash],
+            )
+            .unwrap();
+            previous = Some(hash);
+        }
+        let salt = [7u8; 32];
+        let key = DerivedKey::derive(b"guess", &salt).unwrap();
+        conn.execute(
+            "UPDATE audit_seal SET salt = ?1, mac = ?2",
+            rusqlite::params![
+                salt.as_slice(),
+                seal_mac(key.as_bytes(), 3, previous.as_deref().unwrap())
+            ],
+        )
+        .unwrap();
+        assert_eq!(statuses(&log), vec![AuditStatus::Invalid; 3]);
+
+        // Removing the seal leaves nothing vouching for the events
+        conn.execute("DELETE FROM audit_seal", []).unwrap();
+        assert_eq!(statuses(&log), vec![AuditStatus::Unsealed; 3]);
+    }
+
+    #[test]
+    fn test_detects_moved_anchor() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keyring.db");
+        let db = initialized(Database::open(&path).unwrap());
+        let log = AuditLog::new(&db);
+        record_events(&log, 2);
+        log.seal(PASSPHRASE).unwrap();
+
+        // Pretend older events were pruned
+        let conn = rusqlite::Connection::open(&path).unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
+            rusqlite::params![AUDIT_ANCHOR_SETTING, "00".repeat(32)],
+        )
+        .unwrap();
+        assert_eq!(
+            statuses(&log),
+            vec![AuditStatus::Invalid, AuditStatus::Valid]
+        );
+    }
+
+    #[test]
+    fn test_prune_keeps_chain_valid() {
+        let db = initialized(Database::open_in_memory().unwrap());
+        // Recorded before chaining
+        db.append_audit_event("legacy", None, "{}", 0).unwrap();
+        let log = AuditLog::new(&db);
+        record_events(&log, 2);
+        log.seal(PASSPHRASE).unwrap();
+        assert_eq!(
+            statuses(&log),
+            vec![
+                AuditStatus::Unchained,
+                AuditStatus::Valid,
+                AuditStatus::Valid
+            ]
+        );
+
+        assert_eq!(log.prune(1).unwrap(), 1);
+        assert_eq!(statuses(&log), vec![AuditStatus::Valid; 2]);
+
+        // The last pruned event's hash anchors the next one
+        assert_eq!(log.prune(unix_now() + 1).unwrap(), 2);
+        assert!(log.list(None).unwrap().is_empty());
+        record_events(&log, 1);
+        assert_eq!(statuses(&log), vec![AuditStatus::Unsealed]);
+        log.seal(PASSPHRASE).unwrap();
+        assert_eq!(statuses(&log), vec![AuditStatus::Valid]);
+    }
+
+    #[test]
+    fn test_export_formats() {
+        let db = initialized(Database::open_in_memory().unwrap());
+        db.append_audit_event("old", None, "{}", 0).unwrap();
+        let log = AuditLog::new(&db);
+        log.record("sign", Some("abc"), &serde_json::json!({ "memo": "a,b" }))
+            .unwrap();
+        log.seal(PASSPHRASE).unwrap();
+
+        let events = log.export(PASSPHRASE, Some(1)).unwrap();
+        assert_eq!(events.len(), 1);
+
+        let mut jsonl = Vec::new();
+        write_jsonl(&events, &mut jsonl).unwrap();
+        let line: serde_json::Value = serde_json::from_slice(&jsonl).unwrap();
+        assert_eq!(line["event"], "sign");
+        assert_eq!(line["status"], "valid");
+        assert_eq!(line["hash"], events[0].event.hash.clone().unwrap());
+
+        let mut csv = Vec::new();
+        write_csv(&events, &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "id,event,pubkey,details,created_at,hash,status");
+        assert!(lines[1].starts_with("2,sign,abc,\"{\"\"memo\"\":\"\"a,b\"\"}\","));
+        assert!(lines[1].ends_with(",valid"));
+    }
+}
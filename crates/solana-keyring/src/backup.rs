@@ -0,0 +1,274 @@
+//! Backup verification
+//!
+//! A backup is a copy of the keyring database. Verification decrypts every
+//! keypair row in memory, checks each secret derives the stored public key, and
+//! compares the backup against the live keyring to find keys that exist only in
+//! the live database.
+//!
+//! A verification that finds the backup complete is recorded in the live
+//! keyring, so keys created since then can be flagged as never backed up.
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use crate::db::Database;
+use crate::error::{Error, Result};
+
+/// Setting holding the unix timestamp of the last complete backup
+pub const LAST_BACKUP_SETTING: &str = "last_backup_at";
+
+/// Result of checking a single keypair row in a backup
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyStatus {
+    /// The secret decrypts and matches the public key
+    Ok,
+    /// The secret could not be decrypted
+    DecryptionFailed(String),
+    /// The secret decrypts to a different public key
+    PubkeyMismatch {
+        /// Public key derived from the decrypted secret
+        derived: String,
+    },
+}
+
+/// A keypair row checked in a backup
+#[derive(Debug, Clone)]
+pub struct KeyCheck {
+    /// Stored public key
+    pub pubkey: String,
+    /// Stored label
+    pub label: String,
+    /// Check result
+    pub status: KeyStatus,
+}
+
+/// A live keypair that is not recoverable from the backup
+#[derive(Debug, Clone)]
+pub struct UnprotectedKey {
+    /// Public key
+    pub pubkey: String,
+    /// Label in the live keyring
+    pub label: String,
+}
+
+/// Backup verification results
+#[derive(Debug, Clone, Default)]
+pub struct BackupReport {
+    /// Every keypair row in the backup
+    pub keys: Vec<KeyCheck>,
+    /// Live keys that would be lost if the live database disappeared
+    pub unprotected: Vec<UnprotectedKey>,
+    /// Keys in the backup that are no longer in the live keyring
+    pub only_in_backup: Vec<String>,
+}
+
+impl BackupReport {
+    /// Rows that failed to decrypt or verify
+    pub fn failures(&self) -> impl Iterator<Item = &KeyCheck> {
+        self.keys.iter().filter(|k| k.status != KeyStatus::Ok)
+    }
+
+    /// Whether every row verified and every live key is recoverable
+    pub fn is_complete(&self) -> bool {
+        self.failures().next().is_none() && self.unprotected.is_empty()
+    }
+}
+
+/// Verify a backup, optionally comparing it against the live keyring
+pub fn verify_backup(
+    backup: &Database,
+    passphrase: &[u8],
+    live: Option<&Database>,
+) -> Result<BackupReport> {
+    if !backup.verify_passphrase(passphrase)? {
+        return Err(Error::InvalidPassphrase);
+    }
+
+    let mut report = BackupReport::default();
+    for row in backup.list_keypairs(None)? {
+        let status = match backup.load_keypair(&row.pubkey, passphrase) {
+            Ok(keypair) if keypair.pubkey_base58() == row.pubkey => KeyStatus::Ok,
+            Ok(keypair) => KeyStatus::PubkeyMismatch {
+                derived: keypair.pubkey_base58(),
+            },
+            Err(e) => KeyStatus::DecryptionFailed(e.to_string()),
+        };
+        report.keys.push(KeyCheck {
+            pubkey: row.pubkey,
+            label: row.label,
+            status,
+        });
+    }
+
+    if let Some(live) = live {
+        let recoverable: HashSet<&str> = report
+            .keys
+            .iter()
+            .filter(|k| k.status == KeyStatus::Ok)
+            .map(|k| k.pubkey.as_str())
+            .collect();
+
+        let live_keys = live.list_keypairs(None)?;
+        let live_pubkeys: HashSet<&str> = live_keys.iter().map(|k| k.pubkey.as_str()).collect();
+
+        report.unprotected = live_keys
+            .iter()
+            .filter(|k| !recoverable.contains(k.pubkey.as_str()))
+            .map(|k| UnprotectedKey {
+                pubkey: k.pubkey.clone(),
+                label: k.label.clone(),
+            })
+            .collect();
+        report.only_in_backup = report
+            .keys
+            .iter()
+            .filter(|k| !live_pubkeys.contains(k.pubkey.as_str()))
+            .map(|k| k.pubkey.clone())
+            .collect();
+    }
+
+    Ok(report)
+}
+
+/// How well the last complete backup covers the live keyring
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct BackupStatus {
+    /// Unix timestamp of the last backup verified complete
+    pub last_backup_at: Option<i64>,
+    /// Keypairs created since then, which no known backup holds
+    pub unbacked_keys: usize,
+}
+
+impl BackupStatus {
+    /// Whether some keys were never backed up
+    pub fn is_stale(&self) -> bool {
+        self.unbacked_keys > 0
+    }
+
+    /// A reminder to back up, if some keys were never backed up
+    pub fn reminder(&self) -> Option<String> {
+        if !self.is_stale() {
+            return None;
+        }
+        let since = match self.last_backup_at {
+            Some(_) => "since the last verified backup",
+            None => "and no backup has been verified",
+        };
+        Some(format!(
+            "{} key(s) created {}; copy the keyring and run 'solana-keyring backup verify'",
+            self.unbacked_keys, since
+        ))
+    }
+}
+
+/// Record a backup verified complete at `at`
+pub fn record_backup(live: &Database, at: i64) -> Result<()> {
+    live.set_setting(LAST_BACKUP_SETTING, &at.to_string())
+}
+
+/// Backup status of the live keyring
+pub fn backup_status(live: &Database) -> Result<BackupStatus> {
+    let last_backup_at = live
+        .get_setting(LAST_BACKUP_SETTING)?
+        .and_then(|at| at.parse().ok());
+    let unbacked_keys = live.count_keypairs_created_after(last_backup_at.unwrap_or(i64::MIN))?;
+    Ok(BackupStatus {
+        last_backup_at,
+        unbacked_keys,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keypair::SecureKeypair;
+
+    const PASSPHRASE: &[u8] = b"correct horse";
+
+    fn keyring() -> Database {
+        let db = Database::open_in_memory().unwrap();
+        db.initialize(PASSPHRASE).unwrap();
+        db
+    }
+
+    #[test]
+    fn test_complete_backup() {
+        let backup = keyring();
+        let live = keyring();
+        let keypair = SecureKeypair::generate();
+        backup
+            .store_keypair(&keypair, "a", PASSPHRASE, &[])
+            .unwrap();
+        live.store_keypair(&keypair, "a", PASSPHRASE, &[]).unwrap();
+
+        let report = verify_backup(&backup, PASSPHRASE, Some(&live)).unwrap();
+        assert_eq!(report.keys.len(), 1);
+        assert!(report.is_complete());
+        assert!(report.only_in_backup.is_empty());
+    }
+
+    #[test]
+    fn test_unprotected_and_stale_keys() {
+        let backup = keyring();
+        let live = keyring();
+        let old = SecureKeypair::generate();
+        let new = SecureKeypair::generate();
+        backup.store_keypair(&old, "old", PASSPHRASE, &[]).unwrap();
+        live.store_keypair(&new, "new", PASSPHRASE, &[]).unwrap();
+
+        let report = verify_backup(&backup, PASSPHRASE, Some(&live)).unwrap();
+        assert!(!report.is_complete());
+        assert_eq!(report.unprotected.len(), 1);
+        assert_eq!(report.unprotected[0].label, "new");
+        assert_eq!(report.only_in_backup, vec![old.pubkey_base58()]);
+    }
+
+    #[test]
+    fn test_backup_status() {
+        let live = keyring();
+        live.store_keypair(&SecureKeypair::generate(), "a", PASSPHRASE, &[])
+            .unwrap();
+
+        let status = backup_status(&live).unwrap();
+        assert_eq!(status.last_backup_at, None);
+        assert_eq!(status.unbacked_keys, 1);
+        assert!(status.reminder().is_some());
+
+        let now = crate::spending::unix_now();
+        record_backup(&live, now).unwrap();
+        let status = backup_status(&live).unwrap();
+        assert_eq!(status.last_backup_at, Some(now));
+        assert!(!status.is_stale());
+        assert!(status.reminder().is_none());
+
+        // A key created after the backup was never backed up
+        record_backup(&live, now - 60).unwrap();
+        assert_eq!(backup_status(&live).unwrap().unbacked_keys, 1);
+    }
+
+    #[test]
+    fn test_row_encrypted_with_other_passphrase() {
+        let backup = keyring();
+        let keypair = SecureKeypair::generate();
+        backup
+            .store_keypair(&keypair, "a", b"other passphrase", &[])
+            .unwrap();
+
+        let report = verify_backup(&backup, PASSPHRASE, None).unwrap();
+        assert!(matches!(
+            report.keys[0].status,
+            KeyStatus::DecryptionFailed(_)
+        ));
+        assert!(!report.is_complete());
+    }
+
+    #[test]
+    fn test_wrong_passphrase() {
+        let backup = keyring();
+        assert!(matches!(
+            verify_backup(&backup, b"wrong", None),
+            Err(Error::InvalidPassphrase)
+        ));
+    }
+}
@@ -5,7 +5,7 @@
 
 pub use solana_keyring_biometric::{
     AuthResult, BiometricConfig, Error as BiometricError, authenticate, authenticate_with_config,
-    confirm_signing, confirm_signing_with_config, is_available, is_passcode_available,
+    confirm_signing, confirm_signing_with_config, is_available, is_passcode_available, prompt_text,
 };
 
 use crate::error::{Error, Result};
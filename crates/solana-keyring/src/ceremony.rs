@@ -0,0 +1,248 @@
+//! Key ceremony support
+//!
+//! A ceremony creates a high-value key from mixed entropy, backs it up as a
+//! seed phrase and Shamir shares, and produces a report signed by the new key
+//! that is stored in the audit log.
+
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+use crate::audit::AuditLog;
+use crate::db::Database;
+use crate::error::{Error, Result};
+use crate::keypair::SecureKeypair;
+
+/// Audit log event name for ceremony reports
+pub const CEREMONY_EVENT: &str = "ceremony";
+
+/// Mixes entropy from several sources into a 32-byte seed
+///
+/// Always starts with OS randomness, so additional sources can only add
+/// entropy, never weaken it.
+pub struct EntropyPool {
+    hasher: Sha256,
+    sources: Vec<String>,
+}
+
+impl EntropyPool {
+    /// Create a pool seeded with OS randomness
+    pub fn new() -> Self {
+        let mut os = Zeroizing::new([0u8; 32]);
+        rand::rngs::OsRng.fill_bytes(&mut *os);
+
+        let mut pool = Self {
+            hasher: Sha256::new(),
+            sources: Vec::new(),
+        };
+        pool.add("os", &*os);
+        pool
+    }
+
+    /// Mix in bytes from a named source
+    pub fn add(&mut self, source: &str, bytes: &[u8]) {
+        // Length-prefix each input so sources cannot be confused
+        self.hasher.update((source.len() as u64).to_le_bytes());
+        self.hasher.update(source.as_bytes());
+        self.hasher.update((bytes.len() as u64).to_le_bytes());
+        self.hasher.update(bytes);
+        self.sources.push(source.to_string());
+    }
+
+    /// Names of the sources mixed in so far
+    pub fn sources(&self) -> &[String] {
+        &self.sources
+    }
+
+    /// Finish mixing and return 32 bytes of entropy
+    pub fn finish(self) -> Zeroizing<[u8; 32]> {
+        Zeroizing::new(self.hasher.finalize().into())
+    }
+}
+
+impl Default for EntropyPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parse dice rolls ("1"-"6", whitespace ignored) into bytes
+pub fn parse_dice(rolls: &str) -> Result<Vec<u8>> {
+    rolls
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match c {
+            '1'..='6' => Ok(c as u8 - b'0'),
+            _ => Err(Error::Ceremony(format!("Invalid dice roll: {}", c))),
+        })
+        .collect()
+}
+
+/// Pick `count` distinct random word positions (0-based, ascending) for a backup quiz
+pub fn quiz_positions(word_count: usize, count: usize) -> Vec<usize> {
+    let mut positions =
+        rand::seq::index::sample(&mut rand::thread_rng(), word_count, count.min(word_count))
+            .into_vec();
+    positions.sort_unstable();
+    positions
+}
+
+/// Shamir backup parameters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShareScheme {
+    /// Shares required to recover
+    pub threshold: u8,
+    /// Shares created
+    pub shares: u8,
+}
+
+/// What happened during a ceremony
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CeremonyReport {
+    /// Public key of the new key
+    pub pubkey: String,
+    /// Label of the new key
+    pub label: String,
+    /// Unix timestamp of the ceremony
+    pub created_at: i64,
+    /// Entropy sources mixed into the key
+    pub entropy_sources: Vec<String>,
+    /// Whether the seed phrase backup quiz was passed
+    pub mnemonic_verified: bool,
+    /// Shamir backup, if shares were printed
+    pub shamir: Option<ShareScheme>,
+    /// Keyring databases the key was registered in as watch-only
+    pub watch_only: Vec<String>,
+}
+
+impl CeremonyReport {
+    /// Sign the report with the ceremony key
+    pub fn sign(self, keypair: &SecureKeypair) -> Result<SignedCeremonyReport> {
+        if keypair.pubkey_base58() != self.pubkey {
+            return Err(Error::Ceremony(
+                "Report must be signed by the ceremony key".into(),
+            ));
+        }
+        let signature = keypair.sign(&serde_json::to_vec(&self)?);
+        Ok(SignedCeremonyReport {
+            report: self,
+            signature: bs58::encode(signature).into_string(),
+        })
+    }
+}
+
+/// A ceremony report signed by the key it created
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedCeremonyReport {
+    /// The report
+    pub report: CeremonyReport,
+    /// Base58 ed25519 signature over the JSON-encoded report
+    pub signature: String,
+}
+
+impl SignedCeremonyReport {
+    /// Verify the signature against the report's public key
+    pub fn verify(&self) -> Result<bool> {
+        let pubkey: [u8; 32] = bs58::decode(&self.report.pubkey)
+            .into_vec()?
+            .try_into()
+            .map_err(|_| Error::Ceremony("Public key must be 32 bytes".into()))?;
+        let signature: [u8; 64] = bs58::decode(&self.signature)
+            .into_vec()?
+            .try_into()
+            .map_err(|_| Error::Ceremony("Signature must be 64 bytes".into()))?;
+
+        let key = VerifyingKey::from_bytes(&pubkey)?;
+        Ok(key
+            .verify(
+                &serde_json::to_vec(&self.report)?,
+                &Signature::from_bytes(&signature),
+            )
+            .is_ok())
+    }
+
+    /// Store the report in the audit log
+    pub fn store(&self, db: &Database) -> Result<i64> {
+        AuditLog::new(db).record(CEREMONY_EVENT, Some(&self.report.pubkey), self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(pubkey: String) -> CeremonyReport {
+        CeremonyReport {
+            pubkey,
+            label: "cold".into(),
+            created_at: 1_700_000_000,
+            entropy_sources: vec!["os".into(), "keyboard".into()],
+            mnemonic_verified: true,
+            shamir: Some(ShareScheme {
+                threshold: 2,
+                shares: 3,
+            }),
+            watch_only: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_entropy_pool() {
+        let mut a = EntropyPool::new();
+        a.add("keyboard", b"asdf");
+        assert_eq!(a.sources(), ["os", "keyboard"]);
+
+        // OS randomness makes every pool unique
+        let mut b = EntropyPool::new();
+        b.add("keyboard", b"asdf");
+        assert_ne!(*a.finish(), *b.finish());
+    }
+
+    #[test]
+    fn test_parse_dice() {
+        assert_eq!(parse_dice("1 6\n3").unwrap(), vec![1, 6, 3]);
+        assert!(parse_dice("17").is_err());
+    }
+
+    #[test]
+    fn test_quiz_positions() {
+        let positions = quiz_positions(24, 3);
+        assert_eq!(positions.len(), 3);
+        assert!(positions.windows(2).all(|w| w[0] < w[1]));
+        assert!(positions.iter().all(|&p| p < 24));
+        assert_eq!(quiz_positions(2, 3).len(), 2);
+    }
+
+    #[test]
+    fn test_signed_report() {
+        let keypair = SecureKeypair::generate();
+        let signed = report(keypair.pubkey_base58()).sign(&keypair).unwrap();
+        assert!(signed.verify().unwrap());
+
+        let mut tampered = signed.clone();
+        tampered.report.mnemonic_verified = false;
+        assert!(!tampered.verify().unwrap());
+
+        let other = SecureKeypair::generate();
+        assert!(report(keypair.pubkey_base58()).sign(&other).is_err());
+    }
+
+    #[test]
+    fn test_store_report() {
+        let db = Database::open_in_memory().unwrap();
+        let keypair = SecureKeypair::generate();
+        let signed = report(keypair.pubkey_base58()).sign(&keypair).unwrap();
+        signed.store(&db).unwrap();
+
+        let events = AuditLog::new(&db).list(Some(CEREMONY_EVENT)).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            events[0].pubkey.as_deref(),
+            Some(signed.report.pubkey.as_str())
+        );
+        let stored: SignedCeremonyReport = serde_json::from_str(&events[0].details).unwrap();
+        assert!(stored.verify().unwrap());
+    }
+}
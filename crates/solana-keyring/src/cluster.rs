@@ -0,0 +1,404 @@
+//! Cluster bindings for signers
+//!
+//! A signer can be bound to the cluster it is meant for (mainnet, devnet,
+//! testnet or a custom cluster identified by its genesis hash). Before signing,
+//! the transaction's recent blockhash is looked up on the bound cluster and, if
+//! it isn't found there, on the well-known public clusters. A blockhash that
+//! belongs to another cluster means the request is headed for the wrong
+//! environment, e.g. a devnet test key asked to sign a mainnet transaction.
+//!
+//! Blockhashes found on no cluster (durable nonces, expired blockhashes, or an
+//! unreachable RPC) are not treated as mismatches.
+
+use solana_client::rpc_client::RpcClient;
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::hash::Hash;
+use solana_sdk::message::VersionedMessage;
+
+use crate::db::{ClusterBindingRow, Database};
+use crate::error::{Error, Result};
+
+/// Genesis hash of mainnet-beta
+pub const MAINNET_GENESIS_HASH: &str = "5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d";
+/// Genesis hash of devnet
+pub const DEVNET_GENESIS_HASH: &str = "EtWTRABZaYq6iMfeYKouRu166VU2xqa1wcaWoxPkrZBG";
+/// Genesis hash of testnet
+pub const TESTNET_GENESIS_HASH: &str = "4uhcVJyU9pJkvQyS88uRDiswHXSCkY3zQawwpjk2NsNY";
+
+/// A cluster a signer can be bound to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Cluster {
+    /// Mainnet-beta
+    Mainnet,
+    /// Devnet
+    Devnet,
+    /// Testnet
+    Testnet,
+    /// Any other cluster, by genesis hash
+    Custom(String),
+}
+
+impl Cluster {
+    /// The public clusters probed when a blockhash isn't on the bound cluster
+    pub const WELL_KNOWN: [Cluster; 3] = [Cluster::Mainnet, Cluster::Devnet, Cluster::Testnet];
+
+    /// The cluster with this genesis hash
+    pub fn from_genesis_hash(genesis_hash: &str) -> Self {
+        match genesis_hash {
+            MAINNET_GENESIS_HASH => Cluster::Mainnet,
+            DEVNET_GENESIS_HASH => Cluster::Devnet,
+            TESTNET_GENESIS_HASH => Cluster::Testnet,
+            other => Cluster::Custom(other.to_string()),
+        }
+    }
+
+    /// Genesis hash identifying the cluster
+    pub fn genesis_hash(&self) -> &str {
+        match self {
+            Cluster::Mainnet => MAINNET_GENESIS_HASH,
+            Cluster::Devnet => DEVNET_GENESIS_HASH,
+            Cluster::Testnet => TESTNET_GENESIS_HASH,
+            Cluster::Custom(genesis_hash) => genesis_hash,
+        }
+    }
+
+    /// Public RPC endpoint, if the cluster has one
+    pub fn default_rpc_url(&self) -> Option<&'static str> {
+        match self {
+            Cluster::Mainnet => Some("https://api.mainnet-beta.solana.com"),
+            Cluster::Devnet => Some("https://api.devnet.solana.com"),
+            Cluster::Testnet => Some("https://api.testnet.solana.com"),
+            Cluster::Custom(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Cluster {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Cluster::Mainnet => write!(f, "mainnet"),
+            Cluster::Devnet => write!(f, "devnet"),
+            Cluster::Testnet => write!(f, "testnet"),
+            Cluster::Custom(genesis_hash) => write!(f, "custom:{}", genesis_hash),
+        }
+    }
+}
+
+impl std::str::FromStr for Cluster {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "mainnet" | "mainnet-beta" => Ok(Cluster::Mainnet),
+            "devnet" => Ok(Cluster::Devnet),
+            "testnet" => Ok(Cluster::Testnet),
+            _ => match s.strip_prefix("custom:") {
+                Some(genesis_hash) if genesis_hash.parse::<Hash>().is_ok() => {
+                    Ok(Cluster::from_genesis_hash(genesis_hash))
+                }
+                _ => Err(Error::Policy(format!(
+                    "Unknown cluster: {} (use mainnet, devnet, testnet or custom:<genesis hash>)",
+                    s
+                ))),
+            },
+        }
+    }
+}
+
+/// What happens when a signer is asked to sign for another cluster
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BindingMode {
+    /// Refuse to sign
+    #[default]
+    Refuse,
+    /// Sign after a warning (the agent always prompts)
+    Warn,
+}
+
+impl BindingMode {
+    /// Database representation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BindingMode::Refuse => "refuse",
+            BindingMode::Warn => "warn",
+        }
+    }
+}
+
+impl std::fmt::Display for BindingMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl std::str::FromStr for BindingMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "refuse" => Ok(BindingMode::Refuse),
+            "warn" => Ok(BindingMode::Warn),
+            _ => Err(Error::Policy(format!("Unknown binding mode: {}", s))),
+        }
+    }
+}
+
+/// A signer's cluster binding
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClusterBinding {
+    /// Public key of the bound signer
+    pub pubkey: String,
+    /// Cluster the signer may sign for
+    pub cluster: Cluster,
+    /// RPC endpoint used to look up blockhashes on the bound cluster
+    pub rpc_url: String,
+    /// What to do on a mismatch
+    pub mode: BindingMode,
+}
+
+impl ClusterBinding {
+    /// Check a transaction against the binding over RPC
+    ///
+    /// Returns `None` if the blockhash isn't known to belong to another
+    /// cluster. This makes network requests, so callers shouldn't hold locks
+    /// (or the database) while it runs.
+    pub fn check(&self, message_bytes: &[u8]) -> Result<Option<ClusterMismatch>> {
+        let blockhash = recent_blockhash(message_bytes)?;
+
+        Ok(find_mismatch(self, |cluster| {
+            let url = if *cluster == self.cluster {
+                self.rpc_url.as_str()
+            } else {
+                cluster.default_rpc_url()?
+            };
+            RpcClient::new(url.to_string())
+                .is_blockhash_valid(&blockhash, CommitmentConfig::processed())
+                .ok()
+        }))
+    }
+}
+
+impl TryFrom<ClusterBindingRow> for ClusterBinding {
+    type Error = Error;
+
+    fn try_from(row: ClusterBindingRow) -> Result<Self> {
+        Ok(Self {
+            pubkey: row.pubkey,
+            cluster: Cluster::from_genesis_hash(&row.genesis_hash),
+            rpc_url: row.rpc_url,
+            mode: row.mode.parse()?,
+        })
+    }
+}
+
+/// A transaction whose blockhash belongs to a different cluster than its signer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClusterMismatch {
+    /// The signer's binding
+    pub binding: ClusterBinding,
+    /// Cluster the blockhash was found on
+    pub found: Cluster,
+}
+
+impl std::fmt::Display for ClusterMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Signer {} is bound to {}, but the transaction's blockhash is from {}",
+            self.binding.pubkey, self.binding.cluster, self.found
+        )
+    }
+}
+
+/// Cluster binding operations
+pub struct ClusterBindings<'a> {
+    db: &'a Database,
+}
+
+impl<'a> ClusterBindings<'a> {
+    /// Create a new cluster bindings handle
+    pub fn new(db: &'a Database) -> Self {
+        Self { db }
+    }
+
+    /// Bind a signer to a cluster, replacing any existing binding
+    ///
+    /// Custom clusters have no public endpoint, so `rpc_url` is required for
+    /// them. When given for any cluster, the endpoint's genesis hash must
+    /// match the cluster's.
+    pub fn bind(
+        &self,
+        pubkey: &str,
+        cluster: &Cluster,
+        rpc_url: Option<&str>,
+        mode: BindingMode,
+    ) -> Result<ClusterBinding> {
+        let rpc_url = match (rpc_url, cluster.default_rpc_url()) {
+            (Some(url), _) => {
+                let genesis_hash = RpcClient::new(url.to_string())
+                    .get_genesis_hash()
+                    .map_err(|e| Error::Solana(format!("Failed to query {}: {}", url, e)))?;
+                if genesis_hash.to_string() != cluster.genesis_hash() {
+                    return Err(Error::Policy(format!(
+                        "{} serves {}, not {}",
+                        url,
+                        Cluster::from_genesis_hash(&genesis_hash.to_string()),
+                        cluster
+                    )));
+                }
+                url.to_string()
+            }
+            (None, Some(url)) => url.to_string(),
+            (None, None) => {
+                return Err(Error::Policy(format!(
+                    "An RPC URL is required to bind to {}",
+                    cluster
+                )));
+            }
+        };
+
+        self.db
+            .set_cluster_binding(pubkey, cluster.genesis_hash(), &rpc_url, mode.as_str())?;
+        Ok(ClusterBinding {
+            pubkey: pubkey.to_string(),
+            cluster: cluster.clone(),
+            rpc_url,
+            mode,
+        })
+    }
+
+    /// Remove a signer's binding
+    pub fn unbind(&self, pubkey: &str) -> Result<bool> {
+        self.db.delete_cluster_binding(pubkey)
+    }
+
+    /// A signer's binding, if it has one
+    pub fn get(&self, pubkey: &str) -> Result<Option<ClusterBinding>> {
+        self.db
+            .get_cluster_binding(pubkey)?
+            .map(TryInto::try_into)
+            .transpose()
+    }
+
+    /// All bindings
+    pub fn list(&self) -> Result<Vec<ClusterBinding>> {
+        self.db
+            .list_cluster_bindings()?
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect()
+    }
+}
+
+/// The recent blockhash of a serialized legacy or versioned message
+pub fn recent_blockhash(message_bytes: &[u8]) -> Result<Hash> {
+    let message: VersionedMessage = bincode::deserialize(message_bytes)
+        .map_err(|e| Error::Solana(format!("Failed to parse message: {}", e)))?;
+    Ok(*message.recent_blockhash())
+}
+
+/// Find which cluster a blockhash belongs to, if not the bound one
+///
+/// `is_valid` reports whether the blockhash is valid on a cluster, or `None`
+/// if that cluster can't be queried.
+fn find_mismatch(
+    binding: &ClusterBinding,
+    mut is_valid: impl FnMut(&Cluster) -> Option<bool>,
+) -> Option<ClusterMismatch> {
+    if is_valid(&binding.cluster) == Some(true) {
+        return None;
+    }
+
+    Cluster::WELL_KNOWN
+        .into_iter()
+        .filter(|cluster| *cluster != binding.cluster)
+        .find(|cluster| is_valid(cluster) == Some(true))
+        .map(|found| ClusterMismatch {
+            binding: binding.clone(),
+            found,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binding(cluster: Cluster) -> ClusterBinding {
+        ClusterBinding {
+            pubkey: "DevKey111".to_string(),
+            rpc_url: "http://localhost:8899".to_string(),
+            cluster,
+            mode: BindingMode::Refuse,
+        }
+    }
+
+    #[test]
+    fn test_parse_cluster() {
+        assert_eq!("mainnet-beta".parse::<Cluster>().unwrap(), Cluster::Mainnet);
+        assert_eq!("devnet".parse::<Cluster>().unwrap(), Cluster::Devnet);
+        assert_eq!(
+            format!("custom:{}", TESTNET_GENESIS_HASH)
+                .parse::<Cluster>()
+                .unwrap(),
+            Cluster::Testnet
+        );
+        let custom = format!("custom:{}", Hash::new_unique());
+        assert_eq!(custom.parse::<Cluster>().unwrap().to_string(), custom);
+        assert!("custom:nothash".parse::<Cluster>().is_err());
+        assert!("localnet".parse::<Cluster>().is_err());
+    }
+
+    #[test]
+    fn test_find_mismatch() {
+        let devnet = binding(Cluster::Devnet);
+
+        // Valid on the bound cluster
+        assert_eq!(
+            find_mismatch(&devnet, |c| Some(*c == Cluster::Devnet)),
+            None
+        );
+
+        // Valid on mainnet only
+        let mismatch = find_mismatch(&devnet, |c| Some(*c == Cluster::Mainnet)).unwrap();
+        assert_eq!(mismatch.found, Cluster::Mainnet);
+
+        // Unknown everywhere (durable nonce) or unreachable
+        assert_eq!(find_mismatch(&devnet, |_| Some(false)), None);
+        assert_eq!(find_mismatch(&devnet, |_| None), None);
+
+        // A custom cluster is probed on its own endpoint first
+        let custom = binding(Cluster::Custom(Hash::new_unique().to_string()));
+        let mut probed = Vec::new();
+        find_mismatch(&custom, |c| {
+            probed.push(c.clone());
+            Some(false)
+        });
+        assert_eq!(probed.len(), 4);
+        assert_eq!(probed[0], custom.cluster);
+    }
+
+    #[test]
+    fn test_bindings_round_trip() {
+        let db = Database::open_in_memory().unwrap();
+        let bindings = ClusterBindings::new(&db);
+
+        bindings
+            .bind("DevKey111", &Cluster::Devnet, None, BindingMode::Warn)
+            .unwrap();
+        let binding = bindings.get("DevKey111").unwrap().unwrap();
+        assert_eq!(binding.cluster, Cluster::Devnet);
+        assert_eq!(binding.rpc_url, "https://api.devnet.solana.com");
+        assert_eq!(binding.mode, BindingMode::Warn);
+
+        let custom = Cluster::Custom(Hash::new_unique().to_string());
+        assert!(
+            bindings
+                .bind("DevKey111", &custom, None, BindingMode::Refuse)
+                .is_err()
+        );
+
+        assert_eq!(bindings.list().unwrap().len(), 1);
+        assert!(bindings.unbind("DevKey111").unwrap());
+        assert!(bindings.get("DevKey111").unwrap().is_none());
+    }
+}
@@ -0,0 +1,33 @@
+//! Constant-time comparisons for secret material
+
+/// Compare two byte strings without branching on their contents
+///
+/// The running time depends only on the lengths, so comparing a guess
+/// against a secret does not reveal how many leading bytes matched.
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let diff = a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y));
+    std::hint::black_box(diff) == 0
+}
+
+/// Compare two strings (e.g. passphrases or tokens) in constant time
+pub fn ct_eq_str(a: &str, b: &str) -> bool {
+    ct_eq(a.as_bytes(), b.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ct_eq() {
+        assert!(ct_eq(b"secret", b"secret"));
+        assert!(!ct_eq(b"secret", b"secreT"));
+        assert!(!ct_eq(b"secret", b"secrets"));
+        assert!(ct_eq(b"", b""));
+        assert!(ct_eq_str("passphrase", "passphrase"));
+        assert!(!ct_eq_str("passphrase", "Passphrase"));
+    }
+}
@@ -3,6 +3,7 @@
 use argon2::{Algorithm, Argon2, Params, Version};
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
+use super::ct::ct_eq;
 use crate::error::{Error, Result};
 
 /// Memory cost for Argon2id (64 MB)
@@ -55,12 +56,7 @@ pub fn hash_password(password: &[u8], salt: &[u8; 32]) -> Result<[u8; 32]> {
 /// Verify a password against a stored hash
 pub fn verify_password(password: &[u8], salt: &[u8; 32], expected_hash: &[u8; 32]) -> Result<bool> {
     let computed = hash_password(password, salt)?;
-    // Constant-time comparison
-    Ok(computed
-        .iter()
-        .zip(expected_hash.iter())
-        .fold(0u8, |acc, (a, b)| acc | (a ^ b))
-        == 0)
+    Ok(ct_eq(&computed, expected_hash))
 }
 
 #[cfg(test)]
@@ -1,7 +1,10 @@
 //! Cryptographic primitives for keyring encryption
 
 mod aead;
+mod ct;
 mod kdf;
+pub mod shamir;
 
 pub use aead::{EncryptedData, decrypt_secret, encrypt_secret};
+pub use ct::{ct_eq, ct_eq_str};
 pub use kdf::{DerivedKey, hash_password, verify_password};
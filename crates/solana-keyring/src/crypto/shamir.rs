@@ -0,0 +1,195 @@
+//! Shamir secret sharing over GF(256)
+//!
+//! Each byte of the secret is shared independently with a random polynomial of
+//! degree `threshold - 1`; any `threshold` shares recover the secret and fewer
+//! reveal nothing about it.
+
+use rand::RngCore;
+use zeroize::Zeroizing;
+
+use crate::error::{Error, Result};
+
+/// One share of a split secret
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Share {
+    /// Evaluation point (1-255)
+    pub index: u8,
+    /// Share bytes, the same length as the secret
+    pub data: Vec<u8>,
+}
+
+impl std::fmt::Display for Share {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.index, hex::encode(&self.data))
+    }
+}
+
+impl std::str::FromStr for Share {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || Error::SecretSharing(format!("Invalid share: {}", s));
+
+        let (index, data) = s.trim().split_once('-').ok_or_else(invalid)?;
+        let index: u8 = index.parse().map_err(|_| invalid())?;
+        if index == 0 {
+            return Err(invalid());
+        }
+        let data = hex::decode(data).map_err(|_| invalid())?;
+
+        Ok(Share { index, data })
+    }
+}
+
+/// Split a secret into `shares` shares, any `threshold` of which recover it
+pub fn split(secret: &[u8], threshold: u8, shares: u8) -> Result<Vec<Share>> {
+    if threshold < 2 || threshold > shares {
+        return Err(Error::SecretSharing(format!(
+            "Threshold must be between 2 and the number of shares ({})",
+            shares
+        )));
+    }
+
+    let mut result: Vec<Share> = (1..=shares)
+        .map(|index| Share {
+            index,
+            data: Vec::with_capacity(secret.len()),
+        })
+        .collect();
+
+    let mut coefficients = Zeroizing::new(vec![0u8; threshold as usize]);
+    for &byte in secret {
+        coefficients[0] = byte;
+        rand::thread_rng().fill_bytes(&mut coefficients[1..]);
+
+        for share in &mut result {
+            share.data.push(evaluate(&coefficients, share.index));
+        }
+    }
+
+    Ok(result)
+}
+
+/// Recover a secret from at least `threshold` shares
+///
+/// Passing fewer shares than the threshold returns an unrelated value; callers
+/// should verify the result (e.g. against a known public key).
+pub fn combine(shares: &[Share]) -> Result<Zeroizing<Vec<u8>>> {
+    let first = shares
+        .first()
+        .ok_or_else(|| Error::SecretSharing("No shares provided".into()))?;
+    let len = first.data.len();
+
+    for (i, share) in shares.iter().enumerate() {
+        if share.data.len() != len {
+            return Err(Error::SecretSharing("Shares have different lengths".into()));
+        }
+        if share.index == 0 || shares[..i].iter().any(|s| s.index == share.index) {
+            return Err(Error::SecretSharing(format!(
+                "Duplicate or invalid share index {}",
+                share.index
+            )));
+        }
+    }
+
+    // Lagrange interpolation at x = 0
+    let mut secret = Zeroizing::new(vec![0u8; len]);
+    for (i, share) in shares.iter().enumerate() {
+        let mut basis = 1u8;
+        for (j, other) in shares.iter().enumerate() {
+            if i != j {
+                basis = mul(basis, div(other.index, other.index ^ share.index));
+            }
+        }
+        for (out, &y) in secret.iter_mut().zip(&share.data) {
+            *out ^= mul(y, basis);
+        }
+    }
+
+    Ok(secret)
+}
+
+/// Evaluate a polynomial at `x` (Horner's method)
+fn evaluate(coefficients: &[u8], x: u8) -> u8 {
+    coefficients
+        .iter()
+        .rev()
+        .fold(0u8, |acc, &c| mul(acc, x) ^ c)
+}
+
+/// Multiplication in GF(256) with the AES polynomial, without data-dependent branches
+fn mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0u8;
+    for _ in 0..8 {
+        product ^= a & 0u8.wrapping_sub(b & 1);
+        let carry = 0u8.wrapping_sub(a >> 7);
+        a = (a << 1) ^ (0x1b & carry);
+        b >>= 1;
+    }
+    product
+}
+
+/// Division in GF(256); `b` must be non-zero
+fn div(a: u8, b: u8) -> u8 {
+    // b^254 is the multiplicative inverse of b
+    let mut inverse = 1u8;
+    let mut base = b;
+    let mut exp = 254u8;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            inverse = mul(inverse, base);
+        }
+        base = mul(base, base);
+        exp >>= 1;
+    }
+    mul(a, inverse)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_inverse() {
+        for b in 1..=255u8 {
+            assert_eq!(mul(div(1, b), b), 1);
+        }
+    }
+
+    #[test]
+    fn test_split_combine() {
+        let secret = [0x42u8; 32];
+        let shares = split(&secret, 3, 5).unwrap();
+        assert_eq!(shares.len(), 5);
+
+        assert_eq!(&*combine(&shares[..3]).unwrap(), &secret);
+        assert_eq!(&*combine(&shares[2..]).unwrap(), &secret);
+        assert_eq!(
+            &*combine(&[shares[4].clone(), shares[0].clone(), shares[2].clone()]).unwrap(),
+            &secret
+        );
+        assert_ne!(&*combine(&shares[..2]).unwrap(), &secret);
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        assert!(split(b"secret", 1, 3).is_err());
+        assert!(split(b"secret", 4, 3).is_err());
+
+        let shares = split(b"secret", 2, 3).unwrap();
+        assert!(combine(&[shares[0].clone(), shares[0].clone()]).is_err());
+        assert!(combine(&[]).is_err());
+    }
+
+    #[test]
+    fn test_share_roundtrip() {
+        let share = Share {
+            index: 3,
+            data: vec![0xde, 0xad],
+        };
+        assert_eq!(share.to_string(), "3-dead");
+        assert_eq!("3-dead".parse::<Share>().unwrap(), share);
+        assert!("0-dead".parse::<Share>().is_err());
+        assert!("3-xyz".parse::<Share>().is_err());
+    }
+}
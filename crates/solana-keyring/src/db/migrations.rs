@@ -55,6 +55,40 @@ CREATE TABLE IF NOT EXISTS ledger_tags (
     PRIMARY KEY (ledger_id, tag_id)
 );
 
+-- YubiKey wallets (Ed25519 key in a PIV slot of one device)
+CREATE TABLE IF NOT EXISTS yubikey_wallets (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    pubkey TEXT NOT NULL UNIQUE,
+    label TEXT NOT NULL,
+    serial INTEGER NOT NULL,
+    slot TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Junction table for YubiKey tags
+CREATE TABLE IF NOT EXISTS yubikey_tags (
+    yubikey_id INTEGER NOT NULL REFERENCES yubikey_wallets(id) ON DELETE CASCADE,
+    tag_id INTEGER NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+    PRIMARY KEY (yubikey_id, tag_id)
+);
+
+-- Custom signers (signer types registered by other crates, see driver.rs)
+CREATE TABLE IF NOT EXISTS custom_signers (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    pubkey TEXT NOT NULL UNIQUE,
+    label TEXT NOT NULL,
+    driver TEXT NOT NULL,
+    metadata TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Junction table for custom signer tags
+CREATE TABLE IF NOT EXISTS custom_signer_tags (
+    custom_signer_id INTEGER NOT NULL REFERENCES custom_signers(id) ON DELETE CASCADE,
+    tag_id INTEGER NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+    PRIMARY KEY (custom_signer_id, tag_id)
+);
+
 -- Squads multisigs
 CREATE TABLE IF NOT EXISTS squads_multisigs (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -73,6 +107,15 @@ CREATE TABLE IF NOT EXISTS squads_tags (
     PRIMARY KEY (squads_id, tag_id)
 );
 
+-- Named Squads vaults (vault indexes users refer to by name)
+CREATE TABLE IF NOT EXISTS squads_vaults (
+    multisig_id INTEGER NOT NULL REFERENCES squads_multisigs(id) ON DELETE CASCADE,
+    vault_index INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    PRIMARY KEY (multisig_id, vault_index),
+    UNIQUE(multisig_id, name)
+);
+
 -- Squads multisig members
 CREATE TABLE IF NOT EXISTS squads_members (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -83,6 +126,20 @@ CREATE TABLE IF NOT EXISTS squads_members (
     UNIQUE(multisig_id, member_pubkey)
 );
 
+-- Squads proposals created from this keyring, awaiting approval or execution
+CREATE TABLE IF NOT EXISTS squads_pending_submissions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    multisig_pubkey TEXT NOT NULL,
+    vault_index INTEGER NOT NULL,
+    transaction_index INTEGER NOT NULL,
+    proposal_pubkey TEXT NOT NULL,
+    creator_pubkey TEXT NOT NULL,
+    message_hash TEXT NOT NULL,
+    source TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(multisig_pubkey, transaction_index)
+);
+
 -- Address book
 CREATE TABLE IF NOT EXISTS address_book (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -93,10 +150,126 @@ CREATE TABLE IF NOT EXISTS address_book (
     updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
+-- Junction table for address book tags
+CREATE TABLE IF NOT EXISTS address_book_tags (
+    address_id INTEGER NOT NULL REFERENCES address_book(id) ON DELETE CASCADE,
+    tag_id INTEGER NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+    PRIMARY KEY (address_id, tag_id)
+);
+
+-- Spending limits (rolling-window caps per key or tag)
+CREATE TABLE IF NOT EXISTS spending_limits (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    scope TEXT NOT NULL CHECK (scope IN ('key', 'tag')),
+    target TEXT NOT NULL,
+    max_lamports INTEGER NOT NULL,
+    window_seconds INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(scope, target, window_seconds)
+);
+
+-- Spending counters (one row per signed outflow)
+CREATE TABLE IF NOT EXISTS spending_counters (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    pubkey TEXT NOT NULL,
+    lamports INTEGER NOT NULL,
+    spent_at INTEGER NOT NULL
+);
+
+-- Audit log (append-only record of security-relevant events)
+CREATE TABLE IF NOT EXISTS audit_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    event TEXT NOT NULL,
+    pubkey TEXT,
+    details TEXT NOT NULL,
+    created_at INTEGER NOT NULL
+);
+
+-- Audit chain (hash of each audit event over the one before it)
+CREATE TABLE IF NOT EXISTS audit_chain (
+    event_id INTEGER PRIMARY KEY,
+    hash TEXT NOT NULL
+);
+
+-- Audit seal (passphrase-keyed MAC of the chain up to an event)
+CREATE TABLE IF NOT EXISTS audit_seal (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    event_id INTEGER NOT NULL,
+    salt BLOB NOT NULL,
+    mac TEXT NOT NULL,
+    sealed_at INTEGER NOT NULL
+);
+
+-- Settings (key/value configuration, e.g. audit log retention)
+CREATE TABLE IF NOT EXISTS settings (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+
+-- Secrets (named API keys and tokens, e.g. for RPC providers)
+CREATE TABLE IF NOT EXISTS secrets (
+    name TEXT PRIMARY KEY,
+    encrypted_value BLOB NOT NULL,
+    encryption_nonce BLOB NOT NULL,
+    encryption_salt BLOB NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Signer usage (last time each signer produced a signature)
+CREATE TABLE IF NOT EXISTS signer_usage (
+    pubkey TEXT PRIMARY KEY,
+    last_used_at INTEGER NOT NULL,
+    use_count INTEGER NOT NULL DEFAULT 0
+);
+
+-- Delegations (bot keys allowed to request signatures for one template)
+CREATE TABLE IF NOT EXISTS delegations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    bot_pubkey TEXT NOT NULL,
+    signer_pubkey TEXT NOT NULL,
+    template_hash TEXT NOT NULL,
+    nonce_start INTEGER NOT NULL,
+    nonce_end INTEGER NOT NULL,
+    last_nonce INTEGER,
+    created_at INTEGER NOT NULL,
+    revoked_at INTEGER
+);
+
+-- Cluster bindings (signers that may only sign for one cluster)
+CREATE TABLE IF NOT EXISTS cluster_bindings (
+    pubkey TEXT PRIMARY KEY,
+    genesis_hash TEXT NOT NULL,
+    rpc_url TEXT NOT NULL,
+    mode TEXT NOT NULL CHECK (mode IN ('refuse', 'warn')),
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- High-value keys (mandatory cool-down and typed amount confirmation)
+CREATE TABLE IF NOT EXISTS high_value_keys (
+    pubkey TEXT PRIMARY KEY,
+    cooldown_seconds INTEGER NOT NULL,
+    last_signed_at INTEGER,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Failed unlock attempts (single row; drives escalating delays and hard-lock)
+CREATE TABLE IF NOT EXISTS unlock_failures (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    failed_attempts INTEGER NOT NULL,
+    last_failed_at INTEGER NOT NULL,
+    hard_locked_at INTEGER
+);
+
 -- Indexes for faster lookups
 CREATE INDEX IF NOT EXISTS idx_keypairs_label ON keypairs(label);
 CREATE INDEX IF NOT EXISTS idx_ledger_label ON ledger_wallets(label);
+CREATE INDEX IF NOT EXISTS idx_yubikey_label ON yubikey_wallets(label);
+CREATE INDEX IF NOT EXISTS idx_custom_signers_label ON custom_signers(label);
 CREATE INDEX IF NOT EXISTS idx_squads_label ON squads_multisigs(label);
 CREATE INDEX IF NOT EXISTS idx_address_book_label ON address_book(label);
 CREATE INDEX IF NOT EXISTS idx_tags_name ON tags(name);
+CREATE INDEX IF NOT EXISTS idx_spending_counters_pubkey ON spending_counters(pubkey, spent_at);
+CREATE INDEX IF NOT EXISTS idx_audit_log_event ON audit_log(event, created_at);
+CREATE INDEX IF NOT EXISTS idx_delegations_bot ON delegations(bot_pubkey, signer_pubkey);
 "#;
@@ -3,12 +3,18 @@
 mod migrations;
 mod schema;
 
-pub use schema::{AddressBookRow, KeypairRow, LedgerWalletRow, SquadsMultisigRow, TagRow};
-
+pub use schema::{
+    AddressBookRow, AuditEventRow, AuditSealRow, ClusterBindingRow, CustomSignerRow, DelegationRow,
+    HighValueKeyRow, KeypairRow, LedgerWalletRow, PendingSubmissionRow, SecretRow,
+    SpendingLimitRow, SquadsMultisigRow, SquadsVaultRow, TableStats, TagRow, UnlockFailuresRow,
+    YubikeyWalletRow,
+};
+
+use std::collections::BTreeMap;
 use std::path::Path;
 
-use rusqlite::{Connection, OptionalExtension, params};
-use zeroize::Zeroize;
+use rusqlite::{Connection, OpenFlags, OptionalExtension, params};
+use zeroize::{Zeroize, Zeroizing};
 
 use crate::crypto::{EncryptedData, decrypt_secret, encrypt_secret};
 use crate::error::{Error, Result};
@@ -33,6 +39,17 @@ impl Database {
         Ok(db)
     }
 
+    /// Open an existing database read-only, without running migrations
+    ///
+    /// Used to inspect backups without modifying them.
+    pub fn open_read_only(path: &Path) -> Result<Self> {
+        let conn = Connection::open_with_flags(
+            path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?;
+        Ok(Self { conn })
+    }
+
     /// Open an in-memory database (for testing)
     pub fn open_in_memory() -> Result<Self> {
         let conn = Connection::open_in_memory()?;
@@ -80,8 +97,15 @@ impl Database {
 
     /// Verify the master passphrase
     pub fn verify_passphrase(&self, passphrase: &[u8]) -> Result<bool> {
-        use crate::crypto::verify_password;
+        let (salt, hash) = self.passphrase_hash()?;
+        crate::crypto::verify_password(passphrase, &salt, &hash)
+    }
 
+    /// The salt and Argon2id hash of the master passphrase
+    ///
+    /// Lets a caller sharing the connection verify a passphrase without
+    /// holding it during the (slow) derivation.
+    pub fn passphrase_hash(&self) -> Result<([u8; 32], [u8; 32])> {
         let (salt, hash): (Vec<u8>, Vec<u8>) = self
             .conn
             .query_row(
@@ -102,7 +126,7 @@ impl Database {
             ))
         })?;
 
-        verify_password(passphrase, &salt, &hash)
+        Ok((salt, hash))
     }
 
     // ==================== Keypair Operations ====================
@@ -115,16 +139,23 @@ impl Database {
         master_passphrase: &[u8],
         tags: &[&str],
     ) -> Result<()> {
-        let pubkey_b58 = bs58::encode(keypair.pubkey_bytes()).into_string();
-        let secret_bytes = keypair.secret_bytes();
-
-        let encrypted = encrypt_secret(&secret_bytes[..], master_passphrase)?;
+        let encrypted = keypair.encrypt(master_passphrase)?;
+        self.store_encrypted_keypair(&keypair.pubkey_base58(), label, &encrypted, tags)
+    }
 
+    /// Store a keypair already encrypted with [`SecureKeypair::encrypt`]
+    pub fn store_encrypted_keypair(
+        &self,
+        pubkey: &str,
+        label: &str,
+        encrypted: &EncryptedData,
+        tags: &[&str],
+    ) -> Result<()> {
         self.conn.execute(
             "INSERT INTO keypairs (pubkey, label, encrypted_secret, encryption_nonce, encryption_salt)
              VALUES (?1, ?2, ?3, ?4, ?5)",
             params![
-                pubkey_b58,
+                pubkey,
                 label,
                 encrypted.ciphertext,
                 encrypted.nonce.as_slice(),
@@ -134,7 +165,7 @@ impl Database {
 
         // Add tags
         for tag in tags {
-            self.add_tag_to_keypair(&pubkey_b58, tag)?;
+            self.add_tag_to_keypair(pubkey, tag)?;
         }
 
         Ok(())
@@ -146,6 +177,11 @@ impl Database {
         identifier: &str,
         master_passphrase: &[u8],
     ) -> Result<SecureKeypair> {
+        SecureKeypair::decrypt(&self.load_encrypted_keypair(identifier)?, master_passphrase)
+    }
+
+    /// Load a keypair's encrypted secret, for [`SecureKeypair::decrypt`]
+    pub fn load_encrypted_keypair(&self, identifier: &str) -> Result<EncryptedData> {
         // Try to find by pubkey first, then by label
         let row: Option<(Vec<u8>, Vec<u8>, Vec<u8>)> = self
             .conn
@@ -167,22 +203,11 @@ impl Database {
             .try_into()
             .map_err(|_| Error::Encryption("Invalid salt".into()))?;
 
-        let encrypted = EncryptedData {
+        Ok(EncryptedData {
             ciphertext,
             nonce,
             salt,
-        };
-        let mut secret_bytes = decrypt_secret(&encrypted, master_passphrase)?;
-
-        let result = SecureKeypair::from_bytes(
-            secret_bytes
-                .as_slice()
-                .try_into()
-                .map_err(|_| Error::InvalidKeypairFormat("Wrong key size".into()))?,
-        );
-
-        secret_bytes.zeroize();
-        result
+        })
     }
 
     /// List all keypairs
@@ -223,6 +248,17 @@ impl Database {
         Ok(rows)
     }
 
+    /// Count keypairs created after the given unix timestamp
+    pub fn count_keypairs_created_after(&self, since: i64) -> Result<usize> {
+        let count: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM keypairs
+             WHERE CAST(strftime('%s', created_at) AS INTEGER) > ?1",
+            params![since],
+            |row| row.get(0),
+        )?;
+        Ok(count as usize)
+    }
+
     /// Get tags for a keypair
     pub fn get_keypair_tags(&self, pubkey: &str) -> Result<Vec<String>> {
         let mut stmt = self.conn.prepare(
@@ -237,6 +273,39 @@ impl Database {
             .map_err(Into::into)
     }
 
+    /// Get tags for a signer held by a keypair, Ledger wallet, YubiKey or
+    /// custom driver
+    ///
+    /// Covers the same signers as [`Self::spent_by_tag`], so tag limits apply
+    /// whatever holds the key.
+    pub fn get_signer_tags(&self, pubkey: &str) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT t.name FROM tags t
+             INNER JOIN keypair_tags kt ON t.id = kt.tag_id
+             INNER JOIN keypairs k ON kt.keypair_id = k.id
+             WHERE k.pubkey = ?1
+             UNION
+             SELECT t.name FROM tags t
+             INNER JOIN ledger_tags lt ON t.id = lt.tag_id
+             INNER JOIN ledger_wallets l ON lt.ledger_id = l.id
+             WHERE l.pubkey = ?1
+             UNION
+             SELECT t.name FROM tags t
+             INNER JOIN yubikey_tags yt ON t.id = yt.tag_id
+             INNER JOIN yubikey_wallets y ON yt.yubikey_id = y.id
+             WHERE y.pubkey = ?1
+             UNION
+             SELECT t.name FROM tags t
+             INNER JOIN custom_signer_tags ct ON t.id = ct.tag_id
+             INNER JOIN custom_signers c ON ct.custom_signer_id = c.id
+             WHERE c.pubkey = ?1",
+        )?;
+
+        let tags = stmt.query_map(params![pubkey], |row| row.get(0))?;
+        tags.collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(Into::into)
+    }
+
     /// Delete a keypair
     pub fn delete_keypair(&self, identifier: &str) -> Result<bool> {
         let affected = self.conn.execute(
@@ -427,6 +496,210 @@ impl Database {
         Ok(affected > 0)
     }
 
+    // ==================== YubiKey Wallet Operations ====================
+
+    /// Store a YubiKey wallet
+    pub fn store_yubikey_wallet(
+        &self,
+        pubkey: &str,
+        label: &str,
+        serial: u32,
+        slot: &str,
+        tags: &[&str],
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO yubikey_wallets (pubkey, label, serial, slot)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![pubkey, label, serial, slot],
+        )?;
+
+        for tag in tags {
+            self.add_tag_to_yubikey(pubkey, tag)?;
+        }
+
+        Ok(())
+    }
+
+    /// List all YubiKey wallets
+    pub fn list_yubikey_wallets(&self, tag_filter: Option<&str>) -> Result<Vec<YubikeyWalletRow>> {
+        let query = if tag_filter.is_some() {
+            "SELECT y.id, y.pubkey, y.label, y.serial, y.slot, y.created_at
+             FROM yubikey_wallets y
+             INNER JOIN yubikey_tags yt ON y.id = yt.yubikey_id
+             INNER JOIN tags t ON yt.tag_id = t.id
+             WHERE t.name = ?1
+             ORDER BY y.label"
+        } else {
+            "SELECT id, pubkey, label, serial, slot, created_at
+             FROM yubikey_wallets ORDER BY label"
+        };
+
+        let mut stmt = self.conn.prepare(query)?;
+
+        fn map_row(row: &rusqlite::Row) -> rusqlite::Result<YubikeyWalletRow> {
+            Ok(YubikeyWalletRow {
+                id: row.get(0)?,
+                pubkey: row.get(1)?,
+                label: row.get(2)?,
+                serial: row.get(3)?,
+                slot: row.get(4)?,
+                created_at: row.get(5)?,
+            })
+        }
+
+        let rows: Vec<YubikeyWalletRow> = if let Some(tag) = tag_filter {
+            stmt.query_map(params![tag], map_row)?
+                .collect::<rusqlite::Result<Vec<_>>>()?
+        } else {
+            stmt.query_map([], map_row)?
+                .collect::<rusqlite::Result<Vec<_>>>()?
+        };
+
+        Ok(rows)
+    }
+
+    /// Add a tag to a YubiKey wallet
+    fn add_tag_to_yubikey(&self, pubkey: &str, tag: &str) -> Result<()> {
+        let tag_id = self.get_or_create_tag(tag)?;
+
+        let yubikey_id: i64 = self
+            .conn
+            .query_row(
+                "SELECT id FROM yubikey_wallets WHERE pubkey = ?1",
+                params![pubkey],
+                |row| row.get(0),
+            )
+            .map_err(|_| Error::AddressNotFound(pubkey.into()))?;
+
+        self.conn.execute(
+            "INSERT OR IGNORE INTO yubikey_tags (yubikey_id, tag_id) VALUES (?1, ?2)",
+            params![yubikey_id, tag_id],
+        )?;
+
+        Ok(())
+    }
+
+    /// Delete a YubiKey wallet
+    pub fn delete_yubikey_wallet(&self, identifier: &str) -> Result<bool> {
+        let affected = self.conn.execute(
+            "DELETE FROM yubikey_wallets WHERE pubkey = ?1 OR label = ?1",
+            params![identifier],
+        )?;
+        Ok(affected > 0)
+    }
+
+    // ==================== Custom Signer Operations ====================
+
+    /// Store a signer of a custom type, with its driver's metadata
+    pub fn store_custom_signer(
+        &self,
+        pubkey: &str,
+        label: &str,
+        driver: &str,
+        metadata: &BTreeMap<String, String>,
+        tags: &[&str],
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO custom_signers (pubkey, label, driver, metadata)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![pubkey, label, driver, serde_json::to_string(metadata)?],
+        )?;
+
+        for tag in tags {
+            self.add_tag_to_custom_signer(pubkey, tag)?;
+        }
+
+        Ok(())
+    }
+
+    /// List all custom signers
+    pub fn list_custom_signers(&self, tag_filter: Option<&str>) -> Result<Vec<CustomSignerRow>> {
+        let query = if tag_filter.is_some() {
+            "SELECT c.id, c.pubkey, c.label, c.driver, c.metadata, c.created_at
+             FROM custom_signers c
+             INNER JOIN custom_signer_tags ct ON c.id = ct.custom_signer_id
+             INNER JOIN tags t ON ct.tag_id = t.id
+             WHERE t.name = ?1
+             ORDER BY c.label"
+        } else {
+            "SELECT id, pubkey, label, driver, metadata, created_at
+             FROM custom_signers ORDER BY label"
+        };
+
+        let mut stmt = self.conn.prepare(query)?;
+
+        type RawRow = (i64, String, String, String, String, String);
+        fn map_row(row: &rusqlite::Row) -> rusqlite::Result<RawRow> {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+                row.get(5)?,
+            ))
+        }
+
+        let rows: Vec<RawRow> = if let Some(tag) = tag_filter {
+            stmt.query_map(params![tag], map_row)?
+                .collect::<rusqlite::Result<Vec<_>>>()?
+        } else {
+            stmt.query_map([], map_row)?
+                .collect::<rusqlite::Result<Vec<_>>>()?
+        };
+
+        rows.into_iter()
+            .map(|(id, pubkey, label, driver, metadata, created_at)| {
+                Ok(CustomSignerRow {
+                    id,
+                    pubkey,
+                    label,
+                    driver,
+                    metadata: serde_json::from_str(&metadata)?,
+                    created_at,
+                })
+            })
+            .collect()
+    }
+
+    /// Get a custom signer by public key or label
+    pub fn get_custom_signer(&self, identifier: &str) -> Result<Option<CustomSignerRow>> {
+        Ok(self
+            .list_custom_signers(None)?
+            .into_iter()
+            .find(|row| row.pubkey == identifier || row.label == identifier))
+    }
+
+    /// Add a tag to a custom signer
+    fn add_tag_to_custom_signer(&self, pubkey: &str, tag: &str) -> Result<()> {
+        let tag_id = self.get_or_create_tag(tag)?;
+
+        let custom_signer_id: i64 = self
+            .conn
+            .query_row(
+                "SELECT id FROM custom_signers WHERE pubkey = ?1",
+                params![pubkey],
+                |row| row.get(0),
+            )
+            .map_err(|_| Error::AddressNotFound(pubkey.into()))?;
+
+        self.conn.execute(
+            "INSERT OR IGNORE INTO custom_signer_tags (custom_signer_id, tag_id) VALUES (?1, ?2)",
+            params![custom_signer_id, tag_id],
+        )?;
+
+        Ok(())
+    }
+
+    /// Delete a custom signer
+    pub fn delete_custom_signer(&self, identifier: &str) -> Result<bool> {
+        let affected = self.conn.execute(
+            "DELETE FROM custom_signers WHERE pubkey = ?1 OR label = ?1",
+            params![identifier],
+        )?;
+        Ok(affected > 0)
+    }
+
     // ==================== Squads Multisig Operations ====================
 
     /// Store a Squads multisig
@@ -514,8 +787,100 @@ impl Database {
         Ok(())
     }
 
+    /// Get a Squads multisig by address or label
+    pub fn get_squads_multisig(&self, identifier: &str) -> Result<Option<SquadsMultisigRow>> {
+        self.conn
+            .query_row(
+                "SELECT id, multisig_pubkey, label, vault_index, threshold, created_at, updated_at
+                 FROM squads_multisigs WHERE multisig_pubkey = ?1 OR label = ?1",
+                params![identifier],
+                |row| {
+                    Ok(SquadsMultisigRow {
+                        id: row.get(0)?,
+                        multisig_pubkey: row.get(1)?,
+                        label: row.get(2)?,
+                        vault_index: row.get(3)?,
+                        threshold: row.get(4)?,
+                        created_at: row.get(5)?,
+                        updated_at: row.get(6)?,
+                    })
+                },
+            )
+            .optional()
+            .map_err(Into::into)
+    }
+
+    /// Set the default vault index of a Squads multisig
+    pub fn set_squads_vault_index(&self, identifier: &str, vault_index: u32) -> Result<bool> {
+        let affected = self.conn.execute(
+            "UPDATE squads_multisigs SET vault_index = ?2, updated_at = CURRENT_TIMESTAMP
+             WHERE multisig_pubkey = ?1 OR label = ?1",
+            params![identifier, vault_index],
+        )?;
+        Ok(affected > 0)
+    }
+
+    /// Name a vault of a Squads multisig, replacing any earlier name
+    pub fn set_squads_vault_name(
+        &self,
+        identifier: &str,
+        vault_index: u32,
+        name: &str,
+    ) -> Result<()> {
+        let squads_id = self.squads_id(identifier)?;
+        self.conn.execute(
+            "INSERT INTO squads_vaults (multisig_id, vault_index, name) VALUES (?1, ?2, ?3)
+             ON CONFLICT(multisig_id, vault_index) DO UPDATE SET name = excluded.name",
+            params![squads_id, vault_index, name],
+        )?;
+        Ok(())
+    }
+
+    /// Remove a vault name from a Squads multisig
+    pub fn delete_squads_vault_name(&self, identifier: &str, name: &str) -> Result<bool> {
+        let squads_id = self.squads_id(identifier)?;
+        let affected = self.conn.execute(
+            "DELETE FROM squads_vaults WHERE multisig_id = ?1 AND name = ?2",
+            params![squads_id, name],
+        )?;
+        Ok(affected > 0)
+    }
+
+    /// List the named vaults of a Squads multisig
+    pub fn list_squads_vault_names(&self, identifier: &str) -> Result<Vec<SquadsVaultRow>> {
+        let squads_id = self.squads_id(identifier)?;
+        let mut stmt = self.conn.prepare(
+            "SELECT vault_index, name FROM squads_vaults
+             WHERE multisig_id = ?1 ORDER BY vault_index",
+        )?;
+        let rows = stmt.query_map(params![squads_id], |row| {
+            Ok(SquadsVaultRow {
+                vault_index: row.get(0)?,
+                name: row.get(1)?,
+            })
+        })?;
+        rows.collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(Into::into)
+    }
+
+    /// Row ID of a Squads multisig by address or label
+    fn squads_id(&self, identifier: &str) -> Result<i64> {
+        self.conn
+            .query_row(
+                "SELECT id FROM squads_multisigs WHERE multisig_pubkey = ?1 OR label = ?1",
+                params![identifier],
+                |row| row.get(0),
+            )
+            .map_err(|_| Error::AddressNotFound(identifier.into()))
+    }
+
     /// Delete a Squads multisig
     pub fn delete_squads_multisig(&self, identifier: &str) -> Result<bool> {
+        self.conn.execute(
+            "DELETE FROM squads_vaults WHERE multisig_id IN
+                (SELECT id FROM squads_multisigs WHERE multisig_pubkey = ?1 OR label = ?1)",
+            params![identifier],
+        )?;
         let affected = self.conn.execute(
             "DELETE FROM squads_multisigs WHERE multisig_pubkey = ?1 OR label = ?1",
             params![identifier],
@@ -523,6 +888,65 @@ impl Database {
         Ok(affected > 0)
     }
 
+    /// Record a Squads proposal created from this keyring
+    #[allow(clippy::too_many_arguments)]
+    pub fn record_pending_submission(
+        &self,
+        multisig_pubkey: &str,
+        vault_index: u32,
+        transaction_index: u64,
+        proposal_pubkey: &str,
+        creator_pubkey: &str,
+        message_hash: &str,
+        source: Option<&str>,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO squads_pending_submissions
+                (multisig_pubkey, vault_index, transaction_index, proposal_pubkey,
+                 creator_pubkey, message_hash, source)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                multisig_pubkey,
+                vault_index,
+                transaction_index as i64,
+                proposal_pubkey,
+                creator_pubkey,
+                message_hash,
+                source
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// List pending Squads proposals, optionally for one multisig
+    pub fn list_pending_submissions(
+        &self,
+        multisig_pubkey: Option<&str>,
+    ) -> Result<Vec<PendingSubmissionRow>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, multisig_pubkey, vault_index, transaction_index, proposal_pubkey,
+                    creator_pubkey, message_hash, source, created_at
+             FROM squads_pending_submissions
+             WHERE ?1 IS NULL OR multisig_pubkey = ?1
+             ORDER BY multisig_pubkey, transaction_index",
+        )?;
+        let rows = stmt.query_map(params![multisig_pubkey], |row| {
+            Ok(PendingSubmissionRow {
+                id: row.get(0)?,
+                multisig_pubkey: row.get(1)?,
+                vault_index: row.get(2)?,
+                transaction_index: row.get::<_, i64>(3)? as u64,
+                proposal_pubkey: row.get(4)?,
+                creator_pubkey: row.get(5)?,
+                message_hash: row.get(6)?,
+                source: row.get(7)?,
+                created_at: row.get(8)?,
+            })
+        })?;
+        rows.collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(Into::into)
+    }
+
     // ==================== Address Book Operations ====================
 
     /// Add an address to the address book
@@ -557,6 +981,52 @@ impl Database {
             .map_err(Into::into)
     }
 
+    /// Add a tag to an address book entry
+    pub fn add_tag_to_address(&self, identifier: &str, tag: &str) -> Result<()> {
+        let tag_id = self.get_or_create_tag(tag)?;
+
+        let address_id: i64 = self
+            .conn
+            .query_row(
+                "SELECT id FROM address_book WHERE pubkey = ?1 OR label = ?1",
+                params![identifier],
+                |row| row.get(0),
+            )
+            .map_err(|_| Error::AddressNotFound(identifier.into()))?;
+
+        self.conn.execute(
+            "INSERT OR IGNORE INTO address_book_tags (address_id, tag_id) VALUES (?1, ?2)",
+            params![address_id, tag_id],
+        )?;
+
+        Ok(())
+    }
+
+    /// Remove a tag from an address book entry
+    pub fn remove_tag_from_address(&self, identifier: &str, tag: &str) -> Result<bool> {
+        let affected = self.conn.execute(
+            "DELETE FROM address_book_tags
+             WHERE address_id = (SELECT id FROM address_book WHERE pubkey = ?1 OR label = ?1)
+             AND tag_id = (SELECT id FROM tags WHERE name = ?2)",
+            params![identifier, tag],
+        )?;
+        Ok(affected > 0)
+    }
+
+    /// Get tags for an address book entry
+    pub fn get_address_tags(&self, pubkey: &str) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT t.name FROM tags t
+             INNER JOIN address_book_tags at ON t.id = at.tag_id
+             INNER JOIN address_book a ON at.address_id = a.id
+             WHERE a.pubkey = ?1",
+        )?;
+
+        let tags = stmt.query_map(params![pubkey], |row| row.get(0))?;
+        tags.collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(Into::into)
+    }
+
     /// Delete an address from the address book
     pub fn delete_address(&self, identifier: &str) -> Result<bool> {
         let affected = self.conn.execute(
@@ -575,4 +1045,745 @@ impl Database {
         )?;
         Ok(affected > 0)
     }
+
+    // ==================== Spending Limit Operations ====================
+
+    /// Add a spending limit, returning its ID
+    pub fn add_spending_limit(
+        &self,
+        scope: &str,
+        target: &str,
+        max_lamports: u64,
+        window_seconds: u64,
+    ) -> Result<i64> {
+        self.conn.execute(
+            "INSERT INTO spending_limits (scope, target, max_lamports, window_seconds)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![scope, target, max_lamports as i64, window_seconds as i64],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// List all spending limits
+    pub fn list_spending_limits(&self) -> Result<Vec<SpendingLimitRow>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, scope, target, max_lamports, window_seconds, created_at
+             FROM spending_limits ORDER BY scope, target, window_seconds",
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok(SpendingLimitRow {
+                id: row.get(0)?,
+                scope: row.get(1)?,
+                target: row.get(2)?,
+                max_lamports: row.get::<_, i64>(3)? as u64,
+                window_seconds: row.get::<_, i64>(4)? as u64,
+                created_at: row.get(5)?,
+            })
+        })?;
+
+        rows.collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(Into::into)
+    }
+
+    /// Delete a spending limit
+    pub fn delete_spending_limit(&self, id: i64) -> Result<bool> {
+        let affected = self
+            .conn
+            .execute("DELETE FROM spending_limits WHERE id = ?1", params![id])?;
+        Ok(affected > 0)
+    }
+
+    /// Record an outflow from a signer at the given unix timestamp
+    pub fn record_spend(&self, pubkey: &str, lamports: u64, spent_at: i64) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO spending_counters (pubkey, lamports, spent_at) VALUES (?1, ?2, ?3)",
+            params![pubkey, lamports as i64, spent_at],
+        )?;
+        Ok(())
+    }
+
+    /// Total lamports spent by a signer after the given unix timestamp
+    pub fn spent_by_key(&self, pubkey: &str, since: i64) -> Result<u64> {
+        let total: i64 = self.conn.query_row(
+            "SELECT COALESCE(SUM(lamports), 0) FROM spending_counters
+             WHERE pubkey = ?1 AND spent_at > ?2",
+            params![pubkey, since],
+            |row| row.get(0),
+        )?;
+        Ok(total as u64)
+    }
+
+    /// Total lamports spent by all signers with a tag after the given unix timestamp
+    pub fn spent_by_tag(&self, tag: &str, since: i64) -> Result<u64> {
+        let total: i64 = self.conn.query_row(
+            "SELECT COALESCE(SUM(c.lamports), 0) FROM spending_counters c
+             WHERE c.spent_at > ?2 AND c.pubkey IN (
+                 SELECT k.pubkey FROM keypairs k
+                 INNER JOIN keypair_tags kt ON k.id = kt.keypair_id
+                 INNER JOIN tags t ON kt.tag_id = t.id
+                 WHERE t.name = ?1
+                 UNION
+                 SELECT l.pubkey FROM ledger_wallets l
+                 INNER JOIN ledger_tags lt ON l.id = lt.ledger_id
+                 INNER JOIN tags t ON lt.tag_id = t.id
+                 WHERE t.name = ?1
+                 UNION
+                 SELECT y.pubkey FROM yubikey_wallets y
+                 INNER JOIN yubikey_tags yt ON y.id = yt.yubikey_id
+                 INNER JOIN tags t ON yt.tag_id = t.id
+                 WHERE t.name = ?1
+                 UNION
+                 SELECT cs.pubkey FROM custom_signers cs
+                 INNER JOIN custom_signer_tags ct ON cs.id = ct.custom_signer_id
+                 INNER JOIN tags t ON ct.tag_id = t.id
+                 WHERE t.name = ?1
+             )",
+            params![tag, since],
+            |row| row.get(0),
+        )?;
+        Ok(total as u64)
+    }
+
+    /// Delete spending counters recorded at or before the given unix timestamp
+    pub fn prune_spends(&self, before: i64) -> Result<usize> {
+        let affected = self.conn.execute(
+            "DELETE FROM spending_counters WHERE spent_at <= ?1",
+            params![before],
+        )?;
+        Ok(affected)
+    }
+
+    // ==================== Cluster Binding Operations ====================
+
+    /// Bind a signer to a cluster, replacing any existing binding
+    pub fn set_cluster_binding(
+        &self,
+        pubkey: &str,
+        genesis_hash: &str,
+        rpc_url: &str,
+        mode: &str,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO cluster_bindings (pubkey, genesis_hash, rpc_url, mode)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(pubkey) DO UPDATE SET
+                genesis_hash = excluded.genesis_hash,
+                rpc_url = excluded.rpc_url,
+                mode = excluded.mode",
+            params![pubkey, genesis_hash, rpc_url, mode],
+        )?;
+        Ok(())
+    }
+
+    /// Get a signer's cluster binding
+    pub fn get_cluster_binding(&self, pubkey: &str) -> Result<Option<ClusterBindingRow>> {
+        self.conn
+            .query_row(
+                "SELECT pubkey, genesis_hash, rpc_url, mode, created_at
+                 FROM cluster_bindings WHERE pubkey = ?1",
+                params![pubkey],
+                map_cluster_binding,
+            )
+            .optional()
+            .map_err(Into::into)
+    }
+
+    /// List all cluster bindings
+    pub fn list_cluster_bindings(&self) -> Result<Vec<ClusterBindingRow>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT pubkey, genesis_hash, rpc_url, mode, created_at
+             FROM cluster_bindings ORDER BY pubkey",
+        )?;
+
+        let rows = stmt.query_map([], map_cluster_binding)?;
+        rows.collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(Into::into)
+    }
+
+    /// Remove a signer's cluster binding
+    pub fn delete_cluster_binding(&self, pubkey: &str) -> Result<bool> {
+        let affected = self.conn.execute(
+            "DELETE FROM cluster_bindings WHERE pubkey = ?1",
+            params![pubkey],
+        )?;
+        Ok(affected > 0)
+    }
+
+    // ==================== High-Value Key Operations ====================
+
+    /// Designate a high-value key, or change its cool-down
+    pub fn set_high_value_key(&self, pubkey: &str, cooldown_seconds: u64) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO high_value_keys (pubkey, cooldown_seconds) VALUES (?1, ?2)
+             ON CONFLICT(pubkey) DO UPDATE SET cooldown_seconds = excluded.cooldown_seconds",
+            params![pubkey, cooldown_seconds as i64],
+        )?;
+        Ok(())
+    }
+
+    /// Get a high-value key
+    pub fn get_high_value_key(&self, pubkey: &str) -> Result<Option<HighValueKeyRow>> {
+        self.conn
+            .query_row(
+                "SELECT pubkey, cooldown_seconds, last_signed_at, created_at
+                 FROM high_value_keys WHERE pubkey = ?1",
+                params![pubkey],
+                map_high_value_key,
+            )
+            .optional()
+            .map_err(Into::into)
+    }
+
+    /// List all high-value keys
+    pub fn list_high_value_keys(&self) -> Result<Vec<HighValueKeyRow>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT pubkey, cooldown_seconds, last_signed_at, created_at
+             FROM high_value_keys ORDER BY pubkey",
+        )?;
+
+        let rows = stmt.query_map([], map_high_value_key)?;
+        rows.collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(Into::into)
+    }
+
+    /// Record when a high-value key last signed
+    pub fn set_high_value_last_signed(&self, pubkey: &str, signed_at: i64) -> Result<()> {
+        self.conn.execute(
+            "UPDATE high_value_keys SET last_signed_at = ?2 WHERE pubkey = ?1",
+            params![pubkey, signed_at],
+        )?;
+        Ok(())
+    }
+
+    /// Remove a key's high-value designation
+    pub fn delete_high_value_key(&self, pubkey: &str) -> Result<bool> {
+        let affected = self.conn.execute(
+            "DELETE FROM high_value_keys WHERE pubkey = ?1",
+            params![pubkey],
+        )?;
+        Ok(affected > 0)
+    }
+
+    // ==================== Delegation Operations ====================
+
+    /// Add a delegation, returning its ID
+    pub fn add_delegation(
+        &self,
+        bot_pubkey: &str,
+        signer_pubkey: &str,
+        template_hash: &str,
+        nonce_start: u64,
+        nonce_end: u64,
+        created_at: i64,
+    ) -> Result<i64> {
+        self.conn.execute(
+            "INSERT INTO delegations
+             (bot_pubkey, signer_pubkey, template_hash, nonce_start, nonce_end, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                bot_pubkey,
+                signer_pubkey,
+                template_hash,
+                nonce_start as i64,
+                nonce_end as i64,
+                created_at
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// List delegations, optionally only those not revoked
+    pub fn list_delegations(&self, active_only: bool) -> Result<Vec<DelegationRow>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, bot_pubkey, signer_pubkey, template_hash, nonce_start, nonce_end,
+                    last_nonce, created_at, revoked_at
+             FROM delegations
+             WHERE ?1 = 0 OR revoked_at IS NULL
+             ORDER BY id",
+        )?;
+
+        let rows = stmt.query_map(params![active_only], |row| {
+            Ok(DelegationRow {
+                id: row.get(0)?,
+                bot_pubkey: row.get(1)?,
+                signer_pubkey: row.get(2)?,
+                template_hash: row.get(3)?,
+                nonce_start: row.get::<_, i64>(4)? as u64,
+                nonce_end: row.get::<_, i64>(5)? as u64,
+                last_nonce: row.get::<_, Option<i64>>(6)?.map(|n| n as u64),
+                created_at: row.get(7)?,
+                revoked_at: row.get(8)?,
+            })
+        })?;
+
+        rows.collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(Into::into)
+    }
+
+    /// Revoke a delegation by ID
+    pub fn revoke_delegation(&self, id: i64, revoked_at: i64) -> Result<bool> {
+        let affected = self.conn.execute(
+            "UPDATE delegations SET revoked_at = ?2 WHERE id = ?1 AND revoked_at IS NULL",
+            params![id, revoked_at],
+        )?;
+        Ok(affected > 0)
+    }
+
+    /// Revoke every delegation to a bot, returning how many were revoked
+    pub fn revoke_bot_delegations(&self, bot_pubkey: &str, revoked_at: i64) -> Result<usize> {
+        let affected = self.conn.execute(
+            "UPDATE delegations SET revoked_at = ?2
+             WHERE bot_pubkey = ?1 AND revoked_at IS NULL",
+            params![bot_pubkey, revoked_at],
+        )?;
+        Ok(affected)
+    }
+
+    /// Mark a nonce as used if the delegation is active, the nonce is in range
+    /// and higher than any used before
+    ///
+    /// Returns false if the nonce was not accepted.
+    pub fn use_delegation_nonce(&self, id: i64, nonce: u64) -> Result<bool> {
+        let affected = self.conn.execute(
+            "UPDATE delegations SET last_nonce = ?2
+             WHERE id = ?1 AND revoked_at IS NULL
+               AND ?2 BETWEEN nonce_start AND nonce_end
+               AND (last_nonce IS NULL OR last_nonce < ?2)",
+            params![id, nonce as i64],
+        )?;
+        Ok(affected > 0)
+    }
+
+    // ==================== Audit Log Operations ====================
+
+    /// Append an event to the audit log
+    pub fn append_audit_event(
+        &self,
+        event: &str,
+        pubkey: Option<&str>,
+        details: &str,
+        created_at: i64,
+    ) -> Result<i64> {
+        self.conn.execute(
+            "INSERT INTO audit_log (event, pubkey, details, created_at) VALUES (?1, ?2, ?3, ?4)",
+            params![event, pubkey, details, created_at],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// List audit events in order, optionally filtered by event name
+    pub fn list_audit_events(&self, event: Option<&str>) -> Result<Vec<AuditEventRow>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT a.id, a.event, a.pubkey, a.details, a.created_at, c.hash
+             FROM audit_log a LEFT JOIN audit_chain c ON c.event_id = a.id
+             WHERE ?1 IS NULL OR a.event = ?1
+             ORDER BY a.id",
+        )?;
+
+        let rows = stmt.query_map(params![event], |row| {
+            Ok(AuditEventRow {
+                id: row.get(0)?,
+                event: row.get(1)?,
+                pubkey: row.get(2)?,
+                details: row.get(3)?,
+                created_at: row.get(4)?,
+                hash: row.get(5)?,
+            })
+        })?;
+
+        rows.collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(Into::into)
+    }
+
+    /// Store the chain hash of an audit event
+    pub fn set_audit_hash(&self, event_id: i64, hash: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO audit_chain (event_id, hash) VALUES (?1, ?2)",
+            params![event_id, hash],
+        )?;
+        Ok(())
+    }
+
+    /// The newest audit event, as its ID and chain hash
+    pub fn last_audit_event(&self) -> Result<Option<(i64, Option<String>)>> {
+        self.last_audit_event_before(i64::MAX)
+    }
+
+    /// The newest audit event recorded before the given unix timestamp, as
+    /// its ID and chain hash
+    pub fn last_audit_event_before(&self, before: i64) -> Result<Option<(i64, Option<String>)>> {
+        self.conn
+            .query_row(
+                "SELECT a.id, c.hash FROM audit_log a LEFT JOIN audit_chain c ON c.event_id = a.id
+                 WHERE a.created_at < ?1
+                 ORDER BY a.id DESC LIMIT 1",
+                params![before],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()
+            .map_err(Into::into)
+    }
+
+    /// Replace the audit seal
+    pub fn set_audit_seal(&self, seal: &AuditSealRow) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO audit_seal (id, event_id, salt, mac, sealed_at)
+             VALUES (1, ?1, ?2, ?3, ?4)",
+            params![
+                seal.event_id,
+                seal.salt.as_slice(),
+                seal.mac,
+                seal.sealed_at
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// The audit seal, if the log has been sealed
+    ///
+    /// A seal with a malformed salt is returned with a zero salt, so it fails
+    /// verification instead of disappearing.
+    pub fn audit_seal(&self) -> Result<Option<AuditSealRow>> {
+        self.conn
+            .query_row(
+                "SELECT event_id, salt, mac, sealed_at FROM audit_seal WHERE id = 1",
+                [],
+                |row| {
+                    let salt: Vec<u8> = row.get(1)?;
+                    Ok(AuditSealRow {
+                        event_id: row.get(0)?,
+                        salt: salt.try_into().unwrap_or_default(),
+                        mac: row.get(2)?,
+                        sealed_at: row.get(3)?,
+                    })
+                },
+            )
+            .optional()
+            .map_err(Into::into)
+    }
+
+    /// Delete audit events up to and including the given ID
+    pub fn delete_audit_events_through(&self, id: i64) -> Result<usize> {
+        self.conn
+            .execute("DELETE FROM audit_chain WHERE event_id <= ?1", params![id])?;
+        let affected = self
+            .conn
+            .execute("DELETE FROM audit_log WHERE id <= ?1", params![id])?;
+        Ok(affected)
+    }
+
+    /// Run `f` in an immediate transaction, committing if it succeeds
+    ///
+    /// The write lock is taken up front, so read-then-write sequences can't
+    /// interleave with another process's. Inside another transaction, `f`
+    /// runs in a savepoint that commits with the outer one.
+    pub fn immediate<T>(&self, f: impl FnOnce(&Self) -> Result<T>) -> Result<T> {
+        let (begin, commit, rollback) = if self.conn.is_autocommit() {
+            ("BEGIN IMMEDIATE", "COMMIT", "ROLLBACK")
+        } else {
+            (
+                "SAVEPOINT immediate",
+                "RELEASE immediate",
+                "ROLLBACK TO immediate; RELEASE immediate",
+            )
+        };
+        self.conn.execute_batch(begin)?;
+        match f(self) {
+            Ok(value) => {
+                self.conn.execute_batch(commit)?;
+                Ok(value)
+            }
+            Err(e) => {
+                let _ = self.conn.execute_batch(rollback);
+                Err(e)
+            }
+        }
+    }
+
+    // ==================== Signer Usage ====================
+
+    /// Record that a signer produced a signature at the given unix timestamp
+    pub fn record_signer_use(&self, pubkey: &str, used_at: i64) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO signer_usage (pubkey, last_used_at, use_count) VALUES (?1, ?2, 1)
+             ON CONFLICT(pubkey) DO UPDATE SET
+                last_used_at = MAX(last_used_at, excluded.last_used_at),
+                use_count = use_count + 1",
+            params![pubkey, used_at],
+        )?;
+        Ok(())
+    }
+
+    /// Unix timestamp of a signer's last signature, if it has ever signed
+    pub fn signer_last_used(&self, pubkey: &str) -> Result<Option<i64>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT last_used_at FROM signer_usage WHERE pubkey = ?1",
+                params![pubkey],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    // ==================== Unlock Failures ====================
+
+    /// Failed unlock attempts since the last success, if any
+    pub fn unlock_failures(&self) -> Result<Option<UnlockFailuresRow>> {
+        self.conn
+            .query_row(
+                "SELECT failed_attempts, last_failed_at, hard_locked_at
+                 FROM unlock_failures WHERE id = 1",
+                [],
+                |row| {
+                    Ok(UnlockFailuresRow {
+                        failed_attempts: row.get(0)?,
+                        last_failed_at: row.get(1)?,
+                        hard_locked_at: row.get(2)?,
+                    })
+                },
+            )
+            .optional()
+            .map_err(Into::into)
+    }
+
+    /// Count a failed unlock attempt at the given unix timestamp
+    ///
+    /// Returns the updated count.
+    pub fn record_unlock_failure(&self, failed_at: i64) -> Result<u32> {
+        self.conn
+            .query_row(
+                "INSERT INTO unlock_failures (id, failed_attempts, last_failed_at)
+                 VALUES (1, 1, ?1)
+                 ON CONFLICT(id) DO UPDATE SET
+                    failed_attempts = failed_attempts + 1,
+                    last_failed_at = excluded.last_failed_at
+                 RETURNING failed_attempts",
+                params![failed_at],
+                |row| row.get(0),
+            )
+            .map_err(Into::into)
+    }
+
+    /// Mark unlocking as hard-locked from the given unix timestamp
+    pub fn set_unlock_hard_locked(&self, locked_at: i64) -> Result<()> {
+        self.conn.execute(
+            "UPDATE unlock_failures SET hard_locked_at = ?1 WHERE id = 1",
+            params![locked_at],
+        )?;
+        Ok(())
+    }
+
+    /// Forget failed unlock attempts, lifting any delay or hard-lock
+    pub fn clear_unlock_failures(&self) -> Result<bool> {
+        let affected = self.conn.execute("DELETE FROM unlock_failures", [])?;
+        Ok(affected > 0)
+    }
+
+    // ==================== Settings ====================
+
+    /// Get a setting value
+    pub fn get_setting(&self, key: &str) -> Result<Option<String>> {
+        self.conn
+            .query_row(
+                "SELECT value FROM settings WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(Into::into)
+    }
+
+    /// Set a setting value
+    pub fn set_setting(&self, key: &str, value: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO settings (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![key, value],
+        )?;
+        Ok(())
+    }
+
+    /// Delete a setting
+    pub fn delete_setting(&self, key: &str) -> Result<bool> {
+        let affected = self
+            .conn
+            .execute("DELETE FROM settings WHERE key = ?1", params![key])?;
+        Ok(affected > 0)
+    }
+
+    // ==================== Secret Operations ====================
+
+    /// Store a secret, replacing any secret with the same name
+    pub fn store_secret(&self, name: &str, value: &str, master_passphrase: &[u8]) -> Result<()> {
+        let encrypted = encrypt_secret(value.as_bytes(), master_passphrase)?;
+
+        self.conn.execute(
+            "INSERT INTO secrets (name, encrypted_value, encryption_nonce, encryption_salt)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(name) DO UPDATE SET
+                encrypted_value = excluded.encrypted_value,
+                encryption_nonce = excluded.encryption_nonce,
+                encryption_salt = excluded.encryption_salt,
+                updated_at = CURRENT_TIMESTAMP",
+            params![
+                name,
+                encrypted.ciphertext,
+                encrypted.nonce.as_slice(),
+                encrypted.salt.as_slice(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Decrypt a secret by name
+    pub fn load_secret(&self, name: &str, master_passphrase: &[u8]) -> Result<Zeroizing<String>> {
+        let row: Option<(Vec<u8>, Vec<u8>, Vec<u8>)> = self
+            .conn
+            .query_row(
+                "SELECT encrypted_value, encryption_nonce, encryption_salt
+                 FROM secrets WHERE name = ?1",
+                params![name],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()?;
+
+        let (ciphertext, nonce, salt) = row.ok_or_else(|| Error::SecretNotFound(name.into()))?;
+
+        let nonce: [u8; 12] = nonce
+            .try_into()
+            .map_err(|_| Error::Encryption("Invalid nonce".into()))?;
+        let salt: [u8; 32] = salt
+            .try_into()
+            .map_err(|_| Error::Encryption("Invalid salt".into()))?;
+
+        let encrypted = EncryptedData {
+            ciphertext,
+            nonce,
+            salt,
+        };
+        let value = decrypt_secret(&encrypted, master_passphrase)?;
+        String::from_utf8(value).map(Zeroizing::new).map_err(|e| {
+            let mut bytes = e.into_bytes();
+            bytes.zeroize();
+            Error::Encryption("Secret is not valid UTF-8".into())
+        })
+    }
+
+    /// List secret names
+    pub fn list_secrets(&self) -> Result<Vec<SecretRow>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT name, created_at, updated_at FROM secrets ORDER BY name")?;
+        let rows = stmt.query_map([], |row| {
+            Ok(SecretRow {
+                name: row.get(0)?,
+                created_at: row.get(1)?,
+                updated_at: row.get(2)?,
+            })
+        })?;
+        rows.collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(Into::into)
+    }
+
+    /// Delete a secret
+    pub fn delete_secret(&self, name: &str) -> Result<bool> {
+        let affected = self
+            .conn
+            .execute("DELETE FROM secrets WHERE name = ?1", params![name])?;
+        Ok(affected > 0)
+    }
+
+    // ==================== Maintenance ====================
+
+    /// Rebuild the database file, reclaiming free pages
+    pub fn vacuum(&self) -> Result<()> {
+        self.conn.execute_batch("VACUUM")?;
+        Ok(())
+    }
+
+    /// Rebuild all indexes
+    pub fn reindex(&self) -> Result<()> {
+        self.conn.execute_batch("REINDEX")?;
+        Ok(())
+    }
+
+    /// Run SQLite's quick integrity check, failing if the file is damaged
+    pub fn check_integrity(&self) -> Result<()> {
+        let result: String = self
+            .conn
+            .query_row("PRAGMA quick_check(1)", [], |row| row.get(0))?;
+        if result != "ok" {
+            return Err(Error::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_CORRUPT),
+                Some(result),
+            )));
+        }
+        Ok(())
+    }
+
+    /// Database size in bytes (page count times page size)
+    pub fn size_bytes(&self) -> Result<u64> {
+        let size: i64 = self.conn.query_row(
+            "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(size as u64)
+    }
+
+    /// Row counts and on-disk size of every table
+    pub fn table_stats(&self) -> Result<Vec<TableStats>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT m.name, COALESCE(SUM(d.pgsize), 0)
+             FROM sqlite_master m
+             LEFT JOIN sqlite_master i ON i.tbl_name = m.name
+             LEFT JOIN dbstat d ON d.name = i.name
+             WHERE m.type = 'table' AND m.name NOT LIKE 'sqlite_%'
+             GROUP BY m.name
+             ORDER BY m.name",
+        )?;
+        let tables = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as u64))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        tables
+            .into_iter()
+            .map(|(name, bytes)| {
+                // Table names come from sqlite_master, not user input
+                let rows: i64 = self.conn.query_row(
+                    &format!("SELECT COUNT(*) FROM \"{}\"", name.replace('"', "\"\"")),
+                    [],
+                    |row| row.get(0),
+                )?;
+                Ok(TableStats {
+                    name,
+                    rows: rows as u64,
+                    bytes,
+                })
+            })
+            .collect()
+    }
+}
+
+fn map_high_value_key(row: &rusqlite::Row) -> rusqlite::Result<HighValueKeyRow> {
+    Ok(HighValueKeyRow {
+        pubkey: row.get(0)?,
+        cooldown_seconds: row.get::<_, i64>(1)? as u64,
+        last_signed_at: row.get(2)?,
+        created_at: row.get(3)?,
+    })
+}
+
+fn map_cluster_binding(row: &rusqlite::Row) -> rusqlite::Result<ClusterBindingRow> {
+    Ok(ClusterBindingRow {
+        pubkey: row.get(0)?,
+        genesis_hash: row.get(1)?,
+        rpc_url: row.get(2)?,
+        mode: row.get(3)?,
+        created_at: row.get(4)?,
+    })
 }
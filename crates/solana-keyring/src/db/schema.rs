@@ -1,5 +1,7 @@
 //! Database row types
 
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 /// Keypair row from the database.
@@ -25,6 +27,33 @@ pub struct LedgerWalletRow {
     pub created_at: String,
 }
 
+/// YubiKey wallet row from the database.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct YubikeyWalletRow {
+    pub id: i64,
+    pub pubkey: String,
+    pub label: String,
+    pub serial: u32,
+    /// PIV slot, as two hex digits (e.g. "9c")
+    pub slot: String,
+    pub created_at: String,
+}
+
+/// Custom signer row from the database.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomSignerRow {
+    pub id: i64,
+    pub pubkey: String,
+    pub label: String,
+    /// Name of the registered driver that signs for it
+    pub driver: String,
+    /// Driver-specific settings, as described by the driver's schema
+    pub metadata: BTreeMap<String, String>,
+    pub created_at: String,
+}
+
 /// Squads multisig row from the database.
 #[allow(missing_docs)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,6 +67,31 @@ pub struct SquadsMultisigRow {
     pub updated_at: String,
 }
 
+/// Named Squads vault row from the database.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SquadsVaultRow {
+    pub vault_index: u32,
+    pub name: String,
+}
+
+/// Squads proposal awaiting approval or execution.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingSubmissionRow {
+    pub id: i64,
+    pub multisig_pubkey: String,
+    pub vault_index: u32,
+    pub transaction_index: u64,
+    pub proposal_pubkey: String,
+    pub creator_pubkey: String,
+    /// Hex SHA-256 of the proposed message
+    pub message_hash: String,
+    /// File the message was read from
+    pub source: Option<String>,
+    pub created_at: String,
+}
+
 /// Address book row from the database.
 #[allow(missing_docs)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -50,6 +104,116 @@ pub struct AddressBookRow {
     pub updated_at: String,
 }
 
+/// Spending limit row from the database.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpendingLimitRow {
+    pub id: i64,
+    pub scope: String,
+    pub target: String,
+    pub max_lamports: u64,
+    pub window_seconds: u64,
+    pub created_at: String,
+}
+
+/// Audit log event row from the database.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEventRow {
+    pub id: i64,
+    pub event: String,
+    pub pubkey: Option<String>,
+    /// JSON-encoded event details
+    pub details: String,
+    /// Unix timestamp
+    pub created_at: i64,
+    /// Hex-encoded hash chaining the event to the one before it, absent for
+    /// events recorded before chaining
+    pub hash: Option<String>,
+}
+
+/// Audit seal row from the database.
+#[derive(Debug, Clone)]
+pub struct AuditSealRow {
+    /// Newest event covered by the seal
+    pub event_id: i64,
+    /// Salt for deriving the seal key from the master passphrase
+    pub salt: [u8; 32],
+    /// Hex-encoded HMAC of the sealed event's chain hash
+    pub mac: String,
+    /// Unix timestamp
+    pub sealed_at: i64,
+}
+
+/// Delegation row from the database.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DelegationRow {
+    pub id: i64,
+    pub bot_pubkey: String,
+    pub signer_pubkey: String,
+    /// Hex-encoded template hash
+    pub template_hash: String,
+    pub nonce_start: u64,
+    pub nonce_end: u64,
+    /// Highest nonce used so far
+    pub last_nonce: Option<u64>,
+    /// Unix timestamp
+    pub created_at: i64,
+    /// Unix timestamp
+    pub revoked_at: Option<i64>,
+}
+
+/// Cluster binding row from the database.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterBindingRow {
+    pub pubkey: String,
+    pub genesis_hash: String,
+    pub rpc_url: String,
+    /// "refuse" or "warn"
+    pub mode: String,
+    pub created_at: String,
+}
+
+/// High-value key row from the database.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HighValueKeyRow {
+    pub pubkey: String,
+    pub cooldown_seconds: u64,
+    /// Unix timestamp of the last signature
+    pub last_signed_at: Option<i64>,
+    pub created_at: String,
+}
+
+/// Secret row from the database (the value stays encrypted).
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretRow {
+    pub name: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Failed unlock attempts since the last successful unlock.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnlockFailuresRow {
+    pub failed_attempts: u32,
+    pub last_failed_at: i64,
+    pub hard_locked_at: Option<i64>,
+}
+
+/// Storage statistics for a table (including its indexes).
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableStats {
+    pub name: String,
+    pub rows: u64,
+    pub bytes: u64,
+}
+
 /// Tag row from the database.
 #[allow(missing_docs)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -0,0 +1,454 @@
+//! Delegated signing for bots
+//!
+//! A delegation lets a bot key request signatures from a keyring signer
+//! without a confirmation prompt, but only for transactions matching a
+//! template hash (see [`template_hash`]) and only with request nonces inside a
+//! fixed range. Each request is signed by the bot and must use a nonce higher
+//! than the last, so a captured request cannot be replayed and the range caps
+//! how many transactions the bot can ever get signed.
+//!
+//! The bot never holds the signer's key, and revoking a delegation takes effect
+//! on the agent's next request.
+
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde::Serialize;
+
+use crate::audit::AuditLog;
+use crate::db::{Database, DelegationRow};
+use crate::error::{Error, Result};
+use crate::spending::unix_now;
+use crate::transaction::template_hash;
+
+/// Domain separator for delegated signing requests
+const REQUEST_DOMAIN: &[u8] = b"solana-keyring/delegation/v1";
+
+/// Audit log event for granted delegations
+pub const DELEGATION_GRANTED_EVENT: &str = "delegation-granted";
+
+/// Audit log event for revoked delegations
+pub const DELEGATION_REVOKED_EVENT: &str = "delegation-revoked";
+
+/// Audit log event for transactions signed through a delegation
+pub const DELEGATED_SIGNING_EVENT: &str = "delegated-signing";
+
+/// The bytes a bot signs to request a signature
+///
+/// Binds the request to the signer, the nonce and the exact message, so none
+/// of them can be swapped without invalidating the bot's signature.
+pub fn request_message(signer_pubkey: &str, nonce: u64, message_bytes: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(REQUEST_DOMAIN.len() + 44 + 8 + message_bytes.len());
+    out.extend_from_slice(REQUEST_DOMAIN);
+    out.extend_from_slice(&(signer_pubkey.len() as u32).to_le_bytes());
+    out.extend_from_slice(signer_pubkey.as_bytes());
+    out.extend_from_slice(&nonce.to_le_bytes());
+    out.extend_from_slice(message_bytes);
+    out
+}
+
+/// A delegated signing request from a bot
+#[derive(Debug, Clone)]
+pub struct DelegatedRequest<'a> {
+    /// Base58 public key of the bot
+    pub bot_pubkey: &'a str,
+    /// Base58 public key of the signer
+    pub signer_pubkey: &'a str,
+    /// Serialized transaction message
+    pub message_bytes: &'a [u8],
+    /// Request nonce
+    pub nonce: u64,
+    /// Base58 bot signature over [`request_message`]
+    pub signature: &'a str,
+}
+
+impl DelegatedRequest<'_> {
+    /// Whether the bot's signature is valid
+    pub fn verify(&self) -> Result<bool> {
+        let pubkey: [u8; 32] = bs58::decode(self.bot_pubkey)
+            .into_vec()?
+            .try_into()
+            .map_err(|_| Error::Policy("Bot public key must be 32 bytes".into()))?;
+        let signature: [u8; 64] = bs58::decode(self.signature)
+            .into_vec()?
+            .try_into()
+            .map_err(|_| Error::Policy("Bot signature must be 64 bytes".into()))?;
+
+        let key = VerifyingKey::from_bytes(&pubkey)?;
+        let message = request_message(self.signer_pubkey, self.nonce, self.message_bytes);
+        Ok(key
+            .verify(&message, &Signature::from_bytes(&signature))
+            .is_ok())
+    }
+}
+
+#[derive(Serialize)]
+struct DelegationEvent<'a> {
+    id: i64,
+    bot: &'a str,
+    signer: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    template_hash: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    nonce: Option<u64>,
+}
+
+/// Delegation operations
+pub struct Delegations<'a> {
+    db: &'a Database,
+}
+
+impl<'a> Delegations<'a> {
+    /// Create a new delegations handle
+    pub fn new(db: &'a Database) -> Self {
+        Self { db }
+    }
+
+    /// Allow `bot_pubkey` to have `signer_pubkey` sign transactions matching
+    /// `template_hash`, using nonces from `nonce_start` to `nonce_end`
+    /// inclusive
+    pub fn grant(
+        &self,
+        bot_pubkey: &str,
+        signer_pubkey: &str,
+        template_hash: &[u8; 32],
+        nonce_start: u64,
+        nonce_end: u64,
+    ) -> Result<i64> {
+        if nonce_start > nonce_end {
+            return Err(Error::Policy(
+                "Nonce range start must not exceed its end".into(),
+            ));
+        }
+        if nonce_end > i64::MAX as u64 {
+            return Err(Error::Policy(format!(
+                "Nonces must not exceed {}",
+                i64::MAX
+            )));
+        }
+        bs58::decode(bot_pubkey)
+            .into_vec()
+            .ok()
+            .filter(|bytes| bytes.len() == 32)
+            .ok_or_else(|| Error::Policy(format!("Invalid bot public key: {}", bot_pubkey)))?;
+
+        let template_hash = hex::encode(template_hash);
+        let id = self.db.add_delegation(
+            bot_pubkey,
+            signer_pubkey,
+            &template_hash,
+            nonce_start,
+            nonce_end,
+            unix_now(),
+        )?;
+        AuditLog::new(self.db).record(
+            DELEGATION_GRANTED_EVENT,
+            Some(signer_pubkey),
+            &DelegationEvent {
+                id,
+                bot: bot_pubkey,
+                signer: signer_pubkey,
+                template_hash: Some(&template_hash),
+                nonce: None,
+            },
+        )?;
+        Ok(id)
+    }
+
+    /// List delegations, optionally only those not revoked
+    pub fn list(&self, active_only: bool) -> Result<Vec<DelegationRow>> {
+        self.db.list_delegations(active_only)
+    }
+
+    /// Revoke a delegation by ID
+    pub fn revoke(&self, id: i64) -> Result<bool> {
+        let Some(delegation) = self.list(true)?.into_iter().find(|d| d.id == id) else {
+            return Ok(false);
+        };
+        if !self.db.revoke_delegation(id, unix_now())? {
+            return Ok(false);
+        }
+        self.record_revocation(&delegation)?;
+        Ok(true)
+    }
+
+    /// Revoke every delegation to a bot, returning how many were revoked
+    pub fn revoke_bot(&self, bot_pubkey: &str) -> Result<usize> {
+        let revoked: Vec<DelegationRow> = self
+            .list(true)?
+            .into_iter()
+            .filter(|d| d.bot_pubkey == bot_pubkey)
+            .collect();
+        let count = self.db.revoke_bot_delegations(bot_pubkey, unix_now())?;
+        for delegation in &revoked {
+            self.record_revocation(delegation)?;
+        }
+        Ok(count)
+    }
+
+    fn record_revocation(&self, delegation: &DelegationRow) -> Result<()> {
+        AuditLog::new(self.db).record(
+            DELEGATION_REVOKED_EVENT,
+            Some(&delegation.signer_pubkey),
+            &DelegationEvent {
+                id: delegation.id,
+                bot: &delegation.bot_pubkey,
+                signer: &delegation.signer_pubkey,
+                template_hash: None,
+                nonce: None,
+            },
+        )?;
+        Ok(())
+    }
+
+    /// Check a request against the active delegations and consume its nonce
+    ///
+    /// Returns the delegation that authorized the request. Fails if the bot's
+    /// signature is invalid, no active delegation covers the transaction's
+    /// template, or the nonce is out of range or already used.
+    pub fn authorize(&self, request: &DelegatedRequest) -> Result<DelegationRow> {
+        let delegation = self.check(request)?;
+        self.consume(&delegation, request)?;
+        Ok(DelegationRow {
+            last_nonce: Some(request.nonce),
+            ..delegation
+        })
+    }
+
+    /// Check a request against the active delegations without consuming its
+    /// nonce
+    ///
+    /// Returns the delegation that would authorize the request, to
+    /// [`consume`](Self::consume) once the request is signed. Fails like
+    /// [`authorize`](Self::authorize).
+    pub fn check(&self, request: &DelegatedRequest) -> Result<DelegationRow> {
+        if !request.verify()? {
+            return Err(Error::Policy("Invalid bot signature".into()));
+        }
+
+        let template = hex::encode(template_hash(request.message_bytes)?);
+        let candidates: Vec<DelegationRow> = self
+            .list(true)?
+            .into_iter()
+            .filter(|d| {
+                d.bot_pubkey == request.bot_pubkey
+                    && d.signer_pubkey == request.signer_pubkey
+                    && d.template_hash == template
+            })
+            .collect();
+        if candidates.is_empty() {
+            return Err(Error::Policy(format!(
+                "No active delegation to {} for template {}",
+                request.bot_pubkey, template
+            )));
+        }
+
+        candidates
+            .into_iter()
+            .find(|d| {
+                (d.nonce_start..=d.nonce_end).contains(&request.nonce)
+                    && d.last_nonce.is_none_or(|last| last < request.nonce)
+            })
+            .ok_or_else(|| {
+                Error::Policy(format!(
+                    "Nonce {} is out of range or already used",
+                    request.nonce
+                ))
+            })
+    }
+
+    /// Consume a checked request's nonce and record it in the audit log
+    ///
+    /// Both happen in one transaction, so nothing is recorded if the nonce
+    /// was used (or the delegation revoked) since the check.
+    pub fn consume(&self, delegation: &DelegationRow, request: &DelegatedRequest) -> Result<()> {
+        self.db.immediate(|db| {
+            if !db.use_delegation_nonce(delegation.id, request.nonce)? {
+                return Err(Error::Policy(format!(
+                    "Nonce {} is out of range or already used",
+                    request.nonce
+                )));
+            }
+            AuditLog::new(db).record(
+                DELEGATED_SIGNING_EVENT,
+                Some(request.signer_pubkey),
+                &DelegationEvent {
+                    id: delegation.id,
+                    bot: request.bot_pubkey,
+                    signer: request.signer_pubkey,
+                    template_hash: Some(delegation.template_hash.as_str()),
+                    nonce: Some(request.nonce),
+                },
+            )?;
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keypair::generate_keypair;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::instruction::{AccountMeta, Instruction};
+    use solana_sdk::message::Message;
+    use solana_sdk::pubkey::Pubkey;
+
+    fn message(signer: &Pubkey, recipient: &Pubkey, lamports: u64) -> Vec<u8> {
+        let mut data = 2u32.to_le_bytes().to_vec();
+        data.extend_from_slice(&lamports.to_le_bytes());
+        let ix = Instruction::new_with_bytes(
+            Pubkey::default(),
+            &data,
+            vec![
+                AccountMeta::new(*signer, true),
+                AccountMeta::new(*recipient, false),
+            ],
+        );
+        let mut message = Message::new(&[ix], Some(signer));
+        message.recent_blockhash = Hash::new_unique();
+        bincode::serialize(&message).unwrap()
+    }
+
+    #[test]
+    fn test_authorize_and_revoke() {
+        let db = Database::open_in_memory().unwrap();
+        let delegations = Delegations::new(&db);
+        let bot = generate_keypair();
+        let bot_pubkey = bot.pubkey_base58();
+        let signer = Pubkey::new_unique();
+        let signer_pubkey = signer.to_string();
+        let recipient = Pubkey::new_unique();
+
+        let template = message(&signer, &recipient, 1_000);
+        let id = delegations
+            .grant(
+                &bot_pubkey,
+                &signer_pubkey,
+                &template_hash(&template).unwrap(),
+                1,
+                3,
+            )
+            .unwrap();
+
+        let signed = |message_bytes: &[u8], nonce| {
+            bs58::encode(bot.sign(&request_message(&signer_pubkey, nonce, message_bytes)))
+                .into_string()
+        };
+        let authorize = |message_bytes: &[u8], nonce, signature: &str| {
+            delegations.authorize(&DelegatedRequest {
+                bot_pubkey: &bot_pubkey,
+                signer_pubkey: &signer_pubkey,
+                message_bytes,
+                nonce,
+                signature,
+            })
+        };
+
+        // Same template with a fresh blockhash is accepted once per nonce
+        let tx = message(&signer, &recipient, 1_000);
+        assert_eq!(authorize(&tx, 1, &signed(&tx, 1)).unwrap().id, id);
+        assert!(authorize(&tx, 1, &signed(&tx, 1)).is_err());
+        assert!(authorize(&tx, 4, &signed(&tx, 4)).is_err());
+
+        // Signature must cover the nonce and the message
+        assert!(authorize(&tx, 2, &signed(&tx, 3)).is_err());
+        let other = message(&signer, &recipient, 2_000);
+        assert!(authorize(&other, 2, &signed(&other, 2)).is_err());
+
+        assert!(authorize(&tx, 2, &signed(&tx, 2)).is_ok());
+
+        assert!(delegations.revoke(id).unwrap());
+        assert!(!delegations.revoke(id).unwrap());
+        assert!(authorize(&tx, 3, &signed(&tx, 3)).is_err());
+
+        let events = AuditLog::new(&db).list(None).unwrap();
+        let names: Vec<&str> = events.iter().map(|e| e.event.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                DELEGATION_GRANTED_EVENT,
+                DELEGATED_SIGNING_EVENT,
+                DELEGATED_SIGNING_EVENT,
+                DELEGATION_REVOKED_EVENT
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_then_consume() {
+        let db = Database::open_in_memory().unwrap();
+        let delegations = Delegations::new(&db);
+        let bot = generate_keypair();
+        let bot_pubkey = bot.pubkey_base58();
+        let signer = Pubkey::new_unique();
+        let signer_pubkey = signer.to_string();
+        let tx = message(&signer, &Pubkey::new_unique(), 1_000);
+        delegations
+            .grant(
+                &bot_pubkey,
+                &signer_pubkey,
+                &template_hash(&tx).unwrap(),
+                1,
+                3,
+            )
+            .unwrap();
+
+        let signature =
+            bs58::encode(bot.sign(&request_message(&signer_pubkey, 1, &tx))).into_string();
+        let request = DelegatedRequest {
+            bot_pubkey: &bot_pubkey,
+            signer_pubkey: &signer_pubkey,
+            message_bytes: &tx,
+            nonce: 1,
+            signature: &signature,
+        };
+
+        // Checking leaves the nonce and the audit log alone, so a request
+        // that then fails to sign can be retried
+        let delegation = delegations.check(&request).unwrap();
+        assert!(delegations.check(&request).is_ok());
+        assert!(
+            AuditLog::new(&db)
+                .list(Some(DELEGATED_SIGNING_EVENT))
+                .unwrap()
+                .is_empty()
+        );
+
+        delegations.consume(&delegation, &request).unwrap();
+        assert!(delegations.check(&request).is_err());
+
+        // A nonce used since the check records nothing more
+        assert!(delegations.consume(&delegation, &request).is_err());
+        assert_eq!(
+            AuditLog::new(&db)
+                .list(Some(DELEGATED_SIGNING_EVENT))
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_grant_validation() {
+        let db = Database::open_in_memory().unwrap();
+        let delegations = Delegations::new(&db);
+        let bot = generate_keypair().pubkey_base58();
+
+        assert!(delegations.grant(&bot, "signer", &[0; 32], 5, 1).is_err());
+        assert!(
+            delegations
+                .grant(&bot, "signer", &[0; 32], 0, u64::MAX)
+                .is_err()
+        );
+        assert!(
+            delegations
+                .grant("not-a-key", "signer", &[0; 32], 0, 1)
+                .is_err()
+        );
+
+        delegations.grant(&bot, "a", &[0; 32], 0, 1).unwrap();
+        delegations.grant(&bot, "b", &[0; 32], 0, 1).unwrap();
+        assert_eq!(delegations.revoke_bot(&bot).unwrap(), 2);
+        assert!(delegations.list(true).unwrap().is_empty());
+        assert_eq!(delegations.list(false).unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,298 @@
+//! Custom signer types
+//!
+//! Other crates add a signer type, such as an MPC provider, by implementing
+//! [`SignerDriver`] and calling [`register`] before the keyring is used.
+//! Signers of that type are stored generically, as the driver name plus the
+//! string metadata the driver's schema describes, and the agent signs with
+//! them through [`connect`]. A custom build of the agent only needs to
+//! register its drivers before calling `solana_keyring_agent::run`.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
+use crate::db::{CustomSignerRow, Database};
+use crate::error::{Error, Result};
+use crate::signer::Signer;
+
+/// Driver-specific settings of a custom signer, such as a key ID or endpoint
+pub type Metadata = BTreeMap<String, String>;
+
+/// Names of the built-in signer types, which drivers may not take
+const RESERVED_NAMES: &[&str] = &["keypair", "ledger", "yubikey", "squads", "custom"];
+
+static DRIVERS: OnceLock<RwLock<BTreeMap<&'static str, Arc<dyn SignerDriver>>>> = OnceLock::new();
+
+/// A metadata field a driver accepts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MetadataField {
+    /// Key in the signer's metadata
+    pub name: &'static str,
+    /// What the field holds, for help output
+    pub description: &'static str,
+    /// Whether every signer must set it
+    pub required: bool,
+}
+
+impl MetadataField {
+    /// A field every signer must set
+    pub const fn required(name: &'static str, description: &'static str) -> Self {
+        Self {
+            name,
+            description,
+            required: true,
+        }
+    }
+
+    /// A field signers may leave out
+    pub const fn optional(name: &'static str, description: &'static str) -> Self {
+        Self {
+            name,
+            description,
+            required: false,
+        }
+    }
+}
+
+/// A signer type provided by another crate
+///
+/// Drivers never see the keyring passphrase; anything secret belongs with the
+/// provider the driver talks to, not in the metadata.
+pub trait SignerDriver: Send + Sync {
+    /// Unique name stored with each signer, e.g. "acme-mpc"
+    fn name(&self) -> &'static str;
+
+    /// One-line description for help output
+    fn description(&self) -> &'static str;
+
+    /// Metadata fields the driver accepts
+    fn metadata_schema(&self) -> &'static [MetadataField];
+
+    /// Connect to the signer described by `metadata`
+    ///
+    /// Called with metadata already checked against
+    /// [`metadata_schema`](Self::metadata_schema). Blocking is fine; the agent
+    /// calls this off its async runtime.
+    fn connect(&self, metadata: &Metadata) -> Result<Box<dyn Signer + Send>>;
+}
+
+fn drivers() -> &'static RwLock<BTreeMap<&'static str, Arc<dyn SignerDriver>>> {
+    DRIVERS.get_or_init(|| RwLock::new(BTreeMap::new()))
+}
+
+/// Register a signer type
+///
+/// Fails if the name belongs to a built-in type or another driver.
+pub fn register(driver: impl SignerDriver + 'static) -> Result<()> {
+    let name = driver.name();
+    if RESERVED_NAMES.contains(&name) {
+        return Err(Error::Driver(format!(
+            "'{}' is a built-in signer type",
+            name
+        )));
+    }
+
+    let mut drivers = drivers().write().unwrap_or_else(|e| e.into_inner());
+    if drivers.contains_key(name) {
+        return Err(Error::Driver(format!(
+            "Driver '{}' is already registered",
+            name
+        )));
+    }
+    drivers.insert(name, Arc::new(driver));
+    Ok(())
+}
+
+/// Look up a registered driver by name
+pub fn driver(name: &str) -> Option<Arc<dyn SignerDriver>> {
+    drivers()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(name)
+        .cloned()
+}
+
+/// All registered drivers, by name
+pub fn registered() -> Vec<Arc<dyn SignerDriver>> {
+    drivers()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .values()
+        .cloned()
+        .collect()
+}
+
+/// Check metadata against a driver's schema
+///
+/// Every required field must be set and no unknown fields are allowed, so a
+/// typo can't silently fall back to a default.
+pub fn validate_metadata(driver: &dyn SignerDriver, metadata: &Metadata) -> Result<()> {
+    let schema = driver.metadata_schema();
+    for field in schema.iter().filter(|field| field.required) {
+        if !metadata.contains_key(field.name) {
+            return Err(Error::Driver(format!(
+                "{} signers require '{}' ({})",
+                driver.name(),
+                field.name,
+                field.description
+            )));
+        }
+    }
+    if let Some(key) = metadata
+        .keys()
+        .find(|key| !schema.iter().any(|field| field.name == key.as_str()))
+    {
+        return Err(Error::Driver(format!(
+            "{} signers have no '{}' setting",
+            driver.name(),
+            key
+        )));
+    }
+    Ok(())
+}
+
+/// Add a signer of a registered type to the keyring
+///
+/// Connects once to learn the public key, then stores the signer. Returns the
+/// public key.
+pub fn add_signer(
+    db: &Database,
+    driver_name: &str,
+    label: &str,
+    metadata: &Metadata,
+    tags: &[&str],
+) -> Result<String> {
+    let driver = find_driver(driver_name)?;
+    validate_metadata(driver.as_ref(), metadata)?;
+
+    let pubkey = driver.connect(metadata)?.pubkey().to_string();
+    db.store_custom_signer(&pubkey, label, driver_name, metadata, tags)?;
+    Ok(pubkey)
+}
+
+/// Connect to a stored custom signer
+///
+/// Fails if its driver isn't registered in this process, or if the driver
+/// now reports a different public key than the one stored.
+pub fn connect(row: &CustomSignerRow) -> Result<Box<dyn Signer + Send>> {
+    let driver = find_driver(&row.driver)?;
+    validate_metadata(driver.as_ref(), &row.metadata)?;
+
+    let signer = driver.connect(&row.metadata)?;
+    if signer.pubkey() != row.pubkey {
+        return Err(Error::Driver(format!(
+            "{} signer '{}' reports {}, expected {}",
+            row.driver,
+            row.label,
+            signer.pubkey(),
+            row.pubkey
+        )));
+    }
+    Ok(signer)
+}
+
+fn find_driver(name: &str) -> Result<Arc<dyn SignerDriver>> {
+    driver(name).ok_or_else(|| Error::Driver(format!("No driver registered for '{}'", name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keypair::SecureKeypair;
+    use crate::signer::KeypairSigner;
+
+    /// Signs with a keypair derived from the "seed" setting
+    struct SeedDriver;
+
+    impl SignerDriver for SeedDriver {
+        fn name(&self) -> &'static str {
+            "test-seed"
+        }
+
+        fn description(&self) -> &'static str {
+            "Keypair from a repeated seed byte"
+        }
+
+        fn metadata_schema(&self) -> &'static [MetadataField] {
+            const SCHEMA: &[MetadataField] = &[
+                MetadataField::required("seed", "Seed byte"),
+                MetadataField::optional("region", "Provider region"),
+            ];
+            SCHEMA
+        }
+
+        fn connect(&self, metadata: &Metadata) -> Result<Box<dyn Signer + Send>> {
+            let seed: u8 = metadata["seed"]
+                .parse()
+                .map_err(|_| Error::Driver("Invalid seed".into()))?;
+            Ok(Box::new(KeypairSigner::new(SecureKeypair::from_bytes(
+                &[seed; 32],
+            )?)))
+        }
+    }
+
+    fn metadata(pairs: &[(&str, &str)]) -> Metadata {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_custom_signer_roundtrip() {
+        let db = Database::open_in_memory().unwrap();
+        db.initialize(b"passphrase").unwrap();
+        register(SeedDriver).unwrap();
+        assert!(register(SeedDriver).is_err());
+
+        let pubkey = add_signer(
+            &db,
+            "test-seed",
+            "mpc",
+            &metadata(&[("seed", "7")]),
+            &["ops"],
+        )
+        .unwrap();
+        let expected = SecureKeypair::from_bytes(&[7; 32]).unwrap();
+        assert_eq!(pubkey, expected.pubkey_base58());
+
+        let row = db.get_custom_signer("mpc").unwrap().unwrap();
+        assert_eq!(row.driver, "test-seed");
+        assert_eq!(db.list_custom_signers(Some("ops")).unwrap().len(), 1);
+
+        let signer = connect(&row).unwrap();
+        assert_eq!(signer.sign(b"message").unwrap(), expected.sign(b"message"));
+
+        // The driver now reports a different key for this signer
+        let mut moved = row.clone();
+        moved.metadata = metadata(&[("seed", "8")]);
+        assert!(connect(&moved).is_err());
+
+        assert!(db.delete_custom_signer("mpc").unwrap());
+    }
+
+    #[test]
+    fn test_validate_metadata() {
+        assert!(validate_metadata(&SeedDriver, &metadata(&[("seed", "1")])).is_ok());
+        assert!(validate_metadata(&SeedDriver, &metadata(&[("region", "eu")])).is_err());
+        assert!(
+            validate_metadata(&SeedDriver, &metadata(&[("seed", "1"), ("reigon", "eu")])).is_err()
+        );
+
+        struct Reserved;
+        impl SignerDriver for Reserved {
+            fn name(&self) -> &'static str {
+                "ledger"
+            }
+            fn description(&self) -> &'static str {
+                ""
+            }
+            fn metadata_schema(&self) -> &'static [MetadataField] {
+                &[]
+            }
+            fn connect(&self, _: &Metadata) -> Result<Box<dyn Signer + Send>> {
+                Err(Error::Driver("unused".into()))
+            }
+        }
+        assert!(register(Reserved).is_err());
+    }
+}
@@ -1,5 +1,6 @@
 //! Error types for solana-keyring
 
+use solana_keyring_errors::{CodedError, ErrorCode};
 use thiserror::Error;
 
 /// Result type for solana-keyring operations
@@ -24,6 +25,10 @@ pub enum Error {
     #[error("Invalid passphrase")]
     InvalidPassphrase,
 
+    /// Unlocking paused after failed attempts
+    #[error("{0}")]
+    TooManyAttempts(String),
+
     /// Keyring not initialized
     #[error("Keyring not initialized. Run 'solana-keyring new' first")]
     NotInitialized,
@@ -40,6 +45,10 @@ pub enum Error {
     #[error("Address not found: {0}")]
     AddressNotFound(String),
 
+    /// Secret not found
+    #[error("Secret not found: {0}")]
+    SecretNotFound(String),
+
     /// Invalid keypair format
     #[error("Invalid keypair format: {0}")]
     InvalidKeypairFormat(String),
@@ -52,14 +61,50 @@ pub enum Error {
     #[error("Ledger device not connected")]
     LedgerNotConnected,
 
+    /// Custom signer driver error
+    #[error("Signer driver error: {0}")]
+    Driver(String),
+
     /// Squads error
     #[error("Squads error: {0}")]
     Squads(String),
 
+    /// Spending policy error
+    #[error("Policy error: {0}")]
+    Policy(String),
+
+    /// Seed phrase error
+    #[error("Mnemonic error: {0}")]
+    Mnemonic(String),
+
+    /// Secret sharing error
+    #[error("Secret sharing error: {0}")]
+    SecretSharing(String),
+
+    /// Key ceremony error
+    #[error("Ceremony error: {0}")]
+    Ceremony(String),
+
+    /// Offline bundle error
+    #[error("Offline bundle error: {0}")]
+    OfflineBundle(String),
+
     /// Biometric authentication error
     #[error("Biometric error: {0}")]
     Biometric(String),
 
+    /// Solana CLI config error
+    #[error("Solana CLI config error: {0}")]
+    SolanaConfig(String),
+
+    /// RPC endpoint config error
+    #[error("RPC endpoint config error: {0}")]
+    RpcEndpoint(String),
+
+    /// Message catalog error
+    #[error("Locale error: {0}")]
+    Locale(String),
+
     /// IO error
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
@@ -77,6 +122,62 @@ pub enum Error {
     Json(#[from] serde_json::Error),
 }
 
+impl Error {
+    /// The shared error code for this error.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Error::InvalidPassphrase => ErrorCode::InvalidPassphrase,
+            Error::TooManyAttempts(_) => ErrorCode::TooManyAttempts,
+            Error::NotInitialized => ErrorCode::NotInitialized,
+            Error::AlreadyExists(_)
+            | Error::InvalidKeypairFormat(_)
+            | Error::Mnemonic(_)
+            | Error::OfflineBundle(_)
+            | Error::RpcEndpoint(_) => ErrorCode::InvalidArguments,
+            Error::KeypairNotFound(_) => ErrorCode::SignerNotFound,
+            Error::AddressNotFound(_) | Error::SecretNotFound(_) => ErrorCode::NotFound,
+            Error::Ledger(_) | Error::LedgerNotConnected | Error::Driver(_) => {
+                ErrorCode::HardwareError
+            }
+            Error::Policy(_) => ErrorCode::Unauthorized,
+            Error::Biometric(_) => ErrorCode::ConfirmationRequired,
+            Error::Io(_) => ErrorCode::IoError,
+            Error::Database(_)
+            | Error::Encryption(_)
+            | Error::KeyDerivation(_)
+            | Error::Squads(_)
+            | Error::SecretSharing(_)
+            | Error::Ceremony(_)
+            | Error::SolanaConfig(_)
+            | Error::Locale(_)
+            | Error::Solana(_)
+            | Error::Base58(_)
+            | Error::Json(_) => ErrorCode::InternalError,
+        }
+    }
+}
+
+/// The shared error code for any error, such as one wrapped by `anyhow`.
+///
+/// The first [`Error`], [`CodedError`] or I/O error in the source chain
+/// decides the code; anything else is [`ErrorCode::InternalError`].
+pub fn error_code(err: &(dyn std::error::Error + 'static)) -> ErrorCode {
+    let mut current = Some(err);
+    while let Some(err) = current {
+        if let Some(e) = err.downcast_ref::<Error>() {
+            return e.code();
+        }
+        if let Some(e) = err.downcast_ref::<CodedError>() {
+            return e.code();
+        }
+        if err.is::<std::io::Error>() {
+            return ErrorCode::IoError;
+        }
+        current = err.source();
+    }
+    ErrorCode::InternalError
+}
+
 impl From<aes_gcm::Error> for Error {
     fn from(e: aes_gcm::Error) -> Self {
         Error::Encryption(e.to_string())
@@ -94,3 +195,26 @@ impl From<ed25519_dalek::SignatureError> for Error {
         Error::InvalidKeypairFormat(e.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Error)]
+    #[error("Signing failed")]
+    struct Wrapper(#[source] Error);
+
+    #[test]
+    fn test_error_code() {
+        assert_eq!(Error::LedgerNotConnected.code(), ErrorCode::HardwareError);
+        assert_eq!(
+            error_code(&Wrapper(Error::InvalidPassphrase)),
+            ErrorCode::InvalidPassphrase
+        );
+        assert_eq!(
+            error_code(&CodedError::new(ErrorCode::Locked, "Agent is locked")),
+            ErrorCode::Locked
+        );
+        assert_eq!(error_code(&std::fmt::Error), ErrorCode::InternalError);
+    }
+}
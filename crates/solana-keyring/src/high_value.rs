@@ -0,0 +1,184 @@
+//! High-value keys
+//!
+//! A key designated high-value is held to stricter rules than any policy can
+//! relax: the agent signs with it at most once per cool-down period, never
+//! through a delegation or without a prompt, and only after local biometric
+//! authentication and the user typing the transaction's SOL amount. A stolen
+//! session or a compromised chat approver can't drain the key faster than the
+//! cool-down allows, and each signature needs someone at the machine.
+
+use serde::Serialize;
+
+use crate::audit::AuditLog;
+use crate::db::{Database, HighValueKeyRow};
+use crate::error::Result;
+use crate::spending::parse_sol;
+
+/// Default cool-down between signatures (10 minutes)
+pub const DEFAULT_COOLDOWN_SECONDS: u64 = 600;
+
+/// Audit log event for keys designated high-value
+pub const HIGH_VALUE_SET_EVENT: &str = "high-value-set";
+
+/// Audit log event for keys no longer high-value
+pub const HIGH_VALUE_REMOVED_EVENT: &str = "high-value-removed";
+
+/// A high-value key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HighValueKey {
+    /// Public key of the signer
+    pub pubkey: String,
+    /// Minimum time between signatures
+    pub cooldown_seconds: u64,
+    /// Unix timestamp of the last signature
+    pub last_signed_at: Option<i64>,
+}
+
+impl HighValueKey {
+    /// Seconds until the key may sign again, zero if it may sign now
+    pub fn remaining(&self, now: i64) -> u64 {
+        self.last_signed_at.map_or(0, |last| {
+            let ready_at = last.saturating_add(self.cooldown_seconds as i64);
+            ready_at.saturating_sub(now).max(0) as u64
+        })
+    }
+}
+
+impl From<HighValueKeyRow> for HighValueKey {
+    fn from(row: HighValueKeyRow) -> Self {
+        Self {
+            pubkey: row.pubkey,
+            cooldown_seconds: row.cooldown_seconds,
+            last_signed_at: row.last_signed_at,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct HighValueEvent {
+    cooldown_seconds: Option<u64>,
+}
+
+/// High-value key operations
+pub struct HighValueKeys<'a> {
+    db: &'a Database,
+}
+
+impl<'a> HighValueKeys<'a> {
+    /// Create a new high-value keys handle
+    pub fn new(db: &'a Database) -> Self {
+        Self { db }
+    }
+
+    /// Designate a key high-value, or change its cool-down
+    pub fn designate(&self, pubkey: &str, cooldown_seconds: u64) -> Result<()> {
+        self.db.set_high_value_key(pubkey, cooldown_seconds)?;
+        AuditLog::new(self.db).record(
+            HIGH_VALUE_SET_EVENT,
+            Some(pubkey),
+            &HighValueEvent {
+                cooldown_seconds: Some(cooldown_seconds),
+            },
+        )?;
+        Ok(())
+    }
+
+    /// Remove a key's designation
+    pub fn remove(&self, pubkey: &str) -> Result<bool> {
+        if !self.db.delete_high_value_key(pubkey)? {
+            return Ok(false);
+        }
+        AuditLog::new(self.db).record(
+            HIGH_VALUE_REMOVED_EVENT,
+            Some(pubkey),
+            &HighValueEvent {
+                cooldown_seconds: None,
+            },
+        )?;
+        Ok(true)
+    }
+
+    /// A key's designation, if it is high-value
+    pub fn get(&self, pubkey: &str) -> Result<Option<HighValueKey>> {
+        Ok(self.db.get_high_value_key(pubkey)?.map(Into::into))
+    }
+
+    /// All high-value keys
+    pub fn list(&self) -> Result<Vec<HighValueKey>> {
+        Ok(self
+            .db
+            .list_high_value_keys()?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    /// Start a key's cool-down; does nothing for other keys
+    pub fn record_signature(&self, pubkey: &str, now: i64) -> Result<()> {
+        self.db.set_high_value_last_signed(pubkey, now)
+    }
+}
+
+/// Whether a typed amount (e.g. "1.5" or "1.5 SOL") is exactly `lamports`
+pub fn amount_matches(typed: &str, lamports: u64) -> bool {
+    let typed = typed.trim();
+    let typed = typed
+        .strip_suffix("SOL")
+        .or_else(|| typed.strip_suffix("sol"))
+        .unwrap_or(typed);
+    parse_sol(typed.trim()).is_ok_and(|typed| typed == lamports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cooldown() {
+        let db = Database::open_in_memory().unwrap();
+        let keys = HighValueKeys::new(&db);
+
+        keys.designate("Vault111", DEFAULT_COOLDOWN_SECONDS)
+            .unwrap();
+        let key = keys.get("Vault111").unwrap().unwrap();
+        assert_eq!(key.remaining(1_000), 0);
+
+        keys.record_signature("Vault111", 1_000).unwrap();
+        let key = keys.get("Vault111").unwrap().unwrap();
+        assert_eq!(key.remaining(1_000), 600);
+        assert_eq!(key.remaining(1_599), 1);
+        assert_eq!(key.remaining(1_600), 0);
+
+        // Other keys are unaffected
+        keys.record_signature("Other111", 1_000).unwrap();
+        assert!(keys.get("Other111").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_designation_is_audited() {
+        let db = Database::open_in_memory().unwrap();
+        let keys = HighValueKeys::new(&db);
+
+        keys.designate("Vault111", 60).unwrap();
+        keys.designate("Vault111", 120).unwrap();
+        assert_eq!(keys.list().unwrap().len(), 1);
+        assert_eq!(keys.get("Vault111").unwrap().unwrap().cooldown_seconds, 120);
+
+        assert!(keys.remove("Vault111").unwrap());
+        assert!(!keys.remove("Vault111").unwrap());
+
+        let log = AuditLog::new(&db);
+        assert_eq!(log.list(Some(HIGH_VALUE_SET_EVENT)).unwrap().len(), 2);
+        assert_eq!(log.list(Some(HIGH_VALUE_REMOVED_EVENT)).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_amount_matches() {
+        assert!(amount_matches("1.5", 1_500_000_000));
+        assert!(amount_matches(" 1.50 SOL ", 1_500_000_000));
+        assert!(amount_matches("0", 0));
+        assert!(!amount_matches("1.5", 1_500_000_001));
+        assert!(!amount_matches("15", 1_500_000_000));
+        assert!(!amount_matches("", 0));
+    }
+}
@@ -0,0 +1,359 @@
+//! Localization of user-facing strings
+//!
+//! Transaction summaries, approval prompts and CLI confirmations are looked up
+//! by message ID in catalogs under `locales/`, written in a small subset of
+//! Fluent: `id = text` with `{ $name }` placeholders. Messages missing from the
+//! selected locale fall back to English.
+//!
+//! The locale comes from `SOLANA_KEYRING_LOCALE`, then the `locale` setting in
+//! the keyring database, then `LC_ALL`/`LC_MESSAGES`/`LANG`.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::db::Database;
+use crate::error::{Error, Result};
+
+/// Setting holding the preferred locale
+pub const LOCALE_SETTING: &str = "locale";
+
+/// Environment variable overriding the locale
+pub const LOCALE_ENV: &str = "SOLANA_KEYRING_LOCALE";
+
+/// Locale used when nothing else matches, and the source of message IDs
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// Bundled catalogs, with the default locale first
+const CATALOGS: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.ftl")),
+    ("es", include_str!("../locales/es.ftl")),
+];
+
+static LOCALIZER: OnceLock<Localizer> = OnceLock::new();
+
+/// Locales with a bundled catalog
+pub fn available_locales() -> Vec<&'static str> {
+    CATALOGS.iter().map(|(locale, _)| *locale).collect()
+}
+
+/// Match a locale name such as `es_MX.UTF-8` or `es-mx` to a bundled locale
+pub fn negotiate(requested: &str) -> Option<&'static str> {
+    let tag = requested
+        .split(['.', '@'])
+        .next()
+        .unwrap_or_default()
+        .replace('_', "-")
+        .to_lowercase();
+    let language = tag.split('-').next().unwrap_or_default();
+
+    CATALOGS
+        .iter()
+        .map(|(locale, _)| *locale)
+        .find(|locale| *locale == tag)
+        .or_else(|| CATALOGS.iter().map(|(l, _)| *l).find(|l| *l == language))
+}
+
+/// Messages parsed from a catalog
+#[derive(Debug, Clone, Default)]
+pub struct Catalog {
+    messages: HashMap<String, String>,
+    order: Vec<String>,
+}
+
+impl Catalog {
+    /// Parse a catalog
+    ///
+    /// Lines starting with `#` are comments. Indented lines continue the
+    /// previous message on a new line.
+    pub fn parse(source: &str) -> Result<Self> {
+        let mut catalog = Self::default();
+        let mut current: Option<String> = None;
+
+        for (number, line) in source.lines().enumerate() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                current = None;
+                continue;
+            }
+
+            if line.starts_with(char::is_whitespace) {
+                let Some(id) = &current else {
+                    return Err(Error::Locale(format!(
+                        "line {}: continuation without a message",
+                        number + 1
+                    )));
+                };
+                if let Some(text) = catalog.messages.get_mut(id) {
+                    text.push('\n');
+                    text.push_str(trimmed);
+                }
+                continue;
+            }
+
+            let Some((id, text)) = line.split_once('=') else {
+                return Err(Error::Locale(format!(
+                    "line {}: expected `id = text`",
+                    number + 1
+                )));
+            };
+            let id = id.trim().to_string();
+            if catalog.messages.contains_key(&id) {
+                return Err(Error::Locale(format!(
+                    "line {}: duplicate message '{}'",
+                    number + 1,
+                    id
+                )));
+            }
+            catalog.messages.insert(id.clone(), text.trim().to_string());
+            catalog.order.push(id.clone());
+            current = Some(id);
+        }
+
+        Ok(catalog)
+    }
+
+    /// Message text by ID
+    pub fn get(&self, id: &str) -> Option<&str> {
+        self.messages.get(id).map(String::as_str)
+    }
+
+    /// Message IDs in file order
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.order.iter().map(String::as_str)
+    }
+}
+
+/// Looks up messages in a locale, falling back to English
+#[derive(Debug, Clone)]
+pub struct Localizer {
+    locale: &'static str,
+    catalog: Catalog,
+    fallback: Catalog,
+}
+
+impl Localizer {
+    /// Create a localizer for a bundled locale, or English if it isn't bundled
+    pub fn new(locale: &str) -> Self {
+        let locale = negotiate(locale).unwrap_or(DEFAULT_LOCALE);
+        Self {
+            locale,
+            catalog: bundled(locale),
+            fallback: bundled(DEFAULT_LOCALE),
+        }
+    }
+
+    /// The selected locale
+    pub fn locale(&self) -> &'static str {
+        self.locale
+    }
+
+    /// Format a message, substituting `{ $name }` placeholders
+    ///
+    /// Unknown IDs are returned as-is so a missing message is visible rather
+    /// than silently blank.
+    pub fn format(&self, id: &str, args: &[(&str, &dyn Display)]) -> String {
+        let Some(template) = self.catalog.get(id).or_else(|| self.fallback.get(id)) else {
+            return id.to_string();
+        };
+
+        let mut text = template.to_string();
+        for (name, value) in args {
+            text = text.replace(&format!("{{ ${} }}", name), &value.to_string());
+        }
+        text
+    }
+}
+
+fn bundled(locale: &str) -> Catalog {
+    CATALOGS
+        .iter()
+        .find(|(l, _)| *l == locale)
+        .and_then(|(_, source)| Catalog::parse(source).ok())
+        .unwrap_or_default()
+}
+
+/// Messages in the default locale missing from `locale`
+pub fn missing_messages(locale: &str) -> Vec<String> {
+    let source = bundled(DEFAULT_LOCALE);
+    let target = negotiate(locale).map(bundled).unwrap_or_default();
+    source
+        .ids()
+        .filter(|id| target.get(id).is_none())
+        .map(str::to_string)
+        .collect()
+}
+
+/// A catalog template for translators
+///
+/// Lists every English message, or with `locale` only those it is missing,
+/// in catalog syntax ready to translate.
+pub fn extract(locale: Option<&str>) -> String {
+    let source = bundled(DEFAULT_LOCALE);
+    let missing = locale.map(missing_messages);
+
+    let mut out = String::new();
+    for id in source.ids() {
+        if missing.as_ref().is_some_and(|m| !m.iter().any(|x| x == id)) {
+            continue;
+        }
+        let text = source.get(id).unwrap_or_default().replace('\n', "\n    ");
+        out.push_str(&format!("{} = {}\n", id, text));
+    }
+    out
+}
+
+/// The preferred locale for a keyring database
+///
+/// Checks `SOLANA_KEYRING_LOCALE`, the database's `locale` setting, then the
+/// system locale variables. Returns the default locale if none is bundled.
+pub fn resolve_locale(db: Option<&Database>) -> &'static str {
+    let from_env = |var: &str| std::env::var(var).ok().and_then(|v| negotiate(&v));
+
+    from_env(LOCALE_ENV)
+        .or_else(|| {
+            db.and_then(|db| db.get_setting(LOCALE_SETTING).ok().flatten())
+                .and_then(|v| negotiate(&v))
+        })
+        .or_else(|| from_env("LC_ALL"))
+        .or_else(|| from_env("LC_MESSAGES"))
+        .or_else(|| from_env("LANG"))
+        .unwrap_or(DEFAULT_LOCALE)
+}
+
+/// Set the process-wide locale; call once at startup
+///
+/// Later calls have no effect. Without a call, the locale is resolved from
+/// the environment on first use.
+pub fn init(locale: &str) {
+    let _ = LOCALIZER.set(Localizer::new(locale));
+}
+
+/// Set the process-wide locale from the keyring at `db_path`, if it exists
+pub fn init_for_db(db_path: &Path) {
+    let db = db_path
+        .exists()
+        .then(|| Database::open_read_only(db_path).ok())
+        .flatten();
+    init(resolve_locale(db.as_ref()));
+}
+
+/// The process-wide localizer
+pub fn localizer() -> &'static Localizer {
+    LOCALIZER.get_or_init(|| Localizer::new(resolve_locale(None)))
+}
+
+/// A message in the process-wide locale
+pub fn t(id: &str) -> String {
+    localizer().format(id, &[])
+}
+
+/// A message with placeholders in the process-wide locale
+///
+/// ```ignore
+/// t_with("summary-call", &[("program", &"Jupiter")]);
+/// ```
+pub fn t_with(id: &str, args: &[(&str, &dyn Display)]) -> String {
+    localizer().format(id, args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholders(text: &str) -> Vec<&str> {
+        let mut names: Vec<&str> = text
+            .split("{ $")
+            .skip(1)
+            .filter_map(|rest| rest.split_once(" }").map(|(name, _)| name))
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    #[test]
+    fn test_bundled_catalogs_parse() {
+        for (locale, source) in CATALOGS {
+            let catalog = Catalog::parse(source).unwrap();
+            assert!(catalog.ids().count() > 0, "{} is empty", locale);
+        }
+    }
+
+    #[test]
+    fn test_translations_match_english() {
+        let english = bundled(DEFAULT_LOCALE);
+        for locale in available_locales() {
+            let catalog = bundled(locale);
+            for id in catalog.ids() {
+                let source = english
+                    .get(id)
+                    .unwrap_or_else(|| panic!("{}: unknown message '{}'", locale, id));
+                assert_eq!(
+                    placeholders(catalog.get(id).unwrap()),
+                    placeholders(source),
+                    "{}: placeholders differ in '{}'",
+                    locale,
+                    id
+                );
+            }
+            assert!(
+                missing_messages(locale).is_empty(),
+                "{} is incomplete",
+                locale
+            );
+        }
+    }
+
+    #[test]
+    fn test_negotiate() {
+        assert_eq!(negotiate("es_MX.UTF-8"), Some("es"));
+        assert_eq!(negotiate("en-US"), Some("en"));
+        assert_eq!(negotiate("EN"), Some("en"));
+        assert_eq!(negotiate("xx"), None);
+    }
+
+    #[test]
+    fn test_format_with_fallback() {
+        let es = Localizer::new("es");
+        assert_eq!(es.locale(), "es");
+        assert_eq!(
+            es.format("summary-call", &[("program", &"Jupiter")]),
+            "Llamada a Jupiter"
+        );
+
+        let unknown = Localizer::new("xx");
+        assert_eq!(unknown.locale(), DEFAULT_LOCALE);
+        assert_eq!(unknown.format("confirm-cancelled", &[]), "Cancelled.");
+        assert_eq!(unknown.format("no-such-message", &[]), "no-such-message");
+    }
+
+    #[test]
+    fn test_parse_continuation_and_errors() {
+        let catalog = Catalog::parse("a = one\n  two\nb = three").unwrap();
+        assert_eq!(catalog.get("a"), Some("one\ntwo"));
+        assert_eq!(catalog.ids().collect::<Vec<_>>(), vec!["a", "b"]);
+
+        assert!(Catalog::parse("  orphan").is_err());
+        assert!(Catalog::parse("no equals sign").is_err());
+        assert!(Catalog::parse("a = 1\na = 2").is_err());
+    }
+
+    #[test]
+    fn test_resolve_locale_from_setting() {
+        let db = Database::open_in_memory().unwrap();
+        db.set_setting(LOCALE_SETTING, "es").unwrap();
+        // The environment variable takes precedence when set
+        if std::env::var_os(LOCALE_ENV).is_none() {
+            assert_eq!(resolve_locale(Some(&db)), "es");
+        }
+    }
+
+    #[test]
+    fn test_extract() {
+        let template = extract(None);
+        assert!(template.contains("confirm-cancelled = Cancelled.\n"));
+        assert!(extract(Some("es")).is_empty());
+        assert_eq!(extract(Some("xx")), template);
+    }
+}
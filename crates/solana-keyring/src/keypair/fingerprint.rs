@@ -0,0 +1,58 @@
+//! Short, human-friendly public key fingerprints
+//!
+//! A fingerprint is the first 8 characters of the base58 public key followed
+//! by four emoji derived from its SHA-256 hash, e.g. `7xKXtg2C 🦊🌵🚀🍉`. Two
+//! keys with the same prefix almost never share the emoji, so a substituted
+//! key stands out at a glance.
+
+use sha2::{Digest, Sha256};
+
+/// Number of base58 characters shown in a fingerprint
+const PREFIX_LEN: usize = 8;
+
+/// Number of emoji shown in a fingerprint
+const EMOJI_COUNT: usize = 4;
+
+/// 64 visually distinct emoji, indexed by 6 bits of the hash each
+const EMOJI: [char; 64] = [
+    '🐶', '🐱', '🦊', '🐻', '🐼', '🐨', '🐯', '🦁', '🐮', '🐷', '🐸', '🐵', '🐔', '🐧', '🐦', '🦉',
+    '🐴', '🦄', '🐝', '🐛', '🦋', '🐌', '🐞', '🐢', '🐍', '🐙', '🦀', '🐬', '🐳', '🦈', '🐘', '🦒',
+    '🌵', '🌲', '🌴', '🍀', '🍁', '🍄', '🌻', '🌹', '🌙', '🌞', '🌟', '🔥', '🌈', '💧', '🍕', '🍎',
+    '🍋', '🍉', '🍇', '🍓', '🍒', '🥑', '🥕', '🌽', '🍩', '🎲', '🎸', '🚀', '🎈', '🔑', '🔔', '💎',
+];
+
+/// Compute the fingerprint of a base58 public key
+pub fn fingerprint(pubkey: &str) -> String {
+    let hash = Sha256::digest(pubkey.as_bytes());
+    let bits = u32::from_be_bytes([0, hash[0], hash[1], hash[2]]);
+
+    let emoji: String = (0..EMOJI_COUNT)
+        .map(|i| EMOJI[((bits >> (6 * (EMOJI_COUNT - 1 - i))) & 0x3f) as usize])
+        .collect();
+    let prefix: String = pubkey.chars().take(PREFIX_LEN).collect();
+
+    format!("{} {}", prefix, emoji)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint_format() {
+        let pubkey = "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU";
+        let fp = fingerprint(pubkey);
+
+        assert!(fp.starts_with("7xKXtg2C "));
+        assert_eq!(fp.chars().count(), PREFIX_LEN + 1 + EMOJI_COUNT);
+        assert_eq!(fp, fingerprint(pubkey));
+    }
+
+    #[test]
+    fn test_same_prefix_differs() {
+        assert_ne!(
+            fingerprint("7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU"),
+            fingerprint("7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsV")
+        );
+    }
+}
@@ -0,0 +1,156 @@
+//! BIP39 seed phrases
+//!
+//! Keypairs are derived exactly as `solana-keygen new` and `solana-keygen
+//! recover` do without a derivation path: the first 32 bytes of the BIP39 seed
+//! (PBKDF2 over the phrase with salt `"mnemonic" + passphrase`) are the ed25519
+//! secret key.
+
+use bip39::{Language, Mnemonic};
+use rand::RngCore;
+use zeroize::Zeroizing;
+
+use super::SecureKeypair;
+use crate::error::{Error, Result};
+
+/// Languages tried when parsing a seed phrase, in `solana-keygen` order
+const LANGUAGES: [Language; 8] = [
+    Language::English,
+    Language::SimplifiedChinese,
+    Language::TraditionalChinese,
+    Language::Japanese,
+    Language::Spanish,
+    Language::Korean,
+    Language::French,
+    Language::Italian,
+];
+
+/// Generate a random English seed phrase (12, 15, 18, 21 or 24 words)
+pub fn generate_mnemonic(word_count: usize) -> Result<Zeroizing<String>> {
+    if !(12..=24).contains(&word_count) || !word_count.is_multiple_of(3) {
+        return Err(Error::Mnemonic(format!(
+            "Word count must be 12, 15, 18, 21 or 24, got {}",
+            word_count
+        )));
+    }
+
+    let mut entropy = Zeroizing::new(vec![0u8; word_count * 4 / 3]);
+    rand::thread_rng().fill_bytes(&mut entropy);
+    mnemonic_from_entropy(&entropy)
+}
+
+/// Build a seed phrase from 16-32 bytes of entropy (12-24 words)
+pub fn mnemonic_from_entropy(entropy: &[u8]) -> Result<Zeroizing<String>> {
+    let mnemonic = Mnemonic::from_entropy_in(Language::English, entropy)
+        .map_err(|e| Error::Mnemonic(e.to_string()))?;
+    Ok(Zeroizing::new(mnemonic.to_string()))
+}
+
+/// Recover the entropy encoded by a seed phrase
+pub fn mnemonic_to_entropy(phrase: &str) -> Result<Zeroizing<Vec<u8>>> {
+    let mnemonic = parse(phrase)?;
+    Ok(Zeroizing::new(mnemonic.to_entropy()))
+}
+
+/// Derive a keypair from a validated seed phrase and optional BIP39 passphrase
+///
+/// Matches `solana-keygen recover`: whitespace is collapsed, the phrase must be
+/// a valid BIP39 mnemonic in a supported language, and the passphrase is
+/// NFKD-normalized.
+pub fn keypair_from_mnemonic(phrase: &str, passphrase: &str) -> Result<SecureKeypair> {
+    keypair_from_seed(seed_from_mnemonic(phrase, passphrase)?.as_slice())
+}
+
+/// The 64-byte BIP39 seed of a validated seed phrase, for HD derivation
+pub fn seed_from_mnemonic(phrase: &str, passphrase: &str) -> Result<Zeroizing<[u8; 64]>> {
+    Ok(Zeroizing::new(parse(phrase)?.to_seed(passphrase)))
+}
+
+/// Derive a keypair from a seed phrase without validating it
+///
+/// Matches `solana-keygen recover --skip-seed-phrase-validation`: the trimmed
+/// phrase and passphrase are used as-is, so any phrase yields a keypair.
+pub fn keypair_from_seed_phrase_unchecked(phrase: &str, passphrase: &str) -> Result<SecureKeypair> {
+    keypair_from_seed(&seed_from_seed_phrase_unchecked(phrase, passphrase))
+}
+
+/// The BIP39 seed of a seed phrase without validating it, for HD derivation
+pub fn seed_from_seed_phrase_unchecked(phrase: &str, passphrase: &str) -> Zeroizing<Vec<u8>> {
+    Zeroizing::new(
+        solana_seed_phrase::generate_seed_from_seed_phrase_and_passphrase(
+            phrase.trim(),
+            passphrase,
+        ),
+    )
+}
+
+fn keypair_from_seed(seed: &[u8]) -> Result<SecureKeypair> {
+    let mut secret = Zeroizing::new([0u8; 32]);
+    secret.copy_from_slice(&seed[..32]);
+    SecureKeypair::from_bytes(&secret)
+}
+
+fn parse(phrase: &str) -> Result<Mnemonic> {
+    let sanitized = Zeroizing::new(phrase.split_whitespace().collect::<Vec<_>>().join(" "));
+    LANGUAGES
+        .iter()
+        .find_map(|&language| Mnemonic::parse_in(language, sanitized.as_str()).ok())
+        .ok_or_else(|| Error::Mnemonic("Invalid seed phrase".into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bip39_vector() {
+        // BIP39 test vector: 32 zero bytes, passphrase "TREZOR"
+        let phrase = mnemonic_from_entropy(&[0u8; 32]).unwrap();
+        assert!(phrase.starts_with("abandon abandon"));
+        assert!(phrase.ends_with(" art"));
+
+        let keypair = keypair_from_mnemonic(&phrase, "TREZOR").unwrap();
+        assert_eq!(
+            hex::encode(*keypair.secret_bytes()),
+            "bda85446c68413707090a52022edd26a1c9462295029f2e60cd7c4f2bbd30971"
+        );
+    }
+
+    #[test]
+    fn test_entropy_roundtrip() {
+        let entropy = [7u8; 32];
+        let phrase = mnemonic_from_entropy(&entropy).unwrap();
+        assert_eq!(phrase.split_whitespace().count(), 24);
+        assert_eq!(&*mnemonic_to_entropy(&phrase).unwrap(), &entropy);
+        assert!(mnemonic_to_entropy("abandon abandon").is_err());
+    }
+
+    #[test]
+    fn test_generate_word_counts() {
+        for count in [12, 15, 18, 21, 24] {
+            let phrase = generate_mnemonic(count).unwrap();
+            assert_eq!(phrase.split_whitespace().count(), count);
+        }
+        assert!(generate_mnemonic(13).is_err());
+        assert!(generate_mnemonic(27).is_err());
+    }
+
+    #[test]
+    fn test_validated_and_unchecked_agree() {
+        let phrase = generate_mnemonic(12).unwrap();
+        let messy = format!("  {}\n", phrase.replace(' ', "   "));
+
+        let validated = keypair_from_mnemonic(&messy, "hunter2").unwrap();
+        let unchecked = keypair_from_seed_phrase_unchecked(&phrase, "hunter2").unwrap();
+        assert_eq!(validated.pubkey_bytes(), unchecked.pubkey_bytes());
+
+        // The passphrase changes the address
+        let other = keypair_from_mnemonic(&phrase, "").unwrap();
+        assert_ne!(validated.pubkey_bytes(), other.pubkey_bytes());
+    }
+
+    #[test]
+    fn test_unchecked_accepts_any_phrase() {
+        assert!(keypair_from_mnemonic("not a real seed phrase", "").is_err());
+        assert!(keypair_from_seed_phrase_unchecked("not a real seed phrase", "").is_ok());
+    }
+}
@@ -1,17 +1,26 @@
 //! Keypair management
 
 mod export;
+mod fingerprint;
 mod generate;
 mod import;
+mod mnemonic;
 
 pub use export::{export_base58, export_json};
+pub use fingerprint::fingerprint;
 pub use generate::generate_keypair;
 pub use import::{import_base58, import_json};
+pub use mnemonic::{
+    generate_mnemonic, keypair_from_mnemonic, keypair_from_seed_phrase_unchecked,
+    mnemonic_from_entropy, mnemonic_to_entropy, seed_from_mnemonic,
+    seed_from_seed_phrase_unchecked,
+};
 
 use ed25519_dalek::{SigningKey, VerifyingKey};
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
-use crate::error::Result;
+use crate::crypto::{EncryptedData, decrypt_secret, encrypt_secret};
+use crate::error::{Error, Result};
 
 /// A keypair that zeroizes its secret on drop
 #[derive(ZeroizeOnDrop)]
@@ -51,6 +60,11 @@ impl SecureKeypair {
         bs58::encode(self.pubkey.as_bytes()).into_string()
     }
 
+    /// Get the short human-friendly fingerprint of the public key
+    pub fn fingerprint(&self) -> String {
+        fingerprint(&self.pubkey_base58())
+    }
+
     /// Sign a message
     pub fn sign(&self, message: &[u8]) -> [u8; 64] {
         use ed25519_dalek::Signer;
@@ -62,6 +76,24 @@ impl SecureKeypair {
         zeroize::Zeroizing::new(self.secret.to_bytes())
     }
 
+    /// Encrypt the secret under a master passphrase
+    ///
+    /// Derives the key with Argon2id, so this is slow; it needs no database.
+    pub fn encrypt(&self, master_passphrase: &[u8]) -> Result<EncryptedData> {
+        encrypt_secret(&self.secret_bytes()[..], master_passphrase)
+    }
+
+    /// Decrypt a secret encrypted by [`Self::encrypt`]
+    pub fn decrypt(encrypted: &EncryptedData, master_passphrase: &[u8]) -> Result<Self> {
+        let secret_bytes = zeroize::Zeroizing::new(decrypt_secret(encrypted, master_passphrase)?);
+        Self::from_bytes(
+            secret_bytes
+                .as_slice()
+                .try_into()
+                .map_err(|_| Error::InvalidKeypairFormat("Wrong key size".into()))?,
+        )
+    }
+
     /// Convert to solana-sdk Keypair
     pub fn to_solana_keypair(&self) -> solana_sdk::signer::keypair::Keypair {
         let secret = self.secret_bytes();
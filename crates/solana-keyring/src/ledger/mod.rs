@@ -2,8 +2,13 @@
 
 mod transport;
 
+use std::time::{Duration, Instant};
+
 use crate::error::{Error, Result};
 
+/// How often [`wait_for_device`] checks for a newly attached device
+pub const HOTPLUG_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
 /// Ledger signer for hardware wallet operations
 pub struct LedgerSigner {
     derivation_path: Vec<u32>,
@@ -15,7 +20,7 @@ impl LedgerSigner {
     /// Connect to a Ledger device and get the public key for the given derivation path
     pub fn connect(derivation_path: &str) -> Result<Self> {
         let path = parse_derivation_path(derivation_path)?;
-        let pubkey = transport::get_pubkey(&path)?;
+        let pubkey = transport::get_pubkey(&path, false)?;
         let pubkey_str = bs58::encode(&pubkey).into_string();
 
         Ok(Self {
@@ -35,6 +40,21 @@ impl LedgerSigner {
         &self.pubkey
     }
 
+    /// Show the address on the Ledger screen and wait for the user to approve it
+    ///
+    /// Fails if the user rejects it or the device now derives a different key.
+    pub fn confirm_pubkey_on_device(&self) -> Result<()> {
+        let confirmed = transport::get_pubkey(&self.derivation_path, true)?;
+        if confirmed != self.pubkey {
+            return Err(Error::Ledger(format!(
+                "Device confirmed {}, expected {}",
+                bs58::encode(&confirmed).into_string(),
+                self.pubkey_str
+            )));
+        }
+        Ok(())
+    }
+
     /// Sign a message using the Ledger device
     pub fn sign(&self, message: &[u8]) -> Result<[u8; 64]> {
         transport::sign_message(&self.derivation_path, message)
@@ -46,6 +66,30 @@ impl LedgerSigner {
     }
 }
 
+/// Whether a Ledger device is attached
+///
+/// The device may still be locked or have another app open.
+pub fn is_connected() -> bool {
+    transport::device_connected().unwrap_or(false)
+}
+
+/// Block until a Ledger device is attached, or `timeout` elapses
+///
+/// HID has no portable hotplug events, so the device list is polled every
+/// [`HOTPLUG_POLL_INTERVAL`]. Returns whether a device appeared.
+pub fn wait_for_device(timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if is_connected() {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(HOTPLUG_POLL_INTERVAL);
+    }
+}
+
 /// Parse a derivation path string like "44'/501'/0'/0'"
 fn parse_derivation_path(path: &str) -> Result<Vec<u32>> {
     let mut components = vec![];
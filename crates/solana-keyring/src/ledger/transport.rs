@@ -6,13 +6,22 @@ use crate::error::{Error, Result};
 const SOLANA_CLA: u8 = 0xE0;
 const INS_GET_PUBKEY: u8 = 0x05;
 const INS_SIGN_MESSAGE: u8 = 0x06;
+const P1_NON_CONFIRM: u8 = 0x00;
+const P1_CONFIRM: u8 = 0x01;
+
+// Status word of a request the user rejected on the device
+const SW_USER_REJECTED: u16 = 0x6985;
 
 /// Get the public key from the Ledger device
-pub fn get_pubkey(derivation_path: &[u32]) -> Result<[u8; 32]> {
+///
+/// With `confirm`, the device shows the address and only answers once the
+/// user approves it.
+pub fn get_pubkey(derivation_path: &[u32], confirm: bool) -> Result<[u8; 32]> {
     let transport = open_device()?;
     let data = serialize_derivation_path(derivation_path);
 
-    let response = exchange_apdu(&transport, SOLANA_CLA, INS_GET_PUBKEY, 0x00, 0x00, &data)?;
+    let p1 = if confirm { P1_CONFIRM } else { P1_NON_CONFIRM };
+    let response = exchange_apdu(&transport, SOLANA_CLA, INS_GET_PUBKEY, p1, 0x00, &data)?;
 
     if response.len() < 32 {
         return Err(Error::Ledger("Invalid public key response".into()));
@@ -56,13 +65,21 @@ pub fn sign_message(derivation_path: &[u32], message: &[u8]) -> Result<[u8; 64]>
     Ok(sig)
 }
 
+/// Ledger vendor ID
+const LEDGER_VID: u16 = 0x2c97;
+
+/// Whether a Ledger device is attached
+pub fn device_connected() -> Result<bool> {
+    let api = hidapi::HidApi::new().map_err(|e| Error::Ledger(e.to_string()))?;
+    Ok(api
+        .device_list()
+        .any(|device| device.vendor_id() == LEDGER_VID))
+}
+
 /// Open the Ledger device
 fn open_device() -> Result<hidapi::HidDevice> {
     let api = hidapi::HidApi::new().map_err(|e| Error::Ledger(e.to_string()))?;
 
-    // Ledger vendor ID
-    const LEDGER_VID: u16 = 0x2c97;
-
     for device in api.device_list() {
         if device.vendor_id() == LEDGER_VID
             && let Ok(dev) = api.open_path(device.path())
@@ -134,6 +151,9 @@ fn exchange_apdu(
     let data_end = 7 + data_len - 2;
     let sw = ((response[data_end] as u16) << 8) | (response[data_end + 1] as u16);
 
+    if sw == SW_USER_REJECTED {
+        return Err(Error::Ledger("Rejected on the device".into()));
+    }
     if sw != 0x9000 {
         return Err(Error::Ledger(format!("Ledger error: 0x{:04X}", sw)));
     }
@@ -2,22 +2,60 @@
 //!
 //! This library provides encrypted storage for Solana keypairs with support for:
 //! - Local keypairs with row-level AES-256-GCM encryption
+//! - Short public key fingerprints for spotting key substitution
 //! - Ledger hardware wallet integration
+//! - YubiKey PIV keys, stored by device serial and slot
 //! - Squads multisig support
+//! - Custom signer types registered by other crates, such as MPC providers
 //! - Address book with labels
+//! - Rolling-window spending limits
+//! - Destination allowlist learned from the address book
+//! - Cluster bindings that catch keys signing for the wrong environment
+//! - High-value keys with a signing cool-down and typed amount confirmation
+//! - Policy dry runs that explain which rule decides a transaction, and linting
+//! - Delegated signing for bots, limited to a template and nonce range
+//! - Key ceremonies with seed phrase and Shamir backups
+//! - Audit log of security-relevant events
+//! - Escalating delays and an optional hard-lock after failed unlock attempts
+//! - Database maintenance (vacuum, pruning, size reporting)
 //! - Biometric authentication (TouchID on macOS)
 //! - Transaction parsing and user confirmation
+//! - Signed transaction export as base64, base58 or RPC-style JSON
+//! - Shared terminal output styling (colors, symbols, NO_COLOR)
+//! - Spinners with elapsed time for long CLI operations
+//! - Localized transaction summaries and confirmation prompts
+//! - Reading cluster settings and the default key from the Solana CLI config
+//! - Encrypted secrets, such as RPC provider API keys, and per-endpoint auth
 
 // Allow missing error/panic docs for internal library - errors are clear from context
 #![allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
 
+pub mod allowlist;
+pub mod audit;
+pub mod backup;
 pub mod biometric;
+pub mod ceremony;
+pub mod cluster;
 pub mod crypto;
 pub mod db;
+pub mod delegation;
+pub mod driver;
+pub mod high_value;
+pub mod i18n;
 pub mod keypair;
 pub mod ledger;
+pub mod lockout;
+pub mod maintenance;
+pub mod offline;
+pub mod output;
+pub mod policy;
+pub mod progress;
+pub mod rpc_endpoints;
+pub mod solana_config;
+pub mod spending;
 pub mod squads;
 pub mod transaction;
+pub mod tx_encoding;
 
 mod address_book;
 mod error;
@@ -26,7 +64,7 @@ mod signer;
 
 pub use address_book::AddressBook;
 pub use db::Database;
-pub use error::{Error, Result};
+pub use error::{Error, Result, error_code};
 pub use keypair::SecureKeypair;
 pub use notification::notify;
 pub use signer::{
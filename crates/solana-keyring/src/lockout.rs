@@ -0,0 +1,381 @@
+//! Soft-lock after repeated failed unlock attempts
+//!
+//! Every wrong passphrase sent to the agent is counted in the
+//! `unlock_failures` table, so the count survives agent restarts. After a few
+//! free attempts, each further attempt must wait for an escalating delay
+//! before the passphrase is checked at all, which slows down guessing against
+//! the Argon2 hash through the socket. The owner is notified once failures
+//! reach [`NOTIFY_AFTER`].
+//!
+//! Optionally, unlocking can be hard-locked after a number of failures. A
+//! hard-lock isn't lifted by waiting or by the right passphrase, only by a
+//! recovery code generated beforehand with [`set_recovery_code`]. Recovery
+//! codes are single-use; hard-locking stays off until a new one is generated.
+//!
+//! A successful unlock clears the count.
+
+use rand::RngCore;
+use serde::Serialize;
+
+use crate::audit::AuditLog;
+use crate::crypto::{hash_password, verify_password};
+use crate::db::Database;
+use crate::error::{Error, Result};
+
+/// Failed attempts allowed before delays start
+pub const FREE_ATTEMPTS: u32 = 3;
+
+/// Delay after the first attempt past the free ones, doubled each time
+pub const BASE_DELAY_SECS: u64 = 2;
+
+/// Longest delay between attempts
+pub const MAX_DELAY_SECS: u64 = 15 * 60;
+
+/// Failed attempts after which the owner is notified
+pub const NOTIFY_AFTER: u32 = 5;
+
+/// Setting holding the salted hash of the recovery code
+pub const RECOVERY_CODE_SETTING: &str = "unlock_recovery_code";
+
+/// Setting holding the number of failures that hard-locks unlocking
+pub const HARD_LOCK_SETTING: &str = "unlock_hard_lock_after";
+
+/// Audit log event for failed unlock attempts
+pub const UNLOCK_FAILED_EVENT: &str = "unlock-failed";
+
+/// Audit log event for the failure that hard-locked unlocking
+pub const UNLOCK_HARD_LOCKED_EVENT: &str = "unlock-hard-locked";
+
+/// Audit log event for a new recovery code
+pub const RECOVERY_CODE_SET_EVENT: &str = "recovery-code-set";
+
+/// Audit log event for a hard-lock lifted with the recovery code
+pub const RECOVERY_CODE_USED_EVENT: &str = "recovery-code-used";
+
+#[derive(Serialize)]
+struct LockoutEvent {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    attempts: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hard_lock_after: Option<u32>,
+}
+
+/// Whether an unlock attempt may be checked right now
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnlockGate {
+    /// The passphrase may be checked
+    Open,
+    /// Too many recent failures; try again after this many seconds
+    Delayed {
+        /// Seconds until the next attempt is accepted
+        retry_in: u64,
+    },
+    /// Hard-locked until a recovery code is used
+    HardLocked,
+}
+
+impl UnlockGate {
+    /// The error refusing the attempt, if it is refused
+    pub fn check(self) -> Result<()> {
+        match self {
+            UnlockGate::Open => Ok(()),
+            UnlockGate::Delayed { retry_in } => Err(Error::TooManyAttempts(format!(
+                "Too many failed unlock attempts; try again in {}s",
+                retry_in
+            ))),
+            UnlockGate::HardLocked => Err(Error::TooManyAttempts(
+                "Unlocking is hard-locked after repeated failed attempts; \
+                 run 'solana-keyring lockout recover' with the recovery code"
+                    .to_string(),
+            )),
+        }
+    }
+}
+
+/// What happened after a failed attempt was counted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FailedUnlock {
+    /// Failed attempts since the last success
+    pub attempts: u32,
+    /// Whether this failure should be reported to the owner
+    pub notify: bool,
+    /// Whether this failure hard-locked unlocking
+    pub hard_locked: bool,
+}
+
+/// Current lockout state, for status output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockoutStatus {
+    /// Failed attempts since the last success
+    pub failed_attempts: u32,
+    /// Whether the next attempt is accepted
+    pub gate: UnlockGate,
+    /// Failures that hard-lock unlocking, if enabled
+    pub hard_lock_after: Option<u32>,
+}
+
+/// Delay before the attempt following `attempts` failures
+pub fn delay_after(attempts: u32) -> u64 {
+    if attempts < FREE_ATTEMPTS {
+        return 0;
+    }
+    let doublings = (attempts - FREE_ATTEMPTS).min(32);
+    BASE_DELAY_SECS
+        .saturating_mul(1u64 << doublings)
+        .min(MAX_DELAY_SECS)
+}
+
+/// Whether an unlock attempt at `now` may be checked
+pub fn gate(db: &Database, now: i64) -> Result<UnlockGate> {
+    let Some(failures) = db.unlock_failures()? else {
+        return Ok(UnlockGate::Open);
+    };
+    if failures.hard_locked_at.is_some() {
+        return Ok(UnlockGate::HardLocked);
+    }
+
+    let ready_at = failures.last_failed_at + delay_after(failures.failed_attempts) as i64;
+    if now < ready_at {
+        Ok(UnlockGate::Delayed {
+            retry_in: (ready_at - now) as u64,
+        })
+    } else {
+        Ok(UnlockGate::Open)
+    }
+}
+
+/// Count a failed unlock attempt at `now`
+pub fn record_failure(db: &Database, now: i64) -> Result<FailedUnlock> {
+    let attempts = db.record_unlock_failure(now)?;
+
+    let hard_locked = hard_lock_after(db)?.is_some_and(|limit| attempts >= limit);
+    if hard_locked {
+        db.set_unlock_hard_locked(now)?;
+    }
+
+    AuditLog::new(db).record(
+        if hard_locked {
+            UNLOCK_HARD_LOCKED_EVENT
+        } else {
+            UNLOCK_FAILED_EVENT
+        },
+        None,
+        &LockoutEvent {
+            attempts: Some(attempts),
+            hard_lock_after: None,
+        },
+    )?;
+
+    Ok(FailedUnlock {
+        attempts,
+        notify: attempts >= NOTIFY_AFTER,
+        hard_locked,
+    })
+}
+
+/// Forget failed attempts after a successful unlock
+///
+/// A hard-lock is only lifted by [`recover`].
+pub fn record_success(db: &Database) -> Result<()> {
+    if db
+        .unlock_failures()?
+        .is_some_and(|failures| failures.hard_locked_at.is_none())
+    {
+        db.clear_unlock_failures()?;
+    }
+    Ok(())
+}
+
+/// Current lockout state at `now`
+pub fn status(db: &Database, now: i64) -> Result<LockoutStatus> {
+    Ok(LockoutStatus {
+        failed_attempts: db
+            .unlock_failures()?
+            .map_or(0, |failures| failures.failed_attempts),
+        gate: gate(db, now)?,
+        hard_lock_after: hard_lock_after(db)?,
+    })
+}
+
+/// Failures that hard-lock unlocking, if a recovery code is set
+pub fn hard_lock_after(db: &Database) -> Result<Option<u32>> {
+    if db.get_setting(RECOVERY_CODE_SETTING)?.is_none() {
+        return Ok(None);
+    }
+    Ok(db
+        .get_setting(HARD_LOCK_SETTING)?
+        .and_then(|value| value.parse().ok()))
+}
+
+/// Enable hard-locking after `hard_lock_after` failures and return a new
+/// recovery code
+///
+/// The code is shown once; only its salted hash is stored.
+pub fn set_recovery_code(db: &Database, hard_lock_after: u32) -> Result<String> {
+    if hard_lock_after <= FREE_ATTEMPTS {
+        return Err(Error::Policy(format!(
+            "Hard-lock threshold must be more than {} attempts",
+            FREE_ATTEMPTS
+        )));
+    }
+
+    let mut bytes = [0u8; 15];
+    rand::rngs::OsRng.fill_bytes(&mut bytes);
+    let code = hex::encode(bytes)
+        .as_bytes()
+        .chunks(5)
+        .map(|chunk| String::from_utf8_lossy(chunk).into_owned())
+        .collect::<Vec<_>>()
+        .join("-");
+
+    let mut salt = [0u8; 32];
+    rand::rngs::OsRng.fill_bytes(&mut salt);
+    let hash = hash_password(normalize(&code).as_bytes(), &salt)?;
+
+    db.set_setting(
+        RECOVERY_CODE_SETTING,
+        &format!("{}:{}", hex::encode(salt), hex::encode(hash)),
+    )?;
+    db.set_setting(HARD_LOCK_SETTING, &hard_lock_after.to_string())?;
+    AuditLog::new(db).record(
+        RECOVERY_CODE_SET_EVENT,
+        None,
+        &LockoutEvent {
+            attempts: None,
+            hard_lock_after: Some(hard_lock_after),
+        },
+    )?;
+
+    Ok(code)
+}
+
+/// Turn hard-locking off and forget the recovery code
+///
+/// Refused while hard-locked; use [`recover`] instead.
+pub fn disable_hard_lock(db: &Database) -> Result<bool> {
+    if db
+        .unlock_failures()?
+        .is_some_and(|failures| failures.hard_locked_at.is_some())
+    {
+        return Err(Error::TooManyAttempts(
+            "Unlocking is hard-locked; lift it with the recovery code first".to_string(),
+        ));
+    }
+    let removed = db.delete_setting(RECOVERY_CODE_SETTING)?;
+    db.delete_setting(HARD_LOCK_SETTING)?;
+    Ok(removed)
+}
+
+/// Lift a hard-lock (and any delay) with the recovery code
+///
+/// Returns `false` if the code is wrong. Wrong codes count as failed attempts.
+pub fn recover(db: &Database, code: &str, now: i64) -> Result<bool> {
+    let Some(stored) = db.get_setting(RECOVERY_CODE_SETTING)? else {
+        return Err(Error::Policy("No recovery code is set".to_string()));
+    };
+    let (salt, hash) = stored
+        .split_once(':')
+        .and_then(|(salt, hash)| {
+            Some((
+                <[u8; 32]>::try_from(hex::decode(salt).ok()?).ok()?,
+                <[u8; 32]>::try_from(hex::decode(hash).ok()?).ok()?,
+            ))
+        })
+        .ok_or_else(|| Error::Policy("Stored recovery code is malformed".to_string()))?;
+
+    if !verify_password(normalize(code).as_bytes(), &salt, &hash)? {
+        record_failure(db, now)?;
+        return Ok(false);
+    }
+
+    db.clear_unlock_failures()?;
+    disable_hard_lock(db)?;
+    AuditLog::new(db).record(
+        RECOVERY_CODE_USED_EVENT,
+        None,
+        &LockoutEvent {
+            attempts: None,
+            hard_lock_after: None,
+        },
+    )?;
+    Ok(true)
+}
+
+/// Recovery codes compare without dashes, spaces or case
+fn normalize(code: &str) -> String {
+    code.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delay_after() {
+        assert_eq!(delay_after(0), 0);
+        assert_eq!(delay_after(FREE_ATTEMPTS - 1), 0);
+        assert_eq!(delay_after(FREE_ATTEMPTS), BASE_DELAY_SECS);
+        assert_eq!(delay_after(FREE_ATTEMPTS + 1), BASE_DELAY_SECS * 2);
+        assert_eq!(delay_after(u32::MAX), MAX_DELAY_SECS);
+    }
+
+    #[test]
+    fn test_escalating_delay() {
+        let db = Database::open_in_memory().unwrap();
+
+        for attempt in 1..FREE_ATTEMPTS {
+            record_failure(&db, 1000).unwrap();
+            assert_eq!(gate(&db, 1000).unwrap(), UnlockGate::Open, "{attempt}");
+        }
+
+        let failed = record_failure(&db, 1000).unwrap();
+        assert_eq!(failed.attempts, FREE_ATTEMPTS);
+        assert!(!failed.notify);
+        assert_eq!(
+            gate(&db, 1000).unwrap(),
+            UnlockGate::Delayed {
+                retry_in: BASE_DELAY_SECS
+            }
+        );
+        assert!(gate(&db, 1000).unwrap().check().is_err());
+        assert_eq!(
+            gate(&db, 1000 + BASE_DELAY_SECS as i64).unwrap(),
+            UnlockGate::Open
+        );
+
+        record_success(&db).unwrap();
+        assert_eq!(status(&db, 1000).unwrap().failed_attempts, 0);
+    }
+
+    #[test]
+    fn test_hard_lock_and_recover() {
+        let db = Database::open_in_memory().unwrap();
+        assert!(set_recovery_code(&db, FREE_ATTEMPTS).is_err());
+        let code = set_recovery_code(&db, NOTIFY_AFTER).unwrap();
+        assert_eq!(hard_lock_after(&db).unwrap(), Some(NOTIFY_AFTER));
+
+        let mut last = None;
+        for _ in 0..NOTIFY_AFTER {
+            last = Some(record_failure(&db, 1000).unwrap());
+        }
+        let last = last.unwrap();
+        assert!(last.notify);
+        assert!(last.hard_locked);
+
+        // Waiting or unlocking doesn't lift a hard-lock
+        assert_eq!(gate(&db, i64::MAX).unwrap(), UnlockGate::HardLocked);
+        record_success(&db).unwrap();
+        assert_eq!(gate(&db, i64::MAX).unwrap(), UnlockGate::HardLocked);
+
+        assert!(!recover(&db, "wrong", 2000).unwrap());
+        assert!(recover(&db, &code.to_uppercase().replace('-', " "), 2000).unwrap());
+        assert_eq!(gate(&db, 2000).unwrap(), UnlockGate::Open);
+
+        // Codes are single-use
+        assert_eq!(hard_lock_after(&db).unwrap(), None);
+        assert!(recover(&db, &code, 2000).is_err());
+    }
+}
@@ -0,0 +1,159 @@
+//! Database maintenance
+//!
+//! Long-lived agent hosts accumulate audit events and spending counters.
+//! Maintenance prunes both, rebuilds indexes, vacuums the file and reports
+//! storage per table.
+
+use crate::audit::AuditLog;
+use crate::db::{Database, TableStats};
+use crate::error::Result;
+use crate::spending::SpendingPolicy;
+
+/// Setting holding the audit log retention in seconds
+pub const AUDIT_RETENTION_SETTING: &str = "audit_retention_seconds";
+
+/// What a maintenance run did
+#[derive(Debug, Clone)]
+pub struct MaintenanceReport {
+    /// Audit events removed by the retention policy
+    pub audit_events_pruned: usize,
+    /// Spending counters older than every limit window
+    pub spends_pruned: usize,
+    /// File size before maintenance, in bytes
+    pub size_before: u64,
+    /// File size after maintenance, in bytes
+    pub size_after: u64,
+    /// Storage per table after maintenance
+    pub tables: Vec<TableStats>,
+}
+
+/// Database maintenance operations
+pub struct Maintenance<'a> {
+    db: &'a Database,
+}
+
+impl<'a> Maintenance<'a> {
+    /// Create a new maintenance handle
+    pub fn new(db: &'a Database) -> Self {
+        Self { db }
+    }
+
+    /// Audit log retention in seconds, if configured
+    pub fn audit_retention(&self) -> Result<Option<u64>> {
+        Ok(self
+            .db
+            .get_setting(AUDIT_RETENTION_SETTING)?
+            .and_then(|v| v.parse().ok()))
+    }
+
+    /// Set (or clear, with `None`) the audit log retention
+    pub fn set_audit_retention(&self, seconds: Option<u64>) -> Result<()> {
+        match seconds {
+            Some(seconds) => self
+                .db
+                .set_setting(AUDIT_RETENTION_SETTING, &seconds.to_string()),
+            None => self.db.delete_setting(AUDIT_RETENTION_SETTING).map(|_| ()),
+        }
+    }
+
+    /// Prune, reindex and vacuum the database
+    pub fn run(&self, now: i64) -> Result<MaintenanceReport> {
+        let size_before = self.db.size_bytes()?;
+
+        let audit_events_pruned = match self.audit_retention()? {
+            Some(retention) => {
+                AuditLog::new(self.db).prune(now.saturating_sub(retention as i64))?
+            }
+            None => 0,
+        };
+        let spends_pruned = SpendingPolicy::new(self.db).prune(now)?;
+
+        self.db.reindex()?;
+        self.db.vacuum()?;
+
+        Ok(MaintenanceReport {
+            audit_events_pruned,
+            spends_pruned,
+            size_before,
+            size_after: self.db.size_bytes()?,
+            tables: self.db.table_stats()?,
+        })
+    }
+}
+
+/// Format a byte count with a binary unit
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 86400;
+
+    #[test]
+    fn test_retention_setting() {
+        let db = Database::open_in_memory().unwrap();
+        let maintenance = Maintenance::new(&db);
+        assert_eq!(maintenance.audit_retention().unwrap(), None);
+
+        maintenance
+            .set_audit_retention(Some(90 * DAY as u64))
+            .unwrap();
+        assert_eq!(
+            maintenance.audit_retention().unwrap(),
+            Some(90 * DAY as u64)
+        );
+
+        maintenance.set_audit_retention(None).unwrap();
+        assert_eq!(maintenance.audit_retention().unwrap(), None);
+    }
+
+    #[test]
+    fn test_run_prunes_audit_log() {
+        let db = Database::open_in_memory().unwrap();
+        db.append_audit_event("old", None, "{}", 0).unwrap();
+        db.append_audit_event("new", None, "{}", 10 * DAY).unwrap();
+
+        let maintenance = Maintenance::new(&db);
+
+        // Nothing is pruned without a retention policy
+        let report = maintenance.run(10 * DAY).unwrap();
+        assert_eq!(report.audit_events_pruned, 0);
+
+        maintenance.set_audit_retention(Some(DAY as u64)).unwrap();
+        let report = maintenance.run(10 * DAY).unwrap();
+        assert_eq!(report.audit_events_pruned, 1);
+
+        let events = AuditLog::new(&db).list(None).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event, "new");
+
+        let audit = report
+            .tables
+            .iter()
+            .find(|t| t.name == "audit_log")
+            .unwrap();
+        assert_eq!(audit.rows, 1);
+        assert!(report.tables.iter().any(|t| t.name == "keypairs"));
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(2048), "2.0 KiB");
+        assert_eq!(format_bytes(5 * 1024 * 1024), "5.0 MiB");
+    }
+}
@@ -0,0 +1,323 @@
+//! Offline key bundles
+//!
+//! A key generated on an air-gapped machine is written to a bundle directory
+//! holding the secret encrypted under the keyring passphrase and a public
+//! manifest signed by the new key. Only the manifest is carried to networked
+//! machines, where it registers the key as a watch-only address book entry.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use crate::address_book::AddressBook;
+use crate::crypto::{EncryptedData, decrypt_secret, encrypt_secret};
+use crate::db::Database;
+use crate::error::{Error, Result};
+use crate::keypair::{SecureKeypair, fingerprint};
+
+/// Encrypted key file in a bundle; stays on the air-gapped machine
+pub const KEY_FILE: &str = "key.enc.json";
+
+/// Public manifest file in a bundle; safe to copy to networked machines
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Tag added to watch-only address book entries
+pub const WATCH_ONLY_TAG: &str = "watch-only";
+
+/// Bundle format version
+const BUNDLE_VERSION: u32 = 1;
+
+/// A secret key encrypted with AES-256-GCM under a passphrase
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptedKeyFile {
+    /// Bundle format version
+    pub version: u32,
+    /// Public key of the encrypted secret
+    pub pubkey: String,
+    /// Base64 ciphertext
+    pub ciphertext: String,
+    /// Base64 AES-GCM nonce
+    pub nonce: String,
+    /// Base64 Argon2id salt
+    pub salt: String,
+}
+
+impl EncryptedKeyFile {
+    /// Encrypt a keypair's secret under `passphrase`
+    pub fn encrypt(keypair: &SecureKeypair, passphrase: &[u8]) -> Result<Self> {
+        let encrypted = encrypt_secret(&keypair.secret_bytes()[..], passphrase)?;
+        Ok(Self {
+            version: BUNDLE_VERSION,
+            pubkey: keypair.pubkey_base58(),
+            ciphertext: STANDARD.encode(&encrypted.ciphertext),
+            nonce: STANDARD.encode(encrypted.nonce),
+            salt: STANDARD.encode(encrypted.salt),
+        })
+    }
+
+    /// Decrypt the secret, checking it matches the recorded public key
+    pub fn decrypt(&self, passphrase: &[u8]) -> Result<SecureKeypair> {
+        let invalid = |field: &str| Error::OfflineBundle(format!("Invalid {} in key file", field));
+        let encrypted = EncryptedData {
+            ciphertext: STANDARD
+                .decode(&self.ciphertext)
+                .map_err(|_| invalid("ciphertext"))?,
+            nonce: STANDARD
+                .decode(&self.nonce)
+                .ok()
+                .and_then(|v| v.try_into().ok())
+                .ok_or_else(|| invalid("nonce"))?,
+            salt: STANDARD
+                .decode(&self.salt)
+                .ok()
+                .and_then(|v| v.try_into().ok())
+                .ok_or_else(|| invalid("salt"))?,
+        };
+
+        let secret = Zeroizing::new(
+            decrypt_secret(&encrypted, passphrase).map_err(|_| Error::InvalidPassphrase)?,
+        );
+        let secret: &[u8; 32] = secret[..]
+            .try_into()
+            .map_err(|_| invalid("secret length"))?;
+        let keypair = SecureKeypair::from_bytes(secret)?;
+        if keypair.pubkey_base58() != self.pubkey {
+            return Err(Error::OfflineBundle(
+                "Decrypted key does not match the key file's public key".into(),
+            ));
+        }
+        Ok(keypair)
+    }
+}
+
+/// Public details of a key generated offline
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicManifest {
+    /// Bundle format version
+    pub version: u32,
+    /// Public key
+    pub pubkey: String,
+    /// Key fingerprint, for comparing across machines
+    pub fingerprint: String,
+    /// Label on the air-gapped machine
+    pub label: String,
+    /// Tags on the air-gapped machine
+    pub tags: Vec<String>,
+    /// Unix timestamp of generation
+    pub created_at: i64,
+}
+
+impl PublicManifest {
+    /// Describe a keypair
+    pub fn new(keypair: &SecureKeypair, label: &str, tags: &[&str], created_at: i64) -> Self {
+        let pubkey = keypair.pubkey_base58();
+        Self {
+            version: BUNDLE_VERSION,
+            fingerprint: fingerprint(&pubkey),
+            pubkey,
+            label: label.to_string(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            created_at,
+        }
+    }
+
+    /// Sign the manifest with the key it describes
+    pub fn sign(self, keypair: &SecureKeypair) -> Result<SignedPublicManifest> {
+        if keypair.pubkey_base58() != self.pubkey {
+            return Err(Error::OfflineBundle(
+                "Manifest must be signed by the key it describes".into(),
+            ));
+        }
+        let signature = keypair.sign(&serde_json::to_vec(&self)?);
+        Ok(SignedPublicManifest {
+            manifest: self,
+            signature: bs58::encode(signature).into_string(),
+        })
+    }
+}
+
+/// A public manifest signed by the key it describes
+///
+/// The signature proves the manifest came from whoever holds the secret, so a
+/// mistyped or tampered public key is caught before it is registered.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedPublicManifest {
+    /// The manifest
+    pub manifest: PublicManifest,
+    /// Base58 ed25519 signature over the JSON-encoded manifest
+    pub signature: String,
+}
+
+impl SignedPublicManifest {
+    /// Verify the signature against the manifest's public key
+    pub fn verify(&self) -> Result<bool> {
+        let pubkey: [u8; 32] = bs58::decode(&self.manifest.pubkey)
+            .into_vec()?
+            .try_into()
+            .map_err(|_| Error::OfflineBundle("Public key must be 32 bytes".into()))?;
+        let signature: [u8; 64] = bs58::decode(&self.signature)
+            .into_vec()?
+            .try_into()
+            .map_err(|_| Error::OfflineBundle("Signature must be 64 bytes".into()))?;
+
+        let key = VerifyingKey::from_bytes(&pubkey)?;
+        Ok(key
+            .verify(
+                &serde_json::to_vec(&self.manifest)?,
+                &Signature::from_bytes(&signature),
+            )
+            .is_ok())
+    }
+
+    /// Register the key as a watch-only address book entry under `label`
+    pub fn register_watch_only(&self, db: &Database, label: &str) -> Result<()> {
+        let address_book = AddressBook::new(db);
+        address_book.add(
+            &self.manifest.pubkey,
+            label,
+            Some("Watch-only: generated on an air-gapped machine"),
+        )?;
+        address_book.add_tag(&self.manifest.pubkey, WATCH_ONLY_TAG)?;
+        for tag in &self.manifest.tags {
+            address_book.add_tag(&self.manifest.pubkey, tag)?;
+        }
+        Ok(())
+    }
+}
+
+/// Write a bundle for `keypair` to `dir`
+///
+/// The directory is created if needed. Existing bundle files are never
+/// overwritten. Returns the signed manifest.
+pub fn write_bundle(
+    dir: &Path,
+    keypair: &SecureKeypair,
+    manifest: PublicManifest,
+    passphrase: &[u8],
+) -> Result<SignedPublicManifest> {
+    let key_path = dir.join(KEY_FILE);
+    let manifest_path = dir.join(MANIFEST_FILE);
+    for path in [&key_path, &manifest_path] {
+        if path.exists() {
+            return Err(Error::AlreadyExists(path.display().to_string()));
+        }
+    }
+
+    let key_file = EncryptedKeyFile::encrypt(keypair, passphrase)?;
+    let signed = manifest.sign(keypair)?;
+
+    fs::create_dir_all(dir)?;
+    write_new(&key_path, &serde_json::to_vec_pretty(&key_file)?, true)?;
+    write_new(&manifest_path, &serde_json::to_vec_pretty(&signed)?, false)?;
+    Ok(signed)
+}
+
+/// Read and verify a public manifest from a file or bundle directory
+pub fn read_manifest(path: &Path) -> Result<SignedPublicManifest> {
+    let path = manifest_path(path);
+    let signed: SignedPublicManifest = serde_json::from_slice(&fs::read(&path)?)
+        .map_err(|e| Error::OfflineBundle(format!("{}: {}", path.display(), e)))?;
+    if !signed.verify()? {
+        return Err(Error::OfflineBundle(format!(
+            "{}: signature does not match public key {}",
+            path.display(),
+            signed.manifest.pubkey
+        )));
+    }
+    Ok(signed)
+}
+
+/// Read a key file from a file or bundle directory
+pub fn read_key_file(path: &Path) -> Result<EncryptedKeyFile> {
+    let path = if path.is_dir() {
+        path.join(KEY_FILE)
+    } else {
+        path.to_path_buf()
+    };
+    serde_json::from_slice(&fs::read(&path)?)
+        .map_err(|e| Error::OfflineBundle(format!("{}: {}", path.display(), e)))
+}
+
+fn manifest_path(path: &Path) -> PathBuf {
+    if path.is_dir() {
+        path.join(MANIFEST_FILE)
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// Create a file that must not already exist, owner-only if `secret`
+fn write_new(path: &Path, contents: &[u8], secret: bool) -> Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    if secret {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    #[cfg(not(unix))]
+    let _ = secret;
+
+    options.open(path)?.write_all(contents)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keypair::generate_keypair;
+
+    #[test]
+    fn test_bundle_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let bundle = dir.path().join("cold");
+        let keypair = generate_keypair();
+        let manifest = PublicManifest::new(&keypair, "cold", &["treasury"], 1_700_000_000);
+
+        let signed = write_bundle(&bundle, &keypair, manifest, b"passphrase").unwrap();
+        assert_eq!(signed.manifest.pubkey, keypair.pubkey_base58());
+
+        // The manifest carries no secret and verifies on its own
+        let read = read_manifest(&bundle).unwrap();
+        assert_eq!(read.manifest, signed.manifest);
+        let manifest_json = fs::read_to_string(bundle.join(MANIFEST_FILE)).unwrap();
+        assert!(!manifest_json.contains("ciphertext"));
+
+        let key_file = read_key_file(&bundle).unwrap();
+        let restored = key_file.decrypt(b"passphrase").unwrap();
+        assert_eq!(restored.pubkey_base58(), keypair.pubkey_base58());
+        assert!(matches!(
+            key_file.decrypt(b"wrong"),
+            Err(Error::InvalidPassphrase)
+        ));
+
+        // Bundles are never overwritten
+        let manifest = PublicManifest::new(&keypair, "cold", &[], 0);
+        assert!(matches!(
+            write_bundle(&bundle, &keypair, manifest, b"passphrase"),
+            Err(Error::AlreadyExists(_))
+        ));
+    }
+
+    #[test]
+    fn test_tampered_manifest_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let keypair = generate_keypair();
+        let manifest = PublicManifest::new(&keypair, "cold", &[], 0);
+        let mut signed = write_bundle(dir.path(), &keypair, manifest, b"pw").unwrap();
+
+        signed.manifest.pubkey = generate_keypair().pubkey_base58();
+        fs::write(
+            dir.path().join("tampered.json"),
+            serde_json::to_vec(&signed).unwrap(),
+        )
+        .unwrap();
+        assert!(read_manifest(&dir.path().join("tampered.json")).is_err());
+    }
+}
@@ -0,0 +1,229 @@
+//! Terminal output styling shared by the keyring binaries
+//!
+//! Success, warning and error messages, headings and prompts are formatted
+//! here so `solana-keyring`, the agent, the credential helper and `sol` look the
+//! same. Colors are disabled when `NO_COLOR` is set, `TERM=dumb`, the stream is
+//! not a terminal, or `--plain` is passed. Symbols are used unless
+//! `SOLANA_KEYRING_EMOJI=0` or `--plain`; plain output matches the unstyled
+//! messages so scripts can parse it.
+
+use std::fmt::Display;
+use std::io::IsTerminal;
+use std::sync::OnceLock;
+
+/// Environment variable disabling colors (https://no-color.org)
+pub const NO_COLOR_ENV: &str = "NO_COLOR";
+
+/// Environment variable toggling symbols (`0`/`false`/`off` to disable)
+pub const EMOJI_ENV: &str = "SOLANA_KEYRING_EMOJI";
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const CYAN: &str = "\x1b[36m";
+
+static STYLE: OnceLock<Style> = OnceLock::new();
+
+/// How output is decorated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Style {
+    /// ANSI colors on stdout
+    pub color_stdout: bool,
+    /// ANSI colors on stderr
+    pub color_stderr: bool,
+    /// Symbols such as ✔ and ⚠
+    pub emoji: bool,
+}
+
+impl Style {
+    /// No colors or symbols
+    pub const PLAIN: Style = Style {
+        color_stdout: false,
+        color_stderr: false,
+        emoji: false,
+    };
+
+    /// Detect the style from the environment and terminals
+    pub fn detect(plain: bool) -> Self {
+        if plain {
+            return Self::PLAIN;
+        }
+
+        let no_color = std::env::var_os(NO_COLOR_ENV).is_some_and(|v| !v.is_empty())
+            || std::env::var("TERM").is_ok_and(|t| t == "dumb");
+        let emoji = !std::env::var(EMOJI_ENV)
+            .is_ok_and(|v| matches!(v.to_lowercase().as_str(), "0" | "false" | "off" | "no"));
+
+        Self {
+            color_stdout: !no_color && std::io::stdout().is_terminal(),
+            color_stderr: !no_color && std::io::stderr().is_terminal(),
+            emoji,
+        }
+    }
+
+    /// A completed action, for stdout
+    pub fn success(&self, msg: impl Display) -> String {
+        self.decorate(self.color_stdout, GREEN, "✔", None, msg)
+    }
+
+    /// Something the user should notice, for stderr
+    pub fn warning(&self, msg: impl Display) -> String {
+        self.decorate(self.color_stderr, YELLOW, "⚠", Some("Warning: "), msg)
+    }
+
+    /// A failure, for stderr
+    pub fn error(&self, msg: impl Display) -> String {
+        self.decorate(self.color_stderr, RED, "✖", Some("Error: "), msg)
+    }
+
+    /// A section heading, for stdout
+    pub fn heading(&self, msg: impl Display) -> String {
+        paint(self.color_stdout, BOLD, msg)
+    }
+
+    /// De-emphasized text such as hints, for stdout
+    pub fn dim(&self, msg: impl Display) -> String {
+        paint(self.color_stdout, DIM, msg)
+    }
+
+    /// A value worth picking out, such as a public key, for stdout
+    pub fn highlight(&self, msg: impl Display) -> String {
+        paint(self.color_stdout, CYAN, msg)
+    }
+
+    /// A prompt label; prompts are written to the terminal on stderr
+    pub fn prompt(&self, msg: impl Display) -> String {
+        let msg = msg.to_string();
+        let label = msg.trim_end().trim_end_matches(':');
+        let label = paint(self.color_stderr, BOLD, label);
+        if self.emoji {
+            format!("› {}: ", label)
+        } else {
+            format!("{}: ", label)
+        }
+    }
+
+    fn decorate(
+        &self,
+        color: bool,
+        code: &str,
+        symbol: &str,
+        plain_prefix: Option<&str>,
+        msg: impl Display,
+    ) -> String {
+        let prefix = if self.emoji {
+            format!("{} ", symbol)
+        } else {
+            plain_prefix.unwrap_or_default().to_string()
+        };
+        format!("{}{}", paint(color, code, prefix), msg)
+    }
+}
+
+fn paint(color: bool, code: &str, msg: impl Display) -> String {
+    let msg = msg.to_string();
+    if color && !msg.is_empty() {
+        format!("{}{}{}", code, msg, RESET)
+    } else {
+        msg
+    }
+}
+
+/// Set the process-wide style; call once at startup
+///
+/// Later calls have no effect. Without a call, the style is detected from the
+/// environment on first use.
+pub fn init(plain: bool) {
+    let _ = STYLE.set(Style::detect(plain));
+}
+
+/// The process-wide style
+pub fn style() -> &'static Style {
+    STYLE.get_or_init(|| Style::detect(false))
+}
+
+/// A completed action, for stdout
+pub fn success(msg: impl Display) -> String {
+    style().success(msg)
+}
+
+/// Something the user should notice, for stderr
+pub fn warning(msg: impl Display) -> String {
+    style().warning(msg)
+}
+
+/// A failure, for stderr
+pub fn error(msg: impl Display) -> String {
+    style().error(msg)
+}
+
+/// A section heading, for stdout
+pub fn heading(msg: impl Display) -> String {
+    style().heading(msg)
+}
+
+/// De-emphasized text, for stdout
+pub fn dim(msg: impl Display) -> String {
+    style().dim(msg)
+}
+
+/// A value worth picking out, for stdout
+pub fn highlight(msg: impl Display) -> String {
+    style().highlight(msg)
+}
+
+/// A prompt label, e.g. `prompt("Enter master passphrase")`
+pub fn prompt(msg: impl Display) -> String {
+    style().prompt(msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_matches_unstyled_text() {
+        let style = Style::PLAIN;
+        assert_eq!(style.success("Agent locked."), "Agent locked.");
+        assert_eq!(style.warning("agent is locked"), "Warning: agent is locked");
+        assert_eq!(style.error("boom"), "Error: boom");
+        assert_eq!(style.heading("Agent Status:"), "Agent Status:");
+        assert_eq!(
+            style.prompt("Enter master passphrase: "),
+            "Enter master passphrase: "
+        );
+    }
+
+    #[test]
+    fn test_emoji_without_color() {
+        let style = Style {
+            emoji: true,
+            ..Style::PLAIN
+        };
+        assert_eq!(style.success("Done"), "✔ Done");
+        assert_eq!(style.warning("Careful"), "⚠ Careful");
+        assert_eq!(style.prompt("Passphrase"), "› Passphrase: ");
+    }
+
+    #[test]
+    fn test_colors_per_stream() {
+        let style = Style {
+            color_stdout: true,
+            color_stderr: false,
+            emoji: false,
+        };
+        assert_eq!(style.heading("Title"), "\x1b[1mTitle\x1b[0m");
+        assert_eq!(style.success("Done"), "Done");
+        assert_eq!(style.error("boom"), "Error: boom");
+
+        let style = Style {
+            color_stdout: false,
+            color_stderr: true,
+            emoji: false,
+        };
+        assert_eq!(style.error("boom"), "\x1b[31mError: \x1b[0mboom");
+    }
+}
@@ -0,0 +1,681 @@
+//! Policy dry runs and linting
+//!
+//! The agent decides whether to sign from three kinds of rules: spending
+//! limits, the destination allowlist and cluster bindings. [`PolicyEngine`]
+//! runs those rules against a candidate transaction without signing anything
+//! and reports what each one concluded, so an operator can see exactly which
+//! rule would allow or deny it. [`lint`] checks the configured rules for
+//! contradictions and dead entries before the agent relies on them.
+
+use std::collections::HashSet;
+
+use crate::allowlist::{Allowlist, DestinationPolicy};
+use crate::cluster::{BindingMode, ClusterBindings};
+use crate::db::{Database, SpendingLimitRow};
+use crate::error::Result;
+use crate::spending::{LimitScope, SpendingPolicy, format_sol, format_window};
+use crate::transaction::{is_outflow_countable, sol_outflow, transfer_destinations};
+
+/// What a single rule concluded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// The rule permits the transaction
+    Allow,
+    /// The rule refuses the transaction
+    Deny,
+    /// The rule permits the transaction, but only after a prompt
+    Confirm,
+    /// The rule does not apply, or was not checked
+    Skip,
+}
+
+impl std::fmt::Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Allow => write!(f, "allow"),
+            Verdict::Deny => write!(f, "deny"),
+            Verdict::Confirm => write!(f, "confirm"),
+            Verdict::Skip => write!(f, "skip"),
+        }
+    }
+}
+
+/// The outcome of one rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleResult {
+    /// The rule, e.g. `limit 3 (tag treasury)` or `allowlist (address-book)`
+    pub rule: String,
+    /// What the rule concluded
+    pub verdict: Verdict,
+    /// Why
+    pub reason: String,
+}
+
+/// What the agent would do with a transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    /// Refused without a prompt
+    Denied,
+    /// Signed without a prompt
+    AutoApproved,
+    /// Signed only if the user approves
+    NeedsConfirmation,
+}
+
+impl std::fmt::Display for Decision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Decision::Denied => write!(f, "denied"),
+            Decision::AutoApproved => write!(f, "approved without a prompt"),
+            Decision::NeedsConfirmation => write!(f, "needs confirmation"),
+        }
+    }
+}
+
+/// Every rule's outcome for one transaction and signer
+#[derive(Debug, Clone)]
+pub struct PolicyReport {
+    /// Public key of the signer
+    pub signer: String,
+    /// Lamports the transaction moves out of the signer
+    pub outflow: u64,
+    /// Transfer destinations in the transaction
+    pub destinations: Vec<String>,
+    /// Rule outcomes, in the order the agent checks them
+    pub rules: Vec<RuleResult>,
+    /// The overall decision
+    pub decision: Decision,
+}
+
+impl PolicyReport {
+    /// The rule that settled the decision
+    ///
+    /// `None` when no rule was decisive, i.e. the transaction gets the usual
+    /// confirmation prompt.
+    pub fn deciding_rule(&self) -> Option<&RuleResult> {
+        self.rules.iter().find(|r| match self.decision {
+            Decision::Denied => r.verdict == Verdict::Deny,
+            Decision::AutoApproved => r.verdict == Verdict::Allow && is_allowlist(r),
+            Decision::NeedsConfirmation => r.verdict == Verdict::Confirm,
+        })
+    }
+}
+
+/// Evaluates the agent's signing rules without signing
+pub struct PolicyEngine<'a> {
+    db: &'a Database,
+    destinations: &'a DestinationPolicy,
+    check_cluster: bool,
+}
+
+impl<'a> PolicyEngine<'a> {
+    /// Create an engine using the agent's destination policy
+    pub fn new(db: &'a Database, destinations: &'a DestinationPolicy) -> Self {
+        Self {
+            db,
+            destinations,
+            check_cluster: true,
+        }
+    }
+
+    /// Whether to look up the blockhash for cluster bindings (on by default)
+    ///
+    /// The lookup makes RPC requests; without it, bindings are reported but
+    /// not checked.
+    pub fn with_cluster_check(mut self, check_cluster: bool) -> Self {
+        self.check_cluster = check_cluster;
+        self
+    }
+
+    /// Evaluate a serialized message for `signer` at unix time `now`
+    pub fn evaluate(&self, message_bytes: &[u8], signer: &str, now: i64) -> Result<PolicyReport> {
+        let outflow = sol_outflow(message_bytes, signer)?;
+        let countable = is_outflow_countable(message_bytes)?;
+        let destinations = transfer_destinations(message_bytes)?;
+
+        let mut rules = self.spending_rules(signer, outflow, countable, now)?;
+        rules.push(self.allowlist_rule(&destinations)?);
+        rules.push(self.cluster_rule(message_bytes, signer)?);
+
+        let decision = if rules.iter().any(|r| r.verdict == Verdict::Deny) {
+            Decision::Denied
+        } else if rules.iter().any(|r| r.verdict == Verdict::Confirm) {
+            Decision::NeedsConfirmation
+        } else if rules
+            .iter()
+            .any(|r| r.verdict == Verdict::Allow && is_allowlist(r))
+        {
+            Decision::AutoApproved
+        } else {
+            Decision::NeedsConfirmation
+        };
+
+        Ok(PolicyReport {
+            signer: signer.to_string(),
+            outflow,
+            destinations,
+            rules,
+            decision,
+        })
+    }
+
+    /// One result per spending limit, as [`SpendingPolicy::check`] sees them
+    ///
+    /// A limit can't vouch for a transaction whose outflow isn't `countable`,
+    /// so the agent asks before signing it.
+    fn spending_rules(
+        &self,
+        signer: &str,
+        outflow: u64,
+        countable: bool,
+        now: i64,
+    ) -> Result<Vec<RuleResult>> {
+        let policy = SpendingPolicy::new(self.db);
+        let tags = self.db.get_signer_tags(signer)?;
+
+        let mut rules = Vec::new();
+        for limit in policy.list()? {
+            let scope: LimitScope = limit.scope.parse()?;
+            let applies = match scope {
+                LimitScope::Key => limit.target == signer,
+                LimitScope::Tag => tags.contains(&limit.target),
+            };
+            let rule = limit_name(&limit);
+            if !applies {
+                rules.push(RuleResult {
+                    rule,
+                    verdict: Verdict::Skip,
+                    reason: format!("Does not apply to signer {}", signer),
+                });
+                continue;
+            }
+            if !countable {
+                rules.push(RuleResult {
+                    rule,
+                    verdict: Verdict::Confirm,
+                    reason: "Transaction calls programs that could move SOL the limit can't count"
+                        .into(),
+                });
+                continue;
+            }
+            if outflow == 0 {
+                rules.push(RuleResult {
+                    rule,
+                    verdict: Verdict::Skip,
+                    reason: "Transaction sends no SOL from the signer".into(),
+                });
+                continue;
+            }
+
+            let spent = policy.spent(&limit, now)?;
+            let remaining = limit.max_lamports.saturating_sub(spent);
+            let (verdict, reason) = if spent.saturating_add(outflow) > limit.max_lamports {
+                (
+                    Verdict::Deny,
+                    format!(
+                        "Requested {} SOL, only {} SOL remaining of {} SOL per {}",
+                        format_sol(outflow),
+                        format_sol(remaining),
+                        format_sol(limit.max_lamports),
+                        format_window(limit.window_seconds)
+                    ),
+                )
+            } else {
+                (
+                    Verdict::Allow,
+                    format!(
+                        "Requested {} SOL of {} SOL remaining ({} SOL per {})",
+                        format_sol(outflow),
+                        format_sol(remaining),
+                        format_sol(limit.max_lamports),
+                        format_window(limit.window_seconds)
+                    ),
+                )
+            };
+            rules.push(RuleResult {
+                rule,
+                verdict,
+                reason,
+            });
+        }
+        Ok(rules)
+    }
+
+    fn allowlist_rule(&self, destinations: &[String]) -> Result<RuleResult> {
+        let rule = format!("allowlist ({})", self.destinations);
+        let (verdict, reason) = if !self.destinations.is_enabled() {
+            (Verdict::Skip, "Allowlist is off".to_string())
+        } else if destinations.is_empty() {
+            (
+                Verdict::Skip,
+                "Transaction has no transfer destinations".to_string(),
+            )
+        } else {
+            let untrusted = Allowlist::new(self.db, self.destinations).untrusted(destinations)?;
+            if untrusted.is_empty() {
+                (
+                    Verdict::Allow,
+                    format!("All {} destination(s) are trusted", destinations.len()),
+                )
+            } else {
+                (
+                    Verdict::Confirm,
+                    format!("New destination(s): {}", untrusted.join(", ")),
+                )
+            }
+        };
+        Ok(RuleResult {
+            rule,
+            verdict,
+            reason,
+        })
+    }
+
+    fn cluster_rule(&self, message_bytes: &[u8], signer: &str) -> Result<RuleResult> {
+        let Some(binding) = ClusterBindings::new(self.db).get(signer)? else {
+            return Ok(RuleResult {
+                rule: "cluster".into(),
+                verdict: Verdict::Skip,
+                reason: "Signer is not bound to a cluster".into(),
+            });
+        };
+        let rule = format!("cluster ({}, {})", binding.cluster, binding.mode);
+
+        if !self.check_cluster {
+            return Ok(RuleResult {
+                rule,
+                verdict: Verdict::Skip,
+                reason: "Blockhash not looked up (offline)".into(),
+            });
+        }
+
+        let (verdict, reason) = match binding.check(message_bytes)? {
+            None => (
+                Verdict::Allow,
+                format!(
+                    "Blockhash is not from a cluster other than {}",
+                    binding.cluster
+                ),
+            ),
+            Some(mismatch) => {
+                let verdict = match mismatch.binding.mode {
+                    BindingMode::Refuse => Verdict::Deny,
+                    BindingMode::Warn => Verdict::Confirm,
+                };
+                (verdict, mismatch.to_string())
+            }
+        };
+        Ok(RuleResult {
+            rule,
+            verdict,
+            reason,
+        })
+    }
+}
+
+fn is_allowlist(rule: &RuleResult) -> bool {
+    rule.rule.starts_with("allowlist")
+}
+
+fn limit_name(limit: &SpendingLimitRow) -> String {
+    format!("limit {} ({} {})", limit.id, limit.scope, limit.target)
+}
+
+/// How serious a lint finding is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Probably unintended, but the rules still work
+    Warning,
+    /// The rules contradict each other or cannot be enforced
+    Error,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A problem found in the configured rules
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintFinding {
+    /// How serious it is
+    pub severity: Severity,
+    /// The rule it concerns
+    pub rule: String,
+    /// What is wrong
+    pub message: String,
+}
+
+impl std::fmt::Display for LintFinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}: {}", self.severity, self.rule, self.message)
+    }
+}
+
+/// Check spending limits, cluster bindings and the destination policy
+///
+/// Errors are rules that contradict each other or cannot be enforced;
+/// warnings are rules that never take effect or are likely mistakes.
+pub fn lint(db: &Database, destinations: &DestinationPolicy) -> Result<Vec<LintFinding>> {
+    let mut findings = Vec::new();
+    let mut finding = |severity, rule: String, message: String| {
+        findings.push(LintFinding {
+            severity,
+            rule,
+            message,
+        })
+    };
+
+    // Every signer a limit can apply to, whatever holds its key
+    let pubkeys: HashSet<String> = db
+        .list_keypairs(None)?
+        .into_iter()
+        .map(|k| k.pubkey)
+        .chain(db.list_ledger_wallets(None)?.into_iter().map(|w| w.pubkey))
+        .chain(db.list_yubikey_wallets(None)?.into_iter().map(|w| w.pubkey))
+        .chain(db.list_custom_signers(None)?.into_iter().map(|c| c.pubkey))
+        .collect();
+    let mut tags = HashSet::new();
+    for pubkey in &pubkeys {
+        tags.extend(db.get_signer_tags(pubkey)?);
+    }
+
+    let limits = db.list_spending_limits()?;
+    for (i, limit) in limits.iter().enumerate() {
+        let rule = limit_name(limit);
+        let Ok(scope) = limit.scope.parse::<LimitScope>() else {
+            finding(
+                Severity::Error,
+                rule,
+                format!("Unknown scope '{}'", limit.scope),
+            );
+            continue;
+        };
+        if limit.window_seconds == 0 {
+            finding(Severity::Error, rule.clone(), "Window is zero".into());
+        }
+        if limit.max_lamports == 0 {
+            finding(
+                Severity::Warning,
+                rule.clone(),
+                "Limit is 0 SOL, so every transfer is denied".into(),
+            );
+        }
+        let known = match scope {
+            LimitScope::Key => pubkeys.contains(&limit.target),
+            LimitScope::Tag => tags.contains(&limit.target),
+        };
+        if !known {
+            let what = match scope {
+                LimitScope::Key => "No key in the keyring has this public key",
+                LimitScope::Tag => "No key in the keyring has this tag",
+            };
+            finding(
+                Severity::Warning,
+                rule.clone(),
+                format!("{}, so it never applies", what),
+            );
+        }
+
+        // Limits on the same target with the same window can't coexist, but a
+        // longer window with a smaller maximum always trips first
+        for other in &limits[..i] {
+            if other.scope != limit.scope || other.target != limit.target {
+                continue;
+            }
+            if let Some((loose, strict)) = shadowed(limit, other) {
+                finding(
+                    Severity::Error,
+                    limit_name(loose),
+                    format!(
+                        "Allows {} SOL per {}, but {} caps it at {} SOL per {}",
+                        format_sol(loose.max_lamports),
+                        format_window(loose.window_seconds),
+                        limit_name(strict),
+                        format_sol(strict.max_lamports),
+                        format_window(strict.window_seconds)
+                    ),
+                );
+            }
+        }
+    }
+
+    for binding in db.list_cluster_bindings()? {
+        let rule = format!("cluster binding for {}", binding.pubkey);
+        if binding.mode.parse::<BindingMode>().is_err() {
+            finding(
+                Severity::Error,
+                rule.clone(),
+                format!("Unknown mode '{}'", binding.mode),
+            );
+        }
+        if !pubkeys.contains(binding.pubkey.as_str()) {
+            finding(
+                Severity::Warning,
+                rule,
+                "No key in the keyring has this public key".into(),
+            );
+        }
+    }
+    match destinations {
+        DestinationPolicy::Off => {}
+        DestinationPolicy::AddressBook => {
+            if db.list_addresses()?.is_empty() {
+                finding(
+                    Severity::Warning,
+                    format!("allowlist ({})", destinations),
+                    "Address book is empty, so every transfer needs confirmation".into(),
+                );
+            }
+        }
+        DestinationPolicy::Tagged(tag) => {
+            let mut any = false;
+            for address in db.list_addresses()? {
+                if db.get_address_tags(&address.pubkey)?.contains(tag) {
+                    any = true;
+                    break;
+                }
+            }
+            if !any {
+                finding(
+                    Severity::Warning,
+                    format!("allowlist ({})", destinations),
+                    format!(
+                        "No address book entry is tagged '{}', so every transfer needs confirmation",
+                        tag
+                    ),
+                );
+            }
+        }
+    }
+
+    Ok(findings)
+}
+
+/// Of two limits on the same target, the one that can never be reached,
+/// and the one that makes it so
+fn shadowed<'l>(
+    a: &'l SpendingLimitRow,
+    b: &'l SpendingLimitRow,
+) -> Option<(&'l SpendingLimitRow, &'l SpendingLimitRow)> {
+    if a.window_seconds < b.window_seconds && a.max_lamports >= b.max_lamports {
+        Some((a, b))
+    } else if b.window_seconds < a.window_seconds && b.max_lamports >= a.max_lamports {
+        Some((b, a))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keypair::generate_keypair;
+    use crate::spending::LAMPORTS_PER_SOL;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::instruction::{AccountMeta, Instruction};
+    use solana_sdk::message::Message;
+    use solana_sdk::pubkey::Pubkey;
+
+    const DAY: u64 = 86400;
+
+    fn transfer(from: &Pubkey, to: &Pubkey, lamports: u64) -> Vec<u8> {
+        let mut data = 2u32.to_le_bytes().to_vec();
+        data.extend_from_slice(&lamports.to_le_bytes());
+        let ix = Instruction::new_with_bytes(
+            Pubkey::default(),
+            &data,
+            vec![AccountMeta::new(*from, true), AccountMeta::new(*to, false)],
+        );
+        let mut message = Message::new(&[ix], Some(from));
+        message.recent_blockhash = Hash::new_unique();
+        bincode::serialize(&message).unwrap()
+    }
+
+    #[test]
+    fn test_evaluate_explains_decision() {
+        let db = Database::open_in_memory().unwrap();
+        let keypair = generate_keypair();
+        db.store_keypair(&keypair, "hot", b"pw", &["treasury"])
+            .unwrap();
+        let signer = keypair.pubkey_base58();
+        let from: Pubkey = signer.parse().unwrap();
+        let known = Pubkey::new_unique();
+        db.add_address(&known.to_string(), "Known", None).unwrap();
+
+        let policy = SpendingPolicy::new(&db);
+        policy
+            .add(LimitScope::Tag, "treasury", 5 * LAMPORTS_PER_SOL, DAY)
+            .unwrap();
+        policy
+            .add(LimitScope::Key, "someone-else", LAMPORTS_PER_SOL, DAY)
+            .unwrap();
+
+        let destinations = DestinationPolicy::AddressBook;
+        let engine = PolicyEngine::new(&db, &destinations).with_cluster_check(false);
+
+        // Within the limit and to a known destination
+        let report = engine
+            .evaluate(&transfer(&from, &known, LAMPORTS_PER_SOL), &signer, 0)
+            .unwrap();
+        assert_eq!(report.decision, Decision::AutoApproved);
+        assert_eq!(report.outflow, LAMPORTS_PER_SOL);
+        let verdicts: Vec<_> = report.rules.iter().map(|r| r.verdict).collect();
+        assert_eq!(
+            verdicts,
+            vec![Verdict::Skip, Verdict::Allow, Verdict::Allow, Verdict::Skip]
+        );
+        assert!(
+            report
+                .deciding_rule()
+                .unwrap()
+                .rule
+                .starts_with("allowlist")
+        );
+
+        // A new destination needs a prompt
+        let report = engine
+            .evaluate(
+                &transfer(&from, &Pubkey::new_unique(), LAMPORTS_PER_SOL),
+                &signer,
+                0,
+            )
+            .unwrap();
+        assert_eq!(report.decision, Decision::NeedsConfirmation);
+
+        // Over the tag limit is denied, whatever the destination
+        let report = engine
+            .evaluate(&transfer(&from, &known, 6 * LAMPORTS_PER_SOL), &signer, 0)
+            .unwrap();
+        assert_eq!(report.decision, Decision::Denied);
+        let rule = report.deciding_rule().unwrap();
+        assert!(rule.rule.contains("tag treasury"));
+        assert!(rule.reason.contains("5 SOL remaining"));
+
+        // A program call could move SOL the limit can't count
+        let call = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[],
+            vec![AccountMeta::new(from, true), AccountMeta::new(known, false)],
+        );
+        let message = bincode::serialize(&Message::new(&[call], Some(&from))).unwrap();
+        let report = engine.evaluate(&message, &signer, 0).unwrap();
+        assert_eq!(report.decision, Decision::NeedsConfirmation);
+        assert_eq!(report.outflow, 0);
+        assert_eq!(report.deciding_rule().unwrap().verdict, Verdict::Confirm);
+        assert!(report.rules[1].rule.contains("tag treasury"));
+
+        // Tag limits apply to hardware signers too
+        let ledger = Pubkey::new_unique();
+        db.store_ledger_wallet(&ledger.to_string(), "cold", "44'/501'/0'/0'", &["treasury"])
+            .unwrap();
+        let report = engine
+            .evaluate(
+                &transfer(&ledger, &known, 6 * LAMPORTS_PER_SOL),
+                &ledger.to_string(),
+                0,
+            )
+            .unwrap();
+        assert_eq!(report.decision, Decision::Denied);
+        assert!(
+            report
+                .deciding_rule()
+                .unwrap()
+                .rule
+                .contains("tag treasury")
+        );
+    }
+
+    #[test]
+    fn test_lint() {
+        let db = Database::open_in_memory().unwrap();
+        let keypair = generate_keypair();
+        db.store_keypair(&keypair, "hot", b"pw", &["treasury"])
+            .unwrap();
+        let policy = SpendingPolicy::new(&db);
+        let sol = LAMPORTS_PER_SOL;
+
+        assert!(lint(&db, &DestinationPolicy::Off).unwrap().is_empty());
+
+        policy
+            .add(LimitScope::Tag, "treasury", 10 * sol, DAY)
+            .unwrap();
+        policy
+            .add(LimitScope::Tag, "treasury", 50 * sol, 3600)
+            .unwrap();
+        policy
+            .add(LimitScope::Tag, "treasury", 100 * sol, 7 * DAY)
+            .unwrap();
+        policy.add(LimitScope::Tag, "unused", sol, DAY).unwrap();
+
+        let rules = |severity| {
+            findings
+                .iter()
+                .filter(|f| f.severity == severity)
+                .map(|f| f.rule.as_str())
+                .collect::<Vec<_>>()
+        };
+
+        // Hardware signers' keys and tags count as known
+        let ledger = Pubkey::new_unique().to_string();
+        db.store_ledger_wallet(&ledger, "cold", "44'/501'/0'/0'", &["vault"])
+            .unwrap();
+        policy.add(LimitScope::Tag, "vault", sol, DAY).unwrap();
+        policy.add(LimitScope::Key, &ledger, sol, DAY).unwrap();
+
+        let findings = lint(&db, &DestinationPolicy::Tagged("verified".into())).unwrap();
+
+        // 50 SOL per hour is unreachable under 10 SOL per day
+        assert_eq!(rules(Severity::Error), vec!["limit 2 (tag treasury)"]);
+        assert!(findings[0].message.contains("limit 1 (tag treasury)"));
+
+        assert_eq!(
+            rules(Severity::Warning),
+            vec!["limit 4 (tag unused)", "allowlist (tag:verified)"]
+        );
+    }
+}
@@ -0,0 +1,119 @@
+//! Spinners for long-running CLI operations
+//!
+//! Connecting to a Ledger, waiting for a device confirmation, and sending a
+//! transaction can each take several seconds. A [`Spinner`] shows the
+//! operation and its elapsed time on stderr so the pause isn't silent. When
+//! stderr is not a terminal or output is plain (see [`crate::output`]), the
+//! message is printed once instead and nothing is redrawn, keeping logs and
+//! scripted output readable.
+
+use std::fmt::Display;
+use std::io::IsTerminal;
+use std::time::{Duration, Instant};
+
+use indicatif::{ProgressBar, ProgressStyle};
+
+use crate::output::{self, Style};
+
+/// How often the spinner redraws
+const TICK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// An operation in progress, shown on stderr until finished or dropped
+pub struct Spinner {
+    bar: Option<ProgressBar>,
+    started: Instant,
+}
+
+impl Spinner {
+    /// Start a spinner with a message such as `Connecting to Ledger device...`
+    pub fn start(msg: impl Display) -> Self {
+        let msg = msg.to_string();
+        let bar = enabled().then(|| {
+            let bar = ProgressBar::new_spinner()
+                .with_style(
+                    ProgressStyle::with_template("{spinner} {msg} {elapsed:.dim}")
+                        .expect("valid spinner template"),
+                )
+                .with_message(msg.clone());
+            bar.enable_steady_tick(TICK_INTERVAL);
+            bar
+        });
+        if bar.is_none() {
+            eprintln!("{}", msg);
+        }
+
+        Self {
+            bar,
+            started: Instant::now(),
+        }
+    }
+
+    /// Replace the message, e.g. once a device is connected and waiting for input
+    ///
+    /// Without a spinner the new message is printed on its own line.
+    pub fn set_message(&self, msg: impl Display) {
+        match &self.bar {
+            Some(bar) => bar.set_message(msg.to_string()),
+            None => eprintln!("{}", msg),
+        }
+    }
+
+    /// Time since the spinner started
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Clear the spinner and report `msg` as done on stderr
+    ///
+    /// The elapsed time is appended only when a spinner was drawn, so plain
+    /// output stays stable for scripts.
+    pub fn finish(self, msg: impl Display) {
+        match &self.bar {
+            Some(bar) => {
+                bar.finish_and_clear();
+                eprintln!(
+                    "{} {}",
+                    output::success(msg),
+                    format_elapsed(self.elapsed())
+                );
+            }
+            None => eprintln!("{}", output::success(msg)),
+        }
+    }
+}
+
+impl Drop for Spinner {
+    fn drop(&mut self) {
+        // Leave no half-drawn line behind on errors
+        if let Some(bar) = &self.bar {
+            bar.finish_and_clear();
+        }
+    }
+}
+
+/// Run `f` behind a spinner, clearing it whether or not `f` succeeds
+pub fn with_spinner<T, E>(msg: impl Display, f: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+    let _spinner = Spinner::start(msg);
+    f()
+}
+
+/// Whether spinners are drawn: stderr is a terminal and output isn't plain
+fn enabled() -> bool {
+    *output::style() != Style::PLAIN && std::io::stderr().is_terminal()
+}
+
+/// Elapsed time as shown after a finished operation, e.g. `(2.4s)`
+fn format_elapsed(elapsed: Duration) -> String {
+    format!("({:.1}s)", elapsed.as_secs_f64())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_elapsed() {
+        assert_eq!(format_elapsed(Duration::from_millis(2_430)), "(2.4s)");
+        assert_eq!(format_elapsed(Duration::ZERO), "(0.0s)");
+    }
+}
@@ -0,0 +1,214 @@
+//! Per-endpoint RPC authentication
+//!
+//! Private RPC providers expect an API key in a request header or in the
+//! URL's query string. `rpc.json` in the keyring directory lists the headers
+//! and query parameters to send to each endpoint:
+//!
+//! ```json
+//! {
+//!   "endpoints": [
+//!     {
+//!       "url": "https://mainnet.helius-rpc.com",
+//!       "query": { "api-key": "{{secret:helius}}" }
+//!     },
+//!     {
+//!       "url": "https://example.rpcpool.com",
+//!       "headers": { "Authorization": "Bearer {{secret:triton}}" }
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! `{{secret:NAME}}` placeholders are filled from the keyring's encrypted
+//! secrets store when the endpoint is used, so API keys are never kept in
+//! plain text, shell history or environment variables.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use crate::error::{Error, Result};
+
+/// Opens a secret placeholder
+const SECRET_OPEN: &str = "{{secret:";
+
+/// Closes a secret placeholder
+const SECRET_CLOSE: &str = "}}";
+
+/// Endpoint auth settings from `rpc.json`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RpcEndpoints {
+    /// Configured endpoints
+    #[serde(default)]
+    pub endpoints: Vec<RpcEndpoint>,
+}
+
+/// Headers and query parameters for one endpoint
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RpcEndpoint {
+    /// Endpoint URL; also matches longer URLs it is a prefix of
+    pub url: String,
+    /// Headers sent with every request
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// Query parameters added to the URL
+    #[serde(default)]
+    pub query: BTreeMap<String, String>,
+}
+
+/// Headers and query parameters with secrets filled in
+#[derive(Default)]
+pub struct ResolvedAuth {
+    /// Header names and values
+    pub headers: Vec<(String, Zeroizing<String>)>,
+    /// Query parameter names and values
+    pub query: Vec<(String, Zeroizing<String>)>,
+}
+
+impl RpcEndpoints {
+    /// Load a config file; a missing file configures no endpoints
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = std::fs::read_to_string(path)?;
+        serde_json::from_str(&contents)
+            .map_err(|e| Error::RpcEndpoint(format!("Failed to parse {}: {}", path.display(), e)))
+    }
+
+    /// The endpoint whose URL is the longest prefix of `url`
+    pub fn find(&self, url: &str) -> Option<&RpcEndpoint> {
+        self.endpoints
+            .iter()
+            .filter(|endpoint| url.starts_with(endpoint.url.trim_end_matches('/')))
+            .max_by_key(|endpoint| endpoint.url.len())
+    }
+}
+
+impl RpcEndpoint {
+    /// Names of the secrets the endpoint's values refer to
+    pub fn secret_names(&self) -> Result<Vec<String>> {
+        let mut names = Vec::new();
+        for value in self.headers.values().chain(self.query.values()) {
+            for name in placeholders(value)? {
+                if !names.iter().any(|n| n == name) {
+                    names.push(name.to_string());
+                }
+            }
+        }
+        Ok(names)
+    }
+
+    /// Fill in secret placeholders using `lookup`
+    pub fn resolve<F>(&self, mut lookup: F) -> Result<ResolvedAuth>
+    where
+        F: FnMut(&str) -> Result<Zeroizing<String>>,
+    {
+        let mut fill = |pairs: &BTreeMap<String, String>| -> Result<Vec<_>> {
+            pairs
+                .iter()
+                .map(|(name, value)| Ok((name.clone(), substitute(value, &mut lookup)?)))
+                .collect()
+        };
+        Ok(ResolvedAuth {
+            headers: fill(&self.headers)?,
+            query: fill(&self.query)?,
+        })
+    }
+}
+
+/// Secret names referenced by `{{secret:NAME}}` placeholders in `value`
+fn placeholders(value: &str) -> Result<Vec<&str>> {
+    let mut names = Vec::new();
+    let mut rest = value;
+    while let Some(start) = rest.find(SECRET_OPEN) {
+        let after = &rest[start + SECRET_OPEN.len()..];
+        let end = after.find(SECRET_CLOSE).ok_or_else(|| {
+            Error::RpcEndpoint(format!("Unterminated secret placeholder in '{}'", value))
+        })?;
+        let name = after[..end].trim();
+        if name.is_empty() {
+            return Err(Error::RpcEndpoint("Empty secret name".to_string()));
+        }
+        names.push(name);
+        rest = &after[end + SECRET_CLOSE.len()..];
+    }
+    Ok(names)
+}
+
+/// Replace each placeholder in `value` with its secret
+fn substitute<F>(value: &str, lookup: &mut F) -> Result<Zeroizing<String>>
+where
+    F: FnMut(&str) -> Result<Zeroizing<String>>,
+{
+    let mut out = Zeroizing::new(String::with_capacity(value.len()));
+    let mut rest = value;
+    while let Some(start) = rest.find(SECRET_OPEN) {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + SECRET_OPEN.len()..];
+        let end = after.find(SECRET_CLOSE).ok_or_else(|| {
+            Error::RpcEndpoint(format!("Unterminated secret placeholder in '{}'", value))
+        })?;
+        out.push_str(&lookup(after[..end].trim())?);
+        rest = &after[end + SECRET_CLOSE.len()..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> RpcEndpoints {
+        serde_json::from_str(
+            r#"{
+                "endpoints": [
+                    { "url": "https://rpc.example.com/", "headers": { "Authorization": "Bearer {{secret:token}}" } },
+                    { "url": "https://rpc.example.com/devnet", "query": { "api-key": "{{secret:devnet}}", "client": "keyring" } }
+                ]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_find_longest_prefix() {
+        let config = config();
+        assert_eq!(
+            config.find("https://rpc.example.com/devnet").unwrap().url,
+            "https://rpc.example.com/devnet"
+        );
+        assert_eq!(
+            config.find("https://rpc.example.com").unwrap().url,
+            "https://rpc.example.com/"
+        );
+        assert!(config.find("https://api.mainnet-beta.solana.com").is_none());
+    }
+
+    #[test]
+    fn test_resolve_fills_secrets() {
+        let config = config();
+        let endpoint = config.find("https://rpc.example.com").unwrap();
+        assert_eq!(endpoint.secret_names().unwrap(), vec!["token"]);
+
+        let auth = endpoint
+            .resolve(|name| Ok(Zeroizing::new(format!("<{}>", name))))
+            .unwrap();
+        assert_eq!(auth.headers[0].0, "Authorization");
+        assert_eq!(auth.headers[0].1.as_str(), "Bearer <token>");
+
+        let devnet = config.find("https://rpc.example.com/devnet").unwrap();
+        let missing = devnet.resolve(|name| Err(Error::SecretNotFound(name.to_string())));
+        assert!(matches!(missing, Err(Error::SecretNotFound(name)) if name == "devnet"));
+
+        let broken = RpcEndpoint {
+            url: String::new(),
+            headers: BTreeMap::from([("x-api-key".to_string(), "{{secret:key".to_string())]),
+            query: BTreeMap::new(),
+        };
+        assert!(matches!(broken.secret_names(), Err(Error::RpcEndpoint(_))));
+    }
+}
@@ -14,8 +14,12 @@ pub enum SignerType {
     Keypair,
     /// Ledger hardware wallet
     Ledger,
+    /// YubiKey PIV key
+    Yubikey,
     /// Squads multisig
     Squads,
+    /// Signer type registered by another crate, see [`crate::driver`]
+    Custom,
 }
 
 impl std::fmt::Display for SignerType {
@@ -23,7 +27,9 @@ impl std::fmt::Display for SignerType {
         match self {
             SignerType::Keypair => write!(f, "keypair"),
             SignerType::Ledger => write!(f, "ledger"),
+            SignerType::Yubikey => write!(f, "yubikey"),
             SignerType::Squads => write!(f, "squads"),
+            SignerType::Custom => write!(f, "custom"),
         }
     }
 }
@@ -35,10 +41,19 @@ pub struct SignerInfo {
     pub pubkey: String,
     /// Human-readable label for the signer.
     pub label: String,
-    /// Type of signer (keypair, ledger, or squads).
+    /// Type of signer (keypair, ledger, yubikey, squads, or custom).
     pub signer_type: SignerType,
+    /// Driver name of a custom signer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub driver: Option<String>,
     /// Tags associated with this signer.
     pub tags: Vec<String>,
+    /// When the signer was added (SQLite `CURRENT_TIMESTAMP`, UTC).
+    #[serde(default)]
+    pub created_at: String,
+    /// Unix timestamp of the signer's last signature, if it has signed.
+    #[serde(default)]
+    pub last_used: Option<i64>,
 }
 
 /// Unified signer trait for all signing methods
@@ -140,31 +155,71 @@ pub fn list_signers(db: &Database, tag_filter: Option<&str>) -> Result<Vec<Signe
     // Keypairs
     for row in db.list_keypairs(tag_filter)? {
         let tags = db.get_keypair_tags(&row.pubkey)?;
+        let last_used = db.signer_last_used(&row.pubkey)?;
         signers.push(SignerInfo {
             pubkey: row.pubkey,
             label: row.label,
             signer_type: SignerType::Keypair,
+            driver: None,
             tags,
+            created_at: row.created_at,
+            last_used,
         });
     }
 
     // Ledger wallets
     for row in db.list_ledger_wallets(tag_filter)? {
+        let last_used = db.signer_last_used(&row.pubkey)?;
         signers.push(SignerInfo {
             pubkey: row.pubkey,
             label: row.label,
             signer_type: SignerType::Ledger,
+            driver: None,
             tags: vec![], // TODO: add ledger tags
+            created_at: row.created_at,
+            last_used,
+        });
+    }
+
+    // YubiKey wallets
+    for row in db.list_yubikey_wallets(tag_filter)? {
+        let last_used = db.signer_last_used(&row.pubkey)?;
+        signers.push(SignerInfo {
+            pubkey: row.pubkey,
+            label: row.label,
+            signer_type: SignerType::Yubikey,
+            driver: None,
+            tags: vec![],
+            created_at: row.created_at,
+            last_used,
         });
     }
 
     // Squads multisigs
     for row in db.list_squads_multisigs(tag_filter)? {
+        let last_used = db.signer_last_used(&row.multisig_pubkey)?;
         signers.push(SignerInfo {
             pubkey: row.multisig_pubkey,
             label: row.label,
             signer_type: SignerType::Squads,
+            driver: None,
             tags: vec![], // TODO: add squads tags
+            created_at: row.created_at,
+            last_used,
+        });
+    }
+
+    // Custom signers
+    for row in db.list_custom_signers(tag_filter)? {
+        let last_used = db.signer_last_used(&row.pubkey)?;
+        signers.push(SignerInfo {
+            pubkey: row.pubkey,
+            label: row.label,
+            signer_type: SignerType::Custom,
+            driver: Some(row.driver),
+            tags: vec![],
+            created_at: row.created_at,
+            last_used,
         });
     }
 
@@ -0,0 +1,123 @@
+//! Solana CLI config file integration
+//!
+//! Reads `~/.config/solana/cli/config.yml`, written by `solana config set`,
+//! so users coming from the official CLI keep their cluster settings and
+//! default keypair.
+
+use std::path::{Path, PathBuf};
+
+use solana_cli_config::Config;
+
+use crate::error::{Error, Result};
+use crate::keypair::{SecureKeypair, import_json};
+
+/// Settings from a Solana CLI config file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolanaCliConfig {
+    /// JSON RPC URL
+    pub json_rpc_url: String,
+    /// WebSocket URL, derived from the RPC URL if the file leaves it empty
+    pub websocket_url: String,
+    /// Default keypair path (or a `usb://` / `prompt://` signer URI)
+    pub keypair_path: String,
+    /// Default commitment level
+    pub commitment: String,
+}
+
+impl SolanaCliConfig {
+    /// Default config file location (`~/.config/solana/cli/config.yml`)
+    pub fn default_path() -> Option<PathBuf> {
+        solana_cli_config::CONFIG_FILE.as_ref().map(PathBuf::from)
+    }
+
+    /// Load a config file
+    pub fn load(path: &Path) -> Result<Self> {
+        let config = Config::load(&path.to_string_lossy()).map_err(|e| {
+            Error::SolanaConfig(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+
+        let websocket_url = if config.websocket_url.is_empty() {
+            Config::compute_websocket_url(&config.json_rpc_url)
+        } else {
+            config.websocket_url
+        };
+
+        Ok(Self {
+            json_rpc_url: config.json_rpc_url,
+            websocket_url,
+            keypair_path: config.keypair_path,
+            commitment: config.commitment,
+        })
+    }
+
+    /// Load `path`, or the default config file if `path` is `None`
+    pub fn load_or_default(path: Option<&Path>) -> Result<Self> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => Self::default_path()
+                .ok_or_else(|| Error::SolanaConfig("Could not find home directory".to_string()))?,
+        };
+        Self::load(&path)
+    }
+
+    /// Read the default keypair file
+    ///
+    /// Fails for signer URIs such as `usb://ledger`, which hold no key file.
+    pub fn keypair(&self) -> Result<SecureKeypair> {
+        if self.keypair_path.contains("://") {
+            return Err(Error::SolanaConfig(format!(
+                "Default keypair '{}' is not a keypair file",
+                self.keypair_path
+            )));
+        }
+        import_json(Path::new(&self.keypair_path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_load_computes_websocket_url() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.yml");
+        std::fs::write(
+            &path,
+            "---\njson_rpc_url: \"http://localhost:8899\"\nwebsocket_url: \"\"\nkeypair_path: /tmp/id.json\ncommitment: confirmed\n",
+        )
+        .unwrap();
+
+        let config = SolanaCliConfig::load(&path).unwrap();
+        assert_eq!(config.json_rpc_url, "http://localhost:8899");
+        assert_eq!(config.websocket_url, "ws://localhost:8900/");
+        assert_eq!(config.keypair_path, "/tmp/id.json");
+        assert_eq!(config.commitment, "confirmed");
+    }
+
+    #[test]
+    fn test_keypair_reads_file() {
+        let dir = TempDir::new().unwrap();
+        let keypair = SecureKeypair::generate();
+        let keypair_path = dir.path().join("id.json");
+        std::fs::write(&keypair_path, crate::keypair::export_json(&keypair)).unwrap();
+
+        let config = SolanaCliConfig {
+            json_rpc_url: String::new(),
+            websocket_url: String::new(),
+            keypair_path: keypair_path.to_string_lossy().into_owned(),
+            commitment: String::new(),
+        };
+        assert_eq!(
+            config.keypair().unwrap().pubkey_bytes(),
+            keypair.pubkey_bytes()
+        );
+
+        let ledger = SolanaCliConfig {
+            keypair_path: "usb://ledger".to_string(),
+            ..config
+        };
+        assert!(matches!(ledger.keypair(), Err(Error::SolanaConfig(_))));
+    }
+}
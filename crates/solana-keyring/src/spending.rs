@@ -0,0 +1,432 @@
+//! Spending limits with rolling windows
+//!
+//! Limits cap the SOL a signer (or every signer sharing a tag) may send within a
+//! rolling window, e.g. "at most 10 SOL per 24h for the `treasury` tag". Each
+//! signed outflow is recorded in the `spending_counters` table so limits hold
+//! across agent restarts.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::db::{Database, SpendingLimitRow};
+use crate::error::{Error, Result};
+
+/// Lamports per SOL
+pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+/// What a spending limit applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitScope {
+    /// A single signer, by public key
+    Key,
+    /// All signers with a tag, combined
+    Tag,
+}
+
+impl LimitScope {
+    /// Database representation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LimitScope::Key => "key",
+            LimitScope::Tag => "tag",
+        }
+    }
+}
+
+impl std::fmt::Display for LimitScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl std::str::FromStr for LimitScope {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "key" => Ok(LimitScope::Key),
+            "tag" => Ok(LimitScope::Tag),
+            _ => Err(Error::Policy(format!("Unknown limit scope: {}", s))),
+        }
+    }
+}
+
+/// A signing request that would exceed a spending limit
+#[derive(Debug, Clone)]
+pub struct SpendingDenial {
+    /// The limit that would be exceeded
+    pub limit: SpendingLimitRow,
+    /// Lamports already spent in the current window
+    pub spent: u64,
+    /// Lamports requested by the transaction
+    pub requested: u64,
+}
+
+impl SpendingDenial {
+    /// Lamports still available in the current window
+    pub fn remaining(&self) -> u64 {
+        self.limit.max_lamports.saturating_sub(self.spent)
+    }
+}
+
+impl std::fmt::Display for SpendingDenial {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Spending limit exceeded for {} '{}': requested {} SOL, {} SOL remaining of {} SOL per {}",
+            self.limit.scope,
+            self.limit.target,
+            format_sol(self.requested),
+            format_sol(self.remaining()),
+            format_sol(self.limit.max_lamports),
+            format_window(self.limit.window_seconds),
+        )
+    }
+}
+
+/// Spending limit operations
+pub struct SpendingPolicy<'a> {
+    db: &'a Database,
+}
+
+impl<'a> SpendingPolicy<'a> {
+    /// Create a new spending policy handle
+    pub fn new(db: &'a Database) -> Self {
+        Self { db }
+    }
+
+    /// Add a limit of `max_lamports` per `window_seconds`
+    pub fn add(
+        &self,
+        scope: LimitScope,
+        target: &str,
+        max_lamports: u64,
+        window_seconds: u64,
+    ) -> Result<i64> {
+        if window_seconds == 0 {
+            return Err(Error::Policy("Window must be longer than zero".into()));
+        }
+        self.db
+            .add_spending_limit(scope.as_str(), target, max_lamports, window_seconds)
+    }
+
+    /// List all limits
+    pub fn list(&self) -> Result<Vec<SpendingLimitRow>> {
+        self.db.list_spending_limits()
+    }
+
+    /// Remove a limit by ID
+    pub fn remove(&self, id: i64) -> Result<bool> {
+        self.db.delete_spending_limit(id)
+    }
+
+    /// Lamports spent against a limit in its current window
+    pub fn spent(&self, limit: &SpendingLimitRow, now: i64) -> Result<u64> {
+        let since = now.saturating_sub(limit.window_seconds as i64);
+        match limit.scope.parse()? {
+            LimitScope::Key => self.db.spent_by_key(&limit.target, since),
+            LimitScope::Tag => self.db.spent_by_tag(&limit.target, since),
+        }
+    }
+
+    /// Check whether a signer with the given tags may spend `lamports` now
+    ///
+    /// Returns the first limit that would be exceeded, if any.
+    pub fn check(
+        &self,
+        pubkey: &str,
+        tags: &[String],
+        lamports: u64,
+        now: i64,
+    ) -> Result<Option<SpendingDenial>> {
+        if lamports == 0 {
+            return Ok(None);
+        }
+
+        for limit in self.list()? {
+            if !applies(&limit, pubkey, tags)? {
+                continue;
+            }
+
+            let spent = self.spent(&limit, now)?;
+            if spent.saturating_add(lamports) > limit.max_lamports {
+                return Ok(Some(SpendingDenial {
+                    limit,
+                    spent,
+                    requested: lamports,
+                }));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Whether any limit applies to a signer with the given tags
+    ///
+    /// Callers that can't determine a transaction's outflow use this to
+    /// refuse it rather than count it as zero.
+    pub fn is_limited(&self, pubkey: &str, tags: &[String]) -> Result<bool> {
+        for limit in self.list()? {
+            if applies(&limit, pubkey, tags)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Record an outflow against the signer's counters
+    pub fn record(&self, pubkey: &str, lamports: u64, now: i64) -> Result<()> {
+        if lamports == 0 {
+            return Ok(());
+        }
+        self.db.record_spend(pubkey, lamports, now)
+    }
+
+    /// Remove counters older than the longest configured window
+    pub fn prune(&self, now: i64) -> Result<usize> {
+        let longest = self
+            .list()?
+            .iter()
+            .map(|l| l.window_seconds)
+            .max()
+            .unwrap_or(0);
+        self.db.prune_spends(now.saturating_sub(longest as i64))
+    }
+}
+
+/// Whether a limit covers a signer with the given tags
+fn applies(limit: &SpendingLimitRow, pubkey: &str, tags: &[String]) -> Result<bool> {
+    Ok(match limit.scope.parse()? {
+        LimitScope::Key => limit.target == pubkey,
+        LimitScope::Tag => tags.contains(&limit.target),
+    })
+}
+
+/// Current unix timestamp in seconds
+pub fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Parse a SOL amount (e.g. "1.5") into lamports
+pub fn parse_sol(s: &str) -> Result<u64> {
+    let invalid = || Error::Policy(format!("Invalid SOL amount: {}", s));
+
+    let (whole, frac) = s.trim().split_once('.').unwrap_or((s.trim(), ""));
+    if frac.len() > 9 || (whole.is_empty() && frac.is_empty()) {
+        return Err(invalid());
+    }
+
+    let whole: u64 = if whole.is_empty() {
+        0
+    } else {
+        whole.parse().map_err(|_| invalid())?
+    };
+    let frac: u64 = if frac.is_empty() {
+        0
+    } else {
+        format!("{:0<9}", frac).parse().map_err(|_| invalid())?
+    };
+
+    whole
+        .checked_mul(LAMPORTS_PER_SOL)
+        .and_then(|l| l.checked_add(frac))
+        .ok_or_else(invalid)
+}
+
+/// Format lamports as a SOL amount
+pub fn format_sol(lamports: u64) -> String {
+    let s = format!(
+        "{}.{:09}",
+        lamports / LAMPORTS_PER_SOL,
+        lamports % LAMPORTS_PER_SOL
+    );
+    s.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// Parse a window like "30m", "24h" or "7d" into seconds
+pub fn parse_window(s: &str) -> Result<u64> {
+    let invalid = || Error::Policy(format!("Invalid window: {} (use e.g. 30m, 24h, 7d)", s));
+
+    let s = s.trim();
+    let (num, unit) = s.split_at(s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len()));
+    let num: u64 = num.parse().map_err(|_| invalid())?;
+    let multiplier = match unit {
+        "s" | "" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86400,
+        _ => return Err(invalid()),
+    };
+
+    match num.checked_mul(multiplier) {
+        Some(0) | None => Err(invalid()),
+        Some(secs) => Ok(secs),
+    }
+}
+
+/// Format a window in seconds using the largest whole unit
+pub fn format_window(seconds: u64) -> String {
+    if seconds.is_multiple_of(86400) {
+        format!("{}d", seconds / 86400)
+    } else if seconds.is_multiple_of(3600) {
+        format!("{}h", seconds / 3600)
+    } else if seconds.is_multiple_of(60) {
+        format!("{}m", seconds / 60)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: u64 = 86400;
+
+    #[test]
+    fn test_parse_format_sol() {
+        assert_eq!(parse_sol("1").unwrap(), LAMPORTS_PER_SOL);
+        assert_eq!(parse_sol("1.5").unwrap(), 1_500_000_000);
+        assert_eq!(parse_sol(".000000001").unwrap(), 1);
+        assert!(parse_sol("1.0000000001").is_err());
+        assert!(parse_sol("abc").is_err());
+
+        assert_eq!(format_sol(1_500_000_000), "1.5");
+        assert_eq!(format_sol(2 * LAMPORTS_PER_SOL), "2");
+        assert_eq!(format_sol(0), "0");
+    }
+
+    #[test]
+    fn test_parse_format_window() {
+        assert_eq!(parse_window("24h").unwrap(), DAY);
+        assert_eq!(parse_window("7d").unwrap(), 7 * DAY);
+        assert_eq!(parse_window("30m").unwrap(), 1800);
+        assert!(parse_window("0h").is_err());
+        assert!(parse_window("1w").is_err());
+
+        assert_eq!(format_window(DAY), "1d");
+        assert_eq!(format_window(7200), "2h");
+        assert_eq!(format_window(90), "90s");
+    }
+
+    #[test]
+    fn test_key_limit_rolling_window() {
+        let db = Database::open_in_memory().unwrap();
+        let policy = SpendingPolicy::new(&db);
+        policy
+            .add(LimitScope::Key, "alice", 10 * LAMPORTS_PER_SOL, DAY)
+            .unwrap();
+
+        let now = 1_000_000;
+        policy.record("alice", 8 * LAMPORTS_PER_SOL, now).unwrap();
+
+        let denial = policy
+            .check("alice", &[], 3 * LAMPORTS_PER_SOL, now + 60)
+            .unwrap()
+            .expect("should exceed limit");
+        assert_eq!(denial.remaining(), 2 * LAMPORTS_PER_SOL);
+        assert!(
+            denial
+                .to_string()
+                .contains("2 SOL remaining of 10 SOL per 1d")
+        );
+
+        assert!(
+            policy
+                .check("alice", &[], 2 * LAMPORTS_PER_SOL, now + 60)
+                .unwrap()
+                .is_none()
+        );
+
+        // Once the earlier spend falls out of the window, the allowance resets
+        assert!(
+            policy
+                .check("alice", &[], 3 * LAMPORTS_PER_SOL, now + DAY as i64)
+                .unwrap()
+                .is_none()
+        );
+
+        // Other keys are unaffected
+        assert!(
+            policy
+                .check("bob", &[], 100 * LAMPORTS_PER_SOL, now)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_is_limited() {
+        let db = Database::open_in_memory().unwrap();
+        let policy = SpendingPolicy::new(&db);
+        policy.add(LimitScope::Key, "alice", 1, DAY).unwrap();
+        policy.add(LimitScope::Tag, "hot", 1, DAY).unwrap();
+
+        assert!(policy.is_limited("alice", &[]).unwrap());
+        assert!(policy.is_limited("bob", &["hot".to_string()]).unwrap());
+        assert!(!policy.is_limited("bob", &["cold".to_string()]).unwrap());
+    }
+
+    #[test]
+    fn test_tag_limit_applies_to_ledger() {
+        let db = Database::open_in_memory().unwrap();
+        let ledger = "LedgerPubkey1111111111111111111111111111111";
+        db.store_ledger_wallet(ledger, "cold", "44'/501'/0'/0'", &["hot"])
+            .unwrap();
+        let policy = SpendingPolicy::new(&db);
+        policy
+            .add(LimitScope::Tag, "hot", LAMPORTS_PER_SOL, DAY)
+            .unwrap();
+
+        let tags = db.get_signer_tags(ledger).unwrap();
+        assert_eq!(tags, vec!["hot".to_string()]);
+        assert!(policy.is_limited(ledger, &tags).unwrap());
+
+        policy.record(ledger, LAMPORTS_PER_SOL, 1_000).unwrap();
+        let denial = policy
+            .check(ledger, &tags, 1, 1_060)
+            .unwrap()
+            .expect("should exceed the tag limit");
+        assert_eq!(denial.remaining(), 0);
+    }
+
+    #[test]
+    fn test_tag_limit_applies_to_custom_signer() {
+        let db = Database::open_in_memory().unwrap();
+        let signer = "CustomPubkey111111111111111111111111111111";
+        let other = "OtherPubkey1111111111111111111111111111111";
+        db.store_custom_signer(signer, "remote", "test", &Default::default(), &["hot"])
+            .unwrap();
+        let policy = SpendingPolicy::new(&db);
+        policy
+            .add(LimitScope::Tag, "hot", LAMPORTS_PER_SOL, DAY)
+            .unwrap();
+
+        let tags = db.get_signer_tags(signer).unwrap();
+        assert_eq!(tags, vec!["hot".to_string()]);
+
+        // The driver's spends count against the tag for every tagged signer
+        policy.record(signer, LAMPORTS_PER_SOL, 1_000).unwrap();
+        assert!(policy.check(signer, &tags, 1, 1_060).unwrap().is_some());
+        assert!(
+            policy
+                .check(other, &["hot".to_string()], 1, 1_060)
+                .unwrap()
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_prune() {
+        let db = Database::open_in_memory().unwrap();
+        let policy = SpendingPolicy::new(&db);
+        policy.add(LimitScope::Key, "alice", 1, DAY).unwrap();
+
+        policy.record("alice", 1, 0).unwrap();
+        policy.record("alice", 1, DAY as i64 * 2).unwrap();
+
+        assert_eq!(policy.prune(DAY as i64 * 2).unwrap(), 1);
+    }
+}
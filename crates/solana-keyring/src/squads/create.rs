@@ -0,0 +1,236 @@
+//! Squads multisig creation
+
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use solana_client::rpc_client::RpcClient;
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+    transaction::Transaction,
+};
+
+use super::{
+    Permission,
+    instructions::{MultisigCreateArgsV2, multisig_create_v2},
+    pda::{get_multisig_pda, get_program_config_pda, get_vault_pda},
+    state::{Member, ProgramConfig},
+};
+use crate::error::{Error, Result};
+use crate::keypair::SecureKeypair;
+
+/// Settings for a new multisig
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultisigConfig {
+    /// Members and their permissions
+    pub members: Vec<Member>,
+    /// Approvals needed to execute a proposal
+    pub threshold: u16,
+    /// Seconds between approval and when a proposal can be executed
+    pub time_lock: u32,
+    /// Authority that can change the config without a vote
+    pub config_authority: Option<Pubkey>,
+    /// Account that receives rent from closed accounts
+    pub rent_collector: Option<Pubkey>,
+}
+
+impl MultisigConfig {
+    /// Check the settings against the program's rules
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |msg: String| Err(Error::Squads(format!("Invalid multisig: {}", msg)));
+
+        if self.members.is_empty() {
+            return invalid("no members".into());
+        }
+
+        let mut keys = HashSet::new();
+        for member in &self.members {
+            if !keys.insert(member.key) {
+                return invalid(format!("duplicate member {}", member.key));
+            }
+            if member.permissions == 0 || member.permissions > 7 {
+                return invalid(format!(
+                    "invalid permissions {} for {}",
+                    member.permissions, member.key
+                ));
+            }
+        }
+
+        let count = |permission: Permission| {
+            self.members
+                .iter()
+                .filter(|member| permission.has(member.permissions))
+                .count()
+        };
+        if count(Permission::Initiate) == 0 {
+            return invalid("no member can initiate proposals".into());
+        }
+        if count(Permission::Execute) == 0 {
+            return invalid("no member can execute proposals".into());
+        }
+
+        let voters = count(Permission::Vote);
+        if self.threshold == 0 || usize::from(self.threshold) > voters {
+            return invalid(format!(
+                "threshold {} must be between 1 and the {} voting members",
+                self.threshold, voters
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses `PUBKEY` (all permissions) or `PUBKEY:PERMISSIONS`, where
+/// permissions are a comma-separated list of `initiate`, `vote` and `execute`
+/// or `all`
+impl FromStr for Member {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (key, permissions) = s.split_once(':').unwrap_or((s, "all"));
+        let key = key
+            .parse()
+            .map_err(|_| Error::Squads(format!("Invalid member key: {}", key)))?;
+
+        let mut mask = 0;
+        for permission in permissions.split(',').map(str::trim) {
+            mask |= match permission.to_lowercase().as_str() {
+                "initiate" => Permission::Initiate as u8,
+                "vote" => Permission::Vote as u8,
+                "execute" => Permission::Execute as u8,
+                "all" => 7,
+                other => {
+                    return Err(Error::Squads(format!("Unknown permission: {}", other)));
+                }
+            };
+        }
+
+        Ok(Self {
+            key,
+            permissions: mask,
+        })
+    }
+}
+
+/// A multisig created by [`create_multisig`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CreatedMultisig {
+    /// The multisig account
+    pub multisig: Pubkey,
+    /// The default vault (index 0)
+    pub vault: Pubkey,
+    /// Signature of the creation transaction
+    pub signature: Signature,
+}
+
+/// Create a multisig, paid for by `creator`
+///
+/// The creator pays rent and the creation fee and need not be a member. A
+/// fresh key seeds the multisig address.
+pub async fn create_multisig(
+    rpc_url: &str,
+    creator: &SecureKeypair,
+    config: &MultisigConfig,
+) -> Result<CreatedMultisig> {
+    config.validate()?;
+
+    let program_id: Pubkey = super::SQUADS_PROGRAM_ID
+        .parse()
+        .map_err(|_| Error::Squads("Invalid program ID".into()))?;
+    let rpc = RpcClient::new_with_commitment(rpc_url.to_string(), CommitmentConfig::confirmed());
+
+    let program_config_pda = get_program_config_pda(&program_id);
+    let program_config_data = rpc
+        .get_account_data(&program_config_pda)
+        .map_err(|e| Error::Squads(format!("Failed to fetch program config: {}", e)))?;
+    let program_config = ProgramConfig::from_account_data(&program_config_data)?;
+
+    let creator = creator.to_solana_keypair();
+    let create_key = Keypair::new();
+    let multisig = get_multisig_pda(&create_key.pubkey(), &program_id);
+
+    let args = MultisigCreateArgsV2 {
+        config_authority: config.config_authority,
+        threshold: config.threshold,
+        members: config.members.clone(),
+        time_lock: config.time_lock,
+        rent_collector: config.rent_collector,
+        memo: None,
+    };
+    let instruction = multisig_create_v2(
+        program_config_pda,
+        program_config.treasury,
+        multisig,
+        create_key.pubkey(),
+        creator.pubkey(),
+        args,
+        program_id,
+    );
+
+    let blockhash = rpc
+        .get_latest_blockhash()
+        .map_err(|e| Error::Squads(format!("Failed to get blockhash: {}", e)))?;
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&creator.pubkey()),
+        &[&creator, &create_key],
+        blockhash,
+    );
+
+    let signature = rpc
+        .send_and_confirm_transaction(&tx)
+        .map_err(|e| Error::Squads(format!("Failed to create multisig: {}", e)))?;
+
+    Ok(CreatedMultisig {
+        multisig,
+        vault: get_vault_pda(&multisig, 0, &program_id),
+        signature,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_member() {
+        let key = Pubkey::new_unique();
+
+        let member: Member = key.to_string().parse().unwrap();
+        assert_eq!(member.key, key);
+        assert_eq!(member.permissions, 7);
+
+        let member: Member = format!("{}:vote,execute", key).parse().unwrap();
+        assert_eq!(member.permissions, 6);
+
+        assert!(format!("{}:admin", key).parse::<Member>().is_err());
+        assert!("not-a-key:vote".parse::<Member>().is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        let member = |permissions| Member {
+            key: Pubkey::new_unique(),
+            permissions,
+        };
+        let config = |members: Vec<Member>, threshold| MultisigConfig {
+            members,
+            threshold,
+            time_lock: 0,
+            config_authority: None,
+            rent_collector: None,
+        };
+
+        let members = vec![member(7), member(2), member(4)];
+        assert!(config(members.clone(), 2).validate().is_ok());
+        // Only two members can vote
+        assert!(config(members.clone(), 3).validate().is_err());
+        assert!(config(members.clone(), 0).validate().is_err());
+        assert!(config(vec![members[0], members[0]], 1).validate().is_err());
+        // Nobody can execute
+        assert!(config(vec![member(3)], 1).validate().is_err());
+        assert!(config(vec![], 1).validate().is_err());
+    }
+}
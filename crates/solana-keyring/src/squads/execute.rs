@@ -1,14 +1,15 @@
 //! Squads proposal execution
 
-use solana_commitment_config::CommitmentConfig;
 use solana_sdk::{
-    instruction::AccountMeta, pubkey::Pubkey, signature::Signer, transaction::Transaction,
+    account::Account, instruction::AccountMeta, pubkey::Pubkey, signature::Signer,
+    transaction::Transaction,
 };
 
 use super::{
     SquadsSigner,
     instructions::vault_transaction_execute,
-    pda::{get_proposal_pda, get_transaction_pda, get_vault_pda},
+    pda::{get_proposal_pda, get_transaction_pda},
+    state::{TransactionMessage, VaultTransaction},
 };
 use crate::error::{Error, Result};
 
@@ -23,15 +24,34 @@ pub async fn execute_proposal(signer: &SquadsSigner, transaction_index: u64) ->
     // Derive PDAs
     let proposal_pda = get_proposal_pda(&multisig_pda, transaction_index, &program_id);
     let transaction_pda = get_transaction_pda(&multisig_pda, transaction_index, &program_id);
-    let vault_pda = get_vault_pda(&multisig_pda, signer.vault_index(), &program_id);
 
     // Fetch the vault transaction account to get the accounts list
     let tx_data = rpc
         .get_account_data(&transaction_pda)
         .map_err(|e| Error::Squads(format!("Failed to fetch transaction account: {}", e)))?;
-
-    // Parse the remaining accounts from the vault transaction
-    let remaining_accounts = parse_vault_transaction_accounts(&tx_data, vault_pda)?;
+    let message = VaultTransaction::from_account_data(&tx_data)?.message;
+
+    // Resolve the accounts the message loads from address lookup tables
+    let table_keys: Vec<Pubkey> = message
+        .address_table_lookups
+        .iter()
+        .map(|lookup| lookup.account_key)
+        .collect();
+    let tables = if table_keys.is_empty() {
+        Vec::new()
+    } else {
+        rpc.get_multiple_accounts(&table_keys)
+            .map_err(|e| Error::Squads(format!("Failed to fetch lookup tables: {}", e)))?
+            .into_iter()
+            .zip(&table_keys)
+            .map(|(account, key)| {
+                let account = account
+                    .ok_or_else(|| Error::Squads(format!("Lookup table {} not found", key)))?;
+                parse_lookup_table(key, &account)
+            })
+            .collect::<Result<Vec<_>>>()?
+    };
+    let remaining_accounts = execute_accounts(&message, &tables)?;
 
     // Build vault transaction execute instruction
     let execute_ix = vault_transaction_execute(
@@ -58,114 +78,141 @@ pub async fn execute_proposal(signer: &SquadsSigner, transaction_index: u64) ->
 
     // Send transaction
     let signature = rpc
-        .send_and_confirm_transaction_with_spinner_and_commitment(
-            &tx,
-            CommitmentConfig::confirmed(),
-        )
+        .send_and_confirm_transaction(&tx)
         .map_err(|e| Error::Squads(format!("Failed to execute proposal: {}", e)))?;
 
-    println!("Executed proposal {}: {}", transaction_index, signature);
-
     Ok(signature.to_string())
 }
 
-/// Parse the remaining accounts needed for execution from the vault transaction data
-fn parse_vault_transaction_accounts(tx_data: &[u8], vault_pda: Pubkey) -> Result<Vec<AccountMeta>> {
-    // VaultTransaction struct layout (after 8-byte Anchor discriminator):
-    // - multisig: Pubkey (32)
-    // - creator: Pubkey (32)
-    // - index: u64 (8)
-    // - bump: u8 (1)
-    // - vault_index: u8 (1)
-    // - vault_bump: u8 (1)
-    // - ephemeral_signer_bumps: Vec<u8> (4 + n)
-    // - message: TransactionMessage (variable)
-
-    const FIXED_SIZE: usize = 8 + 32 + 32 + 8 + 1 + 1 + 1;
-
-    if tx_data.len() < FIXED_SIZE {
-        return Err(Error::Squads("Invalid vault transaction data".into()));
-    }
-
-    // The vault is always needed as a signer for execution
-    let mut accounts = vec![AccountMeta::new(vault_pda, false)];
+/// Address lookup table program ID
+const ADDRESS_LOOKUP_TABLE_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("AddressLookupTab1e1111111111111111111111111");
 
-    // Skip the fixed fields to get to ephemeral_signer_bumps
-    let mut offset = FIXED_SIZE;
+/// Size of the lookup table header that precedes its addresses
+const LOOKUP_TABLE_META_SIZE: usize = 56;
 
-    if offset + 4 > tx_data.len() {
-        return Err(Error::Squads("Invalid vault transaction data".into()));
+/// Parse the addresses stored in an address lookup table account
+fn parse_lookup_table(key: &Pubkey, account: &Account) -> Result<(Pubkey, Vec<Pubkey>)> {
+    if account.owner != ADDRESS_LOOKUP_TABLE_PROGRAM_ID {
+        return Err(Error::Squads(format!(
+            "{} is not an address lookup table",
+            key
+        )));
     }
+    let addresses = account
+        .data
+        .get(LOOKUP_TABLE_META_SIZE..)
+        .ok_or_else(|| Error::Squads(format!("Lookup table {} is too small", key)))?
+        .chunks(32)
+        .map(Pubkey::try_from)
+        .collect::<std::result::Result<_, _>>()
+        .map_err(|_| Error::Squads(format!("Lookup table {} has a partial address", key)))?;
+    Ok((*key, addresses))
+}
 
-    // Read ephemeral_signer_bumps length (Borsh Vec encoding: 4-byte length prefix)
-    let ephemeral_len = u32::from_le_bytes(
-        tx_data[offset..offset + 4]
-            .try_into()
-            .map_err(|_| Error::Squads("Failed to parse ephemeral signers length".into()))?,
-    ) as usize;
-    offset += 4 + ephemeral_len;
-
-    // Now we're at the TransactionMessage
-    // TransactionMessage struct (Borsh encoding):
-    // - num_signers: u8 (1)
-    // - num_writable_signers: u8 (1)
-    // - num_writable_non_signers: u8 (1)
-    // - account_keys: Vec<Pubkey> (4 + 32*n)
-    // - instructions: Vec<CompiledInstruction> (variable)
-    // - address_table_lookups: Vec<MessageAddressTableLookup> (variable)
-
-    if offset + 3 > tx_data.len() {
-        return Ok(accounts); // Return just the vault if we can't parse further
-    }
-
-    let num_signers = tx_data[offset] as usize;
-    let num_writable_signers = tx_data[offset + 1] as usize;
-    let num_writable_non_signers = tx_data[offset + 2] as usize;
-    offset += 3;
-
-    if offset + 4 > tx_data.len() {
-        return Ok(accounts);
+/// The remaining accounts `vault_transaction_execute` expects
+///
+/// Squads expects the lookup table accounts first, then the static account
+/// keys, then the writable and readonly accounts loaded from the tables. None
+/// of them sign; the vault signs via CPI.
+fn execute_accounts(
+    message: &TransactionMessage,
+    tables: &[(Pubkey, Vec<Pubkey>)],
+) -> Result<Vec<AccountMeta>> {
+    let mut accounts: Vec<AccountMeta> = message
+        .address_table_lookups
+        .iter()
+        .map(|lookup| AccountMeta::new_readonly(lookup.account_key, false))
+        .collect();
+
+    for (i, pubkey) in message.account_keys.iter().enumerate() {
+        if message.is_writable_index(i) {
+            accounts.push(AccountMeta::new(*pubkey, false));
+        } else {
+            accounts.push(AccountMeta::new_readonly(*pubkey, false));
+        }
     }
 
-    // Read account_keys length
-    let num_keys = u32::from_le_bytes(
-        tx_data[offset..offset + 4]
-            .try_into()
-            .map_err(|_| Error::Squads("Failed to parse account keys length".into()))?,
-    ) as usize;
-    offset += 4;
-
-    // Read each account key and add to remaining accounts
-    for i in 0..num_keys {
-        if offset + 32 > tx_data.len() {
-            break;
+    let mut readonly = Vec::new();
+    for lookup in &message.address_table_lookups {
+        let (_, addresses) = tables
+            .iter()
+            .find(|(key, _)| *key == lookup.account_key)
+            .ok_or_else(|| Error::Squads(format!("Missing lookup table {}", lookup.account_key)))?;
+        let get = |index: &u8| {
+            addresses.get(*index as usize).copied().ok_or_else(|| {
+                Error::Squads(format!(
+                    "Index {} out of range for lookup table {}",
+                    index, lookup.account_key
+                ))
+            })
+        };
+        for index in &lookup.writable_indexes {
+            accounts.push(AccountMeta::new(get(index)?, false));
         }
-
-        let key_bytes: [u8; 32] = tx_data[offset..offset + 32]
-            .try_into()
-            .map_err(|_| Error::Squads("Failed to parse account key".into()))?;
-
-        let pubkey = Pubkey::new_from_array(key_bytes);
-
-        // Skip the vault itself, we already added it
-        if pubkey != vault_pda {
-            // Determine if this account is writable based on position
-            let is_signer = i < num_signers;
-            let is_writable = if is_signer {
-                i < num_writable_signers
-            } else {
-                i < num_signers + num_writable_non_signers
-            };
-
-            if is_writable {
-                accounts.push(AccountMeta::new(pubkey, false));
-            } else {
-                accounts.push(AccountMeta::new_readonly(pubkey, false));
-            }
+        for index in &lookup.readonly_indexes {
+            readonly.push(AccountMeta::new_readonly(get(index)?, false));
         }
-
-        offset += 32;
     }
+    accounts.extend(readonly);
 
     Ok(accounts)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::squads::state::AddressTableLookup;
+
+    #[test]
+    fn test_execute_accounts_with_lookup_tables() {
+        let vault = Pubkey::new_unique();
+        let program = Pubkey::new_unique();
+        let table = Pubkey::new_unique();
+        let loaded: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+
+        let message = TransactionMessage {
+            num_signers: 1,
+            num_writable_signers: 1,
+            num_writable_non_signers: 0,
+            account_keys: vec![vault, program],
+            instructions: vec![],
+            address_table_lookups: vec![AddressTableLookup {
+                account_key: table,
+                writable_indexes: vec![2],
+                readonly_indexes: vec![0],
+            }],
+        };
+
+        let mut data = vec![0u8; LOOKUP_TABLE_META_SIZE];
+        for key in &loaded {
+            data.extend_from_slice(key.as_ref());
+        }
+        let account = Account {
+            lamports: 1,
+            data,
+            owner: ADDRESS_LOOKUP_TABLE_PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        let tables = vec![parse_lookup_table(&table, &account).unwrap()];
+
+        let accounts = execute_accounts(&message, &tables).unwrap();
+        let keys: Vec<Pubkey> = accounts.iter().map(|meta| meta.pubkey).collect();
+        assert_eq!(keys, vec![table, vault, program, loaded[2], loaded[0]]);
+        assert!(accounts[1].is_writable && accounts[3].is_writable);
+        assert!(!accounts[2].is_writable && !accounts[4].is_writable);
+        assert!(accounts.iter().all(|meta| !meta.is_signer));
+
+        // Indexes past the end of the table are rejected
+        let mut message = message;
+        message.address_table_lookups[0].readonly_indexes = vec![3];
+        assert!(execute_accounts(&message, &tables).is_err());
+
+        let foreign = Account {
+            owner: Pubkey::new_unique(),
+            ..account
+        };
+        assert!(parse_lookup_table(&table, &foreign).is_err());
+    }
+}
@@ -8,6 +8,8 @@ use solana_sdk::{
     pubkey::Pubkey,
 };
 
+use super::state::{Member, write_optional_pubkey};
+
 /// System program ID
 const SYSTEM_PROGRAM_ID: Pubkey = solana_sdk::pubkey!("11111111111111111111111111111111");
 
@@ -19,8 +21,33 @@ mod discriminator {
     pub const PROPOSAL_CREATE: [u8; 8] = [132, 116, 68, 174, 216, 160, 198, 22];
     /// proposal_approve
     pub const PROPOSAL_APPROVE: [u8; 8] = [227, 43, 144, 43, 163, 82, 190, 5];
+    /// proposal_reject
+    pub const PROPOSAL_REJECT: [u8; 8] = [243, 62, 134, 156, 230, 106, 246, 135];
+    /// proposal_cancel
+    pub const PROPOSAL_CANCEL: [u8; 8] = [27, 42, 127, 237, 38, 163, 84, 203];
     /// vault_transaction_execute
     pub const VAULT_TRANSACTION_EXECUTE: [u8; 8] = [142, 231, 170, 21, 232, 184, 207, 168];
+    /// multisig_create_v2
+    pub const MULTISIG_CREATE_V2: [u8; 8] = [50, 221, 199, 93, 40, 245, 139, 233];
+}
+
+/// Arguments for multisig_create_v2 instruction
+#[derive(BorshSerialize)]
+pub struct MultisigCreateArgsV2 {
+    /// Authority that can change the config without a vote
+    #[borsh(serialize_with = "write_optional_pubkey")]
+    pub config_authority: Option<Pubkey>,
+    /// Approvals needed to execute a proposal
+    pub threshold: u16,
+    /// Members and their permissions
+    pub members: Vec<Member>,
+    /// Seconds between approval and when a proposal can be executed
+    pub time_lock: u32,
+    /// Account that receives rent from closed accounts
+    #[borsh(serialize_with = "write_optional_pubkey")]
+    pub rent_collector: Option<Pubkey>,
+    /// Optional memo
+    pub memo: Option<String>,
 }
 
 /// Arguments for vault_transaction_create instruction
@@ -45,7 +72,8 @@ pub struct ProposalCreateArgs {
     pub draft: bool,
 }
 
-/// Arguments for proposal_approve instruction
+/// Arguments for proposal_approve, proposal_reject and proposal_cancel
+/// instructions
 #[derive(BorshSerialize)]
 pub struct ProposalVoteArgs {
     /// Optional memo
@@ -110,7 +138,64 @@ pub fn proposal_approve(
     args: ProposalVoteArgs,
     program_id: Pubkey,
 ) -> Instruction {
-    let mut data = discriminator::PROPOSAL_APPROVE.to_vec();
+    proposal_vote(
+        discriminator::PROPOSAL_APPROVE,
+        multisig,
+        proposal,
+        member,
+        args,
+        program_id,
+    )
+}
+
+/// Build a proposal_reject instruction
+pub fn proposal_reject(
+    multisig: Pubkey,
+    proposal: Pubkey,
+    member: Pubkey,
+    args: ProposalVoteArgs,
+    program_id: Pubkey,
+) -> Instruction {
+    proposal_vote(
+        discriminator::PROPOSAL_REJECT,
+        multisig,
+        proposal,
+        member,
+        args,
+        program_id,
+    )
+}
+
+/// Build a proposal_cancel instruction
+///
+/// Cancels an approved proposal that has not been executed yet
+pub fn proposal_cancel(
+    multisig: Pubkey,
+    proposal: Pubkey,
+    member: Pubkey,
+    args: ProposalVoteArgs,
+    program_id: Pubkey,
+) -> Instruction {
+    proposal_vote(
+        discriminator::PROPOSAL_CANCEL,
+        multisig,
+        proposal,
+        member,
+        args,
+        program_id,
+    )
+}
+
+/// Build an instruction that takes the accounts and arguments of a vote
+fn proposal_vote(
+    discriminator: [u8; 8],
+    multisig: Pubkey,
+    proposal: Pubkey,
+    member: Pubkey,
+    args: ProposalVoteArgs,
+    program_id: Pubkey,
+) -> Instruction {
+    let mut data = discriminator.to_vec();
     data.extend(borsh::to_vec(&args).unwrap());
 
     Instruction {
@@ -149,3 +234,33 @@ pub fn vault_transaction_execute(
         data,
     }
 }
+
+/// Build a multisig_create_v2 instruction
+///
+/// `create_key` seeds the multisig PDA and must sign; `creator` pays rent and
+/// the creation fee to `treasury`
+pub fn multisig_create_v2(
+    program_config: Pubkey,
+    treasury: Pubkey,
+    multisig: Pubkey,
+    create_key: Pubkey,
+    creator: Pubkey,
+    args: MultisigCreateArgsV2,
+    program_id: Pubkey,
+) -> Instruction {
+    let mut data = discriminator::MULTISIG_CREATE_V2.to_vec();
+    data.extend(borsh::to_vec(&args).unwrap());
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(program_config, false),
+            AccountMeta::new(treasury, false),
+            AccountMeta::new(multisig, false),
+            AccountMeta::new_readonly(create_key, true),
+            AccountMeta::new(creator, true),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data,
+    }
+}
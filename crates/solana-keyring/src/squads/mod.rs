@@ -1,20 +1,32 @@
 //! Squads multisig integration
 //!
-//! Implements Squads Protocol v4 for multi-signature transaction management.
+//! Implements Squads Protocol v4 for multi-signature transaction management,
+//! creates multisigs, proposes externally built messages, and verifies
+//! proposals against a locally stored intent.
 
+mod create;
 mod execute;
 mod instructions;
 mod pda;
 mod proposal;
+mod propose;
+pub mod state;
+mod vaults;
+mod verify;
 
 use solana_client::rpc_client::RpcClient;
+use solana_commitment_config::CommitmentConfig;
 use solana_sdk::pubkey::Pubkey;
 
 use crate::error::{Error, Result};
 use crate::keypair::SecureKeypair;
 
+pub use create::*;
 pub use instructions::*;
 pub use pda::*;
+pub use propose::*;
+pub use vaults::*;
+pub use verify::*;
 
 /// Squads V4 program ID (mainnet)
 pub const SQUADS_PROGRAM_ID: &str = "SQDS4nPHovALA9Sm5LCgJqkKhkYshJwKhN9kD3h8Zzg";
@@ -27,6 +39,7 @@ pub struct SquadsSigner {
     member_keypair: SecureKeypair,
     pubkey_str: String,
     program_id: Pubkey,
+    memo: Option<String>,
 }
 
 impl SquadsSigner {
@@ -45,7 +58,10 @@ impl SquadsSigner {
             .parse()
             .map_err(|_| Error::Squads("Invalid program ID".into()))?;
 
-        let rpc_client = RpcClient::new(rpc_url.to_string());
+        // Confirm proposal transactions without solana-client's own spinner;
+        // callers show progress with `crate::progress`
+        let rpc_client =
+            RpcClient::new_with_commitment(rpc_url.to_string(), CommitmentConfig::confirmed());
 
         Ok(Self {
             pubkey_str: multisig_address.to_string(),
@@ -54,9 +70,18 @@ impl SquadsSigner {
             rpc_client,
             member_keypair,
             program_id,
+            memo: None,
         })
     }
 
+    /// Attach a memo to the proposals and votes this signer sends
+    ///
+    /// Squads shows the memo next to the proposal in its UI.
+    pub fn with_memo(mut self, memo: Option<String>) -> Self {
+        self.memo = memo;
+        self
+    }
+
     /// Get the multisig public key
     pub fn pubkey(&self) -> &str {
         &self.pubkey_str
@@ -74,7 +99,17 @@ impl SquadsSigner {
 
     /// Approve a proposal
     pub async fn approve_proposal(&self, transaction_index: u64) -> Result<()> {
-        proposal::approve_proposal(self, transaction_index).await
+        proposal::vote(self, transaction_index, proposal::Vote::Approve).await
+    }
+
+    /// Reject a proposal
+    pub async fn reject_proposal(&self, transaction_index: u64) -> Result<()> {
+        proposal::vote(self, transaction_index, proposal::Vote::Reject).await
+    }
+
+    /// Cancel an approved proposal
+    pub async fn cancel_proposal(&self, transaction_index: u64) -> Result<()> {
+        proposal::vote(self, transaction_index, proposal::Vote::Cancel).await
     }
 
     /// Execute a proposal
@@ -106,6 +141,11 @@ impl SquadsSigner {
     pub(crate) fn program_id(&self) -> &Pubkey {
         &self.program_id
     }
+
+    /// Get the memo attached to proposals and votes
+    pub(crate) fn memo(&self) -> Option<String> {
+        self.memo.clone()
+    }
 }
 
 /// Member permissions (bitmask)
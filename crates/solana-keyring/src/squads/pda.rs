@@ -10,6 +10,12 @@ pub const SEED_AUTHORITY: &[u8] = b"authority";
 pub const SEED_TRANSACTION: &[u8] = b"transaction";
 /// Seed prefix for proposal PDAs
 pub const SEED_PROPOSAL: &[u8] = b"proposal";
+/// Seed prefix for multisig and program config PDAs
+pub const SEED_PREFIX: &[u8] = b"multisig";
+/// Seed for multisig PDAs
+pub const SEED_MULTISIG: &[u8] = b"multisig";
+/// Seed for the program config PDA
+pub const SEED_PROGRAM_CONFIG: &[u8] = b"program_config";
 
 /// Get the vault PDA for a multisig
 pub fn get_vault_pda(multisig: &Pubkey, vault_index: u8, program_id: &Pubkey) -> Pubkey {
@@ -55,6 +61,23 @@ pub fn get_proposal_pda(multisig: &Pubkey, transaction_index: u64, program_id: &
     pda
 }
 
+/// Get the multisig PDA seeded by a create key
+pub fn get_multisig_pda(create_key: &Pubkey, program_id: &Pubkey) -> Pubkey {
+    let (pda, _bump) = Pubkey::find_program_address(
+        &[SEED_PREFIX, SEED_MULTISIG, create_key.as_ref()],
+        program_id,
+    );
+    pda
+}
+
+/// Get the program config PDA, which names the treasury that receives the
+/// multisig creation fee
+pub fn get_program_config_pda(program_id: &Pubkey) -> Pubkey {
+    let (pda, _bump) =
+        Pubkey::find_program_address(&[SEED_PREFIX, SEED_PROGRAM_CONFIG], program_id);
+    pda
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -83,4 +106,13 @@ mod tests {
         let proposal_pda = get_proposal_pda(&multisig, 1, &program_id);
         assert_ne!(proposal_pda, multisig);
     }
+
+    #[test]
+    fn test_multisig_pda_derivation() {
+        let create_key = Pubkey::new_unique();
+        let program_id: Pubkey = super::super::SQUADS_PROGRAM_ID.parse().unwrap();
+        let multisig = get_multisig_pda(&create_key, &program_id);
+        assert_ne!(multisig, create_key);
+        assert_ne!(multisig, get_program_config_pda(&program_id));
+    }
 }
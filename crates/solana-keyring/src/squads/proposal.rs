@@ -1,15 +1,15 @@
-//! Squads proposal creation and approval
+//! Squads proposal creation and voting
 
-use solana_commitment_config::CommitmentConfig;
 use solana_sdk::{pubkey::Pubkey, signature::Signer, transaction::Transaction};
 
 use super::{
-    SquadsSigner,
+    Permission, SquadsSigner,
     instructions::{
         ProposalCreateArgs, ProposalVoteArgs, VaultTransactionCreateArgs, proposal_approve,
-        proposal_create, vault_transaction_create,
+        proposal_cancel, proposal_create, proposal_reject, vault_transaction_create,
     },
     pda::{get_proposal_pda, get_transaction_pda},
+    state::Multisig,
 };
 use crate::error::{Error, Result};
 
@@ -29,26 +29,24 @@ pub async fn create_proposal(
         .get_account_data(&multisig_pda)
         .map_err(|e| Error::Squads(format!("Failed to fetch multisig account: {}", e)))?;
 
-    // Parse transaction_index from multisig account data
-    // The Squads v4 Multisig struct layout (after 8-byte Anchor discriminator):
-    // - create_key: Pubkey (32)
-    // - config_authority: Pubkey (32)
-    // - threshold: u16 (2)
-    // - time_lock: u32 (4)
-    // - transaction_index: u64 (8)
-    // Offset = 8 + 32 + 32 + 2 + 4 = 78
-    const TX_INDEX_OFFSET: usize = 8 + 32 + 32 + 2 + 4;
-
-    if multisig_data.len() < TX_INDEX_OFFSET + 8 {
-        return Err(Error::Squads("Invalid multisig account data".into()));
+    let multisig = Multisig::from_account_data(&multisig_data)?;
+
+    // Fail before paying fees if the program would reject the member
+    let permissions = multisig
+        .members
+        .iter()
+        .find(|m| m.key == member_pubkey)
+        .map_or(0, |m| m.permissions);
+    for permission in [Permission::Initiate, Permission::Vote] {
+        if !permission.has(permissions) {
+            return Err(Error::Squads(format!(
+                "{} lacks the {:?} permission on multisig {}",
+                member_pubkey, permission, multisig_pda
+            )));
+        }
     }
 
-    let transaction_index = u64::from_le_bytes(
-        multisig_data[TX_INDEX_OFFSET..TX_INDEX_OFFSET + 8]
-            .try_into()
-            .map_err(|_| Error::Squads("Failed to parse transaction index".into()))?,
-    );
-    let next_index = transaction_index + 1;
+    let next_index = multisig.transaction_index + 1;
 
     // Derive PDAs for the new transaction and proposal
     let transaction_pda = get_transaction_pda(&multisig_pda, next_index, &program_id);
@@ -59,7 +57,7 @@ pub async fn create_proposal(
         vault_index: signer.vault_index(),
         ephemeral_signers: 0,
         transaction_message: transaction_message.to_vec(),
-        memo: None,
+        memo: signer.memo(),
     };
 
     let vault_tx_ix = vault_transaction_create(
@@ -100,20 +98,35 @@ pub async fn create_proposal(
     );
 
     // Send transaction
-    let signature = rpc
-        .send_and_confirm_transaction_with_spinner_and_commitment(
-            &tx,
-            CommitmentConfig::confirmed(),
-        )
+    rpc.send_and_confirm_transaction(&tx)
         .map_err(|e| Error::Squads(format!("Failed to create proposal: {}", e)))?;
 
-    println!("Created proposal at index {}: {}", next_index, signature);
-
     Ok((proposal_pda, next_index))
 }
 
-/// Approve a proposal
-pub async fn approve_proposal(signer: &SquadsSigner, transaction_index: u64) -> Result<()> {
+/// A member's vote on a proposal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Vote {
+    /// Approve an active proposal
+    Approve,
+    /// Reject an active proposal
+    Reject,
+    /// Cancel an approved proposal before it is executed
+    Cancel,
+}
+
+impl Vote {
+    fn verb(self) -> &'static str {
+        match self {
+            Self::Approve => "approve",
+            Self::Reject => "reject",
+            Self::Cancel => "cancel",
+        }
+    }
+}
+
+/// Vote on a proposal with the member key
+pub async fn vote(signer: &SquadsSigner, transaction_index: u64, vote: Vote) -> Result<()> {
     let rpc = signer.rpc_client();
     let multisig_pda = *signer.multisig_pda();
     let member = signer.member_keypair().to_solana_keypair();
@@ -123,10 +136,16 @@ pub async fn approve_proposal(signer: &SquadsSigner, transaction_index: u64) ->
     // Derive proposal PDA
     let proposal_pda = get_proposal_pda(&multisig_pda, transaction_index, &program_id);
 
-    // Build proposal approve instruction
-    let vote_args = ProposalVoteArgs { memo: None };
-
-    let approve_ix = proposal_approve(
+    // Build the vote instruction
+    let vote_args = ProposalVoteArgs {
+        memo: signer.memo(),
+    };
+    let build = match vote {
+        Vote::Approve => proposal_approve,
+        Vote::Reject => proposal_reject,
+        Vote::Cancel => proposal_cancel,
+    };
+    let vote_ix = build(
         multisig_pda,
         proposal_pda,
         member_pubkey,
@@ -140,22 +159,12 @@ pub async fn approve_proposal(signer: &SquadsSigner, transaction_index: u64) ->
         .map_err(|e| Error::Squads(format!("Failed to get blockhash: {}", e)))?;
 
     // Build and sign transaction
-    let tx = Transaction::new_signed_with_payer(
-        &[approve_ix],
-        Some(&member_pubkey),
-        &[member],
-        blockhash,
-    );
+    let tx =
+        Transaction::new_signed_with_payer(&[vote_ix], Some(&member_pubkey), &[member], blockhash);
 
     // Send transaction
-    let signature = rpc
-        .send_and_confirm_transaction_with_spinner_and_commitment(
-            &tx,
-            CommitmentConfig::confirmed(),
-        )
-        .map_err(|e| Error::Squads(format!("Failed to approve proposal: {}", e)))?;
-
-    println!("Approved proposal {}: {}", transaction_index, signature);
+    rpc.send_and_confirm_transaction(&tx)
+        .map_err(|e| Error::Squads(format!("Failed to {} proposal: {}", vote.verb(), e)))?;
 
     Ok(())
 }
@@ -0,0 +1,195 @@
+//! Proposing externally built transactions
+//!
+//! Tooling outside the keyring (deploy scripts, program upgrade builders)
+//! often produces an unsigned message with the vault as its signer. These
+//! helpers read such a message, check that the vault is the only account
+//! that has to sign it, and encode it as the `transaction_message` of a
+//! vault transaction.
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use sha2::{Digest, Sha256};
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+
+use super::pda::get_vault_pda;
+use crate::error::{Error, Result};
+
+/// Parse an unsigned message file
+///
+/// Accepts the wire format as raw bytes, base64 or base58, holding either a
+/// bare legacy or v0 message or a transaction whose signatures are all
+/// empty.
+pub fn read_unsigned_message(data: &[u8]) -> Result<VersionedMessage> {
+    let bytes = match std::str::from_utf8(data).map(str::trim) {
+        Ok(text) if !text.is_empty() => STANDARD
+            .decode(text)
+            .ok()
+            .or_else(|| bs58::decode(text).into_vec().ok())
+            .unwrap_or_else(|| data.to_vec()),
+        _ => data.to_vec(),
+    };
+
+    if let Some(tx) = deserialize_exact::<VersionedTransaction>(&bytes) {
+        if tx.signatures.iter().any(|s| *s != Default::default()) {
+            return Err(Error::Squads(
+                "Transaction is already signed; propose the unsigned message".into(),
+            ));
+        }
+        return Ok(tx.message);
+    }
+    deserialize_exact::<VersionedMessage>(&bytes).ok_or_else(|| {
+        Error::Squads("File is not a serialized Solana message or transaction".into())
+    })
+}
+
+/// Deserialize only if the value uses every byte
+fn deserialize_exact<T: serde::de::DeserializeOwned + serde::Serialize>(bytes: &[u8]) -> Option<T> {
+    let value: T = bincode::deserialize(bytes).ok()?;
+    (bincode::serialized_size(&value).ok()? == bytes.len() as u64).then_some(value)
+}
+
+/// The vault index whose authority is the message's only signer
+///
+/// A vault transaction is signed by the vault PDA when it executes; any
+/// other required signer could never sign it.
+pub fn vault_authority(
+    message: &VersionedMessage,
+    multisig: &Pubkey,
+    program_id: &Pubkey,
+) -> Result<u8> {
+    let num_signers = message.header().num_required_signatures as usize;
+    let signers = message
+        .static_account_keys()
+        .get(..num_signers)
+        .ok_or_else(|| Error::Squads("Invalid message header".into()))?;
+
+    let [signer] = signers else {
+        return Err(Error::Squads(format!(
+            "Message requires {} signers; a vault transaction can only be signed by the vault",
+            signers.len()
+        )));
+    };
+
+    (0..=u8::MAX)
+        .find(|&index| get_vault_pda(multisig, index, program_id) == *signer)
+        .ok_or_else(|| {
+            Error::Squads(format!(
+                "Message signer {} is not a vault of multisig {}",
+                signer, multisig
+            ))
+        })
+}
+
+/// Hex SHA-256 of a message's wire format, for matching proposals to files
+pub fn message_hash(message: &VersionedMessage) -> String {
+    hex::encode(Sha256::digest(message.serialize()))
+}
+
+/// Encode a message as the `transaction_message` of `vault_transaction_create`
+pub fn vault_message_bytes(message: &VersionedMessage) -> Result<Vec<u8>> {
+    fn len_u8(len: usize) -> Result<u8> {
+        u8::try_from(len).map_err(|_| Error::Squads("Message list too long".into()))
+    }
+
+    let header = message.header();
+    let account_keys = message.static_account_keys();
+    let num_signers = header.num_required_signatures;
+    let num_writable_signers = num_signers
+        .checked_sub(header.num_readonly_signed_accounts)
+        .ok_or_else(|| Error::Squads("Invalid message header".into()))?;
+    let num_writable_non_signers = account_keys
+        .len()
+        .checked_sub(num_signers as usize + header.num_readonly_unsigned_accounts as usize)
+        .and_then(|n| u8::try_from(n).ok())
+        .ok_or_else(|| Error::Squads("Invalid message header".into()))?;
+
+    let mut out = vec![num_signers, num_writable_signers, num_writable_non_signers];
+
+    out.push(len_u8(account_keys.len())?);
+    for key in account_keys {
+        out.extend_from_slice(key.as_ref());
+    }
+
+    out.push(len_u8(message.instructions().len())?);
+    for ix in message.instructions() {
+        out.push(ix.program_id_index);
+        out.push(len_u8(ix.accounts.len())?);
+        out.extend_from_slice(&ix.accounts);
+        let data_len = u16::try_from(ix.data.len())
+            .map_err(|_| Error::Squads("Instruction data too long".into()))?;
+        out.extend_from_slice(&data_len.to_le_bytes());
+        out.extend_from_slice(&ix.data);
+    }
+
+    let lookups = message.address_table_lookups().unwrap_or_default();
+    out.push(len_u8(lookups.len())?);
+    for lookup in lookups {
+        out.extend_from_slice(lookup.account_key.as_ref());
+        out.push(len_u8(lookup.writable_indexes.len())?);
+        out.extend_from_slice(&lookup.writable_indexes);
+        out.push(len_u8(lookup.readonly_indexes.len())?);
+        out.extend_from_slice(&lookup.readonly_indexes);
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::instruction::{AccountMeta, Instruction};
+    use solana_sdk::message::Message;
+
+    use super::*;
+    use crate::squads::SQUADS_PROGRAM_ID;
+
+    fn transfer_from(from: Pubkey) -> VersionedMessage {
+        let ix = Instruction::new_with_bytes(
+            Pubkey::default(),
+            &[2, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0],
+            vec![
+                AccountMeta::new(from, true),
+                AccountMeta::new(Pubkey::new_unique(), false),
+            ],
+        );
+        VersionedMessage::Legacy(Message::new(&[ix], Some(&from)))
+    }
+
+    #[test]
+    fn test_read_and_check_vault_authority() {
+        let program_id: Pubkey = SQUADS_PROGRAM_ID.parse().unwrap();
+        let multisig = Pubkey::new_unique();
+        let vault = get_vault_pda(&multisig, 3, &program_id);
+
+        let message = transfer_from(vault);
+        let encoded = STANDARD.encode(bincode::serialize(&message).unwrap());
+        let read = read_unsigned_message(format!("{}\n", encoded).as_bytes()).unwrap();
+        assert_eq!(read, message);
+        assert_eq!(vault_authority(&read, &multisig, &program_id).unwrap(), 3);
+
+        let unsigned = VersionedTransaction {
+            signatures: vec![Default::default()],
+            message: message.clone(),
+        };
+        let raw = bincode::serialize(&unsigned).unwrap();
+        assert_eq!(read_unsigned_message(&raw).unwrap(), message);
+
+        let other = transfer_from(Pubkey::new_unique());
+        assert!(vault_authority(&other, &multisig, &program_id).is_err());
+    }
+
+    #[test]
+    fn test_vault_message_bytes() {
+        let program_id: Pubkey = SQUADS_PROGRAM_ID.parse().unwrap();
+        let vault = get_vault_pda(&Pubkey::new_unique(), 0, &program_id);
+        let bytes = vault_message_bytes(&transfer_from(vault)).unwrap();
+
+        // 1 signer (writable), 1 writable non-signer, 3 keys
+        assert_eq!(&bytes[..4], &[1, 1, 1, 3]);
+        assert_eq!(&bytes[4..36], vault.as_ref());
+        // One instruction: program index 2, accounts [0, 1], 12 bytes of data
+        assert_eq!(&bytes[100..106], &[1, 2, 2, 0, 1, 12]);
+        assert_eq!(bytes.len(), 4 + 96 + 1 + 1 + 1 + 2 + 2 + 12 + 1);
+    }
+}
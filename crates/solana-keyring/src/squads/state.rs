@@ -0,0 +1,282 @@
+//! Squads v4 account state
+//!
+//! Borsh layouts of the multisig, proposal and vault transaction accounts.
+//! Each account starts with an 8-byte Anchor discriminator, which
+//! `from_account_data` skips; trailing bytes left by reallocation are ignored.
+
+use borsh::io::{Read, Result as IoResult, Write};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::error::{Error, Result};
+
+/// Deserialize an Anchor account, skipping its discriminator
+fn from_account_data<T: BorshDeserialize>(data: &[u8], name: &str) -> Result<T> {
+    let mut body = data
+        .get(8..)
+        .ok_or_else(|| Error::Squads(format!("{} account too small", name)))?;
+    T::deserialize(&mut body).map_err(|e| Error::Squads(format!("Invalid {} account: {}", name, e)))
+}
+
+/// A multisig account
+#[derive(Debug, Clone, PartialEq, Eq, BorshDeserialize)]
+pub struct Multisig {
+    /// Key used to seed the multisig PDA
+    #[borsh(deserialize_with = "read_pubkey")]
+    pub create_key: Pubkey,
+    /// Authority that can change the config without a vote
+    #[borsh(deserialize_with = "read_pubkey")]
+    pub config_authority: Pubkey,
+    /// Approvals needed to execute a proposal
+    pub threshold: u16,
+    /// Seconds between approval and when a proposal can be executed
+    pub time_lock: u32,
+    /// Index of the last transaction created
+    pub transaction_index: u64,
+    /// Transactions at or below this index can no longer be executed
+    pub stale_transaction_index: u64,
+    /// Account that receives rent from closed accounts
+    #[borsh(deserialize_with = "read_optional_pubkey")]
+    pub rent_collector: Option<Pubkey>,
+    /// PDA bump
+    pub bump: u8,
+    /// Members and their permissions
+    pub members: Vec<Member>,
+}
+
+impl Multisig {
+    /// Parse a multisig account
+    pub fn from_account_data(data: &[u8]) -> Result<Self> {
+        from_account_data(data, "multisig")
+    }
+}
+
+/// A member of a multisig
+#[derive(Debug, Clone, Copy, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct Member {
+    /// Member key
+    #[borsh(serialize_with = "write_pubkey", deserialize_with = "read_pubkey")]
+    pub key: Pubkey,
+    /// Permission bitmask, see [`Permission`](super::Permission)
+    pub permissions: u8,
+}
+
+/// The program's global config
+#[derive(Debug, Clone, PartialEq, Eq, BorshDeserialize)]
+pub struct ProgramConfig {
+    /// Authority that can change the config
+    #[borsh(deserialize_with = "read_pubkey")]
+    pub authority: Pubkey,
+    /// Lamports charged to create a multisig
+    pub multisig_creation_fee: u64,
+    /// Account the creation fee is paid to
+    #[borsh(deserialize_with = "read_pubkey")]
+    pub treasury: Pubkey,
+}
+
+impl ProgramConfig {
+    /// Parse the program config account
+    pub fn from_account_data(data: &[u8]) -> Result<Self> {
+        from_account_data(data, "program config")
+    }
+}
+
+/// Status of a proposal, with the unix time it was reached
+#[derive(Debug, Clone, Copy, PartialEq, Eq, BorshDeserialize)]
+#[allow(missing_docs)]
+pub enum ProposalStatus {
+    Draft { timestamp: i64 },
+    Active { timestamp: i64 },
+    Rejected { timestamp: i64 },
+    Approved { timestamp: i64 },
+    Executing,
+    Executed { timestamp: i64 },
+    Cancelled { timestamp: i64 },
+}
+
+/// A proposal account
+#[derive(Debug, Clone, PartialEq, Eq, BorshDeserialize)]
+pub struct Proposal {
+    /// Multisig the proposal belongs to
+    #[borsh(deserialize_with = "read_pubkey")]
+    pub multisig: Pubkey,
+    /// Index of the transaction the proposal is for
+    pub transaction_index: u64,
+    /// Current status
+    pub status: ProposalStatus,
+    /// PDA bump
+    pub bump: u8,
+    /// Members who approved
+    #[borsh(deserialize_with = "read_pubkeys")]
+    pub approved: Vec<Pubkey>,
+    /// Members who rejected
+    #[borsh(deserialize_with = "read_pubkeys")]
+    pub rejected: Vec<Pubkey>,
+    /// Members who cancelled
+    #[borsh(deserialize_with = "read_pubkeys")]
+    pub cancelled: Vec<Pubkey>,
+}
+
+impl Proposal {
+    /// Parse a proposal account
+    pub fn from_account_data(data: &[u8]) -> Result<Self> {
+        from_account_data(data, "proposal")
+    }
+}
+
+/// A vault transaction account
+#[derive(Debug, Clone, PartialEq, Eq, BorshDeserialize)]
+pub struct VaultTransaction {
+    /// Multisig the transaction belongs to
+    #[borsh(deserialize_with = "read_pubkey")]
+    pub multisig: Pubkey,
+    /// Member who created the transaction
+    #[borsh(deserialize_with = "read_pubkey")]
+    pub creator: Pubkey,
+    /// Transaction index
+    pub index: u64,
+    /// PDA bump
+    pub bump: u8,
+    /// Vault the transaction runs as
+    pub vault_index: u8,
+    /// Bump of the vault PDA
+    pub vault_bump: u8,
+    /// Bumps of the ephemeral signer PDAs
+    pub ephemeral_signer_bumps: Vec<u8>,
+    /// The inner transaction
+    pub message: TransactionMessage,
+}
+
+impl VaultTransaction {
+    /// Parse a vault transaction account
+    pub fn from_account_data(data: &[u8]) -> Result<Self> {
+        from_account_data(data, "vault transaction")
+    }
+}
+
+/// The inner transaction of a vault transaction, as stored on chain
+#[derive(Debug, Clone, PartialEq, Eq, BorshDeserialize)]
+pub struct TransactionMessage {
+    /// Number of signer accounts
+    pub num_signers: u8,
+    /// Number of writable signer accounts
+    pub num_writable_signers: u8,
+    /// Number of writable non-signer accounts
+    pub num_writable_non_signers: u8,
+    /// Static account keys
+    #[borsh(deserialize_with = "read_pubkeys")]
+    pub account_keys: Vec<Pubkey>,
+    /// Compiled instructions
+    pub instructions: Vec<CompiledInstruction>,
+    /// Address lookup tables the message loads accounts from
+    pub address_table_lookups: Vec<AddressTableLookup>,
+}
+
+impl TransactionMessage {
+    /// Whether the static account at `index` is writable
+    pub fn is_writable_index(&self, index: usize) -> bool {
+        let num_signers = self.num_signers as usize;
+        if index < num_signers {
+            index < self.num_writable_signers as usize
+        } else {
+            index < num_signers + self.num_writable_non_signers as usize
+        }
+    }
+}
+
+/// A compiled instruction inside a stored transaction message
+#[derive(Debug, Clone, PartialEq, Eq, BorshDeserialize)]
+pub struct CompiledInstruction {
+    /// Index of the program ID in the account keys
+    pub program_id_index: u8,
+    /// Indexes of the instruction accounts in the account keys
+    pub account_indexes: Vec<u8>,
+    /// Instruction data
+    pub data: Vec<u8>,
+}
+
+/// An address lookup table a stored transaction message loads accounts from
+#[derive(Debug, Clone, PartialEq, Eq, BorshDeserialize)]
+pub struct AddressTableLookup {
+    /// Lookup table address
+    #[borsh(deserialize_with = "read_pubkey")]
+    pub account_key: Pubkey,
+    /// Indexes of writable accounts in the table
+    pub writable_indexes: Vec<u8>,
+    /// Indexes of readonly accounts in the table
+    pub readonly_indexes: Vec<u8>,
+}
+
+fn read_pubkey<R: Read>(reader: &mut R) -> IoResult<Pubkey> {
+    Ok(Pubkey::new_from_array(<[u8; 32]>::deserialize_reader(
+        reader,
+    )?))
+}
+
+fn read_pubkeys<R: Read>(reader: &mut R) -> IoResult<Vec<Pubkey>> {
+    Ok(Vec::<[u8; 32]>::deserialize_reader(reader)?
+        .into_iter()
+        .map(Pubkey::new_from_array)
+        .collect())
+}
+
+fn write_pubkey<W: Write>(key: &Pubkey, writer: &mut W) -> IoResult<()> {
+    key.to_bytes().serialize(writer)
+}
+
+pub(super) fn write_optional_pubkey<W: Write>(
+    key: &Option<Pubkey>,
+    writer: &mut W,
+) -> IoResult<()> {
+    key.map(|key| key.to_bytes()).serialize(writer)
+}
+
+fn read_optional_pubkey<R: Read>(reader: &mut R) -> IoResult<Option<Pubkey>> {
+    Ok(Option::<[u8; 32]>::deserialize_reader(reader)?.map(Pubkey::new_from_array))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_multisig() {
+        let member = Pubkey::new_unique();
+        let collector = Pubkey::new_unique();
+
+        let mut data = vec![0u8; 8 + 32 + 32];
+        data.extend_from_slice(&3u16.to_le_bytes());
+        data.extend_from_slice(&60u32.to_le_bytes());
+        data.extend_from_slice(&41u64.to_le_bytes());
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.push(1);
+        data.extend_from_slice(collector.as_ref());
+        data.push(255);
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(member.as_ref());
+        data.push(7);
+        // Space reserved for more members
+        data.extend_from_slice(&[0u8; 33]);
+
+        let multisig = Multisig::from_account_data(&data).unwrap();
+        assert_eq!(multisig.threshold, 3);
+        assert_eq!(multisig.time_lock, 60);
+        assert_eq!(multisig.transaction_index, 41);
+        assert_eq!(multisig.rent_collector, Some(collector));
+        assert_eq!(multisig.members[0].key, member);
+        assert_eq!(multisig.members[0].permissions, 7);
+
+        assert!(Multisig::from_account_data(&data[..100]).is_err());
+    }
+
+    #[test]
+    fn test_member_round_trip() {
+        let member = Member {
+            key: Pubkey::new_unique(),
+            permissions: 3,
+        };
+        let data = borsh::to_vec(&member).unwrap();
+        assert_eq!(data.len(), 33);
+        assert_eq!(Member::try_from_slice(&data).unwrap(), member);
+    }
+}
@@ -0,0 +1,303 @@
+//! Squads vault discovery and selection
+//!
+//! A Squads v4 multisig controls up to 256 vaults, one PDA per index. Nothing
+//! on chain lists the vaults in use, so discovery derives every vault PDA and
+//! keeps those with an account. Vaults can be named in the keyring, and each
+//! multisig entry stores the vault used when none is given.
+
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+
+use super::pda::get_vault_pda;
+use crate::db::{Database, SquadsMultisigRow};
+use crate::error::{Error, Result};
+
+/// Number of vaults a multisig can have (vault indexes are a `u8`)
+pub const MAX_VAULTS: usize = 256;
+
+/// Accounts fetched per `getMultipleAccounts` request
+const ACCOUNTS_PER_REQUEST: usize = 100;
+
+/// A vault found on chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VaultInfo {
+    /// Vault index
+    pub index: u8,
+    /// Vault PDA
+    pub address: Pubkey,
+    /// SOL balance in lamports
+    pub lamports: u64,
+}
+
+/// Find the vaults of a multisig that have an account on chain
+///
+/// Derives all [`MAX_VAULTS`] vault PDAs and fetches them in batches, so this
+/// makes three RPC requests.
+pub fn discover_vaults(
+    rpc: &RpcClient,
+    multisig: &Pubkey,
+    program_id: &Pubkey,
+) -> Result<Vec<VaultInfo>> {
+    let addresses: Vec<Pubkey> = (0..MAX_VAULTS)
+        .map(|index| get_vault_pda(multisig, index as u8, program_id))
+        .collect();
+
+    let mut vaults = Vec::new();
+    for (batch, chunk) in addresses.chunks(ACCOUNTS_PER_REQUEST).enumerate() {
+        let accounts = rpc
+            .get_multiple_accounts(chunk)
+            .map_err(|e| Error::Squads(format!("Failed to fetch vault accounts: {}", e)))?;
+
+        for (offset, (address, account)) in chunk.iter().zip(accounts).enumerate() {
+            if let Some(account) = account {
+                vaults.push(VaultInfo {
+                    index: (batch * ACCOUNTS_PER_REQUEST + offset) as u8,
+                    address: *address,
+                    lamports: account.lamports,
+                });
+            }
+        }
+    }
+
+    Ok(vaults)
+}
+
+/// A vault picked by index or by the name given to it in the keyring
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VaultSelector {
+    /// Vault index
+    Index(u8),
+    /// Vault name
+    Name(String),
+}
+
+impl std::str::FromStr for VaultSelector {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s.is_empty() {
+            return Err(Error::Squads("Vault name cannot be empty".into()));
+        }
+        if s.bytes().all(|b| b.is_ascii_digit()) {
+            return s
+                .parse()
+                .map(VaultSelector::Index)
+                .map_err(|_| Error::Squads(format!("Vault index out of range: {}", s)));
+        }
+        Ok(VaultSelector::Name(s.to_string()))
+    }
+}
+
+impl std::fmt::Display for VaultSelector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VaultSelector::Index(index) => write!(f, "{}", index),
+            VaultSelector::Name(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// A multisig vault resolved for signing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedVault {
+    /// Multisig address
+    pub multisig: Pubkey,
+    /// Vault index
+    pub index: u8,
+    /// Vault name, if it has one
+    pub name: Option<String>,
+}
+
+impl ResolvedVault {
+    /// The vault PDA
+    pub fn address(&self, program_id: &Pubkey) -> Pubkey {
+        get_vault_pda(&self.multisig, self.index, program_id)
+    }
+}
+
+/// Vault names and defaults for stored multisigs
+pub struct SquadsVaults<'a> {
+    db: &'a Database,
+}
+
+impl<'a> SquadsVaults<'a> {
+    /// Create a new vaults handle
+    pub fn new(db: &'a Database) -> Self {
+        Self { db }
+    }
+
+    /// Name a vault, replacing any earlier name for its index
+    ///
+    /// Names can't look like indexes, so a selector is never ambiguous.
+    pub fn name(&self, multisig: &str, index: u8, name: &str) -> Result<()> {
+        if !matches!(name.parse::<VaultSelector>()?, VaultSelector::Name(_)) {
+            return Err(Error::Squads(format!(
+                "Vault name cannot be a number: {}",
+                name
+            )));
+        }
+        self.multisig(multisig)?;
+        if self
+            .names(multisig)?
+            .iter()
+            .any(|(i, n)| n == name && *i != index)
+        {
+            return Err(Error::Squads(format!(
+                "Vault name already in use: {}",
+                name
+            )));
+        }
+        self.db.set_squads_vault_name(multisig, index as u32, name)
+    }
+
+    /// Remove a vault name
+    pub fn unname(&self, multisig: &str, name: &str) -> Result<bool> {
+        self.db.delete_squads_vault_name(multisig, name)
+    }
+
+    /// Named vaults of a multisig, as (index, name) in index order
+    pub fn names(&self, multisig: &str) -> Result<Vec<(u8, String)>> {
+        self.db
+            .list_squads_vault_names(multisig)?
+            .into_iter()
+            .map(|row| Ok((vault_index(row.vault_index)?, row.name)))
+            .collect()
+    }
+
+    /// Set the vault used when none is given
+    pub fn set_default(&self, multisig: &str, vault: &VaultSelector) -> Result<ResolvedVault> {
+        let resolved = self.resolve(multisig, Some(vault))?;
+        self.db
+            .set_squads_vault_index(multisig, resolved.index as u32)?;
+        Ok(resolved)
+    }
+
+    /// Resolve a vault of a multisig
+    ///
+    /// `multisig` is a stored multisig's label or address. Without a
+    /// selector, the multisig's default vault is used. A multisig address
+    /// that isn't stored can only use vault indexes, defaulting to vault 0.
+    pub fn resolve(&self, multisig: &str, vault: Option<&VaultSelector>) -> Result<ResolvedVault> {
+        let Some(row) = self.db.get_squads_multisig(multisig)? else {
+            let address = multisig
+                .parse()
+                .map_err(|_| Error::Squads(format!("Unknown multisig: {}", multisig)))?;
+            return match vault {
+                None => Ok(ResolvedVault {
+                    multisig: address,
+                    index: 0,
+                    name: None,
+                }),
+                Some(VaultSelector::Index(index)) => Ok(ResolvedVault {
+                    multisig: address,
+                    index: *index,
+                    name: None,
+                }),
+                Some(VaultSelector::Name(name)) => Err(Error::Squads(format!(
+                    "Unknown vault '{}': multisig {} is not in the keyring",
+                    name, multisig
+                ))),
+            };
+        };
+
+        let names = self.names(&row.multisig_pubkey)?;
+        let index = match vault {
+            None => vault_index(row.vault_index)?,
+            Some(VaultSelector::Index(index)) => *index,
+            Some(VaultSelector::Name(name)) => names
+                .iter()
+                .find(|(_, n)| n == name)
+                .map(|(index, _)| *index)
+                .ok_or_else(|| {
+                    Error::Squads(format!("Unknown vault '{}' for {}", name, row.label))
+                })?,
+        };
+
+        Ok(ResolvedVault {
+            multisig: parse_multisig(&row)?,
+            index,
+            name: names
+                .into_iter()
+                .find(|(i, _)| *i == index)
+                .map(|(_, name)| name),
+        })
+    }
+
+    fn multisig(&self, identifier: &str) -> Result<SquadsMultisigRow> {
+        self.db
+            .get_squads_multisig(identifier)?
+            .ok_or_else(|| Error::Squads(format!("Unknown multisig: {}", identifier)))
+    }
+}
+
+fn vault_index(index: u32) -> Result<u8> {
+    u8::try_from(index).map_err(|_| Error::Squads(format!("Invalid vault index: {}", index)))
+}
+
+fn parse_multisig(row: &SquadsMultisigRow) -> Result<Pubkey> {
+    row.multisig_pubkey.parse().map_err(|_| {
+        Error::Squads(format!(
+            "Invalid multisig address in keyring: {}",
+            row.multisig_pubkey
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_selector() {
+        assert_eq!(
+            "3".parse::<VaultSelector>().unwrap(),
+            VaultSelector::Index(3)
+        );
+        assert_eq!(
+            "ops".parse::<VaultSelector>().unwrap(),
+            VaultSelector::Name("ops".into())
+        );
+        assert!("256".parse::<VaultSelector>().is_err());
+        assert!("".parse::<VaultSelector>().is_err());
+    }
+
+    #[test]
+    fn test_resolve_vaults() {
+        let db = Database::open_in_memory().unwrap();
+        let multisig = Pubkey::new_unique();
+        db.store_squads_multisig(&multisig.to_string(), "treasury", 0, 2, &[])
+            .unwrap();
+        let vaults = SquadsVaults::new(&db);
+
+        vaults.name("treasury", 2, "ops").unwrap();
+        assert!(vaults.name("treasury", 3, "7").is_err());
+        assert!(vaults.name("treasury", 3, "ops").is_err());
+        assert_eq!(vaults.names("treasury").unwrap(), [(2, "ops".to_string())]);
+
+        // Vault 0 until a default is set
+        assert_eq!(vaults.resolve("treasury", None).unwrap().index, 0);
+        let ops = VaultSelector::Name("ops".into());
+        vaults.set_default("treasury", &ops).unwrap();
+        let resolved = vaults.resolve(&multisig.to_string(), None).unwrap();
+        assert_eq!(resolved.index, 2);
+        assert_eq!(resolved.name.as_deref(), Some("ops"));
+
+        assert_eq!(
+            vaults
+                .resolve("treasury", Some(&VaultSelector::Index(5)))
+                .unwrap()
+                .index,
+            5
+        );
+        assert!(
+            vaults
+                .resolve("treasury", Some(&VaultSelector::Name("payroll".into())))
+                .is_err()
+        );
+
+        // Multisigs outside the keyring only take indexes
+        let other = Pubkey::new_unique().to_string();
+        assert_eq!(vaults.resolve(&other, None).unwrap().index, 0);
+        assert!(vaults.resolve(&other, Some(&ops)).is_err());
+    }
+}
@@ -0,0 +1,699 @@
+//! Verifying Squads proposals against a local intent
+//!
+//! A member asked to approve a proposal created by someone else sees only an
+//! opaque vault transaction. An intent file records what the proposal is
+//! supposed to do (its vault, the programs it calls, its transfers or its
+//! exact instructions), agreed on out of band, and [`verify_intent`] compares
+//! it with the vault transaction stored on chain.
+
+use std::collections::HashMap;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::error::{Error, Result};
+use crate::spending::{format_sol, parse_sol};
+
+/// System program ID
+const SYSTEM_PROGRAM_ID: &str = "11111111111111111111111111111111";
+
+/// SPL Token and Token-2022 program IDs
+const TOKEN_PROGRAM_IDS: [&str; 2] = [
+    "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+    "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb",
+];
+
+/// Address lookup used by a vault transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VaultLookup {
+    /// Lookup table address
+    pub table: Pubkey,
+    /// Indexes of writable addresses loaded from the table
+    pub writable_indexes: Vec<u8>,
+    /// Indexes of readonly addresses loaded from the table
+    pub readonly_indexes: Vec<u8>,
+}
+
+/// An instruction of a vault transaction, with its accounts resolved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VaultInstruction {
+    /// Program invoked
+    pub program_id: Pubkey,
+    /// Instruction accounts, in order
+    pub accounts: Vec<Pubkey>,
+    /// Instruction data
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CompiledInstruction {
+    program_id_index: u8,
+    account_indexes: Vec<u8>,
+    data: Vec<u8>,
+}
+
+/// A vault transaction account
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VaultTransaction {
+    /// Multisig the transaction belongs to
+    pub multisig: Pubkey,
+    /// Member who created it
+    pub creator: Pubkey,
+    /// Transaction index
+    pub index: u64,
+    /// Vault the transaction runs as
+    pub vault_index: u8,
+    /// Static account keys
+    pub account_keys: Vec<Pubkey>,
+    /// Lookup tables the transaction loads accounts from
+    pub lookups: Vec<VaultLookup>,
+    instructions: Vec<CompiledInstruction>,
+}
+
+impl VaultTransaction {
+    /// Parse a vault transaction account
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        if data.get(..8) != Some(&account_discriminator("VaultTransaction")[..]) {
+            return Err(Error::Squads(
+                "Account is not a vault transaction (config transactions can't be verified)".into(),
+            ));
+        }
+
+        // VaultTransaction layout (after the 8-byte Anchor discriminator):
+        // multisig, creator, index (u64), bump, vault_index, vault_bump,
+        // ephemeral_signer_bumps (Vec<u8>), then the message with Borsh
+        // (u32-prefixed) vectors
+        let mut reader = Reader { data, offset: 8 };
+        let multisig = reader.pubkey()?;
+        let creator = reader.pubkey()?;
+        let index = reader.u64()?;
+        reader.u8()?;
+        let vault_index = reader.u8()?;
+        reader.u8()?;
+        let ephemeral_len = reader.len()?;
+        reader.take(ephemeral_len)?;
+
+        // Signer and writable counts aren't needed to compare contents
+        reader.take(3)?;
+
+        let num_keys = reader.len()?;
+        let mut account_keys = Vec::with_capacity(num_keys.min(256));
+        for _ in 0..num_keys {
+            account_keys.push(reader.pubkey()?);
+        }
+
+        let num_instructions = reader.len()?;
+        let mut instructions = Vec::with_capacity(num_instructions.min(256));
+        for _ in 0..num_instructions {
+            instructions.push(CompiledInstruction {
+                program_id_index: reader.u8()?,
+                account_indexes: reader.bytes()?,
+                data: reader.bytes()?,
+            });
+        }
+
+        let num_lookups = reader.len()?;
+        let mut lookups = Vec::with_capacity(num_lookups.min(256));
+        for _ in 0..num_lookups {
+            lookups.push(VaultLookup {
+                table: reader.pubkey()?,
+                writable_indexes: reader.bytes()?,
+                readonly_indexes: reader.bytes()?,
+            });
+        }
+
+        Ok(Self {
+            multisig,
+            creator,
+            index,
+            vault_index,
+            account_keys,
+            lookups,
+            instructions,
+        })
+    }
+
+    /// The instructions with their accounts resolved
+    ///
+    /// `tables` maps each lookup table the transaction uses to its addresses.
+    pub fn instructions(
+        &self,
+        tables: &HashMap<Pubkey, Vec<Pubkey>>,
+    ) -> Result<Vec<VaultInstruction>> {
+        // Static keys, then writable loaded addresses, then readonly ones
+        let mut keys = self.account_keys.clone();
+        for writable in [true, false] {
+            for lookup in &self.lookups {
+                let addresses = tables.get(&lookup.table).ok_or_else(|| {
+                    Error::Squads(format!("Lookup table {} was not loaded", lookup.table))
+                })?;
+                let indexes = if writable {
+                    &lookup.writable_indexes
+                } else {
+                    &lookup.readonly_indexes
+                };
+                for &index in indexes {
+                    keys.push(*addresses.get(index as usize).ok_or_else(|| {
+                        Error::Squads(format!(
+                            "Lookup table {} has no address at index {}",
+                            lookup.table, index
+                        ))
+                    })?);
+                }
+            }
+        }
+
+        let key = |index: u8| {
+            keys.get(index as usize)
+                .copied()
+                .ok_or_else(|| Error::Squads(format!("Account index {} is out of range", index)))
+        };
+        self.instructions
+            .iter()
+            .map(|ix| {
+                Ok(VaultInstruction {
+                    program_id: key(ix.program_id_index)?,
+                    accounts: ix
+                        .account_indexes
+                        .iter()
+                        .map(|&index| key(index))
+                        .collect::<Result<_>>()?,
+                    data: ix.data.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// A SOL or token transfer made by an instruction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transfer {
+    /// Recipient: a wallet for SOL, a token account for tokens
+    pub destination: Pubkey,
+    /// Lamports, or token base units
+    pub amount: u64,
+    /// Whether this is a token transfer
+    pub token: bool,
+    /// Token mint, if the instruction names it
+    pub mint: Option<Pubkey>,
+}
+
+impl std::fmt::Display for Transfer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.token, self.mint) {
+            (false, _) => write!(f, "{} SOL to {}", format_sol(self.amount), self.destination),
+            (true, Some(mint)) => write!(
+                f,
+                "{} base units of {} to {}",
+                self.amount, mint, self.destination
+            ),
+            (true, None) => write!(
+                f,
+                "{} token base units to {}",
+                self.amount, self.destination
+            ),
+        }
+    }
+}
+
+/// SOL and token transfers made by instructions
+///
+/// Covers System Program transfers and SPL Token `Transfer` and
+/// `TransferChecked`; only `TransferChecked` names the mint.
+pub fn transfers(instructions: &[VaultInstruction]) -> Vec<Transfer> {
+    let amount_at = |data: &[u8], offset: usize| {
+        data.get(offset..offset + 8)
+            .and_then(|bytes| bytes.try_into().ok())
+            .map(u64::from_le_bytes)
+    };
+
+    instructions
+        .iter()
+        .filter_map(|ix| {
+            let program_id = ix.program_id.to_string();
+            if program_id == SYSTEM_PROGRAM_ID {
+                let discriminator = u32::from_le_bytes(ix.data.get(0..4)?.try_into().ok()?);
+                (discriminator == 2).then_some(())?;
+                Some(Transfer {
+                    destination: *ix.accounts.get(1)?,
+                    amount: amount_at(&ix.data, 4)?,
+                    token: false,
+                    mint: None,
+                })
+            } else if TOKEN_PROGRAM_IDS.contains(&program_id.as_str()) {
+                match ix.data.first()? {
+                    3 => Some(Transfer {
+                        destination: *ix.accounts.get(1)?,
+                        amount: amount_at(&ix.data, 1)?,
+                        token: true,
+                        mint: None,
+                    }),
+                    12 => Some(Transfer {
+                        destination: *ix.accounts.get(2)?,
+                        amount: amount_at(&ix.data, 1)?,
+                        token: true,
+                        mint: Some(*ix.accounts.get(1)?),
+                    }),
+                    _ => None,
+                }
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// What a proposal is expected to do, loaded from a JSON intent file
+///
+/// Every field is optional, but at least one must be given.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProposalIntent {
+    /// Vault the transaction must run as
+    #[serde(default)]
+    pub vault_index: Option<u8>,
+    /// Programs the transaction may invoke
+    #[serde(default)]
+    pub programs: Option<Vec<String>>,
+    /// The transaction's transfers; any other transfer is a mismatch
+    #[serde(default)]
+    pub transfers: Option<Vec<IntentTransfer>>,
+    /// The transaction's exact instructions, in order
+    #[serde(default)]
+    pub instructions: Option<Vec<IntentInstruction>>,
+}
+
+/// An expected transfer
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IntentTransfer {
+    /// Recipient: a wallet for SOL, a token account for tokens
+    pub destination: String,
+    /// SOL amount (e.g. "1.5"), or base units for token transfers
+    pub amount: String,
+    /// Token mint; omitted for SOL transfers
+    #[serde(default)]
+    pub mint: Option<String>,
+}
+
+/// An expected instruction
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IntentInstruction {
+    /// Program invoked
+    pub program_id: String,
+    /// Instruction accounts, in order
+    #[serde(default)]
+    pub accounts: Vec<String>,
+    /// Base64 instruction data
+    #[serde(default)]
+    pub data: String,
+}
+
+/// Result of comparing a proposal with an intent
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntentReport {
+    /// Ways the proposal differs from the intent
+    pub mismatches: Vec<String>,
+    /// Parts of the intent that couldn't be fully checked
+    pub warnings: Vec<String>,
+}
+
+impl IntentReport {
+    /// Whether the proposal matches the intent
+    pub fn matches(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+/// Compare a vault transaction's contents with an intent
+pub fn verify_intent(
+    intent: &ProposalIntent,
+    vault_index: u8,
+    instructions: &[VaultInstruction],
+) -> Result<IntentReport> {
+    if intent.vault_index.is_none()
+        && intent.programs.is_none()
+        && intent.transfers.is_none()
+        && intent.instructions.is_none()
+    {
+        return Err(Error::Squads("Intent has nothing to check".into()));
+    }
+
+    let mut report = IntentReport::default();
+
+    if let Some(expected) = intent.vault_index
+        && expected != vault_index
+    {
+        report.mismatches.push(format!(
+            "Runs as vault {}, expected vault {}",
+            vault_index, expected
+        ));
+    }
+
+    if let Some(programs) = &intent.programs {
+        let allowed = programs
+            .iter()
+            .map(|program| parse_pubkey(program))
+            .collect::<Result<Vec<_>>>()?;
+        let mut seen = Vec::new();
+        for ix in instructions {
+            if !allowed.contains(&ix.program_id) && !seen.contains(&ix.program_id) {
+                seen.push(ix.program_id);
+                report
+                    .mismatches
+                    .push(format!("Invokes unexpected program {}", ix.program_id));
+            }
+        }
+    }
+
+    if let Some(expected) = &intent.transfers {
+        check_transfers(expected, &transfers(instructions), &mut report)?;
+    }
+
+    if let Some(expected) = &intent.instructions {
+        check_instructions(expected, instructions, &mut report)?;
+    }
+
+    Ok(report)
+}
+
+fn check_transfers(
+    expected: &[IntentTransfer],
+    actual: &[Transfer],
+    report: &mut IntentReport,
+) -> Result<()> {
+    let mut remaining: Vec<&Transfer> = actual.iter().collect();
+
+    for transfer in expected {
+        let destination = parse_pubkey(&transfer.destination)?;
+        let (amount, mint) = match &transfer.mint {
+            None => (parse_sol(&transfer.amount)?, None),
+            Some(mint) => (
+                transfer.amount.trim().parse::<u64>().map_err(|_| {
+                    Error::Squads(format!("Invalid token amount: {}", transfer.amount))
+                })?,
+                Some(parse_pubkey(mint)?),
+            ),
+        };
+
+        let found = remaining.iter().position(|t| {
+            t.destination == destination
+                && t.amount == amount
+                && t.token == mint.is_some()
+                && (t.mint.is_none() || t.mint == mint)
+        });
+        match found {
+            Some(i) => {
+                let matched = remaining.remove(i);
+                if let (Some(mint), None) = (mint, matched.mint) {
+                    report.warnings.push(format!(
+                        "Transfer to {} doesn't name its mint, so {} wasn't checked",
+                        destination, mint
+                    ));
+                }
+            }
+            None => report.mismatches.push(format!(
+                "Missing transfer of {} to {}",
+                match mint {
+                    None => format!("{} SOL", format_sol(amount)),
+                    Some(mint) => format!("{} base units of {}", amount, mint),
+                },
+                destination
+            )),
+        }
+    }
+
+    for transfer in remaining {
+        report
+            .mismatches
+            .push(format!("Unexpected transfer of {}", transfer));
+    }
+    Ok(())
+}
+
+fn check_instructions(
+    expected: &[IntentInstruction],
+    actual: &[VaultInstruction],
+    report: &mut IntentReport,
+) -> Result<()> {
+    if expected.len() != actual.len() {
+        report.mismatches.push(format!(
+            "Has {} instructions, expected {}",
+            actual.len(),
+            expected.len()
+        ));
+    }
+
+    for (i, (expected, actual)) in expected.iter().zip(actual).enumerate() {
+        let program_id = parse_pubkey(&expected.program_id)?;
+        let accounts = expected
+            .accounts
+            .iter()
+            .map(|account| parse_pubkey(account))
+            .collect::<Result<Vec<_>>>()?;
+        let data = STANDARD
+            .decode(&expected.data)
+            .map_err(|e| Error::Squads(format!("Invalid data for instruction {}: {}", i + 1, e)))?;
+
+        if actual.program_id != program_id {
+            report.mismatches.push(format!(
+                "Instruction {} invokes {}, expected {}",
+                i + 1,
+                actual.program_id,
+                program_id
+            ));
+        }
+        if actual.accounts != accounts {
+            report.mismatches.push(format!(
+                "Instruction {} has different accounts than expected",
+                i + 1
+            ));
+        }
+        if actual.data != data {
+            report.mismatches.push(format!(
+                "Instruction {} has different data than expected",
+                i + 1
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Anchor discriminator of an account type
+fn account_discriminator(name: &str) -> [u8; 8] {
+    let hash = Sha256::digest(format!("account:{}", name));
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash[..8]);
+    discriminator
+}
+
+fn parse_pubkey(address: &str) -> Result<Pubkey> {
+    address
+        .parse()
+        .map_err(|_| Error::Squads(format!("Invalid address in intent: {}", address)))
+}
+
+fn truncated() -> Error {
+    Error::Squads("Vault transaction account is truncated".into())
+}
+
+/// Cursor over Borsh-encoded account data
+struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self.offset.checked_add(len).ok_or_else(truncated)?;
+        let bytes = self.data.get(self.offset..end).ok_or_else(truncated)?;
+        self.offset = end;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        let bytes = self.take(8)?.try_into().map_err(|_| truncated())?;
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    fn len(&mut self) -> Result<usize> {
+        let bytes = self.take(4)?.try_into().map_err(|_| truncated())?;
+        Ok(u32::from_le_bytes(bytes) as usize)
+    }
+
+    fn pubkey(&mut self) -> Result<Pubkey> {
+        let bytes: [u8; 32] = self.take(32)?.try_into().map_err(|_| truncated())?;
+        Ok(Pubkey::new_from_array(bytes))
+    }
+
+    fn bytes(&mut self) -> Result<Vec<u8>> {
+        let len = self.len()?;
+        Ok(self.take(len)?.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn system_transfer(lamports: u64) -> Vec<u8> {
+        let mut data = 2u32.to_le_bytes().to_vec();
+        data.extend_from_slice(&lamports.to_le_bytes());
+        data
+    }
+
+    /// Encode a vault transaction account with one lookup table
+    fn vault_transaction_account(
+        multisig: Pubkey,
+        keys: &[Pubkey],
+        table: Pubkey,
+        instructions: &[(u8, Vec<u8>, Vec<u8>)],
+    ) -> Vec<u8> {
+        let vec_len = |out: &mut Vec<u8>, len: usize| {
+            out.extend_from_slice(&(len as u32).to_le_bytes());
+        };
+
+        let mut out = account_discriminator("VaultTransaction").to_vec();
+        out.extend_from_slice(multisig.as_ref());
+        out.extend_from_slice(Pubkey::new_unique().as_ref());
+        out.extend_from_slice(&7u64.to_le_bytes());
+        out.extend_from_slice(&[255, 1, 254]);
+        vec_len(&mut out, 0);
+        out.extend_from_slice(&[1, 1, 1]);
+        vec_len(&mut out, keys.len());
+        for key in keys {
+            out.extend_from_slice(key.as_ref());
+        }
+        vec_len(&mut out, instructions.len());
+        for (program, accounts, data) in instructions {
+            out.push(*program);
+            vec_len(&mut out, accounts.len());
+            out.extend_from_slice(accounts);
+            vec_len(&mut out, data.len());
+            out.extend_from_slice(data);
+        }
+        vec_len(&mut out, 1);
+        out.extend_from_slice(table.as_ref());
+        vec_len(&mut out, 1);
+        out.push(1);
+        vec_len(&mut out, 0);
+        out
+    }
+
+    #[test]
+    fn test_parse_and_resolve() {
+        let multisig = Pubkey::new_unique();
+        let vault = Pubkey::new_unique();
+        let system: Pubkey = SYSTEM_PROGRAM_ID.parse().unwrap();
+        let (table, recipient) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let data = vault_transaction_account(
+            multisig,
+            &[vault, system],
+            table,
+            &[(1, vec![0, 2], system_transfer(1_500_000_000))],
+        );
+
+        let tx = VaultTransaction::parse(&data).unwrap();
+        assert_eq!(tx.multisig, multisig);
+        assert_eq!(tx.index, 7);
+        assert_eq!(tx.vault_index, 1);
+
+        // The recipient is loaded from the lookup table
+        assert!(tx.instructions(&HashMap::new()).is_err());
+        let tables = HashMap::from([(table, vec![Pubkey::new_unique(), recipient])]);
+        let instructions = tx.instructions(&tables).unwrap();
+        assert_eq!(instructions[0].accounts, vec![vault, recipient]);
+        assert_eq!(
+            transfers(&instructions),
+            vec![Transfer {
+                destination: recipient,
+                amount: 1_500_000_000,
+                token: false,
+                mint: None,
+            }]
+        );
+
+        assert!(VaultTransaction::parse(&data[..data.len() - 4]).is_err());
+        assert!(VaultTransaction::parse(&[0; 200]).is_err());
+    }
+
+    #[test]
+    fn test_verify_intent() {
+        let system: Pubkey = SYSTEM_PROGRAM_ID.parse().unwrap();
+        let (vault, recipient) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let instructions = vec![VaultInstruction {
+            program_id: system,
+            accounts: vec![vault, recipient],
+            data: system_transfer(1_500_000_000),
+        }];
+
+        let intent: ProposalIntent = serde_json::from_value(serde_json::json!({
+            "vault_index": 0,
+            "programs": [SYSTEM_PROGRAM_ID],
+            "transfers": [{ "destination": recipient.to_string(), "amount": "1.5" }],
+            "instructions": [{
+                "program_id": SYSTEM_PROGRAM_ID,
+                "accounts": [vault.to_string(), recipient.to_string()],
+                "data": STANDARD.encode(system_transfer(1_500_000_000)),
+            }],
+        }))
+        .unwrap();
+        assert!(verify_intent(&intent, 0, &instructions).unwrap().matches());
+
+        // A different amount, vault and extra program all show up
+        let mut tampered = instructions.clone();
+        tampered[0].data = system_transfer(15_000_000_000);
+        tampered.push(VaultInstruction {
+            program_id: Pubkey::new_unique(),
+            accounts: vec![],
+            data: vec![],
+        });
+        let report = verify_intent(&intent, 1, &tampered).unwrap();
+        assert!(!report.matches());
+        assert_eq!(
+            report.mismatches.len(),
+            6,
+            "vault, program, missing and unexpected transfer, count, data: {:?}",
+            report.mismatches
+        );
+
+        assert!(verify_intent(&ProposalIntent::default(), 0, &instructions).is_err());
+    }
+
+    #[test]
+    fn test_token_transfer_without_mint_warns() {
+        let token: Pubkey = TOKEN_PROGRAM_IDS[0].parse().unwrap();
+        let (source, destination, mint) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let mut data = vec![3];
+        data.extend_from_slice(&250u64.to_le_bytes());
+        let instructions = vec![VaultInstruction {
+            program_id: token,
+            accounts: vec![source, destination, Pubkey::new_unique()],
+            data,
+        }];
+
+        let intent = ProposalIntent {
+            transfers: Some(vec![IntentTransfer {
+                destination: destination.to_string(),
+                amount: "250".into(),
+                mint: Some(mint.to_string()),
+            }]),
+            ..Default::default()
+        };
+        let report = verify_intent(&intent, 0, &instructions).unwrap();
+        assert!(report.matches());
+        assert_eq!(report.warnings.len(), 1);
+    }
+}
@@ -1,14 +1,26 @@
 //! Transaction parsing and summarization
 
-use solana_sdk::{message::Message, pubkey::Pubkey};
+use serde::{Serialize, Serializer};
+use sha2::{Digest, Sha256};
+use solana_sdk::{
+    message::{VersionedMessage, compiled_instruction::CompiledInstruction},
+    pubkey::Pubkey,
+};
 
 /// System program ID
 const SYSTEM_PROGRAM_ID: &str = "11111111111111111111111111111111";
 
+/// Domain separator for summary hashes
+const SUMMARY_HASH_DOMAIN: &[u8] = b"solana-keyring/summary/v1";
+
+/// Domain separator for template hashes
+const TEMPLATE_HASH_DOMAIN: &[u8] = b"solana-keyring/template/v1";
+
 use crate::error::Result;
+use crate::i18n::{t, t_with};
 
 /// Summary of a transaction for display to the user
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct TransactionSummary {
     /// Human-readable description of the transaction
     pub description: String,
@@ -18,10 +30,34 @@ pub struct TransactionSummary {
     pub accounts: Vec<AccountInfo>,
     /// Estimated fee in lamports
     pub estimated_fee: Option<u64>,
+    /// Canonical hash of the parsed transaction
+    ///
+    /// Independent of locale and display formatting, so two devices showing
+    /// the same fingerprint are approving the same transaction.
+    #[serde(serialize_with = "serialize_hash")]
+    pub hash: [u8; 32],
+}
+
+fn serialize_hash<S: Serializer>(
+    hash: &[u8; 32],
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_str(&hex::encode(hash))
+}
+
+impl TransactionSummary {
+    /// Short form of the summary hash for comparing by eye, e.g. `3F9A-0C1D-77B2-E4A8`
+    pub fn fingerprint(&self) -> String {
+        self.hash[..8]
+            .chunks(2)
+            .map(|pair| format!("{:02X}{:02X}", pair[0], pair[1]))
+            .collect::<Vec<_>>()
+            .join("-")
+    }
 }
 
 /// Account information in a transaction.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct AccountInfo {
     /// The account's public key address.
     pub address: String,
@@ -37,15 +73,22 @@ impl std::fmt::Display for TransactionSummary {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "{}", self.description)?;
         writeln!(f)?;
-        writeln!(f, "Programs: {}", self.programs.join(", "))?;
+        writeln!(
+            f,
+            "{}",
+            t_with(
+                "summary-programs",
+                &[("programs", &self.programs.join(", "))]
+            )
+        )?;
         writeln!(f)?;
-        writeln!(f, "Accounts:")?;
+        writeln!(f, "{}", t("summary-accounts"))?;
         for acc in &self.accounts {
             let flags = match (acc.is_signer, acc.is_writable) {
-                (true, true) => "[signer, writable]",
-                (true, false) => "[signer]",
-                (false, true) => "[writable]",
-                (false, false) => "",
+                (true, true) => t("summary-flag-signer-writable"),
+                (true, false) => t("summary-flag-signer"),
+                (false, true) => t("summary-flag-writable"),
+                (false, false) => String::new(),
             };
             if let Some(label) = &acc.label {
                 writeln!(f, "  {} ({}) {}", acc.address, label, flags)?;
@@ -53,23 +96,30 @@ impl std::fmt::Display for TransactionSummary {
                 writeln!(f, "  {} {}", acc.address, flags)?;
             }
         }
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{}",
+            t_with("summary-hash", &[("hash", &self.fingerprint())])
+        )?;
         Ok(())
     }
 }
 
-/// Parse a transaction message and create a summary
+/// Parse a legacy or v0 transaction message and create a summary
+///
+/// Accounts loaded from address lookup tables can't be resolved offline, so
+/// only the message's static accounts are listed.
 pub fn summarize_transaction(message_bytes: &[u8]) -> Result<TransactionSummary> {
-    // Try to deserialize as a Message
-    let message: Message = bincode::deserialize(message_bytes)
-        .map_err(|e| crate::error::Error::Solana(format!("Failed to parse message: {}", e)))?;
+    let message = parse_versioned(message_bytes)?;
+    let account_keys = message.static_account_keys();
 
     let mut programs = Vec::new();
     let mut description_parts = Vec::new();
 
     // Analyze each instruction
-    for ix in &message.instructions {
-        let program_id = message
-            .account_keys
+    for ix in message.instructions() {
+        let program_id = account_keys
             .get(ix.program_id_index as usize)
             .map(|p| p.to_string())
             .unwrap_or_else(|| "Unknown".to_string());
@@ -78,22 +128,24 @@ pub fn summarize_transaction(message_bytes: &[u8]) -> Result<TransactionSummary>
         programs.push(program_name.clone());
 
         // Try to decode known instruction types
-        if let Some(desc) =
-            decode_instruction(&program_id, &ix.data, &message.account_keys, &ix.accounts)
-        {
+        if let Some(desc) = decode_instruction(&program_id, &ix.data, account_keys, &ix.accounts) {
             description_parts.push(desc);
         } else {
-            description_parts.push(format!("Call to {}", program_name));
+            description_parts.push(t_with("summary-call", &[("program", &program_name)]));
         }
     }
 
+    let loaded = loaded_account_count(&message);
+    if loaded > 0 {
+        description_parts.push(t_with("summary-lookup-accounts", &[("count", &loaded)]));
+    }
+
     // Build account info
-    let accounts: Vec<AccountInfo> = message
-        .account_keys
+    let accounts: Vec<AccountInfo> = account_keys
         .iter()
         .enumerate()
         .map(|(i, pubkey)| {
-            let is_signer = i < message.header.num_required_signatures as usize;
+            let is_signer = i < message.header().num_required_signatures as usize;
             let is_writable = message.is_maybe_writable(i, None);
             AccountInfo {
                 address: pubkey.to_string(),
@@ -113,9 +165,282 @@ pub fn summarize_transaction(message_bytes: &[u8]) -> Result<TransactionSummary>
         programs,
         accounts,
         estimated_fee: Some(5000), // Default fee estimate
+        hash: hash_message(SUMMARY_HASH_DOMAIN, &message, true),
     })
 }
 
+/// Hash of a legacy or v0 message ignoring its blockhash
+///
+/// Transactions built from the same template (same accounts, programs and
+/// instruction data) share a template hash even though each one uses a fresh
+/// blockhash, which lets a delegation authorize a repeating transaction.
+pub fn template_hash(message_bytes: &[u8]) -> Result<[u8; 32]> {
+    let message = parse_versioned(message_bytes)?;
+    Ok(hash_message(TEMPLATE_HASH_DOMAIN, &message, false))
+}
+
+/// Hash the parsed contents of a message
+///
+/// Covers the accounts with their signer and writable flags, optionally the
+/// blockhash, and every instruction's program, accounts and data, each
+/// length-prefixed. A v0 message's accounts from lookup tables are covered
+/// by table and position, followed by its lookups; legacy messages hash as
+/// they always have.
+fn hash_message(domain: &[u8], message: &VersionedMessage, include_blockhash: bool) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(domain);
+
+    let account_keys = message.static_account_keys();
+    hasher.update((account_keys.len() as u32).to_le_bytes());
+    for (i, key) in account_keys.iter().enumerate() {
+        let is_signer = i < message.header().num_required_signatures as usize;
+        let is_writable = message.is_maybe_writable(i, None);
+        hasher.update(key.as_ref());
+        hasher.update([u8::from(is_signer) | (u8::from(is_writable) << 1)]);
+    }
+    if include_blockhash {
+        hasher.update(message.recent_blockhash().as_ref());
+    }
+
+    // Loaded accounts follow the static ones: every table's writable
+    // entries, then every table's read-only entries
+    let lookups = message.address_table_lookups().unwrap_or_default();
+    let loaded: Vec<(&Pubkey, u8)> = lookups
+        .iter()
+        .flat_map(|l| l.writable_indexes.iter().map(move |&i| (&l.account_key, i)))
+        .chain(
+            lookups
+                .iter()
+                .flat_map(|l| l.readonly_indexes.iter().map(move |&i| (&l.account_key, i))),
+        )
+        .collect();
+    let update_key = |hasher: &mut Sha256, index: u8| {
+        let index = index as usize;
+        match account_keys.get(index) {
+            Some(key) => hasher.update(key.as_ref()),
+            None => match loaded.get(index - account_keys.len()) {
+                Some((table, position)) => {
+                    hasher.update(table.as_ref());
+                    hasher.update([*position]);
+                }
+                None => hasher.update(Pubkey::default().as_ref()),
+            },
+        }
+    };
+
+    let instructions = message.instructions();
+    hasher.update((instructions.len() as u32).to_le_bytes());
+    for ix in instructions {
+        update_key(&mut hasher, ix.program_id_index);
+        hasher.update((ix.accounts.len() as u32).to_le_bytes());
+        for &index in &ix.accounts {
+            update_key(&mut hasher, index);
+        }
+        hasher.update((ix.data.len() as u32).to_le_bytes());
+        hasher.update(&ix.data);
+    }
+
+    if let VersionedMessage::V0(_) = message {
+        hasher.update((lookups.len() as u32).to_le_bytes());
+        for lookup in lookups {
+            hasher.update(lookup.account_key.as_ref());
+            for indexes in [&lookup.writable_indexes, &lookup.readonly_indexes] {
+                hasher.update((indexes.len() as u32).to_le_bytes());
+                hasher.update(indexes);
+            }
+        }
+    }
+
+    hasher.finalize().into()
+}
+
+/// Number of accounts a message loads from address lookup tables
+fn loaded_account_count(message: &VersionedMessage) -> usize {
+    message
+        .address_table_lookups()
+        .unwrap_or_default()
+        .iter()
+        .map(|l| l.writable_indexes.len() + l.readonly_indexes.len())
+        .sum()
+}
+
+/// Parse a legacy or v0 message
+fn parse_versioned(message_bytes: &[u8]) -> Result<VersionedMessage> {
+    bincode::deserialize(message_bytes)
+        .map_err(|e| crate::error::Error::Solana(format!("Failed to parse message: {}", e)))
+}
+
+/// Total lamports a transaction moves out of `from` via System Program
+/// transfers, account creations and nonce withdrawals
+///
+/// Accepts legacy and v0 messages. The key authorizing a debit must sign, so
+/// it is always one of the message's static keys. Only System Program
+/// instructions are counted; see [`is_outflow_countable`].
+pub fn sol_outflow(message_bytes: &[u8], from: &str) -> Result<u64> {
+    let message = parse_versioned(message_bytes)?;
+    let account_keys = message.static_account_keys();
+
+    let mut total: u64 = 0;
+    for ix in message.instructions() {
+        let is_system = account_keys
+            .get(ix.program_id_index as usize)
+            .is_some_and(|p| p.to_string() == SYSTEM_PROGRAM_ID);
+        if !is_system {
+            continue;
+        }
+        if let Some((authority, lamports)) = system_debit(account_keys, ix)
+            && authority.to_string() == from
+        {
+            total = total.saturating_add(lamports);
+        }
+    }
+
+    Ok(total)
+}
+
+/// Whether [`sol_outflow`] accounts for everything a transaction can move
+/// out of its signers
+///
+/// True when every instruction is a System Program instruction that moves
+/// lamports or advances a nonce, an SPL Token transfer, a compute budget or a
+/// memo. Any other program could debit a signer through a CPI, and system
+/// instructions such as `Assign` hand the signer's account to another program.
+pub fn is_outflow_countable(message_bytes: &[u8]) -> Result<bool> {
+    let message = parse_versioned(message_bytes)?;
+    let account_keys = message.static_account_keys();
+
+    Ok(message.instructions().iter().all(|ix| {
+        let is_system = account_keys
+            .get(ix.program_id_index as usize)
+            .is_some_and(|p| p.to_string() == SYSTEM_PROGRAM_ID);
+        if is_system {
+            // AdvanceNonceAccount
+            let advance_nonce = ix.data.get(0..4) == Some(&4u32.to_le_bytes()[..]);
+            advance_nonce || system_debit(account_keys, ix).is_some()
+        } else {
+            !matches!(classify_transfer(account_keys, ix), TransferKind::Other)
+        }
+    }))
+}
+
+/// The key authorizing a System Program debit and the lamports moved, for
+/// instructions that move lamports
+fn system_debit<'a>(
+    account_keys: &'a [Pubkey],
+    ix: &CompiledInstruction,
+) -> Option<(&'a Pubkey, u64)> {
+    let discriminator = u32::from_le_bytes(ix.data.get(0..4)?.try_into().ok()?);
+    let (authority, lamports_at) = match discriminator {
+        // CreateAccount and Transfer, funded by the first account
+        0 | 2 => (0, 4),
+        // CreateAccountWithSeed, after the base pubkey and length-prefixed seed
+        3 => {
+            let seed_len = u64::from_le_bytes(ix.data.get(36..44)?.try_into().ok()?);
+            (0, usize::try_from(seed_len).ok()?.checked_add(44)?)
+        }
+        // WithdrawNonceAccount, authorized by the fifth account
+        5 => (4, 4),
+        // TransferWithSeed, from an account derived from the second
+        11 => (1, 4),
+        _ => return None,
+    };
+
+    let lamports = ix.data.get(lamports_at..lamports_at.checked_add(8)?)?;
+    let authority = ix
+        .accounts
+        .get(authority)
+        .and_then(|&i| account_keys.get(i as usize))?;
+    Some((authority, u64::from_le_bytes(lamports.try_into().ok()?)))
+}
+
+/// Destination accounts of System Program transfers and SPL Token transfers
+///
+/// Token transfers report the destination token account, not its owner.
+/// Destinations loaded from an address lookup table can't be resolved here
+/// and are left out.
+pub fn transfer_destinations(message_bytes: &[u8]) -> Result<Vec<String>> {
+    let message = parse_versioned(message_bytes)?;
+    let account_keys = message.static_account_keys();
+
+    let mut destinations = Vec::new();
+    for ix in message.instructions() {
+        let TransferKind::Transfer(Some(dest)) = classify_transfer(account_keys, ix) else {
+            continue;
+        };
+        let dest = dest.to_string();
+        if !destinations.contains(&dest) {
+            destinations.push(dest);
+        }
+    }
+
+    Ok(destinations)
+}
+
+/// Whether a transaction does nothing but transfer to resolvable destinations
+///
+/// Compute budget and memo instructions are allowed alongside the transfers.
+/// Any other instruction (an approval, an authority change, a program call)
+/// or a destination behind a lookup table makes this false, so such
+/// transactions are never approved on the strength of their destinations.
+pub fn is_transfer_only(message_bytes: &[u8]) -> Result<bool> {
+    let message = parse_versioned(message_bytes)?;
+    let account_keys = message.static_account_keys();
+
+    let mut transfers = 0;
+    for ix in message.instructions() {
+        match classify_transfer(account_keys, ix) {
+            TransferKind::Transfer(Some(_)) => transfers += 1,
+            TransferKind::Benign => {}
+            TransferKind::Transfer(None) | TransferKind::Other => return Ok(false),
+        }
+    }
+
+    Ok(transfers > 0)
+}
+
+/// How an instruction bears on destination checks
+enum TransferKind<'a> {
+    /// A transfer, with its destination if it is a static key
+    Transfer(Option<&'a Pubkey>),
+    /// A compute budget or memo instruction, which moves nothing
+    Benign,
+    /// Anything else
+    Other,
+}
+
+fn classify_transfer<'a>(account_keys: &'a [Pubkey], ix: &CompiledInstruction) -> TransferKind<'a> {
+    let Some(program_id) = account_keys.get(ix.program_id_index as usize) else {
+        return TransferKind::Other;
+    };
+
+    let dest_index = match program_id.to_string().as_str() {
+        SYSTEM_PROGRAM_ID => match ix.data.get(0..4).map(|d| d.try_into()) {
+            // Transfer
+            Some(Ok(d)) if u32::from_le_bytes(d) == 2 => 1,
+            // TransferWithSeed
+            Some(Ok(d)) if u32::from_le_bytes(d) == 11 => 2,
+            _ => return TransferKind::Other,
+        },
+        "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        | "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb" => match ix.data.first() {
+            // Transfer
+            Some(3) => 1,
+            // TransferChecked
+            Some(12) => 2,
+            _ => return TransferKind::Other,
+        },
+        "ComputeBudget111111111111111111111111111111"
+        | "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr" => return TransferKind::Benign,
+        _ => return TransferKind::Other,
+    };
+
+    TransferKind::Transfer(
+        ix.accounts
+            .get(dest_index)
+            .and_then(|&i| account_keys.get(i as usize)),
+    )
+}
+
 /// Identify a program by its address
 fn identify_program(program_id: &str) -> String {
     match program_id {
@@ -185,13 +510,19 @@ fn decode_system_instruction(
                 .map(|p| truncate_pubkey(&p.to_string()))
                 .unwrap_or_else(|| "?".to_string());
 
-            Some(format!("Transfer {:.6} SOL to {}", sol, to))
+            Some(t_with(
+                "summary-transfer-sol",
+                &[("amount", &format!("{:.6}", sol)), ("to", &to)],
+            ))
         }
         // CreateAccount
         0 => {
             let lamports = u64::from_le_bytes(data.get(4..12)?.try_into().ok()?);
             let sol = lamports as f64 / 1_000_000_000.0;
-            Some(format!("Create account with {:.6} SOL", sol))
+            Some(t_with(
+                "summary-create-account",
+                &[("amount", &format!("{:.6}", sol))],
+            ))
         }
         _ => None,
     }
@@ -206,11 +537,11 @@ fn decode_token_instruction(data: &[u8]) -> Option<String> {
         3 => {
             // Transfer
             let amount = u64::from_le_bytes(data.get(1..9)?.try_into().ok()?);
-            Some(format!("Token transfer: {} units", amount))
+            Some(t_with("summary-token-transfer", &[("amount", &amount)]))
         }
-        7 => Some("Mint tokens".to_string()),
-        8 => Some("Burn tokens".to_string()),
-        9 => Some("Close token account".to_string()),
+        7 => Some(t("summary-token-mint")),
+        8 => Some(t("summary-token-burn")),
+        9 => Some(t("summary-token-close")),
         _ => None,
     }
 }
@@ -222,3 +553,379 @@ fn truncate_pubkey(pubkey: &str) -> String {
         pubkey.to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::instruction::{AccountMeta, Instruction};
+    use solana_sdk::message::{AddressLookupTableAccount, Message, v0};
+
+    fn transfer(from: &Pubkey, to: &Pubkey, lamports: u64) -> Instruction {
+        let mut data = 2u32.to_le_bytes().to_vec();
+        data.extend_from_slice(&lamports.to_le_bytes());
+        Instruction::new_with_bytes(
+            SYSTEM_PROGRAM_ID.parse().unwrap(),
+            &data,
+            vec![AccountMeta::new(*from, true), AccountMeta::new(*to, false)],
+        )
+    }
+
+    #[test]
+    fn test_sol_outflow() {
+        let from = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let to = Pubkey::new_unique();
+
+        let message = Message::new(
+            &[
+                transfer(&from, &to, 1_000),
+                transfer(&from, &to, 500),
+                transfer(&other, &to, 7_000),
+            ],
+            Some(&from),
+        );
+        let bytes = bincode::serialize(&message).unwrap();
+
+        assert_eq!(sol_outflow(&bytes, &from.to_string()).unwrap(), 1_500);
+        assert_eq!(sol_outflow(&bytes, &other.to_string()).unwrap(), 7_000);
+        assert_eq!(sol_outflow(&bytes, &to.to_string()).unwrap(), 0);
+    }
+
+    fn system(data: Vec<u8>, accounts: Vec<AccountMeta>) -> Instruction {
+        Instruction::new_with_bytes(SYSTEM_PROGRAM_ID.parse().unwrap(), &data, accounts)
+    }
+
+    fn seed(seed: &str) -> Vec<u8> {
+        let mut data = (seed.len() as u64).to_le_bytes().to_vec();
+        data.extend_from_slice(seed.as_bytes());
+        data
+    }
+
+    #[test]
+    fn test_sol_outflow_seed_and_nonce() {
+        let from = Pubkey::new_unique();
+        let to = Pubkey::new_unique();
+        let derived = Pubkey::new_unique();
+        let nonce = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+
+        // CreateAccountWithSeed funded by `from`
+        let mut data = 3u32.to_le_bytes().to_vec();
+        data.extend_from_slice(from.as_ref());
+        data.extend_from_slice(&seed("vault"));
+        data.extend_from_slice(&1_000u64.to_le_bytes());
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.extend_from_slice(owner.as_ref());
+        let create_with_seed = system(
+            data,
+            vec![
+                AccountMeta::new(from, true),
+                AccountMeta::new(derived, false),
+                AccountMeta::new_readonly(from, true),
+            ],
+        );
+
+        // TransferWithSeed out of an account derived from `from`
+        let mut data = 11u32.to_le_bytes().to_vec();
+        data.extend_from_slice(&200u64.to_le_bytes());
+        data.extend_from_slice(&seed("vault"));
+        data.extend_from_slice(owner.as_ref());
+        let transfer_with_seed = system(
+            data,
+            vec![
+                AccountMeta::new(derived, false),
+                AccountMeta::new_readonly(from, true),
+                AccountMeta::new(to, false),
+            ],
+        );
+
+        // WithdrawNonceAccount authorized by `from`
+        let mut data = 5u32.to_le_bytes().to_vec();
+        data.extend_from_slice(&30u64.to_le_bytes());
+        let withdraw_nonce = system(
+            data,
+            vec![
+                AccountMeta::new(nonce, false),
+                AccountMeta::new(to, false),
+                AccountMeta::new_readonly(Pubkey::new_unique(), false),
+                AccountMeta::new_readonly(Pubkey::new_unique(), false),
+                AccountMeta::new_readonly(from, true),
+            ],
+        );
+
+        let bytes = bincode::serialize(&Message::new(
+            &[create_with_seed, transfer_with_seed, withdraw_nonce],
+            Some(&from),
+        ))
+        .unwrap();
+        assert_eq!(sol_outflow(&bytes, &from.to_string()).unwrap(), 1_230);
+        assert_eq!(sol_outflow(&bytes, &derived.to_string()).unwrap(), 0);
+        assert!(is_outflow_countable(&bytes).unwrap());
+    }
+
+    #[test]
+    fn test_is_outflow_countable() {
+        let from = Pubkey::new_unique();
+        let to = Pubkey::new_unique();
+        let serialize = |instructions: &[Instruction]| {
+            bincode::serialize(&Message::new(instructions, Some(&from))).unwrap()
+        };
+
+        let memo = Instruction::new_with_bytes(
+            "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr"
+                .parse()
+                .unwrap(),
+            b"hi",
+            vec![AccountMeta::new(from, true)],
+        );
+        let advance_nonce = system(
+            4u32.to_le_bytes().to_vec(),
+            vec![AccountMeta::new(Pubkey::new_unique(), false)],
+        );
+        let token_transfer = Instruction::new_with_bytes(
+            "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+                .parse()
+                .unwrap(),
+            &[3, 1, 0, 0, 0, 0, 0, 0, 0],
+            vec![
+                AccountMeta::new(Pubkey::new_unique(), false),
+                AccountMeta::new(Pubkey::new_unique(), false),
+                AccountMeta::new_readonly(from, true),
+            ],
+        );
+        assert!(
+            is_outflow_countable(&serialize(&[
+                advance_nonce,
+                transfer(&from, &to, 1),
+                token_transfer,
+                memo
+            ]))
+            .unwrap()
+        );
+
+        // A program wrapping the transfer could debit the signer by CPI
+        let wrapped = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[],
+            vec![AccountMeta::new(from, true), AccountMeta::new(to, false)],
+        );
+        let bytes = serialize(&[wrapped]);
+        assert_eq!(sol_outflow(&bytes, &from.to_string()).unwrap(), 0);
+        assert!(!is_outflow_countable(&bytes).unwrap());
+
+        // Assigning the signer's account hands it to another program
+        let mut data = 1u32.to_le_bytes().to_vec();
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        let assign = system(data, vec![AccountMeta::new(from, true)]);
+        assert!(!is_outflow_countable(&serialize(&[assign])).unwrap());
+
+        // A truncated transfer can't be counted
+        let truncated = system(
+            2u32.to_le_bytes().to_vec(),
+            vec![AccountMeta::new(from, true)],
+        );
+        assert!(!is_outflow_countable(&serialize(&[truncated])).unwrap());
+    }
+
+    #[test]
+    fn test_sol_outflow_v0() {
+        let from = Pubkey::new_unique();
+        let to = Pubkey::new_unique();
+
+        let message = v0::Message::try_compile(
+            &from,
+            &[transfer(&from, &to, 2_500), transfer(&from, &to, 500)],
+            &[],
+            Hash::new_unique(),
+        )
+        .unwrap();
+        let bytes = bincode::serialize(&VersionedMessage::V0(message)).unwrap();
+
+        assert_eq!(sol_outflow(&bytes, &from.to_string()).unwrap(), 3_000);
+        assert_eq!(sol_outflow(&bytes, &to.to_string()).unwrap(), 0);
+        assert!(sol_outflow(&bytes[..bytes.len() / 2], &from.to_string()).is_err());
+    }
+
+    #[test]
+    fn test_summary_hash() {
+        let from = Pubkey::new_unique();
+        let to = Pubkey::new_unique();
+
+        let summarize = |lamports| {
+            let message = Message::new(&[transfer(&from, &to, lamports)], Some(&from));
+            summarize_transaction(&bincode::serialize(&message).unwrap()).unwrap()
+        };
+
+        let a = summarize(1_000);
+        assert_eq!(a.hash, summarize(1_000).hash);
+        assert_ne!(a.hash, summarize(1_001).hash);
+
+        let fingerprint = a.fingerprint();
+        assert_eq!(fingerprint.len(), 19);
+        assert_eq!(fingerprint.split('-').count(), 4);
+        assert!(a.to_string().contains(&fingerprint));
+    }
+
+    #[test]
+    fn test_template_hash_ignores_blockhash() {
+        let from = Pubkey::new_unique();
+        let to = Pubkey::new_unique();
+
+        let serialize = |lamports, blockhash| {
+            let mut message = Message::new(&[transfer(&from, &to, lamports)], Some(&from));
+            message.recent_blockhash = blockhash;
+            bincode::serialize(&message).unwrap()
+        };
+
+        let a = serialize(1_000, Hash::new_unique());
+        let b = serialize(1_000, Hash::new_unique());
+        assert_eq!(template_hash(&a).unwrap(), template_hash(&b).unwrap());
+        assert_ne!(
+            template_hash(&a).unwrap(),
+            template_hash(&serialize(1_001, Hash::new_unique())).unwrap()
+        );
+        assert_ne!(
+            summarize_transaction(&a).unwrap().hash,
+            summarize_transaction(&b).unwrap().hash
+        );
+    }
+
+    #[test]
+    fn test_summary_and_template_hash_v0() {
+        let from = Pubkey::new_unique();
+        let to = Pubkey::new_unique();
+        let table = |addresses| AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses,
+        };
+
+        let serialize = |tables: &[AddressLookupTableAccount], blockhash| {
+            let message =
+                v0::Message::try_compile(&from, &[transfer(&from, &to, 1_000)], tables, blockhash)
+                    .unwrap();
+            bincode::serialize(&VersionedMessage::V0(message)).unwrap()
+        };
+
+        let plain = serialize(&[], Hash::new_unique());
+        let summary = summarize_transaction(&plain).unwrap();
+        assert_eq!(summary.accounts.len(), 3);
+        assert_eq!(
+            template_hash(&plain).unwrap(),
+            template_hash(&serialize(&[], Hash::new_unique())).unwrap()
+        );
+
+        let first = table(vec![to]);
+        let a = serialize(std::slice::from_ref(&first), Hash::new_unique());
+        let b = serialize(std::slice::from_ref(&first), Hash::new_unique());
+        let summary = summarize_transaction(&a).unwrap();
+        assert_eq!(summary.accounts.len(), 2);
+        assert_ne!(summary.hash, summarize_transaction(&b).unwrap().hash);
+        assert_eq!(template_hash(&a).unwrap(), template_hash(&b).unwrap());
+        assert_ne!(template_hash(&a).unwrap(), template_hash(&plain).unwrap());
+
+        let second = serialize(&[table(vec![to])], Hash::new_unique());
+        assert_ne!(template_hash(&a).unwrap(), template_hash(&second).unwrap());
+
+        // The legacy encoding of the same transfer hashes differently from v0
+        let legacy =
+            bincode::serialize(&Message::new(&[transfer(&from, &to, 1_000)], Some(&from))).unwrap();
+        assert_ne!(
+            template_hash(&legacy).unwrap(),
+            template_hash(&plain).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_transfer_destinations() {
+        let from = Pubkey::new_unique();
+        let a = Pubkey::new_unique();
+        let b = Pubkey::new_unique();
+
+        let message = Message::new(
+            &[
+                transfer(&from, &a, 1),
+                transfer(&from, &b, 1),
+                transfer(&from, &a, 1),
+            ],
+            Some(&from),
+        );
+        let bytes = bincode::serialize(&message).unwrap();
+
+        assert_eq!(
+            transfer_destinations(&bytes).unwrap(),
+            vec![a.to_string(), b.to_string()]
+        );
+    }
+
+    #[test]
+    fn test_is_transfer_only() {
+        let from = Pubkey::new_unique();
+        let to = Pubkey::new_unique();
+        let token_program: Pubkey = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+            .parse()
+            .unwrap();
+        let serialize = |instructions: &[Instruction]| {
+            bincode::serialize(&Message::new(instructions, Some(&from))).unwrap()
+        };
+        let program = |program_id: Pubkey, data: &[u8]| {
+            Instruction::new_with_bytes(program_id, data, vec![AccountMeta::new(from, true)])
+        };
+
+        let memo = program(
+            "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr"
+                .parse()
+                .unwrap(),
+            b"hi",
+        );
+        let compute_budget = program(
+            "ComputeBudget111111111111111111111111111111"
+                .parse()
+                .unwrap(),
+            &[2, 0, 0, 0, 0],
+        );
+        assert!(
+            is_transfer_only(&serialize(&[
+                compute_budget,
+                transfer(&from, &to, 1),
+                memo.clone(),
+            ]))
+            .unwrap()
+        );
+        assert!(!is_transfer_only(&serialize(&[memo])).unwrap());
+
+        // A trusted transfer must not carry anything else along with it
+        let assign = program(SYSTEM_PROGRAM_ID.parse().unwrap(), &1u32.to_le_bytes());
+        let set_authority = program(token_program, &[6, 2, 1]);
+        let approve = program(token_program, &[4, 1, 0, 0, 0, 0, 0, 0, 0]);
+        let close_account = program(token_program, &[9]);
+        let arbitrary = program(Pubkey::new_unique(), &[]);
+        for other in [assign, set_authority, approve, close_account, arbitrary] {
+            let bytes = serialize(&[transfer(&from, &to, 1), other]);
+            assert_eq!(transfer_destinations(&bytes).unwrap(), vec![to.to_string()]);
+            assert!(!is_transfer_only(&bytes).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_is_transfer_only_lookup_destination() {
+        let from = Pubkey::new_unique();
+        let to = Pubkey::new_unique();
+        let table = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: vec![to],
+        };
+
+        let message = v0::Message::try_compile(
+            &from,
+            &[transfer(&from, &to, 1)],
+            std::slice::from_ref(&table),
+            Hash::new_unique(),
+        )
+        .unwrap();
+        let bytes = bincode::serialize(&VersionedMessage::V0(message)).unwrap();
+
+        assert!(transfer_destinations(&bytes).unwrap().is_empty());
+        assert!(!is_transfer_only(&bytes).unwrap());
+    }
+}
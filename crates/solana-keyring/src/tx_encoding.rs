@@ -0,0 +1,188 @@
+//! Signed transaction output formats
+//!
+//! Lets the CLIs hand a signed transaction to any broadcaster instead of
+//! sending it themselves: base64 or base58 wire format, or RPC-style JSON
+//! matching `getTransaction` with `"encoding": "json"`.
+
+use std::fmt;
+use std::str::FromStr;
+
+use base64::Engine;
+use serde_json::json;
+use solana_sdk::{message::Message, signature::Signature, transaction::Transaction};
+
+use crate::error::{Error, Result};
+
+/// How a signed transaction is written out
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TxEncoding {
+    /// Base64 wire format, as accepted by `sendTransaction`
+    #[default]
+    Base64,
+    /// Base58 wire format
+    Base58,
+    /// RPC-style JSON with decoded message fields
+    Json,
+}
+
+impl FromStr for TxEncoding {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "base64" => Ok(Self::Base64),
+            "base58" => Ok(Self::Base58),
+            "json" => Ok(Self::Json),
+            _ => Err(Error::Solana(format!(
+                "Unknown transaction encoding '{}' (expected base64, base58 or json)",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for TxEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Base64 => "base64",
+            Self::Base58 => "base58",
+            Self::Json => "json",
+        })
+    }
+}
+
+/// Build a transaction from a serialized message and one signer's signature
+///
+/// The signature is placed in the slot of the required signer it verifies
+/// against; other signers' slots are left empty for them to fill.
+pub fn attach_signature(message_bytes: &[u8], signature: Signature) -> Result<Transaction> {
+    let message: Message = bincode::deserialize(message_bytes)
+        .map_err(|e| Error::Solana(format!("Failed to parse message: {}", e)))?;
+
+    let signers = usize::from(message.header.num_required_signatures);
+    let position = message
+        .account_keys
+        .iter()
+        .take(signers)
+        .position(|key| signature.verify(key.as_ref(), message_bytes))
+        .ok_or_else(|| Error::Solana("Signature does not match any required signer".to_string()))?;
+
+    let mut tx = Transaction::new_unsigned(message);
+    tx.signatures[position] = signature;
+    Ok(tx)
+}
+
+/// Encode a transaction for output
+pub fn encode_transaction(tx: &Transaction, encoding: TxEncoding) -> Result<String> {
+    let wire = || {
+        bincode::serialize(tx)
+            .map_err(|e| Error::Solana(format!("Failed to serialize transaction: {}", e)))
+    };
+
+    match encoding {
+        TxEncoding::Base64 => Ok(base64::engine::general_purpose::STANDARD.encode(wire()?)),
+        TxEncoding::Base58 => Ok(bs58::encode(wire()?).into_string()),
+        TxEncoding::Json => Ok(serde_json::to_string_pretty(&transaction_json(tx))?),
+    }
+}
+
+fn transaction_json(tx: &Transaction) -> serde_json::Value {
+    let message = &tx.message;
+    let instructions: Vec<_> = message
+        .instructions
+        .iter()
+        .map(|ix| {
+            json!({
+                "programIdIndex": ix.program_id_index,
+                "accounts": ix.accounts,
+                "data": bs58::encode(&ix.data).into_string(),
+            })
+        })
+        .collect();
+    let signatures: Vec<_> = tx.signatures.iter().map(ToString::to_string).collect();
+    let account_keys: Vec<_> = message
+        .account_keys
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+
+    json!({
+        "signatures": signatures,
+        "message": {
+            "header": {
+                "numRequiredSignatures": message.header.num_required_signatures,
+                "numReadonlySignedAccounts": message.header.num_readonly_signed_accounts,
+                "numReadonlyUnsignedAccounts": message.header.num_readonly_unsigned_accounts,
+            },
+            "accountKeys": account_keys,
+            "recentBlockhash": message.recent_blockhash.to_string(),
+            "instructions": instructions,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::instruction::{AccountMeta, Instruction};
+    use solana_sdk::pubkey::Pubkey;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    fn two_signer_message(payer: &Pubkey, cosigner: &Pubkey) -> Message {
+        let ix = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[1, 2, 3],
+            vec![
+                AccountMeta::new(*payer, true),
+                AccountMeta::new_readonly(*cosigner, true),
+            ],
+        );
+        Message::new_with_blockhash(&[ix], Some(payer), &Hash::new_unique())
+    }
+
+    #[test]
+    fn test_attach_signature() {
+        let payer = Keypair::new();
+        let cosigner = Keypair::new();
+        let message = two_signer_message(&payer.pubkey(), &cosigner.pubkey());
+        let bytes = message.serialize();
+
+        let tx = attach_signature(&bytes, cosigner.sign_message(&bytes)).unwrap();
+        assert_eq!(tx.signatures[0], Signature::default());
+        assert_eq!(tx.signatures[1], cosigner.sign_message(&bytes));
+
+        let stranger = Keypair::new();
+        assert!(attach_signature(&bytes, stranger.sign_message(&bytes)).is_err());
+    }
+
+    #[test]
+    fn test_encodings_round_trip() {
+        let payer = Keypair::new();
+        let cosigner = Keypair::new();
+        let bytes = two_signer_message(&payer.pubkey(), &cosigner.pubkey()).serialize();
+        let tx = attach_signature(&bytes, payer.sign_message(&bytes)).unwrap();
+
+        let base64 = encode_transaction(&tx, TxEncoding::Base64).unwrap();
+        let wire = base64::engine::general_purpose::STANDARD
+            .decode(base64)
+            .unwrap();
+        assert_eq!(bincode::deserialize::<Transaction>(&wire).unwrap(), tx);
+
+        let base58 = encode_transaction(&tx, TxEncoding::Base58).unwrap();
+        assert_eq!(bs58::decode(base58).into_vec().unwrap(), wire);
+
+        let json: serde_json::Value =
+            serde_json::from_str(&encode_transaction(&tx, TxEncoding::Json).unwrap()).unwrap();
+        assert_eq!(json["signatures"][0], tx.signatures[0].to_string());
+        assert_eq!(json["message"]["header"]["numRequiredSignatures"], 2);
+        assert_eq!(json["message"]["instructions"][0]["data"], "Ldp");
+    }
+
+    #[test]
+    fn test_parse_encoding() {
+        assert_eq!("JSON".parse::<TxEncoding>().unwrap(), TxEncoding::Json);
+        assert_eq!(TxEncoding::Base58.to_string(), "base58");
+        assert!("hex".parse::<TxEncoding>().is_err());
+    }
+}
@@ -0,0 +1,189 @@
+//! Solana Wallet
+//!
+//! One executable bundling the keyring CLI, agent, credential helper and
+//! solite. Called through a link named after one of the tools, it runs that
+//! tool; otherwise the first argument picks it, as in
+//! `solana-wallet agent start`.
+
+use std::ffi::{OsStr, OsString};
+use std::fmt::Debug;
+use std::path::Path;
+use std::process::ExitCode;
+
+/// Name of this binary
+const PROGRAM: &str = "solana-wallet";
+
+/// A tool bundled into this binary
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tool {
+    #[cfg(feature = "keyring")]
+    Keyring,
+    #[cfg(feature = "agent")]
+    Agent,
+    #[cfg(feature = "credential-helper")]
+    CredentialHelper,
+    #[cfg(feature = "solite")]
+    Solite,
+}
+
+impl Tool {
+    /// Every tool enabled at build time
+    const ALL: &[Tool] = &[
+        #[cfg(feature = "keyring")]
+        Tool::Keyring,
+        #[cfg(feature = "agent")]
+        Tool::Agent,
+        #[cfg(feature = "credential-helper")]
+        Tool::CredentialHelper,
+        #[cfg(feature = "solite")]
+        Tool::Solite,
+    ];
+
+    /// Subcommand that selects the tool
+    fn subcommand(self) -> &'static str {
+        match self {
+            #[cfg(feature = "keyring")]
+            Tool::Keyring => "keyring",
+            #[cfg(feature = "agent")]
+            Tool::Agent => "agent",
+            #[cfg(feature = "credential-helper")]
+            Tool::CredentialHelper => "credential-helper",
+            #[cfg(feature = "solite")]
+            Tool::Solite => "solite",
+        }
+    }
+
+    /// Name of the tool's standalone binary
+    fn binary(self) -> &'static str {
+        match self {
+            #[cfg(feature = "keyring")]
+            Tool::Keyring => "solana-keyring",
+            #[cfg(feature = "agent")]
+            Tool::Agent => "solana-keyring-agent",
+            #[cfg(feature = "credential-helper")]
+            Tool::CredentialHelper => "solana-credential-helper",
+            #[cfg(feature = "solite")]
+            Tool::Solite => "solite",
+        }
+    }
+
+    /// One-line description for the usage message
+    fn about(self) -> &'static str {
+        match self {
+            #[cfg(feature = "keyring")]
+            Tool::Keyring => "Manage keys, tags, policies and multisigs",
+            #[cfg(feature = "agent")]
+            Tool::Agent => "Run the agent that keeps the keyring unlocked",
+            #[cfg(feature = "credential-helper")]
+            Tool::CredentialHelper => "Sign transactions for other programs",
+            #[cfg(feature = "solite")]
+            Tool::Solite => "Check balances and transfer SOL",
+        }
+    }
+
+    /// Find a tool by subcommand or binary name
+    fn from_name(name: &OsStr) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|tool| name == tool.subcommand() || name == tool.binary())
+    }
+
+    /// Run the tool with `args`, starting with the program name
+    ///
+    /// `prefix` holds the arguments that select the tool, so the agent can
+    /// re-execute itself through this binary.
+    #[cfg_attr(not(feature = "agent"), allow(unused_variables))]
+    fn run(self, args: Vec<OsString>, prefix: &[&str]) -> ExitCode {
+        match self {
+            #[cfg(feature = "keyring")]
+            Tool::Keyring => solana_keyring_cli::run(args),
+            #[cfg(feature = "agent")]
+            Tool::Agent => {
+                block_on(solana_keyring_agent::run(args, prefix)).map_or(ExitCode::FAILURE, report)
+            }
+            #[cfg(feature = "credential-helper")]
+            Tool::CredentialHelper => {
+                block_on(solana_credential_helper::run(args)).unwrap_or(ExitCode::FAILURE)
+            }
+            #[cfg(feature = "solite")]
+            Tool::Solite => block_on(solite::run(args)).map_or(ExitCode::FAILURE, report),
+        }
+    }
+}
+
+/// Run a future to completion on a new runtime, as `#[tokio::main]` does
+#[allow(dead_code)]
+fn block_on<F: Future>(future: F) -> Option<F::Output> {
+    match tokio::runtime::Runtime::new() {
+        Ok(runtime) => Some(runtime.block_on(future)),
+        Err(e) => {
+            eprintln!("Error: {:?}", e);
+            None
+        }
+    }
+}
+
+/// Exit the way `main` returning a `Result` does
+#[allow(dead_code)]
+fn report<E: Debug>(result: Result<(), E>) -> ExitCode {
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {:?}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn print_usage() {
+    println!("Usage: {} <TOOL> [ARGS]...", PROGRAM);
+    println!();
+    println!("Tools:");
+    for tool in Tool::ALL {
+        println!("  {:<19}{}", tool.subcommand(), tool.about());
+    }
+    println!();
+    println!("Linked or copied under a tool's own name, it runs that tool:");
+    for tool in Tool::ALL {
+        println!("  ln -s {} {}", PROGRAM, tool.binary());
+    }
+}
+
+fn main() -> ExitCode {
+    let mut args: Vec<OsString> = std::env::args_os().collect();
+
+    // Busybox-style: dispatch on the name we were called by
+    let called_as = args
+        .first()
+        .and_then(|arg| Path::new(arg).file_stem())
+        .and_then(Tool::from_name);
+    if let Some(tool) = called_as {
+        return tool.run(args, &[tool.subcommand()]);
+    }
+
+    let Some(arg) = args.get(1) else {
+        print_usage();
+        return ExitCode::from(2);
+    };
+    match Tool::from_name(arg) {
+        Some(tool) => {
+            args.remove(1);
+            args[0] = format!("{} {}", PROGRAM, tool.subcommand()).into();
+            tool.run(args, &[tool.subcommand()])
+        }
+        None if arg == "-h" || arg == "--help" || arg == "help" => {
+            print_usage();
+            ExitCode::SUCCESS
+        }
+        None if arg == "-V" || arg == "--version" => {
+            println!("{} {}", PROGRAM, env!("CARGO_PKG_VERSION"));
+            ExitCode::SUCCESS
+        }
+        None => {
+            eprintln!("Error: unknown tool '{}'", arg.to_string_lossy());
+            print_usage();
+            ExitCode::from(2)
+        }
+    }
+}
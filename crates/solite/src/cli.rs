@@ -3,10 +3,22 @@
 use std::path::PathBuf;
 
 use clap::{Parser, Subcommand};
+use solana_actor::Commitment;
+use solana_keyring::tx_encoding::TxEncoding;
+use solana_sdk::hash::Hash;
 
 #[derive(Parser)]
 #[command(name = "sol", about = "Simple SOL transfer CLI", version)]
 pub struct Cli {
+    /// Disable colors and symbols (also honors NO_COLOR)
+    #[arg(long, global = true)]
+    pub plain: bool,
+
+    /// Default the RPC URL, WebSocket URL and sender to a Solana CLI config
+    /// file (~/.config/solana/cli/config.yml if no path is given)
+    #[arg(long, global = true, value_name = "CONFIG", num_args = 0..=1)]
+    pub solana_config: Option<Option<PathBuf>>,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -22,9 +34,10 @@ pub enum Commands {
 
 #[derive(clap::Args)]
 pub struct TransferArgs {
-    /// Source address (public key or label from keyring)
+    /// Source address (public key or label from keyring); defaults to the
+    /// Solana CLI config's keypair with --solana-config
     #[arg(long)]
-    pub from: String,
+    pub from: Option<String>,
 
     /// Destination address (public key or label from keyring)
     #[arg(long)]
@@ -34,9 +47,18 @@ pub struct TransferArgs {
     #[arg(long)]
     pub amount: f64,
 
-    /// RPC URL
-    #[arg(long, default_value = "https://api.mainnet-beta.solana.com")]
-    pub rpc: String,
+    /// RPC URL (default: Solana CLI config with --solana-config, else mainnet)
+    #[arg(long)]
+    pub rpc: Option<String>,
+
+    /// WebSocket URL used to confirm the transaction (default: Solana CLI
+    /// config with --solana-config, else confirm by polling RPC)
+    #[arg(long)]
+    pub ws: Option<String>,
+
+    /// Commitment level to wait for: processed, confirmed or finalized
+    #[arg(long, value_name = "LEVEL", default_value_t = Commitment::Confirmed)]
+    pub commitment: Commitment,
 
     /// Use keyring agent instead of prompting for passphrase
     #[arg(long)]
@@ -53,6 +75,24 @@ pub struct TransferArgs {
     /// Skip confirmation prompt
     #[arg(long, short = 'y')]
     pub yes: bool,
+
+    /// Sign without checking the balance or sending, and print the signed
+    /// transaction for another machine to broadcast
+    #[arg(long)]
+    pub sign_only: bool,
+
+    /// Blockhash to sign with, so --sign-only needs no RPC access
+    #[arg(long, requires = "sign_only")]
+    pub blockhash: Option<Hash>,
+
+    /// Encoding of the signed transaction: base64, base58 or json
+    /// (RPC-style); default base64
+    #[arg(long, value_name = "ENCODING", requires = "sign_only")]
+    pub tx_encoding: Option<TxEncoding>,
+
+    /// Write the signed transaction to a file instead of stdout
+    #[arg(long, value_name = "PATH", requires = "sign_only")]
+    pub tx_out: Option<PathBuf>,
 }
 
 #[derive(clap::Args)]
@@ -60,9 +100,9 @@ pub struct BalanceArgs {
     /// Address to check (public key or label from keyring)
     pub address: String,
 
-    /// RPC URL
-    #[arg(long, default_value = "https://api.mainnet-beta.solana.com")]
-    pub rpc: String,
+    /// RPC URL (default: Solana CLI config with --solana-config, else mainnet)
+    #[arg(long)]
+    pub rpc: Option<String>,
 
     /// Database path (for resolving labels)
     #[arg(long)]
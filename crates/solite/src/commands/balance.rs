@@ -1,13 +1,17 @@
 //! Check account balance
 
+use std::path::PathBuf;
+
 use anyhow::Result;
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::pubkey::Pubkey;
 
+use super::Cluster;
 use crate::cli::BalanceArgs;
 
-pub async fn run(args: BalanceArgs) -> Result<()> {
-    let rpc = RpcClient::new(&args.rpc);
+pub async fn run(args: BalanceArgs, solana_config: &Option<Option<PathBuf>>) -> Result<()> {
+    let cluster = Cluster::resolve(solana_config, args.rpc.as_deref(), None)?;
+    let rpc = RpcClient::new(cluster.rpc_url);
 
     // Resolve address (could be a pubkey or label)
     let pubkey = resolve_address(&args.address, args.db_path.as_ref())?;
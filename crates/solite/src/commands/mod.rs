@@ -2,3 +2,47 @@
 
 pub mod balance;
 pub mod transfer;
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use solana_keyring::solana_config::SolanaCliConfig;
+
+/// RPC URL used when none is given or configured
+const DEFAULT_RPC_URL: &str = "https://api.mainnet-beta.solana.com";
+
+/// Cluster settings from flags, falling back to the Solana CLI config
+pub struct Cluster {
+    pub rpc_url: String,
+    pub ws_url: Option<String>,
+    pub config: Option<SolanaCliConfig>,
+}
+
+impl Cluster {
+    /// Resolve settings; explicit flags win over the Solana CLI config
+    pub fn resolve(
+        solana_config: &Option<Option<PathBuf>>,
+        rpc: Option<&str>,
+        ws: Option<&str>,
+    ) -> Result<Self> {
+        let config = solana_config
+            .as_ref()
+            .map(|path| SolanaCliConfig::load_or_default(path.as_deref()))
+            .transpose()?;
+
+        let rpc_url = rpc
+            .map(str::to_string)
+            .or_else(|| config.as_ref().map(|c| c.json_rpc_url.clone()))
+            .unwrap_or_else(|| DEFAULT_RPC_URL.to_string());
+        let ws_url = ws
+            .map(str::to_string)
+            .or_else(|| config.as_ref().map(|c| c.websocket_url.clone()))
+            .filter(|url| !url.is_empty());
+
+        Ok(Self {
+            rpc_url,
+            ws_url,
+            config,
+        })
+    }
+}
@@ -1,40 +1,91 @@
 //! Transfer SOL command
 
 use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::Duration;
 
 use anyhow::Result;
+use solana_actor::{Commitment, Connection, RpcConnection};
+use solana_client::pubsub_client::PubsubClient;
 use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSignatureSubscribeConfig;
+use solana_client::rpc_response::{ProcessedSignatureResult, RpcSignatureResult};
 use solana_credential_helper_client::{CredentialHelperClient, CredentialHelperConfig, SignerType};
+use solana_keyring::i18n::{t, t_with};
+use solana_keyring::output;
+use solana_keyring::progress::Spinner;
+use solana_keyring::tx_encoding::encode_transaction;
 use solana_sdk::{
     native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, signature::Signature, transaction::Transaction,
 };
 use solana_system_interface::instruction as system_instruction;
 
+use super::Cluster;
 use crate::cli::TransferArgs;
 
-pub async fn run(args: TransferArgs) -> Result<()> {
-    let rpc = RpcClient::new(&args.rpc);
+/// How long to wait for a WebSocket confirmation
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(90);
+
+pub async fn run(args: TransferArgs, solana_config: &Option<Option<PathBuf>>) -> Result<()> {
+    let cluster = Cluster::resolve(solana_config, args.rpc.as_deref(), args.ws.as_deref())?;
+    let rpc = RpcClient::new(cluster.rpc_url.clone());
+
+    // Default the sender to the Solana CLI config's keypair
+    let from = match (&args.from, &cluster.config) {
+        (Some(from), _) => from.clone(),
+        (None, Some(config)) => config.keypair()?.pubkey_base58(),
+        (None, None) => anyhow::bail!("--from is required without --solana-config"),
+    };
 
     // Resolve source and destination addresses (can be pubkeys or labels)
-    let from_pubkey = resolve_address(&args.from, args.db_path.as_ref())?;
+    let from_pubkey = resolve_address(&from, args.db_path.as_ref())?;
     let to_pubkey = resolve_address(&args.to, args.db_path.as_ref())?;
 
     let lamports = (args.amount * LAMPORTS_PER_SOL as f64) as u64;
 
-    // Get balance to verify sufficient funds
-    let balance = rpc.get_balance(&from_pubkey)?;
+    // Get balance to verify sufficient funds; an offline signer can't
+    let balance = if args.sign_only {
+        None
+    } else {
+        Some(rpc.get_balance(&from_pubkey)?)
+    };
 
-    println!("Transfer Details:");
-    println!("  From: {} ({})", args.from, from_pubkey);
-    println!("  To:   {} ({})", args.to, to_pubkey);
-    println!("  Amount: {} SOL ({} lamports)", args.amount, lamports);
+    println!("{}", output::heading(t("transfer-details")));
+    println!(
+        "  {}",
+        t_with(
+            "transfer-from",
+            &[("label", &from), ("address", &from_pubkey)]
+        )
+    );
+    println!(
+        "  {}",
+        t_with(
+            "transfer-to",
+            &[("label", &args.to), ("address", &to_pubkey)]
+        )
+    );
     println!(
-        "  Current balance: {} SOL",
-        balance as f64 / LAMPORTS_PER_SOL as f64
+        "  {}",
+        t_with(
+            "transfer-amount",
+            &[("amount", &args.amount), ("lamports", &lamports)]
+        )
     );
+    if let Some(balance) = balance {
+        println!(
+            "  {}",
+            t_with(
+                "transfer-balance",
+                &[("balance", &(balance as f64 / LAMPORTS_PER_SOL as f64))]
+            )
+        );
+    }
     println!();
 
-    if balance < lamports {
+    if let Some(balance) = balance
+        && balance < lamports
+    {
         anyhow::bail!(
             "Insufficient balance: {} SOL < {} SOL",
             balance as f64 / LAMPORTS_PER_SOL as f64,
@@ -44,14 +95,14 @@ pub async fn run(args: TransferArgs) -> Result<()> {
 
     // Confirm unless --yes flag
     if !args.yes {
-        print!("Proceed with transfer? [y/N] ");
+        print!("{} ", t("confirm-transfer"));
         io::stdout().flush()?;
 
         let mut input = String::new();
         io::stdin().read_line(&mut input)?;
 
         if !input.trim().eq_ignore_ascii_case("y") {
-            println!("Cancelled.");
+            println!("{}", t("confirm-cancelled"));
             return Ok(());
         }
     }
@@ -59,8 +110,11 @@ pub async fn run(args: TransferArgs) -> Result<()> {
     // Build transfer instruction
     let instruction = system_instruction::transfer(&from_pubkey, &to_pubkey, lamports);
 
-    // Get recent blockhash
-    let blockhash = rpc.get_latest_blockhash()?;
+    // Get recent blockhash unless one was given for offline signing
+    let blockhash = match args.blockhash {
+        Some(blockhash) => blockhash,
+        None => rpc.get_latest_blockhash()?,
+    };
 
     // Build transaction (unsigned) with blockhash
     let message = solana_sdk::message::Message::new_with_blockhash(
@@ -74,6 +128,13 @@ pub async fn run(args: TransferArgs) -> Result<()> {
     let tx_message_bytes = tx.message.serialize();
 
     println!("Signing transaction...");
+    // Matches the hash shown in the agent's approval prompt
+    if let Ok(summary) = solana_keyring::transaction::summarize_transaction(&tx_message_bytes) {
+        println!(
+            "{}",
+            output::dim(t_with("summary-hash", &[("hash", &summary.fingerprint())]))
+        );
+    }
 
     // Sign via credential helper client
     let signature = sign_transaction(&args, &from_pubkey, &tx_message_bytes).await?;
@@ -82,18 +143,98 @@ pub async fn run(args: TransferArgs) -> Result<()> {
     let mut signed_tx = tx;
     signed_tx.signatures = vec![signature];
 
-    // Send and confirm
-    println!("Sending transaction...");
-    let tx_signature = rpc.send_and_confirm_transaction(&signed_tx)?;
+    if args.sign_only {
+        return write_signed_transaction(&args, &signed_tx);
+    }
+
+    // Send and wait for the requested commitment
+    let spinner = Spinner::start("Sending transaction...");
+    let tx_signature = match &cluster.ws_url {
+        Some(ws_url) => send_and_confirm_ws(&rpc, ws_url, &signed_tx, args.commitment)?,
+        None => {
+            let signature = rpc.send_transaction(&signed_tx)?;
+            RpcConnection::new(&cluster.rpc_url)
+                .confirm_signature(&signature, args.commitment)
+                .await
+                .map_err(|e| {
+                    anyhow::anyhow!(
+                        "Transaction {} did not reach {}: {}",
+                        signature,
+                        args.commitment,
+                        e
+                    )
+                })?;
+            signature
+        }
+    };
+    spinner.finish(format!("Transaction {}", args.commitment));
 
     println!();
-    println!("Success!");
+    println!("{}", output::success("Success!"));
     println!("Transaction signature: {}", tx_signature);
     println!("Explorer: https://solscan.io/tx/{}", tx_signature);
 
     Ok(())
 }
 
+/// Print or save the signed transaction for broadcasting elsewhere
+fn write_signed_transaction(args: &TransferArgs, tx: &Transaction) -> Result<()> {
+    let encoded = encode_transaction(tx, args.tx_encoding.unwrap_or_default())?;
+    match &args.tx_out {
+        Some(path) => {
+            std::fs::write(path, encoded)?;
+            println!(
+                "{}",
+                output::success(format!("Wrote signed transaction to {}", path.display()))
+            );
+        }
+        None => println!("{}", encoded),
+    }
+    Ok(())
+}
+
+/// Send a transaction and wait for it to reach `commitment` over a WebSocket
+/// subscription
+fn send_and_confirm_ws(
+    rpc: &RpcClient,
+    ws_url: &str,
+    tx: &Transaction,
+    commitment: Commitment,
+) -> Result<Signature> {
+    let signature = tx.signatures[0];
+    let config = RpcSignatureSubscribeConfig {
+        commitment: Some(commitment.into()),
+        enable_received_notification: Some(false),
+    };
+    let (mut subscription, receiver) =
+        PubsubClient::signature_subscribe(ws_url, &signature, Some(config))
+            .map_err(|e| anyhow::anyhow!("Failed to subscribe at {}: {}", ws_url, e))?;
+
+    rpc.send_transaction(tx)?;
+    let result = receiver.recv_timeout(CONFIRM_TIMEOUT);
+    let _ = subscription.send_unsubscribe();
+    let _ = subscription.shutdown();
+
+    match result {
+        Ok(response) => match response.value {
+            RpcSignatureResult::ProcessedSignature(ProcessedSignatureResult { err: None }) => {
+                Ok(signature)
+            }
+            RpcSignatureResult::ProcessedSignature(ProcessedSignatureResult { err: Some(err) }) => {
+                anyhow::bail!("Transaction {} failed: {}", signature, err)
+            }
+            RpcSignatureResult::ReceivedSignature(_) => {
+                anyhow::bail!("Transaction {} was received but not confirmed", signature)
+            }
+        },
+        Err(_) => anyhow::bail!(
+            "Transaction {} was not confirmed within {}s",
+            signature,
+            CONFIRM_TIMEOUT.as_secs()
+        ),
+    }
+}
+
 fn resolve_address(address: &str, db_path: Option<&std::path::PathBuf>) -> Result<Pubkey> {
     // Try to parse as a pubkey first
     if let Ok(pubkey) = address.parse::<Pubkey>() {
@@ -160,7 +301,7 @@ async fn sign_transaction(
 
 fn sign_directly(
     args: &TransferArgs,
-    _signer_pubkey: &Pubkey,
+    signer_pubkey: &Pubkey,
     message_bytes: &[u8],
 ) -> Result<Signature> {
     let db_path = args
@@ -174,14 +315,14 @@ fn sign_directly(
     }
 
     // Prompt for passphrase
-    let passphrase = rpassword::prompt_password("Enter master passphrase: ")?;
+    let passphrase = rpassword::prompt_password(output::prompt("Enter master passphrase"))?;
 
     if !db.verify_passphrase(passphrase.as_bytes())? {
         anyhow::bail!("Invalid passphrase");
     }
 
     // Load keypair
-    let keypair = db.load_keypair(&args.from, passphrase.as_bytes())?;
+    let keypair = db.load_keypair(&signer_pubkey.to_string(), passphrase.as_bytes())?;
 
     // Sign
     let signature_bytes = keypair.sign(message_bytes);
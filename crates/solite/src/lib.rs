@@ -0,0 +1,34 @@
+//! sol - Simple SOL transfer CLI
+//!
+//! A minimal CLI tool demonstrating solana-keyring for signing transactions.
+//! Built as a library so it can be bundled into a combined executable.
+
+mod cli;
+mod commands;
+
+use std::ffi::OsString;
+
+use anyhow::Result;
+use clap::Parser;
+
+use cli::{Cli, Commands};
+
+/// Run the CLI with `args`, starting with the program name
+///
+/// # Errors
+///
+/// Returns the error of the command that failed.
+pub async fn run<I, T>(args: I) -> Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let cli = Cli::parse_from(args);
+    solana_keyring::output::init(cli.plain);
+    solana_keyring::i18n::init_for_db(&solana_keyring::default_db_path());
+
+    match cli.command {
+        Commands::Transfer(args) => commands::transfer::run(args, &cli.solana_config).await,
+        Commands::Balance(args) => commands::balance::run(args, &cli.solana_config).await,
+    }
+}